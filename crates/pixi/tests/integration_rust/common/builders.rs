@@ -154,6 +154,7 @@ pub trait HasDependencyConfig: Sized {
     fn dependency_config_with_specs(specs: Vec<&str>) -> DependencyConfig {
         DependencyConfig {
             specs: specs.iter().map(|s| s.to_string()).collect(),
+            from_file: None,
             host: false,
             build: false,
             pypi: false,
@@ -444,6 +444,7 @@ impl PixiControl {
             args: add::Args {
                 workspace_config: WorkspaceConfig {
                     manifest_path: Some(self.manifest_path()),
+                    lock_profile: None,
                     backend_override: self.backend_override.clone(),
                 },
                 dependency_config: AddBuilder::dependency_config_with_specs(specs),
@@ -454,6 +455,7 @@ impl PixiControl {
                 },
                 config: Default::default(),
                 editable: false,
+                assume_yes: false,
             },
         }
     }
@@ -484,6 +486,8 @@ impl PixiControl {
                     ..Default::default()
                 },
                 dependency_config: AddBuilder::dependency_config_with_specs(vec![spec]),
+                dry_run: false,
+                assume_yes: false,
                 no_install_config: NoInstallConfig { no_install: true },
                 lock_file_update_config: LockFileUpdateConfig {
                     no_lockfile_update: false,
@@ -651,11 +655,14 @@ impl PixiControl {
                     frozen: false,
                     locked: false,
                 },
+                channel_priority_config: Default::default(),
+                pypi_build_config: Default::default(),
                 config: Default::default(),
                 all: false,
                 skip: None,
                 skip_with_deps: None,
                 only: None,
+                repair: false,
             },
         }
     }
@@ -682,6 +689,10 @@ impl PixiControl {
                 no_install: true,
                 dry_run: false,
                 specs: Default::default(),
+                solve_strategy_config: Default::default(),
+                channel_priority_config: Default::default(),
+                exclude_newer_config: Default::default(),
+                pypi_build_config: Default::default(),
                 json: false,
             },
         }
@@ -716,7 +727,12 @@ impl PixiControl {
                     manifest_path: Some(self.manifest_path()),
                     ..Default::default()
                 },
+                command: None,
                 no_install_config: NoInstallConfig { no_install: false },
+                solve_strategy_config: Default::default(),
+                channel_priority_config: Default::default(),
+                exclude_newer_config: Default::default(),
+                pypi_build_config: Default::default(),
                 check: false,
                 json: false,
             },
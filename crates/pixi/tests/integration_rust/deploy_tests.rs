@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+
+use pixi_cli::{LockFileUsageConfig, cli_config::WorkspaceConfig, deploy};
+use pixi_utils::prefix::Prefix as CondaPrefix;
+use rattler_conda_types::{PackageName, Platform};
+use tempfile::TempDir;
+use url::Url;
+
+use crate::common::PixiControl;
+
+/// Returns the url of a channel with real packages for the common platforms.
+fn dummy_channel_url() -> Url {
+    let channel_path: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../tests/data/channels/channels/dummy_channel_1");
+    let channel_path = fs_err::canonicalize(channel_path).expect("canonicalize channel path");
+    Url::from_directory_path(&channel_path).expect("valid file url")
+}
+
+fn deploy_args(pixi: &PixiControl, prefix: &Path) -> deploy::Args {
+    deploy::Args {
+        project_config: WorkspaceConfig {
+            manifest_path: Some(pixi.manifest_path()),
+            ..Default::default()
+        },
+        lock_file_usage: LockFileUsageConfig::default(),
+        environment: None,
+        prefix: prefix.to_path_buf(),
+        target_platform: None,
+        config: Default::default(),
+    }
+}
+
+async fn is_conda_package_installed(prefix_path: &Path, package_name: &str) -> bool {
+    let conda_prefix = CondaPrefix::new(prefix_path.to_path_buf());
+    conda_prefix
+        .find_designated_package(&PackageName::try_from(package_name).unwrap())
+        .await
+        .is_ok()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn deploy_installs_outside_of_workspace() {
+    let platform = Platform::current();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+        [workspace]
+        name = "deploy"
+        channels = ["{channel}"]
+        platforms = ["{platform}"]
+
+        [dependencies]
+        dummy-g = "*"
+        "#,
+        channel = dummy_channel_url(),
+    ))
+    .unwrap();
+
+    let target = TempDir::new().unwrap();
+    let prefix = target.path().join("app").join("env");
+    deploy::execute(deploy_args(&pixi, &prefix)).await.unwrap();
+
+    // The environment and the dependencies of its packages are installed in
+    // the prefix, and not in the workspace.
+    assert!(is_conda_package_installed(&prefix, "dummy-g").await);
+    assert!(is_conda_package_installed(&prefix, "dummy-b").await);
+    assert!(!pixi.default_env_path().unwrap().exists());
+
+    // Deploying again updates the existing environment.
+    deploy::execute(deploy_args(&pixi, &prefix)).await.unwrap();
+    assert!(is_conda_package_installed(&prefix, "dummy-g").await);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn deploy_refuses_non_empty_directory() {
+    let platform = Platform::current();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+        [workspace]
+        name = "deploy"
+        channels = ["{channel}"]
+        platforms = ["{platform}"]
+
+        [dependencies]
+        dummy-a = "*"
+        "#,
+        channel = dummy_channel_url(),
+    ))
+    .unwrap();
+
+    let prefix = TempDir::new().unwrap();
+    fs_err::write(prefix.path().join("important.txt"), "keep me").unwrap();
+
+    let err = deploy::execute(deploy_args(&pixi, prefix.path()))
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("is not empty and does not contain an environment"),
+        "unexpected error: {err}"
+    );
+    assert!(prefix.path().join("important.txt").exists());
+    assert!(!is_conda_package_installed(prefix.path(), "dummy-a").await);
+}
+
+/// Returns a platform of the test channel that differs from the current one.
+fn other_platform() -> Platform {
+    if Platform::current() == Platform::Linux64 {
+        Platform::Win64
+    } else {
+        Platform::Linux64
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn deploy_for_target_platform() {
+    let platform = Platform::current();
+    let target_platform = other_platform();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+        [workspace]
+        name = "deploy"
+        channels = ["{channel}"]
+        platforms = ["{platform}", "{target_platform}"]
+
+        [dependencies]
+        dummy-g = "*"
+        "#,
+        channel = dummy_channel_url(),
+    ))
+    .unwrap();
+
+    let target = TempDir::new().unwrap();
+    let prefix = target.path().join("env");
+    deploy::execute(deploy::Args {
+        target_platform: Some(target_platform),
+        ..deploy_args(&pixi, &prefix)
+    })
+    .await
+    .unwrap();
+
+    // The packages of the target platform are installed, not the ones of the
+    // current platform.
+    for name in ["dummy-g", "dummy-b"] {
+        let record = CondaPrefix::new(prefix.clone())
+            .find_designated_package(&PackageName::try_from(name).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            record.repodata_record.package_record.subdir,
+            target_platform.as_str()
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn deploy_refuses_unsupported_target_platform() {
+    let platform = Platform::current();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+        [workspace]
+        name = "deploy"
+        channels = ["{channel}"]
+        platforms = ["{platform}"]
+
+        [dependencies]
+        dummy-a = "*"
+        "#,
+        channel = dummy_channel_url(),
+    ))
+    .unwrap();
+
+    let target = TempDir::new().unwrap();
+    let prefix = target.path().join("env");
+    let err = deploy::execute(deploy::Args {
+        target_platform: Some(other_platform()),
+        ..deploy_args(&pixi, &prefix)
+    })
+    .await
+    .unwrap_err();
+    assert!(
+        err.to_string().contains(&format!(
+            "does not support the platform '{}'",
+            other_platform()
+        )),
+        "unexpected error: {err}"
+    );
+    assert!(!prefix.exists());
+}
@@ -603,6 +603,9 @@ async fn install_conda_meta_history() {
     let conda_meta_history_file = prefix.join("conda-meta/history");
 
     assert!(conda_meta_history_file.exists());
+    let history = fs_err::read_to_string(conda_meta_history_file).unwrap();
+    assert!(history.starts_with("==> "));
+    assert!(history.contains("# cmd: "));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
@@ -1526,6 +1529,122 @@ async fn test_exclude_newer() {
     ));
 }
 
+#[tokio::test]
+async fn test_exclude_newer_cli_override() {
+    setup_tracing();
+
+    let mut package_database = MockRepoData::default();
+    package_database.add_package(
+        Package::build("foo", "1")
+            .with_timestamp("2010-12-02T02:07:43Z".parse().unwrap())
+            .finish(),
+    );
+    package_database.add_package(
+        Package::build("foo", "2")
+            .with_timestamp("2020-12-02T07:00:00Z".parse().unwrap())
+            .finish(),
+    );
+
+    let channel = package_database.into_channel().await.unwrap();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+    [workspace]
+    name = "test-exclude-newer-override"
+    channels = ["{channel_a}"]
+    platforms = ["{platform}"]
+
+    [dependencies]
+    foo = "*"
+    "#,
+        channel_a = channel.url(),
+        platform = Platform::current()
+    ))
+    .unwrap();
+
+    // Locking with `--exclude-newer` selects the older version of the package
+    let mut lock = pixi.lock();
+    lock.args.exclude_newer_config.exclude_newer = Some("2015-12-02".parse().unwrap());
+    lock.await.unwrap();
+    let lock = pixi.lock_file().await.unwrap();
+    assert!(lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::current(),
+        "foo ==1"
+    ));
+
+    // Without the override the environment is re-solved with the newest version
+    pixi.lock().await.unwrap();
+    let lock = pixi.lock_file().await.unwrap();
+    assert!(lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::current(),
+        "foo ==2"
+    ));
+}
+
+#[tokio::test]
+async fn test_lock_profile() {
+    setup_tracing();
+
+    let mut package_database = MockRepoData::default();
+    package_database.add_package(Package::build("foo", "1").finish());
+    package_database.add_package(Package::build("foo", "2").finish());
+
+    let channel = package_database.into_channel().await.unwrap();
+    let pixi = PixiControl::from_manifest(&format!(
+        r#"
+    [workspace]
+    name = "test-lock-profile"
+    channels = ["{channel_a}"]
+    platforms = ["{platform}"]
+
+    [workspace.lock-profiles.minimal]
+    solve-strategy = "lowest"
+
+    [dependencies]
+    foo = "*"
+    "#,
+        channel_a = channel.url(),
+        platform = Platform::current()
+    ))
+    .unwrap();
+
+    // The default lock-file uses the newest version of the package
+    pixi.lock().await.unwrap();
+    let lock = pixi.lock_file().await.unwrap();
+    assert!(lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::current(),
+        "foo ==2"
+    ));
+
+    // The lock-file of the profile is written next to it with the oldest version
+    let mut lock = pixi.lock();
+    lock.args.workspace_config.lock_profile = Some("minimal".parse().unwrap());
+    lock.await.unwrap();
+    let profile_lock =
+        rattler_lock::LockFile::from_path(&pixi.workspace_path().join("pixi.minimal.lock"))
+            .unwrap();
+    assert!(profile_lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::current(),
+        "foo ==1"
+    ));
+
+    // The default lock-file is left untouched
+    let lock = pixi.lock_file().await.unwrap();
+    assert!(lock.contains_match_spec(
+        consts::DEFAULT_ENVIRONMENT_NAME,
+        Platform::current(),
+        "foo ==2"
+    ));
+
+    // An unknown profile is an error
+    let mut lock = pixi.lock();
+    lock.args.workspace_config.lock_profile = Some("unknown".parse().unwrap());
+    assert!(lock.await.is_err());
+}
+
 #[tokio::test]
 #[cfg_attr(
     any(not(feature = "online_tests"), not(feature = "slow_integration_tests")),
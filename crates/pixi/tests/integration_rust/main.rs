@@ -3,6 +3,7 @@ use std::sync::Once;
 mod add_tests;
 mod build_tests;
 mod common;
+mod deploy_tests;
 mod global_tests;
 mod init_tests;
 mod install_filter_tests;
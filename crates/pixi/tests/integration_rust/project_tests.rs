@@ -6,7 +6,7 @@ use crate::setup_tracing;
 use insta::assert_debug_snapshot;
 use pixi_config::Config;
 use pixi_core::Workspace;
-use pixi_manifest::FeaturesExt;
+use pixi_manifest::{FeaturesExt, pypi::pypi_options::IndexStrategy};
 use pixi_test_utils::MockRepoData;
 use rattler_conda_types::{NamedChannelOrUrl, Platform};
 use tempfile::TempDir;
@@ -148,6 +148,24 @@ fn parse_valid_docs_manifests() {
         }
     }
 }
+#[test]
+fn parse_docs_pypi_index_strategy() {
+    let path = PathBuf::from(env!("CARGO_WORKSPACE_DIR"))
+        .join("docs/source_files/pixi_tomls/pypi_index_strategy.toml");
+    let pixi_toml = fs_err::read_to_string(&path).unwrap();
+    let workspace = Workspace::from_str(&PathBuf::from("pixi.toml"), &pixi_toml).unwrap();
+
+    let pypi_options = workspace.default_environment().pypi_options();
+    assert_eq!(
+        pypi_options.index_strategy,
+        Some(IndexStrategy::UnsafeBestMatch)
+    );
+    assert_eq!(
+        pypi_options.extra_index_urls,
+        Some(vec![Url::parse("https://pypi.example.com/simple").unwrap()])
+    );
+}
+
 #[test]
 fn parse_valid_docs_pyproject_manifests() {
     setup_tracing();
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use indexmap::{IndexMap, IndexSet};
-use miette::IntoDiagnostic;
+use miette::Report;
 use pixi_core::workspace::{Environment, PypiDeps, UpdateDeps, WorkspaceMut};
 use pixi_core::{Workspace, environment::LockFileUsage};
 use pixi_manifest::{
@@ -12,9 +12,14 @@ use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
 use pixi_spec::PixiSpec;
 use rattler_conda_types::{Channel, MatchSpec, PackageName, Platform, RepoDataRecord};
 
+use crate::ApiError;
 use crate::interface::Interface;
 use crate::workspace::add::GitOptions;
-use crate::workspace::{DependencyOptions, InitOptions, ReinstallOptions};
+use crate::workspace::{
+    AddEnvironmentOptions, AddFeatureOptions, DependencyOptions, DiscoveryReport, DiscoveryStart,
+    InitOptions, ListSolveGroupsOptions, LockFileView, ReinstallOptions, RemovePreview,
+    SolveGroupInfo,
+};
 
 pub struct DefaultContext<I: Interface> {
     _interface: I,
@@ -33,8 +38,10 @@ impl<I: Interface> DefaultContext<I> {
         match_spec: MatchSpec,
         channels: IndexSet<Channel>,
         platform: Platform,
-    ) -> miette::Result<Option<Vec<RepoDataRecord>>> {
-        crate::workspace::search::search_exact(None, match_spec, channels, platform).await
+    ) -> Result<Option<Vec<RepoDataRecord>>, ApiError> {
+        crate::workspace::search::search_exact(None, match_spec, channels, platform)
+            .await
+            .map_err(ApiError::from)
     }
 
     /// Returns all matching packages with their latest versions
@@ -43,8 +50,19 @@ impl<I: Interface> DefaultContext<I> {
         search: &str,
         channels: IndexSet<Channel>,
         platform: Platform,
-    ) -> miette::Result<Option<Vec<RepoDataRecord>>> {
-        crate::workspace::search::search_wildcard(None, search, channels, platform).await
+    ) -> Result<Option<Vec<RepoDataRecord>>, ApiError> {
+        crate::workspace::search::search_wildcard(None, search, channels, platform)
+            .await
+            .map_err(ApiError::from)
+    }
+
+    /// Returns the workspace that is found from `start` and how it was found,
+    /// or `None` if there is no workspace.
+    pub fn discover_workspace(
+        &self,
+        start: DiscoveryStart,
+    ) -> Result<Option<DiscoveryReport>, ApiError> {
+        crate::workspace::discovery::discover(start).map_err(ApiError::from)
     }
 }
 
@@ -65,20 +83,38 @@ impl<I: Interface> WorkspaceContext<I> {
         &self.workspace
     }
 
-    pub fn workspace_mut(&self) -> miette::Result<WorkspaceMut> {
-        self.workspace.clone().modify().into_diagnostic()
+    pub fn workspace_mut(&self) -> Result<WorkspaceMut, ApiError> {
+        self.workspace
+            .clone()
+            .modify()
+            .map_err(|err| ApiError::from(Report::from(err)))
     }
 
-    pub async fn init(interface: I, options: InitOptions) -> miette::Result<Workspace> {
-        crate::workspace::init::init(&interface, options).await
+    pub async fn init(interface: I, options: InitOptions) -> Result<Workspace, ApiError> {
+        crate::workspace::init::init(&interface, options)
+            .await
+            .map_err(ApiError::from)
     }
 
     pub async fn name(&self) -> String {
         crate::workspace::workspace::name::get(&self.workspace).await
     }
 
-    pub async fn set_name(&self, name: &str) -> miette::Result<()> {
-        crate::workspace::workspace::name::set(&self.interface, self.workspace_mut()?, name).await
+    pub async fn set_name(&self, name: &str) -> Result<(), ApiError> {
+        crate::workspace::workspace::name::set(&self.interface, self.workspace_mut()?, name)
+            .await
+            .map_err(ApiError::from)
+    }
+
+    pub async fn rename(&self, name: &str, lock_file_usage: LockFileUsage) -> Result<(), ApiError> {
+        crate::workspace::workspace::name::rename(
+            &self.interface,
+            self.workspace_mut()?,
+            name,
+            lock_file_usage,
+        )
+        .await
+        .map_err(ApiError::from)
     }
 
     pub async fn list_environments(&self) -> Vec<Environment<'_>> {
@@ -92,26 +128,71 @@ impl<I: Interface> WorkspaceContext<I> {
         solve_group: Option<String>,
         no_default_feature: bool,
         force: bool,
-    ) -> miette::Result<()> {
+    ) -> Result<(), ApiError> {
+        self.add_environment_with_options(
+            name,
+            AddEnvironmentOptions {
+                features,
+                solve_group,
+                no_default_feature,
+                force,
+            },
+        )
+        .await
+    }
+
+    pub async fn add_environment_with_options(
+        &self,
+        name: EnvironmentName,
+        options: AddEnvironmentOptions,
+    ) -> Result<(), ApiError> {
         crate::workspace::workspace::environment::add(
             &self.interface,
             self.workspace_mut()?,
             name,
-            features,
-            solve_group,
-            no_default_feature,
-            force,
+            options,
         )
         .await
+        .map_err(ApiError::from)
     }
 
-    pub async fn remove_environment(&self, name: &str) -> miette::Result<()> {
+    pub async fn remove_environment(&self, name: &str) -> Result<(), ApiError> {
         crate::workspace::workspace::environment::remove(
             &self.interface,
             self.workspace_mut()?,
             name,
         )
         .await
+        .map_err(ApiError::from)
+    }
+
+    pub async fn list_solve_groups(
+        &self,
+        platform: Option<Platform>,
+        lock_file_usage: LockFileUsage,
+    ) -> Result<Vec<SolveGroupInfo>, ApiError> {
+        self.list_solve_groups_with_options(ListSolveGroupsOptions {
+            platform,
+            lock_file_usage,
+        })
+        .await
+    }
+
+    pub async fn list_solve_groups_with_options(
+        &self,
+        options: ListSolveGroupsOptions,
+    ) -> Result<Vec<SolveGroupInfo>, ApiError> {
+        crate::workspace::workspace::solve_group::list(&self.workspace, options)
+            .await
+            .map_err(ApiError::from)
+    }
+
+    /// Returns a read-only view of the lock-file of the workspace, as it is
+    /// on disk.
+    pub async fn lock_file(&self) -> Result<LockFileView, ApiError> {
+        crate::workspace::workspace::lock_file::get(&self.workspace)
+            .await
+            .map_err(ApiError::from)
     }
 
     pub async fn list_features(&self) -> IndexMap<FeatureName, Feature> {
@@ -169,16 +250,32 @@ impl<I: Interface> WorkspaceContext<I> {
             .await
     }
 
+    pub async fn add_feature(
+        &self,
+        feature: FeatureName,
+        options: AddFeatureOptions,
+    ) -> Result<(), ApiError> {
+        crate::workspace::workspace::feature::add_feature(
+            &self.interface,
+            self.workspace_mut()?,
+            feature,
+            options,
+        )
+        .await
+        .map_err(ApiError::from)
+    }
+
     pub async fn remove_feature(
         &self,
         feature: &FeatureName,
-    ) -> miette::Result<Vec<EnvironmentName>> {
+    ) -> Result<Vec<EnvironmentName>, ApiError> {
         crate::workspace::workspace::feature::remove_feature(
             &self.interface,
             self.workspace_mut()?,
             feature,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn add_conda_deps(
@@ -187,8 +284,9 @@ impl<I: Interface> WorkspaceContext<I> {
         spec_type: SpecType,
         dep_options: DependencyOptions,
         git_options: GitOptions,
-    ) -> miette::Result<Option<UpdateDeps>> {
+    ) -> Result<Option<UpdateDeps>, ApiError> {
         Box::pin(crate::workspace::add::add_conda_dep(
+            &self.interface,
             self.workspace_mut()?,
             specs,
             spec_type,
@@ -196,6 +294,7 @@ impl<I: Interface> WorkspaceContext<I> {
             git_options,
         ))
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn add_pypi_deps(
@@ -203,14 +302,16 @@ impl<I: Interface> WorkspaceContext<I> {
         pypi_deps: PypiDeps,
         editable: bool,
         options: DependencyOptions,
-    ) -> miette::Result<Option<UpdateDeps>> {
+    ) -> Result<Option<UpdateDeps>, ApiError> {
         Box::pin(crate::workspace::add::add_pypi_dep(
+            &self.interface,
             self.workspace_mut()?,
             pypi_deps,
             editable,
             options,
         ))
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn remove_conda_deps(
@@ -218,7 +319,7 @@ impl<I: Interface> WorkspaceContext<I> {
         specs: IndexMap<PackageName, MatchSpec>,
         spec_type: SpecType,
         dep_options: DependencyOptions,
-    ) -> miette::Result<()> {
+    ) -> Result<Option<RemovePreview>, ApiError> {
         Box::pin(crate::workspace::remove::remove_conda_deps(
             self.workspace_mut()?,
             specs,
@@ -226,26 +327,28 @@ impl<I: Interface> WorkspaceContext<I> {
             dep_options,
         ))
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn remove_pypi_deps(
         &self,
         pypi_deps: PypiDeps,
         options: DependencyOptions,
-    ) -> miette::Result<()> {
+    ) -> Result<Option<RemovePreview>, ApiError> {
         Box::pin(crate::workspace::remove::remove_pypi_deps(
             self.workspace_mut()?,
             pypi_deps,
             options,
         ))
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn reinstall(
         &self,
         options: ReinstallOptions,
         lock_file_usage: LockFileUsage,
-    ) -> miette::Result<()> {
+    ) -> Result<(), ApiError> {
         crate::workspace::reinstall::reinstall(
             &self.interface,
             &self.workspace,
@@ -253,13 +356,16 @@ impl<I: Interface> WorkspaceContext<I> {
             lock_file_usage,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn list_tasks(
         &self,
         environment: Option<EnvironmentName>,
-    ) -> miette::Result<HashMap<EnvironmentName, HashMap<TaskName, Task>>> {
-        crate::workspace::task::list_tasks(&self.workspace, environment).await
+    ) -> Result<HashMap<EnvironmentName, HashMap<TaskName, Task>>, ApiError> {
+        crate::workspace::task::list_tasks(&self.workspace, environment)
+            .await
+            .map_err(ApiError::from)
     }
 
     pub async fn add_task(
@@ -268,7 +374,7 @@ impl<I: Interface> WorkspaceContext<I> {
         task: Task,
         feature: FeatureName,
         platform: Option<Platform>,
-    ) -> miette::Result<()> {
+    ) -> Result<(), ApiError> {
         crate::workspace::task::add_task(
             &self.interface,
             self.workspace_mut()?,
@@ -278,6 +384,7 @@ impl<I: Interface> WorkspaceContext<I> {
             platform,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn alias_task(
@@ -285,7 +392,7 @@ impl<I: Interface> WorkspaceContext<I> {
         name: TaskName,
         task: Task,
         platform: Option<Platform>,
-    ) -> miette::Result<()> {
+    ) -> Result<(), ApiError> {
         crate::workspace::task::alias_task(
             &self.interface,
             self.workspace_mut()?,
@@ -294,6 +401,7 @@ impl<I: Interface> WorkspaceContext<I> {
             platform,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn remove_task(
@@ -301,7 +409,7 @@ impl<I: Interface> WorkspaceContext<I> {
         names: Vec<TaskName>,
         platform: Option<Platform>,
         feature: FeatureName,
-    ) -> miette::Result<()> {
+    ) -> Result<(), ApiError> {
         crate::workspace::task::remove_tasks(
             &self.interface,
             self.workspace_mut()?,
@@ -310,6 +418,7 @@ impl<I: Interface> WorkspaceContext<I> {
             feature,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     pub async fn search_exact(
@@ -317,7 +426,7 @@ impl<I: Interface> WorkspaceContext<I> {
         match_spec: MatchSpec,
         channels: IndexSet<Channel>,
         platform: Platform,
-    ) -> miette::Result<Option<Vec<RepoDataRecord>>> {
+    ) -> Result<Option<Vec<RepoDataRecord>>, ApiError> {
         crate::workspace::search::search_exact(
             Some(&self.workspace),
             match_spec,
@@ -325,6 +434,7 @@ impl<I: Interface> WorkspaceContext<I> {
             platform,
         )
         .await
+        .map_err(ApiError::from)
     }
 
     /// Returns all matching packages with their latest versions
@@ -333,8 +443,9 @@ impl<I: Interface> WorkspaceContext<I> {
         search: &str,
         channels: IndexSet<Channel>,
         platform: Platform,
-    ) -> miette::Result<Option<Vec<RepoDataRecord>>> {
+    ) -> Result<Option<Vec<RepoDataRecord>>, ApiError> {
         crate::workspace::search::search_wildcard(Some(&self.workspace), search, channels, platform)
             .await
+            .map_err(ApiError::from)
     }
 }
@@ -0,0 +1,163 @@
+use std::{error::Error, fmt::Display};
+
+use miette::{Diagnostic, Report};
+use pixi_core::lock_file::{LockFileNotUpToDateError, SolveCondaEnvironmentError};
+use pixi_manifest::{LoadManifestsError, TomlError};
+use rattler_repodata_gateway::GatewayError;
+
+/// The error of an operation of a [`WorkspaceContext`](crate::WorkspaceContext)
+/// or [`DefaultContext`](crate::DefaultContext).
+///
+/// Every variant carries the full diagnostic of the failure, the variant
+/// itself tells what kind of failure occurred, so that consumers can decide
+/// whether to retry or how to present the error.
+#[derive(Debug)]
+pub enum ApiError {
+    /// The manifest could not be parsed or modified.
+    Manifest(Report),
+    /// The requirements of an environment could not be solved.
+    SolveConflict(Report),
+    /// A request to a channel or index failed.
+    Network(Report),
+    /// Reading or writing a file failed.
+    Io(Report),
+    /// The lock-file is out of date, but updating it is not allowed.
+    LockFileStale(Report),
+    /// Any other failure.
+    Other(Report),
+}
+
+impl ApiError {
+    /// Returns the diagnostic of the failure.
+    pub fn report(&self) -> &Report {
+        match self {
+            Self::Manifest(report)
+            | Self::SolveConflict(report)
+            | Self::Network(report)
+            | Self::Io(report)
+            | Self::LockFileStale(report)
+            | Self::Other(report) => report,
+        }
+    }
+
+    /// Converts the error into the diagnostic of the failure.
+    pub fn into_report(self) -> Report {
+        match self {
+            Self::Manifest(report)
+            | Self::SolveConflict(report)
+            | Self::Network(report)
+            | Self::Io(report)
+            | Self::LockFileStale(report)
+            | Self::Other(report) => report,
+        }
+    }
+
+    /// Returns true if retrying the operation might succeed.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Network(_))
+    }
+}
+
+/// Classifies the report by the first error in its chain that is of a known
+/// type.
+impl From<Report> for ApiError {
+    fn from(report: Report) -> Self {
+        let variant = report.chain().find_map(classify).unwrap_or(ApiError::Other);
+        variant(report)
+    }
+}
+
+/// Returns the variant of [`ApiError`] for an error of a known type.
+fn classify(error: &(dyn Error + 'static)) -> Option<fn(Report) -> ApiError> {
+    if error.is::<LockFileNotUpToDateError>() {
+        Some(ApiError::LockFileStale)
+    } else if let Some(LoadManifestsError::Io(_)) = error.downcast_ref::<LoadManifestsError>() {
+        Some(ApiError::Io)
+    } else if error.is::<TomlError>() || error.is::<LoadManifestsError>() {
+        Some(ApiError::Manifest)
+    } else if let Some(
+        SolveCondaEnvironmentError::SolveFailed { .. } | SolveCondaEnvironmentError::Conflict(_),
+    ) = error.downcast_ref::<SolveCondaEnvironmentError>()
+    {
+        Some(ApiError::SolveConflict)
+    } else if let Some(
+        GatewayError::ReqwestError(_)
+        | GatewayError::ReqwestMiddlewareError(_)
+        | GatewayError::FetchRepoDataError(_),
+    ) = error.downcast_ref::<GatewayError>()
+    {
+        Some(ApiError::Network)
+    } else if error.is::<std::io::Error>() {
+        Some(ApiError::Io)
+    } else {
+        None
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.report(), f)
+    }
+}
+
+impl Error for ApiError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.report().source()
+    }
+}
+
+impl Diagnostic for ApiError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.report().code()
+    }
+
+    fn severity(&self) -> Option<miette::Severity> {
+        self.report().severity()
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.report().help()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.report().url()
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        self.report().source_code()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        self.report().labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.report().related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.report().diagnostic_source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_report() {
+        let error = ApiError::from(Report::from(LockFileNotUpToDateError));
+        assert!(matches!(error, ApiError::LockFileStale(_)));
+        assert_eq!(
+            error.to_string(),
+            "lock-file not up-to-date with the workspace"
+        );
+
+        let report = Report::from(LockFileNotUpToDateError).wrap_err("failed to update");
+        assert!(matches!(ApiError::from(report), ApiError::LockFileStale(_)));
+
+        let error = ApiError::from(miette::miette!("something else"));
+        assert!(matches!(error, ApiError::Other(_)));
+        assert!(!error.is_retryable());
+    }
+}
@@ -3,6 +3,9 @@ pub mod workspace;
 mod context;
 pub use context::{DefaultContext, WorkspaceContext};
 
+mod error;
+pub use error::ApiError;
+
 mod interface;
 pub use interface::Interface;
 
@@ -1,4 +1,7 @@
+use std::io::IsTerminal;
+
 use indexmap::IndexMap;
+use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_core::{
     environment::sanity_check_workspace,
@@ -6,13 +9,116 @@ use pixi_core::{
 };
 use pixi_manifest::{FeatureName, KnownPreviewFeature, SpecType};
 use pixi_spec::{GitSpec, SourceLocationSpec, SourceSpec};
-use rattler_conda_types::{MatchSpec, PackageName};
+use rattler_conda_types::{MatchSpec, PackageName, Platform};
+
+use crate::Interface;
 
 mod options;
 
 pub use options::{DependencyOptions, GitOptions};
 
-pub async fn add_conda_dep(
+/// Asks the user to confirm a change to the manifest. Changes are accepted
+/// without asking when they were accepted up front, or when there is no
+/// terminal to ask on, so that scripts keep working.
+async fn confirm_change<I: Interface>(
+    interface: &I,
+    options: &DependencyOptions,
+    message: &str,
+) -> miette::Result<bool> {
+    if options.assume_yes || (interface.is_cli().await && !std::io::stderr().is_terminal()) {
+        return Ok(true);
+    }
+    interface.confirm(message).await
+}
+
+/// Makes sure the feature and platforms that the dependencies are added to
+/// exist, offering to create them if they don't.
+async fn ensure_feature_and_platforms<I: Interface>(
+    interface: &I,
+    workspace: &mut WorkspaceMut,
+    options: &DependencyOptions,
+) -> miette::Result<()> {
+    let feature_name = &options.feature;
+    let manifest = &workspace.workspace().workspace.value;
+    let feature = manifest.feature(feature_name);
+
+    if feature.is_none() {
+        if !confirm_change(
+            interface,
+            options,
+            &format!("The feature '{feature_name}' does not exist. Do you want to create it?"),
+        )
+        .await?
+        {
+            miette::bail!(
+                help = "use `pixi workspace feature add` to create the feature first",
+                "the feature '{}' does not exist",
+                feature_name
+            );
+        }
+        workspace.manifest().add_feature(feature_name)?;
+        interface
+            .info(&format!("Created the feature {feature_name}"))
+            .await;
+    }
+
+    // A feature that restricts its platforms only supports those, otherwise the
+    // platforms of the workspace are used.
+    let manifest = &workspace.workspace().workspace.value;
+    let (supported, owner) = match manifest
+        .feature(feature_name)
+        .and_then(|feature| feature.platforms.as_ref())
+    {
+        Some(platforms) if !feature_name.is_default() => (platforms, feature_name.clone()),
+        _ => (&manifest.workspace.platforms, FeatureName::DEFAULT),
+    };
+    let missing: Vec<Platform> = options
+        .platforms
+        .iter()
+        .filter(|platform| !supported.contains(*platform))
+        .copied()
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let target = if owner.is_default() {
+        String::from("the workspace")
+    } else {
+        format!("the feature '{owner}'")
+    };
+    let (description, pronoun) = match missing.as_slice() {
+        [platform] => (format!("platform {platform} is"), "it"),
+        _ => (
+            format!("platforms {} are", missing.iter().format(", ")),
+            "them",
+        ),
+    };
+    if !confirm_change(
+        interface,
+        options,
+        &format!("The {description} not supported by {target}. Do you want to add {pronoun}?"),
+    )
+    .await?
+    {
+        miette::bail!("the {description} not supported by {target}");
+    }
+    workspace.manifest().add_platforms(missing.iter(), &owner)?;
+    interface
+        .info(&format!(
+            "Added the {} to {target}",
+            match missing.as_slice() {
+                [platform] => format!("platform {platform}"),
+                _ => format!("platforms {}", missing.iter().format(", ")),
+            }
+        ))
+        .await;
+
+    Ok(())
+}
+
+pub async fn add_conda_dep<I: Interface>(
+    interface: &I,
     mut workspace: WorkspaceMut,
     specs: IndexMap<PackageName, MatchSpec>,
     spec_type: SpecType,
@@ -21,10 +127,7 @@ pub async fn add_conda_dep(
 ) -> miette::Result<Option<UpdateDeps>> {
     sanity_check_workspace(workspace.workspace()).await?;
 
-    // Add the platform if it is not already present
-    workspace
-        .manifest()
-        .add_platforms(dep_options.platforms.iter(), &FeatureName::DEFAULT)?;
+    ensure_feature_and_platforms(interface, &mut workspace, &dep_options).await?;
 
     let mut match_specs = IndexMap::default();
     let mut source_specs = IndexMap::default();
@@ -105,7 +208,8 @@ pub async fn add_conda_dep(
     Ok(update_deps)
 }
 
-pub async fn add_pypi_dep(
+pub async fn add_pypi_dep<I: Interface>(
+    interface: &I,
     mut workspace: WorkspaceMut,
     pypi_deps: PypiDeps,
     editable: bool,
@@ -113,10 +217,7 @@ pub async fn add_pypi_dep(
 ) -> miette::Result<Option<UpdateDeps>> {
     sanity_check_workspace(workspace.workspace()).await?;
 
-    // Add the platform if it is not already present
-    workspace
-        .manifest()
-        .add_platforms(options.platforms.iter(), &FeatureName::DEFAULT)?;
+    ensure_feature_and_platforms(interface, &mut workspace, &options).await?;
 
     // TODO: add dry_run logic to add
     let dry_run = false;
@@ -14,6 +14,12 @@ pub struct DependencyOptions {
     /// Don't modify the environment, only modify the lock-file.
     pub no_install: bool,
     pub lock_file_usage: LockFileUsage,
+    /// Only compute the changes without writing anything to disk.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Create a missing feature or platform without asking.
+    #[serde(default)]
+    pub assume_yes: bool,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+
+use pixi_core::workspace::{
+    DiscoveryReason, DiscoveryStart, WorkspaceLocator, WorkspaceLocatorError,
+};
+use pixi_manifest::ManifestKind;
+use serde::Serialize;
+
+use crate::workspace::ManifestFormat;
+
+/// Describes the workspace that was discovered and how it was found.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveryReport {
+    /// The name of the workspace.
+    pub name: String,
+    /// The root directory of the workspace.
+    pub root: PathBuf,
+    /// The path of the manifest of the workspace.
+    pub manifest_path: PathBuf,
+    /// The format of the manifest of the workspace.
+    pub manifest_format: ManifestFormat,
+    /// How the workspace was found.
+    pub reason: DiscoveryReason,
+}
+
+/// Discovers the workspace from `start`, taking the `PIXI_PROJECT_MANIFEST`
+/// environment variable into account. Returns `None` if there is no workspace.
+///
+/// Warnings of the manifest are not reported and the `requires-pixi` of the
+/// workspace is not checked, so that this is cheap and does not fail for
+/// workspaces that can be located but not used.
+pub fn discover(start: DiscoveryStart) -> miette::Result<Option<DiscoveryReport>> {
+    let (workspace, reason) = match WorkspaceLocator::default()
+        .with_search_start(start)
+        .with_consider_environment(true)
+        .with_ignore_pixi_version_check(true)
+        .locate_with_reason()
+    {
+        Ok(located) => located,
+        Err(
+            WorkspaceLocatorError::WorkspaceNotFound(_)
+            | WorkspaceLocatorError::PyprojectWithoutPixi(_),
+        ) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let provenance = &workspace.workspace.provenance;
+    Ok(Some(DiscoveryReport {
+        name: workspace.display_name().to_string(),
+        root: workspace.root().to_path_buf(),
+        manifest_path: provenance.path.clone(),
+        manifest_format: match provenance.kind {
+            ManifestKind::Pixi => ManifestFormat::Pixi,
+            ManifestKind::Pyproject => ManifestFormat::Pyproject,
+            ManifestKind::MojoProject => ManifestFormat::Mojoproject,
+        },
+        reason,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+        [workspace]
+        name = "discovered"
+        channels = []
+        platforms = []
+        "#;
+
+    #[test]
+    fn test_discover() {
+        let workspace_dir = tempfile::tempdir().unwrap();
+        let manifest_path = workspace_dir.path().join("pixi.toml");
+        fs_err::write(&manifest_path, MANIFEST).unwrap();
+        let sub_dir = workspace_dir.path().join("sub");
+        fs_err::create_dir(&sub_dir).unwrap();
+        let empty_dir = tempfile::tempdir().unwrap();
+
+        temp_env::with_var_unset("PIXI_PROJECT_MANIFEST", || {
+            // The workspace is found by searching the parents of the start.
+            let report = discover(DiscoveryStart::SearchRoot(sub_dir.clone()))
+                .unwrap()
+                .unwrap();
+            assert_eq!(report.name, "discovered");
+            assert_eq!(report.root, workspace_dir.path());
+            assert_eq!(report.manifest_path, manifest_path);
+            assert!(matches!(report.manifest_format, ManifestFormat::Pixi));
+            assert_eq!(report.reason, DiscoveryReason::Search);
+
+            let report = discover(DiscoveryStart::ExplicitManifest(manifest_path.clone()))
+                .unwrap()
+                .unwrap();
+            assert_eq!(report.reason, DiscoveryReason::ExplicitManifest);
+
+            // There is no workspace to discover.
+            assert!(
+                discover(DiscoveryStart::SearchRoot(empty_dir.path().to_path_buf()))
+                    .unwrap()
+                    .is_none()
+            );
+        });
+
+        // Without a workspace to search, the manifest of the environment is used.
+        temp_env::with_var("PIXI_PROJECT_MANIFEST", Some(&manifest_path), || {
+            let report = discover(DiscoveryStart::SearchRoot(empty_dir.path().to_path_buf()))
+                .unwrap()
+                .unwrap();
+            assert_eq!(report.manifest_path, manifest_path);
+            assert_eq!(report.reason, DiscoveryReason::EnvironmentVariable);
+        });
+    }
+}
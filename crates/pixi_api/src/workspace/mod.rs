@@ -4,6 +4,11 @@ pub(crate) mod add;
 pub use add::{DependencyOptions, GitOptions};
 
 pub(crate) mod remove;
+pub use remove::RemovePreview;
+
+pub(crate) mod discovery;
+pub use discovery::DiscoveryReport;
+pub use pixi_core::workspace::{DiscoveryReason, DiscoveryStart};
 
 pub(crate) mod init;
 pub use init::{GitAttributes, InitOptions, ManifestFormat};
@@ -17,3 +22,9 @@ pub(crate) mod task;
 
 #[allow(clippy::module_inception)]
 pub(crate) mod workspace;
+pub use workspace::environment::AddEnvironmentOptions;
+pub use workspace::feature::AddFeatureOptions;
+pub use workspace::lock_file::{LockFileView, LockedEnvironment, LockedPackage, LockedPlatform};
+pub use workspace::solve_group::{
+    Downgrade, ListSolveGroupsOptions, SharedPackage, SolveGroupInfo,
+};
@@ -1,22 +1,89 @@
+use std::path::PathBuf;
+
 use indexmap::IndexMap;
 use miette::{Context, IntoDiagnostic};
 use pixi_core::{
     InstallFilter, UpdateLockFileOptions,
     environment::{LockFileUsage, get_update_lock_file_and_prefix},
-    lock_file::{ReinstallPackages, UpdateMode},
+    lock_file::{ReinstallPackages, UpdateContext, UpdateMode},
     workspace::{PypiDeps, WorkspaceMut},
 };
+use pixi_diff::LockFileDiff;
 use pixi_manifest::{FeaturesExt, SpecType};
 use rattler_conda_types::{MatchSpec, PackageName};
 
 use crate::workspace::DependencyOptions;
 
+/// The changes that removing dependencies would make, computed without
+/// touching anything on disk.
+pub struct RemovePreview {
+    /// The path of the manifest that would be modified.
+    pub manifest_path: PathBuf,
+    /// The contents of the manifest before the removal.
+    pub original_manifest: String,
+    /// The contents of the manifest after the removal.
+    pub updated_manifest: String,
+    /// The changes to the lock-file, or `None` if the lock-file would not be
+    /// updated.
+    pub lock_file_diff: Option<LockFileDiff>,
+}
+
+/// Computes the [`RemovePreview`] for a workspace whose manifest has already
+/// been modified in memory. The modifications are never saved, dropping the
+/// [`WorkspaceMut`] discards them.
+async fn preview_removal(
+    workspace: WorkspaceMut,
+    original_manifest: String,
+    options: &DependencyOptions,
+) -> miette::Result<RemovePreview> {
+    let lock_file_diff = if options.lock_file_usage == LockFileUsage::Update {
+        let original_lock_file = workspace
+            .workspace()
+            .load_lock_file()
+            .await?
+            .into_lock_file_or_empty_with_warning();
+        let updated_lock_file = UpdateContext::builder(workspace.workspace())
+            .with_lock_file(original_lock_file.clone())
+            .with_no_install(true)
+            .finish()
+            .await?
+            .update()
+            .await?
+            .into_lock_file();
+        Some(LockFileDiff::from_lock_files(
+            &original_lock_file,
+            &updated_lock_file,
+        ))
+    } else {
+        None
+    };
+
+    Ok(RemovePreview {
+        manifest_path: workspace.workspace().workspace.provenance.path.clone(),
+        original_manifest,
+        updated_manifest: workspace.document().to_string(),
+        lock_file_diff,
+    })
+}
+
+/// Reads the manifest as it currently is on disk, used as the baseline for a
+/// [`RemovePreview`].
+fn read_original_manifest(workspace: &WorkspaceMut) -> miette::Result<String> {
+    Ok(workspace
+        .workspace()
+        .workspace
+        .provenance
+        .read()
+        .into_diagnostic()?
+        .into_inner())
+}
+
 pub async fn remove_conda_deps(
     mut workspace: WorkspaceMut,
     specs: IndexMap<PackageName, MatchSpec>,
     spec_type: SpecType,
     options: DependencyOptions,
-) -> miette::Result<()> {
+) -> miette::Result<Option<RemovePreview>> {
     // Prevent removing Python if PyPI dependencies exist
     for name in specs.keys() {
         if name.as_source() == "python" {
@@ -39,6 +106,7 @@ pub async fn remove_conda_deps(
         }
     }
 
+    let original_manifest = read_original_manifest(&workspace)?;
     for name in specs.keys() {
         workspace
             .manifest()
@@ -48,6 +116,13 @@ pub async fn remove_conda_deps(
                 name.as_source()
             ))?;
     }
+
+    if options.dry_run {
+        return preview_removal(workspace, original_manifest, &options)
+            .await
+            .map(Some);
+    }
+
     let workspace = workspace.save().await.into_diagnostic()?;
 
     // TODO: update all environments touched by this feature defined.
@@ -67,14 +142,15 @@ pub async fn remove_conda_deps(
         .await?;
     }
 
-    Ok(())
+    Ok(None)
 }
 
 pub async fn remove_pypi_deps(
     mut workspace: WorkspaceMut,
     pypi_deps: PypiDeps,
     options: DependencyOptions,
-) -> miette::Result<()> {
+) -> miette::Result<Option<RemovePreview>> {
+    let original_manifest = read_original_manifest(&workspace)?;
     for name in pypi_deps.keys() {
         workspace
             .manifest()
@@ -85,6 +161,12 @@ pub async fn remove_pypi_deps(
             ))?;
     }
 
+    if options.dry_run {
+        return preview_removal(workspace, original_manifest, &options)
+            .await
+            .map(Some);
+    }
+
     let workspace = workspace.save().await.into_diagnostic()?;
 
     // TODO: update all environments touched by this feature defined.
@@ -104,5 +186,5 @@ pub async fn remove_pypi_deps(
         .await?;
     }
 
-    Ok(())
+    Ok(None)
 }
@@ -7,6 +7,46 @@ use pixi_manifest::EnvironmentName;
 
 use crate::Interface;
 
+/// The options of an environment that is added with [`add`].
+#[derive(Clone, Debug, Default)]
+pub struct AddEnvironmentOptions {
+    /// The features of the environment, the default feature is always
+    /// included unless `no_default_feature` is set.
+    pub features: Option<Vec<String>>,
+    /// The solve group the environment belongs to.
+    pub solve_group: Option<String>,
+    /// Don't include the default feature in the environment.
+    pub no_default_feature: bool,
+    /// Overwrite the environment if it already exists.
+    pub force: bool,
+}
+
+impl AddEnvironmentOptions {
+    /// Sets the features of the environment.
+    pub fn with_features(mut self, features: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.features = Some(features.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the solve group of the environment.
+    pub fn with_solve_group(mut self, solve_group: impl Into<String>) -> Self {
+        self.solve_group = Some(solve_group.into());
+        self
+    }
+
+    /// Sets whether the default feature is excluded from the environment.
+    pub fn with_no_default_feature(mut self, no_default_feature: bool) -> Self {
+        self.no_default_feature = no_default_feature;
+        self
+    }
+
+    /// Sets whether an existing environment is overwritten.
+    pub fn with_force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+}
+
 pub async fn list(workspace: &Workspace) -> Vec<Environment<'_>> {
     workspace.environments()
 }
@@ -15,13 +55,10 @@ pub async fn add<I: Interface>(
     interface: &I,
     mut workspace: WorkspaceMut,
     name: EnvironmentName,
-    features: Option<Vec<String>>,
-    solve_group: Option<String>,
-    no_default_feature: bool,
-    force: bool,
+    options: AddEnvironmentOptions,
 ) -> miette::Result<()> {
     let environment_exists = workspace.workspace().environment(&name).is_some();
-    if environment_exists && !force {
+    if environment_exists && !options.force {
         if interface.is_cli().await {
             return Err(miette::miette!(
                 help = "use --force to overwrite the existing environment",
@@ -36,9 +73,9 @@ pub async fn add<I: Interface>(
     // Add the platforms to the lock-file
     workspace.manifest().add_environment(
         name.as_str().to_string(),
-        features,
-        solve_group,
-        no_default_feature,
+        options.features,
+        options.solve_group,
+        options.no_default_feature,
     )?;
 
     // Save the workspace to disk
@@ -79,3 +116,104 @@ pub async fn remove<I: Interface>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use pixi_manifest::{FeatureName, HasFeaturesIter};
+
+    use super::*;
+
+    struct TestInterface;
+
+    impl Interface for TestInterface {
+        async fn is_cli(&self) -> bool {
+            false
+        }
+
+        async fn confirm(&self, _msg: &str) -> miette::Result<bool> {
+            Ok(true)
+        }
+
+        async fn info(&self, _msg: &str) {}
+
+        async fn success(&self, _msg: &str) {}
+
+        async fn warning(&self, _msg: &str) {}
+
+        async fn error(&self, _msg: &str) {}
+    }
+
+    #[tokio::test]
+    async fn test_add_environment_with_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest_path = dir.path().join("pixi.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"[workspace]
+name = "foo"
+channels = []
+platforms = []
+
+[feature.test.dependencies]
+"#,
+        )
+        .unwrap();
+        let workspace = || Workspace::from_path(&manifest_path).unwrap();
+        let name = EnvironmentName::Named(String::from("test"));
+
+        add(
+            &TestInterface,
+            workspace().modify().unwrap(),
+            name.clone(),
+            AddEnvironmentOptions::default()
+                .with_features(["test"])
+                .with_solve_group("main")
+                .with_no_default_feature(true),
+        )
+        .await
+        .unwrap();
+
+        let added = workspace();
+        let environment = added.environment(&name).unwrap();
+        assert_eq!(
+            environment
+                .features()
+                .map(|f| f.name.clone())
+                .collect::<Vec<_>>(),
+            vec![FeatureName::from("test")]
+        );
+        assert_eq!(
+            environment
+                .solve_group()
+                .map(|group| group.name().to_string()),
+            Some(String::from("main"))
+        );
+
+        // An existing environment is only overwritten with `force`.
+        let err = add(
+            &TestInterface,
+            workspace().modify().unwrap(),
+            name.clone(),
+            AddEnvironmentOptions::default(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.to_string(), "the environment 'test' already exists");
+        add(
+            &TestInterface,
+            workspace().modify().unwrap(),
+            name.clone(),
+            AddEnvironmentOptions::default().with_force(true),
+        )
+        .await
+        .unwrap();
+        let overwritten = workspace();
+        assert!(
+            overwritten
+                .environment(&name)
+                .unwrap()
+                .solve_group()
+                .is_none()
+        );
+    }
+}
@@ -4,12 +4,12 @@ use indexmap::{IndexMap, IndexSet};
 use miette::IntoDiagnostic;
 use pixi_core::{Workspace, workspace::WorkspaceMut};
 use pixi_manifest::{
-    EnvironmentName, Feature, FeatureName, HasFeaturesIter, PrioritizedChannel, TargetSelector,
-    Task, TaskName,
+    DependencyOverwriteBehavior, EnvironmentName, Feature, FeatureName, HasFeaturesIter,
+    PrioritizedChannel, SpecType, TargetSelector, Task, TaskName,
 };
 use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
 use pixi_spec::PixiSpec;
-use rattler_conda_types::PackageName;
+use rattler_conda_types::{MatchSpec, PackageName, Platform};
 
 use crate::{Interface, workspace::workspace::environment};
 
@@ -91,6 +91,75 @@ pub async fn feature_by_task(
     None
 }
 
+/// The contents of a feature that is added with [`add_feature`].
+#[derive(Clone, Debug, Default)]
+pub struct AddFeatureOptions {
+    /// The conda dependencies of the feature.
+    pub dependencies: IndexMap<PackageName, MatchSpec>,
+    /// The PyPI dependencies of the feature.
+    pub pypi_dependencies: Vec<pep508_rs::Requirement>,
+    /// The tasks of the feature.
+    pub tasks: Vec<(TaskName, Task)>,
+    /// The platforms the feature supports.
+    pub platforms: Vec<Platform>,
+}
+
+pub async fn add_feature<I: Interface>(
+    interface: &I,
+    mut workspace: WorkspaceMut,
+    feature: FeatureName,
+    options: AddFeatureOptions,
+) -> miette::Result<()> {
+    let channel_config = workspace.workspace().channel_config();
+    let mut manifest = workspace.manifest();
+
+    if !manifest.add_feature(&feature)? {
+        return Err(miette::miette!(
+            help = "use `pixi add --feature` or `pixi task add --feature` to extend it",
+            "the feature '{}' already exists",
+            feature
+        ));
+    }
+
+    if !options.platforms.is_empty() {
+        manifest.add_platforms(options.platforms.iter(), &feature)?;
+    }
+
+    for (name, spec) in options.dependencies {
+        let (_, spec) = spec.into_nameless();
+        let spec = PixiSpec::from_nameless_matchspec(spec, &channel_config);
+        manifest.add_dependency(
+            &name,
+            &spec,
+            SpecType::Run,
+            &[],
+            &feature,
+            DependencyOverwriteBehavior::Overwrite,
+        )?;
+    }
+
+    for requirement in &options.pypi_dependencies {
+        manifest.add_pep508_dependency(
+            (requirement, None),
+            &[],
+            &feature,
+            None,
+            DependencyOverwriteBehavior::Overwrite,
+            None,
+        )?;
+    }
+
+    for (name, task) in options.tasks {
+        manifest.add_task(name, task, None, &feature)?;
+    }
+
+    workspace.save().await.into_diagnostic()?;
+
+    interface.success(&format!("Added feature {feature}")).await;
+
+    Ok(())
+}
+
 pub async fn remove_feature<I: Interface>(
     interface: &I,
     mut workspace: WorkspaceMut,
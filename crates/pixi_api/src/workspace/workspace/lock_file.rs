@@ -0,0 +1,204 @@
+use pixi_core::Workspace;
+use rattler_conda_types::Platform;
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::Serialize;
+
+/// A read-only view of the lock-file of a workspace.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LockFileView {
+    pub environments: Vec<LockedEnvironment>,
+}
+
+/// An environment of the lock-file with the packages of every platform.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockedEnvironment {
+    pub name: String,
+    /// The channels of the environment, in order of priority.
+    pub channels: Vec<String>,
+    pub platforms: Vec<LockedPlatform>,
+}
+
+/// The packages that are locked for a platform of an environment.
+#[derive(Debug, Clone, Serialize)]
+pub struct LockedPlatform {
+    pub platform: Platform,
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A package of the lock-file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub kind: &'static str,
+    /// The URL or path the package is installed from.
+    pub location: String,
+    pub md5: Option<String>,
+    pub sha256: Option<String>,
+}
+
+impl LockFileView {
+    /// Returns the environment with the given name.
+    pub fn environment(&self, name: &str) -> Option<&LockedEnvironment> {
+        self.environments.iter().find(|env| env.name == name)
+    }
+}
+
+impl LockedEnvironment {
+    /// Returns the packages that are locked for the given platform.
+    pub fn packages(&self, platform: Platform) -> Option<&[LockedPackage]> {
+        self.platforms
+            .iter()
+            .find(|locked| locked.platform == platform)
+            .map(|locked| locked.packages.as_slice())
+    }
+}
+
+impl From<&LockFile> for LockFileView {
+    fn from(lock_file: &LockFile) -> Self {
+        let mut environments = lock_file
+            .environments()
+            .map(|(name, environment)| LockedEnvironment {
+                name: name.to_string(),
+                channels: environment
+                    .channels()
+                    .iter()
+                    .map(|channel| channel.url.clone())
+                    .collect(),
+                platforms: environment
+                    .packages_by_platform()
+                    .map(|(platform, packages)| LockedPlatform {
+                        platform,
+                        packages: packages.map(LockedPackage::from).collect(),
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        environments.sort_by(|a, b| a.name.cmp(&b.name));
+        for environment in &mut environments {
+            environment
+                .platforms
+                .sort_by_key(|locked| locked.platform.as_str());
+        }
+        Self { environments }
+    }
+}
+
+impl From<LockedPackageRef<'_>> for LockedPackage {
+    fn from(package: LockedPackageRef<'_>) -> Self {
+        match package {
+            LockedPackageRef::Conda(conda) => {
+                let record = conda.record();
+                Self {
+                    name: record.name.as_normalized().to_string(),
+                    version: record.version.to_string(),
+                    kind: "conda",
+                    location: conda.location().to_string(),
+                    md5: record.md5.map(|hash| format!("{hash:x}")),
+                    sha256: record.sha256.map(|hash| format!("{hash:x}")),
+                }
+            }
+            LockedPackageRef::Pypi(pypi, _) => Self {
+                name: pypi.name.to_string(),
+                version: pypi.version.to_string(),
+                kind: "pypi",
+                location: pypi.location.to_string(),
+                md5: pypi
+                    .hash
+                    .as_ref()
+                    .and_then(|hash| hash.md5().map(|md5| format!("{md5:x}"))),
+                sha256: pypi
+                    .hash
+                    .as_ref()
+                    .and_then(|hash| hash.sha256().map(|sha256| format!("{sha256:x}"))),
+            },
+        }
+    }
+}
+
+/// Reads the lock-file of the workspace as it is on disk, without updating it.
+/// A workspace without a lock-file results in an empty view.
+pub async fn get(workspace: &Workspace) -> miette::Result<LockFileView> {
+    let lock_file = workspace.load_lock_file().await?.into_lock_file()?;
+    Ok(LockFileView::from(&lock_file))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_lock_file_view() {
+        let lock_file = LockFile::from_str(
+            r#"
+version: 6
+environments:
+  test:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+      osx-arm64:
+      - conda: https://conda.anaconda.org/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+packages:
+- conda: https://conda.anaconda.org/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+  sha256: e58f9eeb416b92b550e824bcb1b9fb1958dee69abfe3089dfd1a9173e3a0528a
+  md5: 19f9db5f4f1b7f5ef5f6d67207f25f38
+  license: BSD
+  size: 3566
+  timestamp: 1562343890778
+"#,
+        )
+        .unwrap();
+
+        let view = LockFileView::from(&lock_file);
+
+        // Environments and platforms are sorted by name.
+        assert_eq!(
+            view.environments
+                .iter()
+                .map(|env| env.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["default", "test"]
+        );
+        let test = view.environment("test").unwrap();
+        assert_eq!(
+            test.channels,
+            vec![String::from("https://conda.anaconda.org/conda-forge/")]
+        );
+        assert_eq!(
+            test.platforms
+                .iter()
+                .map(|locked| locked.platform)
+                .collect::<Vec<_>>(),
+            vec![Platform::Linux64, Platform::OsxArm64]
+        );
+
+        assert_eq!(
+            test.packages(Platform::Linux64).unwrap(),
+            &[LockedPackage {
+                name: String::from("_r-mutex"),
+                version: String::from("1.0.1"),
+                kind: "conda",
+                location: String::from(
+                    "https://conda.anaconda.org/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2"
+                ),
+                md5: Some(String::from("19f9db5f4f1b7f5ef5f6d67207f25f38")),
+                sha256: Some(String::from(
+                    "e58f9eeb416b92b550e824bcb1b9fb1958dee69abfe3089dfd1a9173e3a0528a"
+                )),
+            }]
+        );
+        assert!(test.packages(Platform::Win64).is_none());
+        assert!(view.environment("missing").is_none());
+    }
+}
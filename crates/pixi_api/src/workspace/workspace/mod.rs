@@ -1,3 +1,5 @@
 pub mod environment;
 pub mod feature;
+pub mod lock_file;
 pub mod name;
+pub mod solve_group;
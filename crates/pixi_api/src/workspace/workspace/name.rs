@@ -1,5 +1,7 @@
 use miette::IntoDiagnostic;
-use pixi_core::{Workspace, workspace::WorkspaceMut};
+use pixi_core::{
+    UpdateLockFileOptions, Workspace, environment::LockFileUsage, workspace::WorkspaceMut,
+};
 
 use crate::interface::Interface;
 
@@ -28,3 +30,52 @@ pub async fn set<I: Interface>(
 
     Ok(())
 }
+
+pub async fn rename<I: Interface>(
+    interface: &I,
+    mut workspace: WorkspaceMut,
+    name: &str,
+    lock_file_usage: LockFileUsage,
+) -> miette::Result<()> {
+    let old_name = workspace.workspace().display_name().to_string();
+
+    // Set the new workspace name
+    workspace.manifest().set_name(name)?;
+
+    // Save workspace
+    let workspace = workspace.save().await.into_diagnostic()?;
+
+    // The detached environments are stored in a directory named after the
+    // workspace.
+    if let Some(path) = workspace.remove_renamed_detached_environments(&old_name)? {
+        interface
+            .info(&format!(
+                "Removed the detached environments in '{}', they will be installed again under the new name",
+                path.display()
+            ))
+            .await;
+    }
+
+    // A package that inherits its name from the workspace is locked under the
+    // old name.
+    if matches!(lock_file_usage, LockFileUsage::Update) && workspace.lock_file_path().is_file() {
+        workspace
+            .update_lock_file(UpdateLockFileOptions {
+                lock_file_usage,
+                no_install: true,
+                max_concurrent_solves: workspace.config().max_concurrent_solves(),
+            })
+            .await?;
+    }
+
+    // Report back to the user
+    interface
+        .success(&format!(
+            "Renamed workspace '{}' to '{}'.",
+            old_name,
+            workspace.display_name()
+        ))
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,407 @@
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_core::{
+    UpdateLockFileOptions, Workspace, environment::LockFileUsage, workspace::SolveGroup,
+};
+use pixi_manifest::FeaturesExt;
+use rattler_conda_types::{MatchSpec, Matches, PackageName, Platform, RepoDataRecord};
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::Serialize;
+
+/// The environments of a solve group and how the group affects the locked
+/// packages.
+#[derive(Debug, Clone, Serialize)]
+pub struct SolveGroupInfo {
+    pub name: String,
+    pub platform: Platform,
+    pub environments: Vec<String>,
+    /// The packages that are locked in more than one environment of the
+    /// group, and are therefore forced to a common version.
+    pub shared_packages: Vec<SharedPackage>,
+    /// The dependencies that are locked at a lower version than their own
+    /// environment allows, because of the specs of another environment in
+    /// the group.
+    pub downgrades: Vec<Downgrade>,
+}
+
+/// A package that is locked in more than one environment of a solve group.
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedPackage {
+    pub name: String,
+    pub version: String,
+    pub kind: &'static str,
+    pub environments: Vec<String>,
+}
+
+/// A dependency of an environment that is held back by the solve group.
+#[derive(Debug, Clone, Serialize)]
+pub struct Downgrade {
+    pub environment: String,
+    pub package: String,
+    pub locked: String,
+    /// The newest version that the specs of the environment itself allow.
+    pub allowed: String,
+    /// The environments of the group whose specs exclude the `allowed`
+    /// version.
+    pub constrained_by: Vec<String>,
+}
+
+/// The options of [`list`].
+#[derive(Clone, Debug, Default)]
+pub struct ListSolveGroupsOptions {
+    /// The platform to inspect, defaults to the best platform of the first
+    /// environment of every group.
+    pub platform: Option<Platform>,
+    pub lock_file_usage: LockFileUsage,
+}
+
+impl ListSolveGroupsOptions {
+    /// Sets the platform to inspect.
+    pub fn with_platform(mut self, platform: Platform) -> Self {
+        self.platform = Some(platform);
+        self
+    }
+
+    /// Sets how the lock-file is updated before it is inspected.
+    pub fn with_lock_file_usage(mut self, lock_file_usage: LockFileUsage) -> Self {
+        self.lock_file_usage = lock_file_usage;
+        self
+    }
+}
+
+pub async fn list(
+    workspace: &Workspace,
+    options: ListSolveGroupsOptions,
+) -> miette::Result<Vec<SolveGroupInfo>> {
+    let ListSolveGroupsOptions {
+        platform,
+        lock_file_usage,
+    } = options;
+    let solve_groups = workspace.solve_groups();
+    if solve_groups.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let lock_file = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage,
+            no_install: true,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await?
+        .0
+        .into_lock_file();
+
+    let mut infos = Vec::with_capacity(solve_groups.len());
+    for solve_group in solve_groups {
+        let platform = platform.unwrap_or_else(|| {
+            solve_group
+                .environments()
+                .next()
+                .map(|env| env.best_platform())
+                .unwrap_or_else(Platform::current)
+        });
+        let shared_packages = shared_packages(&solve_group, &lock_file, platform);
+        let downgrades = downgrades(workspace, &solve_group, &lock_file, platform).await?;
+        infos.push(SolveGroupInfo {
+            name: solve_group.name().to_string(),
+            platform,
+            environments: solve_group
+                .environments()
+                .map(|env| env.name().to_string())
+                .collect(),
+            shared_packages,
+            downgrades,
+        });
+    }
+
+    Ok(infos)
+}
+
+/// Returns the packages that are locked in more than one environment of the
+/// group, sorted by name.
+fn shared_packages(
+    solve_group: &SolveGroup<'_>,
+    lock_file: &LockFile,
+    platform: Platform,
+) -> Vec<SharedPackage> {
+    let mut packages: BTreeMap<(String, &'static str), SharedPackage> = BTreeMap::new();
+    for environment in solve_group.environments() {
+        let Some(locked) = lock_file
+            .environment(environment.name().as_str())
+            .and_then(|env| env.packages(platform).map(Vec::from_iter))
+        else {
+            continue;
+        };
+        for package in locked {
+            let (name, version, kind) = match package {
+                LockedPackageRef::Conda(conda) => (
+                    conda.record().name.as_normalized().to_string(),
+                    conda.record().version.to_string(),
+                    "conda",
+                ),
+                LockedPackageRef::Pypi(pypi, _) => {
+                    (pypi.name.to_string(), pypi.version.to_string(), "pypi")
+                }
+            };
+            packages
+                .entry((name.clone(), kind))
+                .or_insert_with(|| SharedPackage {
+                    name,
+                    version,
+                    kind,
+                    environments: Vec::new(),
+                })
+                .environments
+                .push(environment.name().to_string());
+        }
+    }
+
+    packages
+        .into_values()
+        .filter(|package| package.environments.len() > 1)
+        .collect()
+}
+
+/// Returns the conda dependencies of the environments in the group that are
+/// locked at a lower version than the specs of their own environment allow,
+/// where the newer version is excluded by the specs of another environment.
+async fn downgrades(
+    workspace: &Workspace,
+    solve_group: &SolveGroup<'_>,
+    lock_file: &LockFile,
+    platform: Platform,
+) -> miette::Result<Vec<Downgrade>> {
+    let environments = solve_group.environments().collect_vec();
+    if environments.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    // All environments of the group lock the same version of a package.
+    let locked = environments
+        .iter()
+        .filter_map(|env| lock_file.environment(env.name().as_str()))
+        .flat_map(|env| {
+            env.conda_packages(platform)
+                .map(Vec::from_iter)
+                .unwrap_or_default()
+        })
+        .map(|package| {
+            let record = package.record();
+            (record.name.clone(), record.version.version().clone())
+        })
+        .collect::<HashMap<_, _>>();
+
+    // The binary specs of the direct dependencies of every environment.
+    let channel_config = workspace.channel_config();
+    let mut specs: Vec<(String, HashMap<PackageName, Vec<MatchSpec>>)> = Vec::new();
+    for environment in &environments {
+        let mut env_specs = HashMap::new();
+        for (name, pixi_specs) in environment.combined_dependencies(Some(platform)).iter() {
+            if !locked.contains_key(name) {
+                continue;
+            }
+            let mut match_specs = Vec::new();
+            for spec in pixi_specs.iter().cloned() {
+                if let Some(nameless) = spec
+                    .try_into_nameless_match_spec(&channel_config)
+                    .into_diagnostic()?
+                {
+                    match_specs.push(MatchSpec::from_nameless(
+                        nameless,
+                        Some(name.clone().into()),
+                    ));
+                }
+            }
+            if !match_specs.is_empty() {
+                env_specs.insert(name.clone(), match_specs);
+            }
+        }
+        specs.push((environment.name().to_string(), env_specs));
+    }
+
+    let names = specs
+        .iter()
+        .flat_map(|(_, env_specs)| env_specs.keys().cloned())
+        .unique()
+        .collect_vec();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let channels = solve_group
+        .channels()
+        .into_iter()
+        .cloned()
+        .map(|channel| channel.into_channel(&channel_config))
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()?;
+    let repodata = workspace
+        .repodata_gateway()?
+        .query(
+            channels,
+            [platform, Platform::NoArch],
+            names.iter().cloned().map(MatchSpec::from),
+        )
+        .await
+        .into_diagnostic()
+        .context("failed to get repodata")?;
+    let records = repodata
+        .iter()
+        .flat_map(|repodata| repodata.iter())
+        // Development versions are not considered.
+        .filter(|record| !record.package_record.version.version().is_dev())
+        .collect_vec();
+
+    let matches_all = |specs: &[MatchSpec], record: &RepoDataRecord| {
+        specs.iter().all(|spec| spec.matches(record))
+    };
+
+    let mut downgrades = Vec::new();
+    for (environment, env_specs) in &specs {
+        for (name, match_specs) in env_specs.iter().sorted_by_key(|(name, _)| *name) {
+            let current = &locked[name];
+            let Some(allowed) = records
+                .iter()
+                .copied()
+                .filter(|record| &record.package_record.name == name)
+                .filter(|record| matches_all(match_specs, record))
+                .map(|record| record.package_record.version.version())
+                .max()
+            else {
+                continue;
+            };
+            if allowed <= current {
+                continue;
+            }
+
+            // The environments that do not accept any build of the allowed
+            // version.
+            let candidates = records
+                .iter()
+                .copied()
+                .filter(|record| {
+                    &record.package_record.name == name
+                        && record.package_record.version.version() == allowed
+                        && matches_all(match_specs, record)
+                })
+                .collect_vec();
+            let constrained_by = specs
+                .iter()
+                .filter(|(other, _)| other != environment)
+                .filter(|(_, other_specs)| {
+                    other_specs.get(name).is_some_and(|other_specs| {
+                        !candidates
+                            .iter()
+                            .any(|record| matches_all(other_specs, record))
+                    })
+                })
+                .map(|(other, _)| other.clone())
+                .collect_vec();
+            if constrained_by.is_empty() {
+                continue;
+            }
+
+            downgrades.push(Downgrade {
+                environment: environment.clone(),
+                package: name.as_source().to_string(),
+                locked: current.to_string(),
+                allowed: allowed.to_string(),
+                constrained_by,
+            });
+        }
+    }
+
+    Ok(downgrades)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use super::*;
+
+    #[test]
+    fn test_shared_packages() {
+        let workspace = Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = ["conda-forge"]
+        platforms = ["linux-64"]
+
+        [feature.test.dependencies]
+        pytest = "*"
+
+        [environments]
+        default = { solve-group = "main" }
+        test = { features = ["test"], solve-group = "main" }
+        "#,
+        )
+        .unwrap();
+        let lock_file = LockFile::from_str(
+            r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.1-h0_0.conda
+  test:
+    channels:
+    - url: https://conda.anaconda.org/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.1-h0_0.conda
+      - conda: https://conda.anaconda.org/conda-forge/noarch/pytest-8.3.4-pyhd8ed1ab_0.conda
+packages:
+- conda: https://conda.anaconda.org/conda-forge/linux-64/python-3.12.1-h0_0.conda
+  sha256: e58f9eeb416b92b550e824bcb1b9fb1958dee69abfe3089dfd1a9173e3a0528a
+  md5: 19f9db5f4f1b7f5ef5f6d67207f25f38
+  license: PSF-2.0
+  size: 3566
+  timestamp: 1562343890778
+- conda: https://conda.anaconda.org/conda-forge/noarch/pytest-8.3.4-pyhd8ed1ab_0.conda
+  sha256: e58f9eeb416b92b550e824bcb1b9fb1958dee69abfe3089dfd1a9173e3a0528a
+  md5: 19f9db5f4f1b7f5ef5f6d67207f25f38
+  license: MIT
+  size: 3566
+  timestamp: 1562343890778
+"#,
+        )
+        .unwrap();
+
+        let solve_groups = workspace.solve_groups();
+        let [solve_group] = solve_groups.as_slice() else {
+            panic!("expected a single solve group");
+        };
+
+        // Only the packages that are locked in both environments are shared.
+        let shared = shared_packages(solve_group, &lock_file, Platform::Linux64);
+        let shared = shared
+            .iter()
+            .map(|package| {
+                (
+                    package.name.as_str(),
+                    package.version.as_str(),
+                    package.kind,
+                    package.environments.clone(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(
+            shared,
+            vec![(
+                "python",
+                "3.12.1",
+                "conda",
+                vec![String::from("default"), String::from("test")]
+            )]
+        );
+    }
+}
@@ -3,12 +3,22 @@
 //! This crate provides a centralized way to create and configure authentication
 //! storage that respects pixi's configuration settings.
 
-use std::{any::Any, path::PathBuf, sync::Arc};
+mod oidc;
 
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+pub use oidc::{OidcError, OidcMiddleware};
 use pixi_config::Config;
 use rattler_networking::{
-    AuthenticationMiddleware, AuthenticationStorage,
-    authentication_storage::{self, AuthenticationStorageError},
+    Authentication, AuthenticationMiddleware, AuthenticationStorage,
+    authentication_storage::{
+        AuthenticationStorageError, StorageBackend,
+        backends::{
+            file::{FileStorage, FileStorageError},
+            keyring::KeyringAuthenticationStorage,
+            netrc::NetRcStorage,
+        },
+    },
 };
 
 /// Creates an [`AuthenticationStorage`] configured according to pixi's settings.
@@ -16,7 +26,9 @@ use rattler_networking::{
 /// This respects:
 /// - Environment variables (`RATTLER_AUTH_FILE`)
 /// - Pixi config's `authentication_override_file` setting
-/// - Default system keyring/credential storage
+/// - Pixi config's `auth.storage` setting, which selects whether the system
+///   keyring, the credentials file or both are used
+/// - Credentials from the `.netrc` file
 ///
 /// # Example
 ///
@@ -30,7 +42,17 @@ use rattler_networking::{
 pub fn get_auth_store(
     config: &Config,
 ) -> Result<AuthenticationStorage, AuthenticationStorageError> {
-    let mut store = AuthenticationStorage::from_env_and_defaults()?;
+    let mut store = AuthenticationStorage::empty();
+
+    if let Ok(auth_file) = std::env::var("RATTLER_AUTH_FILE") {
+        tracing::info!(
+            "\"RATTLER_AUTH_FILE\" environment variable set, using file storage at {}",
+            auth_file
+        );
+        store.add_backend(Arc::from(FileStorage::from_path(PathBuf::from(auth_file))?));
+    }
+
+    // This should be the first place before the keyring authentication
     if let Some(auth_file) = config.authentication_override_file() {
         tracing::info!("Loading authentication from file: {:?}", auth_file);
 
@@ -38,28 +60,53 @@ pub fn get_auth_store(
             tracing::warn!("Authentication file does not exist: {:?}", auth_file);
         }
 
-        // This should be the first place before the keyring authentication
-        // i.e. either index 0 if RATTLER_AUTH_FILE is not set or index 1 if it is
-        let first_storage = store.backends.first().unwrap();
-        let index = if first_storage.type_id()
-            == std::any::TypeId::of::<authentication_storage::backends::file::FileStorage>()
-        {
-            1
-        } else {
-            0
-        };
-        store.backends.insert(
-            index,
-            Arc::from(
-                authentication_storage::backends::file::FileStorage::from_path(PathBuf::from(
-                    auth_file,
-                ))?,
-            ),
-        );
+        store.add_backend(Arc::from(FileStorage::from_path(auth_file.clone())?));
+    }
+
+    let storage = config.credential_storage();
+    if storage.uses_keyring() {
+        store.add_backend(Arc::from(KeyringAuthenticationStorage::default()));
+    }
+    if storage.uses_file() {
+        store.add_backend(Arc::from(FileStorage::new()?));
     }
+
+    store.add_backend(Arc::from(NetRcStorage::from_env().unwrap_or_else(
+        |(path, err)| {
+            tracing::warn!("error reading netrc file from {}: {}", path.display(), err);
+            NetRcStorage::default()
+        },
+    )));
+
     Ok(store)
 }
 
+/// Moves all credentials from the plaintext credentials file into the system
+/// keyring.
+///
+/// Credentials are only removed from the file after they have been stored in
+/// the keyring successfully. Returns the hosts that were migrated.
+pub fn migrate_credentials_to_keyring() -> Result<Vec<String>, AuthenticationStorageError> {
+    let file = FileStorage::new()?;
+    let keyring = KeyringAuthenticationStorage::default();
+
+    let credentials: BTreeMap<String, Authentication> = match fs_err::read_to_string(&file.path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|err| FileStorageError::JSONError(file.path.clone(), err))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(err) => return Err(FileStorageError::IOError(err).into()),
+    };
+
+    let mut migrated = Vec::with_capacity(credentials.len());
+    for (host, authentication) in credentials {
+        keyring.store(&host, &authentication)?;
+        file.delete(&host)?;
+        migrated.push(host);
+    }
+
+    Ok(migrated)
+}
+
 /// Creates an [`AuthenticationMiddleware`] configured according to pixi's settings.
 ///
 /// This is a convenience function that creates an auth store and wraps it in middleware.
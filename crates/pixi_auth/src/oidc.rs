@@ -0,0 +1,199 @@
+//! Short-lived tokens obtained through OIDC.
+//!
+//! In CI it is preferable to not store long-lived credentials at all. Instead,
+//! the identity token issued by the CI provider is exchanged for a token that
+//! is only valid for a short amount of time. This module implements a
+//! middleware that performs this exchange for the hosts configured in
+//! `auth.oidc` and refreshes the token once it expires.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use http::Extensions;
+use pixi_config::OidcConfig;
+use reqwest::{
+    Request, Response,
+    header::{AUTHORIZATION, HeaderValue},
+};
+use reqwest_middleware::{Middleware, Next};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use url::Url;
+
+/// How long a token is assumed to be valid if the token endpoint does not
+/// report an expiry.
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(5 * 60);
+
+/// Tokens are refreshed this long before they actually expire to account for
+/// clock skew and in-flight requests.
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error(
+        "no OIDC identity token available, set '{0}' or run in a GitHub Actions job with `id-token: write` permissions"
+    )]
+    MissingIdToken(String),
+
+    #[error("failed to request an OIDC identity token")]
+    IdToken(#[source] reqwest::Error),
+
+    #[error("failed to exchange the OIDC identity token at '{0}'")]
+    Exchange(Url, #[source] reqwest::Error),
+
+    #[error("the token returned by '{0}' is not a valid header value")]
+    InvalidToken(Url),
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubIdToken {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangedToken {
+    token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    expires_at: Instant,
+}
+
+/// Middleware that authenticates requests with short-lived tokens obtained
+/// through OIDC.
+///
+/// Requests that already carry an `Authorization` header are left untouched.
+#[derive(Clone)]
+pub struct OidcMiddleware {
+    client: reqwest::Client,
+    hosts: HashMap<String, OidcConfig>,
+    tokens: Arc<Mutex<HashMap<String, CachedToken>>>,
+}
+
+impl OidcMiddleware {
+    /// Constructs the middleware for the hosts configured in `auth.oidc`.
+    pub fn new(client: reqwest::Client, hosts: HashMap<String, OidcConfig>) -> Self {
+        Self {
+            client,
+            hosts,
+            tokens: Arc::default(),
+        }
+    }
+
+    /// Returns a valid token for the given host, exchanging a new identity
+    /// token if there is no cached token or it is about to expire.
+    async fn token(&self, host: &str, oidc: &OidcConfig) -> Result<String, OidcError> {
+        // Holding the lock during the exchange makes sure that concurrent
+        // requests do not all exchange a new token.
+        let mut tokens = self.tokens.lock().await;
+        if let Some(cached) = tokens.get(host) {
+            if cached.expires_at > Instant::now() + REFRESH_MARGIN {
+                return Ok(cached.token.clone());
+            }
+            tracing::debug!("OIDC token for '{host}' expired, refreshing");
+        }
+
+        let id_token = self.id_token(oidc).await?;
+        let exchanged: ExchangedToken = self
+            .client
+            .post(oidc.token_endpoint.clone())
+            .json(&serde_json::json!({ "token": id_token }))
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .map_err(|err| OidcError::Exchange(oidc.token_endpoint.clone(), err))?
+            .json()
+            .await
+            .map_err(|err| OidcError::Exchange(oidc.token_endpoint.clone(), err))?;
+
+        let lifetime = exchanged
+            .expires_in
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TOKEN_LIFETIME);
+        tokens.insert(
+            host.to_string(),
+            CachedToken {
+                token: exchanged.token.clone(),
+                expires_at: Instant::now() + lifetime,
+            },
+        );
+
+        Ok(exchanged.token)
+    }
+
+    /// Retrieves the identity token of the CI provider.
+    async fn id_token(&self, oidc: &OidcConfig) -> Result<String, OidcError> {
+        if let Some(env) = &oidc.id_token_env {
+            return std::env::var(env).map_err(|_| OidcError::MissingIdToken(env.clone()));
+        }
+
+        // GitHub Actions exposes an endpoint to request identity tokens.
+        let (Ok(request_url), Ok(request_token)) = (
+            std::env::var("ACTIONS_ID_TOKEN_REQUEST_URL"),
+            std::env::var("ACTIONS_ID_TOKEN_REQUEST_TOKEN"),
+        ) else {
+            return Err(OidcError::MissingIdToken(String::from(
+                "ACTIONS_ID_TOKEN_REQUEST_URL",
+            )));
+        };
+
+        let mut request = self.client.get(request_url).bearer_auth(request_token);
+        if let Some(audience) = &oidc.audience {
+            request = request.query(&[("audience", audience)]);
+        }
+
+        let token: GithubIdToken = request
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .map_err(OidcError::IdToken)?
+            .json()
+            .await
+            .map_err(OidcError::IdToken)?;
+
+        Ok(token.value)
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for OidcMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if req.headers().contains_key(AUTHORIZATION) {
+            return next.run(req, extensions).await;
+        }
+
+        let Some((host, oidc)) = req
+            .url()
+            .host_str()
+            .and_then(|host| self.hosts.get_key_value(host))
+        else {
+            return next.run(req, extensions).await;
+        };
+
+        let token = self
+            .token(host, oidc)
+            .await
+            .map_err(reqwest_middleware::Error::middleware)?;
+        let mut header = HeaderValue::from_str(&format!("Bearer {token}")).map_err(|_| {
+            reqwest_middleware::Error::middleware(OidcError::InvalidToken(
+                oidc.token_endpoint.clone(),
+            ))
+        })?;
+        header.set_sensitive(true);
+        req.headers_mut().insert(AUTHORIZATION, header);
+
+        next.run(req, extensions).await
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::PathBuf;
+
+/// Copies the JSON Schema of the manifest into the output directory so that
+/// the crate doesn't include files from outside of its own directory.
+fn main() {
+    let manifest_dir = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("set by cargo"));
+
+    let schema = manifest_dir.join("../../schema/schema.json");
+    println!("cargo:rerun-if-changed={}", schema.display());
+    std::fs::copy(&schema, out_dir.join("schema.json")).unwrap_or_else(|err| {
+        panic!(
+            "failed to copy the manifest schema from '{}': {err}",
+            schema.display()
+        )
+    });
+}
@@ -4,7 +4,7 @@ use pixi_api::{
     workspace::{DependencyOptions, GitOptions},
 };
 use pixi_config::ConfigCli;
-use pixi_core::{DependencyType, WorkspaceLocator};
+use pixi_core::DependencyType;
 
 use crate::{
     cli_config::{DependencyConfig, LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig},
@@ -58,6 +58,9 @@ use crate::{
 ///   `dependency-groups.aws` array
 /// - `pixi add --pypi --editable 'boto3 @ file://absolute/path/to/boto3'` will add
 ///   the local editable `boto3` to the `pypi-dependencies` array
+/// - `pixi add --pypi --editable ./libs/mylib` will add the local package at
+///   `./libs/mylib` as an editable dependency, its name is read from its
+///   `pyproject.toml`
 ///
 /// Note that if `--platform` or `--editable` are specified, the pypi dependency
 /// will be added to the `tool.pixi.pypi-dependencies` table instead as native
@@ -92,6 +95,11 @@ pub struct Args {
     /// Whether the pypi requirement should be editable
     #[arg(long, requires = "pypi")]
     pub editable: bool,
+
+    /// Create the feature or platforms that the dependencies are added to
+    /// without asking, if they don't exist yet.
+    #[arg(short = 'y', long = "yes", alias = "assume-yes")]
+    pub assume_yes: bool,
 }
 
 impl TryFrom<&Args> for DependencyOptions {
@@ -103,6 +111,8 @@ impl TryFrom<&Args> for DependencyOptions {
             platforms: args.dependency_config.platforms.clone(),
             no_install: args.no_install_config.no_install,
             lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            dry_run: false,
+            assume_yes: args.assume_yes,
         })
     }
 }
@@ -122,9 +132,12 @@ impl From<&Args> for GitOptions {
     }
 }
 
-pub async fn execute(args: Args) -> miette::Result<()> {
-    let mut workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+pub async fn execute(mut args: Args) -> miette::Result<()> {
+    args.dependency_config.read_specs_from_file()?;
+
+    let mut workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone());
 
@@ -169,10 +182,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                     .collect(),
                 None => args
                     .dependency_config
-                    .pypi_deps(&workspace)?
-                    .into_iter()
-                    .map(|(name, req)| (name, (req, None, None)))
-                    .collect(),
+                    .pypi_requirements(&workspace, args.editable)?,
             };
 
             workspace_ctx
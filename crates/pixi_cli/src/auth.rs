@@ -0,0 +1,307 @@
+use clap::Parser;
+use miette::{Context, IntoDiagnostic};
+use pixi_auth::{get_auth_store, migrate_credentials_to_keyring};
+use pixi_config::Config;
+use rattler_networking::{Authentication, AuthenticationStorage};
+use reqwest::header::CONTENT_TYPE;
+use url::Url;
+
+/// Command line arguments that contain authentication data
+#[derive(Parser, Debug)]
+pub struct LoginArgs {
+    /// The host to authenticate with (e.g. prefix.dev)
+    host: String,
+
+    /// The token to use (for authentication with prefix.dev)
+    #[clap(long)]
+    token: Option<String>,
+
+    /// The username to use (for basic HTTP authentication)
+    #[clap(long)]
+    username: Option<String>,
+
+    /// The password to use (for basic HTTP authentication)
+    #[clap(long)]
+    password: Option<String>,
+
+    /// The token to use on anaconda.org / quetz authentication
+    #[clap(long)]
+    conda_token: Option<String>,
+
+    /// The S3 access key ID
+    #[clap(long, requires_all = ["s3_secret_access_key"], conflicts_with_all = ["token", "username", "password", "conda_token"])]
+    s3_access_key_id: Option<String>,
+
+    /// The S3 secret access key
+    #[clap(long, requires_all = ["s3_access_key_id"])]
+    s3_secret_access_key: Option<String>,
+
+    /// The S3 session token
+    #[clap(long, requires_all = ["s3_access_key_id"])]
+    s3_session_token: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct LogoutArgs {
+    /// The host to remove authentication for
+    host: String,
+}
+
+#[derive(Parser, Debug)]
+pub enum Subcommand {
+    /// Store authentication information for a given host
+    Login(LoginArgs),
+    /// Remove authentication information for a given host
+    Logout(LogoutArgs),
+    /// Move credentials from the plaintext credentials file into the system
+    /// keyring
+    Migrate,
+}
+
+/// Login to prefix.dev or anaconda.org servers to access private channels
+///
+/// Credentials are stored according to the `auth.storage` configuration,
+/// which uses the system keyring when available by default.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(subcommand)]
+    subcommand: Subcommand,
+}
+
+/// Returns the key under which the credentials for the given host are
+/// stored. This strips the scheme and uses a wildcard for top-level domains.
+fn storage_key(host: &str) -> miette::Result<String> {
+    let host = if host.contains("://") {
+        Url::parse(host)
+            .into_diagnostic()?
+            .host_str()
+            .ok_or_else(|| miette::miette!("'{host}' does not contain a host"))?
+            .to_string()
+    } else {
+        host.to_string()
+    };
+
+    if host.matches('.').count() == 1 {
+        Ok(format!("*.{host}"))
+    } else {
+        Ok(host)
+    }
+}
+
+fn authentication_from_args(args: &LoginArgs) -> miette::Result<Authentication> {
+    let auth = if let Some(conda_token) = &args.conda_token {
+        Authentication::CondaToken(conda_token.clone())
+    } else if let Some(username) = &args.username {
+        let Some(password) = &args.password else {
+            miette::bail!("Password must be provided when using basic authentication");
+        };
+        Authentication::BasicHTTP {
+            username: username.clone(),
+            password: password.clone(),
+        }
+    } else if let Some(token) = &args.token {
+        Authentication::BearerToken(token.clone())
+    } else if let (Some(access_key_id), Some(secret_access_key)) =
+        (&args.s3_access_key_id, &args.s3_secret_access_key)
+    {
+        Authentication::S3Credentials {
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret_access_key.clone(),
+            session_token: args.s3_session_token.clone(),
+        }
+    } else {
+        miette::bail!("No authentication method provided");
+    };
+
+    if args.host.contains("prefix.dev") && !matches!(auth, Authentication::BearerToken(_)) {
+        miette::bail!(
+            "Authentication with prefix.dev requires a token. Use `--token` to provide one"
+        );
+    }
+
+    if args.host.contains("anaconda.org") && !matches!(auth, Authentication::CondaToken(_)) {
+        miette::bail!(
+            "Authentication with anaconda.org requires a conda token. Use `--conda-token` to provide one"
+        );
+    }
+
+    if args.host.contains("s3://") != matches!(auth, Authentication::S3Credentials { .. }) {
+        miette::bail!(
+            "Authentication with S3 requires a S3 access key ID and a secret access key. Use `--s3-access-key-id` and `--s3-secret-access-key` to provide them"
+        );
+    }
+
+    Ok(auth)
+}
+
+/// Validates a prefix.dev token and returns the name of the user it belongs
+/// to, or `None` if the token is invalid.
+async fn validate_prefix_dev_token(
+    client: &reqwest::Client,
+    token: &str,
+    host: &str,
+) -> miette::Result<Option<(String, Url)>> {
+    let prefix_url = if let Ok(url) = std::env::var("PREFIX_DEV_API_URL") {
+        Url::parse(&url)
+            .into_diagnostic()
+            .context("PREFIX_DEV_API_URL must be a valid URL")?
+    } else {
+        let host = host.replace("*.", "");
+        let host_url = if host.contains("://") {
+            Url::parse(&host).into_diagnostic()?
+        } else {
+            Url::parse(&format!("https://{host}")).into_diagnostic()?
+        };
+        let host = host_url.host_str().unwrap_or("prefix.dev");
+        let host = host.strip_prefix("repo.").unwrap_or(host);
+        Url::parse(&format!("https://{host}")).into_diagnostic()?
+    };
+
+    let response: serde_json::Value = client
+        .post(prefix_url.join("api/graphql").into_diagnostic()?)
+        .bearer_auth(token)
+        .header(CONTENT_TYPE, "application/json")
+        .body(r#"{"query":"query { viewer { login } }"}"#)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .into_diagnostic()?
+        .text()
+        .await
+        .into_diagnostic()
+        .and_then(|text| serde_json::from_str(&text).into_diagnostic())
+        .context("failed to parse the response of prefix.dev")?;
+
+    Ok(response["data"]["viewer"]["login"]
+        .as_str()
+        .map(|login| (login.to_string(), prefix_url)))
+}
+
+async fn login(
+    args: LoginArgs,
+    config: &Config,
+    storage: AuthenticationStorage,
+) -> miette::Result<()> {
+    let auth = authentication_from_args(&args)?;
+    let host = storage_key(&args.host)?;
+    eprintln!(
+        "Authenticating with {host} using {} method ({} storage)",
+        auth.method(),
+        config.credential_storage()
+    );
+
+    // Only validate the token for prefix.dev
+    if args.host.contains("prefix.dev")
+        && let Authentication::BearerToken(token) = &auth
+    {
+        let client = pixi_utils::reqwest::LazyReqwestClient::new(config)?.into_client();
+        let Some((username, url)) = validate_prefix_dev_token(&client, token, &args.host).await?
+        else {
+            miette::bail!("Unauthorized or invalid token");
+        };
+        println!(
+            "{}Token is valid. Logged into {url} as \"{username}\". Storing credentials...",
+            console::style(console::Emoji("✅ ", "")).green()
+        );
+    }
+
+    storage
+        .store(&host, &auth)
+        .map_err(|err| miette::miette!("{err}"))
+        .context("failed to store the credentials")
+}
+
+fn logout(args: LogoutArgs, storage: AuthenticationStorage) -> miette::Result<()> {
+    let host = storage_key(&args.host)?;
+    println!("Removing authentication for {host}");
+    storage
+        .delete(&host)
+        .map_err(|err| miette::miette!("{err}"))
+        .context("failed to remove the credentials")
+}
+
+fn migrate(config: &Config) -> miette::Result<()> {
+    if !config.credential_storage().uses_keyring() {
+        miette::bail!(
+            "credentials are configured to be stored in a file (`auth.storage = \"{}\"`), set it to `keyring` or `auto` before migrating",
+            config.credential_storage()
+        );
+    }
+
+    let migrated = migrate_credentials_to_keyring()
+        .into_diagnostic()
+        .context("failed to migrate credentials to the system keyring")?;
+    if migrated.is_empty() {
+        eprintln!("No credentials found in the credentials file");
+    }
+    for host in migrated {
+        eprintln!(
+            "{}Moved credentials for {host} to the system keyring",
+            console::style(console::Emoji("✔ ", "")).green()
+        );
+    }
+    Ok(())
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::load_global();
+    let storage = get_auth_store(&config).into_diagnostic()?;
+
+    match args.subcommand {
+        Subcommand::Login(args) => login(args, &config, storage).await,
+        Subcommand::Logout(args) => logout(args, storage),
+        Subcommand::Migrate => migrate(&config),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn login_args(host: &str) -> LoginArgs {
+        LoginArgs {
+            host: host.to_string(),
+            token: None,
+            username: None,
+            password: None,
+            conda_token: None,
+            s3_access_key_id: None,
+            s3_secret_access_key: None,
+            s3_session_token: None,
+        }
+    }
+
+    #[test]
+    fn test_storage_key() {
+        assert_eq!(storage_key("prefix.dev").unwrap(), "*.prefix.dev");
+        assert_eq!(storage_key("repo.prefix.dev").unwrap(), "repo.prefix.dev");
+        assert_eq!(
+            storage_key("https://myquetz.server.org/channel").unwrap(),
+            "myquetz.server.org"
+        );
+    }
+
+    #[test]
+    fn test_authentication_from_args() {
+        let mut args = login_args("example.com");
+        assert!(authentication_from_args(&args).is_err());
+
+        args.username = Some("user".to_string());
+        assert!(authentication_from_args(&args).is_err());
+
+        args.password = Some("pass".to_string());
+        assert!(matches!(
+            authentication_from_args(&args).unwrap(),
+            Authentication::BasicHTTP { .. }
+        ));
+
+        let mut args = login_args("prefix.dev");
+        args.conda_token = Some("token".to_string());
+        assert!(authentication_from_args(&args).is_err());
+
+        let mut args = login_args("example.com");
+        args.s3_access_key_id = Some("key".to_string());
+        args.s3_secret_access_key = Some("secret".to_string());
+        assert!(authentication_from_args(&args).is_err());
+    }
+}
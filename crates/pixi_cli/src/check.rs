@@ -0,0 +1,416 @@
+use std::{collections::HashSet, ops::Range, str::FromStr, sync::Arc};
+
+use clap::Parser;
+use futures::StreamExt;
+use itertools::Itertools;
+use miette::{Diagnostic, IntoDiagnostic, NamedSource, Report, Severity, SourceSpan};
+use pixi_config::ConfigCli;
+use pixi_core::Workspace;
+use pixi_manifest::{ManifestKind, WithWarnings};
+use rattler_conda_types::NamedChannelOrUrl;
+use thiserror::Error;
+use toml_edit::{Item, TableLike, Value};
+
+use crate::{cli_config::WorkspaceConfig, doctor::is_channel_reachable};
+
+/// Check the manifest of the workspace for problems.
+///
+/// Reports unknown keys, features that are not used by any environment,
+/// targets for platforms the workspace does not support, tasks that depend on
+/// tasks that do not exist and channels that cannot be reached. Exits with a
+/// non-zero code if an error was found.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+/// A task depends on a task that is not defined in the workspace.
+#[derive(Debug, Error, Diagnostic)]
+#[error("the task '{task}' depends on '{dependency}', which does not exist")]
+#[diagnostic(help(
+    "Define the task '{dependency}' or remove it from the dependencies of '{task}'"
+))]
+struct MissingTaskDependency {
+    task: String,
+    dependency: String,
+    #[source_code]
+    source: NamedSource<Arc<str>>,
+    #[label("this task does not exist")]
+    span: Option<SourceSpan>,
+}
+
+/// A target does not match any of the platforms it can be used on.
+#[derive(Debug, Error, Diagnostic)]
+#[error("the target '{target}' does not match any platform of {owner}")]
+#[diagnostic(
+    severity(Warning),
+    help("Add a matching platform to {owner}, or remove the target")
+)]
+struct UnsupportedTarget {
+    target: String,
+    owner: String,
+    #[source_code]
+    source: NamedSource<Arc<str>>,
+    #[label("this target is never used")]
+    span: Option<SourceSpan>,
+}
+
+/// A channel of the workspace cannot be reached.
+#[derive(Debug, Error, Diagnostic)]
+#[error("the channel '{channel}' could not be reached")]
+#[diagnostic(help(
+    "Check the name of the channel and your network connection, and log in with `pixi auth login` if the channel is private"
+))]
+struct UnreachableChannel {
+    channel: String,
+    #[source_code]
+    source: NamedSource<Arc<str>>,
+    #[label("the repodata of this channel could not be fetched")]
+    span: Option<SourceSpan>,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    // Errors in the manifest, like unknown keys, fail the discovery of the
+    // workspace. Problems that do not, like unused features, are warnings.
+    let WithWarnings {
+        value: workspace,
+        warnings,
+    } = args
+        .workspace_config
+        .workspace_locator()
+        .with_emit_warnings(false)
+        .locate_with_warnings()?;
+    let workspace = workspace.with_cli_config(args.config);
+
+    let provenance = &workspace.workspace.provenance;
+    let contents: Arc<str> = Arc::from(provenance.read().into_diagnostic()?.into_inner());
+    let source = NamedSource::new(
+        provenance.absolute_path().to_string_lossy(),
+        contents.clone(),
+    );
+    let document = toml_edit::Document::parse(contents.as_ref()).into_diagnostic()?;
+    let pixi_table = match provenance.kind {
+        ManifestKind::Pyproject => document
+            .as_table()
+            .get("tool")
+            .and_then(|tool| tool.get("pixi"))
+            .and_then(Item::as_table_like),
+        ManifestKind::Pixi | ManifestKind::MojoProject => Some(document.as_table() as _),
+    };
+    let pixi_tables = pixi_table.map(nested_pixi_tables).unwrap_or_default();
+
+    let mut diagnostics = warnings.into_iter().map(Report::from).collect_vec();
+    diagnostics.extend(
+        check_task_dependencies(&pixi_tables, &source)
+            .into_iter()
+            .map(Report::from),
+    );
+    diagnostics.extend(
+        check_target_platforms(&workspace, &source)
+            .into_iter()
+            .map(Report::from),
+    );
+    diagnostics.extend(
+        check_channels(&workspace, &pixi_tables, &source)
+            .await?
+            .into_iter()
+            .map(Report::from),
+    );
+
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic:?}");
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity().unwrap_or(Severity::Error) == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if diagnostics.is_empty() {
+        eprintln!(
+            "{}No problems found in {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            provenance.path.display()
+        );
+    } else {
+        eprintln!(
+            "{}Found {errors} error{} and {warnings} warning{} in {}",
+            if errors > 0 {
+                console::style(console::Emoji("✘ ", "")).red()
+            } else {
+                console::style(console::Emoji("⚠ ", "")).yellow()
+            },
+            if errors == 1 { "" } else { "s" },
+            if warnings == 1 { "" } else { "s" },
+            provenance.path.display()
+        );
+    }
+
+    if errors > 0 {
+        return Err(crate::ExitStatus(1).into());
+    }
+
+    Ok(())
+}
+
+/// Returns the pixi table itself and the nested tables that can define tasks
+/// and channels: its targets, its features and the targets of its features.
+fn nested_pixi_tables(pixi_table: &dyn TableLike) -> Vec<&dyn TableLike> {
+    let features = pixi_table
+        .get("feature")
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|features| features.iter())
+        .filter_map(|(_, feature)| feature.as_table_like());
+    std::iter::once(pixi_table)
+        .chain(features)
+        .flat_map(with_targets)
+        .collect()
+}
+
+/// Returns the table and its `target` tables.
+fn with_targets(table: &dyn TableLike) -> Vec<&dyn TableLike> {
+    let targets = table
+        .get("target")
+        .and_then(Item::as_table_like)
+        .into_iter()
+        .flat_map(|targets| targets.iter())
+        .filter_map(|(_, target)| target.as_table_like());
+    std::iter::once(table).chain(targets).collect()
+}
+
+/// A task dependency together with the span of its name in the manifest.
+struct TaskDependency<'a> {
+    name: &'a str,
+    span: Option<Range<usize>>,
+}
+
+/// Returns the dependency defined by an entry of `depends-on` or of an alias.
+fn task_dependency(value: &Value) -> Option<TaskDependency<'_>> {
+    match value {
+        Value::String(name) => Some(TaskDependency {
+            name: name.value(),
+            span: value.span(),
+        }),
+        Value::InlineTable(table) => {
+            let name = table.get("task")?;
+            Some(TaskDependency {
+                name: name.as_str()?,
+                span: name.span(),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the dependencies of a task.
+fn task_dependencies(task: &Item) -> Vec<TaskDependency<'_>> {
+    // An alias is defined as an array of dependencies.
+    if let Some(alias) = task.as_array() {
+        return alias.iter().filter_map(task_dependency).collect();
+    }
+
+    let Some(depends_on) = task
+        .as_table_like()
+        .and_then(|task| task.get("depends-on").or_else(|| task.get("depends_on")))
+        .and_then(Item::as_value)
+    else {
+        return Vec::new();
+    };
+    match depends_on {
+        Value::Array(dependencies) => dependencies.iter().filter_map(task_dependency).collect(),
+        value => task_dependency(value).into_iter().collect(),
+    }
+}
+
+/// Checks that the tasks only depend on tasks that are defined in the
+/// workspace.
+fn check_task_dependencies(
+    pixi_tables: &[&dyn TableLike],
+    source: &NamedSource<Arc<str>>,
+) -> Vec<MissingTaskDependency> {
+    let tasks = pixi_tables
+        .iter()
+        .filter_map(|table| table.get("tasks").and_then(Item::as_table_like))
+        .flat_map(|tasks| tasks.iter())
+        .collect_vec();
+    let defined = tasks.iter().map(|(name, _)| *name).collect::<HashSet<_>>();
+
+    tasks
+        .iter()
+        .flat_map(|(task, item)| {
+            task_dependencies(item)
+                .into_iter()
+                .filter(|dependency| !defined.contains(dependency.name))
+                .map(|dependency| MissingTaskDependency {
+                    task: task.to_string(),
+                    dependency: dependency.name.to_string(),
+                    source: source.clone(),
+                    span: dependency.span.map(SourceSpan::from),
+                })
+        })
+        .collect()
+}
+
+/// Checks that the targets match at least one of the platforms they can be
+/// used on: the platforms of their feature if it restricts them, otherwise the
+/// platforms of the workspace.
+fn check_target_platforms(
+    workspace: &Workspace,
+    source: &NamedSource<Arc<str>>,
+) -> Vec<UnsupportedTarget> {
+    let manifest = &workspace.workspace.value;
+    manifest
+        .features
+        .iter()
+        .flat_map(|(name, feature)| {
+            let (platforms, owner) = match &feature.platforms {
+                Some(platforms) if !name.is_default() => {
+                    (platforms, format!("the feature '{name}'"))
+                }
+                _ => (&manifest.workspace.platforms, String::from("the workspace")),
+            };
+            feature
+                .targets
+                .user_defined_selectors()
+                .filter(|selector| !platforms.iter().any(|platform| selector.matches(*platform)))
+                .map(|selector| UnsupportedTarget {
+                    target: selector.to_string(),
+                    owner: owner.clone(),
+                    source: source.clone(),
+                    span: feature.targets.source_loc(selector).map(SourceSpan::from),
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Checks that the channels defined in the manifest can be reached.
+async fn check_channels(
+    workspace: &Workspace,
+    pixi_tables: &[&dyn TableLike],
+    source: &NamedSource<Arc<str>>,
+) -> miette::Result<Vec<UnreachableChannel>> {
+    let channels = pixi_tables
+        .iter()
+        .flat_map(|table| {
+            // The channels of the workspace are defined in its `[workspace]`
+            // or legacy `[project]` table.
+            ["workspace", "project"]
+                .into_iter()
+                .filter_map(|name| table.get(name).and_then(Item::as_table_like))
+                .chain(std::iter::once(*table))
+                .filter_map(|table| table.get("channels").and_then(Item::as_array))
+                .flat_map(|channels| channels.iter())
+                .collect_vec()
+        })
+        .filter_map(|value| match value {
+            Value::String(channel) => Some((channel.value(), value.span())),
+            Value::InlineTable(table) => {
+                let channel = table.get("channel")?;
+                Some((channel.as_str()?, channel.span()))
+            }
+            _ => None,
+        })
+        .collect_vec();
+
+    let channel_config = workspace.channel_config();
+    let client = workspace.authenticated_client()?.clone();
+    let unreachable: Vec<_> = futures::stream::iter(channels)
+        .map(|(channel, span)| {
+            let client = client.clone();
+            let url = NamedChannelOrUrl::from_str(channel)
+                .ok()
+                .and_then(|channel| channel.into_base_url(&channel_config).ok());
+            async move {
+                // Channels that cannot be parsed fail the discovery of the
+                // workspace, so they are not reported again.
+                let url = url?;
+                (!is_channel_reachable(&client, &url).await).then(|| UnreachableChannel {
+                    channel: channel.to_string(),
+                    source: source.clone(),
+                    span: span.map(SourceSpan::from),
+                })
+            }
+        })
+        .buffered(workspace.config().max_concurrent_downloads())
+        .filter_map(futures::future::ready)
+        .collect()
+        .await;
+    Ok(unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_check_target_platforms() {
+        let contents = r#"
+[workspace]
+name = "check"
+channels = []
+platforms = ["linux-64", "osx-arm64"]
+
+[target.unix.dependencies]
+[target.win-64.dependencies]
+
+[feature.cuda]
+platforms = ["linux-64"]
+[feature.cuda.target.linux.dependencies]
+[feature.cuda.target.osx.dependencies]
+
+[feature.docs.target.osx-arm64.dependencies]
+"#;
+        let workspace = Workspace::from_str(Path::new("pixi.toml"), contents).unwrap();
+        let source = NamedSource::new("pixi.toml", Arc::from(contents));
+
+        let unsupported = check_target_platforms(&workspace, &source);
+        assert_eq!(
+            unsupported
+                .iter()
+                .map(|target| (target.target.as_str(), target.owner.as_str()))
+                .collect_vec(),
+            vec![("win-64", "the workspace"), ("osx", "the feature 'cuda'")]
+        );
+        let span = unsupported[0].span.expect("the span of the target");
+        assert!(contents[span.offset()..span.offset() + span.len()].contains("win-64"));
+    }
+
+    #[test]
+    fn test_check_task_dependencies() {
+        let contents = r#"
+[tasks]
+build = { cmd = "make", depends-on = ["configure"] }
+test = { cmd = "pytest", depends-on = "build" }
+all = [{ task = "build" }, { task = "lint" }]
+
+[feature.docs.target.linux-64.tasks]
+configure = "cmake ."
+docs = { cmd = "mkdocs build", depends-on = [{ task = "generate" }] }
+"#;
+        let document = toml_edit::Document::parse(contents).unwrap();
+        let source = NamedSource::new("pixi.toml", Arc::from(contents));
+        let pixi_tables = nested_pixi_tables(document.as_table());
+
+        let missing = check_task_dependencies(&pixi_tables, &source);
+        assert_eq!(
+            missing
+                .iter()
+                .map(|missing| (missing.task.as_str(), missing.dependency.as_str()))
+                .collect_vec(),
+            vec![("all", "lint"), ("docs", "generate")]
+        );
+        let span = missing[0].span.expect("the span of the dependency");
+        assert_eq!(
+            &contents[span.offset()..span.offset() + span.len()],
+            r#""lint""#
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use pixi_config;
 use pixi_consts::consts;
-use pixi_core::WorkspaceLocator;
+use pixi_core::Workspace;
 use pixi_manifest::EnvironmentName;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use crate::cli_config::WorkspaceConfig;
+use crate::list::get_dir_size;
 use clap::Parser;
 use fancy_display::FancyDisplay;
 use fs_err::tokio as tokio_fs;
@@ -20,6 +22,9 @@ use std::str::FromStr;
 pub enum Command {
     #[clap(name = "cache")]
     Cache(CacheArgs),
+
+    #[clap(name = "envs")]
+    Envs(EnvsArgs),
 }
 
 /// Cleanup the environments.
@@ -27,7 +32,8 @@ pub enum Command {
 /// This command removes the information in the .pixi folder.
 /// You can specify the environment to remove with the `--environment` flag.
 ///
-/// Use the `cache` subcommand to clean the cache.
+/// Use the `cache` subcommand to clean the cache and the `envs` subcommand to
+/// clean environments that are no longer defined in the manifest.
 #[derive(Parser, Debug)]
 pub struct Args {
     #[clap(flatten)]
@@ -49,6 +55,25 @@ pub struct Args {
     pub build: bool,
 }
 
+/// Clean the environment directories of the workspace.
+///
+/// With `--orphaned` only the directories of environments and solve groups
+/// that are no longer defined in the manifest are removed.
+#[derive(Parser, Debug)]
+pub struct EnvsArgs {
+    /// Only remove the environments that are no longer defined in the manifest.
+    #[arg(long)]
+    pub orphaned: bool,
+
+    /// Only list the environments that would be removed and their sizes.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Answer yes to all questions.
+    #[clap(short = 'y', long = "yes", alias = "assume-yes")]
+    assume_yes: bool,
+}
+
 /// Clean the cache of your system which are touched by pixi.
 ///
 /// Specify the cache type to clean with the flags.
@@ -97,11 +122,16 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         return Ok(());
     }
 
-    let workspace = WorkspaceLocator::for_cli()
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         .with_closest_package(false)
-        .with_search_start(args.workspace_config.workspace_locator_start())
         .locate()?;
 
+    if let Some(Command::Envs(args)) = args.command {
+        return clean_envs(&workspace, args).await;
+    }
+
     let explicit_environment = args
         .environment
         .map(|n| EnvironmentName::from_str(n.as_str()))
@@ -166,6 +196,115 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     Ok(())
 }
 
+/// Clean the environment directories of the workspace.
+async fn clean_envs(workspace: &Workspace, args: EnvsArgs) -> miette::Result<()> {
+    // Without `--orphaned` all environment directories are removed.
+    let (mut environments, mut solve_groups) = if args.orphaned {
+        (
+            workspace
+                .environments()
+                .iter()
+                .map(|env| env.name().as_str().to_string())
+                .collect(),
+            workspace
+                .solve_groups()
+                .iter()
+                .map(|group| group.name().to_string())
+                .collect(),
+        )
+    } else {
+        (HashSet::new(), HashSet::new())
+    };
+    // Without a lock profile, the directories of the profiles are next to the
+    // environments.
+    if args.orphaned && workspace.lock_profile().is_none() {
+        for profile in workspace.workspace.value.workspace.lock_profiles.keys() {
+            environments.insert(profile.as_str().to_string());
+            solve_groups.insert(profile.as_str().to_string());
+        }
+    }
+    let mut dirs = unknown_dirs(&workspace.environments_dir(), &environments)?;
+    dirs.extend(unknown_dirs(
+        &workspace.solve_group_environments_dir(),
+        &solve_groups,
+    )?);
+
+    if dirs.is_empty() {
+        let message = if args.orphaned {
+            "No orphaned environments found."
+        } else {
+            "No environments found."
+        };
+        eprintln!("{}", console::style(message).green());
+        return Ok(());
+    }
+
+    let sizes = futures::future::try_join_all(
+        dirs.iter()
+            .cloned()
+            .map(|dir| tokio::task::spawn_blocking(move || get_dir_size(dir).unwrap_or_default())),
+    )
+    .await
+    .into_diagnostic()?;
+    let total = indicatif::HumanBytes(sizes.iter().sum());
+
+    for (dir, size) in dirs.iter().zip(&sizes) {
+        eprintln!(
+            "  {} ({})",
+            dir.display(),
+            console::style(indicatif::HumanBytes(*size)).bold()
+        );
+    }
+
+    if args.dry_run {
+        eprintln!(
+            "Would remove {} environment(s), freeing {}",
+            dirs.len(),
+            console::style(total).bold()
+        );
+        return Ok(());
+    }
+
+    if !args.assume_yes
+        && !dialoguer::Confirm::new()
+            .with_prompt(format!(
+                "Do you want to remove {} environment(s), freeing {total}?",
+                dirs.len()
+            ))
+            .interact_opt()
+            .into_diagnostic()?
+            .unwrap_or(false)
+    {
+        eprintln!("{}", console::style("Nothing removed.").yellow());
+        return Ok(());
+    }
+
+    for dir in dirs {
+        remove_folder_with_progress(dir, false).await?;
+    }
+    Ok(())
+}
+
+/// Returns the directories in `dir` whose name is not in `known`, sorted by
+/// name. Returns an empty list if `dir` doesn't exist.
+fn unknown_dirs(dir: &Path, known: &HashSet<String>) -> miette::Result<Vec<PathBuf>> {
+    let entries = match fs_err::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let entry = entry.into_diagnostic()?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.path().is_dir() && !known.contains(&name) {
+            dirs.push(entry.path());
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
 /// Clean the pixi cache folders.
 async fn clean_cache(args: CacheArgs) -> miette::Result<()> {
     let cache_dir = pixi_config::get_cache_dir()?;
@@ -277,3 +416,26 @@ async fn remove_file(file: PathBuf, warning_non_existent: bool) -> miette::Resul
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(dir.path().join("default/conda-meta")).unwrap();
+        fs_err::create_dir_all(dir.path().join("removed/conda-meta")).unwrap();
+        fs_err::write(dir.path().join("file"), "").unwrap();
+
+        let known = HashSet::from(["default".to_string()]);
+        assert_eq!(
+            unknown_dirs(dir.path(), &known).unwrap(),
+            vec![dir.path().join("removed")]
+        );
+        assert_eq!(
+            unknown_dirs(&dir.path().join("missing"), &known).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+}
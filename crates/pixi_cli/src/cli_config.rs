@@ -3,26 +3,30 @@ use clap::Parser;
 use indexmap::IndexMap;
 use indexmap::IndexSet;
 use itertools::Itertools;
-use miette::IntoDiagnostic;
+use miette::{Context, IntoDiagnostic};
 use pep508_rs::Requirement;
 use pixi_build_frontend::BackendOverride;
 use pixi_config::Config;
 use pixi_consts::consts;
 use pixi_core::DependencyType;
-use pixi_core::Workspace;
 use pixi_core::environment::LockFileUsage;
-use pixi_core::workspace::DiscoveryStart;
+use pixi_core::workspace::{DiscoveryStart, PypiDeps};
+use pixi_core::{Workspace, WorkspaceLocator};
 use pixi_manifest::FeaturesExt;
-use pixi_manifest::{FeatureName, SpecType};
+use pixi_manifest::pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation};
+use pixi_manifest::{
+    ChannelPriority, ExcludeNewer, FeatureName, LockProfileName, PypiDependencyLocation,
+    SolveStrategy, SpecType,
+};
 use pixi_spec::GitReference;
 use rattler_conda_types::ChannelConfig;
 use rattler_conda_types::{Channel, NamedChannelOrUrl, Platform};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use url::Url;
 
 use pixi_git::GIT_URL_QUERY_REV_TYPE;
-use pixi_pypi_spec::PypiPackageName;
+use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
 
 /// Workspace configuration
 #[derive(Parser, Debug, Default, Clone)]
@@ -31,6 +35,11 @@ pub struct WorkspaceConfig {
     #[arg(long, global = true, help_heading = consts::CLAP_GLOBAL_OPTIONS)]
     pub manifest_path: Option<PathBuf>,
 
+    /// The lock profile to use, selects the lock-file `pixi.<NAME>.lock` and
+    /// the settings of the profile in the manifest
+    #[arg(long, global = true, env = "PIXI_LOCK_PROFILE", value_name = "NAME", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    pub lock_profile: Option<LockProfileName>,
+
     /// Backend override for testing purposes. This field is ignored by clap
     /// and should only be set programmatically in tests.
     #[clap(skip)]
@@ -45,6 +54,14 @@ impl WorkspaceConfig {
             None => DiscoveryStart::CurrentDir,
         }
     }
+
+    /// Returns the locator of the workspace that is selected by these
+    /// options, which also applies the selected lock profile.
+    pub fn workspace_locator(&self) -> WorkspaceLocator {
+        WorkspaceLocator::for_cli()
+            .with_search_start(self.workspace_locator_start())
+            .with_lock_profile(self.lock_profile.clone())
+    }
 }
 
 /// Channel configuration
@@ -149,6 +166,163 @@ impl NoInstallConfig {
     }
 }
 
+/// Configuration for overriding the solve strategy of the workspace
+#[derive(Parser, Debug, Default, Clone)]
+pub struct SolveStrategyConfig {
+    /// The strategy to use when solving the environments, overriding the
+    /// `solve-strategy` from the manifest. Use `lowest` or `lowest-direct` to
+    /// test the lower bounds of the dependencies.
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        value_parser = clap::builder::PossibleValuesParser::new(["highest", "lowest", "lowest-direct"])
+            .map(|s| s.parse::<SolveStrategy>().expect("all possible values are valid strategies")),
+        help_heading = consts::CLAP_UPDATE_OPTIONS
+    )]
+    pub solve_strategy: Option<SolveStrategy>,
+}
+
+impl SolveStrategyConfig {
+    /// Applies the solve strategy override, if any, to the workspace.
+    pub fn apply(&self, workspace: Workspace) -> Workspace {
+        match self.solve_strategy {
+            Some(solve_strategy) => workspace.with_solve_strategy(solve_strategy),
+            None => workspace,
+        }
+    }
+}
+
+/// Configuration for overriding the channel priority of the workspace.
+#[derive(Parser, Debug, Default, Clone)]
+pub struct ChannelPriorityConfig {
+    /// The channel priority to use when solving the environments, overriding
+    /// the `channel-priority` from the manifest. With `disabled` packages
+    /// are taken from any channel, regardless of the order of the channels.
+    #[arg(
+        long,
+        value_name = "PRIORITY",
+        value_parser = clap::builder::PossibleValuesParser::new(["strict", "disabled"])
+            .map(|s| s.parse::<ChannelPriority>().expect("all possible values are valid priorities")),
+        help_heading = consts::CLAP_UPDATE_OPTIONS
+    )]
+    pub channel_priority: Option<ChannelPriority>,
+}
+
+impl ChannelPriorityConfig {
+    /// Applies the channel priority override, if any, to the workspace.
+    pub fn apply(&self, workspace: Workspace) -> Workspace {
+        match self.channel_priority {
+            Some(channel_priority) => workspace.with_channel_priority(channel_priority),
+            None => workspace,
+        }
+    }
+}
+
+/// Configuration for overriding the `exclude-newer` date of the workspace.
+#[derive(Parser, Debug, Default, Clone)]
+pub struct ExcludeNewerConfig {
+    /// Ignore all conda packages and PyPI releases published after this date,
+    /// overriding the `exclude-newer` from the manifest. Accepts a date like
+    /// `2024-06-01` or a RFC 3339 timestamp like `2024-06-01T12:00:00Z`.
+    #[arg(long, value_name = "DATE", help_heading = consts::CLAP_UPDATE_OPTIONS)]
+    pub exclude_newer: Option<ExcludeNewer>,
+}
+
+impl ExcludeNewerConfig {
+    /// Applies the `exclude-newer` override, if any, to the workspace.
+    pub fn apply(&self, workspace: Workspace) -> Workspace {
+        match self.exclude_newer {
+            Some(exclude_newer) => workspace.with_exclude_newer(exclude_newer),
+            None => workspace,
+        }
+    }
+}
+
+/// Configuration for restricting the PyPI distributions that may be used and
+/// how they are built, in addition to the `no-build`, `no-binary` and
+/// `no-build-isolation` from the manifest.
+#[derive(Parser, Debug, Default, Clone)]
+pub struct PypiBuildConfig {
+    /// Don't build any PyPI source distributions, only use wheels
+    #[arg(long, help_heading = consts::CLAP_UPDATE_OPTIONS)]
+    pub no_build: bool,
+
+    /// Don't build source distributions of this PyPI package. Can be passed
+    /// multiple times
+    #[arg(
+        long,
+        value_name = "PACKAGE",
+        conflicts_with = "no_build",
+        help_heading = consts::CLAP_UPDATE_OPTIONS
+    )]
+    pub no_build_package: Vec<pep508_rs::PackageName>,
+
+    /// Don't use any PyPI wheels, build all packages from source
+    #[arg(long, help_heading = consts::CLAP_UPDATE_OPTIONS)]
+    pub no_binary: bool,
+
+    /// Don't use wheels of this PyPI package, build it from source. Can be
+    /// passed multiple times
+    #[arg(
+        long,
+        value_name = "PACKAGE",
+        conflicts_with = "no_binary",
+        help_heading = consts::CLAP_UPDATE_OPTIONS
+    )]
+    pub no_binary_package: Vec<pep508_rs::PackageName>,
+
+    /// Build all PyPI source distributions without build isolation, so they
+    /// can use the packages of the environment during their build
+    #[arg(long, help_heading = consts::CLAP_UPDATE_OPTIONS)]
+    pub no_build_isolation: bool,
+
+    /// Build this PyPI package without build isolation. Can be passed multiple
+    /// times
+    #[arg(
+        long,
+        value_name = "PACKAGE",
+        conflicts_with = "no_build_isolation",
+        help_heading = consts::CLAP_UPDATE_OPTIONS
+    )]
+    pub no_build_isolation_package: Vec<pep508_rs::PackageName>,
+}
+
+impl PypiBuildConfig {
+    /// Applies the restrictions, if any, to the workspace.
+    pub fn apply(&self, workspace: Workspace) -> Workspace {
+        let no_build = if self.no_build {
+            NoBuild::All
+        } else if self.no_build_package.is_empty() {
+            NoBuild::None
+        } else {
+            NoBuild::Packages(self.no_build_package.iter().cloned().collect())
+        };
+        let no_binary = if self.no_binary {
+            NoBinary::All
+        } else if self.no_binary_package.is_empty() {
+            NoBinary::None
+        } else {
+            NoBinary::Packages(self.no_binary_package.iter().cloned().collect())
+        };
+
+        let no_build_isolation = if self.no_build_isolation {
+            NoBuildIsolation::All
+        } else {
+            NoBuildIsolation::Packages(self.no_build_isolation_package.iter().cloned().collect())
+        };
+
+        let workspace = if no_build == NoBuild::None && no_binary == NoBinary::None {
+            workspace
+        } else {
+            workspace.with_pypi_build_options(&no_build, &no_binary)
+        };
+        if no_build_isolation == NoBuildIsolation::none() {
+            return workspace;
+        }
+        workspace.with_no_build_isolation(&no_build_isolation)
+    }
+}
+
 /// Lock file and installation configuration with --as-is support
 /// Used by shell, shell-hook, and run commands
 #[derive(Parser, Debug, Default, Clone)]
@@ -270,9 +444,14 @@ impl From<GitRev> for GitReference {
 #[derive(Parser, Debug, Default)]
 pub struct DependencyConfig {
     /// The dependency as names, conda MatchSpecs or PyPi requirements
-    #[arg(required = true, value_name = "SPEC")]
+    #[arg(required_unless_present = "from_file", value_name = "SPEC")]
     pub specs: Vec<String>,
 
+    /// Read the dependencies from a file with one spec per line. Empty lines
+    /// and comments starting with `#` are ignored
+    #[arg(long, value_name = "FILE")]
+    pub from_file: Option<PathBuf>,
+
     /// The specified dependencies are host dependencies. Conflicts with `build`
     /// and `pypi`
     #[arg(long, conflicts_with_all = ["build", "pypi"], hide = true)]
@@ -310,6 +489,23 @@ pub struct DependencyConfig {
 }
 
 impl DependencyConfig {
+    /// Adds the specs from the `--from-file` file, if any, to the specs passed
+    /// on the command line so that they are all applied in a single solve.
+    pub(crate) fn read_specs_from_file(&mut self) -> miette::Result<()> {
+        let Some(path) = &self.from_file else {
+            return Ok(());
+        };
+        let contents = fs_err::read_to_string(path)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to read specs from '{}'", path.display()))?;
+        let specs = parse_spec_file(&contents);
+        if specs.is_empty() && self.specs.is_empty() {
+            miette::bail!("no dependencies found in '{}'", path.display());
+        }
+        self.specs.extend(specs);
+        Ok(())
+    }
+
     pub(crate) fn dependency_type(&self) -> DependencyType {
         if self.pypi {
             DependencyType::PypiDependency
@@ -402,6 +598,123 @@ impl DependencyConfig {
             None => None,
         }
     }
+
+    /// Returns the pypi requirements of the specs. A spec that is a path to a
+    /// local package, like `./libs/mylib`, is added as a path dependency on
+    /// that package, which is installed in editable mode if `editable` is set.
+    pub fn pypi_requirements(
+        &self,
+        workspace: &Workspace,
+        editable: bool,
+    ) -> miette::Result<PypiDeps> {
+        let mut requirements = PypiDeps::new();
+        for spec in &self.specs {
+            if is_local_path(spec) {
+                // Only the pixi table can hold a relative path, the native
+                // `project.dependencies` of a pyproject.toml can't.
+                let (name, requirement, pixi_requirement) =
+                    local_pypi_requirement(spec, workspace, editable)?;
+                requirements.insert(
+                    name,
+                    (
+                        requirement,
+                        Some(pixi_requirement),
+                        Some(PypiDependencyLocation::PixiPypiDependencies),
+                    ),
+                );
+            } else {
+                let requirement = Requirement::parse(spec, workspace.root()).into_diagnostic()?;
+                let name = PypiPackageName::from_normalized(requirement.name.clone())
+                    .with_source(spec.to_string());
+                requirements.insert(name, (requirement, None, None));
+            }
+        }
+        Ok(requirements)
+    }
+}
+
+/// Returns true if the spec is a path to a local package instead of a PyPI
+/// requirement. Requirements only contain slashes in a URL after an `@`.
+fn is_local_path(spec: &str) -> bool {
+    !spec.contains('@') && (spec.starts_with('.') || spec.contains(['/', '\\']))
+}
+
+/// Builds the requirement on the local package at `spec`. The name of the
+/// package is read from its `pyproject.toml` and the path in the manifest is
+/// relative to the workspace root.
+fn local_pypi_requirement(
+    spec: &str,
+    workspace: &Workspace,
+    editable: bool,
+) -> miette::Result<(PypiPackageName, Requirement, PixiPypiSpec)> {
+    let path = dunce::canonicalize(spec)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("the package path '{spec}' does not exist"))?;
+
+    let pyproject = path.join(consts::PYPROJECT_MANIFEST);
+    let name = fs_err::read_to_string(&pyproject)
+        .ok()
+        .and_then(|contents| contents.parse::<toml_edit::DocumentMut>().ok())
+        .and_then(|document| {
+            document
+                .get("project")
+                .and_then(|project| project.get("name"))
+                .and_then(|name| name.as_str())
+                .map(str::to_string)
+        })
+        .ok_or_else(|| {
+            miette::miette!(
+                help = "specify the name of the package, e.g. `pixi add --pypi 'mylib @ file:///path/to/mylib'`",
+                "could not determine the name of the package at '{}', it has no `project.name` in its `{}`",
+                path.display(),
+                consts::PYPROJECT_MANIFEST
+            )
+        })?;
+
+    let url = Url::from_directory_path(&path)
+        .map_err(|_| miette::miette!("'{}' is not a valid package path", path.display()))?;
+    let requirement =
+        Requirement::parse(&format!("{name} @ {url}"), workspace.root()).into_diagnostic()?;
+
+    // Keep the path relative to the workspace so that the manifest can be used
+    // on other machines.
+    let root =
+        dunce::canonicalize(workspace.root()).unwrap_or_else(|_| workspace.root().to_path_buf());
+    let manifest_path = match pathdiff::diff_paths(&path, &root) {
+        Some(relative) if relative.starts_with("..") => relative,
+        Some(relative) => Path::new(".").join(relative),
+        None => path,
+    };
+
+    let name = PypiPackageName::from_normalized(requirement.name.clone()).with_source(name);
+    let pixi_requirement = PixiPypiSpec::Path {
+        path: manifest_path,
+        editable: editable.then_some(true),
+        extras: Vec::new(),
+    };
+    Ok((name, requirement, pixi_requirement))
+}
+
+/// Parses the contents of a spec file. Every non-empty line is a spec, a `#`
+/// at the start of a line or preceded by whitespace starts a comment. A `#`
+/// inside a spec, like in `pkg @ git+https://host/repo#subdirectory=sub`, is
+/// kept.
+fn parse_spec_file(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| {
+            let line = if line.trim_start().starts_with('#') {
+                ""
+            } else {
+                line.find(" #")
+                    .or_else(|| line.find("\t#"))
+                    .map_or(line, |comment| &line[..comment])
+            };
+            line.trim()
+        })
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 impl HasSpecs for DependencyConfig {
@@ -451,6 +764,7 @@ mod tests {
 
     use crate::cli_config::{
         GitRev, LockAndInstallConfig, LockFileUpdateConfig, NoInstallConfig, build_vcs_requirement,
+        is_local_path, parse_spec_file,
     };
     use pixi_core::environment::LockFileUsage;
 
@@ -565,4 +879,35 @@ mod tests {
             "should respect individual frozen flag"
         );
     }
+
+    #[test]
+    fn test_is_local_path() {
+        assert!(is_local_path("./libs/mylib"));
+        assert!(is_local_path("../mylib"));
+        assert!(is_local_path("libs/mylib"));
+        assert!(!is_local_path("boto3"));
+        assert!(!is_local_path("boto3>=1.0"));
+        assert!(!is_local_path("boto3 @ file:///path/to/boto3"));
+    }
+
+    #[test]
+    fn test_parse_spec_file() {
+        let contents = "\
+# Packages for the migration
+numpy>=1.26
+
+pandas 2.*  # pinned for now
+  scipy
+pkg @ git+https://github.com/org/repo#subdirectory=sub
+";
+        assert_eq!(
+            parse_spec_file(contents),
+            vec![
+                "numpy>=1.26",
+                "pandas 2.*",
+                "scipy",
+                "pkg @ git+https://github.com/org/repo#subdirectory=sub",
+            ]
+        );
+    }
 }
@@ -1,7 +1,13 @@
 use clap::CommandFactory;
+use indexmap::IndexMap;
 use is_executable::IsExecutable;
 use miette::{Context, IntoDiagnostic};
 use pixi_config::pixi_home;
+use pixi_core::{
+    WorkspaceLocator,
+    activation::{CurrentEnvVarBehavior, get_static_environment_variables},
+    workspace::{DiscoveryStart, Environment, get_activated_environment_variables},
+};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::path::PathBuf;
@@ -103,7 +109,7 @@ pub fn find_external_subcommand(cmd: &str) -> Option<PathBuf> {
 }
 
 /// Execute an external subcommand
-pub fn execute_external_command(args: Vec<String>) -> miette::Result<()> {
+pub async fn execute_external_command(args: Vec<String>) -> miette::Result<()> {
     // There should be always at least one argument, the command itself.
     // but we dont want to panic on runtime, so we handle it as a error.
     let cmd = args
@@ -122,6 +128,7 @@ pub fn execute_external_command(args: Vec<String>) -> miette::Result<()> {
 
         let mut command = std::process::Command::new(&path);
         command.args(cmd_args);
+        command.envs(workspace_environment_variables(cmd_args).await);
 
         imp::execute_command(command)?;
 
@@ -151,6 +158,79 @@ pub fn execute_external_command(args: Vec<String>) -> miette::Result<()> {
     }
 }
 
+/// Returns the environment variables of the activated environment that is
+/// selected by `PIXI_ENVIRONMENT`, in the workspace that is selected by the
+/// `--manifest-path` of the command or found from the current directory, so
+/// external commands do not have to locate the workspace themselves.
+async fn workspace_environment_variables(args: &[String]) -> IndexMap<String, String> {
+    let search_start = match manifest_path_arg(args) {
+        Some(path) => DiscoveryStart::ExplicitManifest(path),
+        None => DiscoveryStart::CurrentDir,
+    };
+    let Ok(workspace) = WorkspaceLocator::for_cli()
+        .with_search_start(search_start)
+        .with_emit_warnings(false)
+        .locate()
+    else {
+        return IndexMap::new();
+    };
+    let Ok(environment) = workspace.environment_from_name_or_env_var(None) else {
+        return IndexMap::new();
+    };
+
+    let activated = get_activated_environment_variables(
+        workspace.env_vars(),
+        &environment,
+        CurrentEnvVarBehavior::Exclude,
+        None,
+        workspace.config().force_activate(),
+        workspace.config().experimental_activation_cache_usage(),
+    )
+    .await;
+    let activated = match activated {
+        Ok(activated) => activated.clone(),
+        Err(err) => {
+            tracing::debug!("failed to activate the environment for the external command: {err}");
+            HashMap::new()
+        }
+    };
+    environment_variables(&environment, activated)
+}
+
+/// Returns the value of the `--manifest-path` argument of an external command.
+fn manifest_path_arg(args: &[String]) -> Option<PathBuf> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--manifest-path" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--manifest-path=") {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Returns the environment variables that describe the given environment to an
+/// external command, together with the variables of its activation.
+fn environment_variables(
+    environment: &Environment<'_>,
+    activated: HashMap<String, String>,
+) -> IndexMap<String, String> {
+    let mut env = get_static_environment_variables(environment);
+    env.extend(activated);
+    // The external command does not run in a pixi shell.
+    env.shift_remove("PIXI_IN_SHELL");
+    env.insert(
+        String::from("CONDA_PREFIX"),
+        environment.dir().to_string_lossy().into_owned(),
+    );
+    env
+}
+
 /// Get directories to search for external commands by looking over PATH and pixi global directory
 fn search_directories() -> Option<Vec<PathBuf>> {
     let mut directories = Vec::new();
@@ -210,7 +290,7 @@ mod imp {
         ))?;
 
         // Exit with the same status code as the child process
-        std::process::exit(status.code().unwrap_or(1));
+        crate::ExitStatus::from_code(status.code().unwrap_or(1))
     }
 }
 
@@ -223,4 +303,51 @@ mod tests {
         let suggestions = find_similar_commands("tee");
         assert!(suggestions.contains(&"tree".to_string()));
     }
+
+    #[test]
+    fn test_environment_variables() {
+        let workspace = pixi_core::Workspace::from_str(
+            std::path::Path::new("/workspace/pixi.toml"),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["linux-64"]
+        "#,
+        )
+        .unwrap();
+
+        let activated = HashMap::from([
+            (String::from("FOO"), String::from("bar")),
+            (String::from("PIXI_IN_SHELL"), String::from("1")),
+        ]);
+        let env = environment_variables(&workspace.default_environment(), activated);
+        assert_eq!(env["FOO"], "bar");
+        assert_eq!(env["PIXI_PROJECT_NAME"], "foo");
+        assert_eq!(env["PIXI_ENVIRONMENT_NAME"], "default");
+        assert_eq!(env["CONDA_DEFAULT_ENV"], "foo");
+        assert_eq!(
+            std::path::Path::new(&env["CONDA_PREFIX"]),
+            workspace.default_environment().dir()
+        );
+        assert!(!env.contains_key("PIXI_IN_SHELL"));
+    }
+
+    #[test]
+    fn test_manifest_path_arg() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            manifest_path_arg(&args(&["--verbose", "--manifest-path", "a/pixi.toml"])),
+            Some(PathBuf::from("a/pixi.toml"))
+        );
+        assert_eq!(
+            manifest_path_arg(&args(&["--manifest-path=b"])),
+            Some(PathBuf::from("b"))
+        );
+        assert_eq!(
+            manifest_path_arg(&args(&["--", "--manifest-path", "c"])),
+            None
+        );
+        assert_eq!(manifest_path_arg(&args(&["--verbose"])), None);
+    }
 }
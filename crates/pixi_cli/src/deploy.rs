@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_config::ConfigCli;
+use pixi_core::{UpdateLockFileOptions, environment::InstallFilter};
+use pixi_manifest::FeaturesExt;
+use pixi_utils::prefix::Prefix;
+use rattler_conda_types::Platform;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Install an environment into a directory outside of the workspace.
+///
+/// The locked environment is installed into the given prefix, e.g.
+/// `/opt/app/env`. The installed environment does not refer back to the
+/// workspace, which makes it suitable for production images. Editable PyPI
+/// packages are installed as regular packages.
+///
+/// Use `--target-platform` to install the environment for another platform
+/// than the current one, e.g. to create a `linux-aarch64` environment on
+/// `linux-64` that is copied into a container image. The post-link scripts
+/// of the packages are not run in that case.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub project_config: WorkspaceConfig,
+
+    #[clap(flatten)]
+    pub lock_file_usage: crate::LockFileUsageConfig,
+
+    /// The environment to deploy
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// The directory to install the environment into
+    #[arg(long)]
+    pub prefix: PathBuf,
+
+    /// The platform to install the environment for, defaults to the current
+    /// platform
+    #[arg(long)]
+    pub target_platform: Option<Platform>,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args
+        .project_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config);
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+    let platform = args
+        .target_platform
+        .unwrap_or_else(|| environment.best_platform());
+    if !environment.platforms().contains(&platform) {
+        miette::bail!(
+            help = "add the platform with `pixi workspace platform add {platform}`",
+            "the environment '{}' does not support the platform '{platform}'",
+            environment.name().fancy_display(),
+        );
+    }
+
+    // Refuse to install into a directory with other contents than an
+    // environment.
+    if args.prefix.is_dir()
+        && !args.prefix.join("conda-meta").is_dir()
+        && fs_err::read_dir(&args.prefix)
+            .into_diagnostic()?
+            .next()
+            .is_some()
+    {
+        miette::bail!(
+            help = "choose an empty directory or the directory of an existing environment",
+            "'{}' is not empty and does not contain an environment",
+            args.prefix.display()
+        );
+    }
+    fs_err::create_dir_all(&args.prefix).into_diagnostic()?;
+    let prefix = Prefix::new(dunce::canonicalize(&args.prefix).into_diagnostic()?);
+
+    let (lock_file, _) = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_usage.to_usage(),
+            no_install: true,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await?;
+    lock_file
+        .install_to_prefix(&environment, &prefix, platform, &InstallFilter::default())
+        .await
+        .wrap_err_with(|| format!("failed to deploy to '{}'", prefix.root().display()))?;
+
+    eprintln!(
+        "{}The {} environment has been deployed to {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        environment.name().fancy_display(),
+        prefix.root().display()
+    );
+    if platform != environment.best_platform() {
+        eprintln!(
+            "{}The environment was installed for {platform}, which is not the platform of this machine ({}). Its post-link scripts were not run and only its noarch packages can be used on this machine.",
+            console::style(console::Emoji("⚠ ", "")).yellow(),
+            environment.best_platform(),
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,514 @@
+use std::path::Path;
+
+use clap::Parser;
+use futures::StreamExt;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_config::{Config, ConfigCli};
+use pixi_consts::consts;
+use pixi_core::{
+    Workspace,
+    environment::integrity::find_damaged_packages,
+    lock_file::check_lock_file,
+    workspace::{
+        WorkspaceLocatorError, virtual_packages::verify_current_platform_can_run_environment,
+    },
+};
+use pixi_global::BinDir;
+use pixi_manifest::FeaturesExt;
+use rattler_conda_types::{ChannelUrl, GenericVirtualPackage, Platform};
+use rattler_networking::LazyClient;
+use rattler_virtual_packages::VirtualPackage;
+use serde::Serialize;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Check the workspace and the pixi installation for common problems.
+///
+/// Checks the manifest, the lock-file, the installed environments, the package
+/// cache, the global executables, the channels and the virtual packages of the
+/// system, and prints how to fix the problems that were found. Exits with a
+/// non-zero code if an error was found.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// Output the report in JSON format
+    #[arg(long)]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum Status {
+    Ok,
+    Info,
+    Warning,
+    Error,
+}
+
+/// The outcome of a single check.
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Ok,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn info(name: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Info,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warning,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+
+    fn error(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Error,
+            message: message.into(),
+            fix: Some(fix.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    healthy: bool,
+    checks: Vec<Check>,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let mut checks = Vec::new();
+
+    let (check, workspace) = check_manifest(args.workspace_config.workspace_locator().locate());
+    checks.push(check);
+    let workspace = workspace.map(|workspace| workspace.with_cli_config(args.config));
+
+    if let Some(workspace) = &workspace {
+        checks.push(check_lock_file_freshness(workspace).await?);
+        checks.extend(check_environments(workspace));
+    }
+    checks.push(check_package_cache()?);
+    checks.extend(check_global_executables().await?);
+    if let Some(workspace) = &workspace {
+        checks.extend(check_channels(workspace).await?);
+    }
+    checks.extend(check_virtual_packages(workspace.as_ref()));
+
+    let healthy = checks.iter().all(|check| check.status != Status::Error);
+    if args.json {
+        let report = Report { healthy, checks };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).into_diagnostic()?
+        );
+    } else {
+        for check in &checks {
+            let marker = match check.status {
+                Status::Ok => console::style(console::Emoji("✔ ", "")).green(),
+                Status::Info => console::style(console::Emoji("ℹ ", "")).blue(),
+                Status::Warning => console::style(console::Emoji("⚠ ", "")).yellow(),
+                Status::Error => console::style(console::Emoji("✘ ", "")).red(),
+            };
+            eprintln!(
+                "{marker}{}: {}",
+                console::style(check.name).bold(),
+                check.message
+            );
+            if let Some(fix) = &check.fix {
+                eprintln!("    {} {fix}", console::style("fix:").cyan());
+            }
+        }
+    }
+
+    if !healthy {
+        return Err(crate::ExitStatus(1).into());
+    }
+    Ok(())
+}
+
+/// Checks that the workspace could be located and that its manifest is valid.
+/// Running outside of a workspace is not a problem, only the checks of the
+/// workspace are skipped.
+fn check_manifest(
+    workspace: Result<Workspace, WorkspaceLocatorError>,
+) -> (Check, Option<Workspace>) {
+    const NAME: &str = "manifest";
+    match workspace {
+        Ok(workspace) => (
+            Check::ok(
+                NAME,
+                format!(
+                    "The manifest {} is valid",
+                    workspace.workspace.provenance.path.display()
+                ),
+            ),
+            Some(workspace),
+        ),
+        Err(WorkspaceLocatorError::WorkspaceNotFound(_)) => (
+            Check::info(
+                NAME,
+                "Not in a workspace, only the pixi installation is checked",
+            ),
+            None,
+        ),
+        Err(err) => (
+            Check::error(
+                NAME,
+                err.to_string(),
+                "Fix the manifest, or run `pixi init` to create a workspace",
+            ),
+            None,
+        ),
+    }
+}
+
+/// Checks that the lock-file satisfies the manifest.
+async fn check_lock_file_freshness(workspace: &Workspace) -> miette::Result<Check> {
+    const NAME: &str = "lock-file";
+    if !workspace.lock_file_path().is_file() {
+        return Ok(Check::warning(
+            NAME,
+            "The workspace has no lock-file",
+            "Run `pixi lock` to create the lock-file",
+        ));
+    }
+    let lock_file = match workspace.load_lock_file().await {
+        Ok(lock_file) => lock_file.into_lock_file_or_empty(),
+        Err(err) => {
+            return Ok(Check::error(
+                NAME,
+                format!("The lock-file is invalid: {err}"),
+                "Run `pixi lock` to recreate the lock-file",
+            ));
+        }
+    };
+    let result = check_lock_file(workspace, &lock_file).await;
+    Ok(if result.is_up_to_date() {
+        Check::ok(NAME, "The lock-file is up-to-date")
+    } else {
+        Check::warning(
+            NAME,
+            format!(
+                "The lock-file is not up-to-date for {}",
+                result
+                    .outdated
+                    .iter()
+                    .map(|environment| &environment.environment)
+                    .format(", ")
+            ),
+            "Run `pixi lock` to update the lock-file",
+        )
+    })
+}
+
+/// Checks that the files of the installed environments match the records in
+/// their `conda-meta` directory.
+fn check_environments(workspace: &Workspace) -> Vec<Check> {
+    const NAME: &str = "environment";
+    workspace
+        .environments()
+        .into_iter()
+        .filter(|environment| environment.dir().join(consts::CONDA_META_DIR).is_dir())
+        .map(|environment| {
+            let name = environment.name();
+            match find_damaged_packages(&environment.dir()) {
+                Ok(damaged) if damaged.is_empty() => {
+                    Check::ok(NAME, format!("The '{name}' environment is intact"))
+                }
+                Ok(damaged) => Check::error(
+                    NAME,
+                    format!(
+                        "The '{name}' environment has damaged packages: {}",
+                        damaged
+                            .iter()
+                            .map(|package| package.name.as_source())
+                            .format(", ")
+                    ),
+                    format!("Run `pixi install --repair -e {name}`"),
+                ),
+                Err(err) => Check::error(
+                    NAME,
+                    format!("The '{name}' environment could not be read: {err}"),
+                    format!("Run `pixi install --repair -e {name}`"),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Checks that every extracted package in the package cache contains its
+/// metadata.
+fn check_package_cache() -> miette::Result<Check> {
+    const NAME: &str = "package-cache";
+    let cache_dir = pixi_config::get_cache_dir()?.join(consts::CONDA_PACKAGE_CACHE_DIR);
+    if !cache_dir.is_dir() {
+        return Ok(Check::ok(NAME, "The package cache is empty"));
+    }
+
+    let mut count = 0;
+    let mut broken = Vec::new();
+    for entry in fs_err::read_dir(&cache_dir).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        count += 1;
+        if !path.join("info").join("index.json").is_file() {
+            broken.push(path);
+        }
+    }
+
+    Ok(if broken.is_empty() {
+        Check::ok(
+            NAME,
+            format!("The {count} packages in {} are intact", cache_dir.display()),
+        )
+    } else {
+        Check::error(
+            NAME,
+            format!(
+                "{} packages in {} are incomplete: {}",
+                broken.len(),
+                cache_dir.display(),
+                broken
+                    .iter()
+                    .filter_map(|path| path.file_name())
+                    .map(|name| name.to_string_lossy())
+                    .format(", ")
+            ),
+            "Run `pixi clean cache --conda` to clear the package cache",
+        )
+    })
+}
+
+/// Checks that the global executables are on the `PATH` and are not shadowed
+/// by other executables with the same name.
+async fn check_global_executables() -> miette::Result<Vec<Check>> {
+    let bin_dir = BinDir::from_env().await?;
+    Ok(check_bin_dir(bin_dir.path()))
+}
+
+/// Checks the executables in the bin directory of pixi global.
+fn check_bin_dir(bin_path: &Path) -> Vec<Check> {
+    const NAME: &str = "global";
+    let entries = match fs_err::read_dir(bin_path) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return vec![Check::info(NAME, "No global executables are installed")];
+        }
+        Err(err) => {
+            return vec![Check::warning(
+                NAME,
+                format!("The global executables could not be read: {err}"),
+                format!("Check the permissions of {}", bin_path.display()),
+            )];
+        }
+    };
+
+    let on_path = std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| same_dir(&dir, bin_path)));
+    if !on_path {
+        return vec![Check::warning(
+            NAME,
+            format!("{} is not on the PATH", bin_path.display()),
+            format!(
+                "Add {} to the PATH in your shell configuration",
+                bin_path.display()
+            ),
+        )];
+    }
+
+    let mut shadowed = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if let Ok(found) = which::which(name)
+            && found.parent().is_some_and(|dir| !same_dir(dir, bin_path))
+        {
+            shadowed.push(format!("{name} ({})", found.display()));
+        }
+    }
+
+    vec![if shadowed.is_empty() {
+        Check::ok(NAME, "The global executables are on the PATH")
+    } else {
+        Check::warning(
+            NAME,
+            format!(
+                "Global executables are shadowed by other executables: {}",
+                shadowed.iter().format(", ")
+            ),
+            format!(
+                "Move {} to the front of the PATH in your shell configuration",
+                bin_path.display()
+            ),
+        )
+    }]
+}
+
+/// Returns true if both paths point to the same directory.
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (dunce::canonicalize(a), dunce::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Checks that the channels of the workspace can be reached.
+async fn check_channels(workspace: &Workspace) -> miette::Result<Vec<Check>> {
+    const NAME: &str = "channel";
+    let channel_config = workspace.channel_config();
+    let channels = workspace
+        .environments()
+        .iter()
+        .map(|environment| environment.channel_urls(&channel_config))
+        .flatten_ok()
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()?
+        .into_iter()
+        .unique()
+        .collect_vec();
+
+    let client = workspace.authenticated_client()?.clone();
+    let checks = futures::stream::iter(channels)
+        .map(|channel| {
+            let client = client.clone();
+            async move {
+                if is_channel_reachable(&client, &channel).await {
+                    Check::ok(NAME, format!("{} is reachable", channel.as_str()))
+                } else {
+                    Check::error(
+                        NAME,
+                        format!("{} is not reachable", channel.as_str()),
+                        "Check your network connection and proxy settings, and log in with `pixi auth login` if the channel is private",
+                    )
+                }
+            }
+        })
+        .buffered(workspace.config().max_concurrent_downloads())
+        .collect()
+        .await;
+    Ok(checks)
+}
+
+/// Returns true if the `noarch` repodata of the channel can be fetched.
+pub(crate) async fn is_channel_reachable(client: &LazyClient, channel: &ChannelUrl) -> bool {
+    let url = channel
+        .platform_url(Platform::NoArch)
+        .join("repodata.json")
+        .expect("repodata.json is a valid url fragment");
+    if url.scheme() == "file" {
+        url.to_file_path().is_ok_and(|path| path.is_file())
+    } else {
+        client
+            .client()
+            .head(url)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success())
+    }
+}
+
+/// Checks that the virtual packages of the system can be detected and satisfy
+/// the system requirements of the environments.
+fn check_virtual_packages(workspace: Option<&Workspace>) -> Vec<Check> {
+    const NAME: &str = "virtual-packages";
+    let overrides = workspace.map_or_else(
+        || Config::load_global().virtual_package_overrides(),
+        |workspace| workspace.config().virtual_package_overrides(),
+    );
+    let virtual_packages = match VirtualPackage::detect(&overrides) {
+        Ok(virtual_packages) => virtual_packages,
+        Err(err) => {
+            return vec![Check::error(
+                NAME,
+                format!("The virtual packages could not be detected: {err}"),
+                "Override the detected versions with the `virtual-package-overrides` configuration",
+            )];
+        }
+    };
+
+    let mut checks = vec![Check::ok(
+        NAME,
+        format!(
+            "Detected {}",
+            virtual_packages
+                .into_iter()
+                .map(GenericVirtualPackage::from)
+                .format(", ")
+        ),
+    )];
+    if let Some(workspace) = workspace {
+        checks.extend(workspace.environments().iter().filter_map(|environment| {
+            verify_current_platform_can_run_environment(environment, None)
+                .err()
+                .map(|err| {
+                    Check::error(
+                        NAME,
+                        format!(
+                            "The '{}' environment cannot run on this system: {err}",
+                            environment.name()
+                        ),
+                        "Adjust the `system-requirements` of the environment",
+                    )
+                })
+        }));
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_workspace_is_not_an_error() {
+        let (check, workspace) = check_manifest(Err(WorkspaceLocatorError::WorkspaceNotFound(
+            std::path::PathBuf::from("/tmp"),
+        )));
+        assert_eq!(check.status, Status::Info);
+        assert!(workspace.is_none());
+    }
+
+    #[test]
+    fn test_missing_bin_dir_is_a_finding() {
+        let dir = tempfile::tempdir().unwrap();
+        let checks = check_bin_dir(&dir.path().join("bin"));
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].status, Status::Info);
+    }
+}
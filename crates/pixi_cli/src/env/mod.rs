@@ -0,0 +1,226 @@
+pub mod register;
+pub mod unregister;
+
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use indexmap::IndexMap;
+use miette::{Context, IntoDiagnostic};
+use pixi_utils::prefix::Prefix;
+use rattler::install::PythonInfo;
+use rattler_conda_types::{PackageName, Platform};
+use serde_json::Value;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Commands to make the environments of the workspace known to other tools.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The subcommand to execute
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Register the python interpreter of an environment with IDEs.
+    ///
+    /// Adds the environment to `~/.conda/environments.txt`, which is read by
+    /// PyCharm and VS Code to discover conda environments, and sets it as the
+    /// interpreter of the workspace in `.vscode/settings.json`. With
+    /// `--kernel` a Jupyter kernel for the environment is installed as well.
+    ///
+    /// Example:
+    /// `pixi env register --environment test --kernel`
+    Register(register::Args),
+    /// Remove the registrations made by `pixi env register`.
+    Unregister(unregister::Args),
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::Register(register_args) => {
+            register::execute(args.workspace_config, register_args).await
+        }
+        Command::Unregister(unregister_args) => {
+            unregister::execute(args.workspace_config, unregister_args).await
+        }
+    }
+}
+
+/// The key of the interpreter of a workspace in the VS Code settings.
+const VSCODE_INTERPRETER_KEY: &str = "python.defaultInterpreterPath";
+
+/// Returns the path of the python interpreter that is installed in the prefix.
+pub(crate) async fn python_executable(prefix: &Prefix) -> miette::Result<PathBuf> {
+    let python = prefix
+        .find_designated_package(&PackageName::new_unchecked("python"))
+        .await
+        .into_diagnostic()
+        .wrap_err("the environment does not contain python, add it with `pixi add python`")?;
+    let info =
+        PythonInfo::from_python_record(&python.repodata_record.package_record, Platform::current())
+            .into_diagnostic()?;
+    Ok(prefix.root().join(info.path()))
+}
+
+/// Returns the file in which conda keeps the list of known environments. IDEs
+/// like PyCharm and VS Code read it to discover conda environments.
+fn conda_environments_file() -> miette::Result<PathBuf> {
+    dirs::home_dir()
+        .map(|home| home.join(".conda").join("environments.txt"))
+        .ok_or_else(|| miette::miette!("could not determine the home directory"))
+}
+
+/// Adds the prefix to the list of known conda environments. Returns `false` if
+/// it was already listed.
+fn add_conda_environment(prefix: &Path) -> miette::Result<bool> {
+    let path = conda_environments_file()?;
+    let contents = read_optional(&path)?.unwrap_or_default();
+    let prefix = prefix.to_string_lossy();
+    if contents.lines().any(|line| line.trim() == prefix) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    let mut contents = contents;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(&format!("{prefix}\n"));
+    fs_err::write(&path, contents).into_diagnostic()?;
+    Ok(true)
+}
+
+/// Removes the prefix from the list of known conda environments. Returns
+/// `false` if it wasn't listed.
+fn remove_conda_environment(prefix: &Path) -> miette::Result<bool> {
+    let path = conda_environments_file()?;
+    let Some(contents) = read_optional(&path)? else {
+        return Ok(false);
+    };
+    let prefix = prefix.to_string_lossy();
+    let remaining = contents
+        .lines()
+        .filter(|line| line.trim() != prefix)
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+    if remaining.len() == contents.len() {
+        return Ok(false);
+    }
+    fs_err::write(&path, remaining).into_diagnostic()?;
+    Ok(true)
+}
+
+/// Returns the VS Code settings of the workspace.
+fn vscode_settings_file(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(".vscode").join("settings.json")
+}
+
+/// Sets the python interpreter of the workspace in its VS Code settings.
+fn register_vscode_interpreter(workspace_root: &Path, python: &Path) -> miette::Result<PathBuf> {
+    let path = vscode_settings_file(workspace_root);
+    let settings = read_optional(&path)?.unwrap_or_default();
+    let settings = set_vscode_interpreter(&settings, python)
+        .wrap_err_with(|| format!("failed to update '{}'", path.display()))?;
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent).into_diagnostic()?;
+    }
+    fs_err::write(&path, format!("{settings}\n")).into_diagnostic()?;
+    Ok(path)
+}
+
+/// Removes the python interpreter from the VS Code settings of the workspace
+/// if it is an interpreter of the prefix. Returns `false` if it wasn't set.
+fn unregister_vscode_interpreter(workspace_root: &Path, prefix: &Path) -> miette::Result<bool> {
+    let path = vscode_settings_file(workspace_root);
+    let Some(settings) = read_optional(&path)? else {
+        return Ok(false);
+    };
+    let Some(settings) = unset_vscode_interpreter(&settings, prefix)
+        .wrap_err_with(|| format!("failed to update '{}'", path.display()))?
+    else {
+        return Ok(false);
+    };
+    fs_err::write(&path, format!("{settings}\n")).into_diagnostic()?;
+    Ok(true)
+}
+
+/// Sets the python interpreter in the VS Code settings, keeping all other
+/// settings.
+fn set_vscode_interpreter(settings: &str, python: &Path) -> miette::Result<String> {
+    let mut settings = parse_vscode_settings(settings)?;
+    settings.insert(
+        VSCODE_INTERPRETER_KEY.to_string(),
+        Value::String(python.to_string_lossy().into_owned()),
+    );
+    serde_json::to_string_pretty(&settings).into_diagnostic()
+}
+
+/// Removes the python interpreter from the VS Code settings if it is an
+/// interpreter of the prefix. Returns `None` if the settings don't change.
+fn unset_vscode_interpreter(settings: &str, prefix: &Path) -> miette::Result<Option<String>> {
+    let mut settings = parse_vscode_settings(settings)?;
+    if !settings
+        .get(VSCODE_INTERPRETER_KEY)
+        .and_then(Value::as_str)
+        .is_some_and(|python| Path::new(python).starts_with(prefix))
+    {
+        return Ok(None);
+    }
+    settings.shift_remove(VSCODE_INTERPRETER_KEY);
+    serde_json::to_string_pretty(&settings)
+        .into_diagnostic()
+        .map(Some)
+}
+
+/// Parses the VS Code settings, the order of the settings is kept.
+fn parse_vscode_settings(settings: &str) -> miette::Result<IndexMap<String, Value>> {
+    if settings.trim().is_empty() {
+        return Ok(IndexMap::new());
+    }
+    serde_json::from_str(settings).into_diagnostic().wrap_err(
+        "failed to parse the VS Code settings, settings with comments are not supported, \
+         set `python.defaultInterpreterPath` manually instead",
+    )
+}
+
+/// Reads the file, returns `None` if it doesn't exist.
+fn read_optional(path: &Path) -> miette::Result<Option<String>> {
+    match fs_err::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).into_diagnostic(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vscode_interpreter() {
+        let python = Path::new("/workspace/.pixi/envs/default/bin/python3.12");
+        let settings = set_vscode_interpreter(r#"{ "editor.tabSize": 4 }"#, python).unwrap();
+        let parsed: Value = serde_json::from_str(&settings).unwrap();
+        assert_eq!(parsed["editor.tabSize"], 4);
+        assert_eq!(
+            parsed[VSCODE_INTERPRETER_KEY],
+            "/workspace/.pixi/envs/default/bin/python3.12"
+        );
+
+        let other = Path::new("/workspace/.pixi/envs/test");
+        assert_eq!(unset_vscode_interpreter(&settings, other).unwrap(), None);
+
+        let prefix = Path::new("/workspace/.pixi/envs/default");
+        let settings = unset_vscode_interpreter(&settings, prefix)
+            .unwrap()
+            .unwrap();
+        let parsed: Value = serde_json::from_str(&settings).unwrap();
+        assert_eq!(parsed, serde_json::json!({ "editor.tabSize": 4 }));
+    }
+}
@@ -0,0 +1,107 @@
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use miette::IntoDiagnostic;
+use pixi_config::ConfigCli;
+use pixi_consts::consts;
+use pixi_core::{
+    UpdateLockFileOptions,
+    environment::{InstallFilter, get_update_lock_file_and_prefix},
+    lock_file::{ReinstallPackages, UpdateMode},
+};
+use rattler_conda_types::PackageName;
+
+use crate::{
+    cli_config::{LockFileUpdateConfig, WorkspaceConfig},
+    kernelspec::{self, KernelSpec},
+};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The environment to register
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// Also install a Jupyter kernel for the environment. The environment
+    /// needs to contain `ipykernel`.
+    #[arg(long)]
+    pub kernel: bool,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(workspace_config: WorkspaceConfig, args: Args) -> miette::Result<()> {
+    let workspace = workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config);
+
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+
+    let (_, prefix) = get_update_lock_file_and_prefix(
+        &environment,
+        UpdateMode::QuickValidate,
+        UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: false,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        },
+        ReinstallPackages::default(),
+        &InstallFilter::default(),
+    )
+    .await?;
+    let python = super::python_executable(&prefix).await?;
+
+    if super::add_conda_environment(prefix.root())? {
+        eprintln!(
+            "{}Added {} to the conda environments discovered by IDEs",
+            console::style(console::Emoji("✔ ", "")).green(),
+            environment.name().fancy_display(),
+        );
+    }
+
+    let settings = super::register_vscode_interpreter(workspace.root(), &python)?;
+    eprintln!(
+        "{}Set {} as the python interpreter in '{}'",
+        console::style(console::Emoji("✔ ", "")).green(),
+        console::style(python.display()).bold(),
+        settings.display(),
+    );
+
+    if args.kernel {
+        if prefix
+            .find_designated_package(&PackageName::new_unchecked("ipykernel"))
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "the {} environment does not contain `ipykernel`, add it with `pixi add ipykernel` to use the kernel",
+                environment.name().fancy_display()
+            );
+        }
+
+        let name = kernelspec::kernel_name(workspace.display_name(), environment.name().as_str());
+        let spec = KernelSpec::pixi_run(
+            &std::env::current_exe().into_diagnostic()?,
+            &workspace.workspace.provenance.path,
+            environment.name().as_str(),
+            format!(
+                "{} ({})",
+                workspace.display_name(),
+                environment.name().as_str()
+            ),
+        );
+        let kernel_dir = kernelspec::install(&kernelspec::kernels_dir()?, &name, &spec)?;
+        eprintln!(
+            "{}Installed the Jupyter kernel {} in '{}'",
+            console::style(console::Emoji("✔ ", "")).green(),
+            consts::ENVIRONMENT_STYLE.apply_to(&name),
+            kernel_dir.display(),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use pixi_config::ConfigCli;
+use pixi_consts::consts;
+
+use crate::{cli_config::WorkspaceConfig, kernelspec};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The environment to unregister
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(workspace_config: WorkspaceConfig, args: Args) -> miette::Result<()> {
+    let workspace = workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config);
+
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+    let prefix = environment.dir();
+    let mut removed = false;
+
+    if super::remove_conda_environment(&prefix)? {
+        removed = true;
+        eprintln!(
+            "{}Removed {} from the conda environments discovered by IDEs",
+            console::style(console::Emoji("✔ ", "")).green(),
+            environment.name().fancy_display(),
+        );
+    }
+
+    if super::unregister_vscode_interpreter(workspace.root(), &prefix)? {
+        removed = true;
+        eprintln!(
+            "{}Removed the python interpreter of {} from the VS Code settings",
+            console::style(console::Emoji("✔ ", "")).green(),
+            environment.name().fancy_display(),
+        );
+    }
+
+    // Only remove the kernel if it was installed for this workspace.
+    let kernels_dir = kernelspec::kernels_dir()?;
+    let name = kernelspec::kernel_name(workspace.display_name(), environment.name().as_str());
+    if kernelspec::read(&kernels_dir, &name)?.is_some_and(|spec| {
+        spec.pixi()
+            .is_some_and(|pixi| pixi.manifest_path == workspace.workspace.provenance.path)
+    }) && kernelspec::remove(&kernels_dir, &name)?
+    {
+        removed = true;
+        eprintln!(
+            "{}Removed the Jupyter kernel {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            consts::ENVIRONMENT_STYLE.apply_to(&name),
+        );
+    }
+
+    if !removed {
+        eprintln!(
+            "The {} environment was not registered",
+            environment.name().fancy_display()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use std::{fmt, io::Write};
+
+use miette::{Diagnostic, ReportHandler, Severity, SourceCode};
+use serde::Serialize;
+use tracing::{Event, Level, Subscriber, field::Field};
+use tracing_subscriber::{fmt::MakeWriter, layer::Context};
+
+/// The format in which errors are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// Human readable diagnostics.
+    #[default]
+    Text,
+    /// A JSON object per diagnostic with the message, code, help and the
+    /// labels with their location in the source file.
+    Json,
+}
+
+/// A [`ReportHandler`] that renders a diagnostic as a single line of JSON.
+pub(crate) struct JsonReportHandler;
+
+impl ReportHandler for JsonReportHandler {
+    fn debug(&self, diagnostic: &dyn Diagnostic, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(&JsonDiagnostic::new(diagnostic, None))
+            .map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+/// A tracing layer that prints warnings and errors as JSON diagnostics, in the
+/// same shape as the errors rendered by [`JsonReportHandler`].
+pub(crate) struct JsonWarningLayer<W> {
+    make_writer: W,
+}
+
+impl<W> JsonWarningLayer<W> {
+    pub(crate) fn new(make_writer: W) -> Self {
+        Self { make_writer }
+    }
+}
+
+impl<S, W> tracing_subscriber::Layer<S> for JsonWarningLayer<W>
+where
+    S: Subscriber,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let level = *event.metadata().level();
+        if level > Level::WARN {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let diagnostic = JsonDiagnostic::from_event(visitor.message, level);
+        if let Ok(mut json) = serde_json::to_string(&diagnostic) {
+            json.push('\n');
+            let _ = self.make_writer.make_writer().write_all(json.as_bytes());
+        }
+    }
+}
+
+/// Collects the message of a tracing event.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl tracing::field::Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonDiagnostic {
+    message: String,
+    code: Option<String>,
+    severity: &'static str,
+    help: Option<String>,
+    url: Option<String>,
+    /// The messages of the errors that caused this diagnostic.
+    causes: Vec<String>,
+    labels: Vec<JsonLabel>,
+    related: Vec<JsonDiagnostic>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLabel {
+    label: Option<String>,
+    /// The name of the file the label points into, e.g. the manifest.
+    filename: Option<String>,
+    /// The byte offset of the span in the file.
+    offset: usize,
+    /// The length of the span in bytes.
+    length: usize,
+    /// The one-based line of the start of the span.
+    line: Option<usize>,
+    /// The one-based column of the start of the span.
+    column: Option<usize>,
+}
+
+impl JsonDiagnostic {
+    fn new(diagnostic: &dyn Diagnostic, parent_source: Option<&dyn SourceCode>) -> Self {
+        let source_code = diagnostic.source_code().or(parent_source);
+
+        let mut causes = Vec::new();
+        let mut source = diagnostic.source();
+        while let Some(error) = source {
+            causes.push(error.to_string());
+            source = error.source();
+        }
+
+        // The labels of a diagnostic that is the source of this diagnostic are
+        // shown together with its own labels.
+        let mut labels = Vec::new();
+        let mut current = Some((diagnostic, source_code));
+        while let Some((diagnostic, source_code)) = current {
+            labels.extend(
+                diagnostic
+                    .labels()
+                    .into_iter()
+                    .flatten()
+                    .map(|label| JsonLabel::new(&label, source_code)),
+            );
+            current = diagnostic.diagnostic_source().map(|diagnostic| {
+                let source_code = diagnostic.source_code().or(source_code);
+                (diagnostic, source_code)
+            });
+        }
+
+        Self {
+            message: diagnostic.to_string(),
+            code: diagnostic.code().map(|code| code.to_string()),
+            severity: match diagnostic.severity().unwrap_or(Severity::Error) {
+                Severity::Advice => "advice",
+                Severity::Warning => "warning",
+                Severity::Error => "error",
+            },
+            help: diagnostic.help().map(|help| help.to_string()),
+            url: diagnostic.url().map(|url| url.to_string()),
+            causes,
+            labels,
+            related: diagnostic
+                .related()
+                .into_iter()
+                .flatten()
+                .map(|related| JsonDiagnostic::new(related, source_code))
+                .collect(),
+        }
+    }
+}
+
+impl JsonDiagnostic {
+    fn from_event(message: String, level: Level) -> Self {
+        Self {
+            message,
+            code: None,
+            severity: if level == Level::ERROR {
+                "error"
+            } else {
+                "warning"
+            },
+            help: None,
+            url: None,
+            causes: Vec::new(),
+            labels: Vec::new(),
+            related: Vec::new(),
+        }
+    }
+}
+
+impl JsonLabel {
+    fn new(label: &miette::LabeledSpan, source_code: Option<&dyn SourceCode>) -> Self {
+        let contents = source_code.and_then(|source| source.read_span(label.inner(), 0, 0).ok());
+        Self {
+            label: label.label().map(str::to_string),
+            filename: contents
+                .as_ref()
+                .and_then(|contents| contents.name().map(str::to_string)),
+            offset: label.offset(),
+            length: label.len(),
+            line: contents.as_ref().map(|contents| contents.line() + 1),
+            column: contents.as_ref().map(|contents| contents.column() + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use miette::{NamedSource, SourceSpan};
+    use thiserror::Error;
+
+    use super::*;
+
+    #[derive(Debug, Error, Diagnostic)]
+    #[error("unknown key")]
+    #[diagnostic(code(pixi::unknown_key), help("remove the key"))]
+    struct UnknownKey {
+        #[source_code]
+        src: NamedSource<String>,
+        #[label("not allowed here")]
+        span: SourceSpan,
+    }
+
+    #[test]
+    fn test_json_diagnostic() {
+        let error = UnknownKey {
+            src: NamedSource::new("pixi.toml", "[workspace]\nfoo = 1\n".to_string()),
+            span: (12, 3).into(),
+        };
+        let json = serde_json::to_value(JsonDiagnostic::new(&error, None)).unwrap();
+        assert_eq!(json["message"], "unknown key");
+        assert_eq!(json["code"], "pixi::unknown_key");
+        assert_eq!(json["severity"], "error");
+        assert_eq!(json["help"], "remove the key");
+        let label = &json["labels"][0];
+        assert_eq!(label["label"], "not allowed here");
+        assert_eq!(label["filename"], "pixi.toml");
+        assert_eq!(label["offset"], 12);
+        assert_eq!(label["length"], 3);
+        assert_eq!(label["line"], 2);
+        assert_eq!(label["column"], 1);
+    }
+
+    #[test]
+    fn test_json_warning() {
+        #[derive(Clone, Default)]
+        struct Buffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+        impl<'a> MakeWriter<'a> for Buffer {
+            type Writer = Buffer;
+            fn make_writer(&'a self) -> Self::Writer {
+                self.clone()
+            }
+        }
+
+        use tracing_subscriber::layer::SubscriberExt;
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry().with(JsonWarningLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("not a diagnostic");
+            tracing::warn!("unknown style '{}'", "foo");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let json: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(json["message"], "unknown style 'foo'");
+        assert_eq!(json["severity"], "warning");
+    }
+}
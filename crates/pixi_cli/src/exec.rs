@@ -14,7 +14,7 @@ use rattler::{
 };
 use rattler_conda_types::{GenericVirtualPackage, MatchSpec, PackageName, Platform};
 use rattler_solve::{SolverImpl, SolverTask, resolvo::Solver};
-use rattler_virtual_packages::{VirtualPackageOverrides, VirtualPackages};
+use rattler_virtual_packages::VirtualPackages;
 use reqwest_middleware::ClientWithMiddleware;
 use uv_configuration::RAYON_INITIALIZE;
 
@@ -156,7 +156,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .with_context(|| format!("failed to execute '{}'", &command))?;
 
     // Return the exit code of the command
-    std::process::exit(status.code().unwrap_or(1));
+    crate::ExitStatus::from_code(status.code().unwrap_or(1))
 }
 
 /// Creates a prefix for the `pixi exec` command.
@@ -234,7 +234,7 @@ pub async fn create_exec_prefix(
 
     // Determine virtual packages of the current platform
     let virtual_packages: Vec<GenericVirtualPackage> =
-        VirtualPackages::detect(&VirtualPackageOverrides::from_env())
+        VirtualPackages::detect(&config.virtual_package_overrides())
             .into_diagnostic()
             .context("failed to determine virtual packages")?
             .into_generic_virtual_packages()
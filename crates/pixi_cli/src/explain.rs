@@ -0,0 +1,195 @@
+use std::str::FromStr;
+
+use clap::Parser;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_config::{Config, ConfigCli};
+use pixi_core::{
+    lock_file::{ConflictReason, ConflictingRequirement, parse_conflicting_requirements},
+    workspace::virtual_packages::get_minimal_virtual_packages,
+};
+use pixi_manifest::SystemRequirements;
+use pixi_progress::{await_in_progress, wrap_in_progress};
+use pixi_utils::reqwest::build_reqwest_clients;
+use rattler_conda_types::{GenericVirtualPackage, MatchSpec, ParseStrictness, Platform};
+use rattler_solve::{SolveError, SolverImpl, SolverTask, resolvo::Solver};
+
+use crate::cli_config::ChannelsConfig;
+
+/// Check whether a set of specs can be installed together.
+///
+/// The specs are solved on their own, without a workspace, using the same
+/// solver that is used to update lock-files. If they cannot be installed
+/// together, the specs that conflict are reported together with the versions
+/// that were considered. Exits with a non-zero code if the specs conflict.
+#[derive(Parser, Debug)]
+#[clap(arg_required_else_help = true)]
+pub struct Args {
+    /// The matchspecs to solve together, e.g. `python=3.8` `"numpy>=2"`.
+    #[arg(required = true, num_args = 1..)]
+    pub specs: Vec<String>,
+
+    #[clap(flatten)]
+    pub channels: ChannelsConfig,
+
+    /// The platform to solve for, defaults to the current platform
+    #[arg(short, long, default_value_t = Platform::current())]
+    pub platform: Platform,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+
+    let specs = args
+        .specs
+        .iter()
+        .map(|spec| {
+            MatchSpec::from_str(spec, ParseStrictness::Lenient)
+                .into_diagnostic()
+                .with_context(|| format!("failed to parse '{spec}'"))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let channels = args.channels.resolve_from_config(&config)?;
+    eprintln!(
+        "Using channels: {}",
+        channels.iter().map(|c| c.name()).format(", ")
+    );
+
+    // Fetch the repodata for the specs
+    let (_, client) = build_reqwest_clients(Some(&config), None)?;
+    let gateway = config.gateway().with_client(client).finish();
+    let repodata = await_in_progress("fetching repodata", |_| async {
+        gateway
+            .query(channels, [args.platform, Platform::NoArch], specs.clone())
+            .recursive(true)
+            .execute()
+            .await
+            .into_diagnostic()
+    })
+    .await
+    .context("failed to get repodata")?;
+
+    // Use the same virtual packages that are used when solving a workspace
+    // without system requirements.
+    let virtual_packages =
+        get_minimal_virtual_packages(args.platform, &SystemRequirements::default())
+            .into_iter()
+            .map(GenericVirtualPackage::from)
+            .collect();
+
+    let solve_result = wrap_in_progress("solving", || {
+        Solver.solve(SolverTask {
+            specs: specs.clone(),
+            virtual_packages,
+            ..SolverTask::from_iter(&repodata)
+        })
+    });
+
+    match solve_result {
+        Ok(solution) => {
+            println!(
+                "{}The specs can be installed together on {}",
+                console::style(console::Emoji("✔ ", "")).green(),
+                args.platform
+            );
+            for spec in &specs {
+                let Some(name) = spec.name.as_ref().and_then(|name| name.as_exact()) else {
+                    continue;
+                };
+                if let Some(record) = solution
+                    .records
+                    .iter()
+                    .find(|record| &record.package_record.name == name)
+                {
+                    println!(
+                        "  {} {} {}",
+                        console::style(name.as_source()).bold(),
+                        record.package_record.version,
+                        console::style(&record.package_record.build).dim()
+                    );
+                }
+            }
+            Ok(())
+        }
+        Err(SolveError::Unsolvable(messages)) => {
+            println!(
+                "{}The specs cannot be installed together on {}\n",
+                console::style(console::Emoji("✘ ", "")).red(),
+                args.platform
+            );
+            for message in &messages {
+                println!("{message}");
+            }
+
+            let conflicts = messages
+                .iter()
+                .flat_map(|message| parse_conflicting_requirements(message))
+                .collect_vec();
+            if !conflicts.is_empty() {
+                println!("The conflict involves these specs:");
+                for conflict in &conflicts {
+                    println!("  - {}", format_conflict(conflict));
+                }
+            }
+
+            Err(crate::ExitStatus(1).into())
+        }
+        Err(err) => Err(err)
+            .into_diagnostic()
+            .context("failed to solve the specs"),
+    }
+}
+
+/// Formats a conflicting spec together with the versions that were considered
+/// for it, e.g. `numpy >=2: considered 2.0.0, 2.0.1`.
+fn format_conflict(conflict: &ConflictingRequirement) -> String {
+    let details = match conflict.reason {
+        ConflictReason::NoCandidates => {
+            String::from("no matching packages were found in the channels")
+        }
+        _ if conflict.considered_versions.is_empty() => String::new(),
+        _ => format!(
+            "considered {}",
+            conflict.considered_versions.iter().format(", ")
+        ),
+    };
+    if details.is_empty() {
+        conflict.requirement()
+    } else {
+        format!("{}: {details}", conflict.requirement())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_conflict() {
+        let message = "The following packages are incompatible
+├─ numpy >=2 can be installed with any of the following options:
+│  └─ numpy 2.0.0 | 2.0.1 would require
+│     └─ python >=3.9,<3.10.0a0, which can be installed with any of the following options:
+│        └─ python 3.9.0
+├─ foo >=99, for which no candidates were found.
+└─ python 3.8.* cannot be installed because there are no viable options:
+   └─ python 3.8.0 is excluded because of the constraint above
+";
+        let conflicts = parse_conflicting_requirements(message)
+            .iter()
+            .map(format_conflict)
+            .collect_vec();
+        assert_eq!(
+            conflicts,
+            vec![
+                "numpy >=2: considered 2.0.0, 2.0.1",
+                "foo >=99: no matching packages were found in the channels",
+                "python 3.8.*: considered 3.8.0",
+            ]
+        );
+    }
+}
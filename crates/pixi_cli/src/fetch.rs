@@ -0,0 +1,321 @@
+use std::{collections::BTreeMap, path::Path};
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use futures::{StreamExt, TryStreamExt};
+use itertools::Itertools;
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_config::ConfigCli;
+use pixi_core::{UpdateLockFileOptions, lock_file::LockFileDerivedData, workspace::Environment};
+use pixi_manifest::FeaturesExt;
+use pixi_progress::await_in_progress;
+use pixi_record::PixiRecord;
+use rattler_conda_types::{MatchSpec, PackageName, Platform, RepoDataRecord};
+use rattler_lock::{LockFile, LockedPackageRef};
+use rattler_networking::retry_policies::default_retry_policy;
+use url::Url;
+
+use crate::cli_config::{LockFileUpdateConfig, WorkspaceConfig};
+
+/// Download the packages of the lock file into the cache without installing
+/// them.
+///
+/// The repodata of the channels of the environments is refreshed and every
+/// conda package that is locked for the selected environments and platforms
+/// is downloaded into the package cache. A later `pixi install --frozen` can
+/// then install the environments without network access, which allows
+/// separating the download step from the install step, e.g. in a separate
+/// Docker layer.
+///
+/// PyPI packages and source packages are not fetched.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The environments to fetch, separated by commas. Defaults to all
+    /// environments
+    #[arg(long, short, value_delimiter = ',')]
+    pub environment: Vec<String>,
+
+    /// The platforms to fetch the packages for, separated by commas. Defaults
+    /// to the current platform
+    #[arg(long, short, value_delimiter = ',')]
+    pub platform: Vec<Platform>,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config);
+
+    let environments = if args.environment.is_empty() {
+        workspace.environments()
+    } else {
+        args.environment
+            .into_iter()
+            .unique()
+            .map(|name| workspace.environment_from_name_or_env_var(Some(name)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let (
+        LockFileDerivedData {
+            lock_file,
+            package_cache,
+            ..
+        },
+        _,
+    ) = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: true,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await
+        .wrap_err("Failed to update lock file")?;
+
+    let (records, repodata_queries) =
+        locked_records(&environments, &args.platform, &lock_file, workspace.root())?;
+
+    // Refresh the repodata of the channels, so that the lock file can also be
+    // updated from the cache.
+    let gateway = workspace.repodata_gateway()?;
+    let channel_config = workspace.channel_config();
+    await_in_progress("fetching repodata", |_| async {
+        for (environment, platform, names) in repodata_queries {
+            let channels = environment
+                .channels()
+                .into_iter()
+                .cloned()
+                .map(|channel| channel.into_channel(&channel_config))
+                .collect::<Result<Vec<_>, _>>()
+                .into_diagnostic()?;
+            gateway
+                .query(
+                    channels,
+                    [platform, Platform::NoArch],
+                    names.into_iter().map(MatchSpec::from),
+                )
+                .await
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    format!(
+                        "failed to fetch the repodata of the {} environment",
+                        environment.name().fancy_display()
+                    )
+                })?;
+        }
+        Ok::<_, miette::Report>(())
+    })
+    .await?;
+
+    let client = workspace.authenticated_client()?.clone();
+    let package_count = records.len();
+    await_in_progress(format!("downloading {package_count} packages"), |_| {
+        futures::stream::iter(records.into_values())
+            .map(|record: RepoDataRecord| {
+                let package_cache = package_cache.clone();
+                let client = client.clone();
+                async move {
+                    package_cache
+                        .get_or_fetch_from_url_with_retry(
+                            &record.package_record,
+                            record.url.clone(),
+                            client,
+                            default_retry_policy(),
+                            None,
+                        )
+                        .await
+                        .into_diagnostic()
+                        .with_context(|| format!("failed to download '{}'", record.url))
+                }
+            })
+            .buffer_unordered(workspace.config().max_concurrent_downloads())
+            .try_collect::<Vec<_>>()
+    })
+    .await?;
+
+    eprintln!(
+        "{}Fetched {} packages of the {} into the cache",
+        console::style(console::Emoji("✔ ", "")).green(),
+        package_count,
+        match environments.iter().exactly_one() {
+            Ok(environment) => format!("{} environment", environment.name().fancy_display()),
+            Err(environments) => format!(
+                "environments {}",
+                environments.format_with(", ", |env, f| f(&env.name().fancy_display()))
+            ),
+        },
+    );
+    Ok(())
+}
+
+/// The repodata that is refreshed for the packages of an environment on a
+/// platform.
+type RepodataQuery<'p> = (Environment<'p>, Platform, Vec<PackageName>);
+
+/// Collects the binary conda packages that are locked for the environments on
+/// the given platforms, or on the best platform of an environment if no
+/// platforms are given. A package that is used by multiple environments is
+/// only returned once.
+fn locked_records<'p>(
+    environments: &[Environment<'p>],
+    platforms: &[Platform],
+    lock_file: &LockFile,
+    workspace_root: &Path,
+) -> miette::Result<(BTreeMap<Url, RepoDataRecord>, Vec<RepodataQuery<'p>>)> {
+    let mut records = BTreeMap::new();
+    let mut repodata_queries = Vec::new();
+    for environment in environments {
+        let platforms = if platforms.is_empty() {
+            vec![environment.best_platform()]
+        } else {
+            platforms
+                .iter()
+                .copied()
+                .filter(|platform| environment.platforms().contains(platform))
+                .collect()
+        };
+        let Some(locked_environment) = lock_file.environment(environment.name().as_str()) else {
+            continue;
+        };
+
+        for platform in platforms {
+            let packages = locked_environment
+                .packages(platform)
+                .map(Vec::from_iter)
+                .unwrap_or_default();
+            let mut names = Vec::new();
+            for package in packages {
+                let LockedPackageRef::Conda(data) = package else {
+                    continue;
+                };
+                if let PixiRecord::Binary(record) =
+                    PixiRecord::from_conda_package_data(data.clone(), workspace_root)
+                        .into_diagnostic()?
+                {
+                    names.push(record.package_record.name.clone());
+                    records.insert(record.url.clone(), record);
+                }
+            }
+            repodata_queries.push((environment.clone(), platform, names));
+        }
+    }
+    Ok((records, repodata_queries))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use pixi_core::Workspace;
+
+    use super::*;
+
+    #[test]
+    fn test_locked_records() {
+        let workspace = Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [workspace]
+        name = "fetch"
+        channels = ["conda-forge"]
+        platforms = ["linux-64", "osx-arm64"]
+
+        [feature.test]
+        platforms = ["linux-64"]
+
+        [environments]
+        test = ["test"]
+        "#,
+        )
+        .unwrap();
+        let lock_file = LockFile::from_str(
+            r#"
+version: 6
+environments:
+  default:
+    channels:
+    - url: https://prefix.dev/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://prefix.dev/conda-forge/noarch/tzdata-2025b-h78e105d_0.conda
+      osx-arm64:
+      - conda: https://prefix.dev/conda-forge/noarch/tzdata-2025b-h78e105d_0.conda
+  test:
+    channels:
+    - url: https://prefix.dev/conda-forge/
+    packages:
+      linux-64:
+      - conda: https://prefix.dev/conda-forge/noarch/tzdata-2025b-h78e105d_0.conda
+      - conda: https://prefix.dev/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+packages:
+- conda: https://prefix.dev/conda-forge/noarch/_r-mutex-1.0.1-anacondar_1.tar.bz2
+  sha256: e58f9eeb416b92b550e824bcb1b9fb1958dee69abfe3089dfd1a9173e3a0528a
+  md5: 19f9db5f4f1b7f5ef5f6d67207f25f38
+  license: BSD
+  size: 3566
+  timestamp: 1562343890778
+- conda: https://prefix.dev/conda-forge/noarch/tzdata-2025b-h78e105d_0.conda
+  sha256: 5aaa366385d716557e365f0a4e9c3fca43ba196872abbbe3d56bb610d131e192
+  md5: 4222072737ccff51314b5ece9c7d6f5a
+  license: LicenseRef-Public-Domain
+  size: 122968
+  timestamp: 1742727099393
+"#,
+        )
+        .unwrap();
+
+        // A package that is shared by the environments is only fetched once, and
+        // platforms that an environment doesn't support are skipped.
+        let (records, queries) = locked_records(
+            &workspace.environments(),
+            &[Platform::Linux64, Platform::OsxArm64],
+            &lock_file,
+            workspace.root(),
+        )
+        .unwrap();
+        assert_eq!(
+            records
+                .values()
+                .map(|record| record.package_record.name.as_normalized())
+                .collect::<Vec<_>>(),
+            ["_r-mutex", "tzdata"]
+        );
+        assert_eq!(
+            queries
+                .iter()
+                .map(|(environment, platform, names)| (
+                    environment.name().as_str().to_string(),
+                    *platform,
+                    names.len()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("default".to_string(), Platform::Linux64, 1),
+                ("default".to_string(), Platform::OsxArm64, 1),
+                ("test".to_string(), Platform::Linux64, 2),
+            ]
+        );
+
+        // A platform that no environment supports fetches nothing.
+        let (records, queries) = locked_records(
+            &workspace.environments(),
+            &[Platform::Win64],
+            &lock_file,
+            workspace.root(),
+        )
+        .unwrap();
+        assert!(records.is_empty());
+        assert!(queries.is_empty());
+    }
+}
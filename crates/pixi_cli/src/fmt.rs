@@ -0,0 +1,58 @@
+use clap::Parser;
+use miette::IntoDiagnostic;
+use pixi_manifest::ManifestDocument;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Format the manifest of the workspace.
+///
+/// Sorts the dependencies by name, normalizes their version specs and writes
+/// inline tables in a consistent style. Comments are preserved. In a
+/// `pyproject.toml` only the `[tool.pixi]` tables are formatted.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// Check whether the manifest is formatted without modifying it. If it is
+    /// not, exit with a non-zero code
+    #[arg(long)]
+    pub check: bool,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let provenance = &workspace.workspace.provenance;
+
+    let original = provenance.read().into_diagnostic()?.into_inner();
+    let mut document = ManifestDocument::from_provenance(provenance)?;
+    document.format();
+    let formatted = document.to_string();
+
+    let path = provenance.path.display();
+    if formatted == original {
+        eprintln!(
+            "{}{path} is already formatted",
+            console::style(console::Emoji("✔ ", "")).green(),
+        );
+        return Ok(());
+    }
+
+    if args.check {
+        eprintln!(
+            "{}{path} would be reformatted, run `pixi fmt` to format it",
+            console::style(console::Emoji("✘ ", "")).red(),
+        );
+        return Err(crate::ExitStatus(1).into());
+    }
+
+    fs_err::tokio::write(&provenance.path, formatted)
+        .await
+        .into_diagnostic()?;
+    eprintln!(
+        "{}Formatted {path}",
+        console::style(console::Emoji("✔ ", "")).green(),
+    );
+
+    Ok(())
+}
@@ -8,7 +8,10 @@ use itertools::Itertools;
 use miette::Report;
 use rattler_conda_types::{MatchSpec, NamedChannelOrUrl, Platform};
 
-use crate::global::{global_specs::GlobalSpecs, revert_environment_after_error};
+use crate::{
+    global::{global_specs::GlobalSpecs, revert_environment_after_error},
+    task::parse_key_val,
+};
 use pixi_config::{self, Config, ConfigCli};
 use pixi_global::{
     self, EnvChanges, EnvState, EnvironmentName, Mapping, Project, StateChange, StateChanges,
@@ -25,6 +28,7 @@ use pixi_global::{
 /// - `pixi global install jupyter --with polars`
 /// - `pixi global install --expose python3.8=python python=3.8`
 /// - `pixi global install --environment science --expose jupyter --expose ipython jupyter ipython polars`
+/// - `pixi global install maven --env MAVEN_OPTS=-Xmx2g`
 #[derive(Parser, Debug, Clone, Default)]
 #[clap(arg_required_else_help = true, verbatim_doc_comment)]
 pub struct Args {
@@ -64,6 +68,12 @@ pub struct Args {
     #[arg(long)]
     with: Vec<MatchSpec>,
 
+    /// Set an environment variable in the exposed executables of the
+    /// environment, use --env key=value multiple times for more than one
+    /// variable.
+    #[arg(long, value_name = "KEY=VALUE", value_parser = parse_key_val)]
+    env: Vec<(String, String)>,
+
     #[clap(flatten)]
     config: ConfigCli,
 
@@ -121,6 +131,10 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         miette::bail!("Can't add packages with `--with` for more than one environment");
     }
 
+    if !args.env.is_empty() && env_to_specs.len() != 1 {
+        miette::bail!("Can't set environment variables with `--env` for more than one environment");
+    }
+
     let mut env_changes = EnvChanges::default();
     let mut last_updated_project = project_original;
     let mut errors: Vec<(EnvironmentName, Report)> = Vec::new();
@@ -232,8 +246,20 @@ async fn setup_environment(
         }
     }
 
+    for (key, value) in &args.env {
+        project.manifest.set_env_var(env_name, key, value)?;
+    }
+
     if project.environment_in_sync_internal(env_name, true).await? {
-        return Ok(StateChanges::new_with_env(env_name.clone()));
+        if args.env.is_empty() {
+            return Ok(StateChanges::new_with_env(env_name.clone()));
+        }
+        // Only the environment variables changed, update the trampolines
+        let state_changes = project
+            .expose_executables_from_environment(env_name)
+            .await?;
+        project.manifest.save().await?;
+        return Ok(state_changes);
     }
 
     // Installing the environment to be able to find the bin paths later
@@ -1,7 +1,10 @@
 use clap::Parser;
 use fancy_display::FancyDisplay;
+use miette::IntoDiagnostic;
 use pixi_config::{Config, ConfigCli};
-use pixi_global::list::{list_all_global_environments, list_specific_global_environment};
+use pixi_global::list::{
+    global_environments_output, list_all_global_environments, list_specific_global_environment,
+};
 use pixi_global::{EnvironmentName, Project};
 use std::str::FromStr;
 
@@ -35,6 +38,11 @@ pub struct Args {
     /// Sorting strategy for the package table of an environment
     #[arg(long, default_value = "name", value_enum, requires = "environment")]
     sort_by: GlobalSortBy,
+
+    /// Output the environments in JSON format, with their prefix, channels,
+    /// installed dependency versions and exposed executables.
+    #[arg(long)]
+    json: bool,
 }
 
 /// Sorting strategy for the package table
@@ -51,6 +59,20 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .await?
         .with_cli_config(config.clone());
 
+    if args.json {
+        let envs = args
+            .environment
+            .map(|environment| EnvironmentName::from_str(environment.as_str()))
+            .transpose()?
+            .map(|env_name| vec![env_name]);
+        let output = global_environments_output(&project, envs, args.regex).await?;
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
     if let Some(environment) = args.environment {
         let env_name = EnvironmentName::from_str(environment.as_str())?;
         // Verify that the environment is in sync with the manifest and report to the user otherwise
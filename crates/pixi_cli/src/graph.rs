@@ -0,0 +1,291 @@
+use std::{collections::HashMap, fmt::Write};
+
+use ahash::HashSet;
+use clap::Parser;
+use itertools::Itertools;
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_core::lock_file::UpdateLockFileOptions;
+use rattler_conda_types::Platform;
+use rattler_lock::LockedPackageRef;
+use serde::Serialize;
+
+use crate::{
+    cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig},
+    shared::tree::{Package, PackageSource},
+    tree::{direct_dependencies, generate_dependency_map},
+};
+
+/// The format to export the dependency graph in.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// A Graphviz DOT graph
+    #[default]
+    Dot,
+    /// A Mermaid flowchart
+    Mermaid,
+    /// The packages with their attributes and dependencies as JSON
+    Json,
+}
+
+/// Export the locked dependency graph of an environment.
+///
+/// Every package of the environment is a node with its kind, version and
+/// size. Packages that are directly specified in the manifest are marked as
+/// explicit.
+#[derive(Debug, Parser)]
+pub struct Args {
+    /// The format of the graph
+    #[arg(long, value_enum, default_value_t)]
+    pub format: GraphFormat,
+
+    /// The platform to export the graph for. Defaults to the current
+    /// platform.
+    #[arg(long, short)]
+    pub platform: Option<Platform>,
+
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The environment to export the graph for. Defaults to the default
+    /// environment.
+    #[arg(short, long)]
+    pub environment: Option<String>,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub no_install_config: NoInstallConfig,
+}
+
+/// A package in the dependency graph.
+#[derive(Debug, Serialize)]
+struct Node {
+    name: String,
+    version: String,
+    kind: &'static str,
+    size_bytes: Option<u64>,
+    explicit: bool,
+    dependencies: Vec<String>,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+
+    let environment = workspace
+        .environment_from_name_or_env_var(args.environment)
+        .wrap_err("Environment not found")?;
+
+    let lock_file = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: args.no_install_config.no_install,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await
+        .wrap_err("Failed to update lock file")?
+        .0
+        .into_lock_file();
+
+    let platform = args.platform.unwrap_or_else(|| environment.best_platform());
+    let locked_deps = lock_file
+        .environment(environment.name().as_str())
+        .and_then(|env| env.packages(platform).map(Vec::from_iter))
+        .unwrap_or_default();
+
+    let sizes = locked_deps
+        .iter()
+        .filter_map(|package| match package {
+            LockedPackageRef::Conda(conda) => Some((
+                conda.record().name.as_normalized().to_string(),
+                conda.record().size?,
+            )),
+            LockedPackageRef::Pypi(..) => None,
+        })
+        .collect::<HashMap<_, _>>();
+    let dep_map = generate_dependency_map(&locked_deps);
+    let direct_deps = direct_dependencies(&environment, &platform, &dep_map);
+    let nodes = graph_nodes(&dep_map, &direct_deps, &sizes);
+
+    let graph = match args.format {
+        GraphFormat::Dot => render_dot(environment.name().as_str(), &nodes),
+        GraphFormat::Mermaid => render_mermaid(&nodes),
+        GraphFormat::Json => serde_json::to_string_pretty(&nodes).into_diagnostic()?,
+    };
+    println!("{graph}");
+
+    Ok(())
+}
+
+/// Returns the nodes of the graph sorted by name. Dependencies on packages
+/// that are not part of the environment, like virtual packages, are left out.
+fn graph_nodes(
+    dep_map: &HashMap<String, Package>,
+    direct_deps: &HashSet<String>,
+    sizes: &HashMap<String, u64>,
+) -> Vec<Node> {
+    dep_map
+        .values()
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .map(|package| Node {
+            name: package.name.clone(),
+            version: package.version.clone(),
+            kind: match package.source {
+                PackageSource::Conda => "conda",
+                PackageSource::Pypi => "pypi",
+            },
+            size_bytes: sizes.get(&package.name).copied(),
+            explicit: direct_deps.contains(&package.name),
+            dependencies: package
+                .dependencies
+                .iter()
+                .filter(|dependency| dep_map.contains_key(*dependency))
+                .sorted()
+                .cloned()
+                .collect(),
+        })
+        .collect()
+}
+
+/// Escapes a string for use in a quoted DOT identifier.
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Quotes a string as a DOT identifier.
+fn dot_quote(value: &str) -> String {
+    format!("\"{}\"", dot_escape(value))
+}
+
+/// Renders the graph as a Graphviz DOT digraph.
+fn render_dot(name: &str, nodes: &[Node]) -> String {
+    let mut dot = format!("digraph {} {{\n", dot_quote(name));
+    for node in nodes {
+        let mut attributes = vec![
+            format!(
+                "label=\"{}\\n{}\"",
+                dot_escape(&node.name),
+                dot_escape(&node.version)
+            ),
+            format!("kind={}", dot_quote(node.kind)),
+            format!("version={}", dot_quote(&node.version)),
+        ];
+        if let Some(size) = node.size_bytes {
+            attributes.push(format!("size_bytes={size}"));
+        }
+        if node.explicit {
+            attributes.push(String::from("style=bold"));
+        }
+        let _ = writeln!(
+            dot,
+            "    {} [{}];",
+            dot_quote(&node.name),
+            attributes.join(", ")
+        );
+    }
+    for node in nodes {
+        for dependency in &node.dependencies {
+            let _ = writeln!(
+                dot,
+                "    {} -> {};",
+                dot_quote(&node.name),
+                dot_quote(dependency)
+            );
+        }
+    }
+    dot.push('}');
+    dot
+}
+
+/// Renders the graph as a Mermaid flowchart. Package names are not valid
+/// Mermaid identifiers, so the nodes are numbered.
+fn render_mermaid(nodes: &[Node]) -> String {
+    let ids = nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.name.as_str(), format!("n{index}")))
+        .collect::<HashMap<_, _>>();
+
+    let mut mermaid = String::from("flowchart TD\n");
+    for node in nodes {
+        let _ = writeln!(
+            mermaid,
+            "    {}[\"{} {}<br/>{}\"]",
+            ids[node.name.as_str()],
+            node.name,
+            node.version,
+            node.kind
+        );
+    }
+    for node in nodes {
+        for dependency in &node.dependencies {
+            let _ = writeln!(
+                mermaid,
+                "    {} --> {}",
+                ids[node.name.as_str()],
+                ids[dependency.as_str()]
+            );
+        }
+    }
+    let explicit = nodes
+        .iter()
+        .filter(|node| node.explicit)
+        .map(|node| ids[node.name.as_str()].as_str())
+        .join(",");
+    if !explicit.is_empty() {
+        mermaid.push_str("    classDef explicit font-weight:bold\n");
+        let _ = writeln!(mermaid, "    class {explicit} explicit");
+    }
+    mermaid.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<Node> {
+        vec![
+            Node {
+                name: String::from("numpy"),
+                version: String::from("1.26.4"),
+                kind: "conda",
+                size_bytes: Some(7000000),
+                explicit: true,
+                dependencies: vec![String::from("python")],
+            },
+            Node {
+                name: String::from("python"),
+                version: String::from("3.12.1"),
+                kind: "conda",
+                size_bytes: None,
+                explicit: false,
+                dependencies: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_render_dot() {
+        assert_eq!(
+            render_dot("default", &nodes()),
+            r#"digraph "default" {
+    "numpy" [label="numpy\n1.26.4", kind="conda", version="1.26.4", size_bytes=7000000, style=bold];
+    "python" [label="python\n3.12.1", kind="conda", version="3.12.1"];
+    "numpy" -> "python";
+}"#
+        );
+    }
+
+    #[test]
+    fn test_render_mermaid() {
+        assert_eq!(
+            render_mermaid(&nodes()),
+            r#"flowchart TD
+    n0["numpy 1.26.4<br/>conda"]
+    n1["python 3.12.1<br/>conda"]
+    n0 --> n1
+    classDef explicit font-weight:bold
+    class n0 explicit"#
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use clap::Parser;
+use comfy_table::{Cell, ContentArrangement, Table, presets::NOTHING};
+use console::Style;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_core::history::History;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Show the installation history of the workspace.
+///
+/// A snapshot of the lock-file and the installed packages is recorded every
+/// time an environment is installed from a changed lock-file. Use
+/// `pixi revert` to restore a snapshot.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// List the recorded snapshots
+    #[clap(visible_alias = "ls")]
+    List(ListArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ListArgs {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// Only list the snapshots of this environment
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// Output the snapshots in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::List(args) => list(args),
+    }
+}
+
+fn list(args: ListArgs) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let snapshots = History::new(&workspace)
+        .snapshots()?
+        .into_iter()
+        .filter(|snapshot| {
+            args.environment
+                .as_ref()
+                .is_none_or(|environment| &snapshot.environment == environment)
+        })
+        .collect_vec();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&snapshots).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    if snapshots.is_empty() {
+        eprintln!("No snapshots have been recorded yet");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    let header_style = Style::new().bold().cyan();
+    table.set_header(
+        [
+            "ID",
+            "Date",
+            "Environment",
+            "Platform",
+            "Packages",
+            "Changes",
+        ]
+        .map(|header| Cell::new(header_style.apply_to(header))),
+    );
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        // Compare with the previous snapshot of the same environment.
+        let changes = snapshots[..index]
+            .iter()
+            .rev()
+            .find(|previous| {
+                previous.environment == snapshot.environment
+                    && previous.platform == snapshot.platform
+            })
+            .map(|previous| {
+                let diff = snapshot.diff(previous);
+                format!(
+                    "+{} -{} ~{}",
+                    console::style(diff.added).green(),
+                    console::style(diff.removed).red(),
+                    console::style(diff.changed).yellow()
+                )
+            })
+            .unwrap_or_default();
+        table.add_row([
+            Cell::new(snapshot.id),
+            Cell::new(snapshot.created.format("%Y-%m-%d %H:%M:%S")),
+            Cell::new(&snapshot.environment),
+            Cell::new(snapshot.platform),
+            Cell::new(snapshot.packages.len()),
+            Cell::new(changes),
+        ]);
+    }
+
+    println!(
+        "{}",
+        table
+            .lines()
+            .map(|line| line.trim().to_string())
+            .format("\n")
+    );
+    Ok(())
+}
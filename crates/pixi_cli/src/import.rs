@@ -1,9 +1,13 @@
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::str::FromStr;
 
 use clap::{Parser, ValueEnum};
 use pixi_config::{Config, ConfigCli};
-use pixi_core::{WorkspaceLocator, environment::sanity_check_workspace};
+use pixi_core::{
+    environment::sanity_check_workspace,
+    lock_file::{UpdateContext, UvLock, filter_lock_file},
+};
 use pixi_manifest::{EnvironmentName, FeatureName, HasFeaturesIter, PrioritizedChannel};
 use pixi_utils::conda_environment_file::CondaEnvFile;
 use pixi_uv_conversions::convert_uv_requirements_to_pep508;
@@ -23,11 +27,19 @@ pub enum ImportFileFormat {
     // TODO: implement conda-lock, conda-txt
     CondaEnv,
     PypiTxt,
+    /// Resolve the PyPI dependencies again, preferring the versions from a
+    /// `uv.lock`.
+    UvLock,
 }
 
 /// Imports a file into an environment in an existing workspace.
 ///
-/// If `--format` isn't provided, `import` will try each format in turn
+/// If `--format` isn't provided, `import` will try each format in turn. A file
+/// named `uv.lock` is imported as a `uv-lock`.
+///
+/// Importing a `uv.lock` does not change the manifest. Instead, the PyPI
+/// dependencies in `pixi.lock` are resolved again, preferring the versions
+/// that uv locked as long as they match the requirements of the workspace.
 #[derive(Parser, Debug, Default, Clone)]
 #[clap(arg_required_else_help = true, verbatim_doc_comment)]
 pub struct Args {
@@ -61,6 +73,8 @@ pub struct Args {
 pub async fn execute(args: Args) -> miette::Result<()> {
     if let Some(format) = &args.format {
         import(args.clone(), format).await
+    } else if args.file.file_name().is_some_and(|name| name == "uv.lock") {
+        import(args, &ImportFileFormat::UvLock).await
     } else if let Ok(result) = import(args.clone(), &ImportFileFormat::CondaEnv).await {
         Ok(result)
     } else if let Ok(result) = import(args, &ImportFileFormat::PypiTxt).await {
@@ -127,12 +141,16 @@ fn convert_uv_requirements_txt_to_pep508(
 }
 
 async fn import(args: Args, format: &ImportFileFormat) -> miette::Result<()> {
+    if *format == ImportFileFormat::UvLock {
+        return import_uv_lock(args).await;
+    }
+
     let (input_file, platforms, workspace_config) =
         (args.file, args.platforms, args.workspace_config);
     let config = Config::from(args.config);
 
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(workspace_config.workspace_locator_start())
+    let workspace = workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(config.clone());
 
@@ -172,6 +190,7 @@ async fn import(args: Args, format: &ImportFileFormat) -> miette::Result<()> {
                 })?;
             (ProcessedInput::PypiTxt, feature_name, environment_name)
         }
+        ImportFileFormat::UvLock => unreachable!("a uv.lock is not imported into the manifest"),
     };
 
     // Add the platforms if they are not already present
@@ -260,3 +279,65 @@ async fn import(args: Args, format: &ImportFileFormat) -> miette::Result<()> {
 
     Ok(())
 }
+
+/// Resolves the PyPI dependencies of the workspace again, preferring the
+/// versions that are locked in a `uv.lock`.
+async fn import_uv_lock(args: Args) -> miette::Result<()> {
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(Config::from(args.config));
+
+    sanity_check_workspace(&workspace).await?;
+
+    let preferred_versions = UvLock::from_path(&args.file)?.into_versions();
+    if preferred_versions.is_empty() {
+        eprintln!(
+            "{}No packages from a registry are locked in {}",
+            console::style(console::Emoji("⚠ ", "")).yellow(),
+            args.file.display()
+        );
+    }
+
+    // Drop the locked PyPI packages, so they are resolved again with the
+    // versions from the uv.lock as preferences.
+    let lock_file = workspace
+        .load_lock_file()
+        .await?
+        .into_lock_file_or_empty_with_warning();
+    let conda_lock_file = filter_lock_file(&workspace, &lock_file, |_, _, package| {
+        package.as_pypi().is_none()
+    });
+
+    let updated_lock_file = UpdateContext::builder(&workspace)
+        .with_lock_file(conda_lock_file)
+        .with_no_install(false)
+        .with_preferred_pypi_versions(preferred_versions.clone())
+        .finish()
+        .await?
+        .update()
+        .await?;
+    updated_lock_file.write_to_disk()?;
+    let lock_file = updated_lock_file.into_lock_file();
+
+    let locked = lock_file
+        .environments()
+        .flat_map(|(_, environment)| environment.pypi_packages_by_platform())
+        .flat_map(|(_, packages)| packages)
+        .map(|(package, _)| (&package.name, &package.version))
+        .collect::<HashSet<_>>();
+    let from_uv_lock = locked
+        .iter()
+        .filter(|(name, version)| preferred_versions.get(*name) == Some(*version))
+        .count();
+
+    eprintln!(
+        "{}Locked {from_uv_lock} of {} PyPI packages at the version from {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        locked.len(),
+        args.file.display()
+    );
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use std::{fmt::Display, io::Write, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use chrono::{DateTime, Local};
 use clap::Parser;
@@ -7,7 +12,7 @@ use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_config;
 use pixi_consts::consts;
-use pixi_core::WorkspaceLocator;
+use pixi_core::Workspace;
 use pixi_global::{BinDir, EnvRoot};
 use pixi_manifest::{EnvironmentName, FeatureName, SystemRequirements};
 use pixi_manifest::{FeaturesExt, HasFeaturesIter};
@@ -16,20 +21,25 @@ use pixi_task::TaskName;
 use pixi_utils::reqwest::tls_backend;
 use rattler_conda_types::{GenericVirtualPackage, Platform};
 use rattler_networking::authentication_storage;
-use rattler_virtual_packages::{VirtualPackage, VirtualPackageOverrides};
+use rattler_virtual_packages::VirtualPackage;
 use serde::Serialize;
 use serde_with::{DisplayFromStr, serde_as};
 use tokio::task::spawn_blocking;
 use toml_edit::ser::to_string;
 
-use crate::cli_config::WorkspaceConfig;
+use crate::{cli_config::WorkspaceConfig, list::get_dir_size};
 
 static WIDTH: usize = 19;
 
+/// The version of the JSON output of `pixi info`, it is increased when fields
+/// are removed or change their meaning.
+const INFO_SCHEMA_VERSION: u32 = 1;
+
 /// Information about the system, workspace and environments for the current machine.
 #[derive(Parser, Debug)]
 pub struct Args {
-    /// Show cache and environment size
+    /// Show the disk usage of the caches, the `.pixi` folder and every
+    /// environment
     #[arg(long)]
     extended: bool,
 
@@ -44,10 +54,12 @@ pub struct Args {
 #[derive(Serialize)]
 pub struct WorkspaceInfo {
     name: String,
+    root: PathBuf,
     manifest_path: PathBuf,
     last_updated: Option<String>,
     pixi_folder_size: Option<String>,
     version: Option<String>,
+    features: Vec<FeatureName>,
 }
 
 #[derive(Serialize)]
@@ -224,13 +236,18 @@ impl Display for GlobalInfo {
 #[serde_as]
 #[derive(Serialize)]
 pub struct Info {
+    schema_version: u32,
     platform: String,
     #[serde_as(as = "Vec<DisplayFromStr>")]
     virtual_packages: Vec<GenericVirtualPackage>,
     version: String,
     tls_backend: String,
     cache_dir: Option<PathBuf>,
+    package_cache_dir: PathBuf,
+    repodata_cache_dir: PathBuf,
     cache_size: Option<String>,
+    package_cache_size: Option<String>,
+    repodata_cache_size: Option<String>,
     auth_dir: PathBuf,
     global_info: Option<GlobalInfo>,
     project_info: Option<WorkspaceInfo>,
@@ -277,6 +294,22 @@ impl Display for Info {
         if let Some(cache_size) = &self.cache_size {
             writeln!(f, "{:>WIDTH$}: {}", bold.apply_to("Cache size"), cache_size)?;
         }
+        if let Some(size) = &self.package_cache_size {
+            writeln!(
+                f,
+                "{:>WIDTH$}: {}",
+                bold.apply_to("Package cache size"),
+                size
+            )?;
+        }
+        if let Some(size) = &self.repodata_cache_size {
+            writeln!(
+                f,
+                "{:>WIDTH$}: {}",
+                bold.apply_to("Repodata cache size"),
+                size
+            )?;
+        }
 
         writeln!(
             f,
@@ -330,6 +363,9 @@ impl Display for Info {
                     update_time
                 )?;
             }
+            if let Some(size) = &pi.pixi_folder_size {
+                writeln!(f, "{:>WIDTH$}: {}", bold.apply_to("Pixi folder size"), size)?;
+            }
         }
 
         if !self.environments_info.is_empty() {
@@ -347,26 +383,43 @@ impl Display for Info {
     }
 }
 
-/// Returns the size of a directory
-fn dir_size(path: impl Into<PathBuf>) -> miette::Result<String> {
-    fn dir_size(mut dir: fs_err::ReadDir) -> miette::Result<u64> {
-        dir.try_fold(0, |acc, file| {
-            let file = file.into_diagnostic()?;
-            let size = match file.metadata().into_diagnostic()? {
-                data if data.is_dir() => {
-                    dir_size(fs_err::read_dir(file.path()).into_diagnostic()?)?
-                }
-                data => data.len(),
-            };
-            Ok(acc + size)
+/// Returns the sizes of the directories, computed in parallel. The size is
+/// `None` if a directory does not exist or could not be read.
+async fn dir_sizes(paths: Vec<PathBuf>) -> miette::Result<HashMap<PathBuf, Option<String>>> {
+    let sizes = paths.into_iter().map(|path| {
+        spawn_blocking(move || {
+            let size = get_dir_size(&path)
+                .ok()
+                .map(|size| indicatif::HumanBytes(size).to_string());
+            (path, size)
         })
-    }
-
-    let size = dir_size(fs_err::read_dir(path.into()).into_diagnostic()?)?;
-    Ok(format!("{} MiB", size / 1024 / 1024))
+    });
+    let sizes = futures::future::try_join_all(sizes)
+        .await
+        .into_diagnostic()?;
+    Ok(sizes.into_iter().collect())
 }
 
 /// Returns last update time of file, formatted: DD-MM-YYYY H:M:S
+/// Returns the information about the workspace.
+fn workspace_info(workspace: &Workspace, pixi_folder_size: Option<String>) -> WorkspaceInfo {
+    WorkspaceInfo {
+        name: workspace.display_name().to_string(),
+        root: workspace.root().to_path_buf(),
+        manifest_path: workspace.workspace.provenance.path.clone(),
+        last_updated: last_updated(workspace.lock_file_path()).ok(),
+        pixi_folder_size,
+        version: workspace
+            .workspace
+            .value
+            .workspace
+            .version
+            .clone()
+            .map(|v| v.to_string()),
+        features: workspace.workspace.value.features.keys().cloned().collect(),
+    }
+}
+
 fn last_updated(path: impl Into<PathBuf>) -> miette::Result<String> {
     let time = fs_err::metadata(path.into())
         .into_diagnostic()?
@@ -380,40 +433,35 @@ fn last_updated(path: impl Into<PathBuf>) -> miette::Result<String> {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
-        .locate()
-        .ok();
-
-    let (pixi_folder_size, cache_size) = if args.extended {
-        let env_dir = workspace.as_ref().map(|p| p.pixi_dir());
-        let cache_dir = pixi_config::get_cache_dir()?;
-        await_in_progress("fetching directory sizes", |_| {
-            spawn_blocking(move || {
-                let env_size = env_dir.and_then(|env| dir_size(env).ok());
-                let cache_size = dir_size(cache_dir).ok();
-                (env_size, cache_size)
-            })
-        })
-        .await
-        .into_diagnostic()?
+    let workspace = args.project_config.workspace_locator().locate().ok();
+
+    let cache_dir = pixi_config::get_cache_dir()?;
+    let package_cache_dir = cache_dir.join(consts::CONDA_PACKAGE_CACHE_DIR);
+    let repodata_cache_dir = cache_dir.join(consts::CONDA_REPODATA_CACHE_DIR);
+
+    // Compute the sizes of the pixi folder, the caches and every environment at
+    // once.
+    let sizes = if args.extended {
+        let paths = [
+            cache_dir.clone(),
+            package_cache_dir.clone(),
+            repodata_cache_dir.clone(),
+        ]
+        .into_iter()
+        .chain(workspace.iter().flat_map(|ws| {
+            let env_dirs = ws.environments().into_iter().map(|env| env.dir());
+            std::iter::once(ws.pixi_dir()).chain(env_dirs)
+        }))
+        .collect();
+        await_in_progress("fetching directory sizes", |_| dir_sizes(paths)).await?
     } else {
-        (None, None)
+        HashMap::new()
     };
+    let size_of = |path: &Path| sizes.get(path).cloned().flatten();
 
-    let project_info = workspace.clone().map(|p| WorkspaceInfo {
-        name: p.display_name().to_string(),
-        manifest_path: p.workspace.provenance.path.clone(),
-        last_updated: last_updated(p.lock_file_path()).ok(),
-        pixi_folder_size,
-        version: p
-            .workspace
-            .value
-            .workspace
-            .version
-            .clone()
-            .map(|v| v.to_string()),
-    });
+    let project_info = workspace
+        .as_ref()
+        .map(|p| workspace_info(p, size_of(&p.pixi_dir())));
 
     let environments_info: Vec<EnvironmentInfo> = workspace
         .as_ref()
@@ -427,16 +475,13 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                         .map(|t| t.into_keys().cloned().collect())
                         .unwrap_or_default();
 
-                    let environment_size =
-                        args.extended.then(|| dir_size(env.dir()).ok()).flatten();
-
                     EnvironmentInfo {
                         name: env.name().clone(),
                         features: env.features().map(|feature| feature.name.clone()).collect(),
                         solve_group: env
                             .solve_group()
                             .map(|solve_group| solve_group.name().to_string()),
-                        environment_size,
+                        environment_size: size_of(&env.dir()),
                         dependencies: env
                             .combined_dependencies(Some(env.best_platform()))
                             .names()
@@ -464,17 +509,17 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         manifest: pixi_global::Project::manifest_dir()?.join(consts::GLOBAL_MANIFEST_DEFAULT_NAME),
     });
 
-    let virtual_packages = VirtualPackage::detect(&VirtualPackageOverrides::from_env())
+    let config = workspace
+        .map(|p| p.config().clone())
+        .unwrap_or_else(pixi_config::Config::load_global);
+
+    let virtual_packages = VirtualPackage::detect(&config.virtual_package_overrides())
         .into_diagnostic()?
         .iter()
         .cloned()
         .map(GenericVirtualPackage::from)
         .collect::<Vec<_>>();
 
-    let config = workspace
-        .map(|p| p.config().clone())
-        .unwrap_or_else(pixi_config::Config::load_global);
-
     let auth_file: PathBuf = if let Ok(auth_file) = std::env::var("RATTLER_AUTH_FILE") {
         auth_file.into()
     } else if let Some(auth_file) = config.authentication_override_file() {
@@ -486,12 +531,17 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     };
 
     let info = Info {
+        schema_version: INFO_SCHEMA_VERSION,
         platform: Platform::current().to_string(),
         virtual_packages,
         version: consts::PIXI_VERSION.to_string(),
         tls_backend: tls_backend().to_string(),
-        cache_dir: Some(pixi_config::get_cache_dir()?),
-        cache_size,
+        cache_size: size_of(&cache_dir),
+        package_cache_size: size_of(&package_cache_dir),
+        repodata_cache_size: size_of(&repodata_cache_dir),
+        cache_dir: Some(cache_dir),
+        package_cache_dir,
+        repodata_cache_dir,
         auth_dir: auth_file,
         project_info,
         environments_info,
@@ -525,3 +575,55 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dir_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let env_dir = dir.path().join("envs").join("default");
+        fs_err::create_dir_all(env_dir.join("bin")).unwrap();
+        fs_err::write(env_dir.join("bin").join("python"), vec![0u8; 2048]).unwrap();
+        fs_err::write(env_dir.join("README"), vec![0u8; 1024]).unwrap();
+        let missing_dir = dir.path().join("envs").join("missing");
+
+        let sizes = dir_sizes(vec![env_dir.clone(), missing_dir.clone()])
+            .await
+            .unwrap();
+        assert_eq!(sizes.len(), 2);
+        assert_eq!(sizes[&env_dir].as_deref(), Some("3.00 KiB"));
+        assert_eq!(sizes[&missing_dir], None);
+    }
+
+    #[test]
+    fn test_workspace_info_json() {
+        let workspace = Workspace::from_str(
+            Path::new("/workspace/pixi.toml"),
+            r#"
+        [workspace]
+        name = "info"
+        version = "0.1.0"
+        channels = []
+        platforms = []
+
+        [feature.test.dependencies]
+        pytest = "*"
+        "#,
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(workspace_info(&workspace, None)).unwrap();
+        assert_eq!(json["name"], "info");
+        assert_eq!(json["root"], "/workspace");
+        assert_eq!(json["manifest_path"], "/workspace/pixi.toml");
+        assert_eq!(json["version"], "0.1.0");
+        assert!(
+            json["features"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!("test"))
+        );
+    }
+}
@@ -1,15 +1,20 @@
 use clap::Parser;
 use fancy_display::FancyDisplay;
 use itertools::Itertools;
+use miette::{IntoDiagnostic, WrapErr};
 use pixi_config::ConfigCli;
+use pixi_consts::consts;
 use pixi_core::{
-    UpdateLockFileOptions, WorkspaceLocator,
-    environment::{InstallFilter, get_update_lock_file_and_prefixes},
+    UpdateLockFileOptions,
+    environment::{
+        InstallFilter, get_update_lock_file_and_prefix, get_update_lock_file_and_prefixes,
+        integrity::find_damaged_packages,
+    },
     lock_file::{LockFileDerivedData, PackageFilterNames, ReinstallPackages, UpdateMode},
 };
-use std::fmt::Write;
+use std::{collections::HashSet, fmt::Write};
 
-use crate::cli_config::WorkspaceConfig;
+use crate::cli_config::{ChannelPriorityConfig, PypiBuildConfig, WorkspaceConfig};
 
 /// Install an environment, both updating the lockfile and installing the
 /// environment.
@@ -17,12 +22,13 @@ use crate::cli_config::WorkspaceConfig;
 /// This command installs an environment, if the lockfile is not up-to-date it
 /// will be updated.
 ///
-/// `pixi install` only installs one environment at a time,
-/// if you have multiple environments you can select the right one with the
-/// `--environment` flag. If you don't provide an environment, the `default`
-/// environment will be installed.
+/// If you have multiple environments you can select the ones to install with
+/// the `--environment` flag, e.g. `--environments lint,test`. If you don't
+/// provide an environment, the `default` environment will be installed.
 ///
 /// If you want to install all environments, you can use the `--all` flag.
+/// The environments are installed concurrently and share the package cache,
+/// so packages that are used by multiple environments are downloaded once.
 ///
 /// Running `pixi install` is not required before running other commands like
 /// `pixi run` or `pixi shell`. These commands will automatically install the
@@ -30,6 +36,9 @@ use crate::cli_config::WorkspaceConfig;
 ///
 /// You can use `pixi reinstall` to reinstall all environments, one environment
 /// or just some packages of an environment.
+///
+/// Use `--repair` to reinstall only the packages whose installed files were
+/// deleted or modified.
 #[derive(Parser, Debug)]
 pub struct Args {
     #[clap(flatten)]
@@ -38,8 +47,14 @@ pub struct Args {
     #[clap(flatten)]
     pub lock_file_usage: crate::LockFileUsageConfig,
 
-    /// The environment to install
-    #[arg(long, short)]
+    #[clap(flatten)]
+    pub channel_priority_config: ChannelPriorityConfig,
+
+    #[clap(flatten)]
+    pub pypi_build_config: PypiBuildConfig,
+
+    /// The environments to install, separated by commas
+    #[arg(long, short, visible_alias = "environments", value_delimiter = ',')]
     pub environment: Option<Vec<String>>,
 
     #[clap(flatten)]
@@ -65,15 +80,22 @@ pub struct Args {
     /// passed multiple times.
     #[arg(long)]
     pub only: Option<Vec<String>>,
+
+    /// Reinstall the packages whose installed files were deleted or modified
+    #[arg(long)]
+    pub repair: bool,
 }
 
 const SKIP_CUTOFF: usize = 5;
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
+    let workspace = args
+        .project_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config);
+    let workspace = args.channel_priority_config.apply(workspace);
+    let workspace = args.pypi_build_config.apply(workspace);
 
     // Install either:
     //
@@ -95,6 +117,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     // Get the environments by name
     let environments = envs
         .into_iter()
+        .unique()
         .map(|env| workspace.environment_from_name_or_env_var(Some(env)))
         .collect::<Result<Vec<_>, _>>()?;
 
@@ -104,19 +127,89 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .skip_with_deps(args.skip_with_deps.clone().unwrap_or_default())
         .target_packages(args.only.clone().unwrap_or_default());
 
-    // Update the prefixes by installing all packages
-    let (LockFileDerivedData { lock_file, .. }, _) = get_update_lock_file_and_prefixes(
-        &environments,
-        UpdateMode::Revalidate,
-        UpdateLockFileOptions {
-            lock_file_usage: args.lock_file_usage.to_usage(),
-            no_install: false,
-            max_concurrent_solves: workspace.config().max_concurrent_solves(),
-        },
-        ReinstallPackages::default(),
-        &filter,
-    )
-    .await?;
+    // Find the damaged packages that need to be reinstalled, per environment
+    let mut damaged_environments = Vec::new();
+    if args.repair {
+        for environment in &environments {
+            let prefix = environment.dir();
+            if !prefix.join(consts::CONDA_META_DIR).is_dir() {
+                continue;
+            }
+            let damaged = find_damaged_packages(&prefix)
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    format!(
+                        "failed to check the {} environment",
+                        environment.name().fancy_display()
+                    )
+                })?;
+            let mut damaged_packages = HashSet::new();
+            for package in damaged {
+                eprintln!(
+                    "{}Repairing {} in the {} environment, {} damaged files",
+                    console::style(console::Emoji("⚠ ", "")).yellow(),
+                    console::style(package.name.as_source()).bold(),
+                    environment.name().fancy_display(),
+                    package.files.len()
+                );
+                damaged_packages.insert(package.name.as_normalized().to_string());
+            }
+            if !damaged_packages.is_empty() {
+                damaged_environments.push((environment.clone(), damaged_packages));
+            }
+        }
+    }
+
+    // Update the prefixes by installing all packages, the damaged packages are
+    // only reinstalled in the environment they are damaged in.
+    let update_lock_file_options = || UpdateLockFileOptions {
+        lock_file_usage: args.lock_file_usage.to_usage(),
+        no_install: false,
+        max_concurrent_solves: workspace.config().max_concurrent_solves(),
+    };
+    let intact_environments = environments
+        .iter()
+        .filter(|env| {
+            !damaged_environments
+                .iter()
+                .any(|(damaged, _)| damaged == *env)
+        })
+        .cloned()
+        .collect_vec();
+    let mut lock_file = None;
+    if !intact_environments.is_empty() {
+        let (
+            LockFileDerivedData {
+                lock_file: updated, ..
+            },
+            _,
+        ) = get_update_lock_file_and_prefixes(
+            &intact_environments,
+            UpdateMode::Revalidate,
+            update_lock_file_options(),
+            ReinstallPackages::None,
+            &filter,
+        )
+        .await?;
+        lock_file = Some(updated);
+    }
+    for (environment, damaged_packages) in damaged_environments {
+        let (
+            LockFileDerivedData {
+                lock_file: updated, ..
+            },
+            _,
+        ) = get_update_lock_file_and_prefix(
+            &environment,
+            UpdateMode::Revalidate,
+            update_lock_file_options(),
+            ReinstallPackages::Some(damaged_packages),
+            &filter,
+        )
+        .await?;
+        lock_file = Some(updated);
+    }
+    let lock_file = lock_file.expect("at least one environment is installed");
 
     // Message what's installed
     let mut message = console::style(console::Emoji("✔ ", "")).green().to_string();
@@ -224,3 +317,25 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_environments() {
+        let args =
+            Args::try_parse_from(["install", "-e", "lint,test", "--environments", "docs"]).unwrap();
+        assert_eq!(
+            args.environment,
+            Some(vec![
+                String::from("lint"),
+                String::from("test"),
+                String::from("docs")
+            ])
+        );
+
+        let args = Args::try_parse_from(["install"]).unwrap();
+        assert_eq!(args.environment, None);
+    }
+}
@@ -0,0 +1,75 @@
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use miette::IntoDiagnostic;
+use pixi_consts::consts;
+use pixi_manifest::FeaturesExt;
+
+use crate::{
+    cli_config::WorkspaceConfig,
+    kernelspec::{self, KernelSpec},
+};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The environment to install the kernel for
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// The name of the kernel, defaults to `pixi-<workspace>-<environment>`.
+    /// It may only contain lowercase letters, digits, `.`, `-` and `_`
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// The name of the kernel that is shown in Jupyter, defaults to
+    /// `<workspace> (<environment>)`
+    #[arg(long)]
+    pub display_name: Option<String>,
+}
+
+pub async fn execute(workspace_config: WorkspaceConfig, args: Args) -> miette::Result<()> {
+    let workspace = workspace_config.workspace_locator().locate()?;
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+
+    let has_ipykernel = environment
+        .combined_dependencies(None)
+        .names()
+        .any(|name| name.as_normalized() == "ipykernel")
+        || environment
+            .pypi_dependencies(None)
+            .names()
+            .any(|name| name.as_source().eq_ignore_ascii_case("ipykernel"));
+    if !has_ipykernel {
+        tracing::warn!(
+            "the {} environment does not depend on `ipykernel`, add it with `pixi add ipykernel` to use the kernel",
+            environment.name().fancy_display()
+        );
+    }
+
+    let name = args.name.unwrap_or_else(|| {
+        kernelspec::kernel_name(workspace.display_name(), environment.name().as_str())
+    });
+    let display_name = args.display_name.unwrap_or_else(|| {
+        format!(
+            "{} ({})",
+            workspace.display_name(),
+            environment.name().as_str()
+        )
+    });
+    let spec = KernelSpec::pixi_run(
+        &std::env::current_exe().into_diagnostic()?,
+        &workspace.workspace.provenance.path,
+        environment.name().as_str(),
+        display_name,
+    );
+    let kernel_dir = kernelspec::install(&kernelspec::kernels_dir()?, &name, &spec)?;
+
+    eprintln!(
+        "{}Installed the Jupyter kernel {} for {} in '{}'",
+        console::style(console::Emoji("✔ ", "")).green(),
+        consts::ENVIRONMENT_STYLE.apply_to(&name),
+        environment.name().fancy_display(),
+        kernel_dir.display(),
+    );
+
+    Ok(())
+}
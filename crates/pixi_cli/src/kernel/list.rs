@@ -0,0 +1,89 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use comfy_table::{Cell, ContentArrangement, Table, presets::NOTHING};
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use crate::kernelspec;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// A kernel that was installed by pixi.
+#[derive(Debug, Serialize)]
+struct Kernel {
+    name: String,
+    display_name: String,
+    manifest_path: PathBuf,
+    environment: String,
+}
+
+pub fn execute(args: Args) -> miette::Result<()> {
+    let kernels = kernelspec::list(&kernelspec::kernels_dir()?)?
+        .into_iter()
+        .filter_map(|(name, spec)| {
+            let pixi = spec.pixi()?.clone();
+            Some(Kernel {
+                name,
+                display_name: spec.display_name,
+                manifest_path: pixi.manifest_path,
+                environment: pixi.environment,
+            })
+        })
+        .collect_vec();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&kernels).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    if kernels.is_empty() {
+        eprintln!("No kernels were installed by pixi");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    let header_style = console::Style::new().bold().cyan();
+    table.set_header(
+        ["Kernel", "Display name", "Environment", "Manifest"]
+            .map(|header| Cell::new(header_style.apply_to(header).to_string())),
+    );
+    for kernel in &kernels {
+        let manifest = if kernel.manifest_path.is_file() {
+            kernel.manifest_path.display().to_string()
+        } else {
+            format!(
+                "{} {}",
+                kernel.manifest_path.display(),
+                console::style("(missing)").red()
+            )
+        };
+        table.add_row([
+            Cell::new(console::style(&kernel.name).bold().to_string()),
+            Cell::new(&kernel.display_name),
+            Cell::new(&kernel.environment),
+            Cell::new(manifest),
+        ]);
+    }
+    println!(
+        "{}",
+        table
+            .lines()
+            .map(|line| line.trim().to_string())
+            .format("\n")
+    );
+
+    Ok(())
+}
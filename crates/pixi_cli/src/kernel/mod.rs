@@ -0,0 +1,48 @@
+pub mod install;
+pub mod list;
+pub mod remove;
+
+use clap::Parser;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Commands to manage the Jupyter kernels of the environments.
+///
+/// The kernels start python with `pixi run`, so notebooks always run in the
+/// up-to-date environment of the workspace.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The subcommand to execute
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Install a Jupyter kernel for an environment of the workspace.
+    ///
+    /// The environment needs to contain `ipykernel`.
+    ///
+    /// Example:
+    /// `pixi kernel install --environment test`
+    Install(install::Args),
+    /// List the Jupyter kernels that were installed by pixi.
+    #[clap(visible_alias = "ls")]
+    List(list::Args),
+    /// Remove a Jupyter kernel that was installed by pixi.
+    #[clap(visible_alias = "rm")]
+    Remove(remove::Args),
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::Install(install_args) => {
+            install::execute(args.workspace_config, install_args).await
+        }
+        Command::List(list_args) => list::execute(list_args),
+        Command::Remove(remove_args) => remove::execute(args.workspace_config, remove_args),
+    }
+}
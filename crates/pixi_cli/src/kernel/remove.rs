@@ -0,0 +1,43 @@
+use clap::Parser;
+use pixi_consts::consts;
+
+use crate::{cli_config::WorkspaceConfig, kernelspec};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The name of the kernel to remove, defaults to the kernel of the
+    /// environment
+    pub name: Option<String>,
+
+    /// The environment to remove the kernel of
+    #[arg(long, short, conflicts_with = "name")]
+    pub environment: Option<String>,
+}
+
+pub fn execute(workspace_config: WorkspaceConfig, args: Args) -> miette::Result<()> {
+    let name = match args.name {
+        Some(name) => name,
+        None => {
+            let workspace = workspace_config.workspace_locator().locate()?;
+            let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+            kernelspec::kernel_name(workspace.display_name(), environment.name().as_str())
+        }
+    };
+
+    let kernels_dir = kernelspec::kernels_dir()?;
+    // Kernels of other tools are never removed.
+    if !kernelspec::read(&kernels_dir, &name)?.is_some_and(|spec| spec.pixi().is_some()) {
+        miette::bail!(
+            "there is no kernel named '{name}' that was installed by pixi, see `pixi kernel list`"
+        );
+    }
+    kernelspec::remove(&kernels_dir, &name)?;
+
+    eprintln!(
+        "{}Removed the Jupyter kernel {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        consts::ENVIRONMENT_STYLE.apply_to(&name),
+    );
+
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+
+use miette::{Context, IntoDiagnostic};
+use serde::{Deserialize, Serialize};
+
+/// The name of the environment variable that overrides the data directory of
+/// Jupyter.
+const JUPYTER_DATA_DIR: &str = "JUPYTER_DATA_DIR";
+
+/// A Jupyter kernel specification, the `kernel.json` in the directory of a
+/// kernel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct KernelSpec {
+    /// The command that starts the kernel, `{connection_file}` is replaced by
+    /// Jupyter.
+    pub argv: Vec<String>,
+    pub display_name: String,
+    pub language: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<KernelMetadata>,
+}
+
+/// The metadata that pixi stores in the kernels it installs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct KernelMetadata {
+    pub pixi: PixiKernelMetadata,
+}
+
+/// The workspace and environment a kernel was installed for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PixiKernelMetadata {
+    pub manifest_path: PathBuf,
+    pub environment: String,
+}
+
+impl KernelSpec {
+    /// Returns a kernel that starts `ipykernel` with `pixi run`, so the kernel
+    /// always runs in the up-to-date environment of the workspace.
+    pub(crate) fn pixi_run(
+        pixi: &Path,
+        manifest_path: &Path,
+        environment: &str,
+        display_name: String,
+    ) -> Self {
+        Self {
+            argv: [
+                pixi.to_string_lossy().as_ref(),
+                "run",
+                "--manifest-path",
+                manifest_path.to_string_lossy().as_ref(),
+                "--environment",
+                environment,
+                "python",
+                "-m",
+                "ipykernel_launcher",
+                "-f",
+                "{connection_file}",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            display_name,
+            language: "python".to_string(),
+            metadata: Some(KernelMetadata {
+                pixi: PixiKernelMetadata {
+                    manifest_path: manifest_path.to_path_buf(),
+                    environment: environment.to_string(),
+                },
+            }),
+        }
+    }
+
+    /// Returns the workspace and environment the kernel was installed for, if
+    /// it was installed by pixi.
+    pub(crate) fn pixi(&self) -> Option<&PixiKernelMetadata> {
+        self.metadata.as_ref().map(|metadata| &metadata.pixi)
+    }
+}
+
+/// Returns the directory in which Jupyter looks for the kernels of the user.
+pub(crate) fn kernels_dir() -> miette::Result<PathBuf> {
+    let data_dir = match std::env::var_os(JUPYTER_DATA_DIR) {
+        Some(dir) => PathBuf::from(dir),
+        None if cfg!(target_os = "macos") => dirs::home_dir()
+            .ok_or_else(|| miette::miette!("could not determine the home directory"))?
+            .join("Library/Jupyter"),
+        None => dirs::data_dir()
+            .ok_or_else(|| miette::miette!("could not determine the data directory"))?
+            .join("jupyter"),
+    };
+    Ok(data_dir.join("kernels"))
+}
+
+/// Returns the name of the kernel of an environment of a workspace. Jupyter
+/// only allows lowercase alphanumeric characters, `.`, `-` and `_` in the
+/// names of kernels.
+pub(crate) fn kernel_name(workspace: &str, environment: &str) -> String {
+    format!("pixi-{workspace}-{environment}")
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Returns the directory of the kernel with the given name, after checking
+/// that the name follows the rules of Jupyter, so it cannot point outside of
+/// `kernels_dir`.
+fn kernel_dir(kernels_dir: &Path, name: &str) -> miette::Result<PathBuf> {
+    let valid = !name.is_empty()
+        && !name.chars().all(|c| c == '.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        miette::bail!(
+            "'{name}' is not a valid kernel name, it may only contain lowercase letters, digits, `.`, `-` and `_`"
+        );
+    }
+    Ok(kernels_dir.join(name))
+}
+
+/// Writes the kernel to `<kernels_dir>/<name>/kernel.json`, replacing an
+/// existing kernel with the same name that was installed for the same
+/// workspace. Returns the directory of the kernel.
+pub(crate) fn install(
+    kernels_dir: &Path,
+    name: &str,
+    spec: &KernelSpec,
+) -> miette::Result<PathBuf> {
+    let kernel_dir = kernel_dir(kernels_dir, name)?;
+    if let Some(existing) = read(kernels_dir, name)? {
+        let manifest_path = |spec: &KernelSpec| spec.pixi().map(|pixi| pixi.manifest_path.clone());
+        if manifest_path(&existing) != manifest_path(spec) {
+            miette::bail!(
+                help = "choose another name with `--name`",
+                "the kernel '{name}' already exists and was not installed for this workspace"
+            );
+        }
+    }
+    fs_err::create_dir_all(&kernel_dir).into_diagnostic()?;
+    let contents = serde_json::to_string_pretty(spec).into_diagnostic()?;
+    fs_err::write(kernel_dir.join("kernel.json"), contents)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("failed to install the kernel '{name}'"))?;
+    Ok(kernel_dir)
+}
+
+/// Reads the kernel with the given name, returns `None` if it doesn't exist.
+pub(crate) fn read(kernels_dir: &Path, name: &str) -> miette::Result<Option<KernelSpec>> {
+    let path = kernel_dir(kernels_dir, name)?.join("kernel.json");
+    match fs_err::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to parse '{}'", path.display()))
+            .map(Some),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).into_diagnostic(),
+    }
+}
+
+/// Returns the names and specifications of the kernels that were installed by
+/// pixi, sorted by name.
+pub(crate) fn list(kernels_dir: &Path) -> miette::Result<Vec<(String, KernelSpec)>> {
+    let entries = match fs_err::read_dir(kernels_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+    let mut kernels = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Kernels of other tools might not be valid for us, they are skipped.
+        if let Ok(Some(spec)) = read(kernels_dir, &name)
+            && spec.pixi().is_some()
+        {
+            kernels.push((name, spec));
+        }
+    }
+    kernels.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(kernels)
+}
+
+/// Removes the kernel with the given name. Returns `false` if it doesn't
+/// exist.
+pub(crate) fn remove(kernels_dir: &Path, name: &str) -> miette::Result<bool> {
+    let kernel_dir = kernel_dir(kernels_dir, name)?;
+    if !kernel_dir.is_dir() {
+        return Ok(false);
+    }
+    fs_err::remove_dir_all(&kernel_dir).into_diagnostic()?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kernel_name() {
+        assert_eq!(
+            kernel_name("My Project", "default"),
+            "pixi-my_project-default"
+        );
+        assert_eq!(kernel_name("pixi", "py3.12"), "pixi-pixi-py3.12");
+    }
+
+    #[test]
+    fn test_kernel_dir_rejects_invalid_names() {
+        let kernels_dir = Path::new("/kernels");
+        assert_eq!(
+            kernel_dir(kernels_dir, "pixi-pixi-py3.12").unwrap(),
+            Path::new("/kernels/pixi-pixi-py3.12")
+        );
+        for name in ["", "..", "../x", "a/b", "My Kernel", "Pixi"] {
+            assert!(kernel_dir(kernels_dir, name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_install_refuses_kernel_of_other_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = |manifest_path: &str| {
+            KernelSpec::pixi_run(
+                Path::new("/usr/bin/pixi"),
+                Path::new(manifest_path),
+                "default",
+                "pixi (default)".to_string(),
+            )
+        };
+        install(dir.path(), "pixi-pixi-default", &spec("/a/pixi.toml")).unwrap();
+        // Installing the kernel of the same workspace again replaces it.
+        install(dir.path(), "pixi-pixi-default", &spec("/a/pixi.toml")).unwrap();
+        assert!(install(dir.path(), "pixi-pixi-default", &spec("/b/pixi.toml")).is_err());
+
+        // Kernels of other tools are not replaced either.
+        fs_err::create_dir_all(dir.path().join("python3")).unwrap();
+        fs_err::write(
+            dir.path().join("python3/kernel.json"),
+            r#"{"argv": ["python"], "display_name": "Python 3", "language": "python"}"#,
+        )
+        .unwrap();
+        assert!(install(dir.path(), "python3", &spec("/a/pixi.toml")).is_err());
+    }
+
+    #[test]
+    fn test_pixi_run_kernel() {
+        let spec = KernelSpec::pixi_run(
+            Path::new("/usr/bin/pixi"),
+            Path::new("/workspace/pixi.toml"),
+            "test",
+            "pixi (test)".to_string(),
+        );
+        assert_eq!(
+            spec.argv,
+            [
+                "/usr/bin/pixi",
+                "run",
+                "--manifest-path",
+                "/workspace/pixi.toml",
+                "--environment",
+                "test",
+                "python",
+                "-m",
+                "ipykernel_launcher",
+                "-f",
+                "{connection_file}",
+            ]
+        );
+        assert_eq!(
+            spec.pixi(),
+            Some(&PixiKernelMetadata {
+                manifest_path: PathBuf::from("/workspace/pixi.toml"),
+                environment: "test".to_string(),
+            })
+        );
+
+        // The metadata is stored next to the fields that Jupyter reads.
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["language"], "python");
+        assert_eq!(json["metadata"]["pixi"]["environment"], "test");
+    }
+
+    #[test]
+    fn test_kernels_dir_from_jupyter_data_dir() {
+        temp_env::with_var(JUPYTER_DATA_DIR, Some("/jupyter"), || {
+            assert_eq!(kernels_dir().unwrap(), Path::new("/jupyter/kernels"));
+        });
+    }
+
+    #[test]
+    fn test_install_and_remove_kernel() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = KernelSpec {
+            argv: vec![
+                "python".to_string(),
+                "-m".to_string(),
+                "ipykernel_launcher".to_string(),
+            ],
+            display_name: "pixi (default)".to_string(),
+            language: "python".to_string(),
+            metadata: Some(KernelMetadata {
+                pixi: PixiKernelMetadata {
+                    manifest_path: PathBuf::from("/workspace/pixi.toml"),
+                    environment: "default".to_string(),
+                },
+            }),
+        };
+        install(dir.path(), "pixi-pixi-default", &spec).unwrap();
+        assert_eq!(
+            read(dir.path(), "pixi-pixi-default").unwrap(),
+            Some(spec.clone())
+        );
+
+        // Kernels that were not installed by pixi are not listed.
+        fs_err::create_dir_all(dir.path().join("python3")).unwrap();
+        fs_err::write(
+            dir.path().join("python3/kernel.json"),
+            r#"{"argv": ["python"], "display_name": "Python 3", "language": "python"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            list(dir.path()).unwrap(),
+            vec![("pixi-pixi-default".to_string(), spec)]
+        );
+
+        assert!(remove(dir.path(), "pixi-pixi-default").unwrap());
+        assert!(!remove(dir.path(), "pixi-pixi-default").unwrap());
+        assert_eq!(read(dir.path(), "pixi-pixi-default").unwrap(), None);
+    }
+}
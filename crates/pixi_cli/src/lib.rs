@@ -12,41 +12,70 @@ use clap::builder::styling::{AnsiColor, Color, Style};
 use clap::{CommandFactory, Parser};
 use indicatif::ProgressDrawTarget;
 use miette::IntoDiagnostic;
+use pixi_config::LogFormat;
 use pixi_consts::consts;
 use pixi_core::environment::LockFileUsage;
 use pixi_progress::global_multi_progress;
 
-use std::{env, io::IsTerminal};
+use error_format::{ErrorFormat, JsonReportHandler, JsonWarningLayer};
+use std::{io::IsTerminal, path::PathBuf, time::Instant};
+use timings::TimingsFormat;
 use tracing::level_filters::LevelFilter;
 
 pub mod add;
+pub mod auth;
 pub mod build;
+pub mod check;
 pub mod clean;
 pub mod cli_config;
 pub mod cli_interface;
 pub mod command_info;
 pub mod completion;
 pub mod config;
+pub mod deploy;
+pub mod doctor;
+pub mod env;
+mod error_format;
 pub mod exec;
+pub mod explain;
+pub mod fetch;
+pub mod fmt;
 pub mod global;
+pub mod graph;
 pub mod has_specs;
+pub mod history;
 pub mod import;
 pub mod info;
 pub mod init;
 pub mod install;
+pub mod kernel;
+mod kernelspec;
+pub mod license;
 pub mod list;
 pub mod lock;
 pub(crate) mod match_spec_or_path;
+mod metrics;
+pub mod outdated;
+pub mod pack;
+pub mod python;
 pub mod reinstall;
 pub mod remove;
+pub mod revert;
 pub mod run;
+mod run_overlay;
+pub mod schema;
 pub mod search;
 pub mod self_update;
 mod shared;
 pub mod shell;
 pub mod shell_hook;
 pub mod task;
+mod task_log;
+mod task_remote_cache;
+mod task_shell;
+mod timings;
 pub mod tree;
+pub mod unpack;
 pub mod update;
 pub mod upgrade;
 pub mod upload;
@@ -113,19 +142,46 @@ pub struct GlobalOptions {
     #[clap(short, long, action = clap::ArgAction::Count, global = true, help_heading = consts::CLAP_GLOBAL_OPTIONS)]
     quiet: u8,
 
-    /// Whether the log needs to be colored.
+    /// Whether the output is colored, `NO_COLOR` and `FORCE_COLOR` take
+    /// precedence.
     #[clap(long, default_value = "auto", global = true, env = "PIXI_COLOR", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
     color: ColorOutput,
 
     /// Hide all progress bars, always turned on if stderr is not a terminal.
     #[clap(long, default_value = "false", global = true, env = "PIXI_NO_PROGRESS", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
     no_progress: bool,
+
+    /// The format of the log output, defaults to the `log-format` configuration
+    /// or `text`.
+    #[clap(long, global = true, env = "PIXI_LOG_FORMAT", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    log_format: Option<LogFormat>,
+
+    /// Print how long the phases of the command took when it finishes
+    #[clap(long, global = true, num_args = 0..=1, default_missing_value = "table", require_equals = true, help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    timings: Option<TimingsFormat>,
+
+    /// Write a JSON summary of the command to this file when it finishes, with
+    /// the durations of its phases, the number of installed and removed
+    /// packages, the cache hit rate and the downloaded bytes
+    #[clap(long, global = true, env = "PIXI_METRICS_FILE", value_name = "PATH", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    metrics_file: Option<PathBuf>,
+
+    /// Print minimal, stable, line-oriented output intended for scripts,
+    /// without tables, styling or progress bars
+    #[clap(long, default_value = "false", global = true, env = "PIXI_PORCELAIN", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    porcelain: bool,
+
+    /// The format of the errors and warnings printed to stderr. `json` prints
+    /// every diagnostic as a JSON object with the location of its labels in
+    /// the source file, e.g. the manifest
+    #[clap(long, default_value = "text", global = true, env = "PIXI_ERROR_FORMAT", help_heading = consts::CLAP_GLOBAL_OPTIONS)]
+    error_format: ErrorFormat,
 }
 
 impl Args {
     /// Whether to show progress bars or not, based on the terminal and the user's preference.
     fn no_progress(&self) -> bool {
-        if !std::io::stderr().is_terminal() {
+        if !std::io::stderr().is_terminal() || self.global_options.porcelain {
             true
         } else {
             self.global_options.no_progress
@@ -146,6 +202,15 @@ impl Args {
             (_, _) => LevelFilter::TRACE, // -vvvv+
         }
     }
+
+    /// Determine the log format, `--log-format` takes precedence over the
+    /// `log-format` configuration.
+    #[allow(unused)]
+    fn log_format(&self, config: &pixi_config::Config) -> LogFormat {
+        self.global_options
+            .log_format
+            .unwrap_or_else(|| config.log_format())
+    }
 }
 
 #[derive(Parser, Debug)]
@@ -154,28 +219,44 @@ pub enum Command {
     // Commands in alphabetical order
     #[clap(visible_alias = "a")]
     Add(add::Args),
-    Auth(rattler::cli::auth::Args),
+    Auth(auth::Args),
     Build(build::Args),
+    Check(check::Args),
     Clean(clean::Args),
     Completion(completion::Args),
     Config(config::Args),
+    Deploy(deploy::Args),
+    Doctor(doctor::Args),
+    Env(env::Args),
     #[clap(visible_alias = "x")]
     Exec(exec::Args),
+    Explain(explain::Args),
+    Fetch(fetch::Args),
+    Fmt(fmt::Args),
     #[clap(visible_alias = "g")]
     Global(global::Args),
+    Graph(graph::Args),
+    History(history::Args),
     Info(info::Args),
     Init(init::Args),
     Import(import::Args),
     #[clap(visible_alias = "i")]
     Install(install::Args),
+    Kernel(kernel::Args),
+    License(license::Args),
     #[clap(visible_alias = "ls")]
     List(list::Args),
     Lock(lock::Args),
+    Outdated(outdated::Args),
+    Pack(pack::Args),
+    Python(python::Args),
     Reinstall(reinstall::Args),
     #[clap(visible_alias = "rm")]
     Remove(remove::Args),
+    Revert(revert::Args),
     #[clap(visible_alias = "r")]
     Run(run::Args),
+    Schema(schema::Args),
     Search(search::Args),
     #[cfg_attr(not(feature = "self_update"), clap(hide = true))]
     #[cfg_attr(feature = "self_update", clap(hide = false))]
@@ -186,11 +267,14 @@ pub enum Command {
     Task(task::Args),
     #[clap(visible_alias = "t")]
     Tree(tree::Args),
+    Unpack(unpack::Args),
     Update(update::Args),
     Upgrade(upgrade::Args),
     Upload(upload::Args),
     #[clap(alias = "project")]
     Workspace(workspace::Args),
+    #[clap(name = "__task-shell", hide = true)]
+    TaskShell(task_shell::Args),
     #[command(external_subcommand)]
     External(Vec<String>),
 }
@@ -228,27 +312,60 @@ impl LockFileUsageConfig {
     }
 }
 
+/// Makes pixi exit with the given code once the command has finished, without
+/// printing an error. Commands return this when they already reported their
+/// outcome, e.g. a failing task or a check that found problems, so that the
+/// timings, the metrics and the JSON errors are still written before exiting.
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+#[error("exited with code {0}")]
+pub struct ExitStatus(pub i32);
+
+impl ExitStatus {
+    /// Returns `Ok` for a zero exit code and an [`ExitStatus`] error otherwise.
+    pub fn from_code(code: i32) -> miette::Result<()> {
+        if code == 0 {
+            Ok(())
+        } else {
+            Err(Self(code).into())
+        }
+    }
+}
+
 pub async fn execute() -> miette::Result<()> {
+    let start = Instant::now();
     let args = Args::parse();
 
     // Extract values we need before moving args
     let no_progress = args.no_progress();
 
+    if args.global_options.porcelain {
+        pixi_utils::porcelain::enable();
+    }
     set_console_colors(&args);
 
+    // The global configuration is needed before the workspace is located, for
+    // the logging and the styles of the output.
+    let global_config = pixi_config::Config::load_global();
+    consts::set_style_theme(global_config.styles().clone());
+
     let use_colors = console::colors_enabled_stderr();
-    let in_ci = matches!(env::var("CI").as_deref(), Ok("1" | "true"));
-    let no_wrap = matches!(env::var("PIXI_NO_WRAP").as_deref(), Ok("1" | "true"));
-    // Set up the default miette handler based on whether we want colors or not.
-    miette::set_hook(Box::new(move |_| {
-        Box::new(
-            miette::MietteHandlerOpts::default()
-                .color(use_colors)
-                // Don't wrap lines in CI environments or when explicitly specified to avoid
-                // breaking logs and tests.
-                .wrap_lines(!in_ci && !no_wrap)
-                .build(),
-        )
+    let in_ci = matches!(std::env::var("CI").as_deref(), Ok("1" | "true"));
+    let no_wrap = matches!(std::env::var("PIXI_NO_WRAP").as_deref(), Ok("1" | "true"));
+    let error_format = args.global_options.error_format;
+    // Set up the default miette handler based on the error format and whether
+    // we want colors or not.
+    miette::set_hook(Box::new(move |_| -> Box<dyn miette::ReportHandler> {
+        match error_format {
+            ErrorFormat::Json => Box::new(JsonReportHandler),
+            ErrorFormat::Text => Box::new(
+                miette::MietteHandlerOpts::default()
+                    .color(use_colors)
+                    // Don't wrap lines in CI environments or when explicitly specified to avoid
+                    // breaking logs and tests.
+                    .wrap_lines(!in_ci && !no_wrap)
+                    .build(),
+            ),
+        }
     }))?;
 
     // Hide all progress bars if the user requested it.
@@ -263,28 +380,134 @@ pub async fn execute() -> miette::Result<()> {
     }
 
     // Setup logging for the application.
-    setup_logging(&args, use_colors)?;
+    setup_logging(&args, &global_config, use_colors)?;
+
+    for name in global_config.styles().keys() {
+        if !consts::STYLE_NAMES.contains(&name.as_str()) {
+            tracing::warn!(
+                "unknown style '{name}' in the `styles` configuration, known styles are: {}",
+                consts::STYLE_NAMES.join(", ")
+            );
+        }
+    }
 
     let (Some(command), global_options) = (args.command, args.global_options) else {
         // match CI expectations
         std::process::exit(2);
     };
 
+    if global_options.timings.is_some() || global_options.metrics_file.is_some() {
+        pixi_utils::timings::enable();
+    }
+    if global_options.metrics_file.is_some() {
+        pixi_utils::metrics::enable();
+    }
+
     // Execute the command
-    execute_command(command, &global_options).await
+    let result = execute_command(command, &global_options).await;
+
+    // Offer to switch to a pixi version that matches the `requires-pixi` of
+    // the workspace.
+    #[cfg(feature = "self_update")]
+    let result = match result {
+        Err(err) => offer_required_pixi_version(err).await,
+        Ok(()) => Ok(()),
+    };
+
+    if let Some(format) = global_options.timings {
+        timings::print_report(format, start.elapsed());
+    }
+    let exit_code = match &result {
+        Ok(()) => 0,
+        Err(err) => err
+            .downcast_ref::<ExitStatus>()
+            .map_or(1, |ExitStatus(code)| *code),
+    };
+    if let Some(path) = &global_options.metrics_file
+        && let Err(err) = metrics::write_report(path, exit_code, start.elapsed())
+    {
+        tracing::warn!("{err:?}");
+    }
+
+    let Err(err) = result else {
+        return Ok(());
+    };
+    if err.downcast_ref::<ExitStatus>().is_some() {
+        std::process::exit(exit_code);
+    }
+
+    // Print the error without the `Error: ` prefix that is added when it is
+    // returned from `main`, so that stderr only contains JSON.
+    if error_format == ErrorFormat::Json {
+        eprintln!("{err:?}");
+        std::process::exit(1);
+    }
+    Err(err)
+}
+
+/// If the error is caused by a workspace that requires a different version of
+/// pixi, asks the user whether to use a matching version. When the user
+/// agrees, that version is downloaded into the cache and the command is
+/// executed again with it. The running binary is left untouched.
+#[cfg(feature = "self_update")]
+async fn offer_required_pixi_version(err: miette::Report) -> miette::Result<()> {
+    let Some(pixi_core::workspace::WorkspaceLocatorError::ExplicitManifestError(
+        pixi_manifest::ExplicitManifestError::SelfVersionMatchError { requires_pixi },
+    )) = err.downcast_ref()
+    else {
+        return Err(err);
+    };
+    if !std::io::stdin().is_terminal()
+        || !std::io::stderr().is_terminal()
+        || !pixi_config::Config::load_global().allow_self_update()
+    {
+        return Err(err);
+    }
+
+    let requires_pixi = requires_pixi.clone();
+    eprintln!("{:?}", err);
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Download the latest pixi version that matches '{requires_pixi}' and run the command with it?"
+        ))
+        .default(false)
+        .show_default(true)
+        .interact()
+        .into_diagnostic()?;
+    if !confirmed {
+        // The error was printed above already.
+        return Err(ExitStatus(1).into());
+    }
+
+    let (version, binary) = self_update::download_matching(&requires_pixi).await?;
+    tracing::info!("running the command with pixi {version}");
+
+    let status = std::process::Command::new(&binary)
+        .args(std::env::args_os().skip(1))
+        .status()
+        .into_diagnostic()?;
+    ExitStatus::from_code(status.code().unwrap_or(1))
 }
 
 #[cfg(feature = "console-subscriber")]
-fn setup_logging(_args: &Args, _use_colors: bool) -> miette::Result<()> {
+fn setup_logging(
+    _args: &Args,
+    _config: &pixi_config::Config,
+    _use_colors: bool,
+) -> miette::Result<()> {
     console_subscriber::init();
     Ok(())
 }
 
 #[cfg(not(feature = "console-subscriber"))]
-fn setup_logging(args: &Args, use_colors: bool) -> miette::Result<()> {
+fn setup_logging(
+    args: &Args,
+    config: &pixi_config::Config,
+    use_colors: bool,
+) -> miette::Result<()> {
     use pixi_utils::indicatif::IndicatifWriter;
     use tracing_subscriber::{
-        EnvFilter, filter::LevelFilter, prelude::__tracing_subscriber_SubscriberExt,
+        EnvFilter, Layer, filter::LevelFilter, prelude::__tracing_subscriber_SubscriberExt,
         util::SubscriberInitExt,
     };
 
@@ -314,7 +537,7 @@ fn setup_logging(args: &Args, use_colors: bool) -> miette::Result<()> {
     } else {
         // No CLI flags - use RUST_LOG if set
         // Parse RUST_LOG because we need to set it other our other directives
-        let env_directives = env::var("RUST_LOG").unwrap_or_default();
+        let env_directives = std::env::var("RUST_LOG").unwrap_or_default();
         let original_directives = format!(
             "apple_codesign=off,pixi={pixi_level},pixi_command_dispatcher={pixi_level},pixi_core={pixi_level},uv_resolver={pixi_level},resolvo={low_level_filter}",
         );
@@ -331,16 +554,40 @@ fn setup_logging(args: &Args, use_colors: bool) -> miette::Result<()> {
             .into_diagnostic()?
     };
 
+    let log_format = args.log_format(config);
+
     // Set up the tracing subscriber
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_ansi(use_colors)
-        .with_target(pixi_level >= LevelFilter::INFO)
-        .with_writer(IndicatifWriter::new(pixi_progress::global_multi_progress()))
-        .without_time();
+    let writer = IndicatifWriter::new(pixi_progress::global_multi_progress());
+    // With `--error-format json` warnings and errors are printed as JSON
+    // diagnostics, all other events are printed in the configured log format.
+    let json_warnings = args.global_options.error_format == ErrorFormat::Json;
+    let warning_layer = json_warnings.then(|| JsonWarningLayer::new(writer.clone()));
+    let fmt_layer = match log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_ansi(use_colors)
+            .with_target(pixi_level >= LevelFilter::INFO)
+            .with_writer(writer)
+            .without_time()
+            .boxed(),
+        // Every event is written as a single line of JSON, including the
+        // timestamp, the target and the spans it was emitted in.
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_ansi(false)
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_target(true)
+            .with_writer(writer)
+            .boxed(),
+    };
+    let fmt_layer = fmt_layer.with_filter(tracing_subscriber::filter::filter_fn(move |meta| {
+        !json_warnings || *meta.level() > tracing::Level::WARN
+    }));
 
     tracing_subscriber::registry()
         .with(env_filter)
         .with(fmt_layer)
+        .with(warning_layer)
         .init();
     Ok(())
 }
@@ -358,30 +605,48 @@ pub async fn execute_command(
         Command::Clean(cmd) => clean::execute(cmd).await,
         Command::Run(cmd) => run::execute(cmd).await,
         Command::Global(cmd) => global::execute(cmd).await,
-        Command::Auth(cmd) => rattler::cli::auth::execute(cmd).await.into_diagnostic(),
+        Command::Auth(cmd) => auth::execute(cmd).await,
         Command::Install(cmd) => install::execute(cmd).await,
+        Command::Kernel(cmd) => kernel::execute(cmd).await,
+        Command::Deploy(cmd) => deploy::execute(cmd).await,
+        Command::Fetch(cmd) => fetch::execute(cmd).await,
+        Command::Doctor(cmd) => doctor::execute(cmd).await,
+        Command::Env(cmd) => env::execute(cmd).await,
         Command::Reinstall(cmd) => reinstall::execute(cmd).await,
+        Command::History(cmd) => history::execute(cmd).await,
+        Command::Revert(cmd) => revert::execute(cmd).await,
         Command::Shell(cmd) => shell::execute(cmd).await,
         Command::ShellHook(cmd) => shell_hook::execute(cmd).await,
         Command::Task(cmd) => task::execute(cmd).await,
+        Command::TaskShell(cmd) => task_shell::execute(cmd).await,
         Command::Info(cmd) => info::execute(cmd).await,
         Command::Import(cmd) => import::execute(cmd).await,
         Command::Upload(cmd) => upload::execute(cmd).await,
         Command::Search(cmd) => search::execute(cmd).await,
+        Command::Schema(cmd) => schema::execute(cmd).await,
         Command::Workspace(cmd) => workspace::execute(cmd).await,
         Command::Remove(cmd) => remove::execute(cmd).await,
         #[cfg(feature = "self_update")]
         Command::SelfUpdate(cmd) => self_update::execute(cmd, global_options).await,
         #[cfg(not(feature = "self_update"))]
         Command::SelfUpdate(cmd) => self_update::execute_stub(cmd, global_options).await,
+        Command::License(cmd) => license::execute(cmd).await,
         Command::List(cmd) => list::execute(cmd).await,
         Command::Tree(cmd) => tree::execute(cmd).await,
+        Command::Graph(cmd) => graph::execute(cmd).await,
+        Command::Pack(cmd) => pack::execute(cmd).await,
+        Command::Python(cmd) => python::execute(cmd).await,
+        Command::Unpack(cmd) => unpack::execute(cmd).await,
         Command::Update(cmd) => update::execute(cmd).await,
         Command::Upgrade(cmd) => upgrade::execute(cmd).await,
         Command::Lock(cmd) => lock::execute(cmd).await,
+        Command::Outdated(cmd) => outdated::execute(cmd).await,
         Command::Exec(args) => exec::execute(args).await,
+        Command::Explain(args) => explain::execute(args).await,
+        Command::Fmt(args) => fmt::execute(args).await,
         Command::Build(args) => build::execute(args).await,
-        Command::External(args) => command_info::execute_external_command(args),
+        Command::Check(args) => check::execute(args).await,
+        Command::External(args) => command_info::execute_external_command(args).await,
     }
 }
 
@@ -399,13 +664,18 @@ pub enum ColorOutput {
 
 fn set_console_colors(args: &Args) {
     // Honor FORCE_COLOR and NO_COLOR environment variables.
-    // Those take precedence over the CLI flag and PIXI_COLOR
-    let color = match env::var("FORCE_COLOR") {
-        Ok(_) => &ColorOutput::Always,
-        Err(_) => match env::var("NO_COLOR") {
-            Ok(_) => &ColorOutput::Never,
-            Err(_) => &args.global_options.color,
-        },
+    // Those take precedence over the CLI flag and PIXI_COLOR.
+    // The porcelain output is never styled.
+    let color = if args.global_options.porcelain {
+        &ColorOutput::Never
+    } else {
+        match std::env::var("FORCE_COLOR") {
+            Ok(_) => &ColorOutput::Always,
+            Err(_) => match std::env::var("NO_COLOR") {
+                Ok(_) => &ColorOutput::Never,
+                Err(_) => &args.global_options.color,
+            },
+        }
     };
 
     match color {
@@ -566,6 +836,36 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_log_format_precedence() {
+        let json_config = pixi_config::Config {
+            log_format: Some(LogFormat::Json),
+            ..Default::default()
+        };
+
+        temp_env::with_var_unset("PIXI_LOG_FORMAT", || {
+            let args = Args::try_parse_from(["pixi", "info"]).unwrap();
+            assert_eq!(
+                args.log_format(&pixi_config::Config::default()),
+                LogFormat::Text
+            );
+            assert_eq!(args.log_format(&json_config), LogFormat::Json);
+
+            let args = Args::try_parse_from(["pixi", "--log-format", "text", "info"]).unwrap();
+            assert_eq!(args.log_format(&json_config), LogFormat::Text);
+
+            assert!(Args::try_parse_from(["pixi", "--log-format", "xml", "info"]).is_err());
+        });
+
+        temp_env::with_var("PIXI_LOG_FORMAT", Some("json"), || {
+            let args = Args::try_parse_from(["pixi", "info"]).unwrap();
+            assert_eq!(
+                args.log_format(&pixi_config::Config::default()),
+                LogFormat::Json
+            );
+        });
+    }
+
     #[test]
     fn test_cli_args_override_env_vars() {
         // Test that CLI arguments take precedence over environment variables
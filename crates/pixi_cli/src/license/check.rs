@@ -0,0 +1,85 @@
+use clap::Parser;
+use pixi_core::Workspace;
+use pixi_manifest::LicenseVerdict;
+use rattler_lock::LockFile;
+
+use super::licensed_packages;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The environments to check. Defaults to all environments.
+    #[arg(short, long, value_delimiter = ',')]
+    pub environment: Vec<String>,
+
+    /// Do not fail on packages with an unknown license when the policy has an
+    /// allow list
+    #[arg(long)]
+    pub allow_unknown: bool,
+}
+
+pub fn execute(workspace: &Workspace, lock_file: &LockFile, args: Args) -> miette::Result<()> {
+    let policy = &workspace
+        .workspace()
+        .workspace
+        .value
+        .workspace
+        .license_policy;
+    if policy.is_empty() {
+        eprintln!(
+            "{}The manifest does not define a license policy, add a `[workspace.license-policy]` section with the allowed or denied licenses",
+            console::style(console::Emoji("⚠️ ", "")).yellow(),
+        );
+        return Ok(());
+    }
+
+    let packages = licensed_packages(lock_file, &args.environment);
+    let mut violations = 0;
+    for package in &packages {
+        let verdict = policy.evaluate(package.license.as_deref());
+        let license = package.license.as_deref().unwrap_or("unknown");
+        let reason = match verdict {
+            LicenseVerdict::Allowed => continue,
+            LicenseVerdict::Denied => "is denied",
+            LicenseVerdict::NotAllowed => "is not allowed",
+            LicenseVerdict::Unknown if policy.allow.is_empty() || args.allow_unknown => {
+                eprintln!(
+                    "{}{} {} ({}) has an unknown license: {}",
+                    console::style(console::Emoji("⚠️ ", "")).yellow(),
+                    console::style(&package.name).bold(),
+                    package.version,
+                    package.kind,
+                    license,
+                );
+                continue;
+            }
+            LicenseVerdict::Unknown => "is unknown",
+        };
+        violations += 1;
+        eprintln!(
+            "{}{} {} ({}): license '{}' {}",
+            console::style(console::Emoji("✘ ", "")).red(),
+            console::style(&package.name).bold(),
+            package.version,
+            package.kind,
+            license,
+            reason,
+        );
+    }
+
+    if violations > 0 {
+        eprintln!(
+            "{}{} of {} packages violate the license policy",
+            console::style(console::Emoji("✘ ", "")).red(),
+            violations,
+            packages.len(),
+        );
+        return Err(crate::ExitStatus(1).into());
+    }
+
+    eprintln!(
+        "{}All {} packages satisfy the license policy",
+        console::style(console::Emoji("✔ ", "")).green(),
+        packages.len(),
+    );
+    Ok(())
+}
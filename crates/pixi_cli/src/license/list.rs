@@ -0,0 +1,61 @@
+use clap::Parser;
+use comfy_table::{Cell, ContentArrangement, Table, presets::NOTHING};
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use rattler_lock::LockFile;
+
+use super::licensed_packages;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The environments to list the licenses for. Defaults to all
+    /// environments.
+    #[arg(short, long, value_delimiter = ',')]
+    pub environment: Vec<String>,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn execute(lock_file: &LockFile, args: Args) -> miette::Result<()> {
+    let packages = licensed_packages(lock_file, &args.environment);
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&packages).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    let header_style = console::Style::new().bold().cyan();
+    table.set_header(
+        ["Package", "Version", "Kind", "License"]
+            .map(|header| Cell::new(header_style.apply_to(header).to_string())),
+    );
+    for package in &packages {
+        table.add_row([
+            Cell::new(console::style(&package.name).bold().to_string()),
+            Cell::new(&package.version),
+            Cell::new(package.kind),
+            Cell::new(match &package.license {
+                Some(license) => license.clone(),
+                None => console::style("unknown").dim().to_string(),
+            }),
+        ]);
+    }
+    println!(
+        "{}",
+        table
+            .lines()
+            .map(|line| line.trim().to_string())
+            .format("\n")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+pub mod check;
+pub mod list;
+
+use std::collections::BTreeMap;
+
+use clap::Parser;
+use miette::WrapErr;
+use pixi_core::lock_file::UpdateLockFileOptions;
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::Serialize;
+
+use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
+
+/// Commands to inspect the licenses of the locked packages.
+///
+/// The licenses are checked against the `[workspace.license-policy]` section
+/// of the manifest, which contains the SPDX expressions of the allowed and
+/// denied licenses.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub no_install_config: NoInstallConfig,
+
+    /// The subcommand to execute
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Check the licenses of the locked packages against the license policy.
+    ///
+    /// Exits with a non-zero code if a package uses a license that is denied,
+    /// or that is not in the allow list.
+    Check(check::Args),
+    /// List the licenses of the locked packages.
+    #[clap(visible_alias = "ls")]
+    List(list::Args),
+}
+
+/// A locked package with its license.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LicensedPackage {
+    pub name: String,
+    pub version: String,
+    pub kind: &'static str,
+    /// The license of the package. PyPI packages do not record their license
+    /// in the lock file, so it is always unknown for them.
+    pub license: Option<String>,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+
+    let lock_file = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: args.no_install_config.no_install,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await
+        .wrap_err("Failed to update lock file")?
+        .0
+        .into_lock_file();
+
+    match args.command {
+        Command::Check(args) => check::execute(&workspace, &lock_file, args),
+        Command::List(args) => list::execute(&lock_file, args),
+    }
+}
+
+/// Returns the packages of all platforms of the given environments, or of all
+/// environments if none are given. A package that is locked in multiple
+/// environments or for multiple platforms is returned once.
+pub fn licensed_packages(lock_file: &LockFile, environments: &[String]) -> Vec<LicensedPackage> {
+    let mut packages = BTreeMap::new();
+    for (name, environment) in lock_file.environments() {
+        if !environments.is_empty() && !environments.iter().any(|env| env == name) {
+            continue;
+        }
+        for (_, locked_packages) in environment.packages_by_platform() {
+            for package in locked_packages {
+                let licensed = match package {
+                    LockedPackageRef::Conda(conda) => {
+                        let record = conda.record();
+                        LicensedPackage {
+                            name: record.name.as_normalized().to_string(),
+                            version: record.version.to_string(),
+                            kind: "conda",
+                            license: record.license.clone(),
+                        }
+                    }
+                    LockedPackageRef::Pypi(pypi, _) => LicensedPackage {
+                        name: pypi.name.to_string(),
+                        version: pypi.version.to_string(),
+                        kind: "pypi",
+                        license: None,
+                    },
+                };
+                packages.insert(
+                    (
+                        licensed.name.clone(),
+                        licensed.version.clone(),
+                        licensed.kind,
+                    ),
+                    licensed,
+                );
+            }
+        }
+    }
+    packages.into_values().collect()
+}
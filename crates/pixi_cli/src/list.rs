@@ -1,4 +1,12 @@
-use std::{borrow::Cow, collections::HashMap, fmt::Display};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use clap::Parser;
 use comfy_table::{Cell, CellAlignment, ContentArrangement, Table, presets::NOTHING};
@@ -7,14 +15,19 @@ use fancy_display::FancyDisplay;
 use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_consts::consts;
-use pixi_core::{WorkspaceLocator, lock_file::UpdateLockFileOptions};
+use pixi_core::{lock_file::UpdateLockFileOptions, workspace::Environment};
 use pixi_manifest::FeaturesExt;
+use pixi_utils::{porcelain, prefix::Prefix};
 use pixi_uv_context::UvResolutionContext;
 use pixi_uv_conversions::{
     ConversionError, pypi_options_to_index_locations, to_uv_normalize, to_uv_version,
 };
 use pypi_modifiers::pypi_tags::{get_pypi_tags, is_python_record};
-use rattler_conda_types::Platform;
+use rattler_cache::package_cache::CacheKey;
+use rattler_conda_types::{
+    Platform, RepoDataRecord, Version,
+    package::{AboutJson, PackageFile},
+};
 use rattler_lock::{CondaPackageData, LockedPackageRef, PypiPackageData, UrlOrPath};
 use serde::Serialize;
 use uv_distribution::RegistryWheelIndex;
@@ -22,7 +35,11 @@ use uv_distribution_types::{
     ConfigSettings, ExtraBuildRequires, ExtraBuildVariables, PackageConfigSettings,
 };
 
-use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
+use crate::{
+    cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig},
+    shared::tree::build_reverse_dependency_map,
+    tree::generate_dependency_map,
+};
 
 // an enum to sort by size or name
 #[derive(clap::ValueEnum, Clone, Debug, Serialize)]
@@ -32,6 +49,19 @@ pub enum SortBy {
     Kind,
 }
 
+/// The format to output the packages in
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListFormat {
+    /// A table with the selected fields
+    #[default]
+    Table,
+    /// A JSON array of all packages
+    Json,
+    /// One JSON object per package and line, written as soon as the package
+    /// is processed
+    Ndjson,
+}
+
 /// Available fields for the list command output
 #[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Field {
@@ -41,8 +71,13 @@ pub enum Field {
     BuildNumber,
     Constrains,
     Depends,
+    #[clap(name = "dependency-count")]
+    DependencyCount,
+    #[clap(name = "dependent-count")]
+    DependentCount,
     #[clap(name = "file-name")]
     FileName,
+    Homepage,
     #[clap(name = "is-editable")]
     IsEditable,
     Kind,
@@ -58,7 +93,9 @@ pub enum Field {
     Sha256,
     Size,
     Source,
+    Status,
     Subdir,
+    Summary,
     Timestamp,
     #[clap(name = "track-features")]
     TrackFeatures,
@@ -75,7 +112,10 @@ impl Display for Field {
             Field::BuildNumber => write!(f, "build-number"),
             Field::Constrains => write!(f, "constrains"),
             Field::Depends => write!(f, "depends"),
+            Field::DependencyCount => write!(f, "dependency-count"),
+            Field::DependentCount => write!(f, "dependent-count"),
             Field::FileName => write!(f, "file-name"),
+            Field::Homepage => write!(f, "homepage"),
             Field::IsEditable => write!(f, "is-editable"),
             Field::Kind => write!(f, "kind"),
             Field::License => write!(f, "license"),
@@ -88,7 +128,9 @@ impl Display for Field {
             Field::Sha256 => write!(f, "sha256"),
             Field::Size => write!(f, "size"),
             Field::Source => write!(f, "source"),
+            Field::Status => write!(f, "status"),
             Field::Subdir => write!(f, "subdir"),
+            Field::Summary => write!(f, "summary"),
             Field::Timestamp => write!(f, "timestamp"),
             Field::TrackFeatures => write!(f, "track-features"),
             Field::Url => write!(f, "url"),
@@ -106,7 +148,10 @@ impl Field {
             Field::BuildNumber => "Build#",
             Field::Constrains => "Constrains",
             Field::Depends => "Depends",
+            Field::DependencyCount => "Deps",
+            Field::DependentCount => "Dependents",
             Field::FileName => "File Name",
+            Field::Homepage => "Homepage",
             Field::IsEditable => "Editable",
             Field::Kind => "Kind",
             Field::License => "License",
@@ -119,7 +164,9 @@ impl Field {
             Field::Sha256 => "SHA256",
             Field::Size => "Size",
             Field::Source => "Source",
+            Field::Status => "Status",
             Field::Subdir => "Subdir",
+            Field::Summary => "Summary",
             Field::Timestamp => "Timestamp",
             Field::TrackFeatures => "Track Features",
             Field::Url => "URL",
@@ -130,7 +177,11 @@ impl Field {
     /// Get the cell alignment for this field
     fn alignment(&self) -> Option<CellAlignment> {
         match self {
-            Field::Size | Field::BuildNumber | Field::Timestamp => Some(CellAlignment::Right),
+            Field::Size
+            | Field::BuildNumber
+            | Field::Timestamp
+            | Field::DependencyCount
+            | Field::DependentCount => Some(CellAlignment::Right),
             _ => None,
         }
     }
@@ -145,6 +196,117 @@ impl Field {
     }
 }
 
+/// How the value of a field is compared in a [`PackageFilter`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FilterOperator {
+    Matches,
+    Equal,
+    NotEqual,
+    GreaterEqual,
+    LessEqual,
+    Greater,
+    Less,
+}
+
+impl FilterOperator {
+    /// The operators in the order they are parsed, longer operators first.
+    const ALL: [(&'static str, FilterOperator); 8] = [
+        ("~", FilterOperator::Matches),
+        ("!=", FilterOperator::NotEqual),
+        ("==", FilterOperator::Equal),
+        (">=", FilterOperator::GreaterEqual),
+        ("<=", FilterOperator::LessEqual),
+        ("=", FilterOperator::Equal),
+        (">", FilterOperator::Greater),
+        ("<", FilterOperator::Less),
+    ];
+}
+
+/// A filter on a field of a package, e.g. `version>=2` or
+/// `source~conda-forge`
+#[derive(Clone, Debug)]
+pub struct PackageFilter {
+    field: Field,
+    operator: FilterOperator,
+    value: String,
+    regex: Option<regex::Regex>,
+}
+
+impl FromStr for PackageFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(index) = s.find(['~', '=', '!', '<', '>']) else {
+            return Err(format!(
+                "'{s}' is not a filter, expected e.g. 'version>=2' or 'source~conda-forge'"
+            ));
+        };
+        let (name, rest) = s.split_at(index);
+        let field = <Field as clap::ValueEnum>::from_str(name.trim(), true)
+            .map_err(|_| format!("unknown field '{}'", name.trim()))?;
+        let (operator, value) = FilterOperator::ALL
+            .iter()
+            .find_map(|(op, operator)| rest.strip_prefix(op).map(|value| (*operator, value)))
+            .ok_or_else(|| format!("unknown operator in filter '{s}'"))?;
+        let value = value.trim().to_string();
+        let regex = match operator {
+            FilterOperator::Matches => Some(
+                regex::Regex::new(&value).map_err(|e| format!("invalid regex '{value}': {e}"))?,
+            ),
+            _ => None,
+        };
+        Ok(Self {
+            field,
+            operator,
+            value,
+            regex,
+        })
+    }
+}
+
+impl PackageFilter {
+    /// Returns true if the field of the package satisfies this filter.
+    fn matches(&self, package: &PackageToOutput) -> bool {
+        let actual = package.get_field_value(self.field);
+        if let Some(regex) = &self.regex {
+            return regex.is_match(&actual);
+        }
+        let ordering = compare_field_values(self.field, &actual, &self.value);
+        match self.operator {
+            FilterOperator::Matches => unreachable!("a regex filter always has a regex"),
+            FilterOperator::Equal => ordering == Some(Ordering::Equal),
+            FilterOperator::NotEqual => ordering != Some(Ordering::Equal),
+            FilterOperator::GreaterEqual => ordering.is_some_and(Ordering::is_ge),
+            FilterOperator::LessEqual => ordering.is_some_and(Ordering::is_le),
+            FilterOperator::Greater => ordering == Some(Ordering::Greater),
+            FilterOperator::Less => ordering == Some(Ordering::Less),
+        }
+    }
+}
+
+/// Compares two values of a field. Versions and numbers are compared by their
+/// value, everything else as a string. Returns `None` if the values cannot be
+/// compared, e.g. because a package has no value for the field.
+fn compare_field_values(field: Field, a: &str, b: &str) -> Option<Ordering> {
+    match field {
+        Field::Version => {
+            let a = Version::from_str(a).ok()?;
+            let b = Version::from_str(b).ok()?;
+            Some(a.cmp(&b))
+        }
+        Field::BuildNumber
+        | Field::Size
+        | Field::Timestamp
+        | Field::DependencyCount
+        | Field::DependentCount => {
+            let a = a.parse::<i64>().ok()?;
+            let b = b.parse::<i64>().ok()?;
+            Some(a.cmp(&b))
+        }
+        _ => Some(a.cmp(b)),
+    }
+}
+
 /// Default fields to display when --fields is not specified
 pub const DEFAULT_FIELDS: [Field; 6] = [
     Field::Name,
@@ -169,14 +331,26 @@ pub struct Args {
     #[arg(long)]
     pub platform: Option<Platform>,
 
-    /// Whether to output in json format
-    #[arg(long, alias = "json-pretty")]
+    /// Whether to output in json format, same as `--format json`
+    #[arg(long, alias = "json-pretty", conflicts_with = "format")]
     pub json: bool,
 
+    /// The output format. `ndjson` writes every package as soon as it is
+    /// processed, in the order of the lock-file, and ignores `--sort-by`
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ListFormat,
+
     /// Sorting strategy
     #[arg(long, default_value = "name", value_enum, conflicts_with = "json")]
     pub sort_by: SortBy,
 
+    /// Only list packages whose field satisfies a filter, e.g. `version>=2` or
+    /// `source~conda-forge`. `~` matches a regular expression, `=`, `!=`,
+    /// `<`, `<=`, `>` and `>=` compare versions and numbers by value. Can be
+    /// repeated, a package has to satisfy all filters
+    #[arg(long = "filter", value_name = "FILTER")]
+    pub filters: Vec<PackageFilter>,
+
     /// Select which fields to display and in what order (comma-separated).
     #[arg(long, value_delimiter = ',', default_values_t = DEFAULT_FIELDS, conflicts_with = "json")]
     pub fields: Vec<Field>,
@@ -198,13 +372,19 @@ pub struct Args {
     /// Only list packages that are explicitly defined in the workspace.
     #[arg(short = 'x', long)]
     pub explicit: bool,
+
+    /// List the packages that are installed in the environment instead of the
+    /// packages in the lock-file. The lock-file is not updated, packages that
+    /// differ from it are flagged in the `status` field.
+    #[arg(long, conflicts_with_all = ["platform", "no_install"])]
+    pub installed: bool,
 }
 
 fn serde_skip_is_editable(editable: &bool) -> bool {
     !(*editable)
 }
 
-#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "lowercase")]
 enum KindPackage {
     Conda,
@@ -229,6 +409,28 @@ impl FancyDisplay for KindPackage {
     }
 }
 
+/// How an installed package differs from the lock-file.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum InstalledStatus {
+    /// The package is installed but not in the lock-file.
+    NotLocked,
+    /// The package is locked with another version or build.
+    Outdated,
+    /// The package is in the lock-file but not installed.
+    NotInstalled,
+}
+
+impl InstalledStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            InstalledStatus::NotLocked => "not locked",
+            InstalledStatus::Outdated => "outdated",
+            InstalledStatus::NotInstalled => "not installed",
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct PackageToOutput {
     name: String,
@@ -240,6 +442,8 @@ struct PackageToOutput {
     source: Option<String>,
     license: Option<String>,
     license_family: Option<String>,
+    summary: Option<String>,
+    homepage: Option<String>,
     #[serde(skip_serializing_if = "serde_skip_is_editable")]
     is_editable: bool,
     md5: Option<String>,
@@ -255,6 +459,14 @@ struct PackageToOutput {
     constrains: Vec<String>,
     depends: Vec<String>,
     track_features: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependency_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependent_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<InstalledStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locked_version: Option<String>,
 }
 
 impl PackageToOutput {
@@ -263,6 +475,65 @@ impl PackageToOutput {
         self.requested_spec.is_some()
     }
 
+    /// Sets the number of dependencies and dependents of this package in the
+    /// lock-file.
+    fn set_dependency_counts(&mut self, counts: &HashMap<String, (usize, usize)>) {
+        if let Some((dependencies, dependents)) = counts.get(&self.name) {
+            self.dependency_count = Some(*dependencies);
+            self.dependent_count = Some(*dependents);
+        }
+    }
+
+    /// Get the unformatted value of a field, as printed in porcelain mode
+    fn get_field_value(&self, field: Field) -> String {
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        match field {
+            Field::Name => self.name.clone(),
+            Field::Version => self.version.clone(),
+            Field::Build => optional(&self.build),
+            Field::BuildNumber => self.build_number.map(|n| n.to_string()).unwrap_or_default(),
+            Field::Size => self
+                .size_bytes
+                .map(|size| size.to_string())
+                .unwrap_or_default(),
+            Field::Kind => match self.kind {
+                KindPackage::Conda => "conda".to_string(),
+                KindPackage::Pypi => "pypi".to_string(),
+            },
+            Field::Source => optional(&self.source),
+            Field::License => optional(&self.license),
+            Field::LicenseFamily => optional(&self.license_family),
+            Field::IsEditable => self.is_editable.to_string(),
+            Field::Md5 => optional(&self.md5),
+            Field::Sha256 => optional(&self.sha256),
+            Field::Arch => optional(&self.arch),
+            Field::Platform => optional(&self.platform),
+            Field::Subdir => optional(&self.subdir),
+            Field::Summary => optional(&self.summary),
+            Field::Homepage => optional(&self.homepage),
+            Field::Timestamp => self.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+            Field::Noarch => optional(&self.noarch),
+            Field::FileName => optional(&self.file_name),
+            Field::Url => optional(&self.url),
+            Field::RequestedSpec => optional(&self.requested_spec),
+            Field::Constrains => self.constrains.join(","),
+            Field::Depends => self.depends.join(","),
+            Field::DependencyCount => self
+                .dependency_count
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            Field::DependentCount => self
+                .dependent_count
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            Field::TrackFeatures => self.track_features.join(","),
+            Field::Status => self
+                .status
+                .map(|status| status.as_str().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
     /// Get a Cell for a field, with proper styling and alignment
     fn get_field_cell(&self, field: Field) -> Cell {
         let mut cell = match field {
@@ -306,6 +577,8 @@ impl PackageToOutput {
             Field::Arch => Cell::new(self.arch.as_deref().unwrap_or_default()),
             Field::Platform => Cell::new(self.platform.as_deref().unwrap_or_default()),
             Field::Subdir => Cell::new(self.subdir.as_deref().unwrap_or_default()),
+            Field::Summary => Cell::new(self.summary.as_deref().unwrap_or_default()),
+            Field::Homepage => Cell::new(self.homepage.as_deref().unwrap_or_default()),
             Field::Timestamp => {
                 Cell::new(self.timestamp.map(|t| t.to_string()).unwrap_or_default())
             }
@@ -315,7 +588,32 @@ impl PackageToOutput {
             Field::RequestedSpec => Cell::new(self.requested_spec.as_deref().unwrap_or_default()),
             Field::Constrains => Cell::new(self.constrains.join(", ")),
             Field::Depends => Cell::new(self.depends.join(", ")),
+            Field::DependencyCount => Cell::new(
+                self.dependency_count
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            ),
+            Field::DependentCount => Cell::new(
+                self.dependent_count
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            ),
             Field::TrackFeatures => Cell::new(self.track_features.join(", ")),
+            Field::Status => {
+                let content = match (self.status, &self.locked_version) {
+                    (Some(InstalledStatus::Outdated), Some(locked)) => format!(
+                        "{}",
+                        Style::new()
+                            .yellow()
+                            .apply_to(format!("outdated, locked: {locked}"))
+                    ),
+                    (Some(status), _) => {
+                        format!("{}", Style::new().yellow().apply_to(status.as_str()))
+                    }
+                    (None, _) => String::new(),
+                };
+                Cell::new(content)
+            }
         };
 
         if let Some(align) = field.alignment() {
@@ -325,6 +623,27 @@ impl PackageToOutput {
     }
 }
 
+/// Counts the dependencies of every package in the lock-file and the packages
+/// that depend on it. Only packages of the environment are counted, virtual
+/// packages are not.
+fn dependency_counts(locked_deps: &[LockedPackageRef<'_>]) -> HashMap<String, (usize, usize)> {
+    let dep_map = build_reverse_dependency_map(&generate_dependency_map(locked_deps));
+    dep_map
+        .values()
+        .map(|package| {
+            let dependencies = package
+                .dependencies
+                .iter()
+                .filter(|dependency| dep_map.contains_key(*dependency))
+                .count();
+            (
+                package.name.clone(),
+                (dependencies, package.needed_by.len()),
+            )
+        })
+        .collect()
+}
+
 /// Get directory size
 pub(crate) fn get_dir_size<P>(path: P) -> std::io::Result<u64>
 where
@@ -380,21 +699,28 @@ impl PackageExt {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let environment = workspace.environment_from_name_or_env_var(args.environment)?;
 
-    let lock_file = workspace
-        .update_lock_file(UpdateLockFileOptions {
-            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
-            no_install: args.no_install_config.no_install,
-            max_concurrent_solves: workspace.config().max_concurrent_solves(),
-        })
-        .await?
-        .0
-        .into_lock_file();
+    let lock_file = if args.installed {
+        // The installed packages are compared with the lock-file as it is, a
+        // stale or missing lock-file should not prevent inspecting them.
+        workspace
+            .load_lock_file()
+            .await?
+            .into_lock_file_or_empty_with_warning()
+    } else {
+        workspace
+            .update_lock_file(UpdateLockFileOptions {
+                lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+                no_install: args.no_install_config.no_install,
+                max_concurrent_solves: workspace.config().max_concurrent_solves(),
+            })
+            .await?
+            .0
+            .into_lock_file()
+    };
 
     // Load the platform
     let platform = args.platform.unwrap_or_else(|| environment.best_platform());
@@ -405,6 +731,10 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .and_then(|env| env.packages(platform).map(Vec::from_iter))
         .unwrap_or_default();
 
+    // The number of dependencies and dependents of each package in the
+    // lock-file
+    let dependency_counts = dependency_counts(&locked_deps);
+
     let locked_deps_ext = locked_deps
         .into_iter()
         .map(|p| match p {
@@ -433,12 +763,13 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     let mut registry_index = if let Some(python_record) = python_record {
         if environment.has_pypi_dependencies() {
             uv_context = UvResolutionContext::from_config(workspace.config())?;
-            index_locations =
-                pypi_options_to_index_locations(&environment.pypi_options(), workspace.root())
-                    .into_diagnostic()?;
+            let pypi_options = environment.pypi_options();
+            index_locations = pypi_options_to_index_locations(&pypi_options, workspace.root())
+                .into_diagnostic()?;
             tags = get_pypi_tags(
                 platform,
                 &environment.system_requirements(),
+                pypi_options.platform_tag(platform),
                 python_record.record(),
             )?;
             Some(RegistryWheelIndex::new(
@@ -459,49 +790,103 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     };
 
     // Get the explicit project dependencies with their requested specs
-    let mut requested_specs: HashMap<String, String> = environment
-        .combined_dependencies(Some(platform))
-        .iter()
-        .map(|(name, specs)| {
-            let spec_str = specs.iter().map(|s| s.to_string()).join(", ");
-            (name.as_source().to_string(), spec_str)
-        })
-        .collect();
-    requested_specs.extend(
-        environment
-            .pypi_dependencies(Some(platform))
-            .into_iter()
-            .map(|(name, reqs)| {
-                let spec = reqs
-                    .first()
-                    .map(|r| r.to_string())
-                    .unwrap_or_else(|| "*".to_string());
-                (name.as_normalized().as_dist_info_name().into_owned(), spec)
-            }),
-    );
+    let requested_specs = requested_specs(&environment, platform);
 
-    let mut packages_to_output = locked_deps_ext
-        .iter()
-        .map(|p| create_package_to_output(p, &requested_specs, registry_index.as_mut()))
-        .collect::<Result<Vec<PackageToOutput>, _>>()?;
+    // The summary and homepage of conda packages are read from the extracted
+    // packages in the cache.
+    let package_cache_dir = pixi_config::get_cache_dir()?.join(consts::CONDA_PACKAGE_CACHE_DIR);
 
-    // Filter packages by regex if needed
-    if let Some(regex) = args.regex {
-        let regex = regex::Regex::new(&regex).map_err(|_| miette::miette!("Invalid regex"))?;
-        packages_to_output = packages_to_output
-            .into_iter()
-            .filter(|p| regex.is_match(&p.name))
-            .collect::<Vec<_>>();
+    let format = if args.json {
+        ListFormat::Json
+    } else {
+        args.format
+    };
+    let regex = args
+        .regex
+        .map(|regex| regex::Regex::new(&regex))
+        .transpose()
+        .map_err(|_| miette::miette!("Invalid regex"))?;
+    let is_selected = |p: &PackageToOutput| {
+        regex.as_ref().is_none_or(|regex| regex.is_match(&p.name))
+            && args.filters.iter().all(|filter| filter.matches(p))
+            && (!args.explicit || p.is_explicit())
+    };
+    let no_packages_found = || {
+        miette::miette!(
+            "No packages found in '{}' environment for '{}' platform.",
+            environment.name().fancy_display(),
+            consts::ENVIRONMENT_STYLE.apply_to(platform),
+        )
+    };
+
+    // Stream the locked packages, so that the first ones can be processed
+    // before all packages are collected.
+    if format == ListFormat::Ndjson && !args.installed {
+        let mut stdout = std::io::stdout().lock();
+        let mut count = 0;
+        for package in &locked_deps_ext {
+            let mut package = create_package_to_output(
+                package,
+                &requested_specs,
+                registry_index.as_mut(),
+                &package_cache_dir,
+            )?;
+            package.set_dependency_counts(&dependency_counts);
+            if is_selected(&package) {
+                write_ndjson_line(&mut stdout, &package)?;
+                count += 1;
+            }
+        }
+        if count == 0 {
+            return Err(no_packages_found());
+        }
+        return Ok(());
     }
 
-    // Filter packages by explicit if needed
-    if args.explicit {
-        packages_to_output = packages_to_output
-            .into_iter()
-            .filter(|p| p.is_explicit())
-            .collect::<Vec<_>>();
+    let mut packages_to_output = if args.installed {
+        let installed = installed_packages(&environment)?;
+        let mut packages_to_output = installed
+            .iter()
+            .map(|p| create_package_to_output(p, &requested_specs, None, &package_cache_dir))
+            .collect::<Result<Vec<PackageToOutput>, _>>()?;
+        for (package, output) in installed.iter().zip(packages_to_output.iter_mut()) {
+            // Only the size of the files of a PyPI package in the cache is known.
+            if let PackageExt::PyPI(p, _) = package
+                && p.hash.is_none()
+                && !p.editable
+            {
+                output.size_bytes = None;
+            }
+        }
+        flag_lock_file_differences(
+            &mut packages_to_output,
+            &installed,
+            &locked_deps_ext,
+            &requested_specs,
+            &package_cache_dir,
+        )?;
+        packages_to_output
+    } else {
+        locked_deps_ext
+            .iter()
+            .map(|p| {
+                create_package_to_output(
+                    p,
+                    &requested_specs,
+                    registry_index.as_mut(),
+                    &package_cache_dir,
+                )
+            })
+            .collect::<Result<Vec<PackageToOutput>, _>>()?
+    };
+
+    for package in packages_to_output.iter_mut() {
+        package.set_dependency_counts(&dependency_counts);
     }
 
+    // Filter packages by regex, filters and explicit if needed
+    packages_to_output.retain(is_selected);
+
     // Sort according to the sorting strategy
     match args.sort_by {
         SortBy::Size => {
@@ -517,24 +902,192 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     }
 
     if packages_to_output.is_empty() {
-        miette::bail!(
-            "No packages found in '{}' environment for '{}' platform.",
-            environment.name().fancy_display(),
-            consts::ENVIRONMENT_STYLE.apply_to(platform),
-        );
+        return Err(no_packages_found());
     }
 
     // Print as table string or JSON
-    if args.json {
+    if format == ListFormat::Json {
         // print packages as json
         json_packages(&packages_to_output);
+    } else if format == ListFormat::Ndjson {
+        let mut stdout = std::io::stdout().lock();
+        for package in &packages_to_output {
+            write_ndjson_line(&mut stdout, package)?;
+        }
     } else {
-        if !environment.is_default() {
-            eprintln!("Environment: {}", environment.name().fancy_display());
+        // print packages as table, with the differences to the lock-file if the
+        // installed packages are listed
+        let mut fields = args.fields;
+        if args.installed && !fields.contains(&Field::Status) {
+            fields.push(Field::Status);
+        }
+        if porcelain::is_enabled() {
+            print_packages_porcelain(&packages_to_output, &fields)?;
+        } else {
+            if !environment.is_default() {
+                eprintln!("Environment: {}", environment.name().fancy_display());
+            }
+            print_packages_as_table(&packages_to_output, &fields);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the conda and PyPI packages that are installed in the prefix of the
+/// environment.
+fn installed_packages(environment: &Environment<'_>) -> miette::Result<Vec<PackageExt>> {
+    let prefix = Prefix::new(environment.dir());
+    if !prefix.root().join("conda-meta").is_dir() {
+        miette::bail!(
+            help = "run `pixi install` to install it",
+            "the environment '{}' is not installed",
+            environment.name().fancy_display()
+        );
+    }
+
+    let prefix_records = prefix.find_installed_packages()?;
+    let python_info = prefix_records
+        .iter()
+        .find(|r| r.repodata_record.package_record.name.as_normalized() == "python")
+        .and_then(|r| {
+            rattler::install::PythonInfo::from_python_record(
+                &r.repodata_record.package_record,
+                Platform::current(),
+            )
+            .ok()
+        });
+
+    let mut packages = prefix_records
+        .into_iter()
+        .map(|record| PackageExt::Conda(CondaPackageData::from(record.repodata_record)))
+        .collect_vec();
+
+    // PyPI packages that were installed by pixi are recorded in the
+    // site-packages, the `.dist-info` of conda packages is skipped.
+    let site_packages = python_info.map(|info| prefix.root().join(info.site_packages_path));
+    let dist_infos = site_packages
+        .and_then(|dir| fs_err::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dist-info"));
+    for dist_info in dist_infos {
+        let installer = fs_err::read_to_string(dist_info.join("INSTALLER")).unwrap_or_default();
+        if installer.trim() != consts::PIXI_UV_INSTALLER {
+            continue;
         }
+        let Some(package) = installed_pypi_package(&dist_info) else {
+            tracing::warn!(
+                "failed to read the metadata of '{}', it is not listed",
+                dist_info.display()
+            );
+            continue;
+        };
+        let name = to_uv_normalize(&package.name).into_diagnostic()?;
+        packages.push(PackageExt::PyPI(package, name));
+    }
 
-        // print packages as table
-        print_packages_as_table(&packages_to_output, &args.fields);
+    Ok(packages)
+}
+
+/// Reads an installed PyPI package from its `.dist-info` directory.
+fn installed_pypi_package(dist_info: &Path) -> Option<PypiPackageData> {
+    let metadata = fs_err::read_to_string(dist_info.join("METADATA")).ok()?;
+    let header = |key: &str| {
+        metadata
+            .lines()
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.split_once(':'))
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value.trim())
+    };
+    let name = pep508_rs::PackageName::from_str(header("name")?).ok()?;
+    let version = pep440_rs::Version::from_str(header("version")?).ok()?;
+    let requires_dist = metadata
+        .lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(k, _)| k.eq_ignore_ascii_case("requires-dist"))
+        .filter_map(|(_, value)| pep508_rs::Requirement::from_str(value.trim()).ok())
+        .collect();
+
+    // A package that was not installed from a registry records where it was
+    // installed from.
+    let direct_url = fs_err::read_to_string(dist_info.join("direct_url.json"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok());
+    let editable = direct_url
+        .as_ref()
+        .and_then(|direct_url| direct_url.pointer("/dir_info/editable"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or_default();
+    let location = direct_url
+        .as_ref()
+        .and_then(|direct_url| direct_url.get("url"))
+        .and_then(serde_json::Value::as_str)
+        .and_then(|url| UrlOrPath::from_str(url).ok())
+        .or_else(|| UrlOrPath::from_str(&dist_info.to_string_lossy()).ok())?;
+
+    Some(PypiPackageData {
+        name,
+        version,
+        location,
+        hash: None,
+        requires_dist,
+        requires_python: None,
+        editable,
+    })
+}
+
+/// Flags the installed packages that differ from the lock-file, and adds the
+/// locked packages that are not installed.
+fn flag_lock_file_differences(
+    packages_to_output: &mut Vec<PackageToOutput>,
+    installed: &[PackageExt],
+    locked: &[PackageExt],
+    requested_specs: &HashMap<String, String>,
+    package_cache_dir: &Path,
+) -> miette::Result<()> {
+    let locked_by_name: HashMap<_, _> = locked
+        .iter()
+        .map(|p| ((KindPackage::from(p), p.name().into_owned()), p))
+        .collect();
+
+    let mut found = HashSet::new();
+    for (package, output) in installed.iter().zip(packages_to_output.iter_mut()) {
+        let key = (KindPackage::from(package), package.name().into_owned());
+        let Some(locked) = locked_by_name.get(&key) else {
+            output.status = Some(InstalledStatus::NotLocked);
+            continue;
+        };
+        found.insert(key);
+        let same_build = match (package, locked) {
+            (PackageExt::Conda(installed), PackageExt::Conda(locked)) => {
+                installed.record().build == locked.record().build
+            }
+            _ => true,
+        };
+        if package.version() != locked.version() || !same_build {
+            output.status = Some(InstalledStatus::Outdated);
+            output.locked_version = Some(match locked {
+                PackageExt::Conda(locked) => {
+                    format!("{} {}", locked.record().version, locked.record().build)
+                }
+                PackageExt::PyPI(..) => locked.version().into_owned(),
+            });
+        }
+    }
+
+    for (key, locked) in locked_by_name {
+        if found.contains(&key) {
+            continue;
+        }
+        let mut output =
+            create_package_to_output(locked, requested_specs, None, package_cache_dir)?;
+        output.status = Some(InstalledStatus::NotInstalled);
+        packages_to_output.push(output);
     }
 
     Ok(())
@@ -564,12 +1117,40 @@ fn print_packages_as_table(packages: &[PackageToOutput], fields: &[Field]) {
     );
 }
 
+/// Prints a line with the tab separated fields of every package, the values are
+/// unformatted and there is no header.
+fn print_packages_porcelain(packages: &[PackageToOutput], fields: &[Field]) -> miette::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    for package in packages {
+        let line = porcelain::line(fields.iter().map(|f| package.get_field_value(*f)));
+        if let Err(err) = writeln!(stdout, "{line}") {
+            if err.kind() == std::io::ErrorKind::BrokenPipe {
+                return Ok(());
+            }
+            return Err(err).into_diagnostic();
+        }
+    }
+    Ok(())
+}
+
 fn json_packages(packages: &Vec<PackageToOutput>) {
     let json_string =
         serde_json::to_string_pretty(&packages).expect("Cannot serialize packages to JSON");
     println!("{json_string}");
 }
 
+/// Writes the package as a single line of JSON.
+fn write_ndjson_line(writer: &mut impl Write, package: &PackageToOutput) -> miette::Result<()> {
+    let line = serde_json::to_string(package).into_diagnostic()?;
+    writeln!(writer, "{line}")
+        .inspect_err(|e| {
+            if e.kind() == std::io::ErrorKind::BrokenPipe {
+                std::process::exit(0);
+            }
+        })
+        .into_diagnostic()
+}
+
 /// Return the size and source location of the pypi package
 fn get_pypi_location_information(location: &UrlOrPath) -> (Option<u64>, Option<String>) {
     match location {
@@ -581,10 +1162,83 @@ fn get_pypi_location_information(location: &UrlOrPath) -> (Option<u64>, Option<S
     }
 }
 
+/// Returns the summary and homepage of a conda package from the `about.json`
+/// of the package in the package cache, if the package was extracted there.
+fn conda_package_about(
+    package_cache_dir: &Path,
+    package: &CondaPackageData,
+) -> (Option<String>, Option<String>) {
+    // The cache key includes a hash of the origin of the package, so packages
+    // with the same name, version and build from different channels don't
+    // share a directory.
+    let cache_key = match RepoDataRecord::try_from(package.clone()) {
+        Ok(record) => CacheKey::from(&record),
+        Err(_) => CacheKey::from(package.record()),
+    };
+    let package_dir = package_cache_dir.join(cache_key.to_string());
+    match AboutJson::from_package_directory(package_dir) {
+        Ok(about) => (
+            about.summary.map(|summary| summary.trim().to_string()),
+            about.home.first().map(ToString::to_string),
+        ),
+        Err(_) => (None, None),
+    }
+}
+
+/// Returns the summary and homepage of a wheel from the core metadata in its
+/// `.dist-info` directory.
+fn wheel_about(wheel_dir: &Path) -> (Option<String>, Option<String>) {
+    let Some(metadata) = fs_err::read_dir(wheel_dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "dist-info"))
+        .and_then(|dist_info| fs_err::read_to_string(dist_info.join("METADATA")).ok())
+    else {
+        return (None, None);
+    };
+    parse_core_metadata_about(&metadata)
+}
+
+/// Returns the `Summary` and the homepage of the headers of core metadata.
+/// The homepage is taken from `Home-page`, or from a `Project-URL` labeled
+/// as the homepage.
+fn parse_core_metadata_about(metadata: &str) -> (Option<String>, Option<String>) {
+    let mut summary = None;
+    let mut home_page = None;
+    let mut project_url = None;
+    // The headers end at the first empty line, the description follows.
+    for line in metadata.lines().take_while(|line| !line.is_empty()) {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.to_ascii_lowercase().as_str() {
+            "summary" if !value.is_empty() => summary = Some(value.to_string()),
+            "home-page" if !value.is_empty() => home_page = Some(value.to_string()),
+            "project-url" => {
+                if let Some((label, url)) = value.split_once(',')
+                    && label
+                        .trim()
+                        .replace(['-', '_', '.'], "")
+                        .eq_ignore_ascii_case("homepage")
+                {
+                    project_url = Some(url.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    (summary, home_page.or(project_url))
+}
+
 fn create_package_to_output<'a, 'b>(
     package: &'b PackageExt,
     requested_specs: &'a HashMap<String, String>,
     registry_index: Option<&'a mut RegistryWheelIndex<'b>>,
+    package_cache_dir: &Path,
 ) -> miette::Result<PackageToOutput> {
     let name = package.name().to_string();
     let version = package.version().into_owned();
@@ -600,6 +1254,9 @@ fn create_package_to_output<'a, 'b>(
         PackageExt::PyPI(_, _) => None,
     };
 
+    // The directory of the unpacked wheel in the cache of uv
+    let mut wheel_dir: Option<PathBuf> = None;
+
     let (size_bytes, source) = match package {
         PackageExt::Conda(pkg) => (
             pkg.record().size,
@@ -621,6 +1278,7 @@ fn create_package_to_output<'a, 'b>(
                         i.dist.filename.version
                             == to_uv_version(&p.version).expect("invalid version")
                     });
+                    wheel_dir = entry.map(|e| e.dist.path.to_path_buf());
                     let size = entry.and_then(|e| get_dir_size(e.dist.path.clone()).ok());
                     let name = entry.map(|e| e.dist.filename.to_string());
                     (size, name)
@@ -643,6 +1301,11 @@ fn create_package_to_output<'a, 'b>(
         PackageExt::PyPI(_, _) => None,
     };
 
+    let (summary, homepage) = match package {
+        PackageExt::Conda(pkg) => conda_package_about(package_cache_dir, pkg),
+        PackageExt::PyPI(_, _) => wheel_dir.as_deref().map(wheel_about).unwrap_or_default(),
+    };
+
     let md5 = match package {
         PackageExt::Conda(pkg) => pkg.record().md5.map(|h| format!("{h:x}")),
         PackageExt::PyPI(p, _) => p
@@ -739,6 +1402,8 @@ fn create_package_to_output<'a, 'b>(
         source,
         license,
         license_family,
+        summary,
+        homepage,
         is_editable,
         md5,
         sha256,
@@ -753,5 +1418,276 @@ fn create_package_to_output<'a, 'b>(
         constrains,
         depends,
         track_features,
+        dependency_count: None,
+        dependent_count: None,
+        status: None,
+        locked_version: None,
     })
 }
+
+/// Returns the specs of the explicit dependencies of the environment by
+/// package name. A dependency that is defined by multiple features of the
+/// environment is shown with the spec of each of them.
+fn requested_specs(environment: &Environment<'_>, platform: Platform) -> HashMap<String, String> {
+    let mut requested_specs: HashMap<String, String> = environment
+        .combined_dependencies(Some(platform))
+        .iter()
+        .map(|(name, specs)| {
+            let spec_str = specs.iter().map(|s| s.to_string()).unique().join(", ");
+            (name.as_source().to_string(), spec_str)
+        })
+        .collect();
+    requested_specs.extend(
+        environment
+            .pypi_dependencies(Some(platform))
+            .into_iter()
+            .map(|(name, reqs)| {
+                let spec = if reqs.is_empty() {
+                    "*".to_string()
+                } else {
+                    reqs.iter().map(|r| r.to_string()).unique().join(", ")
+                };
+                (name.as_normalized().as_dist_info_name().into_owned(), spec)
+            }),
+    );
+    requested_specs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_package_filter() {
+        let filter = PackageFilter::from_str("version>=2").unwrap();
+        assert_eq!(filter.field, Field::Version);
+        assert_eq!(filter.operator, FilterOperator::GreaterEqual);
+        assert_eq!(filter.value, "2");
+
+        let filter = PackageFilter::from_str("source~conda-forge").unwrap();
+        assert_eq!(filter.field, Field::Source);
+        assert_eq!(filter.operator, FilterOperator::Matches);
+        assert!(
+            filter
+                .regex
+                .unwrap()
+                .is_match("https://conda.anaconda.org/conda-forge/")
+        );
+
+        let filter = PackageFilter::from_str("build-number != 0").unwrap();
+        assert_eq!(filter.field, Field::BuildNumber);
+        assert_eq!(filter.operator, FilterOperator::NotEqual);
+        assert_eq!(filter.value, "0");
+
+        assert!(PackageFilter::from_str("version").is_err());
+        assert!(PackageFilter::from_str("colour=red").is_err());
+        assert!(PackageFilter::from_str("name~(").is_err());
+    }
+
+    #[test]
+    fn test_compare_field_values() {
+        // Versions are compared by value, not as strings
+        assert_eq!(
+            compare_field_values(Field::Version, "10.1", "9.2"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            compare_field_values(Field::Version, "2", "2.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            compare_field_values(Field::Size, "1000", "200"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(compare_field_values(Field::Size, "", "200"), None);
+        assert_eq!(
+            compare_field_values(Field::Name, "numpy", "pandas"),
+            Some(Ordering::Less)
+        );
+    }
+
+    #[test]
+    fn test_requested_specs_of_all_features() {
+        let workspace = pixi_core::Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["linux-64"]
+
+        [dependencies]
+        python = ">=3.10"
+        numpy = "*"
+
+        [pypi-dependencies]
+        requests = ">=2"
+
+        [feature.test.dependencies]
+        python = "<3.13"
+        numpy = "*"
+
+        [feature.test.pypi-dependencies]
+        requests = "<3"
+
+        [environments]
+        test = ["test"]
+        "#,
+        )
+        .unwrap();
+
+        let specs = requested_specs(&workspace.environment("test").unwrap(), Platform::Linux64);
+        assert_eq!(specs["python"], "<3.13, >=3.10");
+        assert_eq!(specs["numpy"], "*");
+        // The spec of every feature is shown, in the order of the features.
+        let requests = &specs["requests"];
+        assert!(requests.find("<3").unwrap() < requests.find(">=2").unwrap());
+
+        let specs = requested_specs(&workspace.default_environment(), Platform::Linux64);
+        assert_eq!(specs["python"], ">=3.10");
+        assert!(!specs["requests"].contains("<3"));
+    }
+
+    fn conda_package(name: &str, version: &str, build: &str) -> PackageExt {
+        PackageExt::Conda(conda_package_data(name, version, build, &[]))
+    }
+
+    fn conda_package_data(
+        name: &str,
+        version: &str,
+        build: &str,
+        depends: &[&str],
+    ) -> CondaPackageData {
+        let mut record = RepoDataRecord {
+            package_record: rattler_conda_types::PackageRecord::new(
+                rattler_conda_types::PackageName::new_unchecked(name),
+                Version::from_str(version).unwrap(),
+                build.to_string(),
+            ),
+            file_name: format!("{name}-{version}-{build}.conda"),
+            url: url::Url::parse(&format!(
+                "https://conda.anaconda.org/conda-forge/linux-64/{name}-{version}-{build}.conda"
+            ))
+            .unwrap(),
+            channel: Some(String::from("https://conda.anaconda.org/conda-forge/")),
+        };
+        record.package_record.depends = depends.iter().map(ToString::to_string).collect();
+        record.into()
+    }
+
+    #[test]
+    fn test_dependency_counts() {
+        let packages = [
+            conda_package_data(
+                "numpy",
+                "2.0.0",
+                "py312_0",
+                &["python >=3.12", "libzlib", "__glibc >=2.17"],
+            ),
+            conda_package_data("python", "3.12.1", "h0_0", &["libzlib", "openssl"]),
+            conda_package_data("libzlib", "1.3.1", "h0_0", &[]),
+        ];
+        let locked_deps = packages.iter().map(LockedPackageRef::Conda).collect_vec();
+
+        // Virtual packages and packages that are not locked are not counted.
+        let counts = dependency_counts(&locked_deps);
+        assert_eq!(counts["numpy"], (2, 0));
+        assert_eq!(counts["python"], (1, 1));
+        assert_eq!(counts["libzlib"], (0, 2));
+    }
+
+    #[test]
+    fn test_flag_lock_file_differences() {
+        let installed = vec![
+            conda_package("python", "3.12.1", "h0_0"),
+            conda_package("numpy", "2.0.0", "py312_0"),
+            conda_package("pip", "24.0", "pyh0_0"),
+        ];
+        let locked = vec![
+            conda_package("python", "3.12.1", "h0_1"),
+            conda_package("numpy", "2.0.0", "py312_0"),
+            conda_package("requests", "2.32.3", "pyh0_0"),
+        ];
+        let cache_dir = tempfile::tempdir().unwrap();
+        let requested_specs = HashMap::new();
+        let mut packages_to_output = installed
+            .iter()
+            .map(|p| create_package_to_output(p, &requested_specs, None, cache_dir.path()))
+            .collect::<miette::Result<Vec<_>>>()
+            .unwrap();
+
+        flag_lock_file_differences(
+            &mut packages_to_output,
+            &installed,
+            &locked,
+            &requested_specs,
+            cache_dir.path(),
+        )
+        .unwrap();
+
+        let statuses = packages_to_output
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.status.map(|status| status.as_str()),
+                    p.locked_version.as_deref(),
+                )
+            })
+            .collect_vec();
+        assert_eq!(
+            statuses,
+            vec![
+                ("python", Some("outdated"), Some("3.12.1 h0_1")),
+                ("numpy", None, None),
+                ("pip", Some("not locked"), None),
+                ("requests", Some("not installed"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_ndjson_line() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let requested_specs = HashMap::from([(String::from("numpy"), String::from(">=2"))]);
+        let mut output = Vec::new();
+        for package in [
+            conda_package("python", "3.12.1", "h0_0"),
+            conda_package("numpy", "2.0.0", "py312_0"),
+        ] {
+            let package =
+                create_package_to_output(&package, &requested_specs, None, cache_dir.path())
+                    .unwrap();
+            write_ndjson_line(&mut output, &package).unwrap();
+        }
+
+        // Every package is written as a single JSON object on its own line.
+        let lines = String::from_utf8(output).unwrap();
+        let packages = lines
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect_vec();
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0]["name"], "python");
+        assert_eq!(packages[1]["name"], "numpy");
+        assert_eq!(packages[1]["version"], "2.0.0");
+        assert_eq!(packages[1]["requested_spec"], ">=2");
+
+        // `--json` is a shorthand for `--format json`, they can't be combined.
+        assert!(Args::try_parse_from(["list", "--json", "--format", "ndjson"]).is_err());
+        let args = Args::try_parse_from(["list", "--format", "ndjson"]).unwrap();
+        assert_eq!(args.format, ListFormat::Ndjson);
+    }
+
+    #[test]
+    fn test_parse_core_metadata_about() {
+        let metadata = "Metadata-Version: 2.1\nName: requests\nVersion: 2.32.3\nSummary: Python HTTP for Humans.\nProject-URL: Documentation, https://requests.readthedocs.io\nProject-URL: Homepage, https://requests.readthedocs.io/en/latest\n\nHome-page: https://example.com/description\n";
+        assert_eq!(
+            parse_core_metadata_about(metadata),
+            (
+                Some(String::from("Python HTTP for Humans.")),
+                Some(String::from("https://requests.readthedocs.io/en/latest"))
+            )
+        );
+    }
+}
@@ -1,13 +1,19 @@
 use clap::Parser;
+use itertools::Itertools;
 use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
 use pixi_core::{
-    WorkspaceLocator,
+    Workspace,
     environment::LockFileUsage,
-    lock_file::{LockFileDerivedData, UpdateLockFileOptions},
+    lock_file::{LockFileDerivedData, LockFileMetadata, UpdateLockFileOptions, check_lock_file},
 };
 use pixi_diff::{LockFileDiff, LockFileJsonDiff};
 
+use crate::cli_config::ChannelPriorityConfig;
+use crate::cli_config::ExcludeNewerConfig;
 use crate::cli_config::NoInstallConfig;
+use crate::cli_config::PypiBuildConfig;
+use crate::cli_config::SolveStrategyConfig;
 use crate::cli_config::WorkspaceConfig;
 
 /// Solve environment and update the lock file without installing the
@@ -18,23 +24,69 @@ pub struct Args {
     #[clap(flatten)]
     pub workspace_config: WorkspaceConfig,
 
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[clap(flatten)]
     pub no_install_config: NoInstallConfig,
 
+    #[clap(flatten)]
+    pub solve_strategy_config: SolveStrategyConfig,
+
+    #[clap(flatten)]
+    pub channel_priority_config: ChannelPriorityConfig,
+
+    #[clap(flatten)]
+    pub exclude_newer_config: ExcludeNewerConfig,
+
+    #[clap(flatten)]
+    pub pypi_build_config: PypiBuildConfig,
+
     /// Output the changes in JSON format.
     #[clap(long)]
     pub json: bool,
 
-    /// Check if any changes have been made to the lock file.
-    /// If yes, exit with a non-zero code.
+    /// Check whether the lock file is up-to-date with the manifest without
+    /// updating it. If it is not, exit with a non-zero code.
+    ///
+    /// Combine with `--json` to get a report of the out of date environments
+    /// and platforms.
     #[clap(long)]
     pub check: bool,
 }
 
+#[derive(Debug, Parser)]
+pub enum Command {
+    /// Show the metadata that was recorded when the lock file was written.
+    ///
+    /// The metadata contains the pixi version, the channels with the state of
+    /// their repodata and the virtual package overrides that were used to
+    /// solve the lock file. It is stored in `pixi.lock.meta.json`.
+    #[clap(name = "show-meta")]
+    ShowMeta(ShowMetaArgs),
+}
+
+#[derive(Debug, Parser)]
+pub struct ShowMetaArgs {
+    /// Output the metadata in JSON format
+    #[arg(long)]
+    pub json: bool,
+}
+
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let workspace = args.solve_strategy_config.apply(workspace);
+    let workspace = args.channel_priority_config.apply(workspace);
+    let workspace = args.exclude_newer_config.apply(workspace);
+    let workspace = args.pypi_build_config.apply(workspace);
+
+    if let Some(Command::ShowMeta(show_meta_args)) = args.command {
+        return show_meta(&workspace, show_meta_args.json);
+    }
+
+    if args.check {
+        return check(&workspace, args.json).await;
+    }
 
     // Update the lock-file, and extract it from the derived data to drop additional resources
     // created for the solve.
@@ -72,10 +124,103 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         );
     }
 
-    // Return with a non-zero exit code if `--check` has been passed and the lock
-    // file has been updated
-    if args.check && !diff.is_empty() {
-        std::process::exit(1);
+    Ok(())
+}
+
+/// Verifies that the lock-file satisfies the workspace without solving or
+/// writing anything. Exits with a non-zero code if it does not.
+async fn check(workspace: &Workspace, json: bool) -> miette::Result<()> {
+    let lock_file = workspace.load_lock_file().await?.into_lock_file_or_empty();
+    let result = check_lock_file(workspace, &lock_file).await;
+
+    if json {
+        let json = serde_json::to_string_pretty(&result).into_diagnostic()?;
+        println!("{json}");
+    } else if result.is_up_to_date() {
+        eprintln!(
+            "{}Lock-file is up-to-date",
+            console::style(console::Emoji("✔ ", "")).green()
+        );
+    } else {
+        eprintln!(
+            "{}Lock-file is not up-to-date with the manifest",
+            console::style(console::Emoji("✘ ", "")).red()
+        );
+        for environment in &result.outdated {
+            eprintln!(
+                "  - {}: {}",
+                consts::ENVIRONMENT_STYLE.apply_to(&environment.environment),
+                environment.platforms.iter().format(", ")
+            );
+            for reason in &environment.reasons {
+                match reason.platform {
+                    Some(platform) => eprintln!("      {platform}: {}", reason.reason),
+                    None => eprintln!("      {}", reason.reason),
+                }
+            }
+        }
+        eprintln!("Run `pixi lock` to update the lock-file.");
+    }
+
+    if !result.is_up_to_date() {
+        return Err(crate::ExitStatus(1).into());
+    }
+
+    Ok(())
+}
+
+/// Prints the metadata that was recorded when the lock-file was written.
+fn show_meta(workspace: &Workspace, json: bool) -> miette::Result<()> {
+    let metadata = LockFileMetadata::from_path(&workspace.lock_file_metadata_path())?;
+
+    if json {
+        let json = serde_json::to_string_pretty(&metadata).into_diagnostic()?;
+        println!("{json}");
+        return Ok(());
+    }
+
+    let bold = console::Style::new().bold();
+    println!(
+        "{}: {}",
+        bold.apply_to("Pixi version"),
+        metadata.pixi_version
+    );
+
+    println!("{}", bold.apply_to("Channels"));
+    for channel in &metadata.channels {
+        println!("  {}", channel.url);
+        for subdir in &channel.subdirs {
+            let details = [
+                subdir
+                    .newest_package
+                    .map(|timestamp| format!("newest package {}", timestamp.to_rfc3339())),
+                subdir.etag.as_ref().map(|etag| format!("etag {etag}")),
+                subdir
+                    .last_modified
+                    .as_ref()
+                    .map(|last_modified| format!("last modified {last_modified}")),
+            ];
+            println!(
+                "    {}: {}",
+                consts::PLATFORM_STYLE.apply_to(&subdir.subdir),
+                details.into_iter().flatten().format(", ")
+            );
+        }
+    }
+
+    if !metadata.virtual_package_overrides.is_empty() || !metadata.environment_overrides.is_empty()
+    {
+        println!("{}", bold.apply_to("Virtual package overrides"));
+        for (package, version) in &metadata.virtual_package_overrides {
+            let name = serde_json::to_value(package)
+                .ok()
+                .and_then(|name| name.as_str().map(str::to_string))
+                .unwrap_or_default();
+            println!("  {name}={version}");
+        }
+        for (key, value) in &metadata.environment_overrides {
+            println!("  {key}={value}");
+        }
     }
 
     Ok(())
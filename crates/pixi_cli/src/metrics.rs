@@ -0,0 +1,43 @@
+use std::{path::Path, time::Duration};
+
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use pixi_utils::{
+    metrics::{self, Metrics},
+    timings::{self, PhaseTiming},
+};
+use serde::Serialize;
+
+/// The report written by `--metrics-file`.
+#[derive(Serialize)]
+struct Report {
+    pixi_version: &'static str,
+    /// The arguments of the command, without the executable.
+    args: Vec<String>,
+    /// Whether the command succeeded.
+    success: bool,
+    /// The exit code of pixi, e.g. the exit code of a failing task.
+    exit_code: i32,
+    /// The total duration of the command in seconds.
+    total: f64,
+    phases: Vec<PhaseTiming>,
+    #[serde(flatten)]
+    metrics: Metrics,
+}
+
+/// Writes the recorded timings and metrics of the command as JSON to `path`.
+pub(crate) fn write_report(path: &Path, exit_code: i32, total: Duration) -> miette::Result<()> {
+    let report = Report {
+        pixi_version: consts::PIXI_VERSION,
+        args: std::env::args().skip(1).collect(),
+        success: exit_code == 0,
+        exit_code,
+        total: total.as_secs_f64(),
+        phases: timings::summary(),
+        metrics: metrics::summary(),
+    };
+    let json = serde_json::to_string_pretty(&report).into_diagnostic()?;
+    fs_err::write(path, format!("{json}\n"))
+        .into_diagnostic()
+        .wrap_err("failed to write the metrics file")
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use clap::Parser;
+use comfy_table::{Cell, ContentArrangement, Table, presets::NOTHING};
+use fancy_display::FancyDisplay;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_core::{lock_file::UpdateLockFileOptions, repodata::Repodata};
+use pixi_manifest::FeaturesExt;
+use pixi_progress::await_in_progress;
+use rattler_conda_types::{MatchSpec, PackageName, Platform, Version, match_spec::Matches};
+use serde::Serialize;
+
+use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
+
+/// The impact of upgrading a package, based on the first version segment that
+/// changes.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Impact {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Impact {
+    /// Returns the impact of upgrading from `current` to `newer`.
+    fn between(current: &Version, newer: &Version) -> Self {
+        let prefix = |version: &Version, segments: usize| version.with_segments(..segments);
+        if prefix(current, 1) != prefix(newer, 1) {
+            Impact::Major
+        } else if prefix(current, 2) != prefix(newer, 2) {
+            Impact::Minor
+        } else {
+            Impact::Patch
+        }
+    }
+
+    fn styled(&self) -> console::StyledObject<&'static str> {
+        match self {
+            Impact::Patch => console::style("patch").green(),
+            Impact::Minor => console::style("minor").yellow(),
+            Impact::Major => console::style("major").red(),
+        }
+    }
+}
+
+/// Show the dependencies of an environment for which newer versions are
+/// available.
+///
+/// Only the conda dependencies that are specified in the manifest are checked.
+/// For each of them the newest version that satisfies the spec in the manifest
+/// is shown next to the newest version in the channels. Upgrades are grouped
+/// by their impact: a change of the major, minor or patch version.
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The environment to check. Defaults to the default environment.
+    #[arg(short, long)]
+    pub environment: Option<String>,
+
+    /// The platform to check. Defaults to the current platform.
+    #[arg(long, short)]
+    pub platform: Option<Platform>,
+
+    /// Exit with a non-zero code if an upgrade with at least this impact is
+    /// available
+    #[arg(long, value_enum)]
+    pub fail_on: Option<Impact>,
+
+    /// Output in JSON format
+    #[arg(long)]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub no_install_config: NoInstallConfig,
+}
+
+/// A dependency for which a newer version is available.
+#[derive(Debug, Serialize)]
+struct OutdatedPackage {
+    name: String,
+    spec: String,
+    locked: String,
+    /// The newest version that satisfies the spec in the manifest.
+    compatible: Option<String>,
+    latest: String,
+    impact: Impact,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+    let platform = args.platform.unwrap_or_else(|| environment.best_platform());
+
+    let lock_file = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: args.no_install_config.no_install,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await?
+        .0
+        .into_lock_file();
+
+    // The locked versions of the conda packages.
+    let locked = lock_file
+        .environment(environment.name().as_str())
+        .and_then(|env| env.conda_packages(platform).map(Vec::from_iter))
+        .into_iter()
+        .flatten()
+        .map(|package| {
+            let record = package.record();
+            (record.name.clone(), record.version.version().clone())
+        })
+        .collect::<HashMap<PackageName, Version>>();
+
+    // The specs of the conda dependencies in the manifest, binary
+    // dependencies only, source dependencies are not taken from a channel.
+    let channel_config = workspace.channel_config();
+    let mut specs = Vec::new();
+    for (name, pixi_specs) in environment.combined_dependencies(Some(platform)).iter() {
+        if !locked.contains_key(name) {
+            continue;
+        }
+        let mut match_specs = Vec::new();
+        for spec in pixi_specs.iter().cloned() {
+            if let Some(nameless) = spec
+                .try_into_nameless_match_spec(&channel_config)
+                .into_diagnostic()?
+            {
+                match_specs.push(MatchSpec::from_nameless(
+                    nameless,
+                    Some(name.clone().into()),
+                ));
+            }
+        }
+        if !match_specs.is_empty() {
+            specs.push((name.clone(), match_specs));
+        }
+    }
+
+    let channels = environment
+        .channels()
+        .into_iter()
+        .cloned()
+        .map(|channel| channel.into_channel(&channel_config))
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()?;
+    let gateway = workspace.repodata_gateway()?;
+    let repodata = await_in_progress("fetching repodata", |_| async {
+        gateway
+            .query(
+                channels,
+                [platform, Platform::NoArch],
+                specs.iter().map(|(name, _)| MatchSpec::from(name.clone())),
+            )
+            .await
+            .into_diagnostic()
+    })
+    .await
+    .context("failed to get repodata")?;
+    let records = repodata
+        .iter()
+        .flat_map(|repodata| repodata.iter())
+        .collect_vec();
+
+    let mut outdated = Vec::new();
+    for (name, match_specs) in specs {
+        let current = &locked[&name];
+        let candidates = records
+            .iter()
+            .copied()
+            .filter(|record| record.package_record.name == name)
+            // Development versions are not offered as an upgrade.
+            .filter(|record| !record.package_record.version.version().is_dev())
+            .collect_vec();
+        let Some(latest) = candidates
+            .iter()
+            .map(|record| record.package_record.version.version())
+            .max()
+        else {
+            continue;
+        };
+        if latest <= current {
+            continue;
+        }
+        let compatible = candidates
+            .iter()
+            .filter(|record| match_specs.iter().all(|spec| spec.matches(**record)))
+            .map(|record| record.package_record.version.version())
+            .max()
+            .filter(|compatible| *compatible > current);
+        outdated.push(OutdatedPackage {
+            name: name.as_source().to_string(),
+            spec: match_specs
+                .iter()
+                .map(|spec| spec.to_string())
+                .unique()
+                .join(", "),
+            locked: current.to_string(),
+            compatible: compatible.map(ToString::to_string),
+            latest: latest.to_string(),
+            impact: Impact::between(current, latest),
+        });
+    }
+    outdated.sort_by(|a, b| b.impact.cmp(&a.impact).then_with(|| a.name.cmp(&b.name)));
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&outdated).into_diagnostic()?
+        );
+    } else if outdated.is_empty() {
+        eprintln!(
+            "{}The dependencies of '{}' are up-to-date",
+            console::style(console::Emoji("✔ ", "")).green(),
+            environment.name().fancy_display()
+        );
+    } else {
+        print_outdated(&outdated);
+    }
+
+    if let Some(fail_on) = args.fail_on
+        && outdated.iter().any(|package| package.impact >= fail_on)
+    {
+        return Err(crate::ExitStatus(1).into());
+    }
+
+    Ok(())
+}
+
+fn print_outdated(outdated: &[OutdatedPackage]) {
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    let header_style = console::Style::new().bold().cyan();
+    table.set_header(
+        ["Name", "Spec", "Locked", "Compatible", "Latest", "Impact"]
+            .map(|header| Cell::new(header_style.apply_to(header).to_string())),
+    );
+    for package in outdated {
+        table.add_row([
+            Cell::new(console::style(&package.name).bold().to_string()),
+            Cell::new(&package.spec),
+            Cell::new(&package.locked),
+            Cell::new(package.compatible.as_deref().unwrap_or_default()),
+            Cell::new(&package.latest),
+            Cell::new(package.impact.styled().to_string()),
+        ]);
+    }
+    println!(
+        "{}",
+        table
+            .lines()
+            .map(|line| line.trim().to_string())
+            .format("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_impact_between() {
+        let impact = |current: &str, newer: &str| {
+            Impact::between(
+                &Version::from_str(current).unwrap(),
+                &Version::from_str(newer).unwrap(),
+            )
+        };
+        assert_eq!(impact("1.26.4", "2.0.0"), Impact::Major);
+        assert_eq!(impact("1.26.4", "1.27.0"), Impact::Minor);
+        assert_eq!(impact("1.26.4", "1.26.5"), Impact::Patch);
+        assert_eq!(impact("1.26", "1.26.1"), Impact::Patch);
+        assert_eq!(impact("2024.1.1", "2025.1.1"), Impact::Major);
+    }
+}
@@ -0,0 +1,419 @@
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use futures::{StreamExt, TryStreamExt};
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_core::UpdateLockFileOptions;
+use pixi_manifest::FeaturesExt;
+use pixi_progress::await_in_progress;
+use pixi_record::PixiRecord;
+use rattler_conda_types::{Platform, RepoDataRecord};
+use rattler_digest::{Md5, Sha256, compute_file_digest};
+use rattler_lock::{LockedPackageRef, PackageHashes};
+use rattler_networking::LazyClient;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::cli_config::{LockFileUpdateConfig, WorkspaceConfig};
+
+/// The file in the archive that describes the packed environment.
+pub(crate) const METADATA_FILE_NAME: &str = "pixi-pack.json";
+
+/// The directory in the archive that contains the conda packages, laid out as
+/// a local channel.
+pub(crate) const CHANNEL_DIR: &str = "channel";
+
+/// The directory in the archive that contains the PyPI wheels.
+pub(crate) const PYPI_DIR: &str = "pypi";
+
+/// The line that separates the installer script from the archive in a
+/// self-extracting archive.
+pub(crate) const ARCHIVE_MARKER: &str = "__PIXI_PACK_ARCHIVE__";
+
+/// The version of the archive layout.
+const METADATA_VERSION: u32 = 1;
+
+/// Pack an environment into an archive that can be unpacked without network
+/// access.
+///
+/// The archive contains all the conda packages and PyPI wheels of the locked
+/// environment. Use `pixi unpack` to recreate the environment from it.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The environment to pack
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// The platform to pack the environment for, defaults to the current
+    /// platform
+    #[arg(long, short)]
+    pub platform: Option<Platform>,
+
+    /// The path of the archive, defaults to `environment.tar`, or to
+    /// `environment.sh` with `--create-executable`
+    #[arg(long, short)]
+    pub output_file: Option<PathBuf>,
+
+    /// Create a self-extracting shell script that unpacks the environment with
+    /// `pixi unpack`
+    #[arg(long)]
+    pub create_executable: bool,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+}
+
+/// The description of a packed environment.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PackMetadata {
+    pub version: u32,
+    pub environment: String,
+    pub platform: Platform,
+    pub conda_packages: Vec<RepoDataRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pypi_packages: Vec<PackedPypiPackage>,
+}
+
+/// A PyPI wheel in a packed environment.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct PackedPypiPackage {
+    pub name: String,
+    pub version: String,
+    pub file_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<PackageHashes>,
+}
+
+/// Returns the installer script that starts a self-extracting archive.
+fn installer_script() -> String {
+    format!(
+        r#"#!/bin/sh
+# Self-extracting environment created by `pixi pack`.
+# Usage: sh <this file> [OPTIONS OF PIXI UNPACK]
+set -e
+TEMPDIR="$(mktemp -d)"
+trap 'rm -rf "$TEMPDIR"' EXIT
+ARCHIVE_START=$(awk '/^{ARCHIVE_MARKER}$/ {{ print NR + 1; exit 0; }}' "$0")
+tail -n +"$ARCHIVE_START" "$0" | tar -x -C "$TEMPDIR"
+"${{PIXI:-pixi}}" unpack "$TEMPDIR" "$@"
+exit 0
+{ARCHIVE_MARKER}
+"#
+    )
+}
+
+/// Returns the locked hashes of a conda package.
+pub(crate) fn record_hashes(record: &RepoDataRecord) -> Option<PackageHashes> {
+    PackageHashes::from_hashes(record.package_record.md5, record.package_record.sha256)
+}
+
+/// Verifies that the file matches its locked hash, the sha256 hash is
+/// preferred over the md5 hash. Files without a locked hash are not verified.
+pub(crate) fn verify_hash(path: &Path, hash: Option<&PackageHashes>) -> miette::Result<()> {
+    let (expected, actual) = match hash {
+        Some(hash) if hash.sha256().is_some() => (
+            hash.sha256().map(|sha256| format!("{sha256:x}")),
+            format!(
+                "{:x}",
+                compute_file_digest::<Sha256>(path).into_diagnostic()?
+            ),
+        ),
+        Some(hash) => (
+            hash.md5().map(|md5| format!("{md5:x}")),
+            format!("{:x}", compute_file_digest::<Md5>(path).into_diagnostic()?),
+        ),
+        None => return Ok(()),
+    };
+    if expected.as_deref() != Some(actual.as_str()) {
+        miette::bail!(
+            "the hash of '{}' does not match the lock file, expected {}, got {actual}",
+            path.display(),
+            expected.unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+/// Downloads a file, or copies it if the URL points to a local file, and
+/// verifies it against its locked hash.
+pub(crate) async fn download(
+    client: &LazyClient,
+    url: &Url,
+    destination: &Path,
+    hash: Option<&PackageHashes>,
+) -> miette::Result<()> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|_| miette::miette!("'{url}' is not a valid file path"))?;
+        fs_err::tokio::copy(&path, destination)
+            .await
+            .into_diagnostic()?;
+        return verify_hash(destination, hash);
+    }
+
+    let mut response = client
+        .client()
+        .get(url.clone())
+        .send()
+        .await
+        .into_diagnostic()?
+        .error_for_status()
+        .into_diagnostic()?;
+    let mut file = fs_err::File::create(destination).into_diagnostic()?;
+    while let Some(chunk) = response.chunk().await.into_diagnostic()? {
+        file.write_all(&chunk).into_diagnostic()?;
+    }
+    drop(file);
+    verify_hash(destination, hash)
+}
+
+/// Writes a `repodata.json` for every subdirectory of the local channel so
+/// that the packed packages also form a valid conda channel.
+pub(crate) fn write_repodata(channel_dir: &Path, records: &[RepoDataRecord]) -> miette::Result<()> {
+    let mut subdirs: BTreeMap<&str, serde_json::Map<String, serde_json::Value>> = BTreeMap::new();
+    for record in records {
+        let key = if record.file_name.ends_with(".conda") {
+            "packages.conda"
+        } else {
+            "packages"
+        };
+        let repodata = subdirs
+            .entry(record.package_record.subdir.as_str())
+            .or_default();
+        let packages = repodata
+            .entry(key)
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        if let serde_json::Value::Object(packages) = packages {
+            packages.insert(
+                record.file_name.clone(),
+                serde_json::to_value(&record.package_record).into_diagnostic()?,
+            );
+        }
+    }
+
+    // Every channel needs a noarch subdirectory.
+    subdirs.entry(Platform::NoArch.as_str()).or_default();
+    for (subdir, mut repodata) in subdirs {
+        repodata.insert(
+            String::from("info"),
+            serde_json::json!({ "subdir": subdir }),
+        );
+        let dir = channel_dir.join(subdir);
+        fs_err::create_dir_all(&dir).into_diagnostic()?;
+        fs_err::write(
+            dir.join("repodata.json"),
+            serde_json::to_string_pretty(&repodata).into_diagnostic()?,
+        )
+        .into_diagnostic()?;
+    }
+    Ok(())
+}
+
+/// Writes the contents of the directory as a tar archive, preceded by the
+/// installer script for a self-extracting archive.
+pub(crate) fn write_archive(
+    source: &Path,
+    output_file: &Path,
+    executable: bool,
+) -> miette::Result<()> {
+    let mut file = fs_err::File::create(output_file).into_diagnostic()?;
+    if executable {
+        file.write_all(installer_script().as_bytes())
+            .into_diagnostic()?;
+    }
+    let mut builder = tar::Builder::new(file);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", source).into_diagnostic()?;
+    builder
+        .into_inner()
+        .into_diagnostic()?
+        .flush()
+        .into_diagnostic()?;
+
+    #[cfg(unix)]
+    if executable {
+        use std::os::unix::fs::PermissionsExt;
+        fs_err::set_permissions(output_file, std::fs::Permissions::from_mode(0o755))
+            .into_diagnostic()?;
+    }
+    Ok(())
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let environment = workspace
+        .environment_from_name_or_env_var(args.environment)
+        .wrap_err("Environment not found")?;
+    let platform = args.platform.unwrap_or_else(|| environment.best_platform());
+    if !environment.platforms().contains(&platform) {
+        miette::bail!(
+            "the environment '{}' does not support the platform '{}'",
+            environment.name().fancy_display(),
+            platform
+        );
+    }
+
+    let lock_file = workspace
+        .update_lock_file(UpdateLockFileOptions {
+            lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            no_install: true,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        })
+        .await
+        .wrap_err("Failed to update lock file")?
+        .0
+        .into_lock_file();
+    let packages = lock_file
+        .environment(environment.name().as_str())
+        .and_then(|env| env.packages(platform).map(Vec::from_iter))
+        .unwrap_or_default();
+
+    let mut conda_packages = Vec::new();
+    let mut pypi_packages = Vec::new();
+    for package in packages {
+        match package {
+            LockedPackageRef::Conda(data) => {
+                match PixiRecord::from_conda_package_data(data.clone(), workspace.root())
+                    .into_diagnostic()?
+                {
+                    PixiRecord::Binary(record) => conda_packages.push(record),
+                    PixiRecord::Source(record) => miette::bail!(
+                        help = "build the package with `pixi build` and depend on the built package instead",
+                        "the source package '{}' cannot be packed",
+                        record.package_record.name.as_source()
+                    ),
+                }
+            }
+            LockedPackageRef::Pypi(data, _) => {
+                let url = data
+                    .location
+                    .as_url()
+                    .filter(|url| url.path().ends_with(".whl"));
+                let Some(url) = url else {
+                    miette::bail!(
+                        "the PyPI package '{}' is not a wheel, only wheels can be packed",
+                        data.name
+                    );
+                };
+                let file_name = url
+                    .path_segments()
+                    .and_then(|mut segments| segments.next_back())
+                    .unwrap_or_default()
+                    .to_string();
+                pypi_packages.push((
+                    url.clone(),
+                    PackedPypiPackage {
+                        name: data.name.to_string(),
+                        version: data.version.to_string(),
+                        file_name,
+                        hash: data.hash.clone(),
+                    },
+                ));
+            }
+        }
+    }
+
+    let staging = tempfile::tempdir().into_diagnostic()?;
+    let channel_dir = staging.path().join(CHANNEL_DIR);
+    let pypi_dir = staging.path().join(PYPI_DIR);
+    write_repodata(&channel_dir, &conda_packages)?;
+    if !pypi_packages.is_empty() {
+        fs_err::create_dir_all(&pypi_dir).into_diagnostic()?;
+    }
+
+    let client = workspace.authenticated_client()?.clone();
+    let downloads = conda_packages
+        .iter()
+        .map(|record| {
+            (
+                record.url.clone(),
+                channel_dir
+                    .join(&record.package_record.subdir)
+                    .join(&record.file_name),
+                record_hashes(record),
+            )
+        })
+        .chain(pypi_packages.iter().map(|(url, package)| {
+            (
+                url.clone(),
+                pypi_dir.join(&package.file_name),
+                package.hash.clone(),
+            )
+        }))
+        .collect::<Vec<_>>();
+    let download_count = downloads.len();
+    await_in_progress(format!("downloading {download_count} packages"), |_| {
+        futures::stream::iter(downloads)
+            .map(|(url, destination, hash)| {
+                let client = client.clone();
+                async move {
+                    download(&client, &url, &destination, hash.as_ref())
+                        .await
+                        .with_context(|| format!("failed to download '{url}'"))
+                }
+            })
+            .buffer_unordered(workspace.config().max_concurrent_downloads())
+            .try_collect::<Vec<_>>()
+    })
+    .await?;
+
+    let metadata = PackMetadata {
+        version: METADATA_VERSION,
+        environment: environment.name().to_string(),
+        platform,
+        conda_packages,
+        pypi_packages: pypi_packages
+            .into_iter()
+            .map(|(_, package)| package)
+            .collect(),
+    };
+    fs_err::write(
+        staging.path().join(METADATA_FILE_NAME),
+        serde_json::to_string_pretty(&metadata).into_diagnostic()?,
+    )
+    .into_diagnostic()?;
+
+    let output_file = args.output_file.unwrap_or_else(|| {
+        PathBuf::from(if args.create_executable {
+            "environment.sh"
+        } else {
+            "environment.tar"
+        })
+    });
+    write_archive(staging.path(), &output_file, args.create_executable)
+        .with_context(|| format!("failed to write '{}'", output_file.display()))?;
+
+    eprintln!(
+        "{}Packed {} packages of the environment '{}' for {} into {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        download_count,
+        environment.name().fancy_display(),
+        platform,
+        output_file.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_installer_script_ends_with_marker() {
+        let script = installer_script();
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.ends_with(&format!("\n{ARCHIVE_MARKER}\n")));
+        assert!(script.contains(&format!("/^{ARCHIVE_MARKER}$/ {{ print NR + 1; exit 0; }}")));
+    }
+}
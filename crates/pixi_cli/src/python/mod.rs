@@ -0,0 +1,35 @@
+pub mod pin;
+
+use clap::Parser;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Commands to manage the python version of the workspace.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The subcommand to execute
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// Pin the python version of the workspace.
+    ///
+    /// Sets the `python` dependency of the default feature to the given
+    /// version and updates the lock-file. For a `pyproject.toml` manifest the
+    /// `requires-python` is raised to the version if it does not allow it.
+    ///
+    /// Example:
+    /// `pixi python pin 3.12`
+    Pin(pin::Args),
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.command {
+        Command::Pin(pin_args) => pin::execute(args.workspace_config, pin_args).await,
+    }
+}
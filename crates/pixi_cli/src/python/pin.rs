@@ -0,0 +1,137 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use clap::Parser;
+use indexmap::IndexMap;
+use miette::{IntoDiagnostic, WrapErr};
+use pep440_rs::VersionSpecifiers;
+use pixi_config::ConfigCli;
+use pixi_core::environment::sanity_check_workspace;
+use pixi_manifest::{FeatureName, SpecType};
+use rattler_conda_types::{MatchSpec, PackageName, ParseStrictness, Version};
+use rattler_lock::{LockFile, LockedPackageRef};
+
+use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The python version to pin, e.g. `3.12`
+    #[clap(required = true, num_args = 1)]
+    pub version: String,
+
+    #[clap(flatten)]
+    pub no_install_config: NoInstallConfig,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(workspace_config: WorkspaceConfig, args: Args) -> miette::Result<()> {
+    let workspace = workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config.clone());
+
+    sanity_check_workspace(&workspace).await?;
+
+    let version = Version::from_str(&args.version)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("'{}' is not a valid python version", args.version))?;
+    let pypi_version = pep440_rs::Version::from_str(&args.version)
+        .into_diagnostic()
+        .wrap_err_with(|| format!("'{}' is not a valid python version", args.version))?;
+    let spec = MatchSpec::from_str(&format!("python {version}.*"), ParseStrictness::Strict)
+        .into_diagnostic()?;
+
+    let mut workspace = workspace.modify()?;
+
+    // The `requires-python` of a pyproject.toml only has to change if it
+    // excludes the pinned version.
+    let requires_python = workspace
+        .manifest()
+        .document
+        .requires_python()
+        .and_then(|requires_python| VersionSpecifiers::from_str(requires_python).ok())
+        .filter(|requires_python| !requires_python.contains(&pypi_version))
+        .map(|_| format!(">={pypi_version}"));
+    if let Some(requires_python) = &requires_python {
+        workspace.manifest().set_requires_python(requires_python)?;
+    }
+
+    let match_specs =
+        IndexMap::from([(PackageName::new_unchecked("python"), (spec, SpecType::Run))]);
+    let result = Box::pin(workspace.update_dependencies(
+        match_specs,
+        IndexMap::default(),
+        IndexMap::default(),
+        args.no_install_config.no_install,
+        &args.lock_file_update_config.lock_file_usage()?,
+        &FeatureName::DEFAULT,
+        &[],
+        false,
+        false,
+    ))
+    .await;
+
+    if let Err(err) = result {
+        let workspace = workspace.revert().await.into_diagnostic()?;
+
+        // Point out the locked packages that prevent the pin.
+        if let Ok(lock_file) = workspace
+            .load_lock_file()
+            .await
+            .and_then(|lock_file| lock_file.into_lock_file())
+        {
+            let incompatible = incompatible_pypi_packages(&lock_file, &pypi_version);
+            if !incompatible.is_empty() {
+                eprintln!(
+                    "{}The following locked PyPI packages do not support python {pypi_version}:",
+                    console::style(console::Emoji("⚠️ ", "")).yellow(),
+                );
+                for (name, requires_python) in incompatible {
+                    eprintln!("  - {name} (requires-python {requires_python})");
+                }
+            }
+        }
+        return Err(err);
+    }
+    workspace.save().await.into_diagnostic()?;
+
+    eprintln!(
+        "{}Pinned python to {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        console::style(format!("{version}.*")).bold(),
+    );
+    if let Some(requires_python) = requires_python {
+        eprintln!(
+            "{}Updated requires-python to '{requires_python}'",
+            console::style(console::Emoji("✔ ", "")).green(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns the names of the locked PyPI packages whose `requires-python` does
+/// not allow the python `version`, together with their `requires-python`.
+fn incompatible_pypi_packages(
+    lock_file: &LockFile,
+    version: &pep440_rs::Version,
+) -> BTreeSet<(String, String)> {
+    let mut incompatible = BTreeSet::new();
+    for (_, environment) in lock_file.environments() {
+        for (_, packages) in environment.packages_by_platform() {
+            for package in packages {
+                if let LockedPackageRef::Pypi(pypi, _) = package
+                    && let Some(requires_python) = &pypi.requires_python
+                    && !requires_python.contains(version)
+                {
+                    incompatible.insert((pypi.name.to_string(), requires_python.to_string()));
+                }
+            }
+        }
+    }
+    incompatible
+}
@@ -2,7 +2,6 @@ use clap::Parser;
 use pixi_api::WorkspaceContext;
 use pixi_api::workspace::ReinstallOptions;
 use pixi_config::ConfigCli;
-use pixi_core::WorkspaceLocator;
 use pixi_core::lock_file::{ReinstallEnvironment, ReinstallPackages};
 
 use crate::cli_config::WorkspaceConfig;
@@ -70,8 +69,9 @@ impl From<Args> for ReinstallOptions {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
+    let workspace = args
+        .project_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone());
 
@@ -1,7 +1,15 @@
 use clap::Parser;
-use pixi_api::{WorkspaceContext, workspace::DependencyOptions};
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_api::{
+    WorkspaceContext,
+    workspace::{DependencyOptions, RemovePreview},
+};
 use pixi_config::ConfigCli;
-use pixi_core::{DependencyType, WorkspaceLocator};
+use pixi_consts::consts;
+use pixi_core::DependencyType;
+use rattler_conda_types::Platform;
+use similar::{ChangeTag, TextDiff};
 
 use crate::{cli_config::LockFileUpdateConfig, has_specs::HasSpecs};
 use crate::{
@@ -28,6 +36,15 @@ pub struct Args {
     #[clap(flatten)]
     pub dependency_config: DependencyConfig,
 
+    /// Don't actually modify the manifest, the lock-file or any environment,
+    /// only show what would change.
+    #[clap(short = 'n', long)]
+    pub dry_run: bool,
+
+    /// Don't ask for confirmation before changing the manifest.
+    #[arg(short = 'y', long = "yes", alias = "assume-yes")]
+    pub assume_yes: bool,
+
     #[clap(flatten)]
     pub no_install_config: NoInstallConfig,
     #[clap(flatten)]
@@ -46,19 +63,24 @@ impl TryFrom<&Args> for DependencyOptions {
             platforms: args.dependency_config.platforms.clone(),
             no_install: args.no_install_config.no_install,
             lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+            dry_run: args.dry_run,
+            assume_yes: args.assume_yes,
         })
     }
 }
 
-pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+pub async fn execute(mut args: Args) -> miette::Result<()> {
+    args.dependency_config.read_specs_from_file()?;
+
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone());
 
     let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace.clone());
 
-    match args.dependency_config.dependency_type() {
+    let preview = match args.dependency_config.dependency_type() {
         DependencyType::CondaDependency(spec_type) => {
             workspace_ctx
                 .remove_conda_deps(
@@ -66,7 +88,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                     spec_type,
                     (&args).try_into()?,
                 )
-                .await?;
+                .await?
         }
         DependencyType::PypiDependency => {
             let pypi_deps = args
@@ -77,12 +99,95 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 .collect();
             workspace_ctx
                 .remove_pypi_deps(pypi_deps, (&args).try_into()?)
-                .await?;
+                .await?
         }
     };
 
+    if let Some(preview) = preview {
+        let default_environment = workspace.default_environment();
+        return print_preview(
+            &preview,
+            default_environment.name().as_str(),
+            !args.no_install_config.no_install,
+        );
+    }
+
     args.dependency_config
         .display_success("Removed", Default::default());
 
     Ok(())
 }
+
+/// Prints the changes a `--dry-run` removal would have made.
+///
+/// Only the default environment is updated by `pixi remove`, so only the
+/// packages removed from it on the current platform would be unlinked.
+fn print_preview(
+    preview: &RemovePreview,
+    default_environment: &str,
+    install: bool,
+) -> miette::Result<()> {
+    if preview.original_manifest == preview.updated_manifest {
+        eprintln!(
+            "{}The manifest would not change",
+            console::style(console::Emoji("✔ ", "")).green()
+        );
+    } else {
+        let manifest_diff =
+            TextDiff::from_lines(&preview.original_manifest, &preview.updated_manifest);
+        eprintln!(
+            "The following changes would be made to {}:",
+            console::style(preview.manifest_path.display()).bold()
+        );
+        for hunk in manifest_diff.unified_diff().context_radius(2).iter_hunks() {
+            eprintln!("{}", console::style(hunk.header()).cyan());
+            for change in hunk.iter_changes() {
+                let line = change.to_string_lossy();
+                let line = line.trim_end_matches(['\r', '\n']);
+                match change.tag() {
+                    ChangeTag::Delete => eprintln!("{}", console::style(format!("-{line}")).red()),
+                    ChangeTag::Insert => {
+                        eprintln!("{}", console::style(format!("+{line}")).green())
+                    }
+                    ChangeTag::Equal => eprintln!(" {line}"),
+                }
+            }
+        }
+    }
+
+    let Some(lock_file_diff) = &preview.lock_file_diff else {
+        return Ok(());
+    };
+    if lock_file_diff.is_empty() {
+        eprintln!(
+            "{}The lock-file would not change",
+            console::style(console::Emoji("✔ ", "")).green()
+        );
+        return Ok(());
+    }
+    lock_file_diff
+        .print()
+        .into_diagnostic()
+        .context("failed to print lock-file diff")?;
+
+    if !install {
+        return Ok(());
+    }
+    let unlinked = lock_file_diff
+        .environment
+        .get(default_environment)
+        .and_then(|platforms| platforms.get(&Platform::current()))
+        .map(|diff| diff.removed.iter().map(|p| p.name()).sorted().collect_vec())
+        .unwrap_or_default();
+    if !unlinked.is_empty() {
+        eprintln!(
+            "The following packages would be unlinked from the {} environment:",
+            consts::ENVIRONMENT_STYLE.apply_to(default_environment)
+        );
+        for name in unlinked {
+            eprintln!("  - {}", console::style(name).red());
+        }
+    }
+
+    Ok(())
+}
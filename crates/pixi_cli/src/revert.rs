@@ -0,0 +1,97 @@
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use itertools::Itertools;
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_config::ConfigCli;
+use pixi_core::{
+    InstallFilter, UpdateLockFileOptions,
+    environment::{LockFileUsage, get_update_lock_file_and_prefixes},
+    history::History,
+    lock_file::{ReinstallPackages, UpdateMode},
+};
+
+use crate::cli_config::WorkspaceConfig;
+
+/// Restore the lock-file and the environments to an earlier snapshot.
+///
+/// The lock-file of the snapshot replaces `pixi.lock`. The environment of the
+/// snapshot and all other installed environments are installed from it. The
+/// manifest is not changed, use your version control to restore it as well.
+///
+/// Use `pixi history list` to see the recorded snapshots.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The id of the snapshot to restore
+    pub id: u64,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
+        .locate()?
+        .with_cli_config(args.config);
+
+    let history = History::new(&workspace);
+    let snapshot = history.snapshot(args.id)?;
+    let environment = workspace
+        .environment(snapshot.environment.as_str())
+        .ok_or_else(|| {
+            miette::miette!(
+                "the environment '{}' of snapshot {} does not exist in the workspace",
+                snapshot.environment,
+                snapshot.id
+            )
+        })?;
+
+    fs_err::copy(
+        history.lock_file_path(snapshot.id),
+        workspace.lock_file_path(),
+    )
+    .into_diagnostic()
+    .wrap_err("failed to restore the lock-file")?;
+
+    // The restored lock-file replaces the packages of every environment, so
+    // reinstall the ones that are installed to keep them in sync with it.
+    let environments = std::iter::once(environment.clone())
+        .chain(
+            workspace
+                .environments()
+                .into_iter()
+                .filter(|env| env.name() != environment.name() && env.dir().is_dir()),
+        )
+        .collect_vec();
+
+    // Install the restored lock-file as is, it is not checked against the
+    // manifest.
+    get_update_lock_file_and_prefixes(
+        &environments,
+        UpdateMode::Revalidate,
+        UpdateLockFileOptions {
+            lock_file_usage: LockFileUsage::Frozen,
+            no_install: false,
+            max_concurrent_solves: workspace.config().max_concurrent_solves(),
+        },
+        ReinstallPackages::default(),
+        &InstallFilter::default(),
+    )
+    .await?;
+
+    eprintln!(
+        "{}Reverted to snapshot {} from {}, installed {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        snapshot.id,
+        snapshot.created.format("%Y-%m-%d %H:%M:%S"),
+        environments
+            .iter()
+            .map(|env| env.name().fancy_display())
+            .format(", ")
+    );
+    Ok(())
+}
@@ -2,38 +2,53 @@ use std::{
     collections::{HashMap, HashSet, hash_map::Entry},
     convert::identity,
     ffi::OsString,
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    process::Stdio,
+    str::FromStr,
     string::String,
 };
 
-#[cfg(unix)]
-use std::io::IsTerminal;
-
 use clap::Parser;
-use deno_task_shell::KillSignal;
+use deno_task_shell::{KillSignal, ShellPipeReader, ShellPipeWriter, ShellState};
 use dialoguer::theme::ColorfulTheme;
 use fancy_display::FancyDisplay;
 use indicatif::ProgressDrawTarget;
 use itertools::Itertools;
-use miette::{Diagnostic, IntoDiagnostic};
-use pixi_config::{ConfigCli, ConfigCliActivation};
+use miette::{Context, Diagnostic, IntoDiagnostic};
+use pixi_api::Interface;
+use pixi_config::{
+    Config, ConfigCli, ConfigCliActivation, InstallMissingEnvironments, RunLockPolicy,
+};
 use pixi_core::{
-    Workspace, WorkspaceLocator,
-    environment::sanity_check_workspace,
-    lock_file::{ReinstallPackages, UpdateLockFileOptions, UpdateMode},
-    workspace::{Environment, errors::UnsupportedPlatformError},
+    Workspace,
+    environment::{LockFileUsage, sanity_check_workspace},
+    lock_file::{
+        LockFileDerivedData, LockFileNotUpToDateError, ReinstallPackages, UpdateLockFileOptions,
+        UpdateMode,
+    },
+    workspace::{Environment, HasWorkspaceRef, errors::UnsupportedPlatformError},
 };
 use pixi_manifest::{FeaturesExt, TaskName};
 use pixi_progress::global_multi_progress;
 use pixi_task::{
     AmbiguousTask, CanSkip, ExecutableTask, FailedToParseShellScript, InvalidWorkingDirectory,
-    SearchEnvironments, TaskAndEnvironment, TaskGraph, get_task_env,
+    Sandbox, SandboxError, SearchEnvironments, TaskAndEnvironment, TaskGraph, TaskHash,
+    get_task_env,
 };
-use rattler_conda_types::Platform;
+use rattler_conda_types::{MatchSpec, ParseStrictness, Platform};
 use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio_util::sync::CancellationToken;
 use tracing::Level;
 
-use crate::cli_config::{LockAndInstallConfig, WorkspaceConfig};
+use crate::{
+    cli_config::{LockAndInstallConfig, WorkspaceConfig},
+    cli_interface::CliInterface,
+    run_overlay::RunOverlay,
+    task_log::{DEFAULT_LOG_RETENTION, PrefixedLines, TaskLog},
+    task_remote_cache::RemoteTaskCache,
+};
 
 /// Runs task in the pixi environment.
 ///
@@ -43,6 +58,10 @@ use crate::cli_config::{LockAndInstallConfig, WorkspaceConfig};
 ///
 /// `pixi run` will also update the lockfile and install the environment if it
 /// is required.
+///
+/// An environment that is not installed yet is installed on its own, unless
+/// the `install-missing-environments` configuration is set to `prompt` or
+/// `never`.
 #[derive(Parser, Debug, Default)]
 #[clap(trailing_var_arg = true, disable_help_flag = true)]
 pub struct Args {
@@ -66,6 +85,16 @@ pub struct Args {
     #[arg(long, short)]
     pub environment: Option<String>,
 
+    /// Add a package on top of the environment for this run only, e.g.
+    /// `--with ipython`
+    ///
+    /// The packages are solved with the locked packages of the environment
+    /// pinned and installed into a cached prefix that is layered on top of the
+    /// environment. The manifest, the lock-file and the environment are not
+    /// changed.
+    #[arg(long = "with", value_name = "SPEC")]
+    pub with: Vec<String>,
+
     /// Use a clean environment to run the task
     ///
     /// Using this flag will ignore your current shell environment and use bare
@@ -78,6 +107,37 @@ pub struct Args {
     #[arg(long)]
     pub skip_deps: bool,
 
+    /// Don't rebuild source packages whose sources changed since they were
+    /// built, run the task with the packages that are installed
+    #[arg(long)]
+    pub no_rebuild: bool,
+
+    /// Run the tasks in a sandbox that only allows writing to the workspace
+    /// and denies network access, unless the `sandbox` policy of the task
+    /// allows more
+    ///
+    /// Requires `bwrap` (bubblewrap) on Linux and `sandbox-exec` on macOS.
+    #[arg(long)]
+    pub sandbox: bool,
+
+    /// Write the output of the tasks to log files in `.pixi/logs`, while it is
+    /// still printed to the terminal
+    ///
+    /// The output of every run of a task is written to a new file in
+    /// `.pixi/logs/<task>/`. Tasks with `log = true` are always logged.
+    #[arg(long)]
+    pub log: bool,
+
+    /// Write the log files of the tasks to this directory instead of
+    /// `.pixi/logs`, implies `--log`
+    #[arg(long, value_name = "DIR")]
+    pub log_dir: Option<PathBuf>,
+
+    /// The number of log files that are kept for every task, older log files
+    /// are removed
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_LOG_RETENTION)]
+    pub log_retention: usize,
+
     /// Run the task in dry-run mode (only print the command that would run)
     #[clap(short = 'n', long)]
     pub dry_run: bool,
@@ -104,14 +164,24 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .merge_config(args.config.clone().into());
 
     // Load the workspace
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
+        .with_fallback_roots(Config::load_global().fallback_workspaces())
         .locate()?
         .with_cli_config(cli_config);
 
     // Extract the passed in environment name.
     let environment = workspace.environment_from_name_or_env_var(args.environment.clone())?;
 
+    let with_specs = args
+        .with
+        .iter()
+        .map(|spec| MatchSpec::from_str(spec, ParseStrictness::Lenient))
+        .collect::<Result<Vec<_>, _>>()
+        .into_diagnostic()
+        .context("failed to parse the `--with` specs")?;
+
     // Find the environment to run the task in, if any were specified.
     let explicit_environment = if args.environment.is_none() && environment.is_default() {
         None
@@ -136,14 +206,12 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     let best_platform = environment.best_platform();
 
     // Ensure that the lock-file is up-to-date.
-    let lock_file = workspace
-        .update_lock_file(UpdateLockFileOptions {
-            lock_file_usage: args.lock_and_install_config.lock_file_usage()?,
-            no_install: args.lock_and_install_config.no_install(),
-            max_concurrent_solves: workspace.config().max_concurrent_solves(),
-        })
-        .await?
-        .0;
+    let lock_file = update_lock_file(
+        &workspace,
+        args.lock_and_install_config.lock_file_usage()?,
+        args.lock_and_install_config.no_install(),
+    )
+    .await?;
 
     // Spawn a task that listens for ctrl+c and resets the cursor.
     tokio::spawn(async {
@@ -160,6 +228,13 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     )
     .with_disambiguate_fn(disambiguate_task_interactive);
 
+    // The directory the output of tasks is logged to.
+    let log_all = args.log || args.log_dir.is_some();
+    let log_dir = args
+        .log_dir
+        .clone()
+        .unwrap_or_else(|| workspace.task_logs_folder());
+
     let task_graph =
         TaskGraph::from_cmd_args(&workspace, &search_environment, args.task, args.skip_deps)?;
 
@@ -180,6 +255,27 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     // task.
     let mut task_idx = 0;
     let mut task_envs = HashMap::new();
+    let mut remote_cache = if workspace.config().task_cache().is_default() {
+        None
+    } else {
+        RemoteTaskCache::from_config(
+            workspace.config().task_cache(),
+            workspace.authenticated_client()?.clone(),
+        )
+    };
+    // When the output of several tasks ends up in the same non-interactive log,
+    // every line is prefixed with the name of the task that printed it.
+    let prefix_output = !std::io::stdout().is_terminal()
+        && task_graph
+            .topological_order()
+            .into_iter()
+            .filter(|&task_id| {
+                ExecutableTask::from_task_graph(&task_graph, task_id)
+                    .task()
+                    .is_executable()
+            })
+            .count()
+            > 1;
     let signal = KillSignal::default();
     // make sure that child processes are killed when pixi stops
     let _drop_guard = signal.clone().drop_guard();
@@ -260,19 +356,74 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             }
         };
 
+        // The outputs of a task that has inputs and outputs can be restored
+        // from the remote cache instead of running the task.
+        let input_hash = match (&remote_cache, &task_cache) {
+            (Some(_), _) if executable_task.task().outputs().is_none() => None,
+            (Some(_), Some(hash)) => hash.input_hash(),
+            (Some(_), None) => TaskHash::from_task(&executable_task, lock_file.as_lock_file())
+                .await
+                .into_diagnostic()?
+                .and_then(|hash| hash.input_hash()),
+            (None, _) => None,
+        };
+        if let (Some(cache), Some(input_hash)) = (&remote_cache, &input_hash) {
+            match cache.restore(input_hash, workspace.root()).await {
+                Ok(true) => {
+                    // Update the local cache, so that the next run is a cache hit.
+                    let post_hash = executable_task
+                        .compute_post_run_hash(lock_file.as_lock_file(), None)
+                        .await
+                        .into_diagnostic()?;
+                    executable_task
+                        .save_cache(post_hash)
+                        .await
+                        .into_diagnostic()?;
+                    pixi_progress::println!(
+                        "Task '{}' restored its outputs from the remote cache 🚀",
+                        console::style(executable_task.name().unwrap_or("")).bold()
+                    );
+                    task_idx += 1;
+                    continue;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    tracing::warn!(
+                        "the remote task cache is not used for the remaining tasks: {err}"
+                    );
+                    remote_cache = None;
+                }
+            }
+        }
+
         // If we don't have a command environment yet, we need to compute it. We lazily
         // compute the task environment because we only need the environment if
         // a task is actually executed.
         let task_env: &_ = match task_envs.entry(executable_task.run_environment.clone()) {
             Entry::Occupied(env) => env.into_mut(),
             Entry::Vacant(entry) => {
+                // Make sure we are allowed to install an environment that is
+                // missing. With `--no-install` the task runs without
+                // installing anything, like before.
+                if args.lock_and_install_config.allow_installs()
+                    && !executable_task.run_environment.is_installed()
+                {
+                    ensure_missing_environment_can_be_installed(&executable_task.run_environment)
+                        .await?;
+                }
+
                 // Check if we allow installs
                 if args.lock_and_install_config.allow_installs() {
                     // Ensure there is a valid prefix
+                    let update_mode = if args.no_rebuild {
+                        UpdateMode::QuickValidateWithoutRebuild
+                    } else {
+                        UpdateMode::QuickValidate
+                    };
                     lock_file
                         .prefix(
                             &executable_task.run_environment,
-                            UpdateMode::QuickValidate,
+                            update_mode,
                             &ReinstallPackages::default(),
                             &pixi_core::environment::InstallFilter::default(),
                         )
@@ -285,7 +436,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 // Clear caches based on the filesystem. The tasks might change files on disk.
                 lock_file.command_dispatcher.clear_filesystem_caches().await;
 
-                let command_env = get_task_env(
+                let mut command_env = get_task_env(
                     &executable_task.run_environment,
                     args.clean_env || executable_task.task().clean_env(),
                     Some(lock_file.as_lock_file()),
@@ -293,6 +444,17 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                     workspace.config().experimental_activation_cache_usage(),
                 )
                 .await?;
+
+                // Layer the packages of `--with` on top of the environment.
+                if !with_specs.is_empty() {
+                    let overlay = RunOverlay::create(
+                        &executable_task.run_environment,
+                        lock_file.as_lock_file(),
+                        &with_specs,
+                    )
+                    .await?;
+                    overlay.apply(&executable_task.run_environment.dir(), &mut command_env);
+                }
                 entry.insert(command_env)
             }
         };
@@ -302,10 +464,39 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             .map(|(k, v)| (OsString::from(k), OsString::from(v)))
             .collect();
 
+        // Create the log file of this run of the task.
+        let task_log = if log_all || executable_task.task().log() {
+            let name = executable_task.name().unwrap_or("command");
+            let task_log = TaskLog::create(&log_dir, name, args.log_retention)
+                .into_diagnostic()
+                .wrap_err_with(|| {
+                    format!(
+                        "failed to create a log file for the task in '{}'",
+                        log_dir.display()
+                    )
+                })?;
+            tracing::debug!(
+                "Logging the output of the task to '{}'",
+                task_log.path().display()
+            );
+            Some(task_log)
+        } else {
+            None
+        };
+
         // Execute the task itself within the command environment. If one of the tasks
         // failed with a non-zero exit code, we exit this parent process with
         // the same code.
-        match execute_task(&executable_task, &task_env, signal.clone()).await {
+        match execute_task(
+            &executable_task,
+            &task_env,
+            signal.clone(),
+            args.sandbox,
+            task_log.as_ref(),
+            prefix_output.then(|| executable_task.name().unwrap_or("command")),
+        )
+        .await
+        {
             Ok(_) => {
                 task_idx += 1;
             }
@@ -313,7 +504,13 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                 if code == 127 {
                     command_not_found(&workspace, explicit_environment.clone());
                 }
-                std::process::exit(code);
+                if let Some(task_log) = &task_log {
+                    pixi_progress::println!(
+                        "The output of the task was written to '{}'",
+                        task_log.path().display()
+                    );
+                }
+                return Err(crate::ExitStatus(code).into());
             }
             Err(err) => return Err(err.into()),
         }
@@ -326,6 +523,19 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         if let Some(ref hash) = post_hash {
             executable_task.warn_on_missing_globs(hash);
         }
+
+        // Share the outputs of the task through the remote cache.
+        if let (Some(cache), Some(input_hash), Some(outputs)) = (
+            &remote_cache,
+            &input_hash,
+            post_hash.as_ref().and_then(|hash| hash.outputs.as_ref()),
+        ) {
+            let outputs = outputs.files.files.keys().cloned().collect();
+            if let Err(err) = cache.upload(input_hash, workspace.root(), outputs).await {
+                tracing::warn!("the remote task cache is not used for the remaining tasks: {err}");
+                remote_cache = None;
+            }
+        }
         executable_task
             .save_cache(post_hash)
             .await
@@ -389,6 +599,17 @@ enum TaskExecutionError {
 
     #[error(transparent)]
     UnsupportedPlatformError(#[from] UnsupportedPlatformError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Sandbox(#[from] SandboxError),
+
+    #[error("failed to write the output of the task to '{}'", .path.display())]
+    Log {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
 }
 
 /// Called to execute a single command.
@@ -398,21 +619,58 @@ async fn execute_task(
     task: &ExecutableTask<'_>,
     command_env: &HashMap<OsString, OsString>,
     kill_signal: KillSignal,
+    sandbox: bool,
+    log: Option<&TaskLog>,
+    prefix: Option<&str>,
 ) -> Result<(), TaskExecutionError> {
+    if sandbox {
+        return execute_task_in_sandbox(task, command_env, kill_signal, log, prefix).await;
+    }
+
     let Some(script) = task.as_deno_script()? else {
         return Ok(());
     };
     let cwd = task.working_directory()?;
-    let execute_future = deno_task_shell::execute(
-        script,
-        command_env.clone(),
-        cwd,
-        Default::default(),
-        kill_signal.clone(),
-    );
 
     // Execute the process and forward signals.
-    let status_code = run_future_forwarding_signals(kill_signal, execute_future).await;
+    let stdout = task_output(std::io::stdout(), log, prefix)?;
+    let stderr = task_output(std::io::stderr(), log, prefix)?;
+    let status_code = match stdout.zip(stderr) {
+        None => {
+            let execute_future = deno_task_shell::execute(
+                script,
+                command_env.clone(),
+                cwd,
+                Default::default(),
+                kill_signal.clone(),
+            );
+            run_future_forwarding_signals(kill_signal, execute_future).await
+        }
+        Some((stdout, stderr)) => {
+            let (stdout, stdout_handle) = output_pipe(stdout);
+            let (stderr, stderr_handle) = output_pipe(stderr);
+            let state = ShellState::new(
+                command_env.clone(),
+                cwd,
+                Default::default(),
+                kill_signal.clone(),
+            );
+            let execute_future = deno_task_shell::execute_with_pipes(
+                script,
+                state,
+                ShellPipeReader::stdin(),
+                stdout,
+                stderr,
+            );
+            let status_code = run_future_forwarding_signals(kill_signal, execute_future).await;
+
+            // The writers are dropped once the script finished, wait until the
+            // remaining output is written.
+            let _ = stdout_handle.await;
+            let _ = stderr_handle.await;
+            status_code
+        }
+    };
     if status_code != 0 {
         return Err(TaskExecutionError::NonZeroExitCode(status_code));
     }
@@ -420,6 +678,237 @@ async fn execute_task(
     Ok(())
 }
 
+/// Executes a single command in a sandbox. The script is run by a child pixi
+/// process that is started by the sandbox, so that the builtins of the task
+/// shell are restricted as well.
+async fn execute_task_in_sandbox(
+    task: &ExecutableTask<'_>,
+    command_env: &HashMap<OsString, OsString>,
+    kill_signal: KillSignal,
+    log: Option<&TaskLog>,
+    prefix: Option<&str>,
+) -> Result<(), TaskExecutionError> {
+    let Some(script) = task.as_script()? else {
+        return Ok(());
+    };
+    let cwd = task.working_directory()?;
+    let sandbox = Sandbox::new(task.project().root(), &task.task().sandbox());
+    tracing::debug!(
+        "Running task in a sandbox, writable paths: {}",
+        sandbox.writable().iter().map(|p| p.display()).format(", ")
+    );
+
+    let launcher = sandbox.launcher()?;
+    let current_exe = std::env::current_exe().map_err(SandboxError::CurrentExe)?;
+    let mut command = sandbox.command(&current_exe)?;
+    command
+        .arg("__task-shell")
+        .arg("--cwd")
+        .arg(&cwd)
+        .arg("--")
+        .arg(script.trim())
+        .current_dir(&cwd)
+        .env_clear()
+        .envs(command_env)
+        .kill_on_drop(true);
+    let stdout = task_output(std::io::stdout(), log, prefix)?;
+    let stderr = task_output(std::io::stderr(), log, prefix)?;
+    let output = stdout.zip(stderr);
+    if output.is_some() {
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|source| SandboxError::Launch { launcher, source })?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let tee_output = async {
+        if let (Some((stdout_output, stderr_output)), Some(stdout), Some(stderr)) =
+            (output, stdout, stderr)
+        {
+            let (stdout, stderr) = tokio::join!(
+                copy_output(stdout, stdout_output),
+                copy_output(stderr, stderr_output)
+            );
+            stdout?;
+            stderr?;
+        }
+        Ok::<_, std::io::Error>(())
+    };
+
+    let (status, copied) = run_future_forwarding_signals(kill_signal, async {
+        tokio::join!(child.wait(), tee_output)
+    })
+    .await;
+    let status = status.map_err(|source| SandboxError::Launch { launcher, source })?;
+    match (log, copied) {
+        (Some(log), Err(source)) => {
+            return Err(TaskExecutionError::Log {
+                path: log.path().to_path_buf(),
+                source,
+            });
+        }
+        (None, Err(err)) => tracing::warn!("failed to write the output of the task: {err}"),
+        (_, Ok(())) => {}
+    }
+    match status.code() {
+        Some(0) => Ok(()),
+        code => Err(TaskExecutionError::NonZeroExitCode(code.unwrap_or(1))),
+    }
+}
+
+/// Returns the writer the output of a task is copied to when it is logged or
+/// prefixed with the name of the task, or `None` if the task can write to the
+/// terminal directly.
+fn task_output<W: Write + Send + 'static>(
+    terminal: W,
+    log: Option<&TaskLog>,
+    prefix: Option<&str>,
+) -> Result<Option<Box<dyn Write + Send>>, TaskExecutionError> {
+    let terminal: Box<dyn Write + Send> = match prefix {
+        Some(prefix) => Box::new(PrefixedLines::new(terminal, prefix)),
+        None if log.is_none() => return Ok(None),
+        None => Box::new(terminal),
+    };
+    let Some(log) = log else {
+        return Ok(Some(terminal));
+    };
+    let tee = log
+        .tee(terminal)
+        .map_err(|source| TaskExecutionError::Log {
+            path: log.path().to_path_buf(),
+            source,
+        })?;
+    Ok(Some(Box::new(tee)))
+}
+
+/// Returns a pipe for the output of the task shell that is copied to the
+/// given writer on a separate thread.
+fn output_pipe(
+    mut output: Box<dyn Write + Send>,
+) -> (ShellPipeWriter, tokio::task::JoinHandle<()>) {
+    let (reader, writer) = deno_task_shell::pipe();
+    let handle = tokio::task::spawn_blocking(move || {
+        if let Err(err) = reader.pipe_to(&mut output) {
+            tracing::warn!("failed to write the output of the task: {err}");
+        }
+    });
+    (writer, handle)
+}
+
+/// Copies the output of a child process to the given writer.
+async fn copy_output(
+    mut reader: impl AsyncRead + Unpin,
+    mut output: impl Write,
+) -> std::io::Result<()> {
+    let mut buf = vec![0; 8 * 1024];
+    loop {
+        let read = reader.read(&mut buf).await?;
+        if read == 0 {
+            return output.flush();
+        }
+        output.write_all(&buf[..read])?;
+        output.flush()?;
+    }
+}
+
+/// Updates the lock-file according to the `run-lock-policy` configuration,
+/// unless `--frozen` or `--locked` determine what to do.
+async fn update_lock_file(
+    workspace: &Workspace,
+    lock_file_usage: LockFileUsage,
+    no_install: bool,
+) -> miette::Result<LockFileDerivedData<'_>> {
+    let options = |lock_file_usage| UpdateLockFileOptions {
+        lock_file_usage,
+        no_install,
+        max_concurrent_solves: workspace.config().max_concurrent_solves(),
+    };
+    let policy = workspace.config().run_lock_policy();
+    if lock_file_usage != LockFileUsage::Update || policy == RunLockPolicy::Update {
+        return Ok(workspace
+            .update_lock_file(options(lock_file_usage))
+            .await?
+            .0);
+    }
+
+    let not_up_to_date = || {
+        miette::miette!(
+            help = "Update it with `pixi lock`, or run the task with `--frozen` to use the lock-file as it is",
+            "the lock-file is not up-to-date with the workspace and the `run-lock-policy` does not allow updating it"
+        )
+    };
+    match workspace
+        .update_lock_file(options(LockFileUsage::Locked))
+        .await
+    {
+        Ok((lock_file, _)) => Ok(lock_file),
+        Err(err) if err.downcast_ref::<LockFileNotUpToDateError>().is_none() => Err(err),
+        Err(_) => {
+            if policy == RunLockPolicy::Error || !std::io::stdin().is_terminal() {
+                return Err(not_up_to_date());
+            }
+            let update = CliInterface {}
+                .confirm(
+                    "The lock-file is not up-to-date with the workspace, do you want to update it?",
+                )
+                .await?;
+            if !update {
+                return Err(not_up_to_date());
+            }
+            Ok(workspace
+                .update_lock_file(options(LockFileUsage::Update))
+                .await?
+                .0)
+        }
+    }
+}
+
+/// Called when the environment of a task is not installed. Returns an error if
+/// the environment should not be installed, either because the
+/// `install-missing-environments` configuration is set to `never`, or the user
+/// declined the prompt.
+async fn ensure_missing_environment_can_be_installed(
+    environment: &Environment<'_>,
+) -> miette::Result<()> {
+    let not_installed = || {
+        miette::miette!(
+            help = format!(
+                "Install it with `pixi install --environment {}`",
+                environment.name()
+            ),
+            "the environment '{}' is not installed",
+            environment.name().fancy_display()
+        )
+    };
+
+    match environment
+        .workspace()
+        .config()
+        .install_missing_environments()
+    {
+        InstallMissingEnvironments::Always => Ok(()),
+        InstallMissingEnvironments::Never => Err(not_installed()),
+        InstallMissingEnvironments::Prompt => {
+            if !std::io::stdin().is_terminal() {
+                return Err(not_installed());
+            }
+            let install = CliInterface {}
+                .confirm(&format!(
+                    "The environment '{}' is not installed, do you want to install it?",
+                    environment.name().fancy_display()
+                ))
+                .await?;
+            if install {
+                Ok(())
+            } else {
+                Err(not_installed())
+            }
+        }
+    }
+}
+
 /// Called to disambiguate between environments to run a task in.
 fn disambiguate_task_interactive<'p>(
     problem: &AmbiguousTask<'p>,
@@ -565,3 +1054,47 @@ async fn listen_and_forward_all_signals(kill_signal: KillSignal) {
     }
     futures::future::join_all(futures).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_lock_policy_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = Workspace::from_str(
+            &dir.path().join("pixi.toml"),
+            &format!(
+                r#"
+        [workspace]
+        name = "run"
+        channels = ["conda-forge"]
+        platforms = ["{}"]
+
+        [dependencies]
+        python = "*"
+        "#,
+                Platform::current()
+            ),
+        )
+        .unwrap()
+        .with_cli_config(Config {
+            run_lock_policy: Some(RunLockPolicy::Error),
+            ..Config::default()
+        });
+
+        // The policy refuses to update the lock-file that doesn't exist yet.
+        let err = update_lock_file(&workspace, LockFileUsage::Update, true)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("`run-lock-policy` does not allow"));
+
+        // `--locked` takes precedence over the policy.
+        let err = update_lock_file(&workspace, LockFileUsage::Locked, true)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.downcast_ref::<LockFileNotUpToDateError>().is_some());
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use pixi_core::{
+    repodata::Repodata,
+    workspace::{Environment, HasWorkspaceRef},
+};
+use pixi_manifest::FeaturesExt;
+use pixi_progress::{await_in_progress, global_multi_progress, wrap_in_progress};
+use pixi_utils::{AsyncPrefixGuard, prefix::Prefix};
+use rattler::{
+    install::{IndicatifReporter, Installer, PythonInfo},
+    package_cache::PackageCache,
+};
+use rattler_conda_types::{GenericVirtualPackage, MatchSpec, Platform, RepoDataRecord};
+use rattler_lock::LockFile;
+use rattler_shell::activation::prefix_path_entries;
+use rattler_solve::{SolverImpl, SolverTask, resolvo::Solver};
+use uv_configuration::RAYON_INITIALIZE;
+use xxhash_rust::xxh3::Xxh3;
+
+/// A prefix with extra packages that is layered on top of an environment for
+/// `pixi run --with`.
+///
+/// The extra packages are solved with the locked packages of the environment
+/// pinned, so that shared dependencies have the same version in both
+/// prefixes. Only the packages that are not in the environment are installed
+/// in the overlay. The overlay is cached by the extra packages and the locked
+/// packages, the lock-file and the environment itself are never changed.
+pub(crate) struct RunOverlay {
+    prefix: Prefix,
+    platform: Platform,
+    /// The site-packages directory relative to the prefixes, if the overlay
+    /// contains python.
+    site_packages: Option<PathBuf>,
+}
+
+impl RunOverlay {
+    /// Creates the overlay with the `specs` for the environment, or reuses
+    /// the cached overlay if it was created before.
+    pub(crate) async fn create(
+        environment: &Environment<'_>,
+        lock_file: &LockFile,
+        specs: &[MatchSpec],
+    ) -> miette::Result<Self> {
+        let workspace = environment.workspace();
+        let platform = environment.best_platform();
+        let locked = match lock_file.environment(environment.name().as_str()) {
+            Some(locked) => locked
+                .conda_repodata_records(platform)
+                .into_diagnostic()?
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
+
+        let channel_config = workspace.channel_config();
+        let channels = environment
+            .channels()
+            .into_iter()
+            .cloned()
+            .map(|channel| channel.into_channel(&channel_config))
+            .collect::<Result<Vec<_>, _>>()
+            .into_diagnostic()?;
+
+        let cache_dir = pixi_config::get_cache_dir()?;
+        let prefix = Prefix::new(cache_dir.join(pixi_consts::consts::CACHED_ENVS_DIR).join(
+            overlay_name(environment.name().as_str(), specs, &locked, platform),
+        ));
+
+        let guard = AsyncPrefixGuard::new(prefix.root())
+            .await
+            .into_diagnostic()
+            .context("failed to create prefix guard")?;
+        let mut write_guard =
+            await_in_progress("acquiring write lock on prefix", |_| guard.write())
+                .await
+                .into_diagnostic()
+                .context("failed to acquire write lock to prefix guard")?;
+
+        let records = if write_guard.is_ready() {
+            tracing::info!("reusing existing overlay in {}", prefix.root().display());
+            prefix
+                .find_installed_packages()
+                .into_diagnostic()?
+                .into_iter()
+                .map(|record| record.repodata_record)
+                .collect()
+        } else {
+            write_guard
+                .begin()
+                .await
+                .into_diagnostic()
+                .context("failed to write lock status to prefix guard")?;
+
+            let gateway = workspace.repodata_gateway()?;
+            let repodata = await_in_progress("fetching repodata for `--with`", |_| async {
+                gateway
+                    .query(channels, [platform, Platform::NoArch], specs.to_vec())
+                    .recursive(true)
+                    .execute()
+                    .await
+                    .into_diagnostic()
+            })
+            .await
+            .context("failed to get repodata")?;
+
+            let virtual_packages = environment
+                .virtual_packages(platform)
+                .into_iter()
+                .map(GenericVirtualPackage::from)
+                .collect();
+            let solved = wrap_in_progress("solving the packages of `--with`", || {
+                Solver.solve(SolverTask {
+                    specs: specs.to_vec(),
+                    pinned_packages: locked.clone(),
+                    virtual_packages,
+                    ..SolverTask::from_iter(&repodata)
+                })
+            })
+            .into_diagnostic()
+            .with_context(|| {
+                format!(
+                    "failed to solve {} together with the locked packages of the environment",
+                    specs.iter().format(", ")
+                )
+            })?;
+
+            // Force the initialization of the rayon thread pool to avoid implicit
+            // creation by the Installer.
+            LazyLock::force(&RAYON_INITIALIZE);
+
+            Installer::new()
+                .with_target_platform(platform)
+                .with_download_client(workspace.authenticated_client()?.client().clone())
+                .with_reporter(
+                    IndicatifReporter::builder()
+                        .with_multi_progress(global_multi_progress())
+                        .clear_when_done(true)
+                        .finish(),
+                )
+                .with_package_cache(PackageCache::new(
+                    cache_dir.join(pixi_consts::consts::CONDA_PACKAGE_CACHE_DIR),
+                ))
+                // The packages of the environment are already installed there,
+                // they are only needed to link the noarch python packages.
+                .with_ignored_packages(
+                    locked
+                        .iter()
+                        .map(|record| record.package_record.name.clone())
+                        .collect(),
+                )
+                .install(prefix.root(), solved.records)
+                .await
+                .into_diagnostic()
+                .context("failed to install the packages of `--with`")?
+                .transaction
+                .installed_packages()
+                .cloned()
+                .collect()
+        };
+        write_guard.finish().await.into_diagnostic()?;
+
+        // Python can also come from the environment.
+        let site_packages = records
+            .iter()
+            .chain(&locked)
+            .find(|record| record.package_record.name.as_normalized() == "python")
+            .map(|python| PythonInfo::from_python_record(&python.package_record, platform))
+            .transpose()
+            .into_diagnostic()?
+            .map(|info| info.site_packages_path);
+
+        Ok(Self {
+            prefix,
+            platform,
+            site_packages,
+        })
+    }
+
+    /// Layers the overlay on top of the activated environment in
+    /// `environment_prefix`. The executables of the overlay take precedence,
+    /// and python in either prefix can import the packages of both.
+    pub(crate) fn apply(&self, environment_prefix: &Path, env: &mut HashMap<String, String>) {
+        let paths = prefix_path_entries(self.prefix.root(), &self.platform);
+        prepend_paths(env, "PATH", paths);
+
+        if let Some(site_packages) = &self.site_packages {
+            let paths = vec![
+                self.prefix.root().join(site_packages),
+                environment_prefix.join(site_packages),
+            ];
+            prepend_paths(env, "PYTHONPATH", paths);
+        }
+    }
+}
+
+/// Returns the name of the cached prefix of an overlay.
+fn overlay_name(
+    environment: &str,
+    specs: &[MatchSpec],
+    locked: &[RepoDataRecord],
+    platform: Platform,
+) -> String {
+    let mut hasher = Xxh3::new();
+    specs
+        .iter()
+        .map(ToString::to_string)
+        .sorted()
+        .collect_vec()
+        .hash(&mut hasher);
+    locked
+        .iter()
+        .map(|record| record.url.as_str())
+        .sorted()
+        .collect_vec()
+        .hash(&mut hasher);
+    platform.hash(&mut hasher);
+    format!("run-with-{environment}-{:x}", hasher.finish())
+}
+
+/// Prepends the paths to the path-like variable `name`. The name of the
+/// variable is matched case-insensitively, like `Path` on Windows.
+fn prepend_paths(env: &mut HashMap<String, String>, name: &str, paths: Vec<PathBuf>) {
+    let key = env
+        .keys()
+        .find(|key| key.eq_ignore_ascii_case(name))
+        .cloned()
+        .unwrap_or_else(|| name.to_string());
+    let current = env.get(&key).cloned().unwrap_or_default();
+    let paths = paths
+        .into_iter()
+        .chain(std::env::split_paths(&current))
+        .filter(|path| !path.as_os_str().is_empty());
+    if let Ok(joined) = std::env::join_paths(paths) {
+        env.insert(key, joined.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_conda_types::{PackageRecord, ParseStrictness, VersionWithSource};
+    use url::Url;
+
+    use super::*;
+
+    fn record(name: &str) -> RepoDataRecord {
+        RepoDataRecord {
+            package_record: PackageRecord::new(
+                name.parse().unwrap(),
+                VersionWithSource::from_str("1.0").unwrap(),
+                String::from("h0_0"),
+            ),
+            file_name: format!("{name}-1.0-h0_0.conda"),
+            url: Url::from_str(&format!(
+                "https://conda.anaconda.org/conda-forge/noarch/{name}-1.0-h0_0.conda"
+            ))
+            .unwrap(),
+            channel: Some(String::from("https://conda.anaconda.org/conda-forge/")),
+        }
+    }
+
+    #[test]
+    fn test_overlay_name() {
+        let specs = [MatchSpec::from_str("rich", ParseStrictness::Lenient).unwrap()];
+        let locked = [record("python")];
+        assert_eq!(
+            overlay_name("default", &specs, &locked, Platform::Linux64),
+            overlay_name("default", &specs, &locked, Platform::Linux64)
+        );
+        assert_ne!(
+            overlay_name("default", &specs, &locked, Platform::Linux64),
+            overlay_name("default", &specs, &[], Platform::Linux64)
+        );
+    }
+
+    #[test]
+    fn test_prepend_paths() {
+        let mut env = HashMap::from([("Path".to_string(), "/usr/bin".to_string())]);
+        prepend_paths(&mut env, "PATH", vec![PathBuf::from("/overlay/bin")]);
+        prepend_paths(&mut env, "PYTHONPATH", vec![PathBuf::from("/overlay/lib")]);
+
+        let path = std::env::split_paths(&env["Path"]).collect_vec();
+        assert_eq!(
+            path,
+            vec![PathBuf::from("/overlay/bin"), PathBuf::from("/usr/bin")]
+        );
+        assert_eq!(env["PYTHONPATH"], "/overlay/lib");
+        assert!(!env.contains_key("PATH"));
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use miette::IntoDiagnostic;
+
+/// The JSON Schema of the manifest that is published with this version of
+/// pixi. It is maintained by hand in `schema/model.py`, not derived from the
+/// manifest types, the examples it is tested with are parsed by the manifest
+/// tests to catch differences. The build script copies it into the output
+/// directory of the crate.
+const MANIFEST_SCHEMA: &str = include_str!(concat!(env!("OUT_DIR"), "/schema.json"));
+
+/// Print the JSON Schema of the manifest.
+///
+/// Editors can use the schema to validate and complete `pixi.toml` files. This
+/// is the schema that is published with this version of pixi, its `$id` is
+/// `https://pixi.sh/v<version>/schema/manifest/schema.json`. It is maintained
+/// next to the manifest parser, so it may not describe every key that the
+/// parser accepts.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Write the schema to this file instead of stdout
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    match args.output {
+        Some(path) => {
+            fs_err::tokio::write(&path, MANIFEST_SCHEMA)
+                .await
+                .into_diagnostic()?;
+            eprintln!(
+                "{}Wrote the manifest schema to {}",
+                console::style(console::Emoji("✔ ", "")).green(),
+                path.display()
+            );
+        }
+        None => print!("{MANIFEST_SCHEMA}"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pixi_consts::consts;
+
+    use super::*;
+
+    #[test]
+    fn test_manifest_schema_is_versioned() {
+        let schema: serde_json::Value = serde_json::from_str(MANIFEST_SCHEMA).unwrap();
+        assert_eq!(
+            schema["$id"],
+            format!(
+                "https://pixi.sh/v{}/schema/manifest/schema.json",
+                consts::PIXI_VERSION
+            )
+        );
+    }
+}
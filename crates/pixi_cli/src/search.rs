@@ -49,10 +49,7 @@ pub async fn execute_impl<W: Write>(
     args: Args,
     out: &mut W,
 ) -> miette::Result<Option<Vec<RepoDataRecord>>> {
-    let workspace = match WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
-        .locate()
-    {
+    let workspace = match args.project_config.workspace_locator().locate() {
         Ok(project) => Some(project),
         Err(WorkspaceLocatorError::WorkspaceNotFound(_)) => {
             debug!("No project file found, continuing without project configuration.",);
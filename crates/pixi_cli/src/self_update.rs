@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::io::{Seek, Write};
+use std::path::PathBuf;
 
 use flate2::read::GzDecoder;
 use tar::Archive;
@@ -13,19 +14,30 @@ use reqwest::redirect::Policy;
 use tempfile::{NamedTempFile, TempDir};
 use url::Url;
 
-use rattler_conda_types::Version;
+use rattler_conda_types::{Version, VersionSpec};
 use std::str::FromStr;
 
 use crate::GlobalOptions;
 use pixi_reporters::format_release_notes;
 
 /// Update pixi to the latest version or a specific version.
+///
+/// The downloaded archive is verified against the minisign signature that is
+/// published with the release, using the public key that is embedded in the
+/// pixi binary. Releases without a signature, and pixi binaries that were
+/// built without the key, fall back to the SHA256 checksum that is published
+/// with the release. Self-update can be disabled by setting
+/// `allow-self-update` to `false` in the configuration.
 #[derive(Debug, clap::Parser)]
 pub struct Args {
     /// The desired version (to downgrade or upgrade to).
     #[clap(long)]
     version: Option<Version>,
 
+    /// The release channel to update from, `beta` includes pre-releases.
+    #[clap(long, value_enum, default_value_t = ReleaseChannel::Stable, conflicts_with = "version")]
+    channel: ReleaseChannel,
+
     /// Only show release notes, do not modify the binary.
     #[clap(long)]
     dry_run: bool,
@@ -38,6 +50,24 @@ pub struct Args {
     /// Skip printing the release notes.
     #[clap(long, default_value_t = false)]
     no_release_note: bool,
+
+    /// Do not verify the signature or checksum of the downloaded archive.
+    #[clap(long, default_value_t = false)]
+    no_verify: bool,
+}
+
+/// The minisign public key that the release archives are signed with. It is
+/// embedded by the release build, a binary that was built without it only
+/// verifies the checksums of the archives it downloads.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("PIXI_RELEASE_PUBLIC_KEY");
+
+/// The release channel of pixi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReleaseChannel {
+    /// The latest release.
+    Stable,
+    /// The latest release, including pre-releases.
+    Beta,
 }
 
 /// Response from the Github API when fetching a release by tag.
@@ -52,6 +82,14 @@ struct ReleaseResponse {
 
     /// The tag name of the release
     tag_name: String,
+
+    /// Whether the release is a draft, drafts are never installed.
+    #[serde(default)]
+    draft: bool,
+
+    /// Whether the release is a pre-release.
+    #[serde(default)]
+    prerelease: bool,
 }
 
 fn user_agent() -> String {
@@ -144,6 +182,210 @@ async fn latest_version() -> miette::Result<Version> {
     }
 }
 
+/// Returns the most recent version, including pre-releases.
+async fn latest_beta_version() -> miette::Result<Version> {
+    let url = format!("{}?per_page=20", consts::RELEASES_API);
+
+    let client = build_reqwest_clients(None, None)?.1;
+    let response = client
+        .get(&url)
+        .header("User-Agent", user_agent())
+        .send()
+        .await
+        .into_diagnostic()?;
+    if !response.status().is_success() {
+        miette::bail!("URL: {}. Request failed: {}", url, response.status());
+    }
+
+    let releases: Vec<ReleaseResponse> = response.json().await.into_diagnostic()?;
+    releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| release.tag_name.strip_prefix('v'))
+        .filter_map(|version| Version::from_str(version).ok())
+        .max()
+        .ok_or_else(|| miette::miette!("URL '{}' does not seem to contain any releases.", url))
+}
+
+/// Returns the most recent stable version that matches the given spec.
+async fn latest_matching_version(spec: &VersionSpec) -> miette::Result<Version> {
+    let url = format!("{}?per_page=100", consts::RELEASES_API);
+
+    let client = build_reqwest_clients(None, None)?.1;
+    let response = client
+        .get(&url)
+        .header("User-Agent", user_agent())
+        .send()
+        .await
+        .into_diagnostic()?;
+    if !response.status().is_success() {
+        miette::bail!("URL: {}. Request failed: {}", url, response.status());
+    }
+
+    let releases: Vec<ReleaseResponse> = response.json().await.into_diagnostic()?;
+    releases
+        .iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .filter_map(|release| release.tag_name.strip_prefix('v'))
+        .filter_map(|version| Version::from_str(version).ok())
+        .filter(|version| spec.matches(version))
+        .max()
+        .ok_or_else(|| miette::miette!("there is no pixi release that matches '{}'", spec))
+}
+
+/// Downloads the most recent release that matches the spec into the cache,
+/// and returns its version and the path of its binary. The binary that is
+/// running is left untouched.
+///
+/// This is used when a workspace requires a different version of pixi than
+/// the one that is running.
+pub async fn download_matching(spec: &VersionSpec) -> miette::Result<(Version, PathBuf)> {
+    let version = latest_matching_version(spec).await?;
+    let binary_path = pixi_config::get_cache_dir()?
+        .join(consts::PIXI_BINARIES_CACHE_DIR)
+        .join(version.to_string())
+        .join(pixi_binary_name());
+    if binary_path.is_file() {
+        return Ok((version, binary_path));
+    }
+
+    let binary_tempdir = download_release(Some(&version), Verification::IfPublished, false).await?;
+    let parent = binary_path
+        .parent()
+        .expect("the binary path has a parent directory");
+    fs_err::create_dir_all(parent).into_diagnostic()?;
+    // Move the binary in place at once, so that a concurrent pixi never runs a
+    // partially written binary.
+    let staged = tempfile::NamedTempFile::new_in(parent).into_diagnostic()?;
+    fs_err::copy(
+        binary_tempdir.path().join(pixi_binary_name()),
+        staged.path(),
+    )
+    .into_diagnostic()?;
+    staged.persist(&binary_path).into_diagnostic()?;
+    Ok((version, binary_path))
+}
+
+/// Downloads a file that is published next to the archive of a release.
+/// Returns `None` if the release does not publish the file.
+async fn fetch_release_file(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    url: &str,
+) -> miette::Result<Option<String>> {
+    let response = client
+        .get(url)
+        .header("User-Agent", user_agent())
+        .send()
+        .await
+        .into_diagnostic()?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(miette::miette!(
+            help = "Pass `--no-verify` to update without verifying the archive",
+            "URL {} returned {}",
+            url,
+            response.status()
+        ));
+    }
+    response.text().await.map(Some).into_diagnostic()
+}
+
+/// How the downloaded archive of a release is verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verification {
+    /// The archive has to be verified against a signature or checksum.
+    Required,
+    /// The archive is verified if the release publishes a signature or
+    /// checksum, otherwise a warning is printed. Old releases that are run
+    /// to satisfy `requires-pixi` may not publish either.
+    IfPublished,
+    /// The archive is not verified.
+    Skip,
+}
+
+/// Verifies the downloaded archive. Returns `false` if the release publishes
+/// neither a signature nor a checksum to verify the archive against.
+///
+/// The minisign signature that is published next to the archive is checked
+/// against the key that is embedded in pixi. Unlike a checksum that is
+/// downloaded from the same place as the archive, the signature can't be
+/// forged by someone who can replace the files of a release. Releases that
+/// were published before the archives were signed, and pixi binaries that
+/// were built without the key, are verified against the published SHA256
+/// checksum instead.
+async fn verify_archive(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    download_url: &str,
+    archive: &NamedTempFile,
+) -> miette::Result<bool> {
+    match RELEASE_PUBLIC_KEY {
+        Some(public_key) => {
+            let signature_url = format!("{download_url}.minisig");
+            if let Some(signature) = fetch_release_file(client, &signature_url).await? {
+                let contents = fs_err::read(archive.path()).into_diagnostic()?;
+                return verify_minisign(public_key, &signature, &contents).map(|_| true);
+            }
+            tracing::warn!(
+                "the release does not publish a signature of the archive, verifying its checksum instead"
+            );
+        }
+        None => tracing::debug!(
+            "pixi was built without the public key of the releases, verifying the checksum of the archive instead"
+        ),
+    }
+    verify_checksum(client, download_url, archive).await
+}
+
+/// Downloads the checksum that is published next to the archive, and checks
+/// that it matches the downloaded archive. Returns `false` if the release
+/// does not publish a checksum.
+async fn verify_checksum(
+    client: &reqwest_middleware::ClientWithMiddleware,
+    download_url: &str,
+    archive: &NamedTempFile,
+) -> miette::Result<bool> {
+    let checksum_url = format!("{download_url}.sha256");
+    let Some(checksum_file) = fetch_release_file(client, &checksum_url).await? else {
+        return Ok(false);
+    };
+    let actual = rattler_digest::compute_file_digest::<rattler_digest::Sha256>(archive.path())
+        .into_diagnostic()?;
+    check_checksum(&checksum_file, &format!("{actual:x}")).map(|_| true)
+}
+
+/// Checks that the checksum file, which has the format
+/// `<hex digest>  <file name>`, contains the `actual` hex digest.
+fn check_checksum(checksum_file: &str, actual: &str) -> miette::Result<()> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| miette::miette!("the published checksum file is empty"))?;
+    if !expected.eq_ignore_ascii_case(actual) {
+        miette::bail!(
+            "the checksum of the downloaded archive ({}) does not match the published checksum ({})",
+            actual,
+            expected
+        );
+    }
+    Ok(())
+}
+
+/// Checks that `signature` is a valid minisign signature of `contents` by
+/// `public_key`.
+fn verify_minisign(public_key: &str, signature: &str, contents: &[u8]) -> miette::Result<()> {
+    let signature = minisign_verify::Signature::decode(signature)
+        .map_err(|err| miette::miette!("the signature of the archive is invalid: {err}"))?;
+    let public_key = minisign_verify::PublicKey::from_base64(public_key.trim())
+        .map_err(|err| miette::miette!("the embedded release key is invalid: {err}"))?;
+    public_key
+        .verify(contents, &signature, false)
+        .map_err(|err| {
+            miette::miette!("the downloaded archive is not signed by the pixi release key: {err}")
+        })
+}
+
 async fn fetch_release_notes(version: &Option<Version>) -> miette::Result<String> {
     let url = if let Some(version) = version {
         format!("{}/v{}", consts::RELEASES_API_BY_TAG, version)
@@ -189,6 +431,13 @@ async fn fetch_release_notes(version: &Option<Version>) -> miette::Result<String
 /// * `args` - The self-update specific arguments.
 /// * `global_options` - Reference to the global CLI options.
 pub async fn execute(args: Args, global_options: &GlobalOptions) -> miette::Result<()> {
+    if !Config::load_global().allow_self_update() {
+        return Err(miette::miette!(
+            help = "Please update pixi the way it was installed, or ask your administrator",
+            "self-update is disabled by the `allow-self-update` configuration"
+        ));
+    }
+
     let is_quiet = global_options.quiet > 0;
     // Get the target version, without 'v' prefix, None for force latest version
     let target_version = match &args.version {
@@ -207,13 +456,11 @@ pub async fn execute(args: Args, global_options: &GlobalOptions) -> miette::Resu
                 Some(version.clone())
             }
         }
-        None => {
-            if args.force {
-                None
-            } else {
-                Some(latest_version().await?)
-            }
-        }
+        None => match args.channel {
+            ReleaseChannel::Beta => Some(latest_beta_version().await?),
+            ReleaseChannel::Stable if args.force => None,
+            ReleaseChannel::Stable => Some(latest_version().await?),
+        },
     };
 
     // Get the current version of the pixi binary
@@ -314,11 +561,58 @@ pub async fn execute(args: Args, global_options: &GlobalOptions) -> miette::Resu
         );
     }
 
+    let binary_tempdir = download_release(
+        target_version.as_ref(),
+        if args.no_verify {
+            Verification::Skip
+        } else {
+            Verification::Required
+        },
+        is_quiet,
+    )
+    .await?;
+
+    // Get the new binary path used for self-replacement
+    let new_binary_path = binary_tempdir.path().join(pixi_binary_name());
+
+    // Replace the current binary with the new binary
+    self_replace::self_replace(new_binary_path).into_diagnostic()?;
+
+    if !is_quiet {
+        if let Some(ref target_version) = target_version {
+            eprintln!(
+                "{}Pixi has been updated to version {}.",
+                console::style(console::Emoji("✔ ", "")).green(),
+                target_version
+            );
+        } else {
+            eprintln!(
+                "{}Pixi has been updated to latest release.",
+                console::style(console::Emoji("✔ ", "")).green(),
+            );
+        }
+    }
+
+    if let Some(fetch_release_warning) = fetch_release_warning {
+        tracing::warn!(fetch_release_warning);
+    }
+
+    Ok(())
+}
+
+/// Downloads the release archive of `target_version`, or of the latest
+/// release, verifies it according to `verification` and unpacks it into a
+/// temporary directory that contains the pixi binary.
+async fn download_release(
+    target_version: Option<&Version>,
+    verification: Verification,
+    is_quiet: bool,
+) -> miette::Result<TempDir> {
     // Get the name of the binary to download and install based on the current platform
     let archive_name = default_archive_name()
         .expect("Could not find the default archive name for the current platform");
 
-    let download_url = if let Some(ref target_version) = target_version {
+    let download_url = if let Some(target_version) = target_version {
         format!(
             "{}/download/v{}/{}",
             consts::RELEASES_URL,
@@ -359,20 +653,40 @@ pub async fn execute(args: Args, global_options: &GlobalOptions) -> miette::Resu
         );
     }
 
+    if verification != Verification::Skip {
+        if verify_archive(&client, &download_url, &archived_tempfile).await? {
+            if !is_quiet {
+                eprintln!(
+                    "{}Pixi archive verified.",
+                    console::style(console::Emoji("✔ ", "")).green(),
+                );
+            }
+        } else if verification == Verification::Required {
+            miette::bail!(
+                help = "Pass `--no-verify` to update without verifying the archive",
+                "the release publishes neither a signature nor a checksum of the archive"
+            );
+        } else {
+            tracing::warn!(
+                "the release publishes neither a signature nor a checksum of the archive, it is used without verification"
+            );
+        }
+    }
+
     // Seek to the beginning of the file before uncompressing it
     archived_tempfile
         .rewind()
         .expect("Failed to rewind the archive file");
 
     // Create a temporary directory to unpack the archive
-    let binary_tempdir = &tempfile::tempdir().into_diagnostic()?;
+    let binary_tempdir = tempfile::tempdir().into_diagnostic()?;
 
     // Uncompress the archive
     if archive_name.ends_with(".tar.gz") {
-        unpack_tar_gz(&archived_tempfile, binary_tempdir)?;
+        unpack_tar_gz(&archived_tempfile, &binary_tempdir)?;
     } else if archive_name.ends_with(".zip") {
         let mut archive = zip::ZipArchive::new(archived_tempfile.as_file()).into_diagnostic()?;
-        archive.extract(binary_tempdir).into_diagnostic()?;
+        archive.extract(&binary_tempdir).into_diagnostic()?;
     } else {
         let error_message = format!("Unsupported archive format: {archive_name}");
         Err(miette::miette!(error_message))?
@@ -385,32 +699,7 @@ pub async fn execute(args: Args, global_options: &GlobalOptions) -> miette::Resu
         );
     }
 
-    // Get the new binary path used for self-replacement
-    let new_binary_path = binary_tempdir.path().join(pixi_binary_name());
-
-    // Replace the current binary with the new binary
-    self_replace::self_replace(new_binary_path).into_diagnostic()?;
-
-    if !is_quiet {
-        if let Some(ref target_version) = target_version {
-            eprintln!(
-                "{}Pixi has been updated to version {}.",
-                console::style(console::Emoji("✔ ", "")).green(),
-                target_version
-            );
-        } else {
-            eprintln!(
-                "{}Pixi has been updated to latest release.",
-                console::style(console::Emoji("✔ ", "")).green(),
-            );
-        }
-    }
-
-    if let Some(fetch_release_warning) = fetch_release_warning {
-        tracing::warn!(fetch_release_warning);
-    }
-
-    Ok(())
+    Ok(binary_tempdir)
 }
 
 /// Return the message that should be shown to users when executing with `--dry-run`.
@@ -474,6 +763,43 @@ pub async fn execute_stub(_: Args, _: &GlobalOptions) -> miette::Result<()> {
 mod tests {
     use std::path::PathBuf;
 
+    #[test]
+    pub fn test_verify_minisign_rejects_checksum() {
+        // A checksum file is not accepted in place of a signature.
+        let err = super::verify_minisign(
+            "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3",
+            "untrusted comment: a checksum\n0123456789abcdef\n",
+            b"archive",
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("signature of the archive is invalid")
+        );
+    }
+
+    #[test]
+    pub fn test_check_checksum() {
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        super::check_checksum(
+            &format!("{digest}  pixi-x86_64-unknown-linux-musl.tar.gz\n"),
+            digest,
+        )
+        .unwrap();
+        super::check_checksum(&digest.to_uppercase(), digest).unwrap();
+
+        let err = super::check_checksum(
+            "0000000000000000000000000000000000000000000000000000000000000000  pixi.tar.gz",
+            digest,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("does not match the published checksum")
+        );
+        assert!(super::check_checksum("", digest).is_err());
+    }
+
     #[test]
     pub fn test_unarchive_flat_structure() {
         // This archive contains a single file named "a_file"
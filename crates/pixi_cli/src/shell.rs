@@ -1,7 +1,7 @@
 use std::{collections::HashMap, io::Write, path::PathBuf};
 
 use clap::Parser;
-use miette::IntoDiagnostic;
+use miette::{IntoDiagnostic, WrapErr};
 use rattler_conda_types::Platform;
 use rattler_shell::{
     activation::PathModificationBehavior,
@@ -11,7 +11,7 @@ use which::which;
 
 use pixi_config::{ConfigCli, ConfigCliActivation, ConfigCliPrompt};
 use pixi_core::{
-    UpdateLockFileOptions, WorkspaceLocator,
+    UpdateLockFileOptions,
     activation::CurrentEnvVarBehavior,
     environment::{InstallFilter, get_update_lock_file_and_prefix},
     lock_file::{ReinstallPackages, UpdateMode},
@@ -41,6 +41,11 @@ pub struct Args {
     #[arg(long, short)]
     environment: Option<String>,
 
+    /// Start the shell without sourcing the rc files of the user, like
+    /// `~/.bashrc` or the PowerShell profile
+    #[arg(long)]
+    no_rc: bool,
+
     #[clap(flatten)]
     prompt_config: ConfigCliPrompt,
 
@@ -64,6 +69,7 @@ fn start_powershell(
     pwsh: PowerShell,
     env: &HashMap<String, String>,
     prompt: String,
+    no_rc: bool,
 ) -> miette::Result<Option<i32>> {
     // create a tempfile for activation
     let mut temp_file = tempfile::Builder::new()
@@ -88,6 +94,9 @@ fn start_powershell(
 
     let mut command = std::process::Command::new(pwsh.executable());
     command.arg("-NoLogo");
+    if no_rc {
+        command.arg("-NoProfile");
+    }
     command.arg("-NoExit");
     command.arg("-File");
     command.arg(&temp_path);
@@ -104,6 +113,7 @@ fn start_cmdexe(
     cmdexe: CmdExe,
     env: &HashMap<String, String>,
     prompt: String,
+    no_rc: bool,
 ) -> miette::Result<Option<i32>> {
     // create a tempfile for activation
     let mut temp_file = tempfile::Builder::new()
@@ -128,6 +138,10 @@ fn start_cmdexe(
     let temp_path = temp_file.into_temp_path();
 
     let mut command = std::process::Command::new(cmdexe.executable());
+    if no_rc {
+        // Skip the `AutoRun` commands from the registry
+        command.arg("/D");
+    }
     command.arg("/K");
     command.arg(&temp_path);
 
@@ -280,6 +294,7 @@ async fn start_nu_shell(
     shell: rattler_shell::shell::NuShell,
     env: &HashMap<String, String>,
     prompt: String,
+    no_rc: bool,
 ) -> miette::Result<Option<i32>> {
     // create a tempfile for activation
     let mut temp_file = tempfile::Builder::new()
@@ -311,6 +326,9 @@ async fn start_nu_shell(
     temp_file.flush().into_diagnostic()?;
 
     let mut command = std::process::Command::new(shell.executable());
+    if no_rc {
+        command.arg("--no-config-file");
+    }
     command.arg("--execute");
     command.arg(format!("source {}", temp_file.path().display()));
 
@@ -324,8 +342,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .merge_config(args.prompt_config.into())
         .merge_config(args.config.clone().into());
 
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(config);
 
@@ -368,7 +387,11 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     tracing::info!("Starting shell: {:?}", interactive_shell);
 
     let prompt_hook = if workspace.config().change_ps1() {
-        let prompt_name = prompt::prompt_name(workspace.display_name(), environment.name());
+        let prompt_name = prompt::prompt_name(
+            workspace.display_name(),
+            environment.name(),
+            workspace.config().shell.prompt_template(),
+        );
         [
             prompt::shell_prompt(&interactive_shell, prompt_name.as_str()),
             prompt::shell_hook(&interactive_shell)
@@ -384,9 +407,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
 
     #[cfg(target_family = "windows")]
     let res = match interactive_shell {
-        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, env, prompt_hook).await,
-        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt_hook),
-        ShellEnum::CmdExe(cmdexe) => start_cmdexe(cmdexe, env, prompt_hook),
+        ShellEnum::NuShell(nushell) => start_nu_shell(nushell, env, prompt_hook, args.no_rc).await,
+        ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt_hook, args.no_rc),
+        ShellEnum::CmdExe(cmdexe) => start_cmdexe(cmdexe, env, prompt_hook, args.no_rc),
         ShellEnum::Bash(bash) => {
             start_winbash(bash, env, prompt_hook, &prefix, source_shell_completions)
         }
@@ -398,12 +421,18 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     #[cfg(target_family = "unix")]
     let res = {
         match interactive_shell {
-            ShellEnum::NuShell(nushell) => start_nu_shell(nushell, env, prompt_hook).await,
-            ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt_hook),
+            ShellEnum::NuShell(nushell) => {
+                start_nu_shell(nushell, env, prompt_hook, args.no_rc).await
+            }
+            ShellEnum::PowerShell(pwsh) => start_powershell(pwsh, env, prompt_hook, args.no_rc),
             ShellEnum::Bash(bash) => {
                 start_unix_shell(
                     bash,
-                    vec!["-i"],
+                    if args.no_rc {
+                        vec!["--norc", "-i"]
+                    } else {
+                        vec!["-i"]
+                    },
                     env,
                     prompt_hook,
                     &prefix,
@@ -414,7 +443,11 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             ShellEnum::Zsh(zsh) => {
                 start_unix_shell(
                     zsh,
-                    vec!["-i"],
+                    if args.no_rc {
+                        vec!["-f", "-i"]
+                    } else {
+                        vec!["-i"]
+                    },
                     env,
                     prompt_hook,
                     &prefix,
@@ -425,7 +458,11 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             ShellEnum::Fish(fish) => {
                 start_unix_shell(
                     fish,
-                    vec![],
+                    if args.no_rc {
+                        vec!["--no-config"]
+                    } else {
+                        vec![]
+                    },
                     env,
                     prompt_hook,
                     &prefix,
@@ -436,7 +473,7 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             ShellEnum::Xonsh(xonsh) => {
                 start_unix_shell(
                     xonsh,
-                    vec![],
+                    if args.no_rc { vec!["--no-rc"] } else { vec![] },
                     env,
                     prompt_hook,
                     &prefix,
@@ -450,12 +487,8 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         }
     };
 
-    match res {
-        Ok(Some(code)) => std::process::exit(code),
-        Ok(None) => std::process::exit(0),
-        Err(e) => {
-            eprintln!("Error starting shell: {e}");
-            std::process::exit(1);
-        }
+    match res.wrap_err("Error starting shell")? {
+        Some(code) => crate::ExitStatus::from_code(code),
+        None => Ok(()),
     }
 }
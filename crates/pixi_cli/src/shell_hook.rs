@@ -1,6 +1,7 @@
 use std::{collections::HashMap, default::Default};
 
 use clap::Parser;
+use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_config::{ConfigCli, ConfigCliActivation, ConfigCliPrompt};
 use rattler_lock::LockFile;
@@ -49,7 +50,8 @@ pub struct Args {
     #[arg(long, short)]
     environment: Option<String>,
 
-    /// Emit the environment variables set by running the activation as JSON
+    /// Emit the environment variables set by running the activation as JSON,
+    /// together with the changes to apply to the current environment
     #[clap(long, default_value = "false", conflicts_with = "shell")]
     json: bool,
 
@@ -60,6 +62,70 @@ pub struct Args {
 #[derive(Serialize)]
 struct ShellEnv<'a> {
     environment_variables: &'a HashMap<String, String>,
+    changes: Vec<EnvVarChange>,
+}
+
+/// A change to a variable of the current environment that is required to
+/// activate the environment.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum EnvVarChange {
+    /// Set the variable to the value.
+    Set { name: String, value: String },
+    /// Prepend the value to the current value of the variable, separated by
+    /// the separator. Used for path-like variables such as `PATH`.
+    Prepend {
+        name: String,
+        value: String,
+        separator: &'static str,
+    },
+    /// Remove the variable from the environment.
+    Unset { name: String },
+}
+
+/// The separator of path-like environment variables on this platform.
+const PATH_SEPARATOR: &str = if cfg!(windows) { ";" } else { ":" };
+
+/// Computes the changes to turn the `current` environment into the activated
+/// one. Variables that the activation clears to an empty value are unset, and
+/// variables whose new value ends with their current value are prepended to.
+fn activation_changes(
+    current: &HashMap<String, String>,
+    activated: &HashMap<String, String>,
+) -> Vec<EnvVarChange> {
+    activated
+        .iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .filter_map(|(name, value)| {
+            let current = current.get(name).filter(|current| !current.is_empty());
+            if current == Some(value) {
+                return None;
+            }
+            let name = name.clone();
+            Some(match current {
+                Some(_) if value.is_empty() => EnvVarChange::Unset { name },
+                None if value.is_empty() => return None,
+                Some(current) => match value
+                    .strip_suffix(current.as_str())
+                    .and_then(|prefix| prefix.strip_suffix(PATH_SEPARATOR))
+                {
+                    Some(prefix) => EnvVarChange::Prepend {
+                        name,
+                        value: prefix.to_string(),
+                        separator: PATH_SEPARATOR,
+                    },
+                    None => EnvVarChange::Set {
+                        name,
+                        value: value.clone(),
+                    },
+                },
+                None => EnvVarChange::Set {
+                    name,
+                    value: value.clone(),
+                },
+            })
+        })
+        .collect()
 }
 
 /// Generates the activation script.
@@ -108,7 +174,11 @@ async fn generate_activation_script(
     let hook = prompt::shell_hook(&shell).unwrap_or_default().to_owned();
 
     if project.config().change_ps1() {
-        let prompt_name = prompt::prompt_name(project.display_name(), environment.name());
+        let prompt_name = prompt::prompt_name(
+            project.display_name(),
+            environment.name(),
+            project.config().shell.prompt_template(),
+        );
         let shell_prompt = prompt::shell_prompt(&shell, prompt_name.as_str());
         Ok([script, hook, shell_prompt].join("\n"))
     } else {
@@ -134,8 +204,10 @@ async fn generate_environment_json(
     )
     .await?;
 
+    let current_env = std::env::vars().collect::<HashMap<_, _>>();
     let shell_env = ShellEnv {
         environment_variables,
+        changes: activation_changes(&current_env, environment_variables),
     };
 
     serde_json::to_string(&shell_env).into_diagnostic()
@@ -147,8 +219,9 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         .activation_config
         .merge_config(args.prompt_config.merge_config(args.config.clone().into()));
 
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
+    let workspace = args
+        .project_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(config);
 
@@ -253,6 +326,48 @@ mod tests {
         assert!(script.contains("$env.CONDA_PREFIX = "));
     }
 
+    #[test]
+    fn test_activation_changes() {
+        let current = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("FOO".to_string(), "old".to_string()),
+            ("BAR".to_string(), "bar".to_string()),
+            ("KEEP".to_string(), "keep".to_string()),
+        ]);
+        let activated = HashMap::from([
+            (
+                "PATH".to_string(),
+                format!("/env/bin{PATH_SEPARATOR}/usr/bin"),
+            ),
+            ("FOO".to_string(), "new".to_string()),
+            ("BAR".to_string(), String::new()),
+            ("KEEP".to_string(), "keep".to_string()),
+            ("CONDA_PREFIX".to_string(), "/env".to_string()),
+        ]);
+
+        assert_eq!(
+            activation_changes(&current, &activated),
+            vec![
+                EnvVarChange::Unset {
+                    name: "BAR".to_string()
+                },
+                EnvVarChange::Set {
+                    name: "CONDA_PREFIX".to_string(),
+                    value: "/env".to_string()
+                },
+                EnvVarChange::Set {
+                    name: "FOO".to_string(),
+                    value: "new".to_string()
+                },
+                EnvVarChange::Prepend {
+                    name: "PATH".to_string(),
+                    value: "/env/bin".to_string(),
+                    separator: PATH_SEPARATOR
+                },
+            ]
+        );
+    }
+
     #[cfg(target_family = "windows")]
     #[tokio::test]
     async fn test_shell_hook_windows() {
@@ -20,7 +20,8 @@ use rattler_conda_types::Platform;
 use serde::Serialize;
 use serde_with::serde_as;
 
-use pixi_core::{Workspace, WorkspaceLocator, workspace::Environment};
+use pixi_core::{Workspace, workspace::Environment};
+use pixi_utils::porcelain;
 
 use crate::{cli_config::WorkspaceConfig, cli_interface::CliInterface};
 
@@ -106,7 +107,9 @@ pub struct AddArgs {
 }
 
 /// Parse a single key-value pair
-fn parse_key_val(s: &str) -> Result<(String, String), Box<dyn Error + Send + Sync + 'static>> {
+pub(crate) fn parse_key_val(
+    s: &str,
+) -> Result<(String, String), Box<dyn Error + Send + Sync + 'static>> {
     let pos = s
         .find('=')
         .ok_or_else(|| format!("invalid KEY=value: no `=` found in `{s}`"))?;
@@ -217,6 +220,8 @@ impl From<AddArgs> for Task {
                 description,
                 clean_env,
                 args,
+                sandbox: Default::default(),
+                log: false,
             }))
         }
     }
@@ -308,10 +313,36 @@ fn print_tasks(
     Ok(())
 }
 
+/// Prints a line with the environment, the name and the description of every
+/// task, sorted by environment and name.
+fn print_tasks_porcelain(
+    task_map: HashMap<EnvironmentName, HashMap<TaskName, Task>>,
+) -> Result<(), std::io::Error> {
+    let mut stdout = std::io::stdout().lock();
+    let tasks = task_map
+        .iter()
+        .flat_map(|(env, tasks)| tasks.iter().map(move |(name, task)| (env, name, task)))
+        .sorted_by(|(a_env, a_name, _), (b_env, b_name, _)| {
+            (a_env.as_str(), a_name.as_str()).cmp(&(b_env.as_str(), b_name.as_str()))
+        });
+    for (env, name, task) in tasks {
+        let line = porcelain::line([
+            env.as_str(),
+            name.as_str(),
+            task.description().unwrap_or_default(),
+        ]);
+        if let Err(err) = writeln!(stdout, "{line}") {
+            if err.kind() == std::io::ErrorKind::BrokenPipe {
+                std::process::exit(0);
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace.clone());
 
@@ -361,6 +392,11 @@ async fn list_tasks(
         return Ok(());
     }
 
+    if porcelain::is_enabled() {
+        print_tasks_porcelain(tasks_per_env).into_diagnostic()?;
+        return Ok(());
+    }
+
     print_tasks(tasks_per_env, args.summary).into_diagnostic()?;
     Ok(())
 }
@@ -408,7 +444,8 @@ async fn remove_tasks(
             args.feature
                 .map_or_else(FeatureName::default, FeatureName::from),
         )
-        .await
+        .await?;
+    Ok(())
 }
 
 fn print_tasks_json(project: &Workspace) -> miette::Result<()> {
@@ -0,0 +1,181 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The number of log files that are kept for every task by default.
+pub(crate) const DEFAULT_LOG_RETENTION: usize = 10;
+
+/// The log file of a single run of a task. The output of the task is written
+/// to the terminal and to the log file at the same time.
+pub(crate) struct TaskLog {
+    path: PathBuf,
+    file: fs_err::File,
+}
+
+impl TaskLog {
+    /// Creates a new log file in `<dir>/<task>/` that is named after the
+    /// current time and the id of this process, so that parallel runs of the
+    /// same task don't write to the same file. Only the `retention` most recent
+    /// log files of the task are kept.
+    pub(crate) fn create(dir: &Path, task: &str, retention: usize) -> io::Result<Self> {
+        let task_dir = dir.join(task_dir_name(task));
+        fs_err::create_dir_all(&task_dir)?;
+
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        let path = task_dir.join(format!("{timestamp}-{}.log", std::process::id()));
+        let file = fs_err::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+
+        prune_logs(&task_dir, retention.max(1))?;
+        Ok(Self { path, file })
+    }
+
+    /// Returns the path of the log file.
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns a writer that writes to `terminal` and to the log file.
+    pub(crate) fn tee<W: Write>(&self, terminal: W) -> io::Result<Tee<W>> {
+        Ok(Tee {
+            terminal,
+            file: self.file.try_clone()?,
+        })
+    }
+}
+
+/// A writer that copies everything that is written to the terminal into a log
+/// file.
+pub(crate) struct Tee<W> {
+    terminal: W,
+    file: fs_err::File,
+}
+
+impl<W: Write> Write for Tee<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.terminal.write(buf)?;
+        self.file.write_all(&buf[..written])?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.terminal.flush()?;
+        self.file.flush()
+    }
+}
+
+/// A writer that starts every line with the name of the task, so that the
+/// output of several tasks can be told apart when it is not written to a
+/// terminal.
+pub(crate) struct PrefixedLines<W> {
+    inner: W,
+    prefix: String,
+    line_start: bool,
+}
+
+impl<W> PrefixedLines<W> {
+    pub(crate) fn new(inner: W, task: &str) -> Self {
+        Self {
+            inner,
+            prefix: format!("[{task}] "),
+            line_start: true,
+        }
+    }
+}
+
+impl<W: Write> Write for PrefixedLines<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in buf.split_inclusive(|&b| b == b'\n') {
+            if self.line_start {
+                self.inner.write_all(self.prefix.as_bytes())?;
+            }
+            self.inner.write_all(line)?;
+            self.line_start = line.ends_with(b"\n");
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns the name of the directory of the logs of a task. Characters that
+/// are not allowed in file names on every platform, like the `:` of
+/// `build:docs`, are replaced.
+fn task_dir_name(task: &str) -> String {
+    task.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Removes all but the `retention` most recent log files in `dir`. The names
+/// of the log files start with a timestamp, so sorting them by name sorts them
+/// by age.
+fn prune_logs(dir: &Path, retention: usize) -> io::Result<()> {
+    let mut logs = fs_err::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .collect::<Vec<_>>();
+    logs.sort();
+    let outdated = logs.len().saturating_sub(retention);
+    for path in logs.into_iter().take(outdated) {
+        fs_err::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_log_retention() {
+        let dir = tempfile::tempdir().unwrap();
+        let task_dir = dir.path().join("build_docs");
+        fs_err::create_dir_all(&task_dir).unwrap();
+        for name in ["20240101T000000.000Z-1.log", "20240102T000000.000Z-1.log"] {
+            fs_err::write(task_dir.join(name), "old").unwrap();
+        }
+
+        let log = TaskLog::create(dir.path(), "build:docs", 2).unwrap();
+        let mut tee = log.tee(Vec::new()).unwrap();
+        tee.write_all(b"hello\n").unwrap();
+        assert_eq!(tee.terminal, b"hello\n");
+        assert_eq!(fs_err::read_to_string(log.path()).unwrap(), "hello\n");
+
+        let mut remaining = fs_err::read_dir(&task_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            vec![
+                task_dir.join("20240102T000000.000Z-1.log"),
+                log.path().to_path_buf()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prefixed_lines() {
+        let mut output = PrefixedLines::new(Vec::new(), "build:docs");
+        output.write_all(b"one\ntw").unwrap();
+        output.write_all(b"o\n\nthree").unwrap();
+        assert_eq!(
+            String::from_utf8(output.inner).unwrap(),
+            "[build:docs] one\n[build:docs] two\n[build:docs] \n[build:docs] three"
+        );
+    }
+}
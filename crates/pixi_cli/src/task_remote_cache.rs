@@ -0,0 +1,180 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use miette::{Context, IntoDiagnostic};
+use pixi_config::{TaskCacheConfig, TaskCacheMode};
+use pixi_task::ComputationHash;
+use rattler_networking::LazyClient;
+use reqwest::StatusCode;
+use url::Url;
+
+/// A remote cache that shares the outputs of tasks between machines, e.g.
+/// between CI and developers.
+///
+/// The outputs of a run of a task are stored as a gzipped tarball that is
+/// named after the input hash of the task, so a task whose command, inputs and
+/// environment did not change can restore its outputs instead of running.
+pub(crate) struct RemoteTaskCache {
+    url: Url,
+    mode: TaskCacheMode,
+    client: LazyClient,
+}
+
+impl RemoteTaskCache {
+    /// Constructs the remote cache from the configuration, returns `None` if
+    /// no remote cache is configured.
+    pub(crate) fn from_config(config: &TaskCacheConfig, client: LazyClient) -> Option<Self> {
+        Some(Self {
+            url: config.url.clone()?,
+            mode: config.mode(),
+            client,
+        })
+    }
+
+    /// Returns the URL of the outputs of the task with the given input hash.
+    fn archive_url(&self, input_hash: &ComputationHash) -> Url {
+        let mut url = self.url.clone();
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments
+                .pop_if_empty()
+                .push(&format!("{input_hash}.tar.gz"));
+        }
+        url
+    }
+
+    /// Downloads the outputs of the task with the given input hash and unpacks
+    /// them into `root`. Returns `false` if the cache has no outputs for the
+    /// task.
+    pub(crate) async fn restore(
+        &self,
+        input_hash: &ComputationHash,
+        root: &Path,
+    ) -> miette::Result<bool> {
+        let url = self.archive_url(input_hash);
+        let response = self
+            .client
+            .client()
+            .get(url.clone())
+            .send()
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to reach the remote task cache at '{url}'"))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        let archive = response
+            .error_for_status()
+            .into_diagnostic()?
+            .bytes()
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to download '{url}'"))?;
+
+        let root = root.to_path_buf();
+        tokio::task::spawn_blocking(move || unpack_outputs(&archive, &root))
+            .await
+            .unwrap_or_else(|e| match e.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(_err) => Err(io::Error::other("the operation was cancelled")),
+            })
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to unpack the task outputs of '{url}'"))?;
+        Ok(true)
+    }
+
+    /// Uploads the `outputs` of the task with the given input hash, the paths
+    /// are relative to `root`. Nothing is uploaded in read-only mode.
+    pub(crate) async fn upload(
+        &self,
+        input_hash: &ComputationHash,
+        root: &Path,
+        outputs: Vec<PathBuf>,
+    ) -> miette::Result<()> {
+        if self.mode != TaskCacheMode::ReadWrite || outputs.is_empty() {
+            return Ok(());
+        }
+        if self.url.scheme() == "s3" {
+            miette::bail!(
+                "uploading task outputs to '{}' is not supported, an `s3://` task cache can only be read from",
+                self.url
+            );
+        }
+
+        let root = root.to_path_buf();
+        let archive = tokio::task::spawn_blocking(move || pack_outputs(&root, &outputs))
+            .await
+            .unwrap_or_else(|e| match e.try_into_panic() {
+                Ok(panic) => std::panic::resume_unwind(panic),
+                Err(_err) => Err(io::Error::other("the operation was cancelled")),
+            })
+            .into_diagnostic()
+            .wrap_err("failed to pack the outputs of the task")?;
+
+        let url = self.archive_url(input_hash);
+        self.client
+            .client()
+            .put(url.clone())
+            .body(archive)
+            .send()
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to reach the remote task cache at '{url}'"))?
+            .error_for_status()
+            .into_diagnostic()?;
+        Ok(())
+    }
+}
+
+/// Packs the `outputs`, relative to `root`, into a gzipped tarball.
+fn pack_outputs(root: &Path, outputs: &[PathBuf]) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    builder.follow_symlinks(false);
+    for path in outputs {
+        builder.append_path_with_name(root.join(path), path)?;
+    }
+    builder.into_inner()?.finish()
+}
+
+/// Unpacks a tarball created by [`pack_outputs`] into `root`. Entries that
+/// would be written outside of `root` are skipped.
+fn unpack_outputs(archive: &[u8], root: &Path) -> io::Result<()> {
+    tar::Archive::new(GzDecoder::new(archive)).unpack(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_url() {
+        let cache = RemoteTaskCache {
+            url: Url::parse("https://cache.example.com/tasks/").unwrap(),
+            mode: TaskCacheMode::ReadOnly,
+            client: LazyClient::default(),
+        };
+        assert_eq!(
+            cache
+                .archive_url(&ComputationHash::from("abc123".to_string()))
+                .as_str(),
+            "https://cache.example.com/tasks/abc123.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_pack_and_unpack_outputs() {
+        let source = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(source.path().join("build")).unwrap();
+        fs_err::write(source.path().join("build/out.txt"), "output").unwrap();
+        let archive = pack_outputs(source.path(), &[PathBuf::from("build/out.txt")]).unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        unpack_outputs(&archive, target.path()).unwrap();
+        assert_eq!(
+            fs_err::read_to_string(target.path().join("build/out.txt")).unwrap(),
+            "output"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use deno_task_shell::KillSignal;
+
+/// Run a task script with the task shell.
+///
+/// This is used by `pixi run --sandbox` to run the script of a task inside of
+/// the sandbox, the environment of the task is inherited from the caller.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The working directory of the script
+    #[arg(long)]
+    pub cwd: PathBuf,
+
+    /// The script to run
+    pub script: String,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let script = deno_task_shell::parser::parse(&args.script)
+        .map_err(|err| miette::miette!("failed to parse the task script: {err}"))?;
+    let code = deno_task_shell::execute(
+        script,
+        std::env::vars_os().collect(),
+        args.cwd,
+        Default::default(),
+        KillSignal::default(),
+    )
+    .await;
+    crate::ExitStatus::from_code(code)
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use comfy_table::{Cell, CellAlignment, ContentArrangement, Table, presets::NOTHING};
+use console::Style;
+use itertools::Itertools;
+use pixi_utils::timings::{self, PhaseTiming};
+use serde::Serialize;
+
+/// The format of the timing report printed by `--timings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TimingsFormat {
+    Table,
+    Json,
+}
+
+#[derive(Serialize)]
+struct Report {
+    /// The total duration of the command in seconds.
+    total: f64,
+    phases: Vec<PhaseTiming>,
+}
+
+/// Prints the recorded timings of the phases of the command to stderr.
+pub(crate) fn print_report(format: TimingsFormat, total: Duration) {
+    let phases = timings::summary();
+    match format {
+        TimingsFormat::Json => {
+            let report = Report {
+                total: total.as_secs_f64(),
+                phases,
+            };
+            match serde_json::to_string(&report) {
+                Ok(json) => eprintln!("{json}"),
+                Err(err) => tracing::warn!("failed to serialize the timings: {err}"),
+            }
+        }
+        TimingsFormat::Table => {
+            let mut table = Table::new();
+            table
+                .load_preset(NOTHING)
+                .set_content_arrangement(ContentArrangement::Disabled);
+            let header_style = Style::new().bold().cyan();
+            table.set_header(
+                ["Phase", "Count", "Duration", "Cumulative"]
+                    .map(|header| Cell::new(header_style.apply_to(header))),
+            );
+            for timing in &phases {
+                table.add_row([
+                    Cell::new(timing.phase),
+                    Cell::new(timing.count).set_alignment(CellAlignment::Right),
+                    Cell::new(format_duration(timing.duration)).set_alignment(CellAlignment::Right),
+                    Cell::new(format_duration(timing.cumulative))
+                        .set_alignment(CellAlignment::Right),
+                ]);
+            }
+            table.add_row([
+                Cell::new(console::style("total").bold()),
+                Cell::new(""),
+                Cell::new(format_duration(total)).set_alignment(CellAlignment::Right),
+                Cell::new(""),
+            ]);
+            eprintln!(
+                "{}",
+                table
+                    .lines()
+                    .map(|line| line.trim_end().to_string())
+                    .format("\n")
+            );
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
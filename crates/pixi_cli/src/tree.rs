@@ -9,8 +9,8 @@ use console::Color;
 use fancy_display::FancyDisplay;
 use itertools::Itertools;
 use miette::WrapErr;
+use pixi_core::lock_file::UpdateLockFileOptions;
 use pixi_core::workspace::Environment;
-use pixi_core::{WorkspaceLocator, lock_file::UpdateLockFileOptions};
 use pixi_manifest::FeaturesExt;
 use rattler_conda_types::Platform;
 use rattler_lock::LockedPackageRef;
@@ -65,9 +65,7 @@ pub struct PackageInfo {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let environment = workspace
         .environment_from_name_or_env_var(args.environment)
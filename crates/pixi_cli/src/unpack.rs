@@ -0,0 +1,430 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::LazyLock,
+};
+
+use clap::Parser;
+use miette::{IntoDiagnostic, WrapErr};
+use pixi_config::{Config, ConfigCli};
+use pixi_install_pypi::{
+    PyPIBuildConfig, PyPIContextConfig, PyPIEnvironmentUpdater, PyPIUpdateConfig,
+};
+use pixi_manifest::{
+    EnvironmentName, SystemRequirements,
+    pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation},
+};
+use pixi_progress::global_multi_progress;
+use pixi_python_status::PythonStatus;
+use pixi_record::PixiRecord;
+use pixi_utils::prefix::Prefix;
+use pixi_uv_context::UvResolutionContext;
+use rattler::{
+    install::{IndicatifReporter, Installer},
+    package_cache::PackageCache,
+};
+use rattler_conda_types::Platform;
+use rattler_lock::{PypiPackageData, PypiPackageEnvironmentData, UrlOrPath};
+use rattler_shell::{
+    activation::{ActivationVariables, Activator, PathModificationBehavior},
+    shell::{Shell, ShellEnum},
+};
+use url::Url;
+use uv_configuration::RAYON_INITIALIZE;
+
+use crate::pack::{
+    ARCHIVE_MARKER, CHANNEL_DIR, METADATA_FILE_NAME, PYPI_DIR, PackMetadata, record_hashes,
+    verify_hash,
+};
+
+/// Unpack an environment that was packed with `pixi pack`.
+///
+/// The environment is installed without network access into the `env`
+/// directory of the output directory. An activation script for the
+/// environment is written next to it.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The archive created by `pixi pack`, or a directory with its extracted
+    /// contents
+    pub input: PathBuf,
+
+    /// The directory to create the environment and activation script in
+    #[arg(long, short, default_value = ".")]
+    pub output_directory: PathBuf,
+
+    /// The name of the directory of the environment
+    #[arg(long, default_value = "env")]
+    pub env_name: String,
+
+    /// The shell to write the activation script for, options: [`bash`, `zsh`,
+    /// `xonsh`, `cmd`, `powershell`, `fish`, `nushell`]
+    #[arg(short, long)]
+    pub shell: Option<ShellEnum>,
+
+    #[clap(flatten)]
+    pub config: ConfigCli,
+}
+
+/// Returns the offset of the archive in a file, which is after the installer
+/// script for a self-extracting archive.
+fn archive_offset(file: &mut fs_err::File) -> miette::Result<u64> {
+    let mut reader = BufReader::new(file.file());
+    let mut offset = 0;
+    let mut line = Vec::new();
+    // The installer script is only a few lines long.
+    for _ in 0..32 {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).into_diagnostic()?;
+        if read == 0 {
+            break;
+        }
+        offset += read as u64;
+        if line.trim_ascii_end() == ARCHIVE_MARKER.as_bytes() {
+            return Ok(offset);
+        }
+    }
+    Ok(0)
+}
+
+/// Extracts the archive into the given directory.
+fn extract(archive: &Path, destination: &Path) -> miette::Result<()> {
+    let mut file = fs_err::File::open(archive).into_diagnostic()?;
+    let offset = archive_offset(&mut file)?;
+    file.seek(SeekFrom::Start(offset)).into_diagnostic()?;
+    tar::Archive::new(file)
+        .unpack(destination)
+        .into_diagnostic()
+}
+
+/// Reads the description of the packed environment.
+fn read_metadata(source: &Path) -> miette::Result<PackMetadata> {
+    serde_json::from_str(
+        &fs_err::read_to_string(source.join(METADATA_FILE_NAME))
+            .into_diagnostic()
+            .wrap_err("the input was not created by `pixi pack`")?,
+    )
+    .into_diagnostic()
+}
+
+/// Verifies that the packed files match the hashes that were locked when the
+/// environment was packed.
+fn verify_packed_files(source: &Path, metadata: &PackMetadata) -> miette::Result<()> {
+    for record in &metadata.conda_packages {
+        let path = source
+            .join(CHANNEL_DIR)
+            .join(&record.package_record.subdir)
+            .join(&record.file_name);
+        verify_hash(&path, record_hashes(record).as_ref())?;
+    }
+    for package in &metadata.pypi_packages {
+        let path = source.join(PYPI_DIR).join(&package.file_name);
+        verify_hash(&path, package.hash.as_ref())?;
+    }
+    Ok(())
+}
+
+/// Returns the URL of a packed file.
+fn file_url(path: &Path) -> miette::Result<Url> {
+    Url::from_file_path(path).map_err(|_| miette::miette!("'{}' is not absolute", path.display()))
+}
+
+/// Writes a script that activates the environment.
+fn write_activation_script(
+    prefix: &Path,
+    output_directory: &Path,
+    shell: ShellEnum,
+    platform: Platform,
+) -> miette::Result<PathBuf> {
+    let activator = Activator::from_path(prefix, shell.clone(), platform).into_diagnostic()?;
+    let result = activator
+        .activation(ActivationVariables {
+            conda_prefix: None,
+            path: None,
+            path_modification_behavior: PathModificationBehavior::Prepend,
+            current_env: HashMap::new(),
+        })
+        .into_diagnostic()?;
+    let path = output_directory.join(format!("activate.{}", shell.extension()));
+    fs_err::write(&path, result.script.contents().into_diagnostic()?).into_diagnostic()?;
+    Ok(path)
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let config = Config::with_cli_config(&args.config);
+
+    // Extract the archive unless it was already extracted.
+    let extracted;
+    let source = if args.input.is_dir() {
+        args.input.clone()
+    } else {
+        extracted = tempfile::tempdir().into_diagnostic()?;
+        extract(&args.input, extracted.path())
+            .wrap_err_with(|| format!("failed to extract '{}'", args.input.display()))?;
+        extracted.path().to_path_buf()
+    };
+    let source = dunce::canonicalize(&source).into_diagnostic()?;
+
+    let metadata = read_metadata(&source)?;
+    verify_packed_files(&source, &metadata)?;
+    if metadata.platform != Platform::current() {
+        tracing::warn!(
+            "the environment was packed for {}, but this is {}",
+            metadata.platform,
+            Platform::current()
+        );
+    }
+
+    // Point the records to the packed packages.
+    let records = metadata
+        .conda_packages
+        .into_iter()
+        .map(|mut record| {
+            record.url = file_url(
+                &source
+                    .join(CHANNEL_DIR)
+                    .join(&record.package_record.subdir)
+                    .join(&record.file_name),
+            )?;
+            Ok(record)
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    fs_err::create_dir_all(&args.output_directory).into_diagnostic()?;
+    let output_directory = dunce::canonicalize(&args.output_directory).into_diagnostic()?;
+    let prefix = Prefix::new(output_directory.join(&args.env_name));
+
+    // Force the initialization of the rayon thread pool to avoid implicit creation
+    // by the Installer.
+    LazyLock::force(&RAYON_INITIALIZE);
+
+    // The conda packages are installed from the archive, rattler rewrites the
+    // prefix placeholders in the packages to the new location.
+    let cache_dir = pixi_config::get_cache_dir().wrap_err("failed to determine cache directory")?;
+    let result = Installer::new()
+        .with_target_platform(metadata.platform)
+        .with_reporter(
+            IndicatifReporter::builder()
+                .with_multi_progress(global_multi_progress())
+                .clear_when_done(true)
+                .finish(),
+        )
+        .with_package_cache(PackageCache::new(
+            cache_dir.join(pixi_consts::consts::CONDA_PACKAGE_CACHE_DIR),
+        ))
+        .install(prefix.root(), records.clone())
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to install the conda packages")?;
+
+    if !metadata.pypi_packages.is_empty() {
+        let pypi_records = metadata
+            .pypi_packages
+            .iter()
+            .map(|package| {
+                let path = source.join(PYPI_DIR).join(&package.file_name);
+                let data = PypiPackageData {
+                    name: pep508_rs::PackageName::from_str(&package.name).into_diagnostic()?,
+                    version: pep440_rs::Version::from_str(&package.version).into_diagnostic()?,
+                    location: UrlOrPath::Path(path.to_string_lossy().as_ref().into()),
+                    hash: package.hash.clone(),
+                    requires_dist: Vec::new(),
+                    requires_python: None,
+                    editable: false,
+                };
+                Ok((data, PypiPackageEnvironmentData::default()))
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+        let pixi_records = records
+            .into_iter()
+            .map(PixiRecord::Binary)
+            .collect::<Vec<_>>();
+
+        let environment_name =
+            EnvironmentName::from_str(&metadata.environment).unwrap_or(EnvironmentName::Default);
+        let uv_context = UvResolutionContext::from_config(&config)?;
+        let no_build = NoBuild::All;
+        PyPIEnvironmentUpdater::new(
+            PyPIUpdateConfig {
+                environment_name: &environment_name,
+                prefix: &prefix,
+                platform: metadata.platform,
+                lock_file_dir: &source,
+                system_requirements: &SystemRequirements::default(),
+                platform_tag: None,
+            },
+            PyPIBuildConfig {
+                no_build_isolation: &NoBuildIsolation::default(),
+                no_build: &no_build,
+                no_binary: &NoBinary::default(),
+                index_strategy: None,
+                exclude_newer: None,
+            },
+            PyPIContextConfig {
+                uv_context: &uv_context,
+                pypi_indexes: None,
+                environment_variables_lazy: None,
+            },
+        )
+        .update(
+            &PythonStatus::from_transaction(&result.transaction),
+            &pixi_records,
+            &pypi_records,
+        )
+        .await
+        .wrap_err("failed to install the PyPI packages")?;
+    }
+
+    let shell = args.shell.unwrap_or_default();
+    let activation_script =
+        write_activation_script(prefix.root(), &output_directory, shell, metadata.platform)?;
+
+    eprintln!(
+        "{}Unpacked the environment into {}\n  Activate it with the script {}",
+        console::style(console::Emoji("✔ ", "")).green(),
+        prefix.root().display(),
+        activation_script.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use rattler_conda_types::{PackageRecord, RepoDataRecord, VersionWithSource};
+    use rattler_digest::{Sha256, compute_bytes_digest, compute_file_digest};
+    use rattler_lock::PackageHashes;
+    use rattler_networking::LazyClient;
+
+    use super::*;
+    use crate::pack::{PackedPypiPackage, download, write_archive, write_repodata};
+
+    #[tokio::test]
+    async fn test_pack_unpack_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = LazyClient::default();
+
+        let package = dir.path().join("foo-1.0-h0_0.conda");
+        fs_err::write(&package, b"package").unwrap();
+        let mut package_record = PackageRecord::new(
+            "foo".parse().unwrap(),
+            VersionWithSource::from_str("1.0").unwrap(),
+            String::from("h0_0"),
+        );
+        package_record.subdir = String::from("noarch");
+        package_record.sha256 = Some(compute_file_digest::<Sha256>(&package).unwrap());
+        let record = RepoDataRecord {
+            package_record,
+            file_name: String::from("foo-1.0-h0_0.conda"),
+            url: Url::from_file_path(&package).unwrap(),
+            channel: None,
+        };
+
+        let wheel = dir.path().join("bar-1.0-py3-none-any.whl");
+        fs_err::write(&wheel, b"wheel").unwrap();
+        let wheel_hash = PackageHashes::Sha256(compute_file_digest::<Sha256>(&wheel).unwrap());
+        let pypi_package = PackedPypiPackage {
+            name: String::from("bar"),
+            version: String::from("1.0"),
+            file_name: String::from("bar-1.0-py3-none-any.whl"),
+            hash: Some(wheel_hash.clone()),
+        };
+
+        // Pack the environment.
+        let staging = dir.path().join("staging");
+        write_repodata(&staging.join(CHANNEL_DIR), std::slice::from_ref(&record)).unwrap();
+        fs_err::create_dir_all(staging.join(PYPI_DIR)).unwrap();
+        download(
+            &client,
+            &record.url,
+            &staging
+                .join(CHANNEL_DIR)
+                .join("noarch")
+                .join(&record.file_name),
+            record_hashes(&record).as_ref(),
+        )
+        .await
+        .unwrap();
+        download(
+            &client,
+            &Url::from_file_path(&wheel).unwrap(),
+            &staging.join(PYPI_DIR).join(&pypi_package.file_name),
+            pypi_package.hash.as_ref(),
+        )
+        .await
+        .unwrap();
+        let metadata = PackMetadata {
+            version: 1,
+            environment: String::from("default"),
+            platform: Platform::NoArch,
+            conda_packages: vec![record.clone()],
+            pypi_packages: vec![pypi_package],
+        };
+        fs_err::write(
+            staging.join(METADATA_FILE_NAME),
+            serde_json::to_string_pretty(&metadata).unwrap(),
+        )
+        .unwrap();
+        let archive = dir.path().join("environment.sh");
+        write_archive(&staging, &archive, true).unwrap();
+
+        // Unpack it again, the hashes survive the roundtrip.
+        let extracted = dir.path().join("extracted");
+        fs_err::create_dir_all(&extracted).unwrap();
+        extract(&archive, &extracted).unwrap();
+        let metadata = read_metadata(&extracted).unwrap();
+        assert_eq!(
+            metadata.conda_packages[0].package_record.sha256,
+            record.package_record.sha256
+        );
+        assert_eq!(metadata.pypi_packages[0].hash, Some(wheel_hash));
+        verify_packed_files(&extracted, &metadata).unwrap();
+
+        // A modified file is rejected.
+        fs_err::write(
+            extracted
+                .join(PYPI_DIR)
+                .join(&metadata.pypi_packages[0].file_name),
+            b"modified",
+        )
+        .unwrap();
+        assert!(verify_packed_files(&extracted, &metadata).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_download_verifies_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("foo.whl");
+        fs_err::write(&source, b"wheel").unwrap();
+        let url = Url::from_file_path(&source).unwrap();
+        let destination = dir.path().join("copy.whl");
+
+        let hash = PackageHashes::Sha256(compute_bytes_digest::<Sha256>(b"something else"));
+        assert!(
+            download(&LazyClient::default(), &url, &destination, Some(&hash))
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_archive_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("environment.sh");
+        let header = format!("#!/bin/sh\nexit 0\n{ARCHIVE_MARKER}\n");
+        let mut file = fs_err::File::create(&path).unwrap();
+        file.write_all(header.as_bytes()).unwrap();
+        file.write_all(b"archive").unwrap();
+        drop(file);
+
+        let mut file = fs_err::File::open(&path).unwrap();
+        assert_eq!(archive_offset(&mut file).unwrap(), header.len() as u64);
+
+        let path = dir.path().join("environment.tar");
+        fs_err::write(&path, b"archive").unwrap();
+        let mut file = fs_err::File::open(&path).unwrap();
+        assert_eq!(archive_offset(&mut file).unwrap(), 0);
+    }
+}
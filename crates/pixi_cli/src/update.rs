@@ -6,7 +6,6 @@ use itertools::Itertools;
 use miette::{Context, IntoDiagnostic, MietteDiagnostic};
 use pixi_config::ConfigCli;
 use pixi_consts::consts;
-use pixi_core::WorkspaceLocator;
 use pixi_core::{
     Workspace,
     lock_file::{UpdateContext, filter_lock_file},
@@ -16,7 +15,10 @@ use pixi_manifest::EnvironmentName;
 use rattler_conda_types::Platform;
 use rattler_lock::{LockFile, LockedPackageRef};
 
-use crate::cli_config::WorkspaceConfig;
+use crate::cli_config::{
+    ChannelPriorityConfig, ExcludeNewerConfig, PypiBuildConfig, SolveStrategyConfig,
+    WorkspaceConfig,
+};
 
 /// The `update` command checks if there are newer versions of the dependencies and updates the `pixi.lock` file and environments accordingly.
 ///
@@ -41,6 +43,18 @@ pub struct Args {
     #[clap(flatten)]
     pub specs: UpdateSpecsArgs,
 
+    #[clap(flatten)]
+    pub solve_strategy_config: SolveStrategyConfig,
+
+    #[clap(flatten)]
+    pub channel_priority_config: ChannelPriorityConfig,
+
+    #[clap(flatten)]
+    pub exclude_newer_config: ExcludeNewerConfig,
+
+    #[clap(flatten)]
+    pub pypi_build_config: PypiBuildConfig,
+
     /// Output the changes in JSON format.
     #[clap(long)]
     pub json: bool,
@@ -125,10 +139,15 @@ impl UpdateSpecs {
 
 pub async fn execute(args: Args) -> miette::Result<()> {
     let config = args.config;
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.project_config.workspace_locator_start())
+    let workspace = args
+        .project_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(config);
+    let workspace = args.solve_strategy_config.apply(workspace);
+    let workspace = args.channel_priority_config.apply(workspace);
+    let workspace = args.exclude_newer_config.apply(workspace);
+    let workspace = args.pypi_build_config.apply(workspace);
 
     let specs = UpdateSpecs::from(args.specs);
 
@@ -1,4 +1,4 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, str::FromStr};
 
 use clap::Parser;
 use fancy_display::FancyDisplay;
@@ -8,7 +8,6 @@ use miette::{IntoDiagnostic, MietteDiagnostic, WrapErr};
 use pep508_rs::{MarkerTree, Requirement};
 use pixi_config::ConfigCli;
 use pixi_core::{
-    WorkspaceLocator,
     lock_file::UpdateContext,
     workspace::{MatchSpecs, PypiDeps, WorkspaceMut},
 };
@@ -16,7 +15,7 @@ use pixi_diff::{LockFileDiff, LockFileJsonDiff};
 use pixi_manifest::{FeatureName, SpecType};
 use pixi_pypi_spec::PixiPypiSpec;
 use pixi_spec::PixiSpec;
-use rattler_conda_types::{MatchSpec, Platform, StringMatcher};
+use rattler_conda_types::{MatchSpec, PackageName, Platform, StringMatcher};
 
 use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
 
@@ -65,8 +64,9 @@ pub struct UpgradeSpecsArgs {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone());
 
@@ -130,6 +130,16 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         }
     }
 
+    // Let the user know which packages are held back by a blanket upgrade.
+    let held = &workspace.workspace().workspace.value.workspace.hold;
+    if !held.is_empty() && args.specs.packages.is_none() && !args.json {
+        eprintln!(
+            "{}Not upgrading held packages: {}",
+            console::style(console::Emoji("ℹ️ ", "")).yellow(),
+            held.iter().map(PackageName::as_source).format(", ")
+        );
+    }
+
     let specs_by_feature = features
         .into_iter()
         .map(|f| {
@@ -266,6 +276,45 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     Ok(())
 }
 
+/// Returns true if the package was passed explicitly on the command line.
+fn is_requested(name: &str, args: &Args) -> bool {
+    args.specs
+        .packages
+        .as_ref()
+        .is_some_and(|packages| packages.iter().any(|package| package == name))
+}
+
+/// Returns true if the package should not be upgraded, either because it was
+/// excluded on the command line or because it is held in the manifest. Held
+/// packages are still upgraded when they are requested explicitly.
+fn is_excluded(name: &str, args: &Args, workspace: &WorkspaceMut) -> bool {
+    if args
+        .specs
+        .exclude
+        .as_ref()
+        .is_some_and(|exclude| exclude.iter().any(|excluded| excluded == name))
+    {
+        return true;
+    }
+    !is_requested(name, args)
+        && workspace
+            .workspace()
+            .workspace
+            .value
+            .workspace
+            .hold
+            .iter()
+            .any(|held| is_same_package(held, name))
+}
+
+/// Returns true if the held package refers to the normalized name of a conda
+/// or a PyPI dependency.
+fn is_same_package(held: &PackageName, name: &str) -> bool {
+    held.as_normalized() == name
+        || pep508_rs::PackageName::from_str(held.as_normalized())
+            .is_ok_and(|held| held.as_ref() == name)
+}
+
 /// A grouping of dependency specs by target table.
 struct SpecsByTarget {
     default_match_specs: MatchSpecs,
@@ -381,12 +430,8 @@ pub fn parse_specs_for_platform(
         .flat_map(|deps| deps.into_owned().into_specs());
     // Note: package existence is validated across all platforms in `execute`.
     let match_specs = match_spec_iter
-        // Don't upgrade excluded packages
-        .filter(|(name, _)| match &args.specs.exclude {
-            None => true,
-            Some(exclude) if exclude.contains(&name.as_normalized().to_string()) => false,
-            _ => true,
-        })
+        // Don't upgrade excluded or held packages
+        .filter(|(name, _)| !is_excluded(name.as_normalized(), args, workspace))
         // If specific packages have been requested, only upgrade those
         .filter(|(name, _)| match &args.specs.packages {
             None => true,
@@ -454,12 +499,8 @@ pub fn parse_specs_for_platform(
         })
         .collect();
     let pypi_deps = pypi_deps_iter
-        // Don't upgrade excluded packages
-        .filter(|(name, _)| match &args.specs.exclude {
-            None => true,
-            Some(exclude) if exclude.contains(&name.as_normalized().to_string()) => false,
-            _ => true,
-        })
+        // Don't upgrade excluded or held packages
+        .filter(|(name, _)| !is_excluded(name.as_normalized(), args, workspace))
         // If specific packages have been requested, only upgrade those
         .filter(|(name, _)| match &args.specs.packages {
             None => true,
@@ -1,8 +1,33 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
+
 use clap::Parser;
-use miette::IntoDiagnostic;
+use comfy_table::{Cell, ContentArrangement, Table, presets::NOTHING};
+use console::Style;
+use futures::StreamExt;
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
 use pixi_auth::get_auth_store;
 use pixi_config::Config;
-use rattler_upload::upload::opt::UploadOpts;
+use pixi_utils::reqwest::{build_lazy_reqwest_clients, build_reqwest_clients};
+use rattler_conda_types::{Channel, MatchSpec, PackageName, Platform, package::IndexJson};
+use rattler_networking::{Authentication, AuthenticationStorage};
+use rattler_repodata_gateway::Gateway;
+use rattler_upload::upload::opt::{ServerType, UploadOpts};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use url::Url;
+
+/// The number of times an upload is attempted before giving up.
+const MAX_UPLOAD_ATTEMPTS: u32 = 4;
+
+/// The default server of anaconda.org uploads.
+const ANACONDA_DEFAULT_URL: &str = "https://api.anaconda.org";
+
+/// The server that serves the channels of anaconda.org.
+const ANACONDA_CHANNEL_URL: &str = "https://conda.anaconda.org/";
 
 /// Upload conda packages to various channels
 ///
@@ -13,6 +38,303 @@ use rattler_upload::upload::opt::UploadOpts;
 pub struct Args {
     #[command(flatten)]
     pub upload_opts: UploadOpts,
+
+    /// Skip packages that already exist on the server instead of failing.
+    ///
+    /// Supported for the prefix, anaconda and artifactory server types.
+    #[arg(long, global = true)]
+    pub skip_existing: bool,
+
+    /// The number of packages that are uploaded at the same time, defaults to
+    /// the `concurrency.downloads` configuration.
+    ///
+    /// Supported for the prefix, anaconda and artifactory server types.
+    #[arg(long, global = true)]
+    pub concurrency: Option<usize>,
+}
+
+/// A server for which pixi can check whether a package already exists. Uploads
+/// to these servers are retried when they fail, because a package that made it
+/// to the server can be detected and is not uploaded twice.
+enum UploadTarget {
+    Anaconda {
+        url: Url,
+        owner: String,
+        token: Option<String>,
+    },
+    /// A server that serves the channel at `<url>/<channel>`, like prefix.dev
+    /// and Artifactory.
+    Channel {
+        url: Url,
+        channel: String,
+        authentication: Option<Authentication>,
+    },
+}
+
+/// A package file that is uploaded.
+struct LocalPackage {
+    path: PathBuf,
+    file_name: String,
+    index_json: IndexJson,
+    subdir: String,
+    sha256: String,
+}
+
+impl LocalPackage {
+    fn from_path(path: PathBuf) -> miette::Result<Self> {
+        let index_json: IndexJson = rattler_package_streaming::seek::read_package_file(&path)
+            .into_diagnostic()
+            .with_context(|| format!("failed to read the index.json of {}", path.display()))?;
+        let subdir = index_json
+            .subdir
+            .clone()
+            .ok_or_else(|| miette::miette!("the index.json of {} has no subdir", path.display()))?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| miette::miette!("{} has no file name", path.display()))?;
+        let sha256 = rattler_digest::compute_file_digest::<rattler_digest::Sha256>(&path)
+            .into_diagnostic()
+            .with_context(|| format!("failed to compute the sha256 of {}", path.display()))?;
+        Ok(Self {
+            path,
+            file_name,
+            index_json,
+            subdir,
+            sha256: format!("{sha256:x}"),
+        })
+    }
+}
+
+/// What happened to a package file.
+enum UploadStatus {
+    Uploaded {
+        attempts: u32,
+    },
+    /// A file with the same sha256 is already present on the server.
+    Identical,
+    /// A file with the same name is already present and `--skip-existing` was
+    /// passed.
+    Existing,
+    /// The same file was passed more than once.
+    Duplicate,
+    Failed(miette::Report),
+}
+
+impl UploadTarget {
+    fn from_server_type(
+        server_type: &ServerType,
+        auth_storage: &AuthenticationStorage,
+    ) -> miette::Result<Option<Self>> {
+        let stored_authentication = |url: &Url| {
+            auth_storage
+                .get_by_url(url.clone())
+                .ok()
+                .and_then(|(_, credentials)| credentials)
+        };
+        let target = match server_type {
+            ServerType::Anaconda(opts) => Self::Anaconda {
+                url: anaconda_url(opts.url.as_ref())?,
+                owner: opts.owner.clone(),
+                token: opts.api_key.clone(),
+            },
+            ServerType::Prefix(opts) => Self::Channel {
+                url: opts.url.clone(),
+                channel: opts.channel.clone(),
+                authentication: match &opts.api_key {
+                    Some(api_key) => Some(Authentication::BearerToken(api_key.clone())),
+                    None => stored_authentication(&opts.url),
+                },
+            },
+            ServerType::Artifactory(opts) => Self::Channel {
+                url: opts.url.clone(),
+                channel: opts.channels.clone(),
+                authentication: match (&opts.token, &opts.username, &opts.password) {
+                    (Some(token), _, _) => Some(Authentication::BearerToken(token.clone())),
+                    (None, Some(username), Some(password)) => Some(Authentication::BasicHTTP {
+                        username: username.clone(),
+                        password: password.clone(),
+                    }),
+                    _ => stored_authentication(&opts.url),
+                },
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(target))
+    }
+
+    /// Adds the credentials of the server to a request.
+    fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Anaconda {
+                token: Some(token), ..
+            } => request.header("Authorization", format!("token {token}")),
+            Self::Channel {
+                authentication: Some(Authentication::BearerToken(token)),
+                ..
+            } => request.bearer_auth(token),
+            Self::Channel {
+                authentication: Some(Authentication::BasicHTTP { username, password }),
+                ..
+            } => request.basic_auth(username, Some(password)),
+            _ => request,
+        }
+    }
+
+    /// Returns the url of the channel on the server, if it is known.
+    fn channel_url(&self) -> miette::Result<Option<Url>> {
+        let url = match self {
+            // Packages on anaconda.org are served from a different host than
+            // the API, for other servers the location is not known.
+            Self::Anaconda { url, owner, .. }
+                if url.as_str().trim_end_matches('/') == ANACONDA_DEFAULT_URL =>
+            {
+                Url::parse(ANACONDA_CHANNEL_URL)
+                    .into_diagnostic()?
+                    .join(&format!("{owner}/"))
+            }
+            Self::Anaconda { .. } => return Ok(None),
+            Self::Channel { url, channel, .. } => {
+                with_trailing_slash(url).join(&format!("{channel}/"))
+            }
+        };
+        url.into_diagnostic().map(Some)
+    }
+
+    /// Returns the sha256 of the files in the channel of the packages that
+    /// are uploaded, keyed by subdirectory and file name.
+    ///
+    /// Only the records of the uploaded package names are fetched, for
+    /// channels with sharded repodata that is a single shard per package
+    /// instead of the repodata of the whole subdirectory.
+    async fn sha256s(
+        &self,
+        gateway: &Gateway,
+        packages: &[LocalPackage],
+    ) -> miette::Result<HashMap<(String, String), String>> {
+        let Some(url) = self.channel_url()? else {
+            return Ok(HashMap::new());
+        };
+        let platforms: BTreeSet<Platform> = packages
+            .iter()
+            .filter_map(|package| package.subdir.parse().ok())
+            .collect();
+        let names: BTreeSet<PackageName> = packages
+            .iter()
+            .map(|package| package.index_json.name.clone())
+            .collect();
+        let repodata = gateway
+            .query(
+                [Channel::from_url(url)],
+                platforms,
+                names.into_iter().map(MatchSpec::from),
+            )
+            .await
+            .into_diagnostic()
+            .context("failed to fetch the repodata of the channel")?;
+        Ok(repodata
+            .iter()
+            .flat_map(|repodata| repodata.iter())
+            .filter_map(|record| {
+                let sha256 = record.package_record.sha256.as_ref()?;
+                Some((
+                    (
+                        record.package_record.subdir.clone(),
+                        record.file_name.clone(),
+                    ),
+                    format!("{sha256:x}"),
+                ))
+            })
+            .collect())
+    }
+
+    /// Returns true if the package is already present on the server.
+    async fn contains(&self, client: &Client, package: &LocalPackage) -> miette::Result<bool> {
+        let LocalPackage {
+            file_name,
+            index_json,
+            subdir,
+            ..
+        } = package;
+
+        let request = match self {
+            Self::Anaconda { url, owner, .. } => {
+                let url = with_trailing_slash(url)
+                    .join(&format!(
+                        "dist/{owner}/{}/{}/{subdir}/{file_name}",
+                        index_json.name.as_normalized(),
+                        index_json.version
+                    ))
+                    .into_diagnostic()?;
+                client.get(url)
+            }
+            Self::Channel { url, channel, .. } => {
+                let url = with_trailing_slash(url)
+                    .join(&format!("{channel}/{subdir}/{file_name}"))
+                    .into_diagnostic()?;
+                client.head(url)
+            }
+        };
+
+        let response = self.authenticate(request).send().await.into_diagnostic()?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            _ => response
+                .error_for_status()
+                .map(|_| true)
+                .into_diagnostic()
+                .context("failed to check whether the package exists"),
+        }
+    }
+}
+
+fn anaconda_url(url: Option<&Url>) -> miette::Result<Url> {
+    match url {
+        Some(url) => Ok(url.clone()),
+        None => Url::parse(ANACONDA_DEFAULT_URL).into_diagnostic(),
+    }
+}
+
+fn with_trailing_slash(url: &Url) -> Url {
+    let mut url = url.clone();
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    url
+}
+
+/// Fills in the token of the server from the authentication storage if it was
+/// not passed explicitly. Tokens are looked up for the host of the server, so
+/// that self-hosted servers can be used after `pixi auth login`.
+fn resolve_credentials(
+    server_type: &mut ServerType,
+    auth_storage: &AuthenticationStorage,
+) -> miette::Result<()> {
+    match server_type {
+        ServerType::Anaconda(opts) if opts.api_key.is_none() => {
+            let url = anaconda_url(opts.url.as_ref())?;
+            let credentials = match auth_storage.get_by_url(url) {
+                Ok((_, Some(credentials))) => Some(credentials),
+                _ => auth_storage.get("anaconda.org").ok().flatten(),
+            };
+            if let Some(Authentication::CondaToken(token) | Authentication::BearerToken(token)) =
+                credentials
+            {
+                opts.api_key = Some(token);
+            }
+        }
+        ServerType::Artifactory(opts)
+            if opts.token.is_none() && opts.username.is_none() && opts.password.is_none() =>
+        {
+            if let Ok((_, Some(Authentication::BearerToken(token)))) =
+                auth_storage.get_by_url(opts.url.clone())
+            {
+                opts.token = Some(token);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
@@ -21,7 +343,310 @@ pub async fn execute(args: Args) -> miette::Result<()> {
     let config = Config::load_global();
     let auth_storage = get_auth_store(&config).into_diagnostic()?;
 
-    let opts = args.upload_opts.with_auth_store(Some(auth_storage));
+    let UploadOpts {
+        package_files,
+        mut server_type,
+        common,
+        ..
+    } = args.upload_opts;
+    if package_files.is_empty() {
+        miette::bail!("No package files were provided.");
+    }
+    resolve_credentials(&mut server_type, &auth_storage)?;
+
+    let target = UploadTarget::from_server_type(&server_type, &auth_storage)?;
+    if args.skip_existing {
+        match &mut server_type {
+            ServerType::Prefix(opts) => opts.skip_existing = true,
+            _ if target.is_some() => {}
+            _ => miette::bail!("--skip-existing is not supported for this server type"),
+        }
+    }
+
+    // Servers for which we can't check whether a package exists are handed
+    // over to rattler as a whole, which retries the requests it can.
+    let Some(target) = target else {
+        return rattler_upload::upload_from_args(UploadOpts {
+            package_files,
+            server_type,
+            common,
+            auth_store: Some(auth_storage),
+        })
+        .await;
+    };
+
+    let (client, _) = build_reqwest_clients(Some(&config), None)?;
+
+    // Skip files that were passed more than once, e.g. by overlapping globs.
+    let mut packages = Vec::new();
+    let mut statuses = Vec::new();
+    let mut seen = HashSet::new();
+    for path in package_files {
+        let package = LocalPackage::from_path(path)?;
+        if seen.insert(package.sha256.clone()) {
+            packages.push(package);
+        } else {
+            statuses.push((package, UploadStatus::Duplicate));
+        }
+    }
+
+    // Find the packages that are already on the server with the same contents.
+    let gateway = config
+        .gateway()
+        .with_client(build_lazy_reqwest_clients(Some(&config), None)?.1)
+        .finish();
+    let remote_sha256s = target
+        .sha256s(&gateway, &packages)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::debug!("{err:?}");
+            HashMap::new()
+        });
+
+    let upload_opts = |package_file: PathBuf| UploadOpts {
+        package_files: vec![package_file],
+        server_type: server_type.clone(),
+        common: common.clone(),
+        auth_store: Some(auth_storage.clone()),
+    };
+    let concurrency = args
+        .concurrency
+        .unwrap_or_else(|| config.max_concurrent_downloads())
+        .max(1);
+    let uploads = futures::stream::iter(packages)
+        .map(|package| {
+            let identical = remote_sha256s
+                .get(&(package.subdir.clone(), package.file_name.clone()))
+                .is_some_and(|sha256| *sha256 == package.sha256);
+            let upload = UploadPackage {
+                target: &target,
+                client: &client,
+                upload_opts: &upload_opts,
+                skip_existing: args.skip_existing,
+            };
+            async move {
+                let status = if identical {
+                    UploadStatus::Identical
+                } else {
+                    upload.upload(&package).await
+                };
+                (package, status)
+            }
+        })
+        .buffer_unordered(concurrency)
+        .collect::<Vec<_>>()
+        .await;
+    statuses.extend(uploads);
+    statuses.sort_by(|(a, _), (b, _)| a.path.cmp(&b.path));
+
+    print_summary(&statuses);
+
+    let total = statuses.len();
+    let mut errors = statuses
+        .into_iter()
+        .filter_map(|(_, status)| match status {
+            UploadStatus::Failed(err) => Some(err),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    match errors.len() {
+        0 => Ok(()),
+        1 => Err(errors.remove(0)),
+        failed => {
+            for err in &errors {
+                tracing::error!("{err:?}");
+            }
+            miette::bail!("failed to upload {failed} of {total} packages")
+        }
+    }
+}
+
+/// The state that is shared between the uploads of the packages.
+struct UploadPackage<'a> {
+    target: &'a UploadTarget,
+    client: &'a Client,
+    upload_opts: &'a (dyn Fn(PathBuf) -> UploadOpts + Sync),
+    skip_existing: bool,
+}
+
+impl UploadPackage<'_> {
+    async fn upload(&self, package: &LocalPackage) -> UploadStatus {
+        // If we can't tell whether the package exists, assume it might so that
+        // a failed upload is never mistaken for a successful one.
+        let may_exist = match self.target.contains(self.client, package).await {
+            Ok(exists) => exists,
+            Err(err) if !self.skip_existing => {
+                tracing::debug!("failed to check whether the package exists: {err:?}");
+                true
+            }
+            Err(err) => return UploadStatus::Failed(err),
+        };
+        if may_exist && self.skip_existing {
+            return UploadStatus::Existing;
+        }
+
+        let mut attempt = 1;
+        loop {
+            let opts = (self.upload_opts)(package.path.clone());
+            let Err(err) = rattler_upload::upload_from_args(opts).await else {
+                return UploadStatus::Uploaded { attempts: attempt };
+            };
+            // When the package might already exist, a retry can't tell
+            // whether the failed upload stored it, so it is not retried.
+            if attempt >= MAX_UPLOAD_ATTEMPTS || may_exist || !is_transient(&err) {
+                return UploadStatus::Failed(err);
+            }
+
+            // The upload might have failed after the package was stored.
+            if self
+                .target
+                .contains(self.client, package)
+                .await
+                .unwrap_or(false)
+            {
+                return UploadStatus::Uploaded { attempts: attempt };
+            }
+
+            let backoff = Duration::from_secs(2u64.pow(attempt));
+            tracing::warn!(
+                "failed to upload {}: {err:?}, retrying in {} seconds",
+                package.path.display(),
+                backoff.as_secs()
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Returns true if an upload failed in a way that might succeed when it is
+/// retried, i.e. because of a connection error, rate limiting or an error of
+/// the server. Rejected credentials or conflicting packages are not retried.
+fn is_transient(err: &miette::Report) -> bool {
+    fn is_transient_reqwest(err: &reqwest::Error) -> bool {
+        match err.status() {
+            Some(status) => is_transient_status(status),
+            None => err.is_connect() || err.is_timeout() || err.is_request() || err.is_body(),
+        }
+    }
+
+    if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+        return is_transient_reqwest(err);
+    }
+    err.chain().any(|source| {
+        if let Some(err) = source.downcast_ref::<reqwest::Error>() {
+            is_transient_reqwest(err)
+        } else if let Some(err) = source.downcast_ref::<reqwest_middleware::Error>() {
+            match err {
+                reqwest_middleware::Error::Reqwest(err) => is_transient_reqwest(err),
+                reqwest_middleware::Error::Middleware(_) => false,
+            }
+        } else if let Some(err) = source.downcast_ref::<std::io::Error>() {
+            matches!(
+                err.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        } else {
+            false
+        }
+    })
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Prints a table with what happened to every package file.
+fn print_summary(statuses: &[(LocalPackage, UploadStatus)]) {
+    let mut table = Table::new();
+    table
+        .load_preset(NOTHING)
+        .set_content_arrangement(ContentArrangement::Disabled);
+    let header_style = Style::new().bold().cyan();
+    table.set_header(
+        ["Package", "Subdir", "Status"].map(|header| Cell::new(header_style.apply_to(header))),
+    );
+    for (package, status) in statuses {
+        let status = match status {
+            UploadStatus::Uploaded { attempts: 1 } => {
+                console::style("uploaded".to_string()).green()
+            }
+            UploadStatus::Uploaded { attempts } => {
+                console::style(format!("uploaded after {attempts} attempts")).green()
+            }
+            UploadStatus::Identical => {
+                console::style("skipped, identical file exists".to_string()).dim()
+            }
+            UploadStatus::Existing => console::style("skipped, already exists".to_string()).dim(),
+            UploadStatus::Duplicate => console::style("skipped, duplicate".to_string()).dim(),
+            UploadStatus::Failed(_) => console::style("failed".to_string()).red(),
+        };
+        table.add_row([
+            Cell::new(&package.file_name),
+            Cell::new(&package.subdir),
+            Cell::new(status),
+        ]);
+    }
+
+    eprintln!(
+        "{}",
+        table
+            .lines()
+            .map(|line| line.trim().to_string())
+            .format("\n")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_url() {
+        let anaconda = UploadTarget::Anaconda {
+            url: anaconda_url(None).unwrap(),
+            owner: String::from("my-user"),
+            token: None,
+        };
+        assert_eq!(
+            anaconda.channel_url().unwrap().unwrap().as_str(),
+            "https://conda.anaconda.org/my-user/"
+        );
+
+        let self_hosted = UploadTarget::Anaconda {
+            url: Url::parse("https://api.anaconda.example.com").unwrap(),
+            owner: String::from("my-user"),
+            token: None,
+        };
+        assert!(self_hosted.channel_url().unwrap().is_none());
+
+        let prefix = UploadTarget::Channel {
+            url: Url::parse("https://prefix.dev").unwrap(),
+            channel: String::from("my-channel"),
+            authentication: None,
+        };
+        assert_eq!(
+            prefix.channel_url().unwrap().unwrap().as_str(),
+            "https://prefix.dev/my-channel/"
+        );
+    }
+
+    #[test]
+    fn test_transient_status() {
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_transient_status(StatusCode::BAD_GATEWAY));
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_transient_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_transient_status(StatusCode::FORBIDDEN));
+        assert!(!is_transient_status(StatusCode::CONFLICT));
+    }
 
-    rattler_upload::upload_from_args(opts).await
+    #[test]
+    fn test_transient_error() {
+        assert!(!is_transient(&miette::miette!("package already exists")));
+    }
 }
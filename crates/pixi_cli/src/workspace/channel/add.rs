@@ -1,6 +1,6 @@
 use miette::IntoDiagnostic;
 use pixi_core::{
-    UpdateLockFileOptions, WorkspaceLocator,
+    UpdateLockFileOptions,
     environment::{InstallFilter, get_update_lock_file_and_prefix},
     lock_file::{ReinstallPackages, UpdateMode},
 };
@@ -8,12 +8,16 @@ use pixi_core::{
 use super::AddRemoveArgs;
 
 pub async fn execute(args: AddRemoveArgs) -> miette::Result<()> {
-    let mut workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let mut workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone())
         .modify()?;
 
+    // Make sure the channels exist before they end up in the manifest
+    args.validate(workspace.workspace()).await?;
+
     // Add the channels to the manifest
     workspace.manifest().add_channels(
         args.prioritized_channels(),
@@ -4,8 +4,7 @@ use clap::Parser;
 use miette::IntoDiagnostic;
 
 use fancy_display::FancyDisplay;
-use pixi_core::WorkspaceLocator;
-use pixi_manifest::FeaturesExt;
+use pixi_manifest::{FeatureName, FeaturesExt, HasWorkspaceManifest};
 
 use crate::cli_config::WorkspaceConfig;
 
@@ -16,15 +15,58 @@ pub struct Args {
     /// Whether to display the channel's names or urls
     #[clap(long)]
     pub urls: bool,
+
+    /// List the channels of this feature with their priority, instead of the
+    /// channels of every environment.
+    #[clap(long, short)]
+    pub feature: Option<String>,
 }
 
 pub(crate) fn execute(args: Args) -> miette::Result<()> {
     // Workspace without cli config as it shouldn't be needed here.
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let channel_config = workspace.channel_config();
+
+    if let Some(feature) = args.feature {
+        let feature_name = FeatureName::from(feature);
+        let manifest = workspace.workspace_manifest();
+        let feature = manifest
+            .feature(&feature_name)
+            .ok_or_else(|| miette::miette!("feature '{}' does not exist", feature_name))?;
+        // A feature without channels uses the channels of the workspace.
+        let channels = feature
+            .channels
+            .as_ref()
+            .unwrap_or(&manifest.workspace.channels);
+        for channel in channels {
+            let name = if args.urls {
+                channel
+                    .channel
+                    .clone()
+                    .into_base_url(&channel_config)
+                    .into_diagnostic()?
+                    .to_string()
+            } else {
+                channel.channel.to_string()
+            };
+            let _ = writeln!(
+                std::io::stdout(),
+                "- {}{}",
+                name,
+                channel
+                    .priority
+                    .map_or_else(String::new, |p| format!(" (priority {p})"))
+            )
+            .inspect_err(|e| {
+                if e.kind() == std::io::ErrorKind::BrokenPipe {
+                    std::process::exit(0);
+                }
+            });
+        }
+        return Ok(());
+    }
+
     workspace
         .environments()
         .iter()
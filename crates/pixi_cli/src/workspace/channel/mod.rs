@@ -5,10 +5,14 @@ pub mod remove;
 use clap::Parser;
 use miette::IntoDiagnostic;
 use pixi_config::ConfigCli;
+use pixi_core::Workspace;
 use pixi_manifest::{FeatureName, PrioritizedChannel};
 use rattler_conda_types::{ChannelConfig, NamedChannelOrUrl};
 
-use crate::cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig};
+use crate::{
+    cli_config::{LockFileUpdateConfig, NoInstallConfig, WorkspaceConfig},
+    doctor::is_channel_reachable,
+};
 
 /// Commands to manage workspace channels.
 #[derive(Parser, Debug, Clone)]
@@ -62,6 +66,28 @@ impl AddRemoveArgs {
             .map_or_else(FeatureName::default, FeatureName::from)
     }
 
+    /// Returns an error if the `noarch` repodata of one of the channels can
+    /// not be fetched, e.g. because of a typo in the name.
+    async fn validate(&self, workspace: &Workspace) -> miette::Result<()> {
+        let channel_config = workspace.channel_config();
+        let client = workspace.authenticated_client()?;
+        for channel in &self.channel {
+            let url = channel
+                .clone()
+                .into_base_url(&channel_config)
+                .into_diagnostic()?;
+            if !is_channel_reachable(client, &url).await {
+                miette::bail!(
+                    help = "Check the name of the channel, and log in with `pixi auth login` if the channel is private",
+                    "the channel '{}' could not be found at '{}'",
+                    channel,
+                    url.as_str()
+                );
+            }
+        }
+        Ok(())
+    }
+
     fn report(self, operation: &str, channel_config: &ChannelConfig) -> miette::Result<()> {
         for channel in self.channel {
             match channel {
@@ -97,6 +123,9 @@ impl AddRemoveArgs {
 #[derive(Parser, Debug, Clone)]
 pub enum Command {
     /// Adds a channel to the manifest and updates the lockfile.
+    ///
+    /// The channel is only added if it can be reached. Use `--frozen` to add
+    /// it without updating the lockfile.
     #[clap(visible_alias = "a")]
     Add(AddRemoveArgs),
     /// List the channels in the manifest.
@@ -114,3 +143,47 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         Command::Remove(remove_args) => remove::execute(remove_args).await,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_validate_channels() {
+        let workspace = Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+        "#,
+        )
+        .unwrap();
+
+        let channel_dir = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(channel_dir.path().join("noarch")).unwrap();
+        fs_err::write(
+            channel_dir.path().join("noarch").join("repodata.json"),
+            "{}",
+        )
+        .unwrap();
+        let channel = url::Url::from_directory_path(channel_dir.path()).unwrap();
+        let missing_channel = channel.join("missing/").unwrap();
+
+        let args = AddRemoveArgs {
+            channel: vec![NamedChannelOrUrl::Url(channel)],
+            ..Default::default()
+        };
+        args.validate(&workspace).await.unwrap();
+
+        let args = AddRemoveArgs {
+            channel: vec![NamedChannelOrUrl::Url(missing_channel)],
+            ..Default::default()
+        };
+        let err = args.validate(&workspace).await.unwrap_err();
+        assert!(err.to_string().contains("could not be found"));
+    }
+}
@@ -1,6 +1,6 @@
 use miette::IntoDiagnostic;
 use pixi_core::{
-    UpdateLockFileOptions, WorkspaceLocator,
+    UpdateLockFileOptions,
     environment::{InstallFilter, get_update_lock_file_and_prefix},
     lock_file::{ReinstallPackages, UpdateMode},
 };
@@ -8,8 +8,9 @@ use pixi_core::{
 use super::AddRemoveArgs;
 
 pub async fn execute(args: AddRemoveArgs) -> miette::Result<()> {
-    let mut workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let mut workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone())
         .modify()?;
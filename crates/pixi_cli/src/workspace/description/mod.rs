@@ -2,7 +2,6 @@ pub mod get;
 pub mod set;
 
 use clap::Parser;
-use pixi_core::WorkspaceLocator;
 
 use crate::cli_config::WorkspaceConfig;
 
@@ -32,9 +31,7 @@ pub enum Command {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     match args.command {
         Command::Get => get::execute(workspace).await?,
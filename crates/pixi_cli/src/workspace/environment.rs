@@ -4,11 +4,11 @@ use clap::Parser;
 use fancy_display::FancyDisplay;
 use itertools::Itertools;
 use miette::IntoDiagnostic;
-use pixi_api::WorkspaceContext;
+use pixi_api::{WorkspaceContext, workspace::AddEnvironmentOptions};
 use pixi_consts::consts;
-use pixi_core::WorkspaceLocator;
 use pixi_manifest::EnvironmentName;
 use pixi_manifest::HasFeaturesIter;
+use serde::Serialize;
 
 use crate::{cli_config::WorkspaceConfig, cli_interface::CliInterface};
 
@@ -45,6 +45,13 @@ pub struct AddArgs {
     pub force: bool,
 }
 
+#[derive(Parser, Debug, Default)]
+pub struct ListArgs {
+    /// Output the environments in JSON format.
+    #[clap(long)]
+    pub json: bool,
+}
+
 #[derive(Parser, Debug, Default)]
 pub struct RemoveArgs {
     /// The name of the environment to remove
@@ -58,27 +65,48 @@ pub enum Command {
     Add(AddArgs),
     /// List the environments in the manifest file.
     #[clap(visible_alias = "ls")]
-    List,
+    List(ListArgs),
     /// Remove an environment from the manifest file.
     #[clap(visible_alias = "rm")]
     Remove(RemoveArgs),
 }
 
+/// The JSON representation of an environment in `pixi workspace environment list --json`.
+#[derive(Serialize)]
+struct EnvironmentJson {
+    name: String,
+    features: Vec<String>,
+    solve_group: Option<String>,
+    no_default_feature: bool,
+}
+
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace);
 
     match args.command {
-        Command::List => {
+        Command::List(args) => {
             let envs = workspace_ctx.list_environments().await;
+            if args.json {
+                let envs = envs
+                    .iter()
+                    .map(|e| EnvironmentJson {
+                        name: e.name().to_string(),
+                        features: e.features().map(|f| f.name.to_string()).collect(),
+                        solve_group: e.solve_group().map(|group| group.name().to_string()),
+                        no_default_feature: e.no_default_feature(),
+                    })
+                    .collect_vec();
+                println!("{}", serde_json::to_string_pretty(&envs).into_diagnostic()?);
+                return Ok(());
+            }
+
             writeln!(
                 std::io::stdout(),
                 "Environments:\n{}",
                 envs.iter().format_with("\n", |e, f| f(&format_args!(
-                    "- {}: \n    features: {}{}",
+                    "- {}: \n    features: {}{}{}",
                     e.name().fancy_display(),
                     e.features().map(|f| f.name.fancy_display()).format(", "),
                     if let Some(solve_group) = e.solve_group() {
@@ -88,6 +116,11 @@ pub async fn execute(args: Args) -> miette::Result<()> {
                         )
                     } else {
                         "".to_string()
+                    },
+                    if e.no_default_feature() {
+                        "\n    no_default_feature: true"
+                    } else {
+                        ""
                     }
                 )))
             )
@@ -100,12 +133,14 @@ pub async fn execute(args: Args) -> miette::Result<()> {
         }
         Command::Add(args) => {
             workspace_ctx
-                .add_environment(
+                .add_environment_with_options(
                     args.name,
-                    args.features,
-                    args.solve_group,
-                    args.no_default_feature,
-                    args.force,
+                    AddEnvironmentOptions {
+                        features: args.features,
+                        solve_group: args.solve_group,
+                        no_default_feature: args.no_default_feature,
+                        force: args.force,
+                    },
                 )
                 .await?
         }
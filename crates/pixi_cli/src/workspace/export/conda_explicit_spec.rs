@@ -6,7 +6,7 @@ use std::{
 use clap::Parser;
 use miette::{Context, IntoDiagnostic};
 use pixi_config::ConfigCli;
-use pixi_core::{WorkspaceLocator, lock_file::UpdateLockFileOptions};
+use pixi_core::lock_file::UpdateLockFileOptions;
 use rattler_conda_types::{
     ExplicitEnvironmentEntry, ExplicitEnvironmentSpec, PackageRecord, Platform, RepoDataRecord,
 };
@@ -164,8 +164,9 @@ fn render_env_platform(
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         .locate()?
         .with_cli_config(args.config.clone());
 
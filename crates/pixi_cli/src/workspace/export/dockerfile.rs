@@ -0,0 +1,275 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use fancy_display::FancyDisplay;
+use miette::{Context, IntoDiagnostic};
+use pixi_consts::consts;
+use pixi_manifest::{EnvironmentName, FeaturesExt};
+use rattler_conda_types::{Platform, Version};
+
+use crate::cli_config::WorkspaceConfig;
+
+/// The file name of a Dockerfile.
+const DOCKERFILE_NAME: &str = "Dockerfile";
+
+/// The image that contains pixi, used to install the environment.
+const PIXI_IMAGE: &str = "ghcr.io/prefix-dev/pixi";
+
+/// The base image of the final stage if the environment has no special
+/// requirements.
+const DEFAULT_BASE_IMAGE: &str = "ubuntu:24.04";
+
+/// The CUDA versions of the published `nvidia/cuda` base images and the Ubuntu
+/// release of the images. Images for Ubuntu 24.04 are only published from CUDA
+/// 12.5 onwards.
+const CUDA_IMAGES: &[((u64, u64, u64), &str)] = &[
+    ((11, 7, 1), "22.04"),
+    ((11, 8, 0), "22.04"),
+    ((12, 0, 0), "22.04"),
+    ((12, 0, 1), "22.04"),
+    ((12, 1, 0), "22.04"),
+    ((12, 1, 1), "22.04"),
+    ((12, 2, 0), "22.04"),
+    ((12, 2, 2), "22.04"),
+    ((12, 3, 0), "22.04"),
+    ((12, 3, 1), "22.04"),
+    ((12, 3, 2), "22.04"),
+    ((12, 4, 0), "22.04"),
+    ((12, 4, 1), "22.04"),
+    ((12, 5, 0), "24.04"),
+    ((12, 5, 1), "24.04"),
+    ((12, 6, 0), "24.04"),
+    ((12, 6, 1), "24.04"),
+    ((12, 6, 2), "24.04"),
+    ((12, 6, 3), "24.04"),
+    ((12, 8, 0), "24.04"),
+    ((12, 8, 1), "24.04"),
+    ((12, 9, 0), "24.04"),
+    ((12, 9, 1), "24.04"),
+    ((13, 0, 0), "24.04"),
+    ((13, 0, 1), "24.04"),
+];
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// Explicit path to export the Dockerfile to. If a directory is given, a
+    /// `Dockerfile` is written inside it. Prints the Dockerfile if omitted.
+    pub output_path: Option<PathBuf>,
+
+    /// The environment to install in the image
+    #[arg(long, short)]
+    pub environment: Option<String>,
+
+    /// The platform of the image, defaults to `linux-64` if the environment
+    /// supports it
+    #[arg(long, short)]
+    pub platform: Option<Platform>,
+
+    /// The base image of the final stage, defaults to an image that matches the
+    /// system requirements of the environment
+    #[arg(long)]
+    pub base_image: Option<String>,
+
+    /// The directory in the image to install the environment into
+    #[arg(long, default_value = "/opt/env")]
+    pub prefix: String,
+}
+
+/// Everything that is needed to render a Dockerfile.
+struct Dockerfile<'a> {
+    pixi_image: String,
+    base_image: String,
+    docker_platform: &'static str,
+    environment: &'a EnvironmentName,
+    prefix: &'a str,
+}
+
+impl Dockerfile<'_> {
+    fn render(&self) -> String {
+        let Self {
+            pixi_image,
+            base_image,
+            docker_platform,
+            environment,
+            prefix,
+        } = self;
+        let environment = environment.as_str();
+        format!(
+            "# Generated by `pixi workspace export dockerfile`.
+FROM --platform={docker_platform} {pixi_image} AS build
+WORKDIR /app
+COPY . .
+# install the locked environment into a prefix outside of the workspace
+RUN pixi deploy --locked --environment {environment} --prefix {prefix}
+
+FROM --platform={docker_platform} {base_image} AS production
+# only copy the environment into the final image
+COPY --from=build {prefix} {prefix}
+ENV CONDA_PREFIX={prefix}
+ENV PATH={prefix}/bin:$PATH
+"
+        )
+    }
+}
+
+/// Returns the platform of a Docker image for a conda platform.
+fn docker_platform(platform: Platform) -> Option<&'static str> {
+    match platform {
+        Platform::Linux64 => Some("linux/amd64"),
+        Platform::LinuxAarch64 => Some("linux/arm64"),
+        Platform::LinuxPpc64le => Some("linux/ppc64le"),
+        Platform::LinuxS390X => Some("linux/s390x"),
+        _ => None,
+    }
+}
+
+/// Returns the base image that provides the given CUDA version, or the default
+/// base image if no CUDA is required.
+fn base_image(cuda: Option<&Version>) -> String {
+    let Some(cuda) = cuda else {
+        return String::from(DEFAULT_BASE_IMAGE);
+    };
+    let mut segments = cuda
+        .to_string()
+        .split('.')
+        .map(|segment| segment.parse::<u64>().unwrap_or(0))
+        .collect::<Vec<_>>();
+    segments.resize(3, 0);
+    let required = (segments[0], segments[1], segments[2]);
+
+    // The oldest published image that provides the required version, so that
+    // the image runs with as many drivers as possible. Versions that are newer
+    // than the known images are assumed to be published for Ubuntu 24.04.
+    let ((major, minor, patch), ubuntu) = CUDA_IMAGES
+        .iter()
+        .copied()
+        .find(|(version, _)| version.0 == required.0 && *version >= required)
+        .unwrap_or_else(|| {
+            let ubuntu = if required < (12, 5, 0) {
+                "22.04"
+            } else {
+                "24.04"
+            };
+            (required, ubuntu)
+        });
+    format!("nvidia/cuda:{major}.{minor}.{patch}-base-ubuntu{ubuntu}")
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let environment = workspace.environment_from_name_or_env_var(args.environment)?;
+
+    let platforms = environment.platforms();
+    let platform = match args.platform {
+        Some(platform) => platform,
+        None if platforms.contains(&Platform::Linux64) => Platform::Linux64,
+        None => platforms
+            .iter()
+            .copied()
+            .find(|platform| docker_platform(*platform).is_some())
+            .ok_or_else(|| {
+                miette::miette!(
+                    help = "add a linux platform with `pixi workspace platform add linux-64`",
+                    "the environment '{}' does not support a linux platform",
+                    environment.name().fancy_display()
+                )
+            })?,
+    };
+    if !platforms.contains(&platform) {
+        miette::bail!(
+            "the environment '{}' does not support the platform '{}'",
+            environment.name().fancy_display(),
+            platform
+        );
+    }
+    let docker_platform = docker_platform(platform).ok_or_else(|| {
+        miette::miette!("there are no container images for the platform '{platform}'")
+    })?;
+
+    let system_requirements = environment.system_requirements();
+    let dockerfile = Dockerfile {
+        pixi_image: format!("{PIXI_IMAGE}:{}", consts::PIXI_VERSION),
+        base_image: args
+            .base_image
+            .unwrap_or_else(|| base_image(system_requirements.cuda.as_ref())),
+        docker_platform,
+        environment: environment.name(),
+        prefix: &args.prefix,
+    }
+    .render();
+
+    let output_path = args.output_path.map(|path| {
+        if path.is_dir() {
+            path.join(DOCKERFILE_NAME)
+        } else {
+            path
+        }
+    });
+    if let Some(output_path) = output_path {
+        fs_err::write(&output_path, dockerfile)
+            .into_diagnostic()
+            .with_context(|| "failed to write the Dockerfile")?;
+        eprintln!(
+            "{}Exported the Dockerfile to {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            output_path.display()
+        );
+    } else {
+        print!("{dockerfile}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_render_dockerfile() {
+        let environment = EnvironmentName::Named(String::from("prod"));
+        let dockerfile = Dockerfile {
+            pixi_image: format!("{PIXI_IMAGE}:0.0.0"),
+            base_image: base_image(None),
+            docker_platform: "linux/amd64",
+            environment: &environment,
+            prefix: "/opt/env",
+        };
+        insta::assert_snapshot!(dockerfile.render());
+    }
+
+    #[test]
+    fn test_base_image() {
+        assert_eq!(base_image(None), DEFAULT_BASE_IMAGE);
+        assert_eq!(
+            base_image(Some(&Version::from_str("12").unwrap())),
+            "nvidia/cuda:12.0.0-base-ubuntu22.04"
+        );
+        assert_eq!(
+            base_image(Some(&Version::from_str("12.4.1").unwrap())),
+            "nvidia/cuda:12.4.1-base-ubuntu22.04"
+        );
+        assert_eq!(
+            base_image(Some(&Version::from_str("12.5").unwrap())),
+            "nvidia/cuda:12.5.0-base-ubuntu24.04"
+        );
+        // There is no image for CUDA 12.7, the next one provides it.
+        assert_eq!(
+            base_image(Some(&Version::from_str("12.7").unwrap())),
+            "nvidia/cuda:12.8.0-base-ubuntu24.04"
+        );
+        assert_eq!(
+            base_image(Some(&Version::from_str("11.8").unwrap())),
+            "nvidia/cuda:11.8.0-base-ubuntu22.04"
+        );
+        assert_eq!(
+            base_image(Some(&Version::from_str("14.1").unwrap())),
+            "nvidia/cuda:14.1.0-base-ubuntu24.04"
+        );
+    }
+}
@@ -1,5 +1,7 @@
 pub mod conda_environment;
 pub mod conda_explicit_spec;
+pub mod dockerfile;
+pub mod recipe;
 
 use clap::Parser;
 
@@ -17,12 +19,18 @@ pub enum Command {
     CondaExplicitSpec(conda_explicit_spec::Args),
     /// Export workspace environment to a conda environment.yaml file
     CondaEnvironment(conda_environment::Args),
+    /// Export the package of the workspace to a rattler-build recipe.yaml file
+    Recipe(recipe::Args),
+    /// Export a workspace environment to a multi-stage Dockerfile
+    Dockerfile(dockerfile::Args),
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
     match args.command {
         Command::CondaExplicitSpec(args) => conda_explicit_spec::execute(args).await?,
         Command::CondaEnvironment(args) => conda_environment::execute(args).await?,
+        Command::Recipe(args) => recipe::execute(args).await?,
+        Command::Dockerfile(args) => dockerfile::execute(args).await?,
     };
     Ok(())
 }
@@ -0,0 +1,381 @@
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use miette::{Context, IntoDiagnostic};
+use pixi_manifest::{PackageManifest, SpecType, TargetSelector, WithProvenance};
+use rattler_conda_types::{ChannelConfig, MatchSpec};
+use serde::Serialize;
+
+use crate::cli_config::WorkspaceConfig;
+
+/// The file name of a rattler-build recipe.
+const RECIPE_FILE_NAME: &str = "recipe.yaml";
+
+#[derive(Debug, Parser)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// Explicit path to export the recipe to. If a directory is given, a
+    /// `recipe.yaml` is written inside it. Prints the recipe if omitted.
+    pub output_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+struct Recipe {
+    package: RecipePackage,
+    source: RecipeSource,
+    build: RecipeBuild,
+    requirements: RecipeRequirements,
+    #[serde(skip_serializing_if = "RecipeAbout::is_empty")]
+    about: RecipeAbout,
+}
+
+#[derive(Debug, Serialize)]
+struct RecipePackage {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RecipeSource {
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+struct RecipeBuild {
+    number: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    noarch: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    script: Vec<&'static str>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct RecipeRequirements {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    build: Vec<RecipeRequirement>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    host: Vec<RecipeRequirement>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    run: Vec<RecipeRequirement>,
+}
+
+/// A requirement in a recipe, either unconditional or only for the platforms
+/// matching a selector.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum RecipeRequirement {
+    Spec(String),
+    Conditional {
+        #[serde(rename = "if")]
+        selector: String,
+        then: Vec<String>,
+    },
+}
+
+#[derive(Debug, Default, Serialize)]
+struct RecipeAbout {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    documentation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+}
+
+impl RecipeAbout {
+    fn is_empty(&self) -> bool {
+        self.homepage.is_none()
+            && self.repository.is_none()
+            && self.documentation.is_none()
+            && self.license.is_none()
+            && self.license_file.is_none()
+            && self.summary.is_none()
+    }
+}
+
+/// The build script and the additional requirements that a known build
+/// backend would have provided.
+struct BackendDefaults {
+    noarch: Option<&'static str>,
+    script: Vec<&'static str>,
+    build: Vec<&'static str>,
+    host: Vec<&'static str>,
+}
+
+impl BackendDefaults {
+    fn for_backend(name: &str) -> Option<Self> {
+        match name {
+            "pixi-build-python" => Some(Self {
+                noarch: Some("python"),
+                script: vec!["python -m pip install . -vv --no-deps --no-build-isolation"],
+                build: vec![],
+                host: vec!["python", "pip"],
+            }),
+            "pixi-build-cmake" => Some(Self {
+                noarch: None,
+                script: vec![
+                    "cmake -GNinja -DCMAKE_BUILD_TYPE=Release -DCMAKE_INSTALL_PREFIX=$PREFIX -B build -S .",
+                    "cmake --build build --target install",
+                ],
+                build: vec!["${{ compiler('cxx') }}", "cmake", "ninja"],
+                host: vec![],
+            }),
+            "pixi-build-rust" => Some(Self {
+                noarch: None,
+                script: vec!["cargo install --locked --no-track --root $PREFIX --path ."],
+                build: vec!["${{ compiler('rust') }}"],
+                host: vec![],
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a target selector to the equivalent rattler-build selector.
+fn recipe_selector(selector: &TargetSelector) -> String {
+    match selector {
+        TargetSelector::Platform(platform) => format!("target_platform == '{platform}'"),
+        TargetSelector::Unix => String::from("unix"),
+        TargetSelector::Linux => String::from("linux"),
+        TargetSelector::Win => String::from("win"),
+        TargetSelector::MacOs => String::from("osx"),
+    }
+}
+
+/// Returns the dependencies of the given type as match spec strings. Source
+/// dependencies cannot be expressed in a recipe and are skipped.
+fn recipe_specs(
+    package: &PackageManifest,
+    selector: Option<&TargetSelector>,
+    spec_type: SpecType,
+    channel_config: &ChannelConfig,
+) -> miette::Result<Vec<String>> {
+    let Some(dependencies) = package
+        .targets
+        .for_opt_target(selector)
+        .and_then(|target| target.dependencies.get(&spec_type))
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut specs = Vec::new();
+    for (name, spec) in dependencies.iter_specs() {
+        match spec
+            .clone()
+            .try_into_nameless_match_spec(channel_config)
+            .into_diagnostic()?
+        {
+            Some(spec) => {
+                specs.push(MatchSpec::from_nameless(spec, Some(name.clone().into())).to_string())
+            }
+            None => tracing::warn!(
+                "the source dependency '{}' cannot be expressed in a recipe, skipping it",
+                name.as_source()
+            ),
+        }
+    }
+    Ok(specs)
+}
+
+fn build_recipe(
+    package: &WithProvenance<PackageManifest>,
+    source_path: String,
+    channel_config: &ChannelConfig,
+) -> miette::Result<Recipe> {
+    let manifest = &package.value;
+    let name = manifest.package.name.clone().ok_or_else(|| {
+        miette::miette!(
+            help = "add a `name` to the `[package]` section",
+            "the package does not have a name"
+        )
+    })?;
+    let version = manifest.package.version.as_ref().ok_or_else(|| {
+        miette::miette!(
+            help = "add a `version` to the `[package]` section",
+            "the package does not have a version"
+        )
+    })?;
+
+    let backend = &manifest.build.backend.name;
+    let defaults = BackendDefaults::for_backend(backend.as_normalized());
+    if defaults.is_none() {
+        tracing::warn!(
+            "no build script is known for the build backend '{}', add one to the recipe",
+            backend.as_source()
+        );
+    }
+
+    let mut requirements = RecipeRequirements::default();
+    for (spec_type, entries) in [
+        (SpecType::Build, &mut requirements.build),
+        (SpecType::Host, &mut requirements.host),
+        (SpecType::Run, &mut requirements.run),
+    ] {
+        let specs = recipe_specs(manifest, None, spec_type, channel_config)?;
+
+        // Add the requirements that the build backend would have added, unless
+        // they are already specified.
+        let implicit = match (&defaults, spec_type) {
+            (Some(defaults), SpecType::Build) => defaults.build.as_slice(),
+            (Some(defaults), SpecType::Host) => defaults.host.as_slice(),
+            _ => &[],
+        };
+        entries.extend(
+            implicit
+                .iter()
+                .filter(|implicit| {
+                    !specs
+                        .iter()
+                        .any(|spec| spec.split_whitespace().next() == Some(**implicit))
+                })
+                .map(|implicit| RecipeRequirement::Spec(implicit.to_string())),
+        );
+        entries.extend(specs.into_iter().map(RecipeRequirement::Spec));
+
+        for selector in manifest.targets.user_defined_selectors() {
+            let specs = recipe_specs(manifest, Some(selector), spec_type, channel_config)?;
+            if !specs.is_empty() {
+                entries.push(RecipeRequirement::Conditional {
+                    selector: recipe_selector(selector),
+                    then: specs,
+                });
+            }
+        }
+    }
+
+    let package_info = &manifest.package;
+    let about = RecipeAbout {
+        homepage: package_info.homepage.as_ref().map(ToString::to_string),
+        repository: package_info.repository.as_ref().map(ToString::to_string),
+        documentation: package_info.documentation.as_ref().map(ToString::to_string),
+        license: package_info.license.clone(),
+        license_file: package_info
+            .license_file
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+        summary: package_info.description.clone(),
+    };
+
+    Ok(Recipe {
+        package: RecipePackage {
+            name,
+            version: version.to_string(),
+        },
+        source: RecipeSource { path: source_path },
+        build: RecipeBuild {
+            number: 0,
+            noarch: defaults.as_ref().and_then(|defaults| defaults.noarch),
+            script: defaults.map(|defaults| defaults.script).unwrap_or_default(),
+        },
+        requirements,
+        about,
+    })
+}
+
+/// Returns the path of the package source relative to the directory that
+/// contains the recipe.
+fn relative_source_path(package_root: &Path, recipe_dir: &Path) -> String {
+    let path = pathdiff::diff_paths(package_root, recipe_dir)
+        .unwrap_or_else(|| package_root.to_path_buf());
+    if path.as_os_str().is_empty() {
+        String::from(".")
+    } else {
+        path.to_string_lossy().replace('\\', "/")
+    }
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+    let package = workspace.package.as_ref().ok_or_else(|| {
+        miette::miette!(
+            help = "add a `[package]` section to the manifest to describe the package",
+            "the workspace does not define a package"
+        )
+    })?;
+
+    let package_root = package
+        .provenance
+        .absolute_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| workspace.root().to_path_buf());
+    let output_path = args.output_path.map(|path| {
+        if path.is_dir() {
+            path.join(RECIPE_FILE_NAME)
+        } else {
+            path
+        }
+    });
+    let current_dir = std::env::current_dir().into_diagnostic()?;
+    let recipe_dir = output_path
+        .as_deref()
+        .and_then(Path::parent)
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map(|parent| current_dir.join(parent))
+        .unwrap_or(current_dir);
+
+    let recipe = build_recipe(
+        package,
+        relative_source_path(&package_root, &recipe_dir),
+        workspace.config().global_channel_config(),
+    )?;
+    let recipe = serde_yaml::to_string(&recipe).into_diagnostic()?;
+
+    if let Some(output_path) = output_path {
+        fs_err::write(&output_path, recipe)
+            .into_diagnostic()
+            .with_context(|| "failed to write the recipe")?;
+        eprintln!(
+            "{}Exported the recipe to {}",
+            console::style(console::Emoji("✔ ", "")).green(),
+            output_path.display()
+        );
+    } else {
+        print!("{recipe}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixi_core::Workspace;
+
+    #[test]
+    fn test_export_recipe() {
+        let path =
+            Path::new(env!("CARGO_WORKSPACE_DIR")).join("examples/pixi-build/cpp-sdl/pixi.toml");
+        let workspace = Workspace::from_path(&path).unwrap();
+        let recipe = build_recipe(
+            workspace.package.as_ref().unwrap(),
+            String::from(".."),
+            workspace.config().global_channel_config(),
+        )
+        .unwrap();
+        insta::assert_snapshot!(
+            "test_export_recipe",
+            serde_yaml::to_string(&recipe).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_relative_source_path() {
+        assert_eq!(
+            relative_source_path(Path::new("/ws/pkg"), Path::new("/ws/pkg")),
+            "."
+        );
+        assert_eq!(
+            relative_source_path(Path::new("/ws/pkg"), Path::new("/ws/pkg/recipe")),
+            ".."
+        );
+    }
+}
@@ -1,12 +1,13 @@
-use std::io::Write;
+use std::{io::Write, str::FromStr};
 
 use clap::Parser;
 use fancy_display::FancyDisplay;
+use indexmap::IndexMap;
 use itertools::Itertools;
-use miette::IntoDiagnostic;
-use pixi_api::WorkspaceContext;
-use pixi_core::WorkspaceLocator;
-use pixi_manifest::FeatureName;
+use miette::{Context, IntoDiagnostic};
+use pixi_api::{WorkspaceContext, workspace::AddFeatureOptions};
+use pixi_manifest::{FeatureName, Task, TaskName};
+use rattler_conda_types::{MatchSpec, ParseStrictness, Platform};
 
 use crate::{cli_config::WorkspaceConfig, cli_interface::CliInterface};
 
@@ -21,6 +22,38 @@ pub struct Args {
     pub command: Command,
 }
 
+#[derive(Parser, Debug)]
+pub struct AddArgs {
+    /// The name of the feature to add
+    pub feature: FeatureName,
+
+    /// Conda dependencies to add to the feature, e.g. `"python>=3.12"`
+    #[arg(short, long = "dependency", value_name = "SPEC")]
+    pub dependencies: Vec<String>,
+
+    /// PyPI dependencies to add to the feature, e.g. `"requests>=2"`
+    #[arg(long = "pypi-dependency", value_name = "REQUIREMENT")]
+    pub pypi_dependencies: Vec<String>,
+
+    /// Tasks to add to the feature, in the form `NAME=COMMAND`
+    #[arg(short, long = "task", value_name = "NAME=COMMAND", value_parser = parse_task)]
+    pub tasks: Vec<(TaskName, String)>,
+
+    /// Platforms the feature supports
+    #[arg(short, long = "platform")]
+    pub platforms: Vec<Platform>,
+}
+
+/// Parses a task in the form `NAME=COMMAND`.
+fn parse_task(s: &str) -> Result<(TaskName, String), String> {
+    match s.split_once('=') {
+        Some((name, command)) if !name.is_empty() && !command.trim().is_empty() => {
+            Ok((TaskName::from(name), command.to_string()))
+        }
+        _ => Err(format!("invalid task '{s}', expected `NAME=COMMAND`")),
+    }
+}
+
 #[derive(Parser, Debug)]
 pub struct RemoveArgs {
     /// The name of the feature to remove
@@ -29,6 +62,9 @@ pub struct RemoveArgs {
 
 #[derive(Parser, Debug)]
 pub enum Command {
+    /// Add a feature to the manifest file.
+    #[clap(visible_alias = "a")]
+    Add(AddArgs),
     /// List the features in the manifest file.
     #[clap(visible_alias = "ls")]
     List,
@@ -38,9 +74,7 @@ pub enum Command {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace);
 
@@ -104,6 +138,52 @@ pub async fn execute(args: Args) -> miette::Result<()> {
             })
             .into_diagnostic()?;
         }
+        Command::Add(args) => {
+            let dependencies = args
+                .dependencies
+                .iter()
+                .map(|spec| {
+                    let spec = MatchSpec::from_str(spec, ParseStrictness::Lenient)
+                        .into_diagnostic()
+                        .with_context(|| format!("failed to parse '{spec}'"))?;
+                    let name = spec
+                        .name
+                        .as_ref()
+                        .and_then(|name| name.as_exact())
+                        .cloned()
+                        .ok_or_else(|| {
+                            miette::miette!("'{spec}' does not specify a package name")
+                        })?;
+                    Ok((name, spec))
+                })
+                .collect::<miette::Result<IndexMap<_, _>>>()?;
+            let pypi_dependencies = args
+                .pypi_dependencies
+                .iter()
+                .map(|requirement| {
+                    pep508_rs::Requirement::from_str(requirement)
+                        .into_diagnostic()
+                        .with_context(|| format!("failed to parse '{requirement}'"))
+                })
+                .collect::<miette::Result<Vec<_>>>()?;
+            let tasks = args
+                .tasks
+                .into_iter()
+                .map(|(name, command)| (name, Task::Plain(command.into())))
+                .collect();
+
+            workspace_ctx
+                .add_feature(
+                    args.feature,
+                    AddFeatureOptions {
+                        dependencies,
+                        pypi_dependencies,
+                        tasks,
+                        platforms: args.platforms,
+                    },
+                )
+                .await?;
+        }
         Command::Remove(args) => {
             workspace_ctx.remove_feature(&args.feature).await?;
         }
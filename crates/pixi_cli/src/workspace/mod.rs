@@ -9,7 +9,9 @@ pub mod export;
 pub mod feature;
 pub mod name;
 pub mod platform;
+pub mod rename;
 pub mod requires_pixi;
+pub mod solve_groups;
 pub mod system_requirements;
 pub mod version;
 
@@ -23,8 +25,10 @@ pub enum Command {
     Feature(feature::Args),
     Export(export::Args),
     Name(name::Args),
+    Rename(rename::Args),
     SystemRequirements(system_requirements::Args),
     RequiresPixi(requires_pixi::Args),
+    SolveGroups(solve_groups::Args),
 }
 
 /// Modify the workspace configuration file through the command line.
@@ -47,8 +51,10 @@ pub async fn execute(cmd: Args) -> miette::Result<()> {
         Command::Feature(args) => feature::execute(args).await?,
         Command::Export(cmd) => export::execute(cmd).await?,
         Command::Name(args) => name::execute(args).await?,
+        Command::Rename(args) => rename::execute(args).await?,
         Command::SystemRequirements(args) => system_requirements::execute(args).await?,
         Command::RequiresPixi(args) => requires_pixi::execute(args).await?,
+        Command::SolveGroups(args) => solve_groups::execute(args).await?,
     };
     Ok(())
 }
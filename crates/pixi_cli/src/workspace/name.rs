@@ -1,6 +1,5 @@
 use clap::Parser;
 use pixi_api::WorkspaceContext;
-use pixi_core::WorkspaceLocator;
 
 use crate::{cli_config::WorkspaceConfig, cli_interface::CliInterface};
 
@@ -34,9 +33,7 @@ pub enum Command {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace);
 
@@ -3,7 +3,6 @@ pub mod list;
 pub mod remove;
 
 use clap::Parser;
-use pixi_core::WorkspaceLocator;
 
 use crate::cli_config::WorkspaceConfig;
 
@@ -32,8 +31,9 @@ pub enum Command {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace = args
+        .workspace_config
+        .workspace_locator()
         // Avoid throwing warning messages as we're modifying the workspace
         .with_emit_warnings(
             !matches!(args.command, Command::Add(_)) && !matches!(args.command, Command::Remove(_)),
@@ -0,0 +1,35 @@
+use clap::Parser;
+use pixi_api::WorkspaceContext;
+
+use crate::{
+    cli_config::{LockFileUpdateConfig, WorkspaceConfig},
+    cli_interface::CliInterface,
+};
+
+/// Rename the workspace.
+///
+/// Updates the name in the manifest, removes the detached environments, which
+/// are stored in a directory named after the workspace and are installed again
+/// under the new name, and updates the lock file for packages that inherit
+/// their name from the workspace.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The new workspace name, please only use lowercase letters (a-z), digits (0-9), hyphens (-), and underscores (_)
+    pub name: String,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+
+    let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace);
+    workspace_ctx
+        .rename(&args.name, args.lock_file_update_config.lock_file_usage()?)
+        .await?;
+    Ok(())
+}
@@ -4,7 +4,6 @@ pub mod unset;
 pub mod verify;
 
 use clap::Parser;
-use pixi_core::WorkspaceLocator;
 
 use crate::cli_config::WorkspaceConfig;
 
@@ -35,8 +34,9 @@ pub enum Command {
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace_locator = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
+    let workspace_locator = args
+        .workspace_config
+        .workspace_locator()
         .with_ignore_pixi_version_check(true);
 
     match args.command {
@@ -0,0 +1,134 @@
+use std::io::Write;
+
+use clap::Parser;
+use itertools::Itertools;
+use miette::IntoDiagnostic;
+use pixi_api::{WorkspaceContext, workspace::ListSolveGroupsOptions};
+use pixi_consts::consts;
+use rattler_conda_types::Platform;
+
+use crate::{
+    cli_config::{LockFileUpdateConfig, WorkspaceConfig},
+    cli_interface::CliInterface,
+};
+
+/// Commands to inspect the solve groups of the workspace.
+#[derive(Parser, Debug)]
+pub struct Args {
+    #[clap(flatten)]
+    pub workspace_config: WorkspaceConfig,
+
+    /// The subcommand to execute
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Parser, Debug)]
+pub struct ListArgs {
+    /// The platform to inspect. Defaults to the current platform.
+    #[arg(long, short)]
+    pub platform: Option<Platform>,
+
+    /// Output the solve groups in JSON format.
+    #[clap(long)]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub lock_file_update_config: LockFileUpdateConfig,
+}
+
+#[derive(Parser, Debug)]
+pub enum Command {
+    /// List the solve groups with the environments that share them.
+    ///
+    /// Shows the packages that are forced to a common version because they
+    /// are locked in more than one environment of a group, and the direct
+    /// dependencies that are locked at a lower version than their own
+    /// environment allows because of the specs of another environment in the
+    /// group.
+    #[clap(visible_alias = "ls")]
+    List(ListArgs),
+}
+
+pub async fn execute(args: Args) -> miette::Result<()> {
+    let workspace = args.workspace_config.workspace_locator().locate()?;
+
+    let workspace_ctx = WorkspaceContext::new(CliInterface {}, workspace);
+
+    match args.command {
+        Command::List(args) => {
+            let solve_groups = workspace_ctx
+                .list_solve_groups_with_options(ListSolveGroupsOptions {
+                    platform: args.platform,
+                    lock_file_usage: args.lock_file_update_config.lock_file_usage()?,
+                })
+                .await?;
+
+            if args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&solve_groups).into_diagnostic()?
+                );
+                return Ok(());
+            }
+
+            if solve_groups.is_empty() {
+                eprintln!("The workspace does not define any solve groups");
+                return Ok(());
+            }
+
+            writeln!(
+                std::io::stdout(),
+                "Solve groups:\n{}",
+                solve_groups.iter().format_with("\n", |group, f| {
+                    f(&format_args!(
+                        "- {} ({}):\n    environments: {}\n    shared packages: {}",
+                        consts::SOLVE_GROUP_STYLE.apply_to(&group.name),
+                        group.platform,
+                        group
+                            .environments
+                            .iter()
+                            .map(|env| consts::ENVIRONMENT_STYLE.apply_to(env))
+                            .format(", "),
+                        group.shared_packages.len(),
+                    ))?;
+                    for package in &group.shared_packages {
+                        f(&format_args!(
+                            "\n      {} {} ({}): {}",
+                            console::style(&package.name).bold(),
+                            package.version,
+                            package.kind,
+                            package.environments.iter().format(", "),
+                        ))?;
+                    }
+                    if !group.downgrades.is_empty() {
+                        f(&format_args!("\n    downgrades:"))?;
+                    }
+                    for downgrade in &group.downgrades {
+                        f(&format_args!(
+                            "\n      {} in {}: locked {}, {} allowed, constrained by {}",
+                            console::style(&downgrade.package).bold(),
+                            consts::ENVIRONMENT_STYLE.apply_to(&downgrade.environment),
+                            downgrade.locked,
+                            console::style(&downgrade.allowed).yellow(),
+                            downgrade
+                                .constrained_by
+                                .iter()
+                                .map(|env| consts::ENVIRONMENT_STYLE.apply_to(env))
+                                .format(", "),
+                        ))?;
+                    }
+                    Ok(())
+                })
+            )
+            .inspect_err(|e| {
+                if e.kind() == std::io::ErrorKind::BrokenPipe {
+                    std::process::exit(0);
+                }
+            })
+            .into_diagnostic()?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use clap::Parser;
+use miette::IntoDiagnostic;
+use pixi_core::Workspace;
+use pixi_manifest::{
+    FeatureName, GLIBC_FAMILY, LibCFamilyAndVersion, LibCSystemRequirement, SystemRequirements,
+};
+use rattler_conda_types::Version;
+use rattler_virtual_packages::{VirtualPackageOverrides, VirtualPackages};
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Add the detected system requirements to the manifest.
+    #[clap(long)]
+    pub write: bool,
+
+    /// The name of the feature to add the system requirements to.
+    #[clap(long, short, requires = "write")]
+    pub feature: Option<String>,
+
+    /// Print the detected system requirements in JSON format.
+    #[clap(long, conflicts_with = "write")]
+    pub json: bool,
+}
+
+pub async fn execute(workspace: Workspace, args: Args) -> miette::Result<()> {
+    // Probe the machine itself, overrides do not describe it.
+    let virtual_packages =
+        VirtualPackages::detect(&VirtualPackageOverrides::default()).into_diagnostic()?;
+    let requirements = system_requirements_from(virtual_packages);
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&requirements).into_diagnostic()?
+        );
+        return Ok(());
+    }
+
+    eprintln!(
+        "{}",
+        console::style("The system requirements of this machine:")
+            .bold()
+            .bright()
+    );
+    eprint!("{requirements}");
+
+    if !args.write {
+        return Ok(());
+    }
+    if requirements.is_empty() {
+        eprintln!(
+            "{}No system requirements were detected, the manifest is unchanged",
+            console::style(console::Emoji("⚠ ", "")).yellow(),
+        );
+        return Ok(());
+    }
+
+    let feature_name = args
+        .feature
+        .map_or_else(FeatureName::default, FeatureName::from);
+    let mut workspace = workspace.modify()?;
+    workspace
+        .manifest()
+        .add_system_requirement(requirements, &feature_name)?;
+    workspace.save().await.into_diagnostic()?;
+
+    eprintln!(
+        "{}Added the detected system requirements to the manifest",
+        console::style(console::Emoji("✔ ", "")).green(),
+    );
+
+    Ok(())
+}
+
+/// Returns the system requirements that the detected virtual packages
+/// satisfy.
+///
+/// Only the major and minor version are kept, a workspace that requires a
+/// specific patch release of e.g. the kernel is rarely intended.
+fn system_requirements_from(virtual_packages: VirtualPackages) -> SystemRequirements {
+    SystemRequirements {
+        macos: virtual_packages.osx.map(|osx| major_minor(osx.version)),
+        linux: virtual_packages
+            .linux
+            .map(|linux| major_minor(linux.version)),
+        cuda: virtual_packages.cuda.map(|cuda| major_minor(cuda.version)),
+        libc: virtual_packages.libc.map(|libc| {
+            let version = major_minor(libc.version);
+            if libc.family == GLIBC_FAMILY {
+                LibCSystemRequirement::GlibC(version)
+            } else {
+                LibCSystemRequirement::OtherFamily(LibCFamilyAndVersion {
+                    family: Some(libc.family),
+                    version,
+                })
+            }
+        }),
+        archspec: None,
+    }
+}
+
+fn major_minor(version: Version) -> Version {
+    version.with_segments(..2).unwrap_or(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_virtual_packages::{Cuda, LibC, Linux};
+
+    use super::*;
+
+    #[test]
+    fn test_system_requirements_from() {
+        let requirements = system_requirements_from(VirtualPackages {
+            linux: Some(Linux {
+                version: Version::from_str("6.8.0").unwrap(),
+            }),
+            libc: Some(LibC {
+                family: String::from("glibc"),
+                version: Version::from_str("2.39").unwrap(),
+            }),
+            cuda: Some(Cuda {
+                version: Version::from_str("12.4").unwrap(),
+            }),
+            ..VirtualPackages::default()
+        });
+        assert_eq!(
+            requirements,
+            SystemRequirements {
+                linux: Some(Version::from_str("6.8").unwrap()),
+                cuda: Some(Version::from_str("12.4").unwrap()),
+                libc: Some(LibCSystemRequirement::GlibC(
+                    Version::from_str("2.39").unwrap()
+                )),
+                ..SystemRequirements::default()
+            }
+        );
+    }
+}
@@ -1,8 +1,8 @@
 pub mod add;
+pub mod detect;
 pub mod list;
 
 use clap::{Parser, ValueEnum};
-use pixi_core::WorkspaceLocator;
 
 use crate::cli_config::WorkspaceConfig;
 
@@ -42,15 +42,21 @@ pub enum Command {
     /// List the environments in the manifest file.
     #[clap(visible_alias = "ls")]
     List(list::Args),
+    /// Detect the system requirements of this machine.
+    ///
+    /// Prints the system requirements that the workspace can declare to be
+    /// installable on this machine, e.g. the glibc version, the CUDA driver
+    /// version and the macOS version. Use `--write` to add them to the
+    /// manifest.
+    Detect(detect::Args),
 }
 
 pub async fn execute(args: Args) -> miette::Result<()> {
-    let workspace = WorkspaceLocator::for_cli()
-        .with_search_start(args.workspace_config.workspace_locator_start())
-        .locate()?;
+    let workspace = args.workspace_config.workspace_locator().locate()?;
 
     match args.command {
         Command::Add(args) => add::execute(workspace, args).await,
         Command::List(args) => list::execute(&workspace, args),
+        Command::Detect(args) => detect::execute(workspace, args).await,
     }
 }
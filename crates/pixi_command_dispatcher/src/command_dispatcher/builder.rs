@@ -1,6 +1,7 @@
 use std::{path::PathBuf, sync::Arc};
 
 use pixi_build_frontend::BackendOverride;
+use pixi_config::LinkMode;
 use pixi_git::resolver::GitResolver;
 use pixi_glob::GlobHashCache;
 use pixi_url::resolver::UrlResolver;
@@ -35,6 +36,7 @@ pub struct CommandDispatcherBuilder {
     executor: Executor,
     tool_platform: Option<(Platform, Vec<GenericVirtualPackage>)>,
     execute_link_scripts: bool,
+    link_mode: LinkMode,
 }
 
 impl CommandDispatcherBuilder {
@@ -142,6 +144,11 @@ impl CommandDispatcherBuilder {
         }
     }
 
+    /// Sets how the files of packages are linked into the environments.
+    pub fn with_link_mode(self, link_mode: LinkMode) -> Self {
+        Self { link_mode, ..self }
+    }
+
     /// Completes the builder and returns a new [`CommandDispatcher`].
     pub fn finish(self) -> CommandDispatcher {
         let root_dir = self
@@ -192,6 +199,7 @@ impl CommandDispatcherBuilder {
             package_cache,
             tool_platform,
             execute_link_scripts: self.execute_link_scripts,
+            link_mode: self.link_mode,
             executor: self.executor,
         });
 
@@ -14,6 +14,10 @@ impl TaskSpec for GitUrl {
 
 impl CommandDispatcher {
     /// Check out the git repository associated with the given spec.
+    ///
+    /// If the spec refers to a subdirectory only that subdirectory is checked
+    /// out, so that large repositories can be used without checking out all
+    /// of their files.
     pub async fn pin_and_checkout_git(
         &self,
         git_spec: GitSpec,
@@ -28,7 +32,8 @@ impl CommandDispatcher {
             .map_err(GitError::from)
             .map_err(SourceCheckoutError::GitError)
             .map_err(CommandDispatcherError::Failed)?
-            .with_reference(git_reference.clone());
+            .with_reference(git_reference.clone())
+            .with_sparse_checkout(git_spec.subdirectory.clone());
 
         // Fetch the git url in the background
         let fetch = self
@@ -78,7 +83,8 @@ impl CommandDispatcher {
             git_spec.git.clone(),
             git_spec.source.reference.clone().into(),
             git_spec.source.commit,
-        );
+        )
+        .with_sparse_checkout(git_spec.source.subdirectory.clone());
         // Fetch the git url in the background
         let fetch = self
             .checkout_git_url(git_url)
@@ -17,6 +17,7 @@ pub(crate) use git::GitCheckoutTask;
 pub use instantiate_backend::{InstantiateBackendError, InstantiateBackendSpec};
 use pixi_build_discovery::{DiscoveredBackend, EnabledProtocols};
 use pixi_build_frontend::BackendOverride;
+use pixi_config::LinkMode;
 use pixi_git::resolver::GitResolver;
 use pixi_glob::GlobHashCache;
 use pixi_record::{PinnedPathSpec, PinnedSourceSpec, PixiRecord};
@@ -149,6 +150,9 @@ pub(crate) struct CommandDispatcherData {
     /// True if execution of link scripts is enabled.
     pub execute_link_scripts: bool,
 
+    /// How the files of packages are linked into the environments.
+    pub link_mode: LinkMode,
+
     /// The execution type of the dispatcher.
     pub executor: Executor,
 }
@@ -406,6 +410,11 @@ impl CommandDispatcher {
         self.data.execute_link_scripts
     }
 
+    /// Returns how the files of packages are linked into the environments.
+    pub fn link_mode(&self) -> LinkMode {
+        self.data.link_mode
+    }
+
     /// Returns the channel used to send messages to the command dispatcher.
     fn channel(&self) -> &CommandDispatcherChannel {
         self.channel
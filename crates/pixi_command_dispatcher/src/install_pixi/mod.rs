@@ -4,14 +4,19 @@ use std::{
     borrow::Cow,
     collections::{BTreeMap, HashMap, HashSet},
     ffi::OsStr,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use futures::StreamExt;
 use itertools::{Either, Itertools};
 use miette::Diagnostic;
 use pixi_build_discovery::EnabledProtocols;
+use pixi_config::LinkMode;
 use pixi_record::{PixiRecord, SourceRecord, VariantValue};
+use pixi_utils::{
+    metrics::{self, Counter},
+    timings::{self, Phase},
+};
 use rattler::install::{
     InstallationResultRecord, Installer, InstallerError, Transaction,
     link_script::{LinkScriptError, PrePostLinkResult},
@@ -166,6 +171,20 @@ impl InstallPixiEnvironmentSpec {
         }
         drop(build_futures);
 
+        // The installer silently falls back to copying files when they cannot
+        // be linked from the package cache, fail before installing anything
+        // instead.
+        if command_dispatcher.link_mode() == LinkMode::Hardlink {
+            let package_cache = command_dispatcher.cache_dirs().packages();
+            ensure_hard_links_supported(&package_cache, &self.prefix).map_err(|err| {
+                CommandDispatcherError::Failed(InstallPixiEnvironmentError::HardLinksNotSupported {
+                    package_cache,
+                    prefix: self.prefix.path().to_path_buf(),
+                    source: err,
+                })
+            })?;
+        }
+
         // Install the environment using the prefix installer
         let mut installer = Installer::new()
             .with_target_platform(self.build_environment.host_platform)
@@ -179,10 +198,12 @@ impl InstallPixiEnvironmentSpec {
             installer = installer.with_installed_packages(installed);
         };
 
-        if let Some(reporter) = install_reporter {
-            installer = installer.with_reporter(WrappingInstallReporter(reporter));
+        // The wrapping reporter also counts the downloads for the metrics.
+        if install_reporter.is_some() || metrics::is_enabled() {
+            installer = installer.with_reporter(WrappingInstallReporter::new(install_reporter));
         }
 
+        let _timer = timings::start(Phase::Link);
         let result = installer
             .install(self.prefix.path(), binary_records)
             .await
@@ -194,6 +215,15 @@ impl InstallPixiEnvironmentSpec {
             })
             .map_err(CommandDispatcherError::Failed)?;
 
+        metrics::add(
+            Counter::PackagesInstalled,
+            result.transaction.installed_packages().count() as u64,
+        );
+        metrics::add(
+            Counter::PackagesRemoved,
+            result.transaction.removed_packages().count() as u64,
+        );
+
         Ok(InstallPixiEnvironmentResult {
             transaction: result.transaction,
             post_link_script_result: result.post_link_script_result,
@@ -267,4 +297,70 @@ pub enum InstallPixiEnvironmentError {
     )]
     #[diagnostic(help("try `pixi clean` to reset the environment and run the command again"))]
     ConvertTransactionToPrefixRecord(Prefix, #[source] std::io::Error),
+
+    #[error(
+        "cannot hard link files from the package cache '{}' into '{}'",
+        .package_cache.display(),
+        .prefix.display()
+    )]
+    #[diagnostic(help(
+        "move the package cache to the same filesystem as the environment with `PIXI_CACHE_DIR`, or set `link-mode` to `auto`"
+    ))]
+    HardLinksNotSupported {
+        package_cache: PathBuf,
+        prefix: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Checks that files in the package cache can be hard linked into the prefix,
+/// which requires both to be on the same filesystem. Nothing is written to the
+/// prefix on Unix, so a failed check leaves it untouched.
+fn ensure_hard_links_supported(package_cache: &Path, prefix: &Prefix) -> std::io::Result<()> {
+    fs_err::create_dir_all(package_cache)?;
+    same_filesystem(package_cache, prefix.path())
+}
+
+#[cfg(unix)]
+fn same_filesystem(source_dir: &Path, target_dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    if fs_err::metadata(source_dir)?.dev() == fs_err::metadata(target_dir)?.dev() {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::CrossesDevices,
+            "the directories are on different filesystems",
+        ))
+    }
+}
+
+/// The volume of a directory is not exposed on stable Rust on Windows, so a
+/// hard link is created and removed again.
+#[cfg(not(unix))]
+fn same_filesystem(source_dir: &Path, target_dir: &Path) -> std::io::Result<()> {
+    let file_name = format!(".pixi-link-check-{}", std::process::id());
+    let source = source_dir.join(&file_name);
+    let target = target_dir.join(&file_name);
+    fs_err::write(&source, [])?;
+    let result = fs_err::hard_link(&source, &target);
+    let _ = fs_err::remove_file(&target);
+    let _ = fs_err::remove_file(&source);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hard_link_check_leaves_prefix_empty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let package_cache = temp_dir.path().join("pkgs");
+        let prefix = Prefix::create(temp_dir.path().join("envs").join("default")).unwrap();
+
+        ensure_hard_links_supported(&package_cache, &prefix).unwrap();
+        assert_eq!(fs_err::read_dir(prefix.path()).unwrap().count(), 0);
+        assert_eq!(fs_err::read_dir(&package_cache).unwrap().count(), 0);
+    }
 }
@@ -1,82 +1,169 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use pixi_utils::metrics::{self, Counter};
 use rattler::install::Transaction;
 use rattler_conda_types::{PrefixRecord, RepoDataRecord};
 
-pub struct WrappingInstallReporter(pub Box<dyn rattler::install::Reporter>);
+/// Forwards the events of the installer to the reporter of the caller, if
+/// any, and counts the packages that are taken from the cache or downloaded.
+pub struct WrappingInstallReporter {
+    reporter: Option<Box<dyn rattler::install::Reporter>>,
+    downloads: Mutex<Downloads>,
+}
+
+#[derive(Default)]
+struct Downloads {
+    /// The cache entries for which a package was downloaded.
+    cache_entries: HashSet<usize>,
+    /// The last reported progress of the downloads, in bytes.
+    progress: HashMap<usize, u64>,
+}
+
+impl WrappingInstallReporter {
+    pub fn new(reporter: Option<Box<dyn rattler::install::Reporter>>) -> Self {
+        Self {
+            reporter,
+            downloads: Mutex::default(),
+        }
+    }
+}
 
 impl rattler::install::Reporter for WrappingInstallReporter {
     fn on_transaction_start(&self, transaction: &Transaction<PrefixRecord, RepoDataRecord>) {
-        self.0.on_transaction_start(transaction)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_transaction_start(transaction)
+        }
     }
 
     fn on_transaction_operation_start(&self, operation: usize) {
-        self.0.on_transaction_operation_start(operation)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_transaction_operation_start(operation)
+        }
     }
 
     fn on_populate_cache_start(&self, operation: usize, record: &RepoDataRecord) -> usize {
-        self.0.on_populate_cache_start(operation, record)
+        self.reporter.as_ref().map_or(operation, |reporter| {
+            reporter.on_populate_cache_start(operation, record)
+        })
     }
 
     fn on_validate_start(&self, cache_entry: usize) -> usize {
-        self.0.on_validate_start(cache_entry)
+        self.reporter.as_ref().map_or(cache_entry, |reporter| {
+            reporter.on_validate_start(cache_entry)
+        })
     }
 
     fn on_validate_complete(&self, validate_idx: usize) {
-        self.0.on_validate_complete(validate_idx)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_validate_complete(validate_idx)
+        }
     }
 
     fn on_download_start(&self, cache_entry: usize) -> usize {
-        self.0.on_download_start(cache_entry)
+        let download_idx = self.reporter.as_ref().map_or(cache_entry, |reporter| {
+            reporter.on_download_start(cache_entry)
+        });
+        if let Ok(mut downloads) = self.downloads.lock() {
+            downloads.cache_entries.insert(cache_entry);
+        }
+        metrics::add(Counter::CacheMisses, 1);
+        download_idx
     }
 
     fn on_download_progress(&self, download_idx: usize, progress: u64, total: Option<u64>) {
-        self.0.on_download_progress(download_idx, progress, total)
+        if let Ok(mut downloads) = self.downloads.lock() {
+            downloads.progress.insert(download_idx, progress);
+        }
+        if let Some(reporter) = &self.reporter {
+            reporter.on_download_progress(download_idx, progress, total)
+        }
     }
 
     fn on_download_completed(&self, download_idx: usize) {
-        self.0.on_download_completed(download_idx)
+        let bytes = self
+            .downloads
+            .lock()
+            .ok()
+            .and_then(|mut downloads| downloads.progress.remove(&download_idx));
+        metrics::add(Counter::BytesDownloaded, bytes.unwrap_or_default());
+        if let Some(reporter) = &self.reporter {
+            reporter.on_download_completed(download_idx)
+        }
     }
 
     fn on_populate_cache_complete(&self, cache_entry: usize) {
-        self.0.on_populate_cache_complete(cache_entry)
+        let downloaded = self
+            .downloads
+            .lock()
+            .is_ok_and(|mut downloads| downloads.cache_entries.remove(&cache_entry));
+        if !downloaded {
+            metrics::add(Counter::CacheHits, 1);
+        }
+        if let Some(reporter) = &self.reporter {
+            reporter.on_populate_cache_complete(cache_entry)
+        }
     }
 
     fn on_unlink_start(&self, operation: usize, record: &PrefixRecord) -> usize {
-        self.0.on_unlink_start(operation, record)
+        self.reporter.as_ref().map_or(operation, |reporter| {
+            reporter.on_unlink_start(operation, record)
+        })
     }
 
     fn on_unlink_complete(&self, index: usize) {
-        self.0.on_unlink_complete(index)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_unlink_complete(index)
+        }
     }
 
     fn on_link_start(&self, operation: usize, record: &RepoDataRecord) -> usize {
-        self.0.on_link_start(operation, record)
+        self.reporter.as_ref().map_or(operation, |reporter| {
+            reporter.on_link_start(operation, record)
+        })
     }
 
     fn on_link_complete(&self, index: usize) {
-        self.0.on_link_complete(index)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_link_complete(index)
+        }
     }
 
     fn on_post_link_start(&self, package_name: &str, script_path: &str) -> usize {
-        self.0.on_post_link_start(package_name, script_path)
+        self.reporter.as_ref().map_or(0, |reporter| {
+            reporter.on_post_link_start(package_name, script_path)
+        })
     }
 
     fn on_post_link_complete(&self, index: usize, success: bool) {
-        self.0.on_post_link_complete(index, success)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_post_link_complete(index, success)
+        }
     }
 
     fn on_pre_unlink_start(&self, package_name: &str, script_path: &str) -> usize {
-        self.0.on_pre_unlink_start(package_name, script_path)
+        self.reporter.as_ref().map_or(0, |reporter| {
+            reporter.on_pre_unlink_start(package_name, script_path)
+        })
     }
 
     fn on_pre_unlink_complete(&self, index: usize, success: bool) {
-        self.0.on_pre_unlink_complete(index, success)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_pre_unlink_complete(index, success)
+        }
     }
 
     fn on_transaction_operation_complete(&self, operation: usize) {
-        self.0.on_transaction_operation_complete(operation)
+        if let Some(reporter) = &self.reporter {
+            reporter.on_transaction_operation_complete(operation)
+        }
     }
 
     fn on_transaction_complete(&self) {
-        self.0.on_transaction_complete()
+        if let Some(reporter) = &self.reporter {
+            reporter.on_transaction_complete()
+        }
     }
 }
@@ -9,6 +9,7 @@ use itertools::Itertools;
 use pixi_record::{PixiRecord, SourceRecord};
 use pixi_spec::{BinarySpec, SourceSpec};
 use pixi_spec_containers::DependencyMap;
+use pixi_utils::timings::{self, Phase};
 use rattler_conda_types::{
     ChannelConfig, ChannelUrl, GenericVirtualPackage, MatchSpec, Platform, RepoDataRecord,
 };
@@ -112,6 +113,7 @@ impl SolveCondaEnvironmentSpec {
     ) -> Result<Vec<PixiRecord>, CommandDispatcherError<SolveCondaEnvironmentError>> {
         // Solving is a CPU-intensive task, we spawn this on a background task to allow
         // for more concurrency.
+        let _timer = timings::start(Phase::CondaSolve);
         let solve_result = tokio::task::spawn_blocking(move || {
             // Determine for which records we have source records because those records should only
             //  be installed as source records.
@@ -10,6 +10,7 @@ use pixi_build_discovery::EnabledProtocols;
 use pixi_record::{PixiRecord, VariantValue};
 use pixi_spec::{BinarySpec, PixiSpec, SourceSpec, SpecConversionError};
 use pixi_spec_containers::DependencyMap;
+use pixi_utils::timings::{self, Phase};
 use rattler_conda_types::{Channel, ChannelConfig, ChannelUrl, ParseChannelError, Platform};
 use rattler_repodata_gateway::RepoData;
 use rattler_solve::{ChannelPriority, SolveStrategy};
@@ -172,6 +173,7 @@ impl PixiEnvironmentSpec {
         // all (recursively) discovered source dependencies. This ensures that all
         // repodata required to solve the environment is loaded.
         let fetch_repodata_start = Instant::now();
+        let fetch_repodata_timer = timings::start(Phase::RepodataFetch);
         let query = command_queue
             .gateway()
             .query(
@@ -193,6 +195,7 @@ impl PixiEnvironmentSpec {
             .await
             .map_err(SolvePixiEnvironmentError::QueryError)
             .map_err(CommandDispatcherError::Failed)?;
+        drop(fetch_repodata_timer);
         let total_records = binary_repodata.iter().map(RepoData::len).sum::<usize>();
         tracing::debug!(
             "fetched {total_records} records in {:?}",
@@ -18,6 +18,7 @@ use rattler_conda_types::{
 };
 use rattler_networking::s3_middleware;
 use rattler_repodata_gateway::{Gateway, GatewayBuilder, SourceConfig};
+use rattler_virtual_packages::{Override, VirtualPackageOverrides};
 use reqwest::{NoProxy, Proxy};
 use serde::{
     Deserialize, Serialize,
@@ -27,6 +28,9 @@ use url::Url;
 
 const EXPERIMENTAL: &str = "experimental";
 
+/// The default number of times a failed network request is retried.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
 /// Controls which root certificates to use for TLS connections.
 ///
 /// - `Webpki`: Use bundled Mozilla root certificates (portable, works everywhere)
@@ -170,6 +174,11 @@ pub struct ConfigCli {
     #[arg(long, help_heading = consts::CLAP_CONFIG_OPTIONS)]
     concurrent_solves: Option<usize>,
 
+    /// Override the version of a detected virtual package, e.g.
+    /// `__cuda=12.4`. An empty version removes the virtual package
+    #[arg(long, help_heading = consts::CLAP_CONFIG_OPTIONS, value_name = "NAME=VERSION", value_parser = parse_virtual_package_override)]
+    override_virtual_package: Vec<(OverridableVirtualPackage, String)>,
+
     /// Set pinning strategy
     #[arg(long, help_heading = consts::CLAP_CONFIG_OPTIONS, value_enum)]
     pinning_strategy: Option<PinningStrategy>,
@@ -200,6 +209,12 @@ pub struct ConfigCliPrompt {
     /// Do not change the PS1 variable when starting a prompt.
     #[arg(long, help_heading = consts::CLAP_CONFIG_OPTIONS)]
     change_ps1: Option<bool>,
+
+    /// The template of the name that is added to the prompt, `{workspace}` and
+    /// `{environment}` are replaced with the names of the workspace and the
+    /// environment.
+    #[arg(long, help_heading = consts::CLAP_CONFIG_OPTIONS)]
+    prompt_template: Option<String>,
 }
 
 impl From<ConfigCliPrompt> for Config {
@@ -207,6 +222,7 @@ impl From<ConfigCliPrompt> for Config {
         Self {
             shell: ShellConfig {
                 change_ps1: cli.change_ps1,
+                prompt_template: cli.prompt_template,
                 ..Default::default()
             },
             ..Default::default()
@@ -218,6 +234,7 @@ impl ConfigCliPrompt {
     pub fn merge_config(self, config: Config) -> Config {
         let mut config = config;
         config.shell.change_ps1 = self.change_ps1.or(config.shell.change_ps1);
+        config.shell.prompt_template = self.prompt_template.or(config.shell.prompt_template);
         config
     }
 }
@@ -477,6 +494,104 @@ impl ExperimentalConfig {
     }
 }
 
+/// Controls where credentials stored by `pixi auth login` are kept.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialStorage {
+    /// Use the OS keyring when it is available and fall back to the
+    /// credentials file otherwise.
+    #[default]
+    Auto,
+    /// Only use the OS keyring (macOS Keychain, Windows Credential Manager or
+    /// Secret Service).
+    Keyring,
+    /// Only use the plaintext credentials file.
+    File,
+}
+
+impl CredentialStorage {
+    /// Whether credentials are read from and written to the OS keyring.
+    pub fn uses_keyring(&self) -> bool {
+        matches!(self, CredentialStorage::Auto | CredentialStorage::Keyring)
+    }
+
+    /// Whether credentials are read from and written to the credentials file.
+    pub fn uses_file(&self) -> bool {
+        matches!(self, CredentialStorage::Auto | CredentialStorage::File)
+    }
+}
+
+impl FromStr for CredentialStorage {
+    type Err = serde::de::value::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+impl std::fmt::Display for CredentialStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialStorage::Auto => write!(f, "auto"),
+            CredentialStorage::Keyring => write!(f, "keyring"),
+            CredentialStorage::File => write!(f, "file"),
+        }
+    }
+}
+
+/// Configuration to obtain short-lived tokens for a host through OIDC.
+///
+/// The identity token of the CI provider is exchanged for a token that is
+/// accepted by the host. The token is refreshed automatically once it expires.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct OidcConfig {
+    /// The endpoint that exchanges the identity token for an access token.
+    pub token_endpoint: Url,
+
+    /// The audience to request the identity token for.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+
+    /// Read the identity token from this environment variable instead of
+    /// requesting it from GitHub Actions (e.g. for GitLab CI `id_tokens`).
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token_env: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct AuthConfig {
+    /// Where to store credentials.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<CredentialStorage>,
+
+    /// Hosts for which short-lived tokens are requested through OIDC.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub oidc: HashMap<String, OidcConfig>,
+}
+
+impl AuthConfig {
+    /// Merge the given AuthConfig into the current one.
+    /// The `other` config will have higher priority
+    pub fn merge(self, other: Self) -> Self {
+        let mut oidc = self.oidc;
+        oidc.extend(other.oidc);
+        Self {
+            storage: other.storage.or(self.storage),
+            oidc,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.storage.is_none() && self.oidc.is_empty()
+    }
+}
+
 // Making the default values part of pixi_config to allow for printing the
 // default settings in the future.
 /// The default maximum number of concurrent solves that can be run at once.
@@ -707,6 +822,180 @@ impl FromStr for RunPostLinkScripts {
     }
 }
 
+/// What to do when the signature of a package cannot be verified.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum VerifySignatures {
+    /// Do not verify the signatures of packages.
+    #[default]
+    Off,
+    /// Print a warning for packages whose signature cannot be verified.
+    Warn,
+    /// Fail the installation of packages whose signature cannot be verified.
+    Error,
+}
+
+impl FromStr for VerifySignatures {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// The format of the log output of pixi.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// Human readable log lines.
+    #[default]
+    Text,
+    /// Newline-delimited JSON objects with the level, target, spans and
+    /// timestamp of every event.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// How the files of conda packages are linked from the package cache into
+/// the environments.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkMode {
+    /// Use reflinks or hard links when the filesystem supports them, and copy
+    /// the files otherwise.
+    #[default]
+    Auto,
+    /// Require that the files can be linked from the package cache, so that
+    /// every environment on the same filesystem uses the same copy of a file.
+    /// Fails if the cache and the environment are on different filesystems
+    /// instead of copying the files.
+    Hardlink,
+}
+
+impl FromStr for LinkMode {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// What `pixi run` does when the environment of a task is not installed.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallMissingEnvironments {
+    /// Install the environment.
+    #[default]
+    Always,
+    /// Ask before installing the environment, fail if the terminal is not
+    /// interactive.
+    Prompt,
+    /// Fail instead of installing the environment.
+    Never,
+}
+
+impl FromStr for InstallMissingEnvironments {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// What `pixi run` does when the lock-file is not up-to-date with the
+/// manifest and neither `--frozen` nor `--locked` is passed.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum RunLockPolicy {
+    /// Update the lock-file.
+    #[default]
+    Update,
+    /// Ask before updating the lock-file, fail if the terminal is not
+    /// interactive.
+    Prompt,
+    /// Fail instead of updating the lock-file, like `--locked`.
+    Error,
+}
+
+impl FromStr for RunLockPolicy {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// Whether `pixi run` only downloads the outputs of tasks from the remote
+/// task cache or also uploads them.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskCacheMode {
+    /// Only restore the outputs of tasks from the remote cache.
+    #[default]
+    ReadOnly,
+    /// Also upload the outputs of tasks that were run to the remote cache.
+    ReadWrite,
+}
+
+impl FromStr for TaskCacheMode {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// A virtual package whose detected version can be overridden.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum OverridableVirtualPackage {
+    #[serde(rename = "__win")]
+    Win,
+    #[serde(rename = "__osx")]
+    Osx,
+    #[serde(rename = "__linux")]
+    Linux,
+    #[serde(rename = "__glibc")]
+    Glibc,
+    #[serde(rename = "__cuda")]
+    Cuda,
+    #[serde(rename = "__archspec")]
+    Archspec,
+}
+
+impl OverridableVirtualPackage {
+    /// The environment variable that overrides this virtual package, it takes
+    /// precedence over the configuration.
+    pub fn env_var(self) -> &'static str {
+        match self {
+            Self::Win => "CONDA_OVERRIDE_WIN",
+            Self::Osx => "CONDA_OVERRIDE_OSX",
+            Self::Linux => "CONDA_OVERRIDE_LINUX",
+            Self::Glibc => "CONDA_OVERRIDE_GLIBC",
+            Self::Cuda => "CONDA_OVERRIDE_CUDA",
+            Self::Archspec => "CONDA_OVERRIDE_ARCHSPEC",
+        }
+    }
+}
+
+impl FromStr for OverridableVirtualPackage {
+    type Err = serde::de::value::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+    }
+}
+
+/// Parses a `<name>=<version>` virtual package override from the command line.
+fn parse_virtual_package_override(
+    value: &str,
+) -> Result<(OverridableVirtualPackage, String), String> {
+    let (name, version) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<name>=<version>`, got '{value}'"))?;
+    let name = OverridableVirtualPackage::from_str(name.trim()).map_err(|err| err.to_string())?;
+    Ok((name, version.trim().to_string()))
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
@@ -721,6 +1010,11 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authentication_override_file: Option<PathBuf>,
 
+    /// Credential storage and OIDC configuration.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "AuthConfig::is_default")]
+    pub auth: AuthConfig,
+
     /// If set to true, pixi will not verify the TLS certificate of the server.
     #[serde(default)]
     #[serde(alias = "tls_no_verify")] // BREAK: remove to stop supporting snake_case alias
@@ -811,6 +1105,78 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tool_platform: Option<Platform>,
 
+    /// Whether to verify the signatures of conda packages and the
+    /// attestations of PyPI wheels before they are installed.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verify_signatures: Option<VerifySignatures>,
+
+    /// The `root.json` of the conda content trust metadata to use per channel.
+    /// The signatures of packages from channels without a trust root cannot
+    /// be verified.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub trust_roots: HashMap<Url, PathBuf>,
+
+    /// The format of the log output, `text` or `json`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_format: Option<LogFormat>,
+
+    /// Overrides of the styles of the output, by the name of the style. The
+    /// styles are written as dotted strings like `bold.red`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub styles: HashMap<String, String>,
+
+    /// Overrides of the versions of the virtual packages that are detected on
+    /// the system. An empty version removes the virtual package.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub virtual_package_overrides: HashMap<OverridableVirtualPackage, String>,
+
+    /// The maximum number of times a network request that failed with a
+    /// transient error is retried. Interrupted downloads are resumed up to
+    /// the same number of times.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Whether `pixi run` installs the environment of a task that is not
+    /// installed yet: `always`, `prompt` or `never`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_missing_environments: Option<InstallMissingEnvironments>,
+
+    /// Whether `pixi run` updates a lock-file that is not up-to-date:
+    /// `update`, `prompt` or `error`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_lock_policy: Option<RunLockPolicy>,
+
+    /// Whether `pixi self-update` is allowed to replace the pixi binary. Set
+    /// this to `false` in the system configuration of managed environments.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_self_update: Option<bool>,
+
+    /// How the files of packages are linked into the environments: `auto` or
+    /// `hardlink`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_mode: Option<LinkMode>,
+
+    /// Workspaces that `pixi run` falls back to when it is not invoked from
+    /// within a workspace. The first workspace that exists is used.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fallback_workspaces: Vec<PathBuf>,
+
+    /// The remote cache that the outputs of tasks are shared through.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "TaskCacheConfig::is_default")]
+    pub task_cache: TaskCacheConfig,
+
     //////////////////////
     // Deprecated fields //
     //////////////////////
@@ -829,6 +1195,7 @@ impl Default for Config {
         Self {
             default_channels: Vec::new(),
             authentication_override_file: None,
+            auth: AuthConfig::default(),
             tls_no_verify: None,
             tls_root_certs: None,
             mirrors: HashMap::new(),
@@ -846,6 +1213,18 @@ impl Default for Config {
             proxy_config: ProxyConfig::default(),
             build: BuildConfig::default(),
             tool_platform: None,
+            verify_signatures: None,
+            trust_roots: HashMap::new(),
+            log_format: None,
+            styles: HashMap::new(),
+            virtual_package_overrides: HashMap::new(),
+            max_retries: None,
+            install_missing_environments: None,
+            run_lock_policy: None,
+            allow_self_update: None,
+            link_mode: None,
+            fallback_workspaces: Vec::new(),
+            task_cache: TaskCacheConfig::default(),
 
             // Deprecated fields
             change_ps1: None,
@@ -887,6 +1266,7 @@ impl From<ConfigCli> for Config {
                 },
             },
             pinning_strategy: cli.pinning_strategy,
+            virtual_package_overrides: cli.override_virtual_package.into_iter().collect(),
             ..Default::default()
         }
     }
@@ -939,6 +1319,13 @@ pub struct ShellConfig {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub change_ps1: Option<bool>,
+
+    /// The template of the name that is added to the prompt. `{workspace}`
+    /// and `{environment}` are replaced with the names of the workspace and
+    /// the environment.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
 }
 
 impl ShellConfig {
@@ -949,6 +1336,7 @@ impl ShellConfig {
                 .source_completion_scripts
                 .or(self.source_completion_scripts),
             change_ps1: other.change_ps1.or(self.change_ps1),
+            prompt_template: other.prompt_template.or(self.prompt_template),
         }
     }
 
@@ -956,11 +1344,16 @@ impl ShellConfig {
         self.force_activate.is_none()
             && self.source_completion_scripts.is_none()
             && self.change_ps1.is_none()
+            && self.prompt_template.is_none()
     }
 
     pub fn source_completion_scripts(&self) -> bool {
         self.source_completion_scripts.unwrap_or(true)
     }
+
+    pub fn prompt_template(&self) -> Option<&str> {
+        self.prompt_template.as_deref()
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Eq)]
@@ -978,23 +1371,61 @@ pub struct ProxyConfig {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub non_proxy_hosts: Vec<String>,
+    /// Proxies for specific hosts, these take precedence over the `https` and
+    /// `http` proxies and the `non-proxy-hosts`. A host starting with `*.` or
+    /// `.` also matches all of its subdomains.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, Url>,
 }
 
 impl ProxyConfig {
     pub fn is_default(&self) -> bool {
-        self.https.is_none() && self.https.is_none() && self.non_proxy_hosts.is_empty()
+        self.https.is_none()
+            && self.http.is_none()
+            && self.non_proxy_hosts.is_empty()
+            && self.hosts.is_empty()
     }
     pub fn merge(&self, other: Self) -> Self {
+        let mut hosts = self.hosts.clone();
+        hosts.extend(other.hosts.clone());
         Self {
             https: other.https.as_ref().or(self.https.as_ref()).cloned(),
             http: other.http.as_ref().or(self.http.as_ref()).cloned(),
-            non_proxy_hosts: if other.is_default() {
+            non_proxy_hosts: if other.https.is_none()
+                && other.http.is_none()
+                && other.non_proxy_hosts.is_empty()
+            {
                 self.non_proxy_hosts.clone()
             } else {
                 other.non_proxy_hosts.clone()
             },
+            hosts,
         }
     }
+
+    /// Returns the proxy configured for the given host in `hosts`. An exact
+    /// match is preferred, otherwise the most specific wildcard is used.
+    pub fn proxy_for_host(&self, host: &str) -> Option<&Url> {
+        if let Some(url) = self.hosts.get(host) {
+            return Some(url);
+        }
+
+        self.hosts
+            .iter()
+            .filter_map(|(pattern, url)| {
+                let suffix = pattern
+                    .strip_prefix("*.")
+                    .or_else(|| pattern.strip_prefix('.'))?;
+                let matches = host == suffix
+                    || host
+                        .strip_suffix(suffix)
+                        .is_some_and(|prefix| prefix.ends_with('.'));
+                matches.then_some((suffix.len(), url))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, url)| url)
+    }
 }
 
 /// Container for the package format and compression level
@@ -1116,6 +1547,52 @@ impl BuildConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct TaskCacheConfig {
+    /// The URL of the remote task cache, an `http(s)://` or `s3://` URL.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<Url>,
+
+    /// Whether the outputs of tasks are only downloaded from the remote cache
+    /// or also uploaded to it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<TaskCacheMode>,
+}
+
+impl TaskCacheConfig {
+    pub fn is_default(&self) -> bool {
+        self.url.is_none() && self.mode.is_none()
+    }
+
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            url: other.url.or(self.url),
+            mode: other.mode.or(self.mode),
+        }
+    }
+
+    pub fn mode(&self) -> TaskCacheMode {
+        self.mode.unwrap_or_default()
+    }
+
+    /// Validates that the cache can be used in the configured mode, an `s3://`
+    /// cache can only be read from.
+    pub fn validate(&self) -> miette::Result<()> {
+        if let Some(url) = &self.url
+            && url.scheme() == "s3"
+            && self.mode() == TaskCacheMode::ReadWrite
+        {
+            return Err(miette!(
+                "The `task-cache.mode` cannot be `read-write` for the `s3://` task cache {url}, it can only be read from"
+            ));
+        }
+        Ok(())
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ConfigError {
     #[error("no file was found at {0}")]
@@ -1308,6 +1785,8 @@ impl Config {
             detached_environments.validate()?
         }
 
+        self.task_cache.validate()?;
+
         Ok(())
     }
 
@@ -1371,6 +1850,10 @@ impl Config {
     // Get all possible keys of the configuration
     pub fn get_keys(&self) -> &[&str] {
         &[
+            "allow-self-update",
+            "auth",
+            "auth.oidc",
+            "auth.storage",
             "authentication-override-file",
             "concurrency",
             "concurrency.downloads",
@@ -1379,9 +1862,15 @@ impl Config {
             "detached-environments",
             "experimental",
             "experimental.use-environment-activation-cache",
+            "fallback-workspaces",
+            "install-missing-environments",
+            "link-mode",
+            "log-format",
+            "max-retries",
             "mirrors",
             "pinning-strategy",
             "proxy-config",
+            "proxy-config.hosts",
             "proxy-config.http",
             "proxy-config.https",
             "proxy-config.non-proxy-hosts",
@@ -1395,6 +1884,7 @@ impl Config {
             "repodata-config.disable-jlap",
             "repodata-config.disable-sharded",
             "repodata-config.disable-zstd",
+            "run-lock-policy",
             "run-post-link-scripts",
             "s3-options",
             "s3-options.<bucket>",
@@ -1404,10 +1894,18 @@ impl Config {
             "shell",
             "shell.change-ps1",
             "shell.force-activate",
+            "shell.prompt-template",
             "shell.source-completion-scripts",
+            "styles",
+            "task-cache",
+            "task-cache.mode",
+            "task-cache.url",
             "tls-no-verify",
             "tls-root-certs",
             "tool-platform",
+            "trust-roots",
+            "verify-signatures",
+            "virtual-package-overrides",
         ]
     }
 
@@ -1416,6 +1914,10 @@ impl Config {
     #[must_use]
     pub fn merge_config(mut self, mut other: Config) -> Self {
         self.mirrors.extend(other.mirrors);
+        self.trust_roots.extend(other.trust_roots);
+        self.styles.extend(other.styles);
+        self.virtual_package_overrides
+            .extend(other.virtual_package_overrides);
         other.loaded_from.extend(self.loaded_from);
 
         Self {
@@ -1429,6 +1931,7 @@ impl Config {
             authentication_override_file: other
                 .authentication_override_file
                 .or(self.authentication_override_file),
+            auth: self.auth.merge(other.auth),
             // Extended self.mirrors with other.mirrors
             mirrors: self.mirrors,
             loaded_from: other.loaded_from,
@@ -1456,6 +1959,27 @@ impl Config {
             proxy_config: self.proxy_config.merge(other.proxy_config),
             build: self.build.merge(other.build),
             tool_platform: self.tool_platform.or(other.tool_platform),
+            verify_signatures: other.verify_signatures.or(self.verify_signatures),
+            log_format: other.log_format.or(self.log_format),
+            // Extended self.styles with other.styles
+            styles: self.styles,
+            // Extended self.trust_roots with other.trust_roots
+            trust_roots: self.trust_roots,
+            // Extended self.virtual_package_overrides with other.virtual_package_overrides
+            virtual_package_overrides: self.virtual_package_overrides,
+            max_retries: other.max_retries.or(self.max_retries),
+            install_missing_environments: other
+                .install_missing_environments
+                .or(self.install_missing_environments),
+            run_lock_policy: other.run_lock_policy.or(self.run_lock_policy),
+            allow_self_update: other.allow_self_update.or(self.allow_self_update),
+            link_mode: other.link_mode.or(self.link_mode),
+            fallback_workspaces: if other.fallback_workspaces.is_empty() {
+                self.fallback_workspaces
+            } else {
+                other.fallback_workspaces
+            },
+            task_cache: self.task_cache.merge(other.task_cache),
 
             // Deprecated fields that we can ignore as we handle them inside `shell.` field
             change_ps1: None,
@@ -1493,6 +2017,11 @@ impl Config {
         self.authentication_override_file.as_ref()
     }
 
+    /// Retrieve where credentials are stored (defaults to `auto`).
+    pub fn credential_storage(&self) -> CredentialStorage {
+        self.auth.storage.unwrap_or_default()
+    }
+
     /// Returns the global channel configuration.
     ///
     /// This roots the channel configuration to the current directory. When
@@ -1542,17 +2071,149 @@ impl Config {
         self.tool_platform.unwrap_or(Platform::current())
     }
 
+    /// Retrieve the value for the verify_signatures field (defaults to off).
+    pub fn verify_signatures(&self) -> VerifySignatures {
+        self.verify_signatures.unwrap_or_default()
+    }
+
+    /// Retrieve the value for the log_format field (defaults to text).
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or_default()
+    }
+
+    /// Retrieve the overrides of the styles of the output.
+    pub fn styles(&self) -> &HashMap<String, String> {
+        &self.styles
+    }
+
+    /// Retrieve the value for the max_retries field (defaults to 3).
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries.unwrap_or(DEFAULT_MAX_RETRIES)
+    }
+
+    /// Retrieve the value for the allow_self_update field (defaults to true).
+    pub fn allow_self_update(&self) -> bool {
+        self.allow_self_update.unwrap_or(true)
+    }
+
+    /// Retrieve the value for the install_missing_environments field
+    /// (defaults to always).
+    pub fn install_missing_environments(&self) -> InstallMissingEnvironments {
+        self.install_missing_environments.unwrap_or_default()
+    }
+
+    /// Retrieve the value for the run_lock_policy field (defaults to update).
+    pub fn run_lock_policy(&self) -> RunLockPolicy {
+        self.run_lock_policy.unwrap_or_default()
+    }
+
+    /// Retrieve the value for the link_mode field (defaults to auto).
+    pub fn link_mode(&self) -> LinkMode {
+        self.link_mode.unwrap_or_default()
+    }
+
+    /// Retrieve the workspaces to fall back to when no workspace is found,
+    /// with `~` expanded to the home directory.
+    pub fn fallback_workspaces(&self) -> Vec<PathBuf> {
+        self.fallback_workspaces
+            .iter()
+            .map(|path| match (path.strip_prefix("~"), dirs::home_dir()) {
+                (Ok(rest), Some(home_dir)) => home_dir.join(rest),
+                _ => path.clone(),
+            })
+            .collect()
+    }
+
+    /// Retrieve the configuration of the remote task cache.
+    pub fn task_cache(&self) -> &TaskCacheConfig {
+        &self.task_cache
+    }
+
+    /// Returns the path of the trust root for the given channel, if any.
+    pub fn trust_root(&self, channel: &Url) -> Option<&PathBuf> {
+        let channel = channel.as_str().trim_end_matches('/');
+        self.trust_roots
+            .iter()
+            .find(|(url, _)| url.as_str().trim_end_matches('/') == channel)
+            .map(|(_, path)| path)
+    }
+
+    /// Returns the overrides to use when detecting the virtual packages of the
+    /// system. The `CONDA_OVERRIDE_*` environment variables take precedence
+    /// over the overrides in the configuration.
+    pub fn virtual_package_overrides(&self) -> VirtualPackageOverrides {
+        self.virtual_package_overrides_with_env(|name| std::env::var_os(name).is_some())
+    }
+
+    fn virtual_package_overrides_with_env(
+        &self,
+        env_var_is_set: impl Fn(&str) -> bool,
+    ) -> VirtualPackageOverrides {
+        let mut overrides = VirtualPackageOverrides::from_env();
+        for (package, version) in &self.virtual_package_overrides {
+            if env_var_is_set(package.env_var()) {
+                continue;
+            }
+            let version = Some(Override::String(version.clone()));
+            match package {
+                OverridableVirtualPackage::Win => overrides.win = version,
+                OverridableVirtualPackage::Osx => overrides.osx = version,
+                OverridableVirtualPackage::Linux => overrides.linux = version,
+                OverridableVirtualPackage::Glibc => overrides.libc = version,
+                OverridableVirtualPackage::Cuda => overrides.cuda = version,
+                OverridableVirtualPackage::Archspec => overrides.archspec = version,
+            }
+        }
+        overrides
+    }
+
+    /// Returns the proxies to use for all requests made by pixi, this includes
+    /// repodata fetches, package downloads and PyPI requests.
+    ///
+    /// Per-host proxies from `proxy-config.hosts` are always applied. The
+    /// `https` and `http` proxies are only used when no proxy environment
+    /// variables are set. Hosts in `non-proxy-hosts` and in the `NO_PROXY`
+    /// environment variable bypass these proxies.
     pub fn get_proxies(&self) -> reqwest::Result<Vec<Proxy>> {
-        if (self.proxy_config.https.is_none() && self.proxy_config.http.is_none())
-            || *USE_PROXY_FROM_ENV
-        {
-            return Ok(vec![]);
+        let mut result: Vec<Proxy> = Vec::new();
+
+        if !self.proxy_config.hosts.is_empty() {
+            let proxy_config = self.proxy_config.clone();
+            result.push(Proxy::custom(move |url| {
+                url.host_str()
+                    .and_then(|host| proxy_config.proxy_for_host(host))
+                    .cloned()
+            }));
+        }
+
+        if *USE_PROXY_FROM_ENV {
+            // Adding any proxy disables the proxies that reqwest reads from the
+            // environment, so we have to add them explicitly.
+            if !result.is_empty() {
+                if let Some(url) = &*ENV_HTTP_PROXY {
+                    result.push(Proxy::http(url)?.no_proxy(NoProxy::from_env()));
+                }
+                if let Some(url) = &*ENV_HTTPS_PROXY {
+                    result.push(Proxy::https(url)?.no_proxy(NoProxy::from_env()));
+                }
+            }
+            return Ok(result);
         }
 
-        let config_no_proxy =
-            Some(self.proxy_config.non_proxy_hosts.iter().join(",")).filter(|v| !v.is_empty());
+        if self.proxy_config.https.is_none() && self.proxy_config.http.is_none() {
+            return Ok(result);
+        }
+
+        let config_no_proxy = Some(
+            self.proxy_config
+                .non_proxy_hosts
+                .iter()
+                .map(String::as_str)
+                .chain(ENV_NO_PROXY.as_deref())
+                .join(","),
+        )
+        .filter(|v| !v.is_empty());
 
-        let mut result: Vec<Proxy> = Vec::new();
         let config_no_proxy: Option<NoProxy> =
             config_no_proxy.as_deref().and_then(NoProxy::from_string);
 
@@ -1604,6 +2265,34 @@ impl Config {
             "authentication-override-file" => {
                 self.authentication_override_file = value.map(PathBuf::from);
             }
+            key if key == "auth" || key.starts_with("auth.") => {
+                if key == "auth" {
+                    if let Some(value) = value {
+                        self.auth = serde_json::de::from_str(&value).into_diagnostic()?;
+                    } else {
+                        self.auth = AuthConfig::default();
+                    }
+                    return Ok(());
+                }
+
+                let subkey = key.strip_prefix("auth.").unwrap();
+                match subkey {
+                    "storage" => {
+                        self.auth.storage = value
+                            .map(|v| CredentialStorage::from_str(v.as_str()))
+                            .transpose()
+                            .into_diagnostic()?;
+                    }
+                    "oidc" => {
+                        self.auth.oidc = value
+                            .map(|v| serde_json::de::from_str(&v))
+                            .transpose()
+                            .into_diagnostic()?
+                            .unwrap_or_default();
+                    }
+                    _ => return Err(err),
+                }
+            }
             "tls-no-verify" => {
                 self.tls_no_verify = value.map(|v| v.parse()).transpose().into_diagnostic()?;
             }
@@ -1654,6 +2343,90 @@ impl Config {
                     .transpose()
                     .into_diagnostic()?;
             }
+            "verify-signatures" => {
+                self.verify_signatures = value
+                    .as_deref()
+                    .map(VerifySignatures::from_str)
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse verify-signatures")?;
+            }
+            "log-format" => {
+                self.log_format = value
+                    .as_deref()
+                    .map(LogFormat::from_str)
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse log-format")?;
+            }
+            "trust-roots" => {
+                self.trust_roots = value
+                    .map(|v| serde_json::de::from_str(&v))
+                    .transpose()
+                    .into_diagnostic()?
+                    .unwrap_or_default();
+            }
+            "styles" => {
+                self.styles = value
+                    .map(|v| serde_json::de::from_str(&v))
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse styles")?
+                    .unwrap_or_default();
+            }
+            "virtual-package-overrides" => {
+                self.virtual_package_overrides = value
+                    .map(|v| serde_json::de::from_str(&v))
+                    .transpose()
+                    .into_diagnostic()?
+                    .unwrap_or_default();
+            }
+            "max-retries" => {
+                self.max_retries = value
+                    .map(|v| v.parse())
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse max-retries")?;
+            }
+            "allow-self-update" => {
+                self.allow_self_update = value
+                    .map(|v| v.parse())
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse allow-self-update")?;
+            }
+            "install-missing-environments" => {
+                self.install_missing_environments = value
+                    .as_deref()
+                    .map(InstallMissingEnvironments::from_str)
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse install-missing-environments")?;
+            }
+            "run-lock-policy" => {
+                self.run_lock_policy = value
+                    .as_deref()
+                    .map(RunLockPolicy::from_str)
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse run-lock-policy")?;
+            }
+            "link-mode" => {
+                self.link_mode = value
+                    .as_deref()
+                    .map(LinkMode::from_str)
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse link-mode")?;
+            }
+            "fallback-workspaces" => {
+                self.fallback_workspaces = value
+                    .map(|v| serde_json::de::from_str(&v))
+                    .transpose()
+                    .into_diagnostic()
+                    .wrap_err("failed to parse fallback-workspaces")?
+                    .unwrap_or_default();
+            }
             key if key.starts_with("repodata-config") => {
                 if key == "repodata-config" {
                     self.repodata_config = value
@@ -1868,8 +2641,42 @@ impl Config {
                         self.shell.change_ps1 =
                             value.map(|v| v.parse()).transpose().into_diagnostic()?;
                     }
+                    "prompt-template" => {
+                        self.shell.prompt_template = value;
+                    }
+                    _ => return Err(err),
+                }
+            }
+            key if key.starts_with("task-cache") => {
+                if key == "task-cache" {
+                    if let Some(value) = value {
+                        self.task_cache = serde_json::de::from_str(&value).into_diagnostic()?;
+                    } else {
+                        self.task_cache = TaskCacheConfig::default();
+                    }
+                    return self.task_cache.validate();
+                } else if !key.starts_with("task-cache.") {
+                    return Err(err);
+                }
+                let subkey = key.strip_prefix("task-cache.").unwrap();
+                match subkey {
+                    "url" => {
+                        self.task_cache.url = value
+                            .map(|v| Url::parse(&v))
+                            .transpose()
+                            .into_diagnostic()
+                            .wrap_err("failed to parse task-cache.url")?;
+                    }
+                    "mode" => {
+                        self.task_cache.mode = value
+                            .map(|v| v.parse())
+                            .transpose()
+                            .into_diagnostic()
+                            .wrap_err("failed to parse task-cache.mode")?;
+                    }
                     _ => return Err(err),
                 }
+                self.task_cache.validate()?;
             }
             key if key.starts_with("run-post-link-scripts") => {
                 if let Some(value) = value {
@@ -1915,6 +2722,13 @@ impl Config {
                             .into_diagnostic()?
                             .unwrap_or_default();
                     }
+                    "hosts" => {
+                        self.proxy_config.hosts = value
+                            .map(|v| serde_json::de::from_str(&v))
+                            .transpose()
+                            .into_diagnostic()?
+                            .unwrap_or_default();
+                    }
                     _ => return Err(err),
                 }
             }
@@ -2109,6 +2923,24 @@ UNUSED = "unused"
         );
     }
 
+    #[test]
+    fn test_s3_task_cache_is_read_only() {
+        let toml = r#"
+        [task-cache]
+        url = "s3://bucket/tasks"
+        mode = "read-write"
+        "#;
+        let (config, _) = Config::from_toml(toml, None).unwrap();
+        assert!(config.validate().is_err());
+
+        let toml = r#"
+        [task-cache]
+        url = "s3://bucket/tasks"
+        "#;
+        let (config, _) = Config::from_toml(toml, None).unwrap();
+        assert!(config.validate().is_ok());
+    }
+
     /// Assert that a boolean in `detached_environments` is preserved.
     #[test]
     fn test_detached_environments_bool() {
@@ -2288,6 +3120,7 @@ UNUSED = "unused"
                 force_activate: Some(true),
                 source_completion_scripts: None,
                 change_ps1: Some(false),
+                prompt_template: Some("{workspace}@{environment}".to_string()),
             },
             pypi_config: PyPIConfig {
                 allow_insecure_host: Vec::from(["test".to_string()]),
@@ -2321,6 +3154,27 @@ UNUSED = "unused"
             proxy_config: ProxyConfig::default(),
             build: BuildConfig::default(),
             tool_platform: None,
+            verify_signatures: Some(VerifySignatures::Warn),
+            trust_roots: HashMap::from([(
+                Url::parse("https://conda.anaconda.org/conda-forge").unwrap(),
+                PathBuf::from("/path/to/root.json"),
+            )]),
+            log_format: Some(LogFormat::Json),
+            styles: HashMap::from([("environment".to_string(), "bold.magenta".to_string())]),
+            virtual_package_overrides: HashMap::from([(
+                OverridableVirtualPackage::Cuda,
+                "12.4".to_string(),
+            )]),
+            max_retries: Some(10),
+            install_missing_environments: Some(InstallMissingEnvironments::Prompt),
+            run_lock_policy: Some(RunLockPolicy::Error),
+            allow_self_update: Some(false),
+            link_mode: Some(LinkMode::Hardlink),
+            fallback_workspaces: vec![PathBuf::from("/path/to/tools")],
+            task_cache: TaskCacheConfig {
+                url: Some(Url::parse("https://cache.example.com/tasks").unwrap()),
+                mode: Some(TaskCacheMode::ReadWrite),
+            },
             // Deprecated keys
             change_ps1: None,
             force_activate: None,
@@ -2590,6 +3444,17 @@ UNUSED = "unused"
         config.set("shell.change-ps1", None).unwrap();
         assert_eq!(config.change_ps1, None);
 
+        config
+            .set(
+                "shell.prompt-template",
+                Some("{workspace}@{environment}".to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.shell.prompt_template(),
+            Some("{workspace}@{environment}")
+        );
+
         config
             .set("concurrency.solves", Some("10".to_string()))
             .unwrap();
@@ -2625,6 +3490,160 @@ UNUSED = "unused"
             .unwrap();
         assert_eq!(config.tool_platform, Some(Platform::Linux64));
 
+        // Test verify-signatures
+        config
+            .set("verify-signatures", Some("error".to_string()))
+            .unwrap();
+        assert_eq!(config.verify_signatures(), VerifySignatures::Error);
+
+        // Test log-format
+        config.set("log-format", Some("json".to_string())).unwrap();
+        assert_eq!(config.log_format(), LogFormat::Json);
+
+        // Test trust-roots
+        config
+            .set(
+                "trust-roots",
+                Some(
+                    r#"{"https://conda.anaconda.org/conda-forge": "/path/to/root.json"}"#
+                        .to_string(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            config.trust_root(&Url::parse("https://conda.anaconda.org/conda-forge/").unwrap()),
+            Some(&PathBuf::from("/path/to/root.json"))
+        );
+
+        // Test styles
+        config
+            .set("styles", Some(r#"{"task": "bold.blue"}"#.to_string()))
+            .unwrap();
+        assert_eq!(
+            config.styles(),
+            &HashMap::from([("task".to_string(), "bold.blue".to_string())])
+        );
+
+        // Test virtual-package-overrides
+        config
+            .set(
+                "virtual-package-overrides",
+                Some(r#"{"__cuda": "12.4", "__glibc": ""}"#.to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.virtual_package_overrides,
+            HashMap::from([
+                (OverridableVirtualPackage::Cuda, "12.4".to_string()),
+                (OverridableVirtualPackage::Glibc, String::new()),
+            ])
+        );
+
+        // The environment variables take precedence over the configuration.
+        let overrides =
+            config.virtual_package_overrides_with_env(|name| name == "CONDA_OVERRIDE_CUDA");
+        assert_eq!(overrides.cuda, Some(Override::DefaultEnvVar));
+        assert_eq!(overrides.libc, Some(Override::String(String::new())));
+
+        // Test max-retries
+        config.set("max-retries", Some("10".to_string())).unwrap();
+        assert_eq!(config.max_retries(), 10);
+        config.set("max-retries", None).unwrap();
+        assert_eq!(config.max_retries(), DEFAULT_MAX_RETRIES);
+
+        // Test allow-self-update
+        config
+            .set("allow-self-update", Some("false".to_string()))
+            .unwrap();
+        assert!(!config.allow_self_update());
+        config.set("allow-self-update", None).unwrap();
+        assert!(config.allow_self_update());
+
+        // Test install-missing-environments
+        config
+            .set("install-missing-environments", Some("never".to_string()))
+            .unwrap();
+        assert_eq!(
+            config.install_missing_environments(),
+            InstallMissingEnvironments::Never
+        );
+        assert!(
+            config
+                .set(
+                    "install-missing-environments",
+                    Some("sometimes".to_string())
+                )
+                .is_err()
+        );
+
+        // Test run-lock-policy
+        config
+            .set("run-lock-policy", Some("error".to_string()))
+            .unwrap();
+        assert_eq!(config.run_lock_policy(), RunLockPolicy::Error);
+        assert!(
+            config
+                .set("run-lock-policy", Some("never".to_string()))
+                .is_err()
+        );
+        config.set("run-lock-policy", None).unwrap();
+        assert_eq!(config.run_lock_policy(), RunLockPolicy::Update);
+
+        // Test link-mode
+        config
+            .set("link-mode", Some("hardlink".to_string()))
+            .unwrap();
+        assert_eq!(config.link_mode(), LinkMode::Hardlink);
+        assert!(
+            config
+                .set("link-mode", Some("symlink".to_string()))
+                .is_err()
+        );
+
+        // Test fallback-workspaces
+        config
+            .set(
+                "fallback-workspaces",
+                Some(r#"["/path/to/tools"]"#.to_string()),
+            )
+            .unwrap();
+        assert_eq!(
+            config.fallback_workspaces(),
+            [PathBuf::from("/path/to/tools")]
+        );
+        config
+            .set("fallback-workspaces", Some(r#"["~/tools"]"#.to_string()))
+            .unwrap();
+        assert_eq!(
+            config.fallback_workspaces(),
+            [dirs::home_dir().unwrap().join("tools")]
+        );
+        config.set("fallback-workspaces", None).unwrap();
+        assert!(config.fallback_workspaces().is_empty());
+
+        // Test task-cache
+        config
+            .set(
+                "task-cache.url",
+                Some("https://cache.example.com/tasks".to_string()),
+            )
+            .unwrap();
+        config
+            .set("task-cache.mode", Some("read-write".to_string()))
+            .unwrap();
+        assert_eq!(
+            config.task_cache().url,
+            Some(Url::parse("https://cache.example.com/tasks").unwrap())
+        );
+        assert_eq!(config.task_cache().mode(), TaskCacheMode::ReadWrite);
+        assert!(
+            config
+                .set("task-cache.mode", Some("write".to_string()))
+                .is_err()
+        );
+        config.set("task-cache", None).unwrap();
+        assert!(config.task_cache().is_default());
+
         // Test run-post-link-scripts
         config
             .set("run-post-link-scripts", Some("insecure".to_string()))
@@ -2944,6 +3963,84 @@ UNUSED = "unused"
         assert_eq!(config.proxy_config.non_proxy_hosts[0], "a.com");
     }
 
+    #[test]
+    fn test_proxy_config_hosts() {
+        let toml = r#"
+            [proxy-config]
+            https = "http://proxy-for-https"
+
+            [proxy-config.hosts]
+            "conda.anaconda.org" = "socks5://localhost:1080"
+            "*.example.com" = "http://proxy-for-example"
+            ".internal.example.com" = "socks5h://proxy-for-internal"
+        "#;
+        let (config, _) = Config::from_toml(toml, None).unwrap();
+        let proxy_config = &config.proxy_config;
+        let proxy_for_host =
+            |host: &str| proxy_config.proxy_for_host(host).map(|url| url.to_string());
+
+        assert_eq!(
+            proxy_for_host("conda.anaconda.org").as_deref(),
+            Some("socks5://localhost:1080")
+        );
+        assert_eq!(proxy_for_host("anaconda.org"), None);
+        assert_eq!(
+            proxy_for_host("example.com").as_deref(),
+            Some("http://proxy-for-example/")
+        );
+        assert_eq!(
+            proxy_for_host("pypi.example.com").as_deref(),
+            Some("http://proxy-for-example/")
+        );
+        assert_eq!(
+            proxy_for_host("pypi.internal.example.com").as_deref(),
+            Some("socks5h://proxy-for-internal")
+        );
+        assert_eq!(proxy_for_host("notexample.com"), None);
+
+        // Hosts of both configs are combined when merging
+        let other = ProxyConfig {
+            hosts: HashMap::from([(
+                "pypi.org".to_string(),
+                Url::parse("http://proxy-for-pypi").unwrap(),
+            )]),
+            ..ProxyConfig::default()
+        };
+        let merged = proxy_config.merge(other);
+        assert_eq!(merged.hosts.len(), 4);
+        assert_eq!(merged.https, proxy_config.https);
+    }
+
+    #[test]
+    fn test_auth_config_parse() {
+        let toml = r#"
+            [auth]
+            storage = "keyring"
+
+            [auth.oidc."repo.prefix.dev"]
+            token-endpoint = "https://prefix.dev/api/oidc/mint_token"
+            audience = "prefix.dev"
+        "#;
+        let (config, unused) = Config::from_toml(toml, None).unwrap();
+        assert!(unused.is_empty());
+        assert_eq!(config.credential_storage(), CredentialStorage::Keyring);
+        let oidc = &config.auth.oidc["repo.prefix.dev"];
+        assert_eq!(
+            oidc.token_endpoint,
+            Url::parse("https://prefix.dev/api/oidc/mint_token").unwrap()
+        );
+        assert_eq!(oidc.audience.as_deref(), Some("prefix.dev"));
+        assert_eq!(oidc.id_token_env, None);
+
+        let mut config = Config::default();
+        assert_eq!(config.credential_storage(), CredentialStorage::Auto);
+        config
+            .set("auth.storage", Some("file".to_string()))
+            .unwrap();
+        assert_eq!(config.credential_storage(), CredentialStorage::File);
+        assert!(config.set("auth.unknown", None).is_err());
+    }
+
     use std::str::FromStr;
 
     use rattler_conda_types::{compression_level::CompressionLevel, package::ArchiveType};
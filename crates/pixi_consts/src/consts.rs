@@ -1,9 +1,10 @@
 use console::Style;
 use rattler_conda_types::NamedChannelOrUrl;
 use std::{
+    collections::HashMap,
     fmt::{Display, Formatter},
     str::FromStr,
-    sync::LazyLock,
+    sync::{LazyLock, OnceLock},
 };
 use url::Url;
 
@@ -21,10 +22,12 @@ pub const PIXI_VERSION: &str = match option_env!("PIXI_VERSION") {
 pub const PREFIX_FILE_NAME: &str = "pixi_env_prefix";
 pub const ENVIRONMENTS_DIR: &str = "envs";
 pub const SOLVE_GROUP_ENVIRONMENTS_DIR: &str = "solve-group-envs";
+pub const HISTORY_DIR: &str = "history";
 pub const PYPI_DEPENDENCIES: &str = "pypi-dependencies";
 pub const DEPENDENCIES: &str = "dependencies";
 pub const SYSTEM_REQUIREMENTS: &str = "system-requirements";
 pub const TASK_CACHE_DIR: &str = "task-cache-v0";
+pub const TASK_LOGS_DIR: &str = "logs";
 pub const ACTIVATION_ENV_CACHE_DIR: &str = "activation-env-v0";
 pub const PIXI_UV_INSTALLER: &str = "uv-pixi";
 pub const CONDA_PACKAGE_CACHE_DIR: &str = rattler_cache::PACKAGE_CACHE_DIR;
@@ -34,6 +37,8 @@ pub const CONDA_META_DIR: &str = "conda-meta";
 pub const CONDA_MENU_SCHEMA_DIR: &str = "Menu";
 pub const PYPI_CACHE_DIR: &str = "uv-cache";
 pub const CONDA_PYPI_MAPPING_CACHE_DIR: &str = "conda-pypi-mapping";
+pub const SIGNATURES_CACHE_DIR: &str = "signatures-v0";
+pub const PIXI_BINARIES_CACHE_DIR: &str = "pixi-binaries-v0";
 pub const CACHED_ENVS_DIR: &str = "cached-envs-v0";
 // TODO: CACHED_BUILD_ENVS_DIR was deprecated in favor of CACHED_BUILD_TOOL_ENVS_DIR. This constant will be removed in a future release.
 pub const _CACHED_BUILD_ENVS_DIR: &str = "cached-build-envs-v0";
@@ -97,6 +102,7 @@ pub const ENVIRONMENT_FILE_NAME: &str = "pixi";
 
 // Note: no trailing slash!
 pub const RELEASES_URL: &str = "https://github.com/prefix-dev/pixi/releases";
+pub const RELEASES_API: &str = "https://api.github.com/repos/prefix-dev/pixi/releases";
 pub const RELEASES_API_BY_TAG: &str = "https://api.github.com/repos/prefix-dev/pixi/releases/tags";
 pub const RELEASES_API_LATEST: &str =
     "https://api.github.com/repos/prefix-dev/pixi/releases/latest";
@@ -111,15 +117,58 @@ pub const RATTLER_BUILD_FILE_NAMES: [&str; 2] = ["recipe.yaml", "recipe.yml"];
 pub const RATTLER_BUILD_DIRS: [&str; 2] = ["", "recipe"];
 pub const ROS_BACKEND_FILE_NAMES: [&str; 1] = ["package.xml"];
 
-pub static TASK_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().blue());
-pub static TASK_ERROR_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().red());
-pub static PLATFORM_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().yellow());
-pub static ENVIRONMENT_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().magenta());
-pub static EXPOSED_NAME_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().yellow());
-pub static FEATURE_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().cyan());
-pub static SOLVE_GROUP_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().cyan());
-pub static CONDA_PACKAGE_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().green());
-pub static PYPI_PACKAGE_STYLE: LazyLock<Style> = LazyLock::new(|| Style::new().blue());
+/// The overrides of the styles below, by the name of the style.
+static STYLE_THEME: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Sets the theme that overrides the default styles of the output. The theme
+/// maps the name of a style, like `environment`, to a dotted style string,
+/// like `bold.magenta`. It must be set before any style is used, later calls
+/// are ignored.
+pub fn set_style_theme(theme: HashMap<String, String>) {
+    let _ = STYLE_THEME.set(theme);
+}
+
+/// Returns the style with the given name from the theme, or the default style
+/// if the theme doesn't override it.
+fn themed_style(name: &str, default: Style) -> Style {
+    STYLE_THEME
+        .get()
+        .and_then(|theme| theme.get(name))
+        .map(|style| Style::from_dotted_str(style))
+        .unwrap_or(default)
+}
+
+/// The names of the styles that can be overridden by the theme.
+pub const STYLE_NAMES: [&str; 9] = [
+    "task",
+    "task-error",
+    "platform",
+    "environment",
+    "exposed-name",
+    "feature",
+    "solve-group",
+    "conda-package",
+    "pypi-package",
+];
+
+pub static TASK_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("task", Style::new().blue()));
+pub static TASK_ERROR_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("task-error", Style::new().red()));
+pub static PLATFORM_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("platform", Style::new().yellow()));
+pub static ENVIRONMENT_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("environment", Style::new().magenta()));
+pub static EXPOSED_NAME_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("exposed-name", Style::new().yellow()));
+pub static FEATURE_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("feature", Style::new().cyan()));
+pub static SOLVE_GROUP_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("solve-group", Style::new().cyan()));
+pub static CONDA_PACKAGE_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("conda-package", Style::new().green()));
+pub static PYPI_PACKAGE_STYLE: LazyLock<Style> =
+    LazyLock::new(|| themed_style("pypi-package", Style::new().blue()));
 pub static DEFAULT_PYPI_INDEX_URL: LazyLock<Url> =
     LazyLock::new(|| Url::parse("https://pypi.org/simple").unwrap());
 
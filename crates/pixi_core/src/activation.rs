@@ -6,6 +6,7 @@ use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_manifest::EnvironmentName;
 use pixi_manifest::FeaturesExt;
+use pixi_utils::timings::{self, Phase};
 use rattler_conda_types::Platform;
 use rattler_lock::LockFile;
 use rattler_shell::{
@@ -249,6 +250,8 @@ pub async fn run_activation(
     force_activate: bool,
     experimental: bool,
 ) -> miette::Result<HashMap<String, String>> {
+    let _timer = timings::start(Phase::Activation);
+
     // If the user requested to use the cache and the lockfile is provided, we can try to use the cache.
     if !force_activate && experimental {
         let cache_file = environment
@@ -376,7 +379,7 @@ pub async fn run_activation(
 
 /// Get the environment variables that are statically generated from the project and the environment.
 /// Returns IndexMap to stay sorted, as pixi should export the metadata before exporting variables that could depend on it.
-pub(crate) fn get_static_environment_variables<'p>(
+pub fn get_static_environment_variables<'p>(
     environment: &'p Environment<'p>,
 ) -> IndexMap<String, String> {
     // Get environment variables from the pixi project meta data
@@ -490,6 +493,7 @@ pub(crate) async fn initialize_env_variables(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pixi_consts::consts;
     use std::path::Path;
     use std::str::FromStr;
 
@@ -798,4 +802,59 @@ packages:
             "The new variable should be set"
         );
     }
+
+    #[tokio::test]
+    async fn test_run_activation_cache_based_on_prefix() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let workspace = r#"
+        [workspace]
+        name = "pixi"
+        channels = []
+        platforms = []
+
+        [activation.env]
+        TEST = "ACTIVATION123"
+        "#;
+        let project =
+            Workspace::from_str(temp_dir.path().join("pixi.toml").as_path(), workspace).unwrap();
+        let default_env = project.default_environment();
+        let env = run_activation(
+            &default_env,
+            &CurrentEnvVarBehavior::Include,
+            Some(&LockFile::default()),
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(env.get("TEST").unwrap(), "ACTIVATION123");
+
+        // Modify the variable in cache
+        let cache_file = default_env.activation_cache_file_path();
+        let contents = tokio_fs::read_to_string(&cache_file).await.unwrap();
+        let modified = contents.replace("ACTIVATION123", "ACTIVATION456");
+        tokio_fs::write(&cache_file, modified).await.unwrap();
+
+        // Install a package in the prefix, this should invalidate the cache.
+        let conda_meta = default_env.dir().join(consts::CONDA_META_DIR);
+        tokio_fs::create_dir_all(&conda_meta).await.unwrap();
+        tokio_fs::write(conda_meta.join("foo-1.0-h123_0.json"), "{}")
+            .await
+            .unwrap();
+
+        let env = run_activation(
+            &default_env,
+            &CurrentEnvVarBehavior::Include,
+            Some(&LockFile::default()),
+            false,
+            true,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            env.get("TEST").unwrap(),
+            "ACTIVATION123",
+            "The cache should be invalidated when the prefix changes"
+        );
+    }
 }
@@ -1,9 +1,13 @@
 use std::path::Path;
 
 use fs_err as fs;
+use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_consts::consts;
+use rattler::install::{InstallationResultRecord, Transaction};
+use rattler_conda_types::{ChannelConfig, RepoDataRecord};
 use std::io;
+use url::Url;
 
 // Write the contents to the file at the given path.
 fn write_file<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
@@ -48,16 +52,162 @@ pub fn create_prefix_location_file(environment_dir: &Path) -> miette::Result<()>
     Ok(())
 }
 
-/// Create the conda-meta/history.
-/// This file is needed for `conda run -p .pixi/envs/<env>` to work.
-pub fn create_history_file(environment_dir: &Path) -> miette::Result<()> {
+/// The contents of the `conda-meta/history` file written by older versions of
+/// pixi, which only created the file so that `conda run -p` works.
+const LEGACY_HISTORY_CONTENTS: &str = "// not relevant for pixi but for `conda run -p`";
+
+/// Appends the transaction to the `conda-meta/history` of the environment, in
+/// the format that conda uses. This file is needed for `conda run -p
+/// .pixi/envs/<env>` to work, and tools that detect conda environments, like
+/// IDEs, read it to show the revisions of the environment.
+///
+/// If the environment has no history yet but already contained packages, a
+/// revision with the packages from before the transaction is written first.
+pub fn update_history_file(
+    environment_dir: &Path,
+    transaction: &Transaction<InstallationResultRecord, RepoDataRecord>,
+    channel_config: &ChannelConfig,
+) -> miette::Result<()> {
     let history_file = environment_dir.join(consts::CONDA_META_DIR).join("history");
 
-    tracing::debug!("Verify history file exists: {}", history_file.display());
+    let existing = match fs::read_to_string(&history_file) {
+        Ok(contents) if contents.trim() == LEGACY_HISTORY_CONTENTS => String::new(),
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+    if !existing.is_empty() && transaction.operations.is_empty() {
+        return Ok(());
+    }
+
+    tracing::debug!("Updating history file: {}", history_file.display());
+
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut contents = existing;
+    if contents.is_empty() {
+        let initial = transaction
+            .unchanged_packages()
+            .iter()
+            .chain(transaction.removed_packages())
+            .map(|record| installed_dist(record, channel_config))
+            .sorted()
+            .collect_vec();
+        if !initial.is_empty() {
+            contents.push_str(&format_revision(&timestamp, None, &initial, &[], &[]));
+        }
+    }
+
+    let command = std::env::args().collect_vec().join(" ");
+    let added = transaction
+        .installed_packages()
+        .map(|record| dist(record, channel_config))
+        .sorted()
+        .collect_vec();
+    let removed = transaction
+        .removed_packages()
+        .map(|record| installed_dist(record, channel_config))
+        .sorted()
+        .collect_vec();
+    contents.push_str(&format_revision(
+        &timestamp,
+        Some(&command),
+        &[],
+        &added,
+        &removed,
+    ));
+
+    write_file(history_file, contents).into_diagnostic()
+}
 
-    write_file(
-        history_file,
-        "// not relevant for pixi but for `conda run -p`",
-    )
-    .into_diagnostic()
+/// Formats a revision of the history. `packages` are the packages of a
+/// revision that describes the state of the environment, `added` and `removed`
+/// the packages that were changed by a transaction.
+fn format_revision(
+    timestamp: &str,
+    command: Option<&str>,
+    packages: &[String],
+    added: &[String],
+    removed: &[String],
+) -> String {
+    let mut revision = format!("==> {timestamp} <==\n");
+    if let Some(command) = command {
+        revision.push_str(&format!("# cmd: {command}\n"));
+    }
+    revision.push_str(&format!("# pixi version: {}\n", consts::PIXI_VERSION));
+    for package in packages {
+        revision.push_str(&format!("{package}\n"));
+    }
+    for package in removed {
+        revision.push_str(&format!("-{package}\n"));
+    }
+    for package in added {
+        revision.push_str(&format!("+{package}\n"));
+    }
+    revision
+}
+
+/// Returns the conda dist string of a record, e.g.
+/// `conda-forge/linux-64::python-3.12.0-h1234_0`.
+fn dist(record: &RepoDataRecord, channel_config: &ChannelConfig) -> String {
+    let package = &record.package_record;
+    let name = format!(
+        "{}-{}-{}",
+        package.name.as_normalized(),
+        package.version,
+        package.build
+    );
+    match record
+        .channel
+        .as_deref()
+        .and_then(|channel| Url::parse(channel).ok())
+    {
+        Some(channel) => format!(
+            "{}/{}::{name}",
+            channel_config.canonical_name(&channel),
+            package.subdir
+        ),
+        None => name,
+    }
+}
+
+/// Returns the conda dist string of a package that is installed in the
+/// environment. A minimal record doesn't know the channel of the package, so
+/// only the name, version and build are returned for it.
+fn installed_dist(record: &InstallationResultRecord, channel_config: &ChannelConfig) -> String {
+    match record {
+        InstallationResultRecord::Max(record) => dist(&record.repodata_record, channel_config),
+        InstallationResultRecord::Min(record) => format!(
+            "{}-{}-{}",
+            record.name.as_normalized(),
+            record.version,
+            record.build
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_revision() {
+        let revision = format_revision(
+            "2024-01-01 12:00:00",
+            Some("pixi install"),
+            &[],
+            &["conda-forge/linux-64::python-3.12.0-h1234_0".to_string()],
+            &["conda-forge/linux-64::python-3.11.0-h1234_0".to_string()],
+        );
+        assert_eq!(
+            revision,
+            format!(
+                "==> 2024-01-01 12:00:00 <==\n\
+                 # cmd: pixi install\n\
+                 # pixi version: {}\n\
+                 -conda-forge/linux-64::python-3.11.0-h1234_0\n\
+                 +conda-forge/linux-64::python-3.12.0-h1234_0\n",
+                consts::PIXI_VERSION
+            )
+        );
+    }
 }
@@ -1,18 +1,22 @@
 use std::{collections::HashSet, sync::Arc};
 
 use async_once_cell::OnceCell as AsyncOnceCell;
+use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_command_dispatcher::{BuildEnvironment, CommandDispatcher, InstallPixiEnvironmentSpec};
+use pixi_consts::consts;
 use pixi_manifest::FeaturesExt;
+use pixi_progress::global_multi_progress;
 use pixi_record::PixiRecord;
 use pixi_utils::{prefix::Prefix, variants::VariantConfig};
 use rattler::install::link_script::LinkScriptType;
 use rattler_conda_types::{
     ChannelConfig, ChannelUrl, GenericVirtualPackage, PackageName, Platform,
 };
+use serde::Serialize;
 
 use super::{
-    conda_metadata::{create_history_file, create_prefix_location_file},
+    conda_metadata::{create_prefix_location_file, update_history_file},
     try_increase_rlimit_to_sensible,
 };
 use crate::{
@@ -33,6 +37,18 @@ pub struct CondaPrefixUpdated {
     pub prefix: Prefix,
     /// Any change to the python interpreter.
     pub python_status: Box<PythonStatus>,
+    /// The messages that the installed packages left for the user.
+    pub package_messages: Vec<PackageMessage>,
+}
+
+/// A message that a package left for the user while it was installed, e.g.
+/// setup notes written by its pre- or post-link script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackageMessage {
+    /// The name of the package.
+    pub package: PackageName,
+    /// The message, as written by the package.
+    pub message: String,
 }
 
 /// A task that updates the prefix for a given environment.
@@ -56,9 +72,18 @@ pub struct CondaPrefixUpdaterBuilder<'a> {
     platform: Platform,
     virtual_packages: Vec<GenericVirtualPackage>,
     command_dispatcher: CommandDispatcher,
+    prefix: Option<Prefix>,
 }
 
 impl CondaPrefixUpdaterBuilder<'_> {
+    /// Installs into the given prefix instead of the prefix of the group.
+    pub fn with_prefix(self, prefix: Prefix) -> Self {
+        Self {
+            prefix: Some(prefix),
+            ..self
+        }
+    }
+
     /// Builds the conda prefix updater by extracting the necessary information
     /// from the group.
     pub fn finish(self) -> miette::Result<CondaPrefixUpdater> {
@@ -67,7 +92,7 @@ impl CondaPrefixUpdaterBuilder<'_> {
             .channel_urls(&self.group.workspace().channel_config())
             .into_diagnostic()?;
         let name = self.group.name();
-        let prefix = self.group.prefix();
+        let prefix = self.prefix.unwrap_or_else(|| self.group.prefix());
         let variant_config = self.group.workspace().variants(self.platform)?;
 
         Ok(CondaPrefixUpdater::new(
@@ -102,6 +127,7 @@ impl CondaPrefixUpdater {
             platform,
             virtual_packages,
             command_dispatcher,
+            prefix: None,
         }
     }
 
@@ -147,7 +173,7 @@ impl CondaPrefixUpdater {
 
                 let group_name = self.inner.name.clone();
 
-                let python_status = update_prefix_conda(
+                let (python_status, package_messages) = update_prefix_conda(
                     self.name().to_string(),
                     &self.inner.prefix,
                     pixi_records,
@@ -166,6 +192,7 @@ impl CondaPrefixUpdater {
                     group: group_name,
                     prefix: self.inner.prefix.clone(),
                     python_status: Box::new(python_status),
+                    package_messages,
                 })
             })
             .await
@@ -190,7 +217,7 @@ pub async fn update_prefix_conda(
     command_dispatcher: CommandDispatcher,
     reinstall_packages: Option<HashSet<PackageName>>,
     ignore_packages: Option<HashSet<PackageName>>,
-) -> miette::Result<PythonStatus> {
+) -> miette::Result<(PythonStatus, Vec<PackageMessage>)> {
     // Try to increase the rlimit to a sensible value for installation.
     try_increase_rlimit_to_sensible();
 
@@ -202,7 +229,7 @@ pub async fn update_prefix_conda(
     } = variant_config;
     let result = command_dispatcher
         .install_pixi_environment(InstallPixiEnvironmentSpec {
-            name,
+            name: name.clone(),
             records: pixi_records,
             prefix: rattler_conda_types::prefix::Prefix::create(prefix.root()).into_diagnostic()?,
             installed: None,
@@ -210,7 +237,7 @@ pub async fn update_prefix_conda(
             ignore_packages,
             build_environment,
             channels,
-            channel_config,
+            channel_config: channel_config.clone(),
             variant_configuration: Some(variant_configuration),
             variant_files: Some(variant_files),
             enabled_protocols: Default::default(),
@@ -219,7 +246,7 @@ pub async fn update_prefix_conda(
 
     // Mark the location of the prefix
     create_prefix_location_file(prefix.root())?;
-    create_history_file(prefix.root())?;
+    update_history_file(prefix.root(), &result.transaction, &channel_config)?;
 
     // Check in the prefix if there are any `post-link` scripts that have not been
     // executed, and if yes, issue a one-time warning to the user.
@@ -258,6 +285,109 @@ pub async fn update_prefix_conda(
         }
     }
 
+    // Collect the messages of the link scripts, they are printed together
+    // so that they are not interleaved with the progress of other
+    // environments.
+    let package_messages = package_messages(
+        result
+            .pre_link_script_result
+            .iter()
+            .chain(result.post_link_script_result.iter().flatten())
+            .flat_map(|link_result| link_result.messages.iter()),
+    );
+    print_package_messages(&name, &package_messages);
+
     // Determine if the python version changed.
-    Ok(PythonStatus::from_transaction(&result.transaction))
+    Ok((
+        PythonStatus::from_transaction(&result.transaction),
+        package_messages,
+    ))
+}
+
+/// Converts the messages of the link scripts into package messages, sorted by
+/// package name. Messages that only consist of whitespace are dropped.
+fn package_messages<'a>(
+    messages: impl IntoIterator<Item = (&'a PackageName, &'a String)>,
+) -> Vec<PackageMessage> {
+    messages
+        .into_iter()
+        .map(|(package, message)| PackageMessage {
+            package: package.clone(),
+            message: message.trim().to_string(),
+        })
+        .filter(|message| !message.message.is_empty())
+        .sorted_by(|a, b| a.package.cmp(&b.package))
+        .collect_vec()
+}
+
+/// Prints the messages of the installed packages as a single block, unless
+/// the output is silenced with `--quiet`.
+fn print_package_messages(environment: &str, messages: &[PackageMessage]) {
+    if messages.is_empty() || !tracing::enabled!(tracing::Level::WARN) {
+        return;
+    }
+
+    let block = format_package_messages(environment, messages);
+    global_multi_progress().suspend(|| eprintln!("{block}"));
+}
+
+/// Formats the messages of the installed packages as a single block, with the
+/// message of every package indented below its name.
+fn format_package_messages(environment: &str, messages: &[PackageMessage]) -> String {
+    let mut block = format!(
+        "{}Messages from the packages installed in the '{}' environment:",
+        console::style(console::Emoji("📝 ", "")).cyan(),
+        consts::ENVIRONMENT_STYLE.apply_to(environment),
+    );
+    for PackageMessage { package, message } in messages {
+        block.push_str(&format!(
+            "\n  {}:\n{}",
+            console::style(package.as_source()).bold(),
+            message.lines().map(|line| format!("    {line}")).join("\n")
+        ));
+    }
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_messages() {
+        let numpy = PackageName::new_unchecked("numpy");
+        let cuda = PackageName::new_unchecked("cuda");
+        let empty = PackageName::new_unchecked("empty");
+        let numpy_message = "Run `numpy-setup` first.\n".to_string();
+        let cuda_message = "\nInstall the driver.\nThen reboot.".to_string();
+        let empty_message = " \n".to_string();
+
+        let messages = package_messages([
+            (&numpy, &numpy_message),
+            (&empty, &empty_message),
+            (&cuda, &cuda_message),
+        ]);
+        assert_eq!(
+            messages,
+            [
+                PackageMessage {
+                    package: cuda.clone(),
+                    message: "Install the driver.\nThen reboot.".to_string(),
+                },
+                PackageMessage {
+                    package: numpy.clone(),
+                    message: "Run `numpy-setup` first.".to_string(),
+                },
+            ]
+        );
+
+        console::set_colors_enabled(false);
+        let block = format_package_messages("default", &messages);
+        assert!(
+            block.contains("Messages from the packages installed in the 'default' environment:")
+        );
+        assert!(block.ends_with(
+            "\n  cuda:\n    Install the driver.\n    Then reboot.\n  numpy:\n    Run `numpy-setup` first."
+        ));
+    }
 }
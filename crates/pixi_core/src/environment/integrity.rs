@@ -0,0 +1,225 @@
+//! Verification of the files of the conda packages installed in a prefix.
+//!
+//! Every installed package records the files it installed in its
+//! `conda-meta` record. A package is damaged if one of these files was
+//! removed, replaced by a broken symlink, or if its contents no longer match
+//! the sha256 that was recorded from the `paths.json` of the package.
+
+use std::path::{Path, PathBuf};
+
+use rattler_conda_types::{
+    PackageName, PrefixRecord,
+    prefix_record::{PathType, PathsEntry},
+};
+use rattler_digest::Sha256;
+use serde::Serialize;
+
+/// What is wrong with an installed file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileDamage {
+    /// The file does not exist.
+    Missing,
+    /// The file is a symlink that points to a file that does not exist.
+    BrokenSymlink,
+    /// The contents of the file differ from the installed file.
+    Modified,
+}
+
+/// A file of an installed package that no longer matches what was installed.
+#[derive(Debug, Clone, Serialize)]
+pub struct DamagedFile {
+    pub path: PathBuf,
+    pub damage: FileDamage,
+}
+
+/// An installed package with damaged files.
+#[derive(Debug, Clone, Serialize)]
+pub struct DamagedPackage {
+    pub name: PackageName,
+    pub files: Vec<DamagedFile>,
+}
+
+/// Returns the damage of an installed file, if any.
+fn file_damage(prefix: &Path, entry: &PathsEntry) -> Option<FileDamage> {
+    let path = prefix.join(&entry.relative_path);
+    let Ok(metadata) = fs_err::symlink_metadata(&path) else {
+        return Some(FileDamage::Missing);
+    };
+
+    if metadata.is_symlink() {
+        return (!path.exists()).then_some(FileDamage::BrokenSymlink);
+    }
+
+    if entry.path_type != PathType::HardLink {
+        return None;
+    }
+
+    // Files with a prefix placeholder are rewritten during installation, their
+    // installed digest is recorded separately.
+    let expected_sha256 = match &entry.prefix_placeholder {
+        Some(_) => entry.sha256_in_prefix,
+        None => entry.sha256_in_prefix.or(entry.sha256),
+    };
+    let Some(expected_sha256) = expected_sha256 else {
+        // Without a digest, only the size of a file that was linked as is can
+        // be compared.
+        let size_changed = entry.prefix_placeholder.is_none()
+            && entry
+                .size_in_bytes
+                .is_some_and(|size| size != metadata.len());
+        return size_changed.then_some(FileDamage::Modified);
+    };
+
+    // Comparing the size first avoids hashing most modified files.
+    if entry.prefix_placeholder.is_none()
+        && entry
+            .size_in_bytes
+            .is_some_and(|size| size != metadata.len())
+    {
+        return Some(FileDamage::Modified);
+    }
+    match rattler_digest::compute_file_digest::<Sha256>(&path) {
+        Ok(sha256) if sha256 == expected_sha256 => None,
+        _ => Some(FileDamage::Modified),
+    }
+}
+
+/// Returns the packages in the prefix whose installed files no longer match
+/// their `conda-meta` records.
+pub fn find_damaged_packages(prefix: &Path) -> std::io::Result<Vec<DamagedPackage>> {
+    let records = PrefixRecord::collect_from_prefix::<PrefixRecord>(prefix)?;
+    let mut damaged = records
+        .into_iter()
+        .filter_map(|record| {
+            let files = record
+                .paths_data
+                .paths
+                .iter()
+                // Compiled Python files are optional.
+                .filter(|entry| entry.path_type != PathType::PycFile)
+                .filter_map(|entry| {
+                    file_damage(prefix, entry).map(|damage| DamagedFile {
+                        path: entry.relative_path.clone(),
+                        damage,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (!files.is_empty()).then(|| DamagedPackage {
+                name: record.repodata_record.package_record.name.clone(),
+                files,
+            })
+        })
+        .collect::<Vec<_>>();
+    damaged.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(damaged)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_conda_types::{PackageRecord, RepoDataRecord, Version};
+    use url::Url;
+
+    use super::*;
+
+    fn entry(path: &str, size: u64) -> PathsEntry {
+        PathsEntry {
+            relative_path: PathBuf::from(path),
+            original_path: None,
+            path_type: PathType::HardLink,
+            no_link: false,
+            sha256: None,
+            sha256_in_prefix: None,
+            size_in_bytes: Some(size),
+            file_mode: None,
+            prefix_placeholder: None,
+        }
+    }
+
+    fn entry_with_contents(path: &str, contents: &str) -> PathsEntry {
+        PathsEntry {
+            sha256: Some(rattler_digest::compute_bytes_digest::<Sha256>(contents)),
+            ..entry(path, contents.len() as u64)
+        }
+    }
+
+    fn install(prefix: &Path, name: &str, paths: Vec<PathsEntry>) {
+        let record = PrefixRecord::from_repodata_record(
+            RepoDataRecord {
+                package_record: PackageRecord::new(
+                    PackageName::new_unchecked(name),
+                    Version::from_str("1.0").unwrap(),
+                    String::from("0"),
+                ),
+                file_name: format!("{name}-1.0-0.conda"),
+                url: Url::parse(&format!("https://example.com/{name}-1.0-0.conda")).unwrap(),
+                channel: None,
+            },
+            paths,
+        );
+        let conda_meta = prefix.join("conda-meta");
+        fs_err::create_dir_all(&conda_meta).unwrap();
+        record
+            .write_to_path(conda_meta.join(record.file_name()), true)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_find_damaged_packages() {
+        let prefix = tempfile::tempdir().unwrap();
+        let prefix = prefix.path();
+        fs_err::create_dir_all(prefix.join("bin")).unwrap();
+        fs_err::write(prefix.join("bin/intact"), "intact").unwrap();
+        fs_err::write(prefix.join("bin/modified"), "modified!").unwrap();
+
+        install(prefix, "intact", vec![entry("bin/intact", 6)]);
+        install(
+            prefix,
+            "damaged",
+            vec![entry("bin/missing", 7), entry("bin/modified", 8)],
+        );
+
+        let damaged = find_damaged_packages(prefix).unwrap();
+        assert_eq!(damaged.len(), 1);
+        assert_eq!(damaged[0].name.as_normalized(), "damaged");
+        assert_eq!(
+            damaged[0]
+                .files
+                .iter()
+                .map(|file| file.damage)
+                .collect::<Vec<_>>(),
+            vec![FileDamage::Missing, FileDamage::Modified]
+        );
+    }
+
+    #[test]
+    fn test_modified_contents_with_same_size() {
+        let prefix = tempfile::tempdir().unwrap();
+        let prefix = prefix.path();
+        fs_err::create_dir_all(prefix.join("lib")).unwrap();
+        fs_err::write(prefix.join("lib/intact.py"), "print(1)").unwrap();
+        fs_err::write(prefix.join("lib/modified.py"), "print(2)").unwrap();
+
+        install(
+            prefix,
+            "pkg",
+            vec![
+                entry_with_contents("lib/intact.py", "print(1)"),
+                entry_with_contents("lib/modified.py", "print(1)"),
+            ],
+        );
+
+        let damaged = find_damaged_packages(prefix).unwrap();
+        assert_eq!(damaged.len(), 1);
+        assert_eq!(
+            damaged[0]
+                .files
+                .iter()
+                .map(|file| (file.path.as_path(), file.damage))
+                .collect::<Vec<_>>(),
+            vec![(Path::new("lib/modified.py"), FileDamage::Modified)]
+        );
+    }
+}
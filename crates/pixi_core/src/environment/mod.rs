@@ -1,12 +1,19 @@
 pub(crate) mod conda_metadata;
 mod conda_prefix;
+pub mod integrity;
 pub mod list;
-pub use conda_prefix::{CondaPrefixUpdated, CondaPrefixUpdater, CondaPrefixUpdaterBuilder};
+pub use conda_metadata::update_history_file;
+pub use conda_prefix::{
+    CondaPrefixUpdated, CondaPrefixUpdater, CondaPrefixUpdaterBuilder, PackageMessage,
+};
 use dialoguer::theme::ColorfulTheme;
 use futures::{FutureExt, StreamExt, TryStreamExt, stream};
 use miette::{Context, IntoDiagnostic};
 use pixi_consts::consts;
-use pixi_git::credentials::store_credentials_from_url;
+use pixi_git::{
+    credentials::{GIT_STORE, store_credentials_from_url, stored_credentials},
+    url::RepositoryUrl,
+};
 pub use pixi_install_pypi::{ContinuePyPIPrefixUpdate, on_python_interpreter_change};
 use pixi_manifest::FeaturesExt;
 use pixi_progress::await_in_progress;
@@ -16,6 +23,7 @@ use pixi_spec::{GitSpec, PixiSpec};
 use pixi_utils::{prefix::Prefix, rlimit::try_increase_rlimit_to_sensible};
 use rattler_conda_types::Platform;
 use rattler_lock::{LockFile, LockedPackageRef};
+use rattler_networking::AuthenticationStorage;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
@@ -124,10 +132,14 @@ impl EnvironmentHash {
             value.hash(&mut hasher);
         }
 
-        // Hash the activation scripts
+        // Hash the activation scripts, including their content so editing a
+        // script invalidates the hash.
         let activation_scripts =
             run_environment.activation_scripts(Some(run_environment.best_platform()));
         for script in activation_scripts {
+            fs_err::read(run_environment.workspace().root().join(&script))
+                .ok()
+                .hash(&mut hasher);
             script.hash(&mut hasher);
         }
 
@@ -154,10 +166,52 @@ impl EnvironmentHash {
         urls.sort();
         urls.hash(&mut hasher);
 
+        // Hash the state of the prefix
+        hash_prefix_state(&run_environment.dir(), &mut hasher);
+
         EnvironmentHash(format!("{:x}", hasher.finish()))
     }
 }
 
+/// Hashes the parts of a prefix that influence its activation: the installed
+/// packages, the activation scripts and environment variables the packages
+/// add, and the environment variables that are set with `conda env config
+/// vars`.
+fn hash_prefix_state(prefix: &Path, hasher: &mut Xxh3) {
+    let conda_meta = prefix.join(consts::CONDA_META_DIR);
+
+    // The names of the package records change whenever a package is added,
+    // removed or updated.
+    let mut records = fs_err::read_dir(&conda_meta)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    records.sort();
+    records.hash(hasher);
+
+    fs_err::read(conda_meta.join("state")).ok().hash(hasher);
+
+    for dir in ["etc/conda/activate.d", "etc/conda/env_vars.d"] {
+        let mut files = fs_err::read_dir(prefix.join(dir))
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        files.sort();
+        for file in files {
+            file.file_name().hash(hasher);
+            fs_err::read(&file).ok().hash(hasher);
+        }
+    }
+}
+
 impl Display for EnvironmentHash {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -360,9 +414,22 @@ pub fn extract_git_requirements_from_workspace(project: &Workspace) -> Vec<GitSp
 }
 
 /// Store credentials from [`GitSpec`] requirements.
-pub fn store_credentials_from_requirements(git_requirements: Vec<GitSpec>) {
+///
+/// Requirements on an `http(s)` URL without credentials use the credentials
+/// that are stored for the host in `storage`, e.g. from the `.netrc` file.
+/// These are only used for the conda git checkouts, which pass them to git
+/// without putting them in the URL. The PyPI git checkouts use the credentials
+/// in the URL, and git itself reads the `.netrc` file.
+pub fn store_credentials_from_requirements(
+    git_requirements: Vec<GitSpec>,
+    storage: &AuthenticationStorage,
+) {
     for spec in git_requirements {
-        store_credentials_from_url(&spec.git);
+        if let Some(credentials) = stored_credentials(&spec.git, storage) {
+            GIT_STORE.insert(RepositoryUrl::new(&spec.git), credentials);
+        } else {
+            store_credentials_from_url(&spec.git);
+        }
     }
 }
 
@@ -575,7 +642,8 @@ pub async fn get_update_lock_file_and_prefixes<'env>(
 
     // Store the git credentials from the git requirements
     let requirements = extract_git_requirements_from_workspace(workspace);
-    store_credentials_from_requirements(requirements);
+    let auth_storage = pixi_auth::get_auth_store(workspace.config()).into_diagnostic()?;
+    store_credentials_from_requirements(requirements, &auth_storage);
 
     // Ensure that the lock-file is up-to-date
     let lock_file = workspace
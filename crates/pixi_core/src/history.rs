@@ -0,0 +1,303 @@
+//! The installation history of a workspace.
+//!
+//! Every time an environment is installed, a snapshot of the lock-file and of
+//! the packages that were installed is recorded in `.pixi/history`. A
+//! snapshot can be restored with `pixi revert`.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use miette::Diagnostic;
+use pixi_consts::consts;
+use rattler_conda_types::Platform;
+use rattler_digest::Sha256;
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Workspace;
+
+/// The file in a snapshot directory that describes the snapshot.
+const SNAPSHOT_FILE_NAME: &str = "snapshot.json";
+
+/// The number of snapshots that are kept, older snapshots are removed.
+const MAX_SNAPSHOTS: usize = 50;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum HistoryError {
+    #[error("there is no snapshot with id {0}")]
+    #[diagnostic(help("run `pixi history list` to see the available snapshots"))]
+    NotFound(u64),
+
+    #[error("the snapshot '{}' is invalid", .0.display())]
+    Invalid(PathBuf, #[source] serde_json::Error),
+
+    #[error("failed to write the lock-file of the snapshot")]
+    LockFile(#[source] std::io::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A package that was installed in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotPackage {
+    pub name: String,
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build: Option<String>,
+}
+
+/// The state of an environment after it was installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Snapshot {
+    pub id: u64,
+    pub created: DateTime<Utc>,
+    pub environment: String,
+    pub platform: Platform,
+    pub lock_file_sha256: String,
+    pub packages: Vec<SnapshotPackage>,
+}
+
+/// The changes between two snapshots of an environment.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+}
+
+impl Snapshot {
+    /// Returns the changes of the packages compared to an earlier snapshot.
+    pub fn diff(&self, previous: &Snapshot) -> SnapshotDiff {
+        let previous = previous
+            .packages
+            .iter()
+            .map(|package| (package.name.as_str(), package))
+            .collect::<BTreeMap<_, _>>();
+        let current = self
+            .packages
+            .iter()
+            .map(|package| (package.name.as_str(), package))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut diff = SnapshotDiff::default();
+        for (name, package) in &current {
+            match previous.get(name) {
+                None => diff.added += 1,
+                Some(previous) if previous != package => diff.changed += 1,
+                Some(_) => {}
+            }
+        }
+        diff.removed = previous
+            .keys()
+            .filter(|name| !current.contains_key(*name))
+            .count();
+        diff
+    }
+}
+
+/// The snapshots of a workspace.
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    /// Returns the history of the workspace.
+    pub fn new(workspace: &Workspace) -> Self {
+        Self::from_dir(workspace.pixi_dir().join(consts::HISTORY_DIR))
+    }
+
+    /// Returns the history that is stored in the given directory.
+    pub fn from_dir(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn snapshot_dir(&self, id: u64) -> PathBuf {
+        self.dir.join(id.to_string())
+    }
+
+    /// Returns the path of the lock-file of a snapshot.
+    pub fn lock_file_path(&self, id: u64) -> PathBuf {
+        self.snapshot_dir(id).join(consts::PROJECT_LOCK_FILE)
+    }
+
+    /// Returns all snapshots, oldest first.
+    pub fn snapshots(&self) -> Result<Vec<Snapshot>, HistoryError> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut snapshots = Vec::new();
+        for entry in fs_err::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let Some(id) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            snapshots.push(self.snapshot(id)?);
+        }
+        snapshots.sort_by_key(|snapshot| snapshot.id);
+        Ok(snapshots)
+    }
+
+    /// Returns the snapshot with the given id.
+    pub fn snapshot(&self, id: u64) -> Result<Snapshot, HistoryError> {
+        let path = self.snapshot_dir(id).join(SNAPSHOT_FILE_NAME);
+        if !path.is_file() {
+            return Err(HistoryError::NotFound(id));
+        }
+        let contents = fs_err::read_to_string(&path)?;
+        serde_json::from_str(&contents).map_err(|err| HistoryError::Invalid(path, err))
+    }
+
+    /// Records a snapshot of an environment that was installed from the
+    /// lock-file. Nothing is recorded if the lock-file did not change since
+    /// the last snapshot of the environment.
+    pub fn record(
+        &self,
+        lock_file: &LockFile,
+        environment: &str,
+        platform: Platform,
+    ) -> Result<Option<Snapshot>, HistoryError> {
+        let contents = lock_file
+            .render_to_string()
+            .map_err(HistoryError::LockFile)?;
+        let lock_file_sha256 = format!(
+            "{:x}",
+            rattler_digest::compute_bytes_digest::<Sha256>(contents.as_bytes())
+        );
+
+        let snapshots = self.snapshots()?;
+        let latest = snapshots
+            .iter()
+            .rev()
+            .find(|snapshot| snapshot.environment == environment && snapshot.platform == platform);
+        if latest.is_some_and(|snapshot| snapshot.lock_file_sha256 == lock_file_sha256) {
+            return Ok(None);
+        }
+
+        let packages = lock_file
+            .environment(environment)
+            .and_then(|env| env.packages(platform).map(Vec::from_iter))
+            .into_iter()
+            .flatten()
+            .map(|package| match package {
+                LockedPackageRef::Conda(data) => {
+                    let record = data.record();
+                    SnapshotPackage {
+                        name: record.name.as_normalized().to_string(),
+                        version: record.version.to_string(),
+                        build: Some(record.build.clone()),
+                    }
+                }
+                LockedPackageRef::Pypi(data, _) => SnapshotPackage {
+                    name: data.name.to_string(),
+                    version: data.version.to_string(),
+                    build: None,
+                },
+            })
+            .collect();
+
+        let snapshot = Snapshot {
+            id: snapshots.last().map_or(1, |snapshot| snapshot.id + 1),
+            created: Utc::now(),
+            environment: environment.to_string(),
+            platform,
+            lock_file_sha256,
+            packages,
+        };
+        let dir = self.snapshot_dir(snapshot.id);
+        fs_err::create_dir_all(&dir)?;
+        fs_err::write(dir.join(consts::PROJECT_LOCK_FILE), contents)?;
+        fs_err::write(
+            dir.join(SNAPSHOT_FILE_NAME),
+            serde_json::to_string_pretty(&snapshot)
+                .map_err(|err| HistoryError::Invalid(dir.clone(), err))?,
+        )?;
+
+        // Remove the oldest snapshots.
+        let excess = (snapshots.len() + 1).saturating_sub(MAX_SNAPSHOTS);
+        for old in snapshots.iter().take(excess) {
+            remove_dir(&self.snapshot_dir(old.id))?;
+        }
+
+        Ok(Some(snapshot))
+    }
+}
+
+fn remove_dir(path: &Path) -> Result<(), HistoryError> {
+    match fs_err::remove_dir_all(path) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, version: &str) -> SnapshotPackage {
+        SnapshotPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            build: None,
+        }
+    }
+
+    fn snapshot(id: u64, packages: Vec<SnapshotPackage>) -> Snapshot {
+        Snapshot {
+            id,
+            created: Utc::now(),
+            environment: String::from("default"),
+            platform: Platform::Linux64,
+            lock_file_sha256: String::new(),
+            packages,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let previous = snapshot(1, vec![package("a", "1"), package("b", "1")]);
+        let current = snapshot(2, vec![package("a", "2"), package("c", "1")]);
+        assert_eq!(
+            current.diff(&previous),
+            SnapshotDiff {
+                added: 1,
+                removed: 1,
+                changed: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_record_skips_unchanged_lock_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = History::from_dir(dir.path().join("history"));
+        let lock_file = LockFile::default();
+
+        let snapshot = history
+            .record(&lock_file, "default", Platform::Linux64)
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.id, 1);
+        assert!(history.lock_file_path(1).is_file());
+        assert!(
+            history
+                .record(&lock_file, "default", Platform::Linux64)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(history.snapshots().unwrap().len(), 1);
+        assert!(matches!(
+            history.snapshot(2),
+            Err(HistoryError::NotFound(2))
+        ));
+    }
+}
@@ -2,9 +2,11 @@
 
 pub mod activation;
 pub mod environment;
+pub mod history;
 pub mod lock_file;
 pub mod prompt;
 pub mod repodata;
+pub mod signatures;
 pub mod workspace;
 
 pub mod signals;
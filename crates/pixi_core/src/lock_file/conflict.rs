@@ -0,0 +1,430 @@
+//! Explains solver conflicts in terms of the requirements in the manifest.
+//!
+//! When the conda solver fails, it reports the derivation of the conflict as
+//! a tree. The roots of that tree are the requirements that were passed to
+//! the solver, which are the dependencies from the manifest. This module
+//! extracts those roots, together with the versions that were considered for
+//! them, so that we can point the user at the entries in the manifest that
+//! conflict and suggest which requirements to relax. The entries are located
+//! with the spans of the parsed manifest.
+
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter},
+    ops::Range,
+    str::FromStr,
+};
+
+use fancy_display::FancyDisplay;
+use itertools::Itertools;
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode};
+use pixi_command_dispatcher::{CommandDispatcherError, SolvePixiEnvironmentError};
+use pixi_manifest::{
+    ManifestKind, ManifestProvenance,
+    pyproject::PyProjectManifest,
+    toml::{FromTomlStr, TomlManifest},
+};
+use rattler_conda_types::{PackageName, Platform};
+
+use crate::workspace::grouped_environment::GroupedEnvironmentName;
+
+/// Why a requirement from the manifest takes part in a conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictReason {
+    /// The requirement can be installed on its own, but not together with
+    /// the other requirements.
+    Incompatible,
+
+    /// None of the versions that match the requirement can be installed.
+    NoViableOptions,
+
+    /// No package matches the requirement at all.
+    NoCandidates,
+}
+
+/// A requirement from the manifest that takes part in a solver conflict.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictingRequirement {
+    /// The normalized name of the package.
+    pub name: String,
+
+    /// The version constraint of the requirement as displayed by the solver.
+    pub spec: String,
+
+    /// The versions that were considered for this requirement.
+    pub considered_versions: Vec<String>,
+
+    /// Why this requirement takes part in the conflict.
+    pub reason: ConflictReason,
+}
+
+impl ConflictingRequirement {
+    /// Returns the requirement as displayed by the solver, e.g. `numpy >=2`.
+    pub fn requirement(&self) -> String {
+        if self.spec.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.spec)
+        }
+    }
+}
+
+/// The solver could not find a solution because some of the requirements in
+/// the manifest conflict with each other.
+#[derive(Debug)]
+pub struct SolveConflictError {
+    pub environment_name: GroupedEnvironmentName,
+    pub platform: Platform,
+
+    /// The requirements from the manifest that take part in the conflict.
+    pub conflicts: Vec<ConflictingRequirement>,
+
+    /// The manifest and the locations of the conflicting requirements in it.
+    manifest: Option<(NamedSource<String>, Vec<LabeledSpan>)>,
+
+    source: Box<CommandDispatcherError<SolvePixiEnvironmentError>>,
+}
+
+impl SolveConflictError {
+    /// Explains the error in terms of the requirements of the environment.
+    /// Returns the original error if it is not caused by conflicting
+    /// requirements.
+    pub fn from_solve_error(
+        environment_name: GroupedEnvironmentName,
+        platform: Platform,
+        requirements: &HashSet<String>,
+        manifest: &ManifestProvenance,
+        source: Box<CommandDispatcherError<SolvePixiEnvironmentError>>,
+    ) -> Result<Self, Box<CommandDispatcherError<SolvePixiEnvironmentError>>> {
+        let CommandDispatcherError::Failed(SolvePixiEnvironmentError::SolveError(
+            rattler_solve::SolveError::Unsolvable(messages),
+        )) = source.as_ref()
+        else {
+            return Err(source);
+        };
+
+        let conflicts = messages
+            .iter()
+            .flat_map(|message| parse_conflicting_requirements(message))
+            .filter(|conflict| requirements.contains(&conflict.name))
+            .unique_by(|conflict| conflict.name.clone())
+            .collect_vec();
+        if conflicts.is_empty() {
+            return Err(source);
+        }
+
+        let manifest = match manifest.read() {
+            Ok(contents) => {
+                let contents = contents.into_inner();
+                let parsed = parse_manifest(&contents, manifest.kind);
+                let labels = conflicts
+                    .iter()
+                    .flat_map(|conflict| {
+                        dependency_spans(parsed.as_ref(), &conflict.name)
+                            .into_iter()
+                            .map(|span| {
+                                LabeledSpan::new(
+                                    Some(format!("{} conflicts", conflict.requirement())),
+                                    span.start,
+                                    span.len(),
+                                )
+                            })
+                    })
+                    .collect_vec();
+                (!labels.is_empty()).then(|| {
+                    (
+                        NamedSource::new(manifest.path.to_string_lossy(), contents),
+                        labels,
+                    )
+                })
+            }
+            Err(err) => {
+                tracing::debug!("failed to read the manifest to explain the conflict: {err}");
+                None
+            }
+        };
+
+        Ok(Self {
+            environment_name,
+            platform,
+            conflicts,
+            manifest,
+            source,
+        })
+    }
+}
+
+impl Display for SolveConflictError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to solve requirements of environment '{}' for platform '{}'",
+            self.environment_name.fancy_display(),
+            self.platform
+        )
+    }
+}
+
+impl std::error::Error for SolveConflictError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+impl Diagnostic for SolveConflictError {
+    fn code(&self) -> Option<Box<dyn Display + '_>> {
+        Some(Box::new("solve-conflict"))
+    }
+
+    fn help(&self) -> Option<Box<dyn Display + '_>> {
+        let mut help = String::from("the conflict involves these requirements:");
+        for conflict in &self.conflicts {
+            help.push_str(&format!("\n  - {}", conflict.requirement()));
+            match conflict.reason {
+                ConflictReason::NoCandidates => {
+                    help.push_str(", no matching packages were found in the channels")
+                }
+                ConflictReason::NoViableOptions | ConflictReason::Incompatible
+                    if !conflict.considered_versions.is_empty() =>
+                {
+                    help.push_str(&format!(
+                        ", considered {}",
+                        conflict.considered_versions.iter().format(", ")
+                    ))
+                }
+                _ => {}
+            }
+        }
+
+        let relaxable = self
+            .conflicts
+            .iter()
+            .filter(|conflict| conflict.reason != ConflictReason::NoCandidates)
+            .filter(|conflict| !conflict.spec.is_empty() && conflict.spec != "*")
+            .map(|conflict| format!("`{}`", conflict.requirement()))
+            .collect_vec();
+        if !relaxable.is_empty() {
+            help.push_str(&format!(
+                "\ntry relaxing the version constraint of {}",
+                relaxable.iter().format(" or ")
+            ));
+        }
+
+        Some(Box::new(help))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.manifest
+            .as_ref()
+            .map(|(source, _)| source as &dyn SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let (_, labels) = self.manifest.as_ref()?;
+        Some(Box::new(labels.iter().cloned()))
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Markers that separate the requirement from the explanation in a line of
+/// the derivation tree.
+const REQUIREMENT_MARKERS: [(&str, ConflictReason); 5] = [
+    (" can be installed with", ConflictReason::Incompatible),
+    (
+        ", which can be installed with",
+        ConflictReason::Incompatible,
+    ),
+    (" cannot be installed", ConflictReason::NoViableOptions),
+    (
+        ", which cannot be installed",
+        ConflictReason::NoViableOptions,
+    ),
+    (
+        ", for which no candidates were found",
+        ConflictReason::NoCandidates,
+    ),
+];
+
+/// Markers that end the list of versions of a candidate in the derivation
+/// tree.
+const CANDIDATE_MARKERS: [&str; 3] = [" would require", " is excluded", " cannot be installed"];
+
+/// Extracts the requirements at the root of a derivation tree, e.g.
+///
+/// ```text
+/// The following packages are incompatible
+/// ├─ numpy >=2 can be installed with any of the following options:
+/// │  └─ numpy 2.0.0 | 2.0.1 would require
+/// │     └─ python >=3.9, which can be installed with any of the following options:
+/// │        └─ python 3.9.0
+/// └─ python 3.8.* cannot be installed because there are no viable options:
+///    └─ python 3.8.0 is excluded because ...
+/// ```
+pub fn parse_conflicting_requirements(message: &str) -> Vec<ConflictingRequirement> {
+    let mut requirements: Vec<ConflictingRequirement> = Vec::new();
+    for line in message.lines() {
+        if let Some(root) = line
+            .strip_prefix("├─ ")
+            .or_else(|| line.strip_prefix("└─ "))
+        {
+            if let Some(requirement) = parse_requirement(root) {
+                requirements.push(requirement);
+            }
+            continue;
+        }
+
+        // Direct children of a root list the versions that were considered.
+        let Some(child) = line
+            .strip_prefix("│  ")
+            .or_else(|| line.strip_prefix("   "))
+            .and_then(|line| {
+                line.strip_prefix("├─ ")
+                    .or_else(|| line.strip_prefix("└─ "))
+            })
+        else {
+            continue;
+        };
+        let Some(requirement) = requirements.last_mut() else {
+            continue;
+        };
+        let Some((name, versions)) = child.split_once(' ') else {
+            continue;
+        };
+        if name != requirement.name {
+            continue;
+        }
+        let versions = CANDIDATE_MARKERS
+            .iter()
+            .filter_map(|marker| versions.find(marker))
+            .min()
+            .map_or(versions, |end| &versions[..end]);
+        requirement.considered_versions.extend(
+            versions
+                .split(" | ")
+                .map(str::trim)
+                .filter(|version| !version.is_empty())
+                .map(ToOwned::to_owned),
+        );
+    }
+    requirements
+}
+
+/// Parses a root of the derivation tree into a requirement.
+fn parse_requirement(line: &str) -> Option<ConflictingRequirement> {
+    let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+    // Constraints and locked packages are not requirements from the manifest.
+    if name == "the" || rest.starts_with("is locked") {
+        return None;
+    }
+
+    let (end, reason) = REQUIREMENT_MARKERS
+        .iter()
+        .filter_map(|(marker, reason)| rest.find(marker).map(|end| (end, *reason)))
+        .min_by_key(|(end, _)| *end)?;
+
+    Some(ConflictingRequirement {
+        name: name.to_string(),
+        spec: rest[..end].trim().trim_end_matches(',').to_string(),
+        considered_versions: Vec::new(),
+        reason,
+    })
+}
+
+/// Parses the manifest to find the locations of its dependencies. Returns
+/// `None` if the manifest has no pixi tables or cannot be parsed.
+fn parse_manifest(contents: &str, kind: ManifestKind) -> Option<TomlManifest> {
+    match kind {
+        ManifestKind::Pyproject => PyProjectManifest::from_toml_str(contents).ok()?.tool?.pixi,
+        ManifestKind::Pixi | ManifestKind::MojoProject => {
+            TomlManifest::from_toml_str(contents).ok()
+        }
+    }
+}
+
+/// Returns the locations of the conda dependencies on the given package in
+/// the manifest.
+fn dependency_spans(manifest: Option<&TomlManifest>, name: &str) -> Vec<Range<usize>> {
+    match (manifest, PackageName::from_str(name)) {
+        (Some(manifest), Ok(name)) => manifest.conda_dependency_spans(&name),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFLICT: &str = "The following packages are incompatible
+├─ numpy >=2 can be installed with any of the following options:
+│  └─ numpy 2.0.0 | 2.0.1 would require
+│     └─ python >=3.9,<3.10.0a0, which can be installed with any of the following options:
+│        └─ python 3.9.0
+├─ foo >=99, for which no candidates were found.
+└─ python 3.8.* cannot be installed because there are no viable options:
+   └─ python 3.8.0 is excluded because of the constraint above
+";
+
+    #[test]
+    fn test_parse_conflicting_requirements() {
+        let requirements = parse_conflicting_requirements(CONFLICT);
+        assert_eq!(
+            requirements,
+            vec![
+                ConflictingRequirement {
+                    name: String::from("numpy"),
+                    spec: String::from(">=2"),
+                    considered_versions: vec![String::from("2.0.0"), String::from("2.0.1")],
+                    reason: ConflictReason::Incompatible,
+                },
+                ConflictingRequirement {
+                    name: String::from("foo"),
+                    spec: String::from(">=99"),
+                    considered_versions: vec![],
+                    reason: ConflictReason::NoCandidates,
+                },
+                ConflictingRequirement {
+                    name: String::from("python"),
+                    spec: String::from("3.8.*"),
+                    considered_versions: vec![String::from("3.8.0")],
+                    reason: ConflictReason::NoViableOptions,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dependency_spans() {
+        let manifest = r#"[workspace]
+name = "numpy"
+channels = []
+platforms = []
+
+[dependencies]
+python = "3.8.*"
+
+[feature.new.dependencies]
+"numpy" = ">=2"
+
+[feature.new.target.linux-64.dependencies]
+numpy = { version = ">=2.1" }
+
+[pypi-dependencies]
+foo = "*"
+"#;
+        let parsed = parse_manifest(manifest, ManifestKind::Pixi);
+        let spans = dependency_spans(parsed.as_ref(), "numpy");
+        assert_eq!(
+            spans
+                .iter()
+                .map(|span| &manifest[span.clone()])
+                .collect_vec(),
+            vec![r#""numpy" = ">=2""#, r#"numpy = { version = ">=2.1" }"#]
+        );
+        let spans = dependency_spans(parsed.as_ref(), "python");
+        assert_eq!(&manifest[spans[0].clone()], r#"python = "3.8.*""#);
+        assert!(dependency_spans(parsed.as_ref(), "foo").is_empty());
+    }
+}
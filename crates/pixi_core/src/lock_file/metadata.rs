@@ -0,0 +1,281 @@
+//! Metadata about the inputs of the solve that produced a lock-file.
+//!
+//! The lock-file format has no room for this information, so it is stored in
+//! a JSON file next to the lock-file (`pixi.lock.meta.json`) that is rewritten
+//! when the lock-file changes. Use `pixi lock show-meta` to inspect it.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
+use miette::Diagnostic;
+use pixi_config::OverridableVirtualPackage;
+use pixi_consts::consts;
+use rattler_conda_types::NamedChannelOrUrl;
+use rattler_lock::{LockFile, LockedPackageRef};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Workspace;
+
+/// The prefix of the environment variables that override the detected
+/// virtual packages, e.g. `CONDA_OVERRIDE_CUDA`.
+const CONDA_OVERRIDE_PREFIX: &str = "CONDA_OVERRIDE_";
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum LockFileMetadataError {
+    #[error("no metadata has been recorded for the lock-file")]
+    #[diagnostic(help(
+        "the metadata is recorded the next time the lock-file is written, e.g. by `pixi lock` after the manifest changed"
+    ))]
+    NotFound,
+
+    #[error("the lock-file metadata '{}' is invalid", .0.display())]
+    Invalid(PathBuf, #[source] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// The state of a subdirectory of a channel at the time of the solve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SubdirMetadata {
+    pub subdir: String,
+    /// The timestamp of the newest package that was locked from the
+    /// subdirectory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub newest_package: Option<DateTime<Utc>>,
+    /// The `ETag` of the repodata that was used, if it was fetched as a single
+    /// file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// The `Last-Modified` header of the repodata that was used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+}
+
+/// A channel that was used to solve the lock-file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ChannelMetadata {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subdirs: Vec<SubdirMetadata>,
+}
+
+/// The inputs of the solve that produced a lock-file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockFileMetadata {
+    pub pixi_version: String,
+    pub channels: Vec<ChannelMetadata>,
+    /// The virtual package overrides from the configuration or the command
+    /// line.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub virtual_package_overrides: BTreeMap<OverridableVirtualPackage, String>,
+    /// The `CONDA_OVERRIDE_*` environment variables that were set.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment_overrides: BTreeMap<String, String>,
+}
+
+/// The part of the repodata cache state written by rattler that is recorded.
+#[derive(Deserialize)]
+struct CachedRepodataState {
+    url: String,
+    #[serde(default)]
+    etag: Option<String>,
+    #[serde(default, rename = "mod")]
+    last_modified: Option<String>,
+}
+
+impl LockFileMetadata {
+    /// Collects the metadata of a lock-file that was solved for the
+    /// workspace.
+    pub fn new(workspace: &Workspace, lock_file: &LockFile) -> Self {
+        let channel_config = workspace.channel_config();
+
+        // Collect the channels of all environments, in order.
+        let mut channels: IndexMap<String, BTreeMap<String, SubdirMetadata>> = IndexMap::new();
+        for (_, environment) in lock_file.environments() {
+            for channel in environment.channels() {
+                let url = NamedChannelOrUrl::from_str(&channel.url)
+                    .ok()
+                    .and_then(|channel| channel.into_base_url(&channel_config).ok())
+                    .map_or_else(|| channel.url.clone(), |url| url.to_string());
+                channels.entry(url).or_default();
+            }
+        }
+
+        // Determine the newest package of every subdirectory.
+        for (_, environment) in lock_file.environments() {
+            for (_, packages) in environment.packages_by_platform() {
+                for package in packages {
+                    let LockedPackageRef::Conda(data) = package else {
+                        continue;
+                    };
+                    let location = data.location().to_string();
+                    let Some(subdirs) = channels
+                        .iter_mut()
+                        .find(|(url, _)| location.starts_with(url.as_str()))
+                        .map(|(_, subdirs)| subdirs)
+                    else {
+                        continue;
+                    };
+                    let record = data.record();
+                    let subdir =
+                        subdirs
+                            .entry(record.subdir.clone())
+                            .or_insert_with(|| SubdirMetadata {
+                                subdir: record.subdir.clone(),
+                                newest_package: None,
+                                etag: None,
+                                last_modified: None,
+                            });
+                    let timestamp = record
+                        .timestamp
+                        .map(|timestamp| timestamp.timestamp_millis())
+                        .and_then(DateTime::from_timestamp_millis);
+                    subdir.newest_package = subdir.newest_package.max(timestamp);
+                }
+            }
+        }
+
+        // Add the cache headers of the repodata that was used, if any.
+        let cached = cached_repodata_states();
+        let channels = channels
+            .into_iter()
+            .map(|(url, subdirs)| ChannelMetadata {
+                subdirs: subdirs
+                    .into_values()
+                    .map(|mut subdir| {
+                        let prefix = format!("{url}{}/repodata", subdir.subdir);
+                        if let Some(state) = cached
+                            .iter()
+                            .find(|(cached_url, _)| cached_url.starts_with(&prefix))
+                            .map(|(_, state)| state)
+                        {
+                            subdir.etag = state.etag.clone();
+                            subdir.last_modified = state.last_modified.clone();
+                        }
+                        subdir
+                    })
+                    .collect(),
+                url,
+            })
+            .collect();
+
+        Self {
+            pixi_version: consts::PIXI_VERSION.to_string(),
+            channels,
+            virtual_package_overrides: workspace
+                .config()
+                .virtual_package_overrides
+                .iter()
+                .map(|(package, version)| (*package, version.clone()))
+                .collect(),
+            environment_overrides: std::env::vars()
+                .filter(|(key, _)| key.starts_with(CONDA_OVERRIDE_PREFIX))
+                .collect(),
+        }
+    }
+
+    /// Reads the metadata from the given path.
+    pub fn from_path(path: &Path) -> Result<Self, LockFileMetadataError> {
+        if !path.is_file() {
+            return Err(LockFileMetadataError::NotFound);
+        }
+        let contents = fs_err::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|err| LockFileMetadataError::Invalid(path.to_path_buf(), err))
+    }
+
+    /// Writes the metadata to the given path, unless the file already
+    /// contains it.
+    pub fn to_path(&self, path: &Path) -> Result<(), LockFileMetadataError> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|err| LockFileMetadataError::Invalid(path.to_path_buf(), err))?
+            + "\n";
+        if fs_err::read_to_string(path).is_ok_and(|existing| existing == contents) {
+            return Ok(());
+        }
+        fs_err::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// Reads the state of the repodata that rattler cached, keyed by the url of
+/// the repodata. Sharded repodata has no such state and is not included.
+fn cached_repodata_states() -> HashMap<String, CachedRepodataState> {
+    let Ok(cache_dir) = pixi_config::get_cache_dir() else {
+        return HashMap::new();
+    };
+    let Ok(entries) = fs_err::read_dir(cache_dir.join(consts::CONDA_REPODATA_CACHE_DIR)) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(".info.json"))
+        .filter_map(|path| fs_err::read_to_string(path).ok())
+        .filter_map(|contents| serde_json::from_str::<CachedRepodataState>(&contents).ok())
+        .map(|state| (state.url.clone(), state))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pixi.lock.meta.json");
+        assert!(matches!(
+            LockFileMetadata::from_path(&path),
+            Err(LockFileMetadataError::NotFound)
+        ));
+
+        let metadata = LockFileMetadata {
+            pixi_version: String::from("0.1.0"),
+            channels: vec![ChannelMetadata {
+                url: String::from("https://prefix.dev/conda-forge/"),
+                subdirs: vec![SubdirMetadata {
+                    subdir: String::from("linux-64"),
+                    newest_package: DateTime::from_timestamp_millis(1_700_000_000_000),
+                    etag: Some(String::from("\"abc\"")),
+                    last_modified: None,
+                }],
+            }],
+            virtual_package_overrides: BTreeMap::from([(
+                OverridableVirtualPackage::Cuda,
+                String::from("12.4"),
+            )]),
+            environment_overrides: BTreeMap::new(),
+        };
+        metadata.to_path(&path).unwrap();
+        assert_eq!(LockFileMetadata::from_path(&path).unwrap(), metadata);
+
+        let contents = fs_err::read_to_string(&path).unwrap();
+        assert!(contents.contains("\"__cuda\": \"12.4\""));
+        assert!(!contents.contains("last-modified"));
+
+        // Writing the same metadata again leaves the file alone.
+        let modified = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1000);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        metadata.to_path(&path).unwrap();
+        assert_eq!(
+            fs_err::metadata(&path).unwrap().modified().unwrap(),
+            modified
+        );
+    }
+}
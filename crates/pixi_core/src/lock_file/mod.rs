@@ -1,4 +1,6 @@
+mod conflict;
 mod install_subset;
+mod metadata;
 mod outdated;
 mod package_identifier;
 mod records_by_name;
@@ -7,10 +9,18 @@ mod resolve;
 mod satisfiability;
 mod update;
 mod utils;
+mod uv_lock;
 pub mod virtual_packages;
 
 pub use crate::environment::CondaPrefixUpdater;
+pub use conflict::{
+    ConflictReason, ConflictingRequirement, SolveConflictError, parse_conflicting_requirements,
+};
 pub use install_subset::{FilteredPackages, InstallSubset};
+pub use metadata::{ChannelMetadata, LockFileMetadata, LockFileMetadataError, SubdirMetadata};
+pub use outdated::{
+    LockFileCheck, OutdatedEnvironmentReport, OutdatedReasonReport, check_lock_file,
+};
 pub use package_identifier::PypiPackageIdentifier;
 use pixi_record::PixiRecord;
 pub use pixi_uv_context::UvResolutionContext;
@@ -22,10 +32,12 @@ pub use satisfiability::{
     verify_platform_satisfiability,
 };
 pub use update::{
-    LockFileDerivedData, PackageFilterNames, ReinstallEnvironment, ReinstallPackages,
-    SolveCondaEnvironmentError, UpdateContext, UpdateLockFileOptions, UpdateMode,
+    LockFileDerivedData, LockFileNotUpToDateError, PackageFilterNames, ReinstallEnvironment,
+    ReinstallPackages, SolveCondaEnvironmentError, UpdateContext, UpdateLockFileOptions,
+    UpdateMode,
 };
 pub use utils::filter_lock_file;
+pub use uv_lock::{UvLock, UvLockError};
 
 pub use utils::IoConcurrencyLimit;
 
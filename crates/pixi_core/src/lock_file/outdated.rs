@@ -13,6 +13,7 @@ use pixi_glob::GlobHashCache;
 use pixi_manifest::FeaturesExt;
 use rattler_conda_types::Platform;
 use rattler_lock::{LockFile, LockedPackageRef};
+use serde::Serialize;
 
 /// A struct that contains information about specific outdated environments.
 ///
@@ -33,6 +34,19 @@ pub struct OutdatedEnvironments<'p> {
     /// discarded. This is the case for instance when the order of the
     /// channels changed.
     pub disregard_locked_content: DisregardLockedContent<'p>,
+
+    /// Why the targets are out of date.
+    pub(crate) reasons: Vec<OutdatedReason<'p>>,
+}
+
+/// Describes why an environment, or one of its platforms, is out of date with
+/// the lock-file.
+#[derive(Debug, Clone)]
+pub(crate) struct OutdatedReason<'p> {
+    pub environment: Environment<'p>,
+    /// The platform that is out of date, or `None` if all platforms are.
+    pub platform: Option<Platform>,
+    pub reason: String,
 }
 
 /// A struct that stores whether the locked content of certain environments
@@ -70,6 +84,7 @@ impl<'p> OutdatedEnvironments<'p> {
             mut outdated_conda,
             mut outdated_pypi,
             disregard_locked_content,
+            reasons,
         } = find_unsatisfiable_targets(workspace, lock_file, glob_hash_cache).await;
 
         // Extend the outdated targets to include the solve groups
@@ -118,6 +133,7 @@ impl<'p> OutdatedEnvironments<'p> {
             conda: outdated_conda,
             pypi: outdated_pypi,
             disregard_locked_content,
+            reasons,
         }
     }
 
@@ -128,11 +144,108 @@ impl<'p> OutdatedEnvironments<'p> {
     }
 }
 
+/// The result of checking whether a lock-file is up-to-date with the
+/// workspace, see [`check_lock_file`].
+#[derive(Debug, Default, Serialize)]
+pub struct LockFileCheck {
+    /// The environments that are out of date with the lock-file.
+    pub outdated: Vec<OutdatedEnvironmentReport>,
+}
+
+impl LockFileCheck {
+    /// Returns true if the lock-file satisfies the workspace.
+    pub fn is_up_to_date(&self) -> bool {
+        self.outdated.is_empty()
+    }
+}
+
+/// An environment that is out of date with the lock-file.
+#[derive(Debug, Serialize)]
+pub struct OutdatedEnvironmentReport {
+    pub environment: String,
+    /// The platforms of the environment that need to be solved again.
+    pub platforms: Vec<Platform>,
+    pub reasons: Vec<OutdatedReasonReport>,
+}
+
+/// Why an environment is out of date.
+#[derive(Debug, Serialize)]
+pub struct OutdatedReasonReport {
+    /// The platform that is out of date, or `None` if all platforms are.
+    pub platform: Option<Platform>,
+    pub reason: String,
+}
+
+/// Checks whether the lock-file satisfies the workspace without solving or
+/// modifying anything.
+pub async fn check_lock_file(workspace: &Workspace, lock_file: &LockFile) -> LockFileCheck {
+    let outdated = OutdatedEnvironments::from_workspace_and_lock_file(
+        workspace,
+        lock_file,
+        GlobHashCache::default(),
+    )
+    .await;
+
+    let outdated = workspace
+        .environments()
+        .into_iter()
+        .filter_map(|environment| {
+            let platforms = outdated
+                .conda
+                .get(&environment)
+                .into_iter()
+                .chain(outdated.pypi.get(&environment))
+                .flatten()
+                .copied()
+                .unique()
+                .sorted_by_key(|platform| platform.as_str())
+                .collect_vec();
+            if platforms.is_empty() {
+                return None;
+            }
+
+            let reasons = outdated
+                .reasons
+                .iter()
+                .filter(|reason| reason.environment == environment)
+                .map(|reason| OutdatedReasonReport {
+                    platform: reason.platform,
+                    reason: reason.reason.clone(),
+                })
+                .collect();
+
+            Some(OutdatedEnvironmentReport {
+                environment: environment.name().to_string(),
+                platforms,
+                reasons,
+            })
+        })
+        .collect();
+
+    LockFileCheck { outdated }
+}
+
 #[derive(Debug, Default)]
 struct UnsatisfiableTargets<'p> {
     outdated_conda: HashMap<Environment<'p>, HashSet<Platform>>,
     outdated_pypi: HashMap<Environment<'p>, HashSet<Platform>>,
     disregard_locked_content: DisregardLockedContent<'p>,
+    reasons: Vec<OutdatedReason<'p>>,
+}
+
+impl<'p> UnsatisfiableTargets<'p> {
+    fn add_reason(
+        &mut self,
+        environment: &Environment<'p>,
+        platform: Option<Platform>,
+        reason: impl ToString,
+    ) {
+        self.reasons.push(OutdatedReason {
+            environment: environment.clone(),
+            platform,
+            reason: reason.to_string(),
+        });
+    }
 }
 
 /// Find all targets (combination of environment and platform) who's
@@ -153,6 +266,11 @@ async fn find_unsatisfiable_targets<'p>(
                 "environment '{0}' is out of date because it does not exist in the lock-file.",
                 environment.name().fancy_display()
             );
+            unsatisfiable_targets.add_reason(
+                &environment,
+                None,
+                "the environment does not exist in the lock-file",
+            );
 
             unsatisfiable_targets
                 .outdated_conda
@@ -169,6 +287,7 @@ async fn find_unsatisfiable_targets<'p>(
                 "environment '{0}' is out of date because {unsat}",
                 environment.name().fancy_display()
             );
+            unsatisfiable_targets.add_reason(&environment, None, &unsat);
 
             unsatisfiable_targets
                 .outdated_conda
@@ -244,6 +363,7 @@ async fn find_unsatisfiable_targets<'p>(
                         "the pypi dependencies of environment '{0}' for platform {platform} are out of date because {unsat}",
                         environment.name().fancy_display()
                     );
+                    unsatisfiable_targets.add_reason(&environment, Some(platform), &unsat);
 
                     unsatisfiable_targets
                         .outdated_pypi
@@ -256,6 +376,7 @@ async fn find_unsatisfiable_targets<'p>(
                         "the dependencies of environment '{0}' for platform {platform} are out of date because {unsat}",
                         environment.name().fancy_display()
                     );
+                    unsatisfiable_targets.add_reason(&environment, Some(platform), &unsat);
 
                     unsatisfiable_targets
                         .outdated_conda
@@ -290,6 +411,14 @@ async fn find_unsatisfiable_targets<'p>(
             );
 
             for env in solve_group.environments() {
+                unsatisfiable_targets.add_reason(
+                    &env,
+                    Some(platform),
+                    format!(
+                        "the dependencies of solve group '{}' are out of date because {unsat}",
+                        solve_group.name()
+                    ),
+                );
                 unsatisfiable_targets
                     .outdated_conda
                     .entry(env.clone())
@@ -309,6 +438,7 @@ async fn find_unsatisfiable_targets<'p>(
             "the dependencies of environment '{0}' for platform {platform} are out of date because {unsat}",
             individual_env.name().fancy_display(),
         );
+        unsatisfiable_targets.add_reason(&individual_env, Some(platform), &unsat);
 
         unsatisfiable_targets
             .outdated_conda
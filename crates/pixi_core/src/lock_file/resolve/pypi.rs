@@ -18,13 +18,17 @@ use indicatif::ProgressBar;
 use itertools::{Either, Itertools};
 use miette::{Context, IntoDiagnostic};
 use pixi_consts::consts;
-use pixi_git::git::GitReference;
+use pixi_git::{
+    credentials::{Credentials, GitAuthenticationFailure},
+    git::GitReference,
+};
 use pixi_manifest::{
     EnvironmentName, SolveStrategy, SystemRequirements, pypi::pypi_options::PypiOptions,
 };
 use pixi_pypi_spec::PixiPypiSpec;
 use pixi_record::{LockedGitUrl, PixiRecord};
 use pixi_reporters::{UvReporter, UvReporterOptions};
+use pixi_utils::timings::{self, Phase};
 use pixi_uv_conversions::{
     ConversionError, as_uv_req, configure_insecure_hosts_for_tls_bypass,
     convert_uv_requirements_to_pep508, into_pinned_git_spec, pypi_options_to_build_options,
@@ -39,6 +43,7 @@ use rattler_digest::{Md5, Sha256, parse_digest_from_hex};
 use rattler_lock::{
     PackageHashes, PypiPackageData, PypiPackageEnvironmentData, PypiSourceTreeHashable, UrlOrPath,
 };
+use rattler_networking::LazyClient;
 use typed_path::Utf8TypedPathBuf;
 use url::Url;
 use uv_client::{
@@ -76,7 +81,7 @@ use crate::{
             resolver_provider::CondaResolverProvider,
         },
     },
-    workspace::{Environment, EnvironmentVars},
+    workspace::{Environment, EnvironmentVars, HasWorkspaceRef},
 };
 use pixi_uv_context::UvResolutionContext;
 
@@ -225,6 +230,12 @@ pub enum SolveError {
     },
     #[error("failed to resolve pypi dependencies")]
     Other(#[from] ResolveError),
+    #[error("failed to resolve pypi dependencies")]
+    GitAuthentication {
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+        #[help]
+        advice: &'static str,
+    },
     #[error("build dispatch initialization failed: {message}")]
     BuildDispatchPanic { message: String },
     #[error("unexpected panic during PyPI resolution: {message}")]
@@ -277,6 +288,52 @@ fn create_solve_error(
     }
 }
 
+/// Returns true if the error was caused by uv failing to fetch a git
+/// repository.
+fn is_git_fetch_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    std::iter::successors(Some(error), |error| error.source())
+        .any(|error| error.downcast_ref::<uv_git::GitResolverError>().is_some())
+}
+
+/// Checks whether git failed to authenticate to one of the `git_urls` when
+/// fetching a git repository failed during the resolution, and adds advice on
+/// how to provide the credentials.
+async fn with_git_authentication_advice(
+    error: SolveError,
+    git_urls: &[Url],
+    client: Option<&LazyClient>,
+) -> SolveError {
+    let Some(client) = client else {
+        return error;
+    };
+    let from_git_fetch = match &error {
+        SolveError::Other(error) => is_git_fetch_error(error),
+        SolveError::LookAhead(error) => is_git_fetch_error(error.as_ref()),
+        _ => false,
+    };
+    if !from_git_fetch {
+        return error;
+    }
+    for url in git_urls {
+        let credentials = Credentials::from_url(url);
+        let Some(failure) =
+            GitAuthenticationFailure::probe(url, credentials.as_ref(), client.client()).await
+        else {
+            continue;
+        };
+        let source: Box<dyn std::error::Error + Send + Sync + 'static> = match error {
+            SolveError::Other(error) => Box::new(error),
+            SolveError::LookAhead(error) => miette::Report::new_boxed(error).into(),
+            error => return error,
+        };
+        return SolveError::GitAuthentication {
+            source,
+            advice: failure.help(),
+        };
+    }
+    error
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn resolve_pypi(
     context: UvResolutionContext,
@@ -285,6 +342,7 @@ pub async fn resolve_pypi(
     system_requirements: SystemRequirements,
     locked_pixi_records: &[PixiRecord],
     locked_pypi_packages: &[PypiRecord],
+    preferred_versions: &HashMap<pep508_rs::PackageName, pep440_rs::Version>,
     platform: rattler_conda_types::Platform,
     pb: &ProgressBar,
     project_root: &Path,
@@ -296,6 +354,8 @@ pub async fn resolve_pypi(
     exclude_newer: Option<DateTime<Utc>>,
     solve_strategy: SolveStrategy,
 ) -> miette::Result<(LockedPypiPackages, Option<CondaPrefixUpdated>)> {
+    let _timer = timings::start(Phase::PypiSolve);
+
     // Solve python packages
     pb.set_message("resolving pypi dependencies");
 
@@ -346,6 +406,19 @@ pub async fn resolve_pypi(
         tracing::info!("there are no python packages installed by conda");
     }
 
+    // The git dependencies to check when the resolution fails, to explain
+    // authentication failures.
+    let git_urls = dependencies
+        .values()
+        .flatten()
+        .filter_map(|spec| spec.as_git().map(|git| git.git.clone()))
+        .collect::<Vec<_>>();
+    let git_client = environment_name
+        .workspace()
+        .authenticated_client()
+        .ok()
+        .cloned();
+
     let mut requirements = dependencies
         .into_iter()
         .flat_map(|(name, req)| {
@@ -374,7 +447,12 @@ pub async fn resolve_pypi(
     let marker_environment = determine_marker_environment(platform, python_record.as_ref())?;
 
     // Determine the tags for this particular solve.
-    let tags = get_pypi_tags(platform, &system_requirements, python_record.as_ref())?;
+    let tags = get_pypi_tags(
+        platform,
+        &system_requirements,
+        pypi_options.platform_tag(platform),
+        python_record.as_ref(),
+    )?;
 
     // We need to setup both an interpreter and a requires_python specifier.
     // The interpreter is used to (potentially) build the wheel, and the
@@ -421,6 +499,7 @@ pub async fn resolve_pypi(
         .keyring(context.keyring_provider)
         .connectivity(Connectivity::Online)
         .native_tls(context.use_native_tls)
+        .retries(context.retries)
         .extra_middleware(context.extra_middleware.clone());
 
     let mut uv_client_builder =
@@ -588,7 +667,7 @@ pub async fn resolve_pypi(
 
     // Create preferences from the locked pypi packages
     // This will ensure minimal lock file updates
-    let preferences = locked_pypi_packages
+    let mut preferences = locked_pypi_packages
         .iter()
         .map(|record| {
             let (package_data, _) = record;
@@ -674,6 +753,37 @@ pub async fn resolve_pypi(
         .collect::<Result<Vec<_>, PixiPreferencesError>>()
         .into_diagnostic()?;
 
+    // Prefer the given versions for the packages that are not locked yet.
+    let locked_names = locked_pypi_packages
+        .iter()
+        .map(|(package_data, _)| &package_data.name)
+        .collect::<HashSet<_>>();
+    let preferred = preferred_versions
+        .iter()
+        .filter(|(name, _)| !locked_names.contains(name))
+        .map(|(name, version)| {
+            let requirement = uv_pep508::Requirement {
+                name: to_uv_normalize(name)?,
+                extras: Vec::new().into(),
+                version_or_url: Some(uv_pep508::VersionOrUrl::VersionSpecifier(
+                    uv_pep440::VersionSpecifiers::from(
+                        uv_pep440::VersionSpecifier::equals_version(to_uv_version(version)?),
+                    ),
+                )),
+                marker: uv_pep508::MarkerTree::TRUE,
+                origin: None,
+            };
+            let entry = uv_requirements_txt::RequirementEntry {
+                requirement: uv_requirements_txt::RequirementsTxtRequirement::Named(requirement),
+                hashes: Default::default(),
+            };
+            Ok(Preference::from_entry(entry)?)
+        })
+        .filter_map(|pref| pref.transpose())
+        .collect::<Result<Vec<_>, PixiPreferencesError>>()
+        .into_diagnostic()?;
+    preferences.extend(preferred);
+
     let resolver_env = ResolverEnvironment::specific(marker_environment.clone().into());
 
     let constraints = Constraints::from_requirements(constraints.iter().cloned());
@@ -780,7 +890,14 @@ pub async fn resolve_pypi(
 
     // We try to distinguish between build dispatch panics and any other panics that occur
     let resolution = match resolution_future.catch_unwind().await {
-        Ok(result) => result?,
+        Ok(Ok(resolution)) => resolution,
+        Ok(Err(err)) => {
+            return Err(
+                with_git_authentication_advice(err, &git_urls, git_client.as_ref())
+                    .await
+                    .into(),
+            );
+        }
         Err(panic_payload) => {
             // Try to get the stored initialization error from the lazy build dispatch
             if let Some(stored_error) = lazy_build_dispatch.last_initialization_error() {
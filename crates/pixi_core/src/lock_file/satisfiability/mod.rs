@@ -7,6 +7,7 @@ use std::{
     str::FromStr,
 };
 
+use indexmap::IndexMap;
 use itertools::{Either, Itertools};
 use miette::Diagnostic;
 use pep440_rs::VersionSpecifiers;
@@ -16,7 +17,7 @@ use pixi_git::url::RepositoryUrl;
 use pixi_glob::{GlobHashCache, GlobHashError, GlobHashKey};
 use pixi_manifest::{
     FeaturesExt,
-    pypi::pypi_options::{NoBuild, PrereleaseMode},
+    pypi::pypi_options::{NoBuild, PrereleaseMode, PypiPlatformTag},
 };
 use pixi_record::{
     LockedGitUrl, ParseLockFileError, PinnedSourceSpec, PixiRecord, SourceMismatchError,
@@ -75,6 +76,15 @@ pub enum EnvironmentUnsat {
     )]
     NoBuildWithNonBinaryPackages(String),
 
+    #[error(
+        "the lock-file contains the wheel '{wheel}' for {platform}, but the pypi-option `platform-tags` requires '{tag}' wheels"
+    )]
+    PlatformTagMismatch {
+        wheel: String,
+        platform: Platform,
+        tag: PypiPlatformTag,
+    },
+
     #[error(
         "the lock-file was solved with a different strategy ({locked_strategy}) than the one selected ({expected_strategy})",
         locked_strategy = fmt_solve_strategy(*.locked_strategy),
@@ -480,6 +490,7 @@ pub fn verify_environment_satisfiability(
     // 1. Check if the PyPI indexes are present and match
     // 2. Check if we have a no-build option set, that we only have binary packages,
     //    or an editable source
+    // 3. Check if the locked wheels match the platform tags that are requested
     if !environment.pypi_dependencies(None).is_empty() {
         let group_pypi_options = grouped_env.pypi_options();
         let indexes = rattler_lock::PypiIndexes::from(group_pypi_options.clone());
@@ -492,6 +503,10 @@ pub fn verify_environment_satisfiability(
         if let Some(no_build) = group_pypi_options.no_build.as_ref() {
             verify_pypi_no_build(no_build, locked_environment)?;
         }
+
+        if let Some(platform_tags) = group_pypi_options.platform_tags.as_ref() {
+            verify_pypi_platform_tags(platform_tags, locked_environment)?;
+        }
     }
 
     // Verify solver options
@@ -545,6 +560,51 @@ pub fn verify_environment_satisfiability(
     Ok(())
 }
 
+fn verify_pypi_platform_tags(
+    platform_tags: &IndexMap<Platform, PypiPlatformTag>,
+    locked_environment: rattler_lock::Environment<'_>,
+) -> Result<(), EnvironmentUnsat> {
+    for (platform, packages) in locked_environment.pypi_packages_by_platform() {
+        let Some(tag) = platform_tags.get(&platform) else {
+            continue;
+        };
+
+        for (package, _) in packages {
+            let file_name = match &package.location {
+                UrlOrPath::Url(url) => url
+                    .path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .map(ToString::to_string),
+                UrlOrPath::Path(path) => Path::new(path.as_str())
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+            };
+            let Some(wheel_tags) = file_name
+                .as_deref()
+                .and_then(|name| name.strip_suffix(".whl"))
+                .and_then(|name| name.rsplit('-').next())
+            else {
+                // Not a wheel, so there are no platform tags to check
+                continue;
+            };
+
+            // The platform tags of a wheel can be compressed, e.g.
+            // `manylinux_2_17_x86_64.manylinux2014_x86_64`
+            let compatible = wheel_tags.split('.').any(|wheel_tag| {
+                wheel_tag == "any" || wheel_tag.starts_with(tag.wheel_tag_prefix())
+            });
+            if !compatible {
+                return Err(EnvironmentUnsat::PlatformTagMismatch {
+                    wheel: file_name.unwrap_or_default(),
+                    platform,
+                    tag: *tag,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 fn verify_pypi_no_build(
     no_build: &NoBuild,
     locked_environment: rattler_lock::Environment<'_>,
@@ -19,7 +19,7 @@ use indicatif::ProgressBar;
 use itertools::{Either, Itertools};
 use miette::{Diagnostic, IntoDiagnostic, MietteDiagnostic, Report, WrapErr};
 use pixi_command_dispatcher::{
-    BuildEnvironment, CommandDispatcher, CommandDispatcherError, PixiEnvironmentSpec,
+    BuildEnvironment, CommandDispatcher, CommandDispatcherError, Limits, PixiEnvironmentSpec,
     SolvePixiEnvironmentError,
 };
 use pixi_consts::consts;
@@ -49,7 +49,7 @@ use tracing::Instrument;
 use uv_normalize::ExtraName;
 
 use super::{
-    CondaPrefixUpdater, InstallSubset, PixiRecordsByName, PypiRecordsByName,
+    CondaPrefixUpdater, InstallSubset, LockFileMetadata, PixiRecordsByName, PypiRecordsByName,
     outdated::OutdatedEnvironments, utils::IoConcurrencyLimit,
 };
 use crate::{
@@ -57,13 +57,15 @@ use crate::{
     activation::CurrentEnvVarBehavior,
     environment::{
         CondaPrefixUpdated, EnvironmentFile, InstallFilter, LockFileUsage, LockedEnvironmentHash,
-        PerEnvironmentAndPlatform, PerGroup, PerGroupAndPlatform, PythonStatus,
+        PackageMessage, PerEnvironmentAndPlatform, PerGroup, PerGroupAndPlatform, PythonStatus,
         read_environment_file, write_environment_file,
     },
+    history::History,
     lock_file::{
-        self, PypiRecord, reporter::SolveProgressBar,
+        self, PypiRecord, SolveConflictError, reporter::SolveProgressBar,
         virtual_packages::validate_system_meets_environment_requirements,
     },
+    signatures,
     workspace::{
         Environment, EnvironmentVars, HasWorkspaceRef,
         errors::VariantsError,
@@ -260,11 +262,21 @@ impl Workspace {
 
         let glob_hash_cache = GlobHashCache::default();
 
+        // Fall back to the configured limit if the caller did not specify one.
+        let max_concurrent_solves = match options.max_concurrent_solves {
+            0 => self.config().max_concurrent_solves(),
+            max_concurrent_solves => max_concurrent_solves,
+        };
+
         // Construct a command dispatcher that will be used to run the tasks.
         let multi_progress = global_multi_progress();
         let anchor_pb = multi_progress.add(ProgressBar::hidden());
         let command_dispatcher = self
             .command_dispatcher_builder()?
+            .with_limits(Limits {
+                max_concurrent_solves: max_concurrent_solves.into(),
+                ..Limits::default()
+            })
             .with_reporter(pixi_reporters::TopLevelProgress::new(
                 global_multi_progress(),
                 anchor_pb,
@@ -285,6 +297,7 @@ impl Workspace {
                     package_cache,
                     updated_conda_prefixes: Default::default(),
                     updated_pypi_prefixes: Default::default(),
+                    package_messages: Default::default(),
                     uv_context: Default::default(),
                     io_concurrency_limit: IoConcurrencyLimit::default(),
                     command_dispatcher,
@@ -312,6 +325,7 @@ impl Workspace {
                     package_cache,
                     updated_conda_prefixes: Default::default(),
                     updated_pypi_prefixes: Default::default(),
+                    package_messages: Default::default(),
                     uv_context: Default::default(),
                     io_concurrency_limit: IoConcurrencyLimit::default(),
                     command_dispatcher,
@@ -324,7 +338,7 @@ impl Workspace {
         // If the lock-file is out of date, but we're not allowed to update it, we
         // should exit.
         if !options.lock_file_usage.allow_updates() {
-            miette::bail!("lock-file not up-to-date with the workspace");
+            return Err(LockFileNotUpToDateError.into());
         }
 
         // Construct an update context and perform the actual update.
@@ -335,6 +349,7 @@ impl Workspace {
             .with_lock_file(lock_file)
             .with_glob_hash_cache(glob_hash_cache)
             .with_command_dispatcher(command_dispatcher)
+            .with_max_concurrent_solves(max_concurrent_solves)
             .finish()
             .await?
             .update()
@@ -389,6 +404,12 @@ impl Workspace {
     }
 }
 
+/// The lock-file is out of date, but the lock-file usage does not allow
+/// updating it.
+#[derive(Debug, Error, Diagnostic)]
+#[error("lock-file not up-to-date with the workspace")]
+pub struct LockFileNotUpToDateError;
+
 #[derive(Debug, Error, Diagnostic)]
 enum UpdateError {
     #[error("the lockfile is not up-to-date with requested environment: '{}'", .0.fancy_display())]
@@ -408,6 +429,10 @@ pub enum SolveCondaEnvironmentError {
         source: Box<CommandDispatcherError<SolvePixiEnvironmentError>>,
     },
 
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Conflict(Box<SolveConflictError>),
+
     #[error(
         "failed to map conda packages to their PyPI equivalents. This mapping is required when using PyPI dependencies alongside conda packages."
     )]
@@ -431,9 +456,10 @@ pub struct UpdateLockFileOptions {
     /// Don't install anything to disk.
     pub no_install: bool,
 
-    /// The maximum number of concurrent solves that are allowed to run. If this
-    /// value is None a heuristic is used based on the number of cores
-    /// available from the system.
+    /// The maximum number of concurrent solves that are allowed to run. Conda
+    /// and PyPI solves for different environments and platforms run
+    /// concurrently up to this limit. If this value is `0` the
+    /// `max-concurrent-solves` configuration is used.
     pub max_concurrent_solves: usize,
 }
 
@@ -475,6 +501,10 @@ pub struct LockFileDerivedData<'p> {
     /// dependencies.
     pub updated_pypi_prefixes: DashMap<EnvironmentName, Arc<async_once_cell::OnceCell<Prefix>>>,
 
+    /// The messages that the packages left for the user when they were
+    /// installed into an environment.
+    pub package_messages: DashMap<EnvironmentName, Vec<PackageMessage>>,
+
     /// The cached uv context
     pub uv_context: once_cell::sync::OnceCell<UvResolutionContext>,
 
@@ -495,7 +525,15 @@ pub enum UpdateMode {
     /// Using a fast and simple validation method.
     /// Used for skipping the update if the prefix is already up-to-date, in
     /// activating commands. Like `pixi shell` or `pixi run`.
+    ///
+    /// Environments with source packages are always updated, so packages
+    /// whose sources changed are rebuilt.
     QuickValidate,
+    /// Like [`UpdateMode::QuickValidate`], but environments with source
+    /// packages are not updated if the lock-file did not change. Source
+    /// packages whose sources changed are not rebuilt.
+    /// Like `pixi run --no-rebuild`.
+    QuickValidateWithoutRebuild,
     /// Force a prefix install without running the short validation.
     /// Used for updating the prefix when the lock-file likely out of date.
     /// Like `pixi install` or `pixi update`.
@@ -504,12 +542,30 @@ pub enum UpdateMode {
 
 impl<'p> LockFileDerivedData<'p> {
     /// Write the lock-file to disk.
+    ///
+    /// The metadata next to the lock-file describes the solve that produced
+    /// it, so it is only recorded when the lock-file changes.
     pub fn write_to_disk(&self) -> miette::Result<()> {
         let lock_file_path = self.workspace.lock_file_path();
+        let metadata_path = self.workspace.lock_file_metadata_path();
+        let changed = match (
+            fs_err::read_to_string(&lock_file_path),
+            self.lock_file.render_to_string(),
+        ) {
+            (Ok(existing), Ok(contents)) => existing != contents,
+            _ => true,
+        };
         self.lock_file
             .to_path(&lock_file_path)
             .into_diagnostic()
-            .context("failed to write lock-file to disk")
+            .context("failed to write lock-file to disk")?;
+        if changed || !metadata_path.is_file() {
+            LockFileMetadata::new(self.workspace, &self.lock_file)
+                .to_path(&metadata_path)
+                .into_diagnostic()
+                .context("failed to write lock-file metadata to disk")?;
+        }
+        Ok(())
     }
 
     /// Consumes this instance, dropping any resources that are not needed
@@ -550,12 +606,23 @@ impl<'p> LockFileDerivedData<'p> {
         // Check if the prefix is already up-to-date by validating the hash with the
         // environment file
         let hash = self.locked_environment_hash(environment)?;
-        if update_mode == UpdateMode::QuickValidate
-            && let Some(prefix) = self.cached_prefix(environment, &hash)
+        let rebuild_sources = match update_mode {
+            UpdateMode::QuickValidate => Some(true),
+            UpdateMode::QuickValidateWithoutRebuild => Some(false),
+            UpdateMode::Revalidate => None,
+        };
+        if let Some(rebuild_sources) = rebuild_sources
+            && let Some(prefix) = self.cached_prefix(environment, &hash, rebuild_sources)
         {
             return prefix;
         }
 
+        // The hash of the packages that were installed before the update.
+        let previous_hash = read_environment_file(&environment.dir())
+            .ok()
+            .flatten()
+            .map(|file| file.environment_lock_file_hash);
+
         // Get the up-to-date prefix
         let prefix = self
             .update_prefix(environment, reinstall_packages, filter)
@@ -581,6 +648,19 @@ impl<'p> LockFileDerivedData<'p> {
             },
         )?;
 
+        // Record the installed state so that it can be restored later, if the
+        // update changed the packages of the prefix.
+        if !filter.filter_active()
+            && previous_hash.as_ref() != Some(&hash)
+            && let Err(err) = History::new(self.workspace).record(
+                &self.lock_file,
+                environment.name().as_str(),
+                environment.best_platform(),
+            )
+        {
+            tracing::warn!("failed to record the environment in the history: {err}");
+        }
+
         Ok(prefix)
     }
 
@@ -588,6 +668,7 @@ impl<'p> LockFileDerivedData<'p> {
         &self,
         environment: &Environment<'p>,
         hash: &LockedEnvironmentHash,
+        rebuild_sources: bool,
     ) -> Option<Result<Prefix, Report>> {
         let Ok(Some(environment_file)) = read_environment_file(&environment.dir()) else {
             tracing::debug!(
@@ -598,8 +679,17 @@ impl<'p> LockFileDerivedData<'p> {
         };
 
         if environment_file.environment_lock_file_hash == *hash {
+            if !rebuild_sources {
+                tracing::info!(
+                    "Environment '{}' is up-to-date with lock file hash, not checking source packages for changes",
+                    environment.name().fancy_display()
+                );
+                return Some(Ok(Prefix::new(environment.dir())));
+            }
+
             // If we contain source packages from conda or PyPI we update the prefix by
-            // default
+            // default, the sources of the packages might have changed since they were
+            // built.
             let contains_conda_source_pkgs = self.lock_file.environments().any(|(_, env)| {
                 env.conda_packages(Platform::current())
                     .is_some_and(|mut packages| {
@@ -645,185 +735,249 @@ impl<'p> LockFileDerivedData<'p> {
             .or_default()
             .clone();
         prefix_once_cell
-            .get_or_try_init(async {
-                let start = Instant::now();
-
-                // Validate the virtual packages for the environment match the system
-                validate_system_meets_environment_requirements(
-                    &self.lock_file,
-                    environment.best_platform(),
-                    environment.name(),
-                    None,
-                )
-                .wrap_err(format!(
-                    "Cannot install environment '{}'",
-                    environment.name().fancy_display()
-                ))?;
-
-                let platform = environment.best_platform();
-                let locked_env = self.locked_env(environment)?;
-                let subset = InstallSubset::new(
-                    &filter.skip_with_deps,
-                    &filter.skip_direct,
-                    &filter.target_packages,
-                );
-                let result = subset.filter(locked_env.packages(platform))?;
-                let packages = result.install;
-                let ignored = result.ignore;
+            .get_or_try_init(self.install_prefix(environment, None, reinstall_packages, filter))
+            .await
+            .cloned()
+    }
 
-                // Separate the packages into conda and pypi packages
-                let (conda_packages, pypi_packages) = packages
-                    .into_iter()
-                    .partition::<Vec<_>, _>(|p| p.as_conda().is_some());
+    /// Installs the environment into the given prefix instead of the
+    /// environment directory in the workspace. The prefix does not refer back
+    /// to the workspace, editable PyPI packages are installed as regular
+    /// packages.
+    ///
+    /// The packages of `platform` are installed, which may differ from the
+    /// platform of this machine. In that case the post-link scripts of the
+    /// packages are not run, and environments with PyPI packages are not
+    /// supported.
+    pub async fn install_to_prefix(
+        &self,
+        environment: &Environment<'p>,
+        prefix: &Prefix,
+        platform: Platform,
+        filter: &InstallFilter,
+    ) -> miette::Result<Prefix> {
+        self.install_prefix(
+            environment,
+            Some(InstallTarget { prefix, platform }),
+            &ReinstallPackages::default(),
+            filter,
+        )
+        .await
+    }
 
-                let (ignored_conda, ignored_pypi): (HashSet<_>, HashSet<_>) =
-                    ignored.into_iter().partition_map(|p| match p {
-                        LockedPackageRef::Conda(data) => Either::Left(data.record().name.clone()),
-                        LockedPackageRef::Pypi(data, _) => Either::Right(data.name.clone()),
-                    });
+    /// Installs the conda and PyPI packages of the environment into `target`,
+    /// or into the environment directory if no target is given.
+    async fn install_prefix(
+        &self,
+        environment: &Environment<'p>,
+        target: Option<InstallTarget<'_>>,
+        reinstall_packages: &ReinstallPackages,
+        filter: &InstallFilter,
+    ) -> miette::Result<Prefix> {
+        let start = Instant::now();
 
-                let pixi_records =
-                    locked_packages_to_pixi_records(conda_packages, self.workspace.root())?;
+        let platform = target.map_or_else(|| environment.best_platform(), |target| target.platform);
+        let is_cross_platform = platform != environment.best_platform();
 
-                // Get the manifest's pypi dependencies for this environment to look up editability.
-                // The lock file always stores editable=false, so we apply the actual
-                // editability from the manifest at install time.
-                let manifest_pypi_deps = environment.pypi_dependencies(Some(platform));
+        // Validate the virtual packages for the environment match the system. The
+        // system of another platform is unknown, so it cannot be validated.
+        if !is_cross_platform {
+            validate_system_meets_environment_requirements(
+                &self.lock_file,
+                platform,
+                environment.name(),
+                Some(environment.workspace().config().virtual_package_overrides()),
+            )
+            .wrap_err(format!(
+                "Cannot install environment '{}'",
+                environment.name().fancy_display()
+            ))?;
+        }
 
-                let pypi_records = pypi_packages
-                    .into_iter()
-                    .filter_map(LockedPackageRef::as_pypi)
-                    .map(|(data, env_data)| {
-                        let mut data = data.clone();
-                        data.editable = manifest_pypi_deps
-                            .get(&data.name)
-                            .and_then(|specs| specs.last())
-                            .and_then(|spec| spec.editable())
-                            .unwrap_or(data.editable);
-                        (data, env_data.clone())
-                    })
-                    .collect::<Vec<_>>();
-
-                let conda_reinstall_packages = match reinstall_packages {
-                    ReinstallPackages::None => None,
-                    ReinstallPackages::Some(p) => Some(
-                        p.iter()
-                            .filter_map(|p| PackageName::from_str(p).ok())
-                            .filter(|name| pixi_records.iter().any(|r| r.name() == name))
-                            .collect(),
-                    ),
-                    ReinstallPackages::All => {
-                        Some(pixi_records.iter().map(|r| r.name().clone()).collect())
-                    }
-                };
+        let locked_env = self.locked_env(environment)?;
+        let subset = InstallSubset::new(
+            &filter.skip_with_deps,
+            &filter.skip_direct,
+            &filter.target_packages,
+        );
+        let result = subset.filter(locked_env.packages(platform))?;
+        let packages = result.install;
+        let ignored = result.ignore;
 
-                // Get the prefix with the conda packages installed.
-                let (prefix, python_status) = self
-                    .conda_prefix(environment, conda_reinstall_packages, Some(ignored_conda))
-                    .await?;
+        // Separate the packages into conda and pypi packages
+        let (conda_packages, pypi_packages) = packages
+            .into_iter()
+            .partition::<Vec<_>, _>(|p| p.as_conda().is_some());
 
-                // No `uv` support for WASM right now
-                if platform.arch() == Some(Arch::Wasm32) {
-                    return Ok(prefix);
-                }
+        let (ignored_conda, ignored_pypi): (HashSet<_>, HashSet<_>) =
+            ignored.into_iter().partition_map(|p| match p {
+                LockedPackageRef::Conda(data) => Either::Left(data.record().name.clone()),
+                LockedPackageRef::Pypi(data, _) => Either::Right(data.name.clone()),
+            });
 
-                let pypi_lock_file_names = pypi_records
-                    .iter()
-                    .filter_map(|(data, _)| to_uv_normalize(&data.name).ok())
-                    .collect::<HashSet<_>>();
-
-                // Figure out uv reinstall
-                let (uv_reinstall, uv_packages) = match reinstall_packages {
-                    ReinstallPackages::None => (Some(false), None),
-                    ReinstallPackages::All => (Some(true), None),
-                    ReinstallPackages::Some(pkgs) => (
-                        None,
-                        Some(
-                            pkgs.iter()
-                                .filter_map(|pkg| uv_normalize::PackageName::from_str(pkg).ok())
-                                .filter(|name| pypi_lock_file_names.contains(name))
-                                .collect(),
-                        ),
-                    ),
-                };
+        let pixi_records = locked_packages_to_pixi_records(conda_packages, self.workspace.root())?;
 
-                let uv_context = self
-                    .uv_context
-                    .get_or_try_init(|| UvResolutionContext::from_config(self.workspace.config()))?
-                    .clone()
-                    .set_cache_refresh(uv_reinstall, uv_packages);
-
-                let non_isolated_packages = environment.pypi_options().no_build_isolation;
-                let no_build = environment
-                    .pypi_options()
-                    .no_build
-                    .clone()
-                    .unwrap_or_default();
-                let no_binary = environment
-                    .pypi_options()
-                    .no_binary
-                    .clone()
-                    .unwrap_or_default();
+        // Get the manifest's pypi dependencies for this environment to look up editability.
+        // The lock file always stores editable=false, so we apply the actual
+        // editability from the manifest at install time. Editable packages
+        // refer to the workspace, so they are not editable outside of it.
+        let manifest_pypi_deps = environment.pypi_dependencies(Some(platform));
 
-                // Update the prefix with Pypi records
-                {
-                    let pypi_indexes = self.locked_env(environment)?.pypi_indexes().cloned();
-                    let index_strategy = environment.pypi_options().index_strategy.clone();
-                    let exclude_newer = environment.exclude_newer();
-
-                    let config = PyPIUpdateConfig {
-                        environment_name: environment.name(),
-                        prefix: &prefix,
-                        platform: environment.best_platform(),
-                        lock_file_dir: self.workspace.root(),
-                        system_requirements: &environment.system_requirements(),
-                    };
-
-                    let build_config = PyPIBuildConfig {
-                        no_build_isolation: &non_isolated_packages,
-                        no_build: &no_build,
-                        no_binary: &no_binary,
-                        index_strategy: index_strategy.as_ref(),
-                        exclude_newer: exclude_newer.as_ref(),
-                    };
-
-                    let lazy_env_vars = LazyPixiEnvironmentVars {
-                        environment: environment.clone(),
-                    };
-                    let context_config = PyPIContextConfig {
-                        uv_context: &uv_context,
-                        pypi_indexes: pypi_indexes.as_ref(),
-                        environment_variables_lazy: Some(&lazy_env_vars),
-                    };
-
-                    // Ignored pypi records
-                    let names = ignored_pypi
-                        .iter()
-                        .map(to_uv_normalize)
-                        .collect::<Result<Vec<_>, _>>()
-                        .into_diagnostic()?;
-                    PyPIEnvironmentUpdater::new(config, build_config, context_config)
-                        .with_ignored_extraneous(names)
-                        .update(&python_status, &pixi_records, &pypi_records)
-                        .await
-                }
-                .with_context(|| {
-                    format!(
-                        "Failed to update PyPI packages for environment '{}'",
-                        environment.name().fancy_display()
-                    )
-                })?;
+        let pypi_records = pypi_packages
+            .into_iter()
+            .filter_map(LockedPackageRef::as_pypi)
+            .map(|(data, env_data)| {
+                let mut data = data.clone();
+                data.editable = target.is_none()
+                    && manifest_pypi_deps
+                        .get(&data.name)
+                        .and_then(|specs| specs.last())
+                        .and_then(|spec| spec.editable())
+                        .unwrap_or(data.editable);
+                (data, env_data.clone())
+            })
+            .collect::<Vec<_>>();
+
+        // Installing PyPI packages requires running the Python interpreter of
+        // the environment.
+        if is_cross_platform && !pypi_records.is_empty() {
+            miette::bail!(
+                "cannot install the PyPI packages of the '{}' environment for {platform}, PyPI packages can only be installed for the platform of this machine ({})",
+                environment.name().fancy_display(),
+                environment.best_platform()
+            );
+        }
 
-                tracing::info!(
-                    "Installed environment '{}' in {:?}",
-                    environment.name().fancy_display(),
-                    start.elapsed()
-                );
+        let conda_reinstall_packages = match reinstall_packages {
+            ReinstallPackages::None => None,
+            ReinstallPackages::Some(p) => Some(
+                p.iter()
+                    .filter_map(|p| PackageName::from_str(p).ok())
+                    .filter(|name| pixi_records.iter().any(|r| r.name() == name))
+                    .collect(),
+            ),
+            ReinstallPackages::All => Some(pixi_records.iter().map(|r| r.name().clone()).collect()),
+        };
 
-                Ok(prefix)
-            })
-            .await
-            .cloned()
+        // Get the prefix with the conda packages installed.
+        let (prefix, python_status) = self
+            .conda_prefix(
+                environment,
+                target,
+                conda_reinstall_packages,
+                Some(ignored_conda),
+            )
+            .await?;
+
+        // No `uv` support for WASM right now
+        if platform.arch() == Some(Arch::Wasm32) {
+            return Ok(prefix);
+        }
+
+        let pypi_lock_file_names = pypi_records
+            .iter()
+            .filter_map(|(data, _)| to_uv_normalize(&data.name).ok())
+            .collect::<HashSet<_>>();
+
+        // Figure out uv reinstall
+        let (uv_reinstall, uv_packages) = match reinstall_packages {
+            ReinstallPackages::None => (Some(false), None),
+            ReinstallPackages::All => (Some(true), None),
+            ReinstallPackages::Some(pkgs) => (
+                None,
+                Some(
+                    pkgs.iter()
+                        .filter_map(|pkg| uv_normalize::PackageName::from_str(pkg).ok())
+                        .filter(|name| pypi_lock_file_names.contains(name))
+                        .collect(),
+                ),
+            ),
+        };
+
+        let uv_context = self
+            .uv_context
+            .get_or_try_init(|| UvResolutionContext::from_config(self.workspace.config()))?
+            .clone()
+            .set_cache_refresh(uv_reinstall, uv_packages);
+
+        let non_isolated_packages = environment.pypi_options().no_build_isolation;
+        let no_build = environment
+            .pypi_options()
+            .no_build
+            .clone()
+            .unwrap_or_default();
+        let no_binary = environment
+            .pypi_options()
+            .no_binary
+            .clone()
+            .unwrap_or_default();
+
+        // Verify the provenance of the wheels before they are installed
+        signatures::verify_pypi_packages(
+            self.workspace.config(),
+            self.workspace.authenticated_client()?,
+            pypi_records.iter().map(|(data, _)| data),
+        )
+        .await?;
+
+        // Update the prefix with Pypi records
+        {
+            let pypi_indexes = self.locked_env(environment)?.pypi_indexes().cloned();
+            let index_strategy = environment.pypi_options().index_strategy.clone();
+            let exclude_newer = environment.exclude_newer();
+            let pypi_options = environment.pypi_options();
+
+            let config = PyPIUpdateConfig {
+                environment_name: environment.name(),
+                prefix: &prefix,
+                platform,
+                lock_file_dir: self.workspace.root(),
+                system_requirements: &environment.system_requirements(),
+                platform_tag: pypi_options.platform_tag(platform),
+            };
+
+            let build_config = PyPIBuildConfig {
+                no_build_isolation: &non_isolated_packages,
+                no_build: &no_build,
+                no_binary: &no_binary,
+                index_strategy: index_strategy.as_ref(),
+                exclude_newer: exclude_newer.as_ref(),
+            };
+
+            let lazy_env_vars = LazyPixiEnvironmentVars {
+                environment: environment.clone(),
+            };
+            let context_config = PyPIContextConfig {
+                uv_context: &uv_context,
+                pypi_indexes: pypi_indexes.as_ref(),
+                environment_variables_lazy: Some(&lazy_env_vars),
+            };
+
+            // Ignored pypi records
+            let names = ignored_pypi
+                .iter()
+                .map(to_uv_normalize)
+                .collect::<Result<Vec<_>, _>>()
+                .into_diagnostic()?;
+            PyPIEnvironmentUpdater::new(config, build_config, context_config)
+                .with_ignored_extraneous(names)
+                .update(&python_status, &pixi_records, &pypi_records)
+                .await
+        }
+        .with_context(|| {
+            format!(
+                "Failed to update PyPI packages for environment '{}'",
+                environment.name().fancy_display()
+            )
+        })?;
+
+        tracing::info!(
+            "Installed environment '{}' in {:?}",
+            environment.name().fancy_display(),
+            start.elapsed()
+        );
+
+        Ok(prefix)
     }
 
     fn locked_env(
@@ -838,9 +992,17 @@ impl<'p> LockFileDerivedData<'p> {
     async fn conda_prefix(
         &self,
         environment: &Environment<'p>,
+        target: Option<InstallTarget<'_>>,
         reinstall_packages: Option<HashSet<PackageName>>,
         ignore_packages: Option<HashSet<PackageName>>,
     ) -> miette::Result<(Prefix, PythonStatus)> {
+        // A prefix outside the workspace is not shared with other updates.
+        if target.is_some() {
+            return self
+                .install_conda_prefix(environment, target, reinstall_packages, ignore_packages)
+                .await;
+        }
+
         // If we previously updated this environment, early out.
         let prefix_once_cell = self
             .updated_conda_prefixes
@@ -848,47 +1010,97 @@ impl<'p> LockFileDerivedData<'p> {
             .or_default()
             .clone();
         prefix_once_cell
-            .get_or_try_init(async {
-                // Create object to update the prefix
-                let group = GroupedEnvironment::Environment(environment.clone());
-                let platform = environment.best_platform();
-                let virtual_packages = environment.virtual_packages(platform);
-
-                let conda_prefix_updater = CondaPrefixUpdater::builder(
-                    group,
-                    platform,
-                    virtual_packages
-                        .into_iter()
-                        .map(GenericVirtualPackage::from)
-                        .collect(),
-                    self.command_dispatcher.clone(),
-                )
-                .finish()?;
-
-                // Get the locked environment from the lock-file.
-                let locked_env = self.locked_env(environment)?;
-                let packages = locked_env.packages(platform);
-                let packages = if let Some(iter) = packages {
-                    iter.collect_vec()
-                } else {
-                    Vec::new()
-                };
-                let records = locked_packages_to_pixi_records(packages, self.workspace.root())?;
-
-                // Update the conda prefix
-                let CondaPrefixUpdated {
-                    prefix,
-                    python_status,
-                    ..
-                } = conda_prefix_updater
-                    .update(records, reinstall_packages, ignore_packages)
-                    .await?;
-
-                Ok((prefix.clone(), *python_status.clone()))
-            })
+            .get_or_try_init(self.install_conda_prefix(
+                environment,
+                None,
+                reinstall_packages,
+                ignore_packages,
+            ))
             .await
             .map(|(prefix, python_status)| (prefix.clone(), python_status.clone()))
     }
+
+    /// Installs the conda packages of the environment into `target`, or into
+    /// the environment directory if no target is given.
+    async fn install_conda_prefix(
+        &self,
+        environment: &Environment<'p>,
+        target: Option<InstallTarget<'_>>,
+        reinstall_packages: Option<HashSet<PackageName>>,
+        ignore_packages: Option<HashSet<PackageName>>,
+    ) -> miette::Result<(Prefix, PythonStatus)> {
+        // Create object to update the prefix
+        let group = GroupedEnvironment::Environment(environment.clone());
+        let platform = target.map_or_else(|| environment.best_platform(), |target| target.platform);
+        let virtual_packages = environment.virtual_packages(platform);
+
+        // The post-link scripts of packages for another platform cannot be
+        // executed on this machine.
+        let command_dispatcher = if platform == environment.best_platform() {
+            self.command_dispatcher.clone()
+        } else {
+            self.workspace
+                .command_dispatcher_builder()?
+                .execute_link_scripts(false)
+                .finish()
+        };
+
+        let mut builder = CondaPrefixUpdater::builder(
+            group,
+            platform,
+            virtual_packages
+                .into_iter()
+                .map(GenericVirtualPackage::from)
+                .collect(),
+            command_dispatcher,
+        );
+        if let Some(target) = target {
+            builder = builder.with_prefix(target.prefix.clone());
+        }
+        let conda_prefix_updater = builder.finish()?;
+
+        // Get the locked environment from the lock-file.
+        let locked_env = self.locked_env(environment)?;
+        let packages = locked_env.packages(platform);
+        let packages = if let Some(iter) = packages {
+            iter.collect_vec()
+        } else {
+            Vec::new()
+        };
+        let records = locked_packages_to_pixi_records(packages, self.workspace.root())?;
+
+        // Verify the signatures of the packages before they are installed
+        signatures::verify_conda_packages(
+            self.workspace.config(),
+            self.workspace.authenticated_client()?,
+            &records,
+        )
+        .await?;
+
+        // Update the conda prefix
+        let CondaPrefixUpdated {
+            prefix,
+            python_status,
+            package_messages,
+            ..
+        } = conda_prefix_updater
+            .update(records, reinstall_packages, ignore_packages)
+            .await?;
+        if !package_messages.is_empty() {
+            self.package_messages
+                .insert(environment.name().clone(), package_messages.clone());
+        }
+
+        Ok((prefix.clone(), *python_status.clone()))
+    }
+}
+
+/// A prefix outside of the workspace to install an environment into.
+#[derive(Clone, Copy)]
+struct InstallTarget<'a> {
+    prefix: &'a Prefix,
+    /// The platform of the packages to install.
+    platform: Platform,
 }
 
 /// A trait to lazily evaluate the environment variables for a given pixi environment.
@@ -992,6 +1204,10 @@ pub struct UpdateContext<'p> {
     /// partially missing then the data also won't exist in this field.
     locked_pypi_records: PerEnvironmentAndPlatform<'p, Arc<PypiRecordsByName>>,
 
+    /// Versions of PyPI packages that are preferred when they are not locked
+    /// yet, e.g. the versions from an imported `uv.lock`.
+    preferred_pypi_versions: Arc<HashMap<pep508_rs::PackageName, pep440_rs::Version>>,
+
     /// Information about environments that are considered out of date. Only
     /// these environments are updated.
     outdated_envs: OutdatedEnvironments<'p>,
@@ -1175,23 +1391,36 @@ impl<'p> UpdateContext<'p> {
     }
 }
 
-/// If the project has any source dependencies, like `git` or `path`
-/// dependencies. for pypi dependencies, we need to limit the solve to 1,
-/// because of uv internals
-fn determine_pypi_solve_permits(project: &Workspace) -> usize {
-    // Get all environments
-    let environments = project.environments();
-    for environment in environments {
-        for (_, req) in environment.pypi_dependencies(None).iter() {
-            for dep in req {
-                if dep.is_direct_dependency() {
-                    return 1;
-                }
-            }
-        }
+/// If any of the environments that need to be re-solved has source
+/// dependencies, like `git` or `path` dependencies, for pypi dependencies, we
+/// need to limit the solve to 1, because of uv internals. Environments that
+/// are up-to-date are not solved, so they do not restrict the concurrency.
+fn determine_pypi_solve_permits(
+    outdated: &OutdatedEnvironments<'_>,
+    max_concurrent_solves: usize,
+) -> usize {
+    let has_direct_dependencies = outdated
+        .pypi
+        .keys()
+        .flat_map(|environment| {
+            GroupedEnvironment::from(environment.clone())
+                .environments()
+                .collect_vec()
+        })
+        .any(|environment| {
+            environment
+                .pypi_dependencies(None)
+                .iter()
+                .flat_map(|(_, reqs)| reqs)
+                .any(|dep| dep.is_direct_dependency())
+        });
+
+    if has_direct_dependencies {
+        1
+    } else {
+        // If no source dependencies are found, we can use the default concurrency
+        max_concurrent_solves.max(1)
     }
-    // If no source dependencies are found, we can use the default concurrency
-    project.config().max_concurrent_solves()
 }
 
 pub struct UpdateContextBuilder<'p> {
@@ -1228,6 +1457,12 @@ pub struct UpdateContextBuilder<'p> {
 
     /// Optional list of package names explicitly targeted for update.
     update_targets: Option<std::collections::HashSet<String>>,
+
+    /// The maximum number of pypi solves that are allowed to run concurrently.
+    max_concurrent_solves: Option<usize>,
+
+    /// Versions of PyPI packages that are preferred when they are not locked.
+    preferred_pypi_versions: HashMap<pep508_rs::PackageName, pep440_rs::Version>,
 }
 
 impl<'p> UpdateContextBuilder<'p> {
@@ -1291,6 +1526,28 @@ impl<'p> UpdateContextBuilder<'p> {
         }
     }
 
+    /// Sets the maximum number of pypi solves that are allowed to run
+    /// concurrently. Defaults to the `max-concurrent-solves` configuration.
+    pub fn with_max_concurrent_solves(self, max_concurrent_solves: usize) -> Self {
+        Self {
+            max_concurrent_solves: Some(max_concurrent_solves),
+            ..self
+        }
+    }
+
+    /// Sets the versions of PyPI packages that are preferred when the packages
+    /// are not locked yet. Unlike locked packages, these versions are not
+    /// checked against the lock-file and only guide the resolution.
+    pub fn with_preferred_pypi_versions(
+        self,
+        preferred_pypi_versions: HashMap<pep508_rs::PackageName, pep440_rs::Version>,
+    ) -> Self {
+        Self {
+            preferred_pypi_versions,
+            ..self
+        }
+    }
+
     /// Sets the io concurrency semaphore to use when updating environments.
     #[allow(unused)]
     pub fn with_io_concurrency_semaphore(self, io_concurrency_limit: IoConcurrencyLimit) -> Self {
@@ -1322,6 +1579,11 @@ impl<'p> UpdateContextBuilder<'p> {
                 .await
             }
         };
+        let pypi_solve_permits = determine_pypi_solve_permits(
+            &outdated,
+            self.max_concurrent_solves
+                .unwrap_or_else(|| project.config().max_concurrent_solves()),
+        );
 
         // Extract the current conda records from the lock-file
         // TODO: Should we parallelize this? Measure please.
@@ -1507,6 +1769,7 @@ impl<'p> UpdateContextBuilder<'p> {
             locked_grouped_repodata_records,
             locked_grouped_pypi_records,
             locked_pypi_records,
+            preferred_pypi_versions: Arc::new(self.preferred_pypi_versions),
             outdated_envs: outdated,
 
             solved_repodata_records: HashMap::new(),
@@ -1517,7 +1780,7 @@ impl<'p> UpdateContextBuilder<'p> {
 
             mapping_client,
             package_cache,
-            pypi_solve_semaphore: Arc::new(Semaphore::new(determine_pypi_solve_permits(project))),
+            pypi_solve_semaphore: Arc::new(Semaphore::new(pypi_solve_permits)),
             io_concurrency_limit: self.io_concurrency_limit.unwrap_or_default(),
             command_dispatcher,
             glob_hash_cache,
@@ -1543,6 +1806,8 @@ impl<'p> UpdateContext<'p> {
             mapping_client: None,
             command_dispatcher: None,
             update_targets: None,
+            max_concurrent_solves: None,
+            preferred_pypi_versions: HashMap::new(),
         }
     }
 
@@ -1570,6 +1835,9 @@ impl<'p> UpdateContext<'p> {
         // until all pending tasks have either completed or errored.
         let mut pending_futures = FuturesUnordered::new();
 
+        // Keeps track of how many of the spawned solves have finished.
+        let mut solve_progress = SolveProgress::default();
+
         // Spawn tasks for all the conda targets that are out of date.
         for (environment, platforms) in self.outdated_envs.conda.iter() {
             // Turn the platforms into an IndexSet, so we have a little control over the
@@ -1654,6 +1922,7 @@ impl<'p> UpdateContext<'p> {
 
                 // Store the task so we can poll it later.
                 pending_futures.push(group_solve_task);
+                solve_progress.conda_total += 1;
 
                 // Create an entry that can be used by other tasks to wait for the result.
                 let previous_cell = self
@@ -1760,10 +2029,12 @@ impl<'p> UpdateContext<'p> {
                 self.pypi_solve_semaphore.clone(),
                 project.root().to_path_buf(),
                 locked_group_records,
+                self.preferred_pypi_versions.clone(),
                 self.no_install,
             );
 
             pending_futures.push(pypi_solve_future.boxed_local());
+            solve_progress.pypi_total += 1;
 
             let previous_cell = self
                 .grouped_solved_pypi_records
@@ -1841,6 +2112,7 @@ impl<'p> UpdateContext<'p> {
         top_level_progress.enable_steady_tick(Duration::from_millis(50));
         top_level_progress.set_prefix("updating lock-file");
         top_level_progress.set_length(pending_futures.len() as u64);
+        top_level_progress.set_message(solve_progress.message());
 
         // Iterate over all the futures we spawned and wait for them to complete.
         //
@@ -1862,6 +2134,9 @@ impl<'p> UpdateContext<'p> {
             top_level_progress.inc(1);
             match result? {
                 TaskResult::CondaGroupSolved(group_name, platform, records, duration) => {
+                    solve_progress.conda_finished += 1;
+                    top_level_progress.set_message(solve_progress.message());
+
                     let group = GroupedEnvironment::from_name(project, &group_name)
                         .expect("group should exist");
 
@@ -1899,6 +2174,9 @@ impl<'p> UpdateContext<'p> {
                     duration,
                     conda_prefix,
                 ) => {
+                    solve_progress.pypi_finished += 1;
+                    top_level_progress.set_message(solve_progress.message());
+
                     let group = GroupedEnvironment::from_name(project, &group_name)
                         .expect("group should exist");
 
@@ -2068,6 +2346,7 @@ impl<'p> UpdateContext<'p> {
                 .collect(),
             package_cache: self.package_cache,
             updated_pypi_prefixes: Default::default(),
+            package_messages: Default::default(),
             uv_context,
             io_concurrency_limit: self.io_concurrency_limit,
             command_dispatcher: self.command_dispatcher,
@@ -2151,6 +2430,34 @@ pub enum TaskResult {
     ),
 }
 
+/// Aggregated progress of the conda and pypi solves that are performed
+/// concurrently during a lock-file update.
+#[derive(Debug, Default)]
+struct SolveProgress {
+    conda_total: usize,
+    conda_finished: usize,
+    pypi_total: usize,
+    pypi_finished: usize,
+}
+
+impl SolveProgress {
+    /// Returns a message that summarizes the progress, e.g. `conda 3/8, pypi
+    /// 1/4`.
+    fn message(&self) -> String {
+        let mut parts = Vec::new();
+        if self.conda_total > 0 {
+            parts.push(format!(
+                "conda {}/{}",
+                self.conda_finished, self.conda_total
+            ));
+        }
+        if self.pypi_total > 0 {
+            parts.push(format!("pypi {}/{}", self.pypi_finished, self.pypi_total));
+        }
+        parts.join(", ")
+    }
+}
+
 /// A task that solves the conda dependencies for a given environment.
 #[allow(clippy::too_many_arguments)]
 async fn spawn_solve_conda_environment_task(
@@ -2197,6 +2504,10 @@ async fn spawn_solve_conda_environment_task(
         .pypi_name_mapping_source()
         .map_err(|err| SolveCondaEnvironmentError::PypiMappingFailed(err.into()))?
         .clone();
+    let pypi_name_mapping_overrides = group
+        .workspace()
+        .pypi_name_mapping_overrides()
+        .map_err(|err| SolveCondaEnvironmentError::PypiMappingFailed(err.into()))?;
 
     // Get the channel configuration
     let channel_config = group.workspace().channel_config();
@@ -2214,6 +2525,12 @@ async fn spawn_solve_conda_environment_task(
         variant_files,
     } = group.workspace().variants(platform)?;
 
+    // The requirements from the manifest, used to explain conflicts.
+    let requirements = dependencies
+        .names()
+        .map(|name| name.as_normalized().to_string())
+        .collect::<HashSet<_>>();
+
     let start = Instant::now();
 
     // Solve the environment using the command dispatcher.
@@ -2236,10 +2553,21 @@ async fn spawn_solve_conda_environment_task(
             preferred_build_source: pin_overrides,
         })
         .await
-        .map_err(|source| SolveCondaEnvironmentError::SolveFailed {
-            environment_name: group_name.clone(),
-            platform,
-            source: Box::new(source),
+        .map_err(|source| {
+            match SolveConflictError::from_solve_error(
+                group_name.clone(),
+                platform,
+                &requirements,
+                &group.workspace().workspace.provenance,
+                Box::new(source),
+            ) {
+                Ok(conflict) => SolveCondaEnvironmentError::Conflict(Box::new(conflict)),
+                Err(source) => SolveCondaEnvironmentError::SolveFailed {
+                    environment_name: group_name.clone(),
+                    platform,
+                    source,
+                },
+            }
         })?;
 
     // Add purl's for the conda packages that are also available as pypi packages if
@@ -2247,8 +2575,9 @@ async fn spawn_solve_conda_environment_task(
     if has_pypi_dependencies {
         // TODO: Bring back the pypi mapping reporter
         mapping_client
-            .amend_purls(
+            .amend_purls_with_overrides(
                 &pypi_name_mapping_location,
+                pypi_name_mapping_overrides.as_deref(),
                 records.iter_mut().filter_map(PixiRecord::as_binary_mut),
                 None,
             )
@@ -2461,6 +2790,7 @@ async fn spawn_solve_pypi_task<'p>(
     semaphore: Arc<Semaphore>,
     project_root: PathBuf,
     locked_pypi_packages: Arc<PypiRecordsByName>,
+    preferred_pypi_versions: Arc<HashMap<pep508_rs::PackageName, pep440_rs::Version>>,
     disallow_install_conda_prefix: bool,
 ) -> miette::Result<TaskResult> {
     // Get the Pypi dependencies for this environment
@@ -2529,6 +2859,7 @@ async fn spawn_solve_pypi_task<'p>(
             system_requirements,
             pixi_solve_records,
             locked_pypi_records,
+            &preferred_pypi_versions,
             platform,
             &pb.pb,
             &project_root,
@@ -2573,3 +2904,75 @@ async fn spawn_solve_pypi_task<'p>(
         prefix_task_result,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn outdated_pypi<'p>(
+        environments: impl IntoIterator<Item = Environment<'p>>,
+    ) -> OutdatedEnvironments<'p> {
+        OutdatedEnvironments {
+            conda: HashMap::new(),
+            pypi: environments
+                .into_iter()
+                .map(|environment| (environment, HashSet::from([Platform::Linux64])))
+                .collect(),
+            disregard_locked_content: Default::default(),
+            reasons: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pypi_solve_permits_only_consider_outdated_environments() {
+        let workspace = Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["linux-64"]
+
+        [dependencies]
+        python = "*"
+
+        [pypi-dependencies]
+        requests = "*"
+
+        [feature.local.pypi-dependencies]
+        local = { path = "./local" }
+
+        [environments]
+        local = ["local"]
+        "#,
+        )
+        .unwrap();
+        let default = workspace.default_environment();
+        let local = workspace.environment("local").unwrap();
+
+        // Up-to-date environments with source dependencies don't restrict the
+        // concurrency.
+        assert_eq!(
+            determine_pypi_solve_permits(&outdated_pypi([default]), 4),
+            4
+        );
+        assert_eq!(determine_pypi_solve_permits(&outdated_pypi([local]), 4), 1);
+        assert_eq!(determine_pypi_solve_permits(&outdated_pypi([]), 0), 1);
+    }
+
+    #[test]
+    fn test_solve_progress_message() {
+        let mut progress = SolveProgress::default();
+        assert_eq!(progress.message(), "");
+
+        progress.conda_total = 8;
+        progress.conda_finished = 3;
+        assert_eq!(progress.message(), "conda 3/8");
+
+        progress.pypi_total = 4;
+        progress.pypi_finished = 1;
+        assert_eq!(progress.message(), "conda 3/8, pypi 1/4");
+    }
+}
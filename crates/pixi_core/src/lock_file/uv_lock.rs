@@ -0,0 +1,156 @@
+//! Reading the resolution of a `uv.lock` file.
+//!
+//! The versions that uv resolved are used as preferences when the PyPI
+//! dependencies of a workspace are resolved, so migrating a project from uv
+//! to pixi does not change the versions of its PyPI packages unless that is
+//! required by the specs.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use miette::Diagnostic;
+use pep440_rs::Version;
+use pep508_rs::PackageName;
+use thiserror::Error;
+use toml_edit::{DocumentMut, Item};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum UvLockError {
+    #[error("failed to read '{}'", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error("failed to parse '{}'", .0.display())]
+    Parse(PathBuf, #[source] toml_edit::TomlError),
+}
+
+/// The PyPI packages that are locked in a `uv.lock` file.
+#[derive(Debug, Clone, Default)]
+pub struct UvLock {
+    versions: HashMap<PackageName, Version>,
+}
+
+impl UvLock {
+    /// Reads a `uv.lock` file.
+    pub fn from_path(path: &Path) -> Result<Self, UvLockError> {
+        let contents =
+            fs_err::read_to_string(path).map_err(|err| UvLockError::Io(path.to_path_buf(), err))?;
+        contents
+            .parse()
+            .map_err(|err| UvLockError::Parse(path.to_path_buf(), err))
+    }
+
+    /// Returns the locked version of every package that is taken from a
+    /// registry.
+    ///
+    /// Packages from git, paths and the project itself are not included,
+    /// their version says nothing about their contents. If a package is
+    /// locked with different versions for different markers, the first one
+    /// is used.
+    pub fn versions(&self) -> &HashMap<PackageName, Version> {
+        &self.versions
+    }
+
+    /// Consumes the lock and returns the versions of its packages, see
+    /// [`Self::versions`].
+    pub fn into_versions(self) -> HashMap<PackageName, Version> {
+        self.versions
+    }
+}
+
+impl FromStr for UvLock {
+    type Err = toml_edit::TomlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let document = DocumentMut::from_str(s)?;
+        let packages = document
+            .get("package")
+            .and_then(Item::as_array_of_tables)
+            .into_iter()
+            .flat_map(|packages| packages.iter());
+
+        let mut versions = HashMap::new();
+        for package in packages {
+            let from_registry = package
+                .get("source")
+                .and_then(Item::as_table_like)
+                .is_some_and(|source| source.contains_key("registry"));
+            if !from_registry {
+                continue;
+            }
+            let name = package
+                .get("name")
+                .and_then(Item::as_str)
+                .and_then(|name| PackageName::from_str(name).ok());
+            let version = package
+                .get("version")
+                .and_then(Item::as_str)
+                .and_then(|version| Version::from_str(version).ok());
+            if let (Some(name), Some(version)) = (name, version) {
+                versions.entry(name).or_insert(version);
+            }
+        }
+
+        Ok(Self { versions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_uv_lock() {
+        let lock = UvLock::from_str(
+            r#"
+version = 1
+requires-python = ">=3.12"
+
+[[package]]
+name = "my-project"
+version = "0.1.0"
+source = { editable = "." }
+dependencies = [{ name = "requests" }]
+
+[[package]]
+name = "Requests"
+version = "2.32.3"
+source = { registry = "https://pypi.org/simple" }
+
+[[package]]
+name = "numpy"
+version = "2.1.0"
+source = { registry = "https://pypi.org/simple" }
+resolution-markers = ["python_full_version >= '3.13'"]
+
+[[package]]
+name = "numpy"
+version = "2.0.2"
+source = { registry = "https://pypi.org/simple" }
+resolution-markers = ["python_full_version < '3.13'"]
+
+[[package]]
+name = "flask"
+version = "3.1.0"
+source = { git = "https://github.com/pallets/flask?rev=main#abc" }
+"#,
+        )
+        .unwrap();
+
+        let mut versions = lock
+            .versions()
+            .iter()
+            .map(|(name, version)| (name.to_string(), version.to_string()))
+            .collect::<Vec<_>>();
+        versions.sort();
+        assert_eq!(
+            versions,
+            vec![
+                (String::from("numpy"), String::from("2.1.0")),
+                (String::from("requests"), String::from("2.32.3")),
+            ]
+        );
+    }
+}
@@ -113,10 +113,42 @@ pub fn shell_prompt(shell: &ShellEnum, prompt_name: &str) -> String {
     }
 }
 
-/// Returns prompt name for given project and environment
-pub fn prompt_name(project_name: &str, environment_name: &EnvironmentName) -> String {
+/// Returns prompt name for given project and environment. If a template is
+/// given, its `{workspace}` and `{environment}` placeholders are replaced with
+/// the names of the project and the environment.
+pub fn prompt_name(
+    project_name: &str,
+    environment_name: &EnvironmentName,
+    template: Option<&str>,
+) -> String {
+    if let Some(template) = template {
+        return template
+            .replace("{workspace}", project_name)
+            .replace("{environment}", environment_name.as_str());
+    }
     match environment_name {
         EnvironmentName::Default => project_name.to_string(),
         EnvironmentName::Named(name) => format!("{project_name}:{name}"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_name() {
+        let test = EnvironmentName::Named(String::from("test"));
+        assert_eq!(prompt_name("foo", &EnvironmentName::Default, None), "foo");
+        assert_eq!(prompt_name("foo", &test, None), "foo:test");
+
+        // The placeholders of the template are replaced with the names.
+        let template = Some("{environment}@{workspace}");
+        assert_eq!(
+            prompt_name("foo", &EnvironmentName::Default, template),
+            "default@foo"
+        );
+        assert_eq!(prompt_name("foo", &test, template), "test@foo");
+        assert_eq!(prompt_name("foo", &test, Some("pixi")), "pixi");
+    }
+}
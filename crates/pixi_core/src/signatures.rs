@@ -0,0 +1,1234 @@
+//! Verification of package signatures before packages are installed.
+//!
+//! Signatures are only verified when `verify-signatures` is enabled in the
+//! configuration.
+//!
+//! Conda packages are verified with conda content trust. The `key_mgr.json`
+//! of a channel is verified with the `root.json` that is configured for the
+//! channel in `trust-roots`, and the signatures in the `repodata.json` of the
+//! channel are verified with the package keys that it delegates to. The
+//! verified repodata entry must match the digest of the locked package.
+//!
+//! PyPI wheels are verified with their PEP 740 provenance. The attestations in
+//! the provenance are verified against the sigstore trust root: the signing
+//! certificate has to be issued by Fulcio to the trusted publisher of the
+//! wheel, the signature has to be logged in Rekor, and the signed statement
+//! has to cover the locked digest of the wheel.
+//!
+//! The documents that are fetched for the verification are cached with HTTP
+//! caching semantics, so that installing a prefix again does not download them
+//! again. The `repodata.json` that rattler cached while solving is read from
+//! its cache instead of being downloaded again.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write},
+    marker::PhantomData,
+    path::PathBuf,
+};
+
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use http_cache_reqwest::{CACacheManager, Cache, CacheMode, HttpCache, HttpCacheOptions};
+use itertools::Itertools;
+use miette::Diagnostic;
+use pixi_config::{Config, VerifySignatures};
+use pixi_consts::consts;
+use pixi_record::PixiRecord;
+use rattler_conda_types::RepoDataRecord;
+use rattler_digest::{Sha256, compute_bytes_digest};
+use rattler_lock::PypiPackageData;
+use rattler_networking::LazyClient;
+use reqwest_middleware::ClientBuilder;
+use serde::{
+    Deserialize, Deserializer,
+    de::{DeserializeSeed, IgnoredAny, MapAccess, Visitor},
+};
+use serde_json::Value;
+use sigstore::{
+    crypto::{CosignVerificationKey, Signature as SigstoreSignature},
+    trust::{TrustRoot, sigstore::SigstoreTrustRoot},
+};
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use url::Url;
+use x509_cert::{
+    Certificate,
+    der::{Decode, Encode},
+    ext::pkix::{SubjectAltName, name::GeneralName},
+};
+
+/// The base URL of the PyPI integrity API that serves the provenance of
+/// files.
+const PYPI_INTEGRITY_URL: &str = "https://pypi.org/integrity";
+
+/// The host that serves the files uploaded to PyPI.
+const PYPI_FILES_HOST: &str = "files.pythonhosted.org";
+
+/// The payload type of the DSSE envelopes of PEP 740 attestations.
+const IN_TOTO_PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum SignatureError {
+    #[error("no trust root is configured for the channel '{0}'")]
+    #[diagnostic(help("add the `root.json` of the channel to `trust-roots` in the configuration"))]
+    NoTrustRoot(Url),
+
+    #[error("failed to read the trust root '{}'", .0.display())]
+    ReadTrustRoot(PathBuf, #[source] std::io::Error),
+
+    #[error("failed to fetch '{0}'")]
+    Fetch(Url, #[source] BoxedError),
+
+    #[error("the {0} metadata is invalid")]
+    InvalidMetadata(&'static str, #[source] serde_json::Error),
+
+    #[error("the {role} metadata is not signed by {threshold} trusted key(s)")]
+    Threshold {
+        role: &'static str,
+        threshold: usize,
+    },
+
+    #[error("the {0} metadata expired at {1}")]
+    Expired(&'static str, DateTime<Utc>),
+
+    #[error("the channel '{0}' does not provide a `key_mgr.json`")]
+    MissingKeyManager(Url),
+
+    #[error("the {0} metadata does not delegate to any {1} keys")]
+    MissingDelegation(&'static str, &'static str),
+
+    #[error("'{0}' is not in the repodata of its channel")]
+    NotInRepodata(String),
+
+    #[error("'{0}' is not signed")]
+    Unsigned(String),
+
+    #[error("the signature of '{0}' is invalid")]
+    InvalidSignature(String),
+
+    #[error("'{0}' does not match the signed entry in the repodata of its channel")]
+    DigestMismatch(String),
+
+    #[error("'{0}' is not a wheel from PyPI, its provenance cannot be verified")]
+    UnsupportedSource(String),
+
+    #[error("PyPI does not provide a provenance for '{0}'")]
+    NoProvenance(String),
+
+    #[error("the attestations of '{0}' do not cover the locked wheel")]
+    AttestationMismatch(String),
+
+    #[error("failed to load the sigstore trust root")]
+    TrustRoot(#[source] BoxedError),
+
+    #[error("the attestation of '{0}' is invalid, {1}")]
+    InvalidAttestation(String, &'static str),
+}
+
+/// Metadata that is signed with conda content trust.
+#[derive(Debug, Deserialize)]
+struct Signable {
+    signatures: HashMap<String, SignatureEntry>,
+    signed: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignatureEntry {
+    signature: String,
+}
+
+/// The signed part of a `root.json` or `key_mgr.json`.
+#[derive(Debug, Deserialize)]
+struct SignedMetadata {
+    expiration: DateTime<Utc>,
+    delegations: HashMap<String, Delegation>,
+}
+
+/// The keys that are trusted for a role and the number of them that have to
+/// sign metadata of that role.
+#[derive(Debug, Clone, Deserialize)]
+struct Delegation {
+    pubkeys: Vec<String>,
+    threshold: usize,
+}
+
+/// Verifies the signatures of the conda packages that are about to be
+/// installed. Depending on the configuration, packages that cannot be verified
+/// result in warnings or in an error.
+pub(crate) async fn verify_conda_packages(
+    config: &Config,
+    client: &LazyClient,
+    records: &[PixiRecord],
+) -> miette::Result<()> {
+    let mode = config.verify_signatures();
+    if mode == VerifySignatures::Off {
+        return Ok(());
+    }
+
+    let client = cached_client(client);
+    let client = &client;
+    let mut failures = Vec::new();
+    let by_channel = records
+        .iter()
+        .filter_map(PixiRecord::as_binary)
+        .into_group_map_by(|record| record.url.join("../").ok());
+    for (channel, records) in by_channel {
+        let Some(channel) = channel else {
+            failures.extend(
+                records
+                    .iter()
+                    .map(|record| SignatureError::NotInRepodata(record.file_name.clone())),
+            );
+            continue;
+        };
+
+        let package_keys = match load_package_keys(config, client, &channel).await {
+            Ok(keys) => keys,
+            Err(err) => {
+                failures.push(err);
+                continue;
+            }
+        };
+
+        let by_subdir = records
+            .into_iter()
+            .into_group_map_by(|record| record.package_record.subdir.clone());
+        for (subdir, records) in by_subdir {
+            let file_names = records
+                .iter()
+                .map(|record| record.file_name.as_str())
+                .collect();
+            let entries = match channel.join(&format!("{subdir}/repodata.json")) {
+                Ok(url) => load_signed_entries(client, &url, &file_names).await,
+                Err(err) => Err(SignatureError::Fetch(channel.clone(), err.into())),
+            };
+            let entries = match entries {
+                Ok(Some(entries)) => entries,
+                Ok(None) => {
+                    failures.extend(
+                        records
+                            .iter()
+                            .map(|record| SignatureError::NotInRepodata(record.file_name.clone())),
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    failures.push(err);
+                    continue;
+                }
+            };
+
+            failures.extend(
+                records
+                    .into_iter()
+                    .filter_map(|record| verify_record(&entries, &package_keys, record).err()),
+            );
+        }
+    }
+
+    report(mode, failures)
+}
+
+/// Verifies the provenance of the PyPI wheels that are about to be installed.
+/// Packages from a local path are not verified.
+pub(crate) async fn verify_pypi_packages<'a>(
+    config: &Config,
+    client: &LazyClient,
+    packages: impl IntoIterator<Item = &'a PypiPackageData>,
+) -> miette::Result<()> {
+    let mode = config.verify_signatures();
+    if mode == VerifySignatures::Off {
+        return Ok(());
+    }
+
+    let client = cached_client(client);
+    let trust_root = OnceCell::new();
+    let mut failures = Vec::new();
+    for package in packages {
+        let Some(url) = package.location.as_url() else {
+            continue;
+        };
+        if let Err(err) = verify_wheel(&client, &trust_root, package, url).await {
+            failures.push(err);
+        }
+    }
+
+    report(mode, failures)
+}
+
+/// Turns the verification failures into warnings or an error.
+fn report(mode: VerifySignatures, failures: Vec<SignatureError>) -> miette::Result<()> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    let messages = failures
+        .iter()
+        .map(ToString::to_string)
+        .unique()
+        .collect_vec();
+    if mode == VerifySignatures::Error {
+        return Err(miette::miette!(
+            help = "set `verify-signatures` to `warn` to install packages that cannot be verified",
+            "failed to verify the signatures of the packages:\n{}",
+            messages
+                .iter()
+                .format_with("\n", |message, f| f(&format_args!("  - {message}")))
+        ));
+    }
+
+    for message in messages {
+        tracing::warn!("{message}");
+    }
+    Ok(())
+}
+
+/// Wraps the client with a cache for the documents that are fetched to verify
+/// packages. The `repodata.json` of a channel is only downloaded again when it
+/// changed, and the provenance of a wheel only once.
+fn cached_client(client: &LazyClient) -> LazyClient {
+    let cache_path = pixi_config::get_cache_dir()
+        .ok()
+        .map(|cache_dir| cache_dir.join(consts::SIGNATURES_CACHE_DIR));
+    let client = client.clone();
+    LazyClient::new(move || {
+        let builder = ClientBuilder::from_client(client.client().clone());
+        match &cache_path {
+            Some(cache_path) => builder
+                .with(Cache(HttpCache {
+                    mode: CacheMode::Default,
+                    manager: CACacheManager {
+                        path: cache_path.clone(),
+                        remove_opts: Default::default(),
+                    },
+                    options: HttpCacheOptions::default(),
+                }))
+                .build(),
+            None => builder.build(),
+        }
+    })
+}
+
+/// Fetches a JSON document, returns `None` if it does not exist.
+async fn fetch_json(
+    client: &LazyClient,
+    url: &Url,
+    accept: Option<&str>,
+) -> Result<Option<Value>, SignatureError> {
+    let Some(bytes) = fetch_bytes(client, url, accept).await? else {
+        return Ok(None);
+    };
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))
+}
+
+/// Fetches a document, returns `None` if it does not exist.
+async fn fetch_bytes(
+    client: &LazyClient,
+    url: &Url,
+    accept: Option<&str>,
+) -> Result<Option<Vec<u8>>, SignatureError> {
+    let mut request = client.client().get(url.clone());
+    if let Some(accept) = accept {
+        request = request.header("Accept", accept);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))?;
+    if response.status().as_u16() == 404 {
+        return Ok(None);
+    }
+    response
+        .error_for_status()
+        .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))?
+        .bytes()
+        .await
+        .map(|bytes| Some(bytes.to_vec()))
+        .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))
+}
+
+/// Reads the entries and signatures of the given packages from a
+/// `repodata.json`. The copy that rattler cached while solving is used when
+/// there is one, otherwise the repodata is fetched. Returns `None` if the
+/// repodata does not exist.
+async fn load_signed_entries(
+    client: &LazyClient,
+    url: &Url,
+    file_names: &HashSet<&str>,
+) -> Result<Option<SignedEntries>, SignatureError> {
+    let bytes = match cached_repodata(url) {
+        Some(bytes) => bytes,
+        None => match fetch_bytes(client, url, None).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        },
+    };
+    SignedEntries::from_slice(&bytes, file_names)
+        .map(Some)
+        .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))
+}
+
+/// Returns the contents of the `repodata.json` at the url that rattler cached,
+/// if there is one. Sharded repodata is not cached as a whole.
+fn cached_repodata(url: &Url) -> Option<Vec<u8>> {
+    let cache_dir = pixi_config::get_cache_dir()
+        .ok()?
+        .join(consts::CONDA_REPODATA_CACHE_DIR);
+    fs_err::read_dir(&cache_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(".info.json"))
+        .find(|path| {
+            fs_err::read_to_string(path)
+                .ok()
+                .and_then(|contents| serde_json::from_str::<CachedRepodataState>(&contents).ok())
+                .is_some_and(|state| state.url == url.as_str())
+        })
+        .and_then(|path| {
+            let path = path.to_string_lossy();
+            fs_err::read(path.trim_end_matches(".info.json").to_string() + ".json").ok()
+        })
+}
+
+/// The part of the repodata cache state written by rattler that identifies
+/// the cached repodata.
+#[derive(Deserialize)]
+struct CachedRepodataState {
+    url: String,
+}
+
+/// The entries and signatures of the packages that are verified, read from a
+/// `repodata.json` without keeping the other packages in memory.
+#[derive(Debug, Default)]
+struct SignedEntries {
+    packages: HashMap<String, Value>,
+    signatures: HashMap<String, HashMap<String, SignatureEntry>>,
+}
+
+impl SignedEntries {
+    fn from_slice(bytes: &[u8], file_names: &HashSet<&str>) -> serde_json::Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        let entries = SignedEntriesSeed(file_names).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(entries)
+    }
+}
+
+/// Deserializes the [`SignedEntries`] of the given file names.
+struct SignedEntriesSeed<'a>(&'a HashSet<&'a str>);
+
+impl<'de> DeserializeSeed<'de> for SignedEntriesSeed<'_> {
+    type Value = SignedEntries;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SignedEntriesSeed<'_> {
+    type Value = SignedEntries;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a repodata.json")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = SignedEntries::default();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "packages" | "packages.conda" => entries
+                    .packages
+                    .extend(map.next_value_seed(FilteredMap::new(self.0))?),
+                "signatures" => entries
+                    .signatures
+                    .extend(map.next_value_seed(FilteredMap::new(self.0))?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Deserializes the entries of a map with one of the given keys and skips the
+/// others.
+struct FilteredMap<'a, T> {
+    keys: &'a HashSet<&'a str>,
+    value: PhantomData<T>,
+}
+
+impl<'a, T> FilteredMap<'a, T> {
+    fn new(keys: &'a HashSet<&'a str>) -> Self {
+        Self {
+            keys,
+            value: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for FilteredMap<'_, T> {
+    type Value = HashMap<String, T>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for FilteredMap<'_, T> {
+    type Value = HashMap<String, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = HashMap::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if self.keys.contains(key.as_str()) {
+                let value = map.next_value()?;
+                entries.insert(key, value);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Returns the keys that are trusted to sign the packages of the channel.
+async fn load_package_keys(
+    config: &Config,
+    client: &LazyClient,
+    channel: &Url,
+) -> Result<Delegation, SignatureError> {
+    let root_path = config
+        .trust_root(channel)
+        .ok_or_else(|| SignatureError::NoTrustRoot(channel.clone()))?;
+    let root = fs_err::read_to_string(root_path)
+        .map_err(|err| SignatureError::ReadTrustRoot(root_path.clone(), err))?;
+    let root: Signable =
+        serde_json::from_str(&root).map_err(|err| SignatureError::InvalidMetadata("root", err))?;
+    let root_metadata = verify_metadata("root", &root, None)?;
+
+    let key_mgr_url = channel
+        .join("key_mgr.json")
+        .map_err(|err| SignatureError::Fetch(channel.clone(), err.into()))?;
+    let key_mgr = fetch_json(client, &key_mgr_url, None)
+        .await?
+        .ok_or_else(|| SignatureError::MissingKeyManager(channel.clone()))?;
+    let key_mgr: Signable = serde_json::from_value(key_mgr)
+        .map_err(|err| SignatureError::InvalidMetadata("key_mgr", err))?;
+    let key_mgr_keys = root_metadata
+        .delegations
+        .get("key_mgr")
+        .ok_or(SignatureError::MissingDelegation("root", "key_mgr"))?;
+    let key_mgr_metadata = verify_metadata("key_mgr", &key_mgr, Some(key_mgr_keys))?;
+
+    key_mgr_metadata
+        .delegations
+        .get("pkg_mgr")
+        .cloned()
+        .ok_or(SignatureError::MissingDelegation("key_mgr", "pkg_mgr"))
+}
+
+/// Verifies that metadata is signed by enough of the given keys and that it
+/// has not expired. The `root` metadata is verified with its own keys.
+fn verify_metadata(
+    role: &'static str,
+    metadata: &Signable,
+    keys: Option<&Delegation>,
+) -> Result<SignedMetadata, SignatureError> {
+    let signed: SignedMetadata = serde_json::from_value(metadata.signed.clone())
+        .map_err(|err| SignatureError::InvalidMetadata(role, err))?;
+    let keys = match keys {
+        Some(keys) => keys,
+        None => signed
+            .delegations
+            .get("root")
+            .ok_or(SignatureError::MissingDelegation(role, "root"))?,
+    };
+
+    let message = canonical_json(&metadata.signed);
+    let threshold = keys.threshold.max(1);
+    if count_valid_signatures(message.as_bytes(), &metadata.signatures, keys) < threshold {
+        return Err(SignatureError::Threshold { role, threshold });
+    }
+    if signed.expiration < Utc::now() {
+        return Err(SignatureError::Expired(role, signed.expiration));
+    }
+    Ok(signed)
+}
+
+/// Verifies the signature of a conda package in the repodata of its channel
+/// and that the signed entry describes the locked package.
+fn verify_record(
+    entries: &SignedEntries,
+    keys: &Delegation,
+    record: &RepoDataRecord,
+) -> Result<(), SignatureError> {
+    let file_name = &record.file_name;
+    let entry = entries
+        .packages
+        .get(file_name)
+        .ok_or_else(|| SignatureError::NotInRepodata(file_name.clone()))?;
+    let signatures = entries
+        .signatures
+        .get(file_name)
+        .ok_or_else(|| SignatureError::Unsigned(file_name.clone()))?;
+
+    let message = canonical_json(entry);
+    if count_valid_signatures(message.as_bytes(), signatures, keys) < keys.threshold.max(1) {
+        return Err(SignatureError::InvalidSignature(file_name.clone()));
+    }
+
+    let package_record = &record.package_record;
+    let matches = match (&package_record.sha256, &package_record.md5) {
+        (Some(sha256), _) => {
+            entry.get("sha256").and_then(Value::as_str) == Some(format!("{sha256:x}").as_str())
+        }
+        (None, Some(md5)) => {
+            entry.get("md5").and_then(Value::as_str) == Some(format!("{md5:x}").as_str())
+        }
+        (None, None) => false,
+    };
+    if !matches {
+        return Err(SignatureError::DigestMismatch(file_name.clone()));
+    }
+    Ok(())
+}
+
+/// Returns the number of trusted keys that signed the message.
+fn count_valid_signatures(
+    message: &[u8],
+    signatures: &HashMap<String, SignatureEntry>,
+    keys: &Delegation,
+) -> usize {
+    keys.pubkeys
+        .iter()
+        .unique()
+        .filter(|key| {
+            signatures
+                .get(*key)
+                .is_some_and(|entry| verify_ed25519(key, &entry.signature, message))
+        })
+        .count()
+}
+
+/// Verifies a hex encoded ed25519 signature with a hex encoded public key.
+fn verify_ed25519(public_key: &str, signature: &str, message: &[u8]) -> bool {
+    let Some(public_key) = hex::decode(public_key)
+        .ok()
+        .and_then(|key| <[u8; 32]>::try_from(key).ok())
+        .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+    else {
+        return false;
+    };
+    let Some(signature) = hex::decode(signature)
+        .ok()
+        .and_then(|signature| Signature::from_slice(&signature).ok())
+    else {
+        return false;
+    };
+    public_key.verify(message, &signature).is_ok()
+}
+
+/// Serializes a JSON value the way conda content trust does before signing
+/// it, which matches `json.dumps(value, indent=2, sort_keys=True,
+/// separators=(",", ": "))` in Python.
+fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical_json(value, 0, &mut out);
+    out
+}
+
+fn write_canonical_json(value: &Value, depth: usize, out: &mut String) {
+    let newline = |out: &mut String, depth: usize| {
+        out.push('\n');
+        out.push_str(&"  ".repeat(depth));
+    };
+    match value {
+        Value::Array(items) if !items.is_empty() => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_canonical_json(item, depth + 1, out);
+            }
+            newline(out, depth);
+            out.push(']');
+        }
+        Value::Object(map) if !map.is_empty() => {
+            out.push('{');
+            for (index, (key, item)) in map.iter().sorted_by_key(|(key, _)| *key).enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                newline(out, depth + 1);
+                write_canonical_string(key, out);
+                out.push_str(": ");
+                write_canonical_json(item, depth + 1, out);
+            }
+            newline(out, depth);
+            out.push('}');
+        }
+        Value::String(string) => write_canonical_string(string, out),
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Writes a JSON string with all non-ASCII characters escaped.
+fn write_canonical_string(string: &str, out: &mut String) {
+    out.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c.is_ascii() && !c.is_ascii_control() => out.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    let _ = write!(out, "\\u{unit:04x}");
+                }
+            }
+        }
+    }
+    out.push('"');
+}
+
+/// The provenance of a file on PyPI, as served by the integrity API.
+#[derive(Debug, Deserialize)]
+struct Provenance {
+    attestation_bundles: Vec<AttestationBundle>,
+}
+
+/// The attestations of a file by one trusted publisher.
+#[derive(Debug, Deserialize)]
+struct AttestationBundle {
+    publisher: Publisher,
+    attestations: Vec<Attestation>,
+}
+
+/// The trusted publisher that uploaded a file to PyPI.
+#[derive(Debug, Deserialize)]
+struct Publisher {
+    kind: String,
+    repository: Option<String>,
+    #[serde(alias = "workflow_filepath")]
+    workflow: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Attestation {
+    verification_material: VerificationMaterial,
+    envelope: Envelope,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerificationMaterial {
+    /// The base64 encoded DER of the signing certificate.
+    certificate: String,
+    transparency_entries: Vec<TransparencyEntry>,
+}
+
+/// A DSSE envelope with a base64 encoded in-toto statement.
+#[derive(Debug, Deserialize)]
+struct Envelope {
+    statement: String,
+    signature: String,
+}
+
+/// The entry of an attestation in the Rekor transparency log.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransparencyEntry {
+    #[serde(deserialize_with = "deserialize_int")]
+    log_index: i64,
+    log_id: LogId,
+    #[serde(deserialize_with = "deserialize_int")]
+    integrated_time: i64,
+    inclusion_promise: Option<InclusionPromise>,
+    canonicalized_body: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogId {
+    key_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InclusionPromise {
+    signed_entry_timestamp: String,
+}
+
+/// Deserializes an integer that is encoded as a string, like the 64 bit
+/// integers in protobuf JSON.
+fn deserialize_int<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::Number(number) => number
+            .as_i64()
+            .ok_or_else(|| serde::de::Error::custom("expected an integer")),
+        Value::String(string) => string.parse().map_err(serde::de::Error::custom),
+        _ => Err(serde::de::Error::custom("expected an integer")),
+    }
+}
+
+/// The keys of the sigstore trust root that attestations are verified with.
+struct SigstoreKeys {
+    fulcio_certs: Vec<Certificate>,
+    rekor_keys: Vec<(String, CosignVerificationKey)>,
+}
+
+impl SigstoreKeys {
+    /// Loads the sigstore trust root, which is updated through TUF and cached
+    /// in the pixi cache directory.
+    async fn load() -> Result<Self, SignatureError> {
+        let cache_dir = pixi_config::get_cache_dir()
+            .ok()
+            .map(|cache_dir| cache_dir.join(consts::SIGNATURES_CACHE_DIR).join("tuf"));
+        let trust_root = SigstoreTrustRoot::new(cache_dir.as_deref())
+            .await
+            .map_err(|err| SignatureError::TrustRoot(err.into()))?;
+
+        let fulcio_certs = trust_root
+            .fulcio_certs()
+            .map_err(|err| SignatureError::TrustRoot(err.into()))?
+            .iter()
+            .map(|cert| Certificate::from_der(cert.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| SignatureError::TrustRoot(err.into()))?;
+        let rekor_keys = trust_root
+            .rekor_keys()
+            .map_err(|err| SignatureError::TrustRoot(err.into()))?
+            .into_iter()
+            .map(|key| {
+                // The log id of a Rekor instance is the sha256 of its key.
+                let log_id = format!("{:x}", compute_bytes_digest::<Sha256>(key));
+                CosignVerificationKey::try_from_der(key).map(|key| (log_id, key))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| SignatureError::TrustRoot(err.into()))?;
+
+        Ok(Self {
+            fulcio_certs,
+            rekor_keys,
+        })
+    }
+}
+
+/// Verifies that PyPI serves a provenance for the wheel with a valid
+/// attestation that covers its locked digest.
+async fn verify_wheel(
+    client: &LazyClient,
+    trust_root: &OnceCell<SigstoreKeys>,
+    package: &PypiPackageData,
+    url: &Url,
+) -> Result<(), SignatureError> {
+    let name = package.name.to_string();
+    let file_name = url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|file_name| file_name.ends_with(".whl"));
+    let (Some(file_name), Some(PYPI_FILES_HOST)) = (file_name, url.host_str()) else {
+        return Err(SignatureError::UnsupportedSource(name));
+    };
+    let Some(sha256) = package.hash.as_ref().and_then(|hash| hash.sha256()) else {
+        return Err(SignatureError::AttestationMismatch(file_name.to_string()));
+    };
+
+    let provenance_url = Url::parse(&format!(
+        "{PYPI_INTEGRITY_URL}/{name}/{}/{file_name}/provenance",
+        package.version
+    ))
+    .map_err(|err| SignatureError::Fetch(url.clone(), err.into()))?;
+    let provenance = fetch_json(
+        client,
+        &provenance_url,
+        Some("application/vnd.pypi.integrity.v1+json"),
+    )
+    .await?
+    .ok_or_else(|| SignatureError::NoProvenance(file_name.to_string()))?;
+    let provenance: Provenance = serde_json::from_value(provenance)
+        .map_err(|err| SignatureError::InvalidMetadata("provenance", err))?;
+
+    let keys = trust_root.get_or_try_init(SigstoreKeys::load).await?;
+    let sha256 = format!("{sha256:x}");
+    let mut error = SignatureError::AttestationMismatch(file_name.to_string());
+    for bundle in &provenance.attestation_bundles {
+        for attestation in &bundle.attestations {
+            match verify_attestation(keys, &bundle.publisher, attestation) {
+                Ok(statement) if statement_covers(&statement, file_name, &sha256) => {
+                    return Ok(());
+                }
+                Ok(_) => {}
+                Err(reason) => {
+                    error = SignatureError::InvalidAttestation(file_name.to_string(), reason)
+                }
+            }
+        }
+    }
+    Err(error)
+}
+
+/// Verifies an attestation against the sigstore trust root and returns its
+/// in-toto statement. Returns the reason why the attestation is not valid
+/// otherwise.
+fn verify_attestation(
+    keys: &SigstoreKeys,
+    publisher: &Publisher,
+    attestation: &Attestation,
+) -> Result<Value, &'static str> {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let certificate = base64
+        .decode(&attestation.verification_material.certificate)
+        .ok()
+        .and_then(|der| Certificate::from_der(&der).ok())
+        .ok_or("its certificate cannot be decoded")?;
+    let statement = base64
+        .decode(&attestation.envelope.statement)
+        .map_err(|_| "its statement cannot be decoded")?;
+    let signature = base64
+        .decode(&attestation.envelope.signature)
+        .map_err(|_| "its signature cannot be decoded")?;
+
+    // The certificate has to be issued by Fulcio.
+    let issued_by_fulcio = keys.fulcio_certs.iter().any(|issuer| {
+        issuer.tbs_certificate.subject == certificate.tbs_certificate.issuer
+            && verify_certificate_signature(issuer, &certificate)
+    });
+    if !issued_by_fulcio {
+        return Err("its certificate is not issued by Fulcio");
+    }
+
+    // The statement has to be signed with the key of the certificate.
+    let signed =
+        CosignVerificationKey::try_from(&certificate.tbs_certificate.subject_public_key_info)
+            .is_ok_and(|key| {
+                key.verify_signature(
+                    SigstoreSignature::Raw(&signature),
+                    &dsse_pae(IN_TOTO_PAYLOAD_TYPE, &statement),
+                )
+                .is_ok()
+            });
+    if !signed {
+        return Err("its signature does not match its certificate");
+    }
+
+    // The signature has to be logged in Rekor while the short-lived
+    // certificate was valid.
+    let validity = &certificate.tbs_certificate.validity;
+    let valid_from = validity.not_before.to_unix_duration().as_secs() as i64;
+    let valid_until = validity.not_after.to_unix_duration().as_secs() as i64;
+    let logged = attestation
+        .verification_material
+        .transparency_entries
+        .iter()
+        .any(|entry| {
+            (valid_from..=valid_until).contains(&entry.integrated_time)
+                && verify_transparency_entry(keys, entry, &statement, &attestation.envelope)
+        });
+    if !logged {
+        return Err("it is not in the transparency log");
+    }
+
+    if !matches_publisher(&certificate, publisher) {
+        return Err("its certificate does not belong to the trusted publisher");
+    }
+
+    serde_json::from_slice(&statement).map_err(|_| "its statement is not valid JSON")
+}
+
+/// Verifies that the certificate is signed by the issuer.
+fn verify_certificate_signature(issuer: &Certificate, certificate: &Certificate) -> bool {
+    let Ok(message) = certificate.tbs_certificate.to_der() else {
+        return false;
+    };
+    let Some(signature) = certificate.signature.as_bytes() else {
+        return false;
+    };
+    CosignVerificationKey::try_from(&issuer.tbs_certificate.subject_public_key_info).is_ok_and(
+        |key| {
+            key.verify_signature(SigstoreSignature::Raw(signature), &message)
+                .is_ok()
+        },
+    )
+}
+
+/// Verifies the signed entry timestamp of a Rekor entry, and that the entry
+/// logs the signature of the envelope.
+fn verify_transparency_entry(
+    keys: &SigstoreKeys,
+    entry: &TransparencyEntry,
+    statement: &[u8],
+    envelope: &Envelope,
+) -> bool {
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let (Some(promise), Ok(log_id)) = (
+        &entry.inclusion_promise,
+        base64.decode(&entry.log_id.key_id),
+    ) else {
+        return false;
+    };
+    let Ok(signed_entry_timestamp) = base64.decode(&promise.signed_entry_timestamp) else {
+        return false;
+    };
+    let log_id = hex::encode(log_id);
+    let Some((_, key)) = keys.rekor_keys.iter().find(|(id, _)| *id == log_id) else {
+        return false;
+    };
+
+    // The payload of the signed entry timestamp is the canonical JSON of the
+    // entry, with sorted keys and without whitespace.
+    let payload = serde_json::json!({
+        "body": entry.canonicalized_body,
+        "integratedTime": entry.integrated_time,
+        "logID": log_id,
+        "logIndex": entry.log_index,
+    });
+    if key
+        .verify_signature(
+            SigstoreSignature::Raw(&signed_entry_timestamp),
+            payload.to_string().as_bytes(),
+        )
+        .is_err()
+    {
+        return false;
+    }
+
+    // The logged DSSE entry has to be for this statement and signature.
+    let Some(body) = base64
+        .decode(&entry.canonicalized_body)
+        .ok()
+        .and_then(|body| serde_json::from_slice::<Value>(&body).ok())
+    else {
+        return false;
+    };
+    let payload_hash = format!("{:x}", compute_bytes_digest::<Sha256>(statement));
+    body.pointer("/spec/payloadHash/value")
+        .and_then(Value::as_str)
+        == Some(&payload_hash)
+        && body
+            .pointer("/spec/signatures")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .any(|signature| {
+                signature.get("signature").and_then(Value::as_str)
+                    == Some(envelope.signature.as_str())
+            })
+}
+
+/// Returns the pre-authentication encoding of a DSSE payload, which is what is
+/// signed in a DSSE envelope.
+fn dsse_pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut pae = format!(
+        "DSSEv1 {} {payload_type} {} ",
+        payload_type.len(),
+        payload.len()
+    )
+    .into_bytes();
+    pae.extend_from_slice(payload);
+    pae
+}
+
+/// Returns true if the certificate was issued to the workflow of the trusted
+/// publisher that uploaded the file.
+fn matches_publisher(certificate: &Certificate, publisher: &Publisher) -> bool {
+    let Ok(Some((_, SubjectAltName(names)))) = certificate.tbs_certificate.get::<SubjectAltName>()
+    else {
+        return false;
+    };
+    let (Some(repository), Some(workflow)) = (&publisher.repository, &publisher.workflow) else {
+        return false;
+    };
+    let identity = match publisher.kind.as_str() {
+        "GitHub" => format!("https://github.com/{repository}/.github/workflows/{workflow}@"),
+        "GitLab" => format!("https://gitlab.com/{repository}//{workflow}@"),
+        _ => return false,
+    };
+    names.iter().any(|name| match name {
+        GeneralName::UniformResourceIdentifier(uri) => uri
+            .as_str()
+            .to_lowercase()
+            .starts_with(&identity.to_lowercase()),
+        _ => false,
+    })
+}
+
+/// Returns true if the in-toto statement has the file with the given digest as
+/// its subject.
+fn statement_covers(statement: &Value, file_name: &str, sha256: &str) -> bool {
+    statement
+        .get("subject")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .any(|subject| {
+            subject.get("name").and_then(Value::as_str) == Some(file_name)
+                && subject.pointer("/digest/sha256").and_then(Value::as_str) == Some(sha256)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_json() {
+        let value = json!({
+            "name": "café",
+            "depends": ["python >=3.8"],
+            "constrains": [],
+            "build_number": 0,
+            "about": {},
+        });
+        assert_eq!(
+            canonical_json(&value),
+            "{\n  \"about\": {},\n  \"build_number\": 0,\n  \"constrains\": [],\n  \"depends\": [\n    \"python >=3.8\"\n  ],\n  \"name\": \"caf\\u00e9\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_count_valid_signatures() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = hex::encode(signing_key.verifying_key().as_bytes());
+        let message = canonical_json(&json!({"name": "foo", "version": "1.0"}));
+        let signatures = HashMap::from([(
+            public_key.clone(),
+            SignatureEntry {
+                signature: hex::encode(signing_key.sign(message.as_bytes()).to_bytes()),
+            },
+        )]);
+        let keys = Delegation {
+            pubkeys: vec![public_key],
+            threshold: 1,
+        };
+
+        assert_eq!(
+            count_valid_signatures(message.as_bytes(), &signatures, &keys),
+            1
+        );
+        assert_eq!(
+            count_valid_signatures(b"something else", &signatures, &keys),
+            0
+        );
+    }
+
+    #[test]
+    fn test_verify_metadata_requires_a_signature() {
+        let metadata = Signable {
+            signatures: HashMap::new(),
+            signed: json!({
+                "expiration": "2999-01-01T00:00:00Z",
+                "delegations": {},
+            }),
+        };
+        let keys = Delegation {
+            pubkeys: vec![],
+            threshold: 0,
+        };
+
+        assert!(matches!(
+            verify_metadata("key_mgr", &metadata, Some(&keys)),
+            Err(SignatureError::Threshold { threshold: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_signed_entries_only_keeps_requested_packages() {
+        let repodata = json!({
+            "info": {"subdir": "linux-64"},
+            "packages": {
+                "foo-1.0-0.tar.bz2": {"name": "foo"},
+                "bar-1.0-0.tar.bz2": {"name": "bar"},
+            },
+            "packages.conda": {
+                "baz-1.0-0.conda": {"name": "baz"},
+            },
+            "signatures": {
+                "foo-1.0-0.tar.bz2": {"abc": {"signature": "def"}},
+                "bar-1.0-0.tar.bz2": {"abc": {"signature": "123"}},
+            },
+        });
+        let file_names = HashSet::from(["foo-1.0-0.tar.bz2", "baz-1.0-0.conda"]);
+        let entries =
+            SignedEntries::from_slice(repodata.to_string().as_bytes(), &file_names).unwrap();
+
+        assert_eq!(
+            entries.packages.keys().sorted().collect_vec(),
+            ["baz-1.0-0.conda", "foo-1.0-0.tar.bz2"]
+        );
+        assert_eq!(
+            entries.signatures.keys().collect_vec(),
+            ["foo-1.0-0.tar.bz2"]
+        );
+        assert_eq!(
+            entries.signatures["foo-1.0-0.tar.bz2"]["abc"].signature,
+            "def"
+        );
+    }
+
+    #[test]
+    fn test_statement_covers() {
+        let statement = json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{"name": "foo-1.0-py3-none-any.whl", "digest": {"sha256": "abcd"}}],
+        });
+        assert!(statement_covers(
+            &statement,
+            "foo-1.0-py3-none-any.whl",
+            "abcd"
+        ));
+        assert!(!statement_covers(
+            &statement,
+            "foo-1.0-py3-none-any.whl",
+            "ef01"
+        ));
+        assert!(!statement_covers(
+            &statement,
+            "bar-1.0-py3-none-any.whl",
+            "abcd"
+        ));
+    }
+
+    #[test]
+    fn test_dsse_pae() {
+        assert_eq!(
+            dsse_pae(IN_TOTO_PAYLOAD_TYPE, b"{}"),
+            b"DSSEv1 28 application/vnd.in-toto+json 2 {}"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_transparency_entry() {
+        let entry: TransparencyEntry = serde_json::from_value(json!({
+            "logIndex": "148524",
+            "logId": {"keyId": "wNI9atQGlz+VWfO6LRygH4QUfY/8W4RFwiT5i5WRgB0="},
+            "kindVersion": {"kind": "dsse", "version": "0.0.1"},
+            "integratedTime": 1730000000,
+            "inclusionPromise": {"signedEntryTimestamp": "MEUCIQ=="},
+            "canonicalizedBody": "e30=",
+        }))
+        .unwrap();
+        assert_eq!(entry.log_index, 148524);
+        assert_eq!(entry.integrated_time, 1730000000);
+    }
+}
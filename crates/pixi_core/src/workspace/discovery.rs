@@ -4,9 +4,12 @@ use itertools::Itertools;
 use miette::{Diagnostic, NamedSource, Report};
 use pixi_consts::consts;
 use pixi_manifest::{
-    ExplicitManifestError, LoadManifestsError, Manifests, TomlError, WarningWithSource,
-    WithWarnings, WorkspaceDiscoveryError, utils::WithSourceCode,
+    ExplicitManifestError, LoadManifestsError, LockProfileName, Manifests, TomlError,
+    WarningWithSource, WithWarnings, WorkspaceDiscoveryError, WorkspaceMemberError,
+    utils::WithSourceCode,
 };
+use pixi_utils::timings::{self, Phase};
+use serde::Serialize;
 use thiserror::Error;
 
 use crate::workspace::Workspace;
@@ -43,6 +46,26 @@ impl DiscoveryStart {
     }
 }
 
+/// Describes how the workspace was located.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiscoveryReason {
+    /// The manifest was found by searching the start directory and its
+    /// parents.
+    Search,
+
+    /// The manifest was given explicitly.
+    ExplicitManifest,
+
+    /// No manifest was found by searching, the manifest of the
+    /// `PIXI_PROJECT_MANIFEST` environment variable was used instead.
+    EnvironmentVariable,
+
+    /// No manifest was found by searching, one of the fallback roots was used
+    /// instead.
+    Fallback,
+}
+
 /// A helper struct that helps discover the workspace root and potentially the
 /// "current" package.
 #[derive(Default)]
@@ -52,6 +75,8 @@ pub struct WorkspaceLocator {
     emit_warnings: bool,
     consider_environment: bool,
     ignore_pixi_version_check: bool,
+    fallback_roots: Vec<PathBuf>,
+    lock_profile: Option<LockProfileName>,
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -97,6 +122,42 @@ pub enum WorkspaceLocatorError {
     #[error(transparent)]
     #[diagnostic(transparent)]
     ExplicitManifestError(#[from] ExplicitManifestError),
+
+    /// The members of the workspace could not be resolved.
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Members(#[from] WorkspaceMemberError),
+
+    /// The requested lock profile is not defined in the manifest.
+    #[error("the lock profile '{name}' is not defined in the manifest")]
+    UnknownLockProfile {
+        name: LockProfileName,
+        #[help]
+        help: String,
+    },
+
+    /// The requested lock profile has the same name as an environment.
+    #[error("the lock profile '{name}' has the same name as an environment")]
+    #[diagnostic(help(
+        "the environments of a lock profile are installed in a directory named after the profile, rename the lock profile or the environment"
+    ))]
+    LockProfileConflictsWithEnvironment { name: LockProfileName },
+}
+
+impl From<WorkspaceDiscoveryError> for WorkspaceLocatorError {
+    fn from(err: WorkspaceDiscoveryError) -> Self {
+        match err {
+            WorkspaceDiscoveryError::Toml(err) => WorkspaceLocatorError::Toml(err),
+            WorkspaceDiscoveryError::Io(err) => WorkspaceLocatorError::Io(err),
+            WorkspaceDiscoveryError::ExplicitManifestError(err) => {
+                WorkspaceLocatorError::ExplicitManifestError(err)
+            }
+            WorkspaceDiscoveryError::Canonicalize(source, path) => {
+                WorkspaceLocatorError::Canonicalize { path, source }
+            }
+            WorkspaceDiscoveryError::Members(err) => WorkspaceLocatorError::Members(err),
+        }
+    }
 }
 
 impl WorkspaceLocator {
@@ -148,8 +209,73 @@ impl WorkspaceLocator {
         }
     }
 
+    /// Workspace roots or manifests to use when no workspace is found from
+    /// the search start. The first one that exists is used. These are not
+    /// considered when an explicit manifest is given.
+    pub fn with_fallback_roots(self, fallback_roots: Vec<PathBuf>) -> Self {
+        Self {
+            fallback_roots,
+            ..self
+        }
+    }
+
+    /// The lock profile of the workspace to use. The located workspace reads
+    /// and writes the lock-file of the profile and uses its settings.
+    pub fn with_lock_profile(self, lock_profile: Option<LockProfileName>) -> Self {
+        Self {
+            lock_profile,
+            ..self
+        }
+    }
+
     /// Called to locate the workspace or error out if none could be located.
     pub fn locate(self) -> Result<Workspace, WorkspaceLocatorError> {
+        self.locate_with_reason().map(|(workspace, _)| workspace)
+    }
+
+    /// Locates the workspace like [`Self::locate`], and also returns how the
+    /// workspace was located.
+    pub fn locate_with_reason(self) -> Result<(Workspace, DiscoveryReason), WorkspaceLocatorError> {
+        let emit_warnings = self.emit_warnings;
+        let (
+            WithWarnings {
+                value: workspace,
+                warnings,
+            },
+            reason,
+        ) = self.discover()?;
+
+        // Emit any warnings that were encountered during the discovery process.
+        if emit_warnings && !warnings.is_empty() {
+            tracing::warn!(
+                "Encountered {} warning{} while parsing the manifest:\n{}",
+                warnings.len(),
+                if warnings.len() == 1 { "" } else { "s" },
+                warnings
+                    .into_iter()
+                    .map(Report::from)
+                    .format_with("\n", |w, f| f(&format_args!("{w:?}")))
+            );
+        }
+
+        Ok((workspace, reason))
+    }
+
+    /// Locates the workspace like [`Self::locate`], but returns the warnings
+    /// that were encountered while parsing the manifests instead of emitting
+    /// them.
+    pub fn locate_with_warnings(
+        self,
+    ) -> Result<WithWarnings<Workspace, WarningWithSource>, WorkspaceLocatorError> {
+        self.discover().map(|(workspace, _)| workspace)
+    }
+
+    fn discover(
+        self,
+    ) -> Result<(WithWarnings<Workspace, WarningWithSource>, DiscoveryReason), WorkspaceLocatorError>
+    {
+        let _timer = timings::start(Phase::WorkspaceDiscovery);
+
         // Determine the search root
         let explicit_start = matches!(&self.start, DiscoveryStart::ExplicitManifest(_));
         let discovery_start = match self.start {
@@ -165,21 +291,15 @@ impl WorkspaceLocator {
         let discovery_source = discovery_start.root().to_path_buf();
 
         // Discover the workspace manifest for the current path.
-        let workspace_manifests = match pixi_manifest::WorkspaceDiscoverer::new(discovery_start)
+        let workspace_manifests = pixi_manifest::WorkspaceDiscoverer::new(discovery_start)
             .with_closest_package(self.with_closest_package)
             .discover()
-        {
-            Ok(manifests) => manifests,
-            Err(WorkspaceDiscoveryError::Toml(err)) => {
-                return Err(WorkspaceLocatorError::Toml(err));
-            }
-            Err(WorkspaceDiscoveryError::Io(err)) => return Err(WorkspaceLocatorError::Io(err)),
-            Err(WorkspaceDiscoveryError::ExplicitManifestError(err)) => {
-                return Err(WorkspaceLocatorError::ExplicitManifestError(err));
-            }
-            Err(WorkspaceDiscoveryError::Canonicalize(source, path)) => {
-                return Err(WorkspaceLocatorError::Canonicalize { path, source });
-            }
+            .map_err(WorkspaceLocatorError::from)?;
+
+        let mut reason = if explicit_start {
+            DiscoveryReason::ExplicitManifest
+        } else {
+            DiscoveryReason::Search
         };
 
         // Extract the warnings from the discovered workspace.
@@ -192,6 +312,7 @@ impl WorkspaceLocator {
         };
 
         // Take into consideration any environment variables that may be set.
+        let searched = workspace_manifests.is_some();
         if self.consider_environment
             && !explicit_start
             && let Some(WithWarnings {
@@ -202,6 +323,22 @@ impl WorkspaceLocator {
         {
             warnings.append(&mut env_warnings);
             workspace_manifests = Some(manifests);
+            if !searched {
+                reason = DiscoveryReason::EnvironmentVariable;
+            }
+        }
+
+        // Fall back to the configured workspaces.
+        if workspace_manifests.is_none()
+            && !explicit_start
+            && let Some(WithWarnings {
+                value: manifests,
+                warnings: mut fallback_warnings,
+            }) = Self::discover_fallback(&self.fallback_roots)?
+        {
+            warnings.append(&mut fallback_warnings);
+            workspace_manifests = Some(manifests);
+            reason = DiscoveryReason::Fallback;
         }
 
         // Early out if discovery failed.
@@ -221,26 +358,59 @@ impl WorkspaceLocator {
             return Err(WorkspaceLocatorError::WorkspaceNotFound(discovery_source));
         };
 
-        // Emit any warnings that were encountered during the discovery process.
-        if self.emit_warnings && !warnings.is_empty() {
-            tracing::warn!(
-                "Encountered {} warning{} while parsing the manifest:\n{}",
-                warnings.len(),
-                if warnings.len() == 1 { "" } else { "s" },
-                warnings
-                    .into_iter()
-                    .map(Report::from)
-                    .format_with("\n", |w, f| f(&format_args!("{w:?}")))
-            );
+        let mut workspace = Workspace::from_manifests(discovered_manifests);
+
+        if let Some(name) = self.lock_profile {
+            let lock_profiles = &workspace.workspace.value.workspace.lock_profiles;
+            let Some(profile) = lock_profiles.get(&name).cloned() else {
+                let help = if lock_profiles.is_empty() {
+                    String::from("define lock profiles in the `[workspace.lock-profiles]` table")
+                } else {
+                    format!(
+                        "the available lock profiles are: {}",
+                        lock_profiles.keys().format(", ")
+                    )
+                };
+                return Err(WorkspaceLocatorError::UnknownLockProfile { name, help });
+            };
+            // The environments of a profile are installed in a directory named
+            // after it, next to the environments without a profile.
+            if workspace
+                .environments()
+                .iter()
+                .any(|environment| environment.name().as_str() == name.as_str())
+            {
+                return Err(WorkspaceLocatorError::LockProfileConflictsWithEnvironment { name });
+            }
+            workspace = workspace.with_lock_profile(name, &profile);
         }
 
-        let workspace = Workspace::from_manifests(discovered_manifests);
-
         if !self.ignore_pixi_version_check {
             workspace.verify_current_pixi_meets_requirement()?;
         }
 
-        Ok(workspace)
+        Ok((
+            WithWarnings::from(workspace).with_warnings(warnings),
+            reason,
+        ))
+    }
+
+    /// Discover the workspace of the first fallback root that exists.
+    fn discover_fallback(
+        fallback_roots: &[PathBuf],
+    ) -> Result<Option<WithWarnings<Manifests, WarningWithSource>>, WorkspaceLocatorError> {
+        let Some(root) = fallback_roots.iter().find(|root| root.exists()) else {
+            return Ok(None);
+        };
+        tracing::debug!(
+            "No workspace found, falling back to the workspace at {}",
+            root.display()
+        );
+        pixi_manifest::WorkspaceDiscoverer::new(pixi_manifest::DiscoveryStart::ExplicitManifest(
+            root.clone(),
+        ))
+        .discover()
+        .map_err(WorkspaceLocatorError::from)
     }
 
     /// Apply any environment overrides to a potentially discovered workspace.
@@ -279,6 +449,9 @@ impl WorkspaceLocator {
                         ExplicitManifestError::InvalidManifest(err),
                     ));
                 }
+                Err(LoadManifestsError::Members(err)) => {
+                    return Err(WorkspaceLocatorError::Members(err));
+                }
             }
         }
 
@@ -346,6 +519,33 @@ mod test {
         assert_eq!(workspace.root, project_root);
     }
 
+    #[test]
+    fn test_workspace_locator_fallback_roots() {
+        let crate_root = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let project_root = Path::new(&crate_root).parent().unwrap().parent().unwrap();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let (workspace, reason) = WorkspaceLocator::default()
+            .with_search_start(DiscoveryStart::SearchRoot(temp_dir.path().to_path_buf()))
+            .with_fallback_roots(vec![
+                temp_dir.path().join("missing"),
+                project_root.to_path_buf(),
+            ])
+            .locate_with_reason()
+            .unwrap();
+        assert_eq!(workspace.root, project_root);
+        assert_eq!(reason, DiscoveryReason::Fallback);
+
+        // The fallback roots are not used when an explicit manifest is given.
+        let result = WorkspaceLocator::default()
+            .with_search_start(DiscoveryStart::ExplicitManifest(
+                temp_dir.path().to_path_buf(),
+            ))
+            .with_fallback_roots(vec![project_root.to_path_buf()])
+            .locate();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_pyproject_without_pixi_error() {
         use tempfile::TempDir;
@@ -102,6 +102,11 @@ impl<'p> Environment<'p> {
             .join(self.environment.name.as_str())
     }
 
+    /// Returns true if the environment has been installed in its directory.
+    pub fn is_installed(&self) -> bool {
+        self.dir().join(consts::CONDA_META_DIR).is_dir()
+    }
+
     /// We store a hash of the lockfile and all activation env variables in a
     /// file in the cache. The current name is
     /// `activation_environment-name.json`.
@@ -314,14 +319,23 @@ impl<'p> Environment<'p> {
     /// this environment.
     ///
     /// The environment variables of all features are combined in the order they
-    /// are defined for the environment.
+    /// are defined for the environment. The variables defined in the `env`
+    /// table of the environment itself are applied last and take precedence.
     pub(crate) fn activation_env(&self, platform: Option<Platform>) -> IndexMap<String, String> {
-        self.features()
-            .map(|f| f.activation_env(platform))
-            .fold(IndexMap::new(), |mut acc, env| {
+        let mut env = self.features().map(|f| f.activation_env(platform)).fold(
+            IndexMap::new(),
+            |mut acc, env| {
                 acc.extend(env.iter().map(|(k, v)| (k.clone(), v.clone())));
                 acc
-            })
+            },
+        );
+        env.extend(
+            self.environment
+                .env
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+        env
     }
 
     /// Validates that the given platform is supported by this environment.
@@ -387,12 +401,15 @@ impl Hash for Environment<'_> {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, path::Path};
+    use std::{collections::HashSet, path::Path, str::FromStr};
 
-    use indexmap::indexmap;
+    use indexmap::{IndexSet, indexmap};
     use insta::assert_snapshot;
     use itertools::Itertools;
-    use pixi_manifest::CondaDependencies;
+    use pixi_manifest::{
+        ChannelPriority, CondaDependencies, SolveStrategy,
+        pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation},
+    };
 
     use super::*;
 
@@ -613,6 +630,7 @@ mod tests {
 
             [environments]
             foo = ["foo"]
+            bar = { features = ["foo"], env = { FOO_VAR = "2", BAR_VAR = "$FOO_VAR" } }
             "#,
         )
         .unwrap();
@@ -626,6 +644,15 @@ mod tests {
                 "DEFAULT_VAR".to_string() => "1".to_string(),
             }
         );
+        let bar_env = manifest.environment("bar").unwrap();
+        assert_eq!(
+            bar_env.activation_env(None),
+            indexmap! {
+                "FOO_VAR".to_string() => "2".to_string(),
+                "DEFAULT_VAR".to_string() => "1".to_string(),
+                "BAR_VAR".to_string() => "$FOO_VAR".to_string(),
+            }
+        );
         assert_eq!(
             default_env.activation_env(Some(Platform::Linux64)),
             indexmap! {
@@ -891,6 +918,101 @@ mod tests {
         assert_eq!(bar_opts.extra_index_urls.unwrap().len(), 1);
     }
 
+    #[test]
+    fn test_command_line_overrides_apply_to_all_features() {
+        let manifest = Workspace::from_str(
+            Path::new("pixi.toml"),
+            r#"
+        [project]
+        name = "foobar"
+        channels = []
+        platforms = []
+
+        [feature.extra]
+        solve-strategy = "lowest-direct"
+        channel-priority = "disabled"
+
+        [feature.extra.pypi-options]
+        no-build = ["foo"]
+        no-build-isolation = ["foo"]
+
+        [environments]
+        extra = ["extra"]
+        "#,
+        )
+        .unwrap();
+
+        fn package(name: &str) -> pep508_rs::PackageName {
+            pep508_rs::PackageName::from_str(name).unwrap()
+        }
+
+        // The solve strategy and channel priority replace those of the
+        // features, the PyPI options extend them.
+        type Case = (
+            &'static str,
+            fn(Workspace) -> Workspace,
+            fn(&Environment<'_>),
+        );
+        let cases: [Case; 4] = [
+            (
+                "solve-strategy",
+                |workspace| workspace.with_solve_strategy(SolveStrategy::Lowest),
+                |env| assert_eq!(env.solve_strategy(), SolveStrategy::Lowest),
+            ),
+            (
+                "channel-priority",
+                |workspace| workspace.with_channel_priority(ChannelPriority::Strict),
+                |env| {
+                    assert_eq!(
+                        env.channel_priority().unwrap(),
+                        Some(ChannelPriority::Strict)
+                    )
+                },
+            ),
+            (
+                "no-build",
+                |workspace| {
+                    workspace.with_pypi_build_options(
+                        &NoBuild::Packages(IndexSet::from([package("bar")])),
+                        &NoBinary::All,
+                    )
+                },
+                |env| {
+                    let options = env.pypi_options();
+                    assert_eq!(options.no_binary, Some(NoBinary::All));
+                    let Some(NoBuild::Packages(packages)) = options.no_build else {
+                        panic!("expected the packages that may not be built");
+                    };
+                    assert!(packages.contains(&package("bar")));
+                    assert_eq!(packages.contains(&package("foo")), env.name() == "extra");
+                },
+            ),
+            (
+                "no-build-isolation",
+                |workspace| {
+                    workspace
+                        .with_no_build_isolation(&NoBuildIsolation::from_iter([package("bar")]))
+                },
+                |env| {
+                    let no_build_isolation = env.pypi_options().no_build_isolation;
+                    assert!(no_build_isolation.contains(&package("bar")));
+                    assert_eq!(
+                        no_build_isolation.contains(&package("foo")),
+                        env.name() == "extra"
+                    );
+                },
+            ),
+        ];
+
+        for (name, apply, check) in cases {
+            let workspace = apply(manifest.clone());
+            for env in workspace.environments() {
+                println!("{name} in {}", env.name());
+                check(&env);
+            }
+        }
+    }
+
     #[test]
     fn test_validate_platform() {
         let manifest = Workspace::from_str(
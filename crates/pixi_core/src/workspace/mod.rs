@@ -26,7 +26,7 @@ use crate::{
     repodata::Repodata,
 };
 use async_once_cell::OnceCell as AsyncCell;
-pub use discovery::{DiscoveryStart, WorkspaceLocator, WorkspaceLocatorError};
+pub use discovery::{DiscoveryReason, DiscoveryStart, WorkspaceLocator, WorkspaceLocatorError};
 pub use environment::Environment;
 pub use has_project_ref::HasWorkspaceRef;
 use indexmap::Equivalent;
@@ -40,9 +40,11 @@ use pixi_config::{Config, RunPostLinkScripts};
 use pixi_consts::consts;
 use pixi_diff::LockFileDiff;
 use pixi_manifest::{
-    AssociateProvenance, BuildVariantSource, EnvironmentName, Environments, ExplicitManifestError,
-    HasWorkspaceManifest, LoadManifestsError, ManifestProvenance, Manifests, PackageManifest,
+    AssociateProvenance, BuildVariantSource, ChannelPriority, CondaPypiOverrides, EnvironmentName,
+    Environments, ExcludeNewer, ExplicitManifestError, HasWorkspaceManifest, LoadManifestsError,
+    LockProfile, LockProfileName, ManifestProvenance, Manifests, PackageManifest, SolveStrategy,
     SpecType, WithProvenance, WithWarnings, WorkspaceManifest,
+    pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation, PypiOptions},
 };
 use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
 use pixi_spec::SourceSpec;
@@ -51,12 +53,12 @@ use pixi_utils::{
     reqwest::LazyReqwestClient,
     variants::{VariantConfig, VariantValue},
 };
-use pypi_mapping::{ChannelName, CustomMapping, MappingLocation, MappingSource};
+use pypi_mapping::{ChannelName, CustomMapping, MappingLocation, MappingOverrides, MappingSource};
 use rattler_conda_types::{Channel, ChannelConfig, MatchSpec, PackageName, Platform, Version};
 use rattler_lock::{LockFile, LockedPackageRef};
 use rattler_networking::{LazyClient, s3_middleware};
 use rattler_repodata_gateway::Gateway;
-use rattler_virtual_packages::{VirtualPackageOverrides, VirtualPackages};
+use rattler_virtual_packages::VirtualPackages;
 pub use solve_group::SolveGroup;
 use tokio::sync::Semaphore;
 use url::Url;
@@ -166,6 +168,9 @@ pub struct Workspace {
     /// The cache that contains mapping
     mapping_source: OnceCell<MappingSource>,
 
+    /// The cache that contains the mapping overrides
+    mapping_overrides: OnceCell<Option<Arc<MappingOverrides>>>,
+
     /// The global configuration as loaded from the config file(s)
     config: Config,
 
@@ -177,6 +182,10 @@ pub struct Workspace {
 
     /// Optional backend override for testing purposes
     backend_override: Option<BackendOverride>,
+
+    /// The lock profile that is used, if any. This determines which lock-file
+    /// is read and written.
+    lock_profile: Option<LockProfileName>,
 }
 
 impl Debug for Workspace {
@@ -243,11 +252,13 @@ impl Workspace {
             package: manifest.package,
             env_vars,
             mapping_source: Default::default(),
+            mapping_overrides: Default::default(),
             config,
             s3_config,
             repodata_gateway: Default::default(),
             concurrent_downloads_semaphore: OnceCell::default(),
             backend_override: None,
+            lock_profile: None,
         }
     }
 
@@ -290,6 +301,125 @@ impl Workspace {
         self
     }
 
+    /// Overrides the solve strategy of all environments in the workspace.
+    ///
+    /// This only affects the in-memory manifest, the manifest on disk is left
+    /// untouched. Because the strategy is recorded in the lock-file, any
+    /// environment that was locked with a different strategy is re-solved.
+    pub fn with_solve_strategy(mut self, solve_strategy: SolveStrategy) -> Self {
+        self.workspace.value.workspace.solve_strategy = Some(solve_strategy);
+        for feature in self.workspace.value.features.values_mut() {
+            feature.solve_strategy = Some(solve_strategy);
+        }
+        self
+    }
+
+    /// Overrides the channel priority of all environments in the workspace.
+    ///
+    /// Like [`Self::with_solve_strategy`] this only affects the in-memory
+    /// manifest. Environments that were locked with a different priority are
+    /// re-solved.
+    pub fn with_channel_priority(mut self, channel_priority: ChannelPriority) -> Self {
+        self.workspace.value.workspace.channel_priority = Some(channel_priority);
+        for feature in self.workspace.value.features.values_mut() {
+            feature.channel_priority = Some(channel_priority);
+        }
+        self
+    }
+
+    /// Overrides the `exclude-newer` date of the workspace, ignoring all conda
+    /// packages and PyPI releases that were published after it.
+    ///
+    /// Like [`Self::with_solve_strategy`] this only affects the in-memory
+    /// manifest. Environments that were locked with a different date are
+    /// re-solved.
+    pub fn with_exclude_newer(mut self, exclude_newer: ExcludeNewer) -> Self {
+        self.workspace.value.workspace.exclude_newer = Some(exclude_newer);
+        self
+    }
+
+    /// Uses the lock-file of the given lock profile instead of the default
+    /// lock-file, and applies the settings of the profile.
+    ///
+    /// Like [`Self::with_solve_strategy`] the settings only affect the
+    /// in-memory manifest.
+    pub fn with_lock_profile(mut self, name: LockProfileName, profile: &LockProfile) -> Self {
+        if let Some(solve_strategy) = profile.solve_strategy {
+            self = self.with_solve_strategy(solve_strategy);
+        }
+        if let Some(exclude_newer) = profile.exclude_newer {
+            self = self.with_exclude_newer(exclude_newer);
+        }
+        self.lock_profile = Some(name);
+        self
+    }
+
+    /// Returns the lock profile that is used, if any.
+    pub fn lock_profile(&self) -> Option<&LockProfileName> {
+        self.lock_profile.as_ref()
+    }
+
+    /// Restricts the PyPI distributions that may be used by all environments
+    /// in the workspace, in addition to the `no-build` and `no-binary` from
+    /// the manifest.
+    ///
+    /// This only affects the in-memory manifest. Environments that were
+    /// locked with source distributions that are no longer allowed are
+    /// re-solved.
+    pub fn with_pypi_build_options(mut self, no_build: &NoBuild, no_binary: &NoBinary) -> Self {
+        let restrict = |options: &mut PypiOptions| {
+            options.no_build = Some(options.no_build.take().unwrap_or_default().union(no_build));
+            options.no_binary = Some(
+                options
+                    .no_binary
+                    .take()
+                    .unwrap_or_default()
+                    .union(no_binary),
+            );
+        };
+
+        // Features without pypi-options use the ones of the workspace.
+        restrict(
+            self.workspace
+                .value
+                .workspace
+                .pypi_options
+                .get_or_insert_default(),
+        );
+        for feature in self.workspace.value.features.values_mut() {
+            if let Some(options) = feature.pypi_options.as_mut() {
+                restrict(options);
+            }
+        }
+        self
+    }
+
+    /// Builds the given PyPI packages without build isolation, in addition to
+    /// the `no-build-isolation` of the manifest.
+    ///
+    /// This only affects the in-memory manifest. Build isolation is not
+    /// recorded in the lock-file, so the environments are not re-solved.
+    pub fn with_no_build_isolation(mut self, no_build_isolation: &NoBuildIsolation) -> Self {
+        let extend = |options: &mut PypiOptions| {
+            options.no_build_isolation = options.no_build_isolation.union(no_build_isolation);
+        };
+
+        // Features without pypi-options use the ones of the workspace.
+        extend(
+            self.workspace
+                .value
+                .workspace
+                .pypi_options
+                .get_or_insert_default(),
+        );
+        for feature in self.workspace.value.features.values_mut() {
+            if let Some(options) = feature.pypi_options.as_mut() {
+                extend(options);
+            }
+        }
+        self
+    }
+
     /// Sets the backend override for this workspace. This is primarily used
     /// for testing purposes to inject custom build backends.
     pub fn with_backend_override(mut self, backend_override: BackendOverride) -> Self {
@@ -332,10 +462,16 @@ impl Workspace {
     /// Create the detached-environments path for this project if it is set in
     /// the config
     fn detached_environments_path(&self) -> Option<PathBuf> {
+        self.detached_environments_path_for(self.display_name())
+    }
+
+    /// Returns the detached-environments path the project would use if it was
+    /// named `name`.
+    fn detached_environments_path_for(&self, name: &str) -> Option<PathBuf> {
         if let Ok(Some(detached_environments_path)) = self.config().detached_environments().path() {
             Some(detached_environments_path.join(format!(
                 "{}-{}",
-                self.display_name(),
+                name,
                 xxh3_64(self.root.to_string_lossy().as_bytes())
             )))
         } else {
@@ -343,6 +479,41 @@ impl Workspace {
         }
     }
 
+    /// Removes the detached environments of the project that were installed
+    /// under `old_name`, after the project was renamed. The
+    /// detached-environments directory is keyed by the name of the project,
+    /// and the environments can't be moved to the new directory because
+    /// their scripts and activation files contain the old path. They are
+    /// installed again in the new directory on the next install.
+    ///
+    /// Returns the removed directory if there was one.
+    pub fn remove_renamed_detached_environments(
+        &self,
+        old_name: &str,
+    ) -> miette::Result<Option<PathBuf>> {
+        let (Some(old_path), Some(new_path)) = (
+            self.detached_environments_path_for(old_name),
+            self.detached_environments_path(),
+        ) else {
+            return Ok(None);
+        };
+        if old_path == new_path || !old_path.is_dir() {
+            return Ok(None);
+        }
+
+        fs_err::remove_dir_all(&old_path).into_diagnostic()?;
+
+        // The `.pixi/envs` symlink still points to the old directory.
+        let default_envs_dir = self.default_environments_dir();
+        if default_envs_dir.is_symlink() {
+            fs_err::remove_file(&default_envs_dir).into_diagnostic()?;
+            #[cfg(not(windows))]
+            create_symlink(&new_path.join(consts::ENVIRONMENTS_DIR), &default_envs_dir);
+        }
+
+        Ok(Some(old_path))
+    }
+
     /// Returns the default environment directory without interacting with
     /// config.
     pub fn default_environments_dir(&self) -> PathBuf {
@@ -350,7 +521,16 @@ impl Workspace {
     }
 
     /// Returns the environment directory
+    ///
+    /// Every lock profile installs its environments in a directory of its
+    /// own, `.pixi/envs/<profile>/<environment>`, so switching between the
+    /// profiles doesn't reinstall the environments.
     pub fn environments_dir(&self) -> PathBuf {
+        self.lock_profile_dir(self.shared_environments_dir())
+    }
+
+    /// Returns the environment directory of all lock profiles.
+    fn shared_environments_dir(&self) -> PathBuf {
         let default_envs_dir = self.default_environments_dir();
 
         // Early out if detached-environments is not set
@@ -402,16 +582,40 @@ impl Workspace {
     pub fn solve_group_environments_dir(&self) -> PathBuf {
         // If the detached-environments path is set, use it instead of the default
         // directory.
-        if let Some(detached_environments_path) = self.detached_environments_path() {
-            return detached_environments_path.join(consts::SOLVE_GROUP_ENVIRONMENTS_DIR);
+        let dir = match self.detached_environments_path() {
+            Some(detached_environments_path) => {
+                detached_environments_path.join(consts::SOLVE_GROUP_ENVIRONMENTS_DIR)
+            }
+            None => self.default_solve_group_environments_dir(),
+        };
+        self.lock_profile_dir(dir)
+    }
+
+    /// Returns the subdirectory of `dir` for the lock profile that is used, or
+    /// `dir` itself without a lock profile.
+    fn lock_profile_dir(&self, dir: PathBuf) -> PathBuf {
+        match &self.lock_profile {
+            Some(profile) => dir.join(profile.as_str()),
+            None => dir,
         }
-        self.default_solve_group_environments_dir()
     }
 
     /// Returns the path to the lock file of the project
-    /// [consts::PROJECT_LOCK_FILE]
+    /// [consts::PROJECT_LOCK_FILE], or the lock file of the lock profile that
+    /// is used.
     pub fn lock_file_path(&self) -> PathBuf {
-        self.root.join(consts::PROJECT_LOCK_FILE)
+        match &self.lock_profile {
+            Some(profile) => self.root.join(profile.lock_file_name()),
+            None => self.root.join(consts::PROJECT_LOCK_FILE),
+        }
+    }
+
+    /// Returns the path to the file next to the lock file that records the
+    /// inputs of the solve, see [`crate::lock_file::LockFileMetadata`].
+    pub fn lock_file_metadata_path(&self) -> PathBuf {
+        let mut path = self.lock_file_path().into_os_string();
+        path.push(".meta.json");
+        PathBuf::from(path)
     }
 
     /// Returns the default environment of the project.
@@ -461,7 +665,7 @@ impl Workspace {
     }
 
     /// Returns all the solve groups in the project.
-    pub(crate) fn solve_groups(&self) -> Vec<SolveGroup<'_>> {
+    pub fn solve_groups(&self) -> Vec<SolveGroup<'_>> {
         self.workspace
             .value
             .solve_groups
@@ -560,7 +764,7 @@ impl Workspace {
             if tool_platform.only_platform() == Platform::current().only_platform() {
                 // If the tool platform is the same as the current platform, we just assume the
                 // same virtual packages apply.
-                VirtualPackages::detect(&VirtualPackageOverrides::from_env())
+                VirtualPackages::detect(&self.config().virtual_package_overrides())
                     .unwrap_or_default()
                     .into_generic_virtual_packages()
                     .collect()
@@ -588,6 +792,7 @@ impl Workspace {
                 RunPostLinkScripts::Insecure => true,
                 RunPostLinkScripts::False => false,
             })
+            .with_link_mode(self.config().link_mode())
             .with_tool_platform(tool_platform, tool_virtual_packages))
     }
 
@@ -616,6 +821,11 @@ impl Workspace {
         self.pixi_dir().join(consts::TASK_CACHE_DIR)
     }
 
+    /// Returns the directory the output of tasks is logged to by default.
+    pub fn task_logs_folder(&self) -> PathBuf {
+        self.pixi_dir().join(consts::TASK_LOGS_DIR)
+    }
+
     pub fn activation_env_cache_folder(&self) -> PathBuf {
         self.pixi_dir().join(consts::ACTIVATION_ENV_CACHE_DIR)
     }
@@ -688,31 +898,12 @@ impl Workspace {
                     let mapping = channel_to_location_map
                         .iter()
                         .map(|(channel, mapping_location)| {
-                            let url_or_path = if mapping_location.starts_with("https://")
-                                || mapping_location.starts_with("http://")
-                                || mapping_location.starts_with("file://")
-                            {
-                                match Url::parse(mapping_location) {
-                                    Ok(url) => MappingLocation::Url(url),
-                                    Err(err) => {
-                                        return Err(err).into_diagnostic().context(format!(
-                                            "Could not convert {mapping_location} to URL"
-                                        ));
-                                    }
-                                }
-                            } else {
-                                let path = PathBuf::from(mapping_location);
-                                let abs_path = if path.is_relative() {
-                                    channel_config.root_dir.join(path)
-                                } else {
-                                    path
-                                };
-                                MappingLocation::Path(abs_path)
-                            };
-
                             Ok((
                                 channel.canonical_name().trim_end_matches('/').into(),
-                                url_or_path,
+                                mapping_location_from_str(
+                                    mapping_location,
+                                    &channel_config.root_dir,
+                                )?,
                             ))
                         })
                         .collect::<miette::Result<HashMap<ChannelName, MappingLocation>>>()?;
@@ -727,6 +918,30 @@ impl Workspace {
         })
     }
 
+    /// Returns the conda to pypi name overrides of the workspace, these apply
+    /// to the packages of every channel and take precedence over the mapping
+    /// source.
+    pub fn pypi_name_mapping_overrides(&self) -> miette::Result<Option<Arc<MappingOverrides>>> {
+        self.mapping_overrides
+            .get_or_try_init(|| {
+                let location = match &self.workspace.value.workspace.conda_pypi_overrides {
+                    None => return Ok(None),
+                    Some(CondaPypiOverrides::Location(location)) => {
+                        mapping_location_from_str(location, self.root())?
+                    }
+                    Some(CondaPypiOverrides::Map(map)) => MappingLocation::Memory(
+                        map.iter()
+                            .map(|(name, pypi_name)| {
+                                (name.as_normalized().to_string(), Some(pypi_name.clone()))
+                            })
+                            .collect(),
+                    ),
+                };
+                Ok(Some(Arc::new(MappingOverrides::new(location))))
+            })
+            .cloned()
+    }
+
     /// Constructs a new lock-file where some of the constraints have been
     /// removed.
     fn unlock_packages(
@@ -763,6 +978,27 @@ impl Workspace {
     }
 }
 
+/// Interprets the location of a mapping as a URL if it has an http(s) or file
+/// scheme, and as a path relative to `root_dir` otherwise.
+fn mapping_location_from_str(location: &str, root_dir: &Path) -> miette::Result<MappingLocation> {
+    if location.starts_with("https://")
+        || location.starts_with("http://")
+        || location.starts_with("file://")
+    {
+        Url::parse(location)
+            .map(MappingLocation::Url)
+            .into_diagnostic()
+            .context(format!("Could not convert {location} to URL"))
+    } else {
+        let path = PathBuf::from(location);
+        Ok(MappingLocation::Path(if path.is_relative() {
+            root_dir.join(path)
+        } else {
+            path
+        }))
+    }
+}
+
 pub struct UpdateDeps {
     pub implicit_constraints: HashMap<String, String>,
     pub lock_file_diff: LockFileDiff,
@@ -1267,6 +1503,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mapping_overrides() {
+        let file_contents = r#"
+            [workspace]
+            name = "foo"
+            channels = ["conda-forge"]
+            platforms = []
+            conda-pypi-overrides = { my-private-numpy = "numpy" }
+            "#;
+        let workspace = Workspace::from_str(Path::new("pixi.toml"), file_contents).unwrap();
+        let overrides = workspace.pypi_name_mapping_overrides().unwrap().unwrap();
+        assert_eq!(
+            overrides.location,
+            MappingLocation::Memory(HashMap::from_iter([(
+                "my-private-numpy".to_string(),
+                Some("numpy".to_string())
+            )]))
+        );
+
+        let file_contents = r#"
+            [workspace]
+            name = "foo"
+            channels = ["conda-forge"]
+            platforms = []
+            conda-pypi-overrides = "overrides.json"
+            "#;
+        let workspace = Workspace::from_str(Path::new("pixi.toml"), file_contents).unwrap();
+        let overrides = workspace.pypi_name_mapping_overrides().unwrap().unwrap();
+        assert_eq!(
+            overrides.location,
+            MappingLocation::Path(workspace.root().join("overrides.json"))
+        );
+
+        // The overrides are independent of the mapping source
+        assert!(matches!(
+            workspace.pypi_name_mapping_source().unwrap(),
+            MappingSource::Prefix
+        ));
+    }
+
     #[test]
     fn test_mapping_ensure_feature_channels_also_checked() {
         let file_contents = r#"
@@ -1345,4 +1621,89 @@ mod tests {
             ".pixi directory should be in the symlink's parent directory"
         );
     }
+
+    #[test]
+    fn test_lock_profile_environments_dir() {
+        let workspace = Workspace::from_str(
+            Path::new("/workspace")
+                .join(consts::WORKSPACE_MANIFEST)
+                .as_path(),
+            r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [workspace.lock-profiles.minimal]
+        solve-strategy = "lowest"
+        "#,
+        )
+        .unwrap();
+        let default_envs_dir = workspace.environments_dir();
+        let default_solve_groups_dir = workspace.solve_group_environments_dir();
+
+        let name: LockProfileName = "minimal".parse().unwrap();
+        let profile = workspace.workspace.value.workspace.lock_profiles[&name].clone();
+        let workspace = workspace.with_lock_profile(name, &profile);
+        assert_eq!(
+            workspace.environments_dir(),
+            default_envs_dir.join("minimal")
+        );
+        assert_eq!(
+            workspace.solve_group_environments_dir(),
+            default_solve_groups_dir.join("minimal")
+        );
+        assert_eq!(
+            workspace.default_environment().dir(),
+            default_envs_dir.join("minimal").join("default")
+        );
+    }
+
+    #[test]
+    fn test_remove_renamed_detached_environments() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let detached_dir = temp_dir.path().join("detached");
+        let config = Config {
+            detached_environments: Some(pixi_config::DetachedEnvironments::Path(
+                detached_dir.clone(),
+            )),
+            ..Config::default()
+        };
+        let workspace = Workspace::from_str(
+            &temp_dir.path().join(consts::WORKSPACE_MANIFEST),
+            r#"
+        [workspace]
+        name = "new"
+        channels = []
+        platforms = []
+        "#,
+        )
+        .unwrap()
+        .with_cli_config(config);
+
+        // An environment that was installed before the workspace was renamed
+        // from `old`.
+        let old_path = workspace.detached_environments_path_for("old").unwrap();
+        let old_prefix = old_path.join(consts::ENVIRONMENTS_DIR).join("default");
+        fs_err::create_dir_all(old_prefix.join("conda-meta")).unwrap();
+
+        let removed = workspace
+            .remove_renamed_detached_environments("old")
+            .unwrap();
+        assert_eq!(removed, Some(old_path.clone()));
+        assert!(!old_path.exists());
+
+        // The environments are installed from scratch in the new directory
+        // instead of reusing a prefix that contains the old path.
+        let new_prefix = workspace.environments_dir().join("default");
+        assert!(!new_prefix.exists());
+
+        // Nothing is removed when the name didn't change.
+        assert_eq!(
+            workspace
+                .remove_renamed_detached_environments("new")
+                .unwrap(),
+            None
+        );
+    }
 }
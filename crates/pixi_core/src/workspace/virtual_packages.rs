@@ -19,7 +19,7 @@ use thiserror::Error;
 ///
 /// The method also takes into account system requirements specified in the
 /// project manifest.
-pub(crate) fn get_minimal_virtual_packages(
+pub fn get_minimal_virtual_packages(
     platform: Platform,
     system_requirements: &SystemRequirements,
 ) -> Vec<VirtualPackage> {
@@ -112,7 +112,7 @@ pub fn verify_current_platform_can_run_environment(
             lockfile,
             current_platform,
             environment.name(),
-            None,
+            Some(environment.workspace().config().virtual_package_overrides()),
         )?;
     }
 
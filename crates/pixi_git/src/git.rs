@@ -9,15 +9,19 @@ use reqwest::StatusCode;
 ///   * `GitDatabase` and `GitRepository` that represents a local clone of a remote repository's database.
 use std::{
     fmt::Display,
+    io::IsTerminal,
     path::{Path, PathBuf},
     process::Command,
     str::FromStr,
-    sync::LazyLock,
+    sync::{Arc, LazyLock},
 };
 use url::Url;
 
 use crate::{
     GitError,
+    credentials::{
+        Credentials, GitAuthenticationFailure, git_config_env, is_http, redact_credentials,
+    },
     sha::{GitOid, GitSha},
 };
 
@@ -25,6 +29,7 @@ use crate::{
 /// checkout is ready to go. See [`GitCheckout::reset`] for why we need this.
 const CHECKOUT_READY_LOCK: &str = ".ok";
 pub const GIT_DIR: &str = "GIT_DIR";
+pub const GIT_TERMINAL_PROMPT: &str = "GIT_TERMINAL_PROMPT";
 
 #[derive(Debug, thiserror::Error, Clone)]
 pub enum GitBinaryError {
@@ -194,14 +199,26 @@ impl Display for GitReference {
 /// A remote repository. It gets cloned into a local [`GitDatabase`].
 #[derive(PartialEq, Clone, Debug)]
 pub(crate) struct GitRemote {
-    /// URL to a remote repository.
+    /// URL to a remote repository, without credentials.
     url: Url,
+    /// The credentials to authenticate to the remote repository with.
+    credentials: Option<Arc<Credentials>>,
 }
 
 impl GitRemote {
     /// Creates an instance for a remote repository URL.
-    pub(crate) fn new(url: &Url) -> Self {
-        Self { url: url.clone() }
+    ///
+    /// The credentials are kept out of the URL, so it can be shown to the
+    /// user. They are passed to git separately when fetching.
+    pub(crate) fn new(url: &Url, credentials: Option<Arc<Credentials>>) -> Self {
+        let mut url = redact_credentials(url);
+        // SSH authenticates with a key, only the username belongs in the URL.
+        if !is_http(&url)
+            && let Some(username) = credentials.as_ref().and_then(|c| c.username())
+        {
+            let _ = url.set_username(username);
+        }
+        Self { url, credentials }
     }
 
     /// Fetches and checkouts to a reference or a revision from this remote
@@ -227,7 +244,13 @@ impl GitRemote {
         let locked_ref = locked_rev.map(|oid| GitReference::FullCommit(oid.to_string()));
         let reference = locked_ref.as_ref().unwrap_or(reference);
         if let Some(mut db) = db {
-            fetch(&mut db.repo, self.url.as_str(), reference, client)?;
+            fetch(
+                &mut db.repo,
+                &self.url,
+                self.credentials.as_deref(),
+                reference,
+                client,
+            )?;
 
             let resolved_commit_hash = match locked_rev {
                 Some(rev) => db.contains(rev).then_some(rev),
@@ -248,7 +271,13 @@ impl GitRemote {
 
         fs_err::create_dir_all(into)?;
         let mut repo = GitRepository::init(into)?;
-        fetch(&mut repo, self.url.as_str(), reference, client)?;
+        fetch(
+            &mut repo,
+            &self.url,
+            self.credentials.as_deref(),
+            reference,
+            client,
+        )?;
         let rev = match locked_rev {
             Some(rev) => rev,
             None => reference.resolve(&repo)?,
@@ -277,8 +306,15 @@ pub(crate) struct GitDatabase {
 }
 
 impl GitDatabase {
-    /// Checkouts to a revision at `destination` from this database.
-    pub(crate) fn copy_to(&self, rev: GitOid, destination: &Path) -> Result<GitCheckout, GitError> {
+    /// Checkouts to a revision at `destination` from this database. If
+    /// `sparse_checkout` is given only that subdirectory and the files at the
+    /// root of the repository are checked out.
+    pub(crate) fn copy_to(
+        &self,
+        rev: GitOid,
+        destination: &Path,
+        sparse_checkout: Option<&str>,
+    ) -> Result<GitCheckout, GitError> {
         // If the existing checkout exists, and it is fresh, use it.
         // A non-fresh checkout can happen if the checkout operation was
         // interrupted. In that case, the checkout gets deleted and a new
@@ -289,7 +325,7 @@ impl GitDatabase {
             .filter(GitCheckout::is_fresh)
         {
             Some(co) => co,
-            None => GitCheckout::clone_into(destination, self, rev)?,
+            None => GitCheckout::clone_into(destination, self, rev, sparse_checkout)?,
         };
         Ok(checkout)
     }
@@ -383,7 +419,12 @@ impl GitCheckout {
 
     /// Clone a repo for a `revision` into a local path from a `database`.
     /// This is a filesystem-to-filesystem clone.
-    fn clone_into(into: &Path, database: &GitDatabase, revision: GitOid) -> Result<Self, GitError> {
+    fn clone_into(
+        into: &Path,
+        database: &GitDatabase,
+        revision: GitOid,
+        sparse_checkout: Option<&str>,
+    ) -> Result<Self, GitError> {
         tracing::debug!("cloning into {:?} from {:?}", database.repo.path, into);
         let dirname = into.parent().expect("into path must have a parent");
         fs_err::create_dir_all(dirname)?;
@@ -394,9 +435,14 @@ impl GitCheckout {
         // Perform a local clone of the repository, which will attempt to use
         // hardlinks to set up the repository. This should speed up the clone operation
         // quite a bit if it works.
-        let output = Command::new(GIT.as_ref().map_err(|e| e.clone())?)
-            .arg("clone")
-            .arg("--local")
+        let mut command = Command::new(GIT.as_ref().map_err(|e| e.clone())?);
+        command.arg("clone").arg("--local");
+        // The files are checked out by the reset below, once the sparse
+        // checkout is configured.
+        if sparse_checkout.is_some() {
+            command.arg("--no-checkout");
+        }
+        let output = command
             // Make sure to pass the local file path and not a file://... url. If given a url,
             // Git treats the repository as a remote origin and gets confused because we don't
             // have a HEAD checked out.
@@ -407,6 +453,19 @@ impl GitCheckout {
         tracing::debug!("output after cloning {:?}", output);
 
         let repo = GitRepository::open(into)?;
+        if let Some(subdirectory) = sparse_checkout {
+            // Check out only the subdirectory, in cone mode the files at the
+            // root of the repository are checked out as well.
+            let output = Command::new(GIT.as_ref().map_err(|e| e.clone())?)
+                .arg("sparse-checkout")
+                .arg("set")
+                .arg("--cone")
+                .arg("--")
+                .arg(subdirectory)
+                .current_dir(&repo.path)
+                .output()?;
+            tracing::debug!("output after setting up the sparse checkout {:?}", output);
+        }
         let checkout = GitCheckout::new(revision, repo);
         checkout.reset()?;
         Ok(checkout)
@@ -472,14 +531,16 @@ impl GitCheckout {
 /// * Turns [`GitReference`] into refspecs accordingly.
 /// * Dispatches `git fetch` using the git CLI.
 ///
-/// The `remote_url` argument is the git remote URL where we want to fetch from.
+/// The `remote_url` argument is the git remote URL where we want to fetch from,
+/// the `credentials` to authenticate with are passed to git separately.
 pub(crate) fn fetch(
     repo: &mut GitRepository,
-    remote_url: &str,
+    remote_url: &Url,
+    credentials: Option<&Credentials>,
     reference: &GitReference,
     client: &LazyClient,
 ) -> Result<(), GitError> {
-    let oid_to_fetch = match github_fast_path(repo, remote_url, reference, client) {
+    let oid_to_fetch = match github_fast_path(repo, remote_url.as_str(), reference, client) {
         Ok(FastPathRev::UpToDate) => return Ok(()),
         Ok(FastPathRev::NeedsFetch(rev)) => Some(rev),
         Ok(FastPathRev::Indeterminate) => None,
@@ -567,14 +628,27 @@ pub(crate) fn fetch(
         repo.path.display()
     );
     let result = match refspec_strategy {
-        RefspecStrategy::All => fetch_with_cli(repo, remote_url, refspecs.as_slice(), tags),
+        RefspecStrategy::All => fetch_with_cli(
+            repo,
+            remote_url,
+            credentials,
+            refspecs.as_slice(),
+            tags,
+            client,
+        ),
         RefspecStrategy::First => {
             // Try each refspec
             let mut errors = refspecs
                 .iter()
                 .map_while(|refspec| {
-                    let fetch_result =
-                        fetch_with_cli(repo, remote_url, std::slice::from_ref(refspec), tags);
+                    let fetch_result = fetch_with_cli(
+                        repo,
+                        remote_url,
+                        credentials,
+                        std::slice::from_ref(refspec),
+                        tags,
+                        client,
+                    );
 
                     // Stop after the first success and log failures
                     match fetch_result {
@@ -607,9 +681,11 @@ pub(crate) fn fetch(
 /// Attempts to use `git` CLI installed on the system to fetch a repository.
 fn fetch_with_cli(
     repo: &mut GitRepository,
-    url: &str,
+    url: &Url,
+    credentials: Option<&Credentials>,
     refspecs: &[String],
     tags: bool,
+    client: &LazyClient,
 ) -> Result<(), GitError> {
     let mut cmd = Command::new(GIT.as_ref().map_err(|err| err.clone())?);
     cmd.arg("fetch");
@@ -618,7 +694,7 @@ fn fetch_with_cli(
     }
     cmd.arg("--force") // handle force pushes
         .arg("--update-head-ok") // see discussion in #2078
-        .arg(url)
+        .arg(url.as_str())
         .args(refspecs)
         //     // If cargo is run by git (for example, the `exec` command in `git
         //     // rebase`), the GIT_DIR is set by git and will point to the wrong
@@ -627,12 +703,44 @@ fn fetch_with_cli(
         .env_remove(GIT_DIR)
         .current_dir(&repo.path);
 
+    // Without a terminal git can't ask for credentials, fail instead of
+    // waiting for input that never comes, e.g. in CI.
+    if !std::io::stdin().is_terminal() {
+        cmd.env(GIT_TERMINAL_PROMPT, "0");
+    }
+
+    // Pass the credentials through the environment, arguments of a process
+    // can be read by other users.
+    if let Some(credentials) = credentials
+        && is_http(url)
+    {
+        cmd.envs(git_config_env(&[credentials.git_config(url)]));
+    }
+
     // // We capture the output to avoid streaming it to the user's console during clones.
     // // The required `on...line` callbacks currently do nothing.
     // // The output appears to be included in error messages by default.
     let output = cmd.output()?;
     if !output.status.success() {
         let stderr = String::from_utf8(output.stderr)?;
+        let failure = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .ok()
+            .and_then(|runtime| {
+                runtime.block_on(GitAuthenticationFailure::probe(
+                    url,
+                    credentials,
+                    client.client(),
+                ))
+            });
+        if let Some(failure) = failure {
+            return Err(GitError::Authentication {
+                url: url.to_string(),
+                stderr,
+                failure,
+            });
+        }
         return Err(GitError::Fetch(url.to_string(), stderr));
     }
     tracing::debug!("git fetch output: {:?}", output);
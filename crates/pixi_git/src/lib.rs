@@ -31,6 +31,9 @@ pub struct GitUrl {
     reference: GitReference,
     /// The precise commit to use, if known.
     precise: Option<GitSha>,
+    /// The subdirectory to check out, if only a part of the repository is
+    /// needed.
+    sparse_checkout: Option<String>,
 }
 
 impl GitUrl {
@@ -41,6 +44,7 @@ impl GitUrl {
             repository,
             reference,
             precise,
+            sparse_checkout: None,
         }
     }
 
@@ -50,6 +54,7 @@ impl GitUrl {
             repository,
             reference,
             precise: Some(precise),
+            sparse_checkout: None,
         }
     }
 
@@ -67,6 +72,14 @@ impl GitUrl {
         self
     }
 
+    /// Only check out the given subdirectory of the repository, together with
+    /// the files at the root of the repository.
+    #[must_use]
+    pub fn with_sparse_checkout(mut self, subdirectory: Option<String>) -> Self {
+        self.sparse_checkout = subdirectory;
+        self
+    }
+
     /// Return the [`Url`] of the Git repository.
     pub fn repository(&self) -> &Url {
         &self.repository
@@ -81,6 +94,12 @@ impl GitUrl {
     pub fn precise(&self) -> Option<GitSha> {
         self.precise
     }
+
+    /// Return the subdirectory that is checked out, if only a part of the
+    /// repository is checked out.
+    pub fn sparse_checkout(&self) -> Option<&str> {
+        self.sparse_checkout.as_deref()
+    }
 }
 
 impl TryFrom<Url> for GitUrl {
@@ -191,6 +210,13 @@ pub enum GitError {
     #[error("failed to fetch {0}: {1}")]
     Fetch(String, String),
 
+    #[error("failed to fetch {url}: {stderr}\n{}", failure.help())]
+    Authentication {
+        url: String,
+        stderr: String,
+        failure: credentials::GitAuthenticationFailure,
+    },
+
     #[error(transparent)]
     UrlParse(#[from] ::url::ParseError),
 
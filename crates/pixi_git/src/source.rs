@@ -4,7 +4,6 @@ use rattler_networking::LazyClient;
 /// This module expose `GitSource` type that represents a remote Git source that
 /// can be checked out locally.
 use std::{
-    borrow::Cow,
     hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
     sync::Arc,
@@ -13,7 +12,7 @@ use tracing::instrument;
 
 use crate::{
     GitError, GitUrl, Reporter,
-    credentials::GIT_STORE,
+    credentials::{Credentials, GIT_STORE, redact_credentials},
     git::GitRemote,
     resolver::RepositoryReference,
     sha::{GitOid, GitSha},
@@ -53,7 +52,7 @@ impl GitSource {
     }
 
     /// Fetch the underlying Git repository at the given revision.
-    #[instrument(skip(self), fields(repository = %self.git.repository, rev = self.git.precise.map(tracing::field::display)))]
+    #[instrument(skip(self), fields(repository = %redact_credentials(&self.git.repository), rev = self.git.precise.map(tracing::field::display)))]
     pub fn fetch(self) -> Result<Fetch, GitError> {
         // Compute the canonical URL for the repository.
         let canonical = RepositoryUrl::new(&self.git.repository);
@@ -62,21 +61,19 @@ impl GitSource {
         let ident = cache_digest(&canonical);
         let db_path = self.cache.join("db").join(&ident);
 
-        // Authenticate the URL, if necessary.
-        let remote = if let Some(credentials) = GIT_STORE.get(&canonical) {
-            Cow::Owned(credentials.apply(self.git.repository.clone()))
-        } else {
-            Cow::Borrowed(&self.git.repository)
-        };
+        // Authenticate to the remote, if necessary.
+        let credentials = GIT_STORE
+            .get(&canonical)
+            .or_else(|| Credentials::from_url(&self.git.repository).map(Arc::new));
 
-        let remote = GitRemote::new(&remote);
+        let remote = GitRemote::new(&self.git.repository, credentials);
         let (db, actual_rev, task) = match (self.git.precise, remote.db_at(&db_path).ok()) {
             // If we have a locked revision, and we have a preexisting database
             // which has that revision, then no update needs to happen.
             (Some(rev), Some(db)) if db.contains(rev.into()) => {
                 tracing::debug!(
                     "Using existing Git source `{}` pointed at `{}`",
-                    self.git.repository,
+                    remote.url(),
                     rev
                 );
                 (db, rev, None)
@@ -87,7 +84,7 @@ impl GitSource {
             // situation that we have a locked revision but the database
             // doesn't have it.
             (locked_rev, db) => {
-                tracing::debug!("Updating Git source `{}`", self.git.repository);
+                tracing::debug!("Updating Git source `{}`", remote.url());
 
                 // Report the checkout operation to the reporter.
                 let task = self.reporter.as_ref().map(|reporter| {
@@ -113,18 +110,28 @@ impl GitSource {
         // Check out `actual_rev` from the database to a scoped location on the
         // filesystem. This will use hard links and such to ideally make the
         // checkout operation here pretty fast.
-        let checkout_path = self
-            .cache
-            .join("checkouts")
-            .join(&ident)
-            .join(short_id.as_str());
+        // A sparse checkout only contains a part of the repository, so it is
+        // stored separately from a full checkout of the same revision.
+        let checkouts = self.cache.join("checkouts").join(&ident);
+        let checkout_path = match self.git.sparse_checkout() {
+            Some(subdirectory) => {
+                let mut hasher = DefaultHasher::new();
+                subdirectory.hash(&mut hasher);
+                checkouts.join(format!("{short_id}-sparse-{:x}", hasher.finish()))
+            }
+            None => checkouts.join(short_id.as_str()),
+        };
 
         tracing::debug!(
             "Copying git revision `{}` to path `{}`",
             actual_rev,
             checkout_path.display()
         );
-        db.copy_to(actual_rev.into(), &checkout_path)?;
+        db.copy_to(
+            actual_rev.into(),
+            &checkout_path,
+            self.git.sparse_checkout(),
+        )?;
 
         // Report the checkout operation to the reporter.
         if let Some(task) = task
@@ -133,7 +140,7 @@ impl GitSource {
             reporter.on_checkout_complete(remote.url(), short_id.as_str(), task);
         }
 
-        tracing::trace!("Finished fetching Git source `{}`", self.git.repository);
+        tracing::trace!("Finished fetching Git source `{}`", remote.url());
 
         Ok(Fetch {
             repository: RepositoryReference {
@@ -3,7 +3,7 @@ use crate::{
     BinDir, StateChange,
     trampoline::{Configuration, Trampoline},
 };
-use indexmap::IndexSet;
+use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use miette::IntoDiagnostic;
 use pixi_utils::{
@@ -86,10 +86,14 @@ pub struct ScriptExecMapping {
 /// Create the executables trampolines by running the activation scripts,
 /// recording this information in the trampoline metadata,
 /// and saving both the trampoline and the metadata.
+///
+/// The environment variables of `env` are set on top of the variables of the
+/// activation.
 pub(crate) async fn create_executable_trampolines(
     mapped_executables: &[ScriptExecMapping],
     prefix: &Prefix,
     env_name: &EnvironmentName,
+    env: &IndexMap<String, String>,
 ) -> miette::Result<StateChanges> {
     const IGNORE_CONDA_PREFIX_MARKER: &str = "global-ignore-conda-prefix";
 
@@ -139,6 +143,7 @@ pub(crate) async fn create_executable_trampolines(
         {
             env_for_trampoline.remove("CONDA_PREFIX");
         }
+        env_for_trampoline.extend(env.clone());
         let metadata = Configuration::new(exe, path_diff.clone(), env_for_trampoline);
 
         let exposed_name = Trampoline::name(global_script_path)?;
@@ -1,4 +1,4 @@
-use std::io::Write;
+use std::{io::Write, path::PathBuf};
 
 use fancy_display::FancyDisplay;
 use indexmap::{IndexMap, IndexSet};
@@ -7,7 +7,8 @@ use miette::{IntoDiagnostic, miette};
 use pixi_consts::consts;
 use pixi_core::environment::list::{PackageToOutput, print_package_table};
 use pixi_spec::PixiSpec;
-use rattler_conda_types::{PackageName, PrefixRecord, Version};
+use rattler_conda_types::{PackageName, Platform, PrefixRecord, Version};
+use serde::Serialize;
 
 use super::{EnvChanges, EnvState, EnvironmentName, Mapping, Project, project::ParsedEnvironment};
 use crate::common::find_package_records;
@@ -333,6 +334,110 @@ pub async fn list_all_global_environments(
     Ok(())
 }
 
+/// A global environment in the output of `pixi global list --json`.
+#[derive(Debug, Serialize)]
+pub struct GlobalEnvironmentOutput {
+    pub name: String,
+    /// The directory the environment is installed in.
+    pub prefix: PathBuf,
+    pub platform: Option<Platform>,
+    pub channels: Vec<String>,
+    pub dependencies: Vec<GlobalDependencyOutput>,
+    pub exposed: Vec<GlobalExposedOutput>,
+    pub shortcuts: Vec<String>,
+    /// The environment variables that are set in the exposed executables.
+    pub env: IndexMap<String, String>,
+    /// Whether the installation matches the manifest, if not `pixi global
+    /// sync` has to be run.
+    pub in_sync: bool,
+}
+
+/// A dependency of a global environment with its installed version.
+#[derive(Debug, Serialize)]
+pub struct GlobalDependencyOutput {
+    pub name: String,
+    /// The installed version, `None` if the package is not installed.
+    pub version: Option<String>,
+}
+
+/// An exposed executable of a global environment.
+#[derive(Debug, Serialize)]
+pub struct GlobalExposedOutput {
+    /// The name the executable is exposed as.
+    pub name: String,
+    /// The executable in the environment.
+    pub executable: String,
+}
+
+/// Collects the global environments for the JSON output of `pixi global
+/// list`, optionally filtered by a regex on their names.
+pub async fn global_environments_output(
+    project: &Project,
+    envs: Option<Vec<EnvironmentName>>,
+    regex: Option<String>,
+) -> miette::Result<Vec<GlobalEnvironmentOutput>> {
+    let mut project_envs = project.environments().clone();
+    project_envs.sort_by(|a, _, b, _| a.to_string().cmp(&b.to_string()));
+
+    if let Some(regex) = regex {
+        let regex = regex::Regex::new(&regex).into_diagnostic()?;
+        project_envs.retain(|env_name, _| regex.is_match(env_name.as_str()));
+    }
+
+    if let Some(envs) = envs {
+        project_envs.retain(|env_name, _| envs.contains(env_name));
+    }
+
+    let mut output = Vec::with_capacity(project_envs.len());
+    for (env_name, env) in project_envs {
+        let prefix = project.env_root.path().join(env_name.as_str());
+        let records = find_package_records(&prefix.join(consts::CONDA_META_DIR)).await?;
+
+        let dependencies = env
+            .dependencies
+            .specs
+            .keys()
+            .map(|name| GlobalDependencyOutput {
+                name: name.as_normalized().to_string(),
+                version: records
+                    .iter()
+                    .find(|rec| rec.repodata_record.package_record.name == *name)
+                    .map(|rec| rec.repodata_record.package_record.version.to_string()),
+            })
+            .collect();
+
+        output.push(GlobalEnvironmentOutput {
+            in_sync: project.environment_in_sync(&env_name).await?,
+            name: env_name.to_string(),
+            prefix,
+            platform: env.platform,
+            channels: env
+                .channels
+                .iter()
+                .map(|channel| channel.channel.to_string())
+                .collect(),
+            dependencies,
+            exposed: env
+                .exposed
+                .iter()
+                .map(|mapping| GlobalExposedOutput {
+                    name: mapping.exposed_name().to_string(),
+                    executable: mapping.executable_relname().to_string(),
+                })
+                .collect(),
+            shortcuts: env
+                .shortcuts
+                .iter()
+                .flatten()
+                .map(|name| name.as_normalized().to_string())
+                .collect(),
+            env: env.env,
+        });
+    }
+
+    Ok(output)
+}
+
 /// Display a dependency in a human-readable format.
 fn display_dependency(name: &PackageName, version: Option<Version>) -> String {
     if let Some(version) = version {
@@ -375,3 +480,99 @@ fn format_dependencies(
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, str::FromStr};
+
+    use rattler_conda_types::{PackageRecord, RepoDataRecord, VersionWithSource};
+    use url::Url;
+
+    use super::*;
+    use crate::{BinDir, EnvRoot};
+
+    fn install_record(prefix: &Path, name: &str, version: &str) {
+        let record = RepoDataRecord {
+            package_record: PackageRecord::new(
+                name.parse().unwrap(),
+                VersionWithSource::from_str(version).unwrap(),
+                String::from("h0_0"),
+            ),
+            file_name: format!("{name}-{version}-h0_0.conda"),
+            url: Url::from_str("https://conda.anaconda.org/conda-forge/linux-64/").unwrap(),
+            channel: Some(String::from("https://conda.anaconda.org/conda-forge/")),
+        };
+        let conda_meta = prefix.join(consts::CONDA_META_DIR);
+        fs_err::create_dir_all(&conda_meta).unwrap();
+        PrefixRecord::from_repodata_record(record, vec![])
+            .write_to_path(conda_meta.join(format!("{name}-{version}-h0_0.json")), true)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_global_environments_output() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let project = Project::from_str(
+            &PathBuf::from("dummy"),
+            r#"
+            [envs.python]
+            channels = ["conda-forge"]
+            [envs.python.dependencies]
+            python = "3.12.*"
+            [envs.python.exposed]
+            py = "python"
+
+            [envs.rattler-build]
+            channels = ["conda-forge"]
+            [envs.rattler-build.dependencies]
+            rattler-build = "*"
+            "#,
+            EnvRoot::new(tempdir.path().to_path_buf()).unwrap(),
+            BinDir::new(tempdir.path().to_path_buf()).unwrap(),
+        )
+        .unwrap();
+        install_record(&project.env_root.path().join("python"), "python", "3.12.1");
+        fs_err::create_dir_all(
+            project
+                .env_root
+                .path()
+                .join("rattler-build")
+                .join(consts::CONDA_META_DIR),
+        )
+        .unwrap();
+
+        let output = global_environments_output(&project, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            output
+                .iter()
+                .map(|env| env.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["python", "rattler-build"]
+        );
+
+        let python = &output[0];
+        assert_eq!(python.prefix, project.env_root.path().join("python"));
+        assert_eq!(python.channels, vec![String::from("conda-forge")]);
+        assert_eq!(python.dependencies.len(), 1);
+        assert_eq!(python.dependencies[0].name, "python");
+        assert_eq!(python.dependencies[0].version.as_deref(), Some("3.12.1"));
+        assert_eq!(python.exposed.len(), 1);
+        assert_eq!(python.exposed[0].name, "py");
+        assert_eq!(python.exposed[0].executable, "python");
+        // The exposed executable is missing from the bin directory.
+        assert!(!python.in_sync);
+
+        // Packages that are not installed have no version.
+        let rattler_build = &output[1];
+        assert_eq!(rattler_build.dependencies[0].version, None);
+
+        // The environments can be filtered by name.
+        let output = global_environments_output(&project, None, Some(String::from("^ratt")))
+            .await
+            .unwrap();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].name, "rattler-build");
+    }
+}
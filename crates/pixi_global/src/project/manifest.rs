@@ -415,6 +415,58 @@ impl Manifest {
         Ok(())
     }
 
+    /// Sets an environment variable that the trampolines of the environment
+    /// inject into the exposed executables
+    pub fn set_env_var(
+        &mut self,
+        env_name: &EnvironmentName,
+        key: &str,
+        value: &str,
+    ) -> miette::Result<()> {
+        // Update self.parsed
+        self.parsed
+            .envs
+            .get_mut(env_name)
+            .ok_or_else(|| {
+                miette::miette!("Environment {} doesn't exist", env_name.fancy_display())
+            })?
+            .env
+            .insert(key.to_string(), value.to_string());
+
+        // Update self.document
+        self.document.insert_into_inline_table(
+            &["envs", env_name.as_str(), "env"],
+            key,
+            toml_edit::Value::from(value),
+        )?;
+
+        tracing::debug!("Set environment variable {key} in toml document");
+        Ok(())
+    }
+
+    /// Removes an environment variable that the trampolines of the
+    /// environment inject into the exposed executables
+    pub fn remove_env_var(&mut self, env_name: &EnvironmentName, key: &str) -> miette::Result<()> {
+        // Update self.parsed
+        self.parsed
+            .envs
+            .get_mut(env_name)
+            .ok_or_else(|| {
+                miette::miette!("Environment {} doesn't exist", env_name.fancy_display())
+            })?
+            .env
+            .shift_remove(key)
+            .ok_or_else(|| miette::miette!("The environment variable {key} isn't set"))?;
+
+        // Update self.document
+        self.document
+            .get_or_insert_nested_table(&["envs", env_name.as_str(), "env"])?
+            .remove(key);
+
+        tracing::debug!("Removed environment variable {key} from toml document");
+        Ok(())
+    }
+
     /// Checks if an exposed name already exists in other environments
     pub fn shortcut_already_exists_in_other_envs(
         &self,
@@ -1093,6 +1145,37 @@ mod tests {
         assert_eq!(actual_platform, platform);
     }
 
+    #[test]
+    fn test_set_and_remove_env_var() {
+        let mut manifest = Manifest::default();
+        let env_name = EnvironmentName::from_str("test-env").unwrap();
+        manifest.add_environment(&env_name, None).unwrap();
+
+        manifest
+            .set_env_var(&env_name, "MAVEN_HOME", "/opt/maven")
+            .unwrap();
+
+        // Check document
+        let actual_value = manifest
+            .document
+            .get_or_insert_nested_table(&["envs", env_name.as_str(), "env"])
+            .unwrap()
+            .get("MAVEN_HOME")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert_eq!(actual_value, "/opt/maven");
+
+        // Check parsed
+        let env = &manifest.parsed.envs.get(&env_name).unwrap().env;
+        assert_eq!(env.get("MAVEN_HOME").unwrap(), "/opt/maven");
+
+        manifest.remove_env_var(&env_name, "MAVEN_HOME").unwrap();
+        assert!(manifest.parsed.envs.get(&env_name).unwrap().env.is_empty());
+        assert!(manifest.remove_env_var(&env_name, "MAVEN_HOME").is_err());
+    }
+
     #[test]
     fn test_add_channel() {
         let mut manifest = Manifest::default();
@@ -28,7 +28,7 @@ use pixi_command_dispatcher::{
 };
 use pixi_config::{Config, RunPostLinkScripts, default_channel_config, pixi_home};
 use pixi_consts::consts::{self};
-use pixi_core::repodata::Repodata;
+use pixi_core::{environment::update_history_file, repodata::Repodata};
 use pixi_manifest::PrioritizedChannel;
 use pixi_progress::global_multi_progress;
 use pixi_reporters::TopLevelProgress;
@@ -46,9 +46,7 @@ use rattler_conda_types::{
 use rattler_networking::LazyClient;
 use rattler_repodata_gateway::Gateway;
 // Removed unused rattler_solve imports
-use rattler_virtual_packages::{
-    DetectVirtualPackageError, VirtualPackage, VirtualPackageOverrides,
-};
+use rattler_virtual_packages::{DetectVirtualPackageError, VirtualPackage};
 use tokio::sync::Semaphore;
 use toml_edit::DocumentMut;
 
@@ -548,6 +546,7 @@ impl Project {
     /// We only need to detect virtual packages if the platform is the current
     /// one. Otherwise, we use an empty list
     pub(crate) fn virtual_packages_for(
+        &self,
         platform: &Platform,
     ) -> Result<Vec<GenericVirtualPackage>, DetectVirtualPackageError> {
         if platform
@@ -555,11 +554,13 @@ impl Project {
             .map(|p| p == Platform::current().only_platform().unwrap_or(""))
             .unwrap_or(false)
         {
-            Ok(VirtualPackage::detect(&VirtualPackageOverrides::default())?
-                .iter()
-                .cloned()
-                .map(GenericVirtualPackage::from)
-                .collect())
+            Ok(
+                VirtualPackage::detect(&self.config.virtual_package_overrides())?
+                    .iter()
+                    .cloned()
+                    .map(GenericVirtualPackage::from)
+                    .collect(),
+            )
         } else {
             Ok(vec![])
         }
@@ -611,7 +612,7 @@ impl Project {
 
         let build_environment = BuildEnvironment::simple(
             platform,
-            Self::virtual_packages_for(&platform).into_diagnostic()?,
+            self.virtual_packages_for(&platform).into_diagnostic()?,
         );
         // Create solve spec
         let solve_spec = PixiEnvironmentSpec {
@@ -659,6 +660,12 @@ impl Project {
 
         command_dispatcher.clear_reporter().await;
 
+        update_history_file(
+            prefix.root(),
+            &result.transaction,
+            self.config.global_channel_config(),
+        )?;
+
         let install_changes = get_install_changes(result.transaction);
         Ok(EnvironmentUpdate::new(install_changes, dependencies_names))
     }
@@ -1071,7 +1078,9 @@ impl Project {
             env_name.fancy_display()
         );
 
-        state_changes |= create_executable_trampolines(&script_mapping, &prefix, env_name).await?;
+        state_changes |=
+            create_executable_trampolines(&script_mapping, &prefix, env_name, &environment.env)
+                .await?;
 
         Ok(state_changes)
     }
@@ -1390,6 +1399,7 @@ impl Project {
                     RunPostLinkScripts::Insecure => true,
                     RunPostLinkScripts::False => false,
                 })
+                .with_link_mode(self.config().link_mode())
                 .with_reporter(TopLevelProgress::new(multi_progress, anchor_pb))
                 .finish())
         })
@@ -1561,6 +1571,45 @@ mod tests {
         Project::manifest_dir().unwrap();
     }
 
+    #[tokio::test]
+    async fn test_virtual_package_overrides() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let mut project = Project::from_str(
+            &PathBuf::from("dummy"),
+            SIMPLE_MANIFEST,
+            EnvRoot::new(tempdir.path().to_path_buf()).unwrap(),
+            BinDir::new(tempdir.path().to_path_buf()).unwrap(),
+        )
+        .unwrap();
+        let cuda = |project: &Project| {
+            project
+                .virtual_packages_for(&Platform::current())
+                .unwrap()
+                .into_iter()
+                .find(|package| package.name.as_normalized() == "__cuda")
+                .map(|package| package.version.to_string())
+        };
+
+        project.config = Config {
+            virtual_package_overrides: HashMap::from([(
+                pixi_config::OverridableVirtualPackage::Cuda,
+                "12.4".to_string(),
+            )]),
+            ..Config::default()
+        };
+        assert_eq!(cuda(&project).as_deref(), Some("12.4"));
+
+        // An empty version removes the virtual package.
+        project.config = Config {
+            virtual_package_overrides: HashMap::from([(
+                pixi_config::OverridableVirtualPackage::Cuda,
+                String::new(),
+            )]),
+            ..Config::default()
+        };
+        assert_eq!(cuda(&project), None);
+    }
+
     #[tokio::test]
     async fn test_prune_exposed() {
         let tempdir = tempfile::tempdir().unwrap();
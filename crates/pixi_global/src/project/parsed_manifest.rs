@@ -300,6 +300,10 @@ pub struct ParsedEnvironment {
     #[serde(default, serialize_with = "serialize_expose_mappings")]
     pub exposed: IndexSet<Mapping>,
     pub shortcuts: Option<IndexSet<PackageName>>,
+    /// Environment variables that are set by the trampolines of the exposed
+    /// executables, on top of the variables of the activation.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub env: IndexMap<String, String>,
 }
 
 impl<'de> toml_span::Deserialize<'de> for ParsedEnvironment {
@@ -319,6 +323,10 @@ impl<'de> toml_span::Deserialize<'de> for ParsedEnvironment {
         let shortcuts = th
             .optional_s::<TomlWith<_, TomlIndexSet<TomlFromStr<PackageName>>>>("shortcuts")
             .map(|s| s.value.into_inner());
+        let env = th
+            .optional::<TomlIndexMap<String, String>>("env")
+            .map(TomlIndexMap::into_inner)
+            .unwrap_or_default();
 
         th.finalize(None)?;
 
@@ -328,6 +336,7 @@ impl<'de> toml_span::Deserialize<'de> for ParsedEnvironment {
             dependencies,
             exposed,
             shortcuts,
+            env,
         })
     }
 }
@@ -415,7 +424,7 @@ impl AsRef<str> for ExposedName {
 mod tests {
     use insta::assert_snapshot;
 
-    use super::ParsedManifest;
+    use super::{EnvironmentName, ParsedManifest};
 
     #[test]
     fn test_invalid_key() {
@@ -540,7 +549,13 @@ mod tests {
 
         [envs.python3-10.exposed]
         "python3.10" = "python"
+
+        # The trampolines of the exposed executables set `PYTHONNOUSERSITE`
+        [envs.python3-10.env]
+        PYTHONNOUSERSITE = "1"
         "#;
-        let _manifest = ParsedManifest::from_toml_str(contents).unwrap();
+        let manifest = ParsedManifest::from_toml_str(contents).unwrap();
+        let env = &manifest.envs[&"python3-10".parse::<EnvironmentName>().unwrap()].env;
+        assert_eq!(env.get("PYTHONNOUSERSITE").map(String::as_str), Some("1"));
     }
 }
@@ -13,13 +13,17 @@ use miette::{IntoDiagnostic, WrapErr};
 use pixi_consts::consts;
 use pixi_manifest::{
     EnvironmentName, SystemRequirements,
-    pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation},
+    pypi::pypi_options::{NoBinary, NoBuild, NoBuildIsolation, PypiPlatformTag},
 };
 use pixi_progress::await_in_progress;
 use pixi_python_status::PythonStatus;
 use pixi_record::PixiRecord;
 use pixi_reporters::{UvReporter, UvReporterOptions};
-use pixi_utils::prefix::Prefix;
+use pixi_utils::{
+    metrics::{self, Counter},
+    prefix::Prefix,
+    timings::{self, Phase},
+};
 use pixi_uv_context::UvResolutionContext;
 use pixi_uv_conversions::{
     BuildIsolation, configure_insecure_hosts_for_tls_bypass, locked_indexes_to_index_locations,
@@ -169,6 +173,7 @@ pub struct PyPIUpdateConfig<'a> {
     pub platform: Platform,
     pub lock_file_dir: &'a Path,
     pub system_requirements: &'a SystemRequirements,
+    pub platform_tag: Option<&'a PypiPlatformTag>,
 }
 
 /// Configuration for PyPI build options, grouping all build-related settings
@@ -264,6 +269,7 @@ impl<'a> PyPIEnvironmentUpdater<'a> {
         pixi_records: &[PixiRecord],
         pypi_records: &[PyPIRecords],
     ) -> miette::Result<()> {
+        let _timer = timings::start(Phase::Link);
         let python_info =
             match on_python_interpreter_change(python_status, self.config.prefix, pypi_records)
                 .await?
@@ -325,6 +331,7 @@ impl<'a> PyPIEnvironmentUpdater<'a> {
         let tags = get_pypi_tags(
             self.config.platform,
             self.config.system_requirements,
+            self.config.platform_tag,
             python_record.package_record(),
         )?;
 
@@ -352,6 +359,7 @@ impl<'a> PyPIEnvironmentUpdater<'a> {
             .keyring(self.context_config.uv_context.keyring_provider)
             .connectivity(Connectivity::Online)
             .native_tls(self.context_config.uv_context.use_native_tls)
+            .retries(self.context_config.uv_context.retries)
             .extra_middleware(self.context_config.uv_context.extra_middleware.clone());
 
         let mut uv_client_builder = RegistryClientBuilder::new(
@@ -639,6 +647,18 @@ impl<'a> PyPIEnvironmentUpdater<'a> {
         self.check_and_warn_about_conflicts(&prepared_no_build_isolation_dists, reinstalls, setup)
             .await?;
 
+        // Reinstalled packages are both removed and installed again.
+        metrics::add(
+            Counter::PackagesInstalled,
+            (cached.len() + remote.len()) as u64,
+        );
+        metrics::add(
+            Counter::PackagesRemoved,
+            (extraneous.len() + reinstalls.len()) as u64,
+        );
+        metrics::add(Counter::CacheHits, cached.len() as u64);
+        metrics::add(Counter::CacheMisses, remote.len() as u64);
+
         tracing::info!("{}", format!("finished in {}", elapsed(start.elapsed())));
 
         Ok(())
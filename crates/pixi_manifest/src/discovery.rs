@@ -15,6 +15,7 @@ use toml_span::Deserialize;
 use crate::{
     AssociateProvenance, ManifestKind, ManifestProvenance, ManifestSource, PackageManifest,
     ProvenanceError, TomlError, WithProvenance, WithWarnings, WorkspaceManifest,
+    WorkspaceMemberError,
     pyproject::PyProjectManifest,
     toml::{ExternalWorkspaceProperties, PackageDefaults, TomlManifest},
     utils::WithSourceCode,
@@ -64,6 +65,10 @@ pub enum LoadManifestsError {
     #[error(transparent)]
     #[diagnostic(transparent)]
     ProvenanceError(#[from] ProvenanceError),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Members(#[from] WorkspaceMemberError),
 }
 
 impl Manifests {
@@ -147,15 +152,17 @@ impl Manifests {
                 .collect()
         };
 
-        Ok(WithWarnings::from(Self {
+        let manifests = Self {
             package: package_manifest
                 .map(|package_manifest| WithProvenance::new(package_manifest, provenance.clone())),
             workspace: WithProvenance {
                 provenance,
                 value: workspace_manifest,
             },
-        })
-        .with_warnings(warnings))
+        }
+        .with_resolved_members()?;
+
+        Ok(WithWarnings::from(manifests).with_warnings(warnings))
     }
 }
 
@@ -172,6 +179,9 @@ pub enum ExplicitManifestError {
 
     /// The pixi version could not match the minimum requirement.
     #[error("workspace requires pixi '{}', but I am {}", .requires_pixi, consts::PIXI_VERSION)]
+    #[diagnostic(help(
+        "run `pixi self-update --version <VERSION>` with a version that matches '{requires_pixi}', or update pixi the way it was installed"
+    ))]
     SelfVersionMatchError { requires_pixi: VersionSpec },
 }
 
@@ -190,6 +200,10 @@ pub enum WorkspaceDiscoveryError {
 
     #[error("cannot canonicalize path '{1}' while searching for a manifest.")]
     Canonicalize(#[source] std::io::Error, PathBuf),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Members(#[from] WorkspaceMemberError),
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -521,20 +535,20 @@ impl WorkspaceDiscoverer {
                 }
             };
 
-            return Ok(Some(
-                WithWarnings::from(Manifests {
-                    workspace: WithProvenance::new(workspace_manifest, provenance),
-                    package: closest_package_manifest,
-                })
-                .with_warnings(warnings),
-            ));
+            let manifests = Manifests {
+                workspace: WithProvenance::new(workspace_manifest, provenance),
+                package: closest_package_manifest,
+            }
+            .with_resolved_members()?;
+
+            return Ok(Some(WithWarnings::from(manifests).with_warnings(warnings)));
         }
 
         Ok(None)
     }
 
     /// Discover the workspace manifest in a directory.
-    fn provenance_from_dir(dir: &Path) -> Option<ManifestProvenance> {
+    pub(crate) fn provenance_from_dir(dir: &Path) -> Option<ManifestProvenance> {
         let pixi_toml_path = dir.join(consts::WORKSPACE_MANIFEST);
         let pyproject_toml_path = dir.join(consts::PYPROJECT_MANIFEST);
         let mojoproject_toml_path = dir.join(consts::MOJOPROJECT_MANIFEST);
@@ -5,6 +5,7 @@ use std::{
     str::FromStr,
 };
 
+use indexmap::IndexMap;
 use miette::Diagnostic;
 use regex::Regex;
 use serde::{self, Deserialize, Deserializer, Serialize};
@@ -150,6 +151,11 @@ pub struct Environment {
 
     /// Whether to include the default feature in that environment
     pub no_default_feature: bool,
+
+    /// Environment variables that are set when this environment is activated.
+    /// These take precedence over the activation variables of the features.
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
 }
 
 #[cfg(test)]
@@ -11,7 +11,10 @@ mod feature;
 mod features_ext;
 mod has_features_iter;
 mod has_manifest_ref;
+mod license_policy;
+mod lock_profile;
 mod manifests;
+mod members;
 mod package;
 mod preview;
 pub mod pypi;
@@ -37,15 +40,19 @@ pub use discovery::{
 };
 pub use environment::{Environment, EnvironmentName};
 pub use error::TomlError;
+pub use exclude_newer::ExcludeNewer;
 pub use feature::{Feature, FeatureName};
 pub use features_ext::FeaturesExt;
 pub use has_features_iter::HasFeaturesIter;
 pub use has_manifest_ref::HasWorkspaceManifest;
 use itertools::Itertools;
+pub use license_policy::{LicensePolicy, LicenseVerdict};
+pub use lock_profile::{LockProfile, LockProfileName, ParseLockProfileNameError};
 pub use manifests::{
     AssociateProvenance, ManifestKind, ManifestProvenance, ManifestSource, PackageManifest,
     ProvenanceError, WithProvenance, WorkspaceManifest, WorkspaceManifestMut,
 };
+pub use members::{WorkspaceMember, WorkspaceMemberError, resolve_members};
 use miette::Diagnostic;
 pub use package::Package;
 pub use preview::{KnownPreviewFeature, Preview};
@@ -59,7 +66,9 @@ pub use target::{PackageTarget, TargetSelector, Targets, WorkspaceTarget};
 pub use task::{Task, TaskName};
 use thiserror::Error;
 pub use warning::{Warning, WarningWithSource, WithWarnings};
-pub use workspace::{BuildVariantSource, ChannelPriority, SolveStrategy, Workspace};
+pub use workspace::{
+    BuildVariantSource, ChannelPriority, CondaPypiOverrides, SolveStrategy, Workspace,
+};
 
 pub use crate::{
     environments::Environments,
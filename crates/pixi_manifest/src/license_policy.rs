@@ -0,0 +1,136 @@
+use spdx::{Expression, LicenseReq, ParseMode};
+
+/// The licenses that the packages of a workspace are allowed to use, defined
+/// in the `[workspace.license-policy]` section of the manifest.
+///
+/// Both lists contain SPDX expressions. A license of a package matches an
+/// entry if it is one of the licenses of the expression, so `"MIT OR
+/// Apache-2.0"` is the same as listing `"MIT"` and `"Apache-2.0"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LicensePolicy {
+    /// The licenses that are allowed. If empty, every license that is not
+    /// denied is allowed.
+    pub allow: Vec<String>,
+
+    /// The licenses that are not allowed.
+    pub deny: Vec<String>,
+}
+
+/// The result of checking the license of a package against a
+/// [`LicensePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseVerdict {
+    /// The license satisfies the policy.
+    Allowed,
+    /// The license can only be satisfied with a denied license.
+    Denied,
+    /// The license can not be satisfied with the allowed licenses.
+    NotAllowed,
+    /// The package does not have a license, or it is not a valid SPDX
+    /// expression.
+    Unknown,
+}
+
+impl LicensePolicy {
+    /// Returns true if the policy does not restrict any license.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Checks the license of a package against the policy.
+    ///
+    /// Conda packages often use license names that are not strictly SPDX, so
+    /// the license is parsed leniently.
+    pub fn evaluate(&self, license: Option<&str>) -> LicenseVerdict {
+        let Some(expression) =
+            license.and_then(|license| Expression::parse_mode(license, ParseMode::LAX).ok())
+        else {
+            return LicenseVerdict::Unknown;
+        };
+
+        let allow = parse_entries(&self.allow);
+        let deny = parse_entries(&self.deny);
+
+        if !deny.is_empty() && !expression.evaluate(|req| !matches_any(&deny, req)) {
+            return LicenseVerdict::Denied;
+        }
+        if !allow.is_empty()
+            && !expression.evaluate(|req| matches_any(&allow, req) && !matches_any(&deny, req))
+        {
+            return LicenseVerdict::NotAllowed;
+        }
+        LicenseVerdict::Allowed
+    }
+}
+
+/// Parses the entries of a policy list. The entries are validated when the
+/// manifest is parsed, so invalid entries are skipped here.
+fn parse_entries(entries: &[String]) -> Vec<Expression> {
+    entries
+        .iter()
+        .filter_map(|entry| Expression::parse(entry).ok())
+        .collect()
+}
+
+/// Returns true if the license requirement is one of the licenses of any of
+/// the expressions.
+fn matches_any(expressions: &[Expression], req: &LicenseReq) -> bool {
+    expressions
+        .iter()
+        .flat_map(|expression| expression.requirements())
+        .any(|entry| entry.req == *req)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let policy = LicensePolicy {
+            allow: vec![
+                String::from("MIT OR Apache-2.0"),
+                String::from("BSD-3-Clause"),
+            ],
+            deny: vec![String::from("GPL-3.0-only")],
+        };
+
+        assert_eq!(policy.evaluate(Some("MIT")), LicenseVerdict::Allowed);
+        assert_eq!(
+            policy.evaluate(Some("Apache-2.0 AND BSD-3-Clause")),
+            LicenseVerdict::Allowed
+        );
+        assert_eq!(
+            policy.evaluate(Some("MIT OR GPL-3.0-only")),
+            LicenseVerdict::Allowed
+        );
+        assert_eq!(
+            policy.evaluate(Some("GPL-3.0-only")),
+            LicenseVerdict::Denied
+        );
+        assert_eq!(
+            policy.evaluate(Some("MIT AND GPL-3.0-only")),
+            LicenseVerdict::Denied
+        );
+        assert_eq!(policy.evaluate(Some("MPL-2.0")), LicenseVerdict::NotAllowed);
+        assert_eq!(
+            policy.evaluate(Some("not a license")),
+            LicenseVerdict::Unknown
+        );
+        assert_eq!(policy.evaluate(None), LicenseVerdict::Unknown);
+    }
+
+    #[test]
+    fn test_evaluate_deny_only() {
+        let policy = LicensePolicy {
+            allow: vec![],
+            deny: vec![String::from("AGPL-3.0-only")],
+        };
+
+        assert_eq!(policy.evaluate(Some("MPL-2.0")), LicenseVerdict::Allowed);
+        assert_eq!(
+            policy.evaluate(Some("AGPL-3.0-only")),
+            LicenseVerdict::Denied
+        );
+    }
+}
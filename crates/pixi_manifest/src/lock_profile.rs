@@ -0,0 +1,89 @@
+use std::{fmt, str::FromStr};
+
+use miette::Diagnostic;
+use pixi_consts::consts;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::{exclude_newer::ExcludeNewer, workspace::SolveStrategy};
+
+#[derive(Debug, Clone, Error, Diagnostic, PartialEq)]
+#[error(
+    "Failed to parse lock profile name '{attempted_parse}', please use only lowercase letters, numbers and dashes"
+)]
+pub struct ParseLockProfileNameError {
+    /// The string that was attempted to be parsed.
+    pub attempted_parse: String,
+}
+
+/// The name of a lock profile, defined in the `[workspace.lock-profiles]`
+/// section of the manifest.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LockProfileName(String);
+
+impl LockProfileName {
+    /// Returns the name of the lock profile.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the file name of the lock-file of the profile, e.g.
+    /// `pixi.minimal.lock` for the `minimal` profile.
+    pub fn lock_file_name(&self) -> String {
+        match consts::PROJECT_LOCK_FILE.strip_suffix(".lock") {
+            Some(stem) => format!("{stem}.{}.lock", self.0),
+            None => format!("{}.{}", consts::PROJECT_LOCK_FILE, self.0),
+        }
+    }
+}
+
+impl fmt::Display for LockProfileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for LockProfileName {
+    type Err = ParseLockProfileNameError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        static REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+        let regex = REGEX
+            .get_or_init(|| Regex::new(r"^[a-z0-9-]+$").expect("Regex should be able to compile"));
+
+        if !regex.is_match(s) {
+            return Err(ParseLockProfileNameError {
+                attempted_parse: s.to_string(),
+            });
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+/// A lock profile is an additional lock-file of the workspace that is solved
+/// with different settings than the default lock-file, e.g. to test against
+/// the oldest supported versions of the dependencies.
+///
+/// The settings of a profile take precedence over the settings of the
+/// workspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LockProfile {
+    /// The solve strategy of all environments in the lock-file.
+    pub solve_strategy: Option<SolveStrategy>,
+
+    /// Exclude package candidates that are newer than this date.
+    pub exclude_newer: Option<ExcludeNewer>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lock_file_name() {
+        let name = LockProfileName::from_str("minimal").unwrap();
+        assert_eq!(name.lock_file_name(), "pixi.minimal.lock");
+
+        assert!(LockProfileName::from_str("Minimal").is_err());
+        assert!(LockProfileName::from_str("../minimal").is_err());
+    }
+}
@@ -10,8 +10,10 @@ use toml_edit::{Array, DocumentMut, Item, Table, Value, value};
 
 use crate::{
     FeatureName, LibCSystemRequirement, ManifestKind, ManifestProvenance, PypiDependencyLocation,
-    SpecType, SystemRequirements, Task, TomlError, manifests::table_name::TableName,
-    toml::TomlDocument, utils::WithSourceCode,
+    SpecType, SystemRequirements, Task, TomlError,
+    manifests::{format::format_pixi_table, table_name::TableName},
+    toml::TomlDocument,
+    utils::WithSourceCode,
 };
 
 /// Discriminates between a 'pixi.toml' and a 'pyproject.toml' manifest.
@@ -162,6 +164,24 @@ impl ManifestDocument {
         }
     }
 
+    /// Returns true if the `[dependency-groups]` of a `pyproject.toml` are
+    /// turned into features, which is the default.
+    fn dependency_groups_as_features(&self) -> bool {
+        let ManifestDocument::PyProjectToml(document) = self else {
+            return false;
+        };
+        ["workspace", "project"]
+            .into_iter()
+            .find_map(|table| {
+                document
+                    .get_nested_table(&["tool", "pixi", table])
+                    .ok()?
+                    .get("dependency-groups-as-features")?
+                    .as_bool()
+            })
+            .unwrap_or(true)
+    }
+
     fn manifest_mut(&mut self) -> &mut TomlDocument {
         match self {
             ManifestDocument::PyProjectToml(document) => document,
@@ -391,10 +411,15 @@ impl ManifestDocument {
         //  - When explicitly requested
         //  - When a specific platform is requested, as markers are not supported (https://github.com/prefix-dev/pixi/issues/2149)
         //  - When an editable install is requested
+        //  - When a feature is requested but dependency groups are not turned into
+        //    features
         if matches!(self, ManifestDocument::PixiToml(_))
             || matches!(location, Some(PypiDependencyLocation::PixiPypiDependencies))
             || platform.is_some()
             || editable.is_some_and(|e| e)
+            || (!feature_name.is_default()
+                && location.is_none()
+                && !self.dependency_groups_as_features())
         {
             let mut pypi_requirement = match pixi_requirement {
                 Some(existing) => existing.update_requirement(requirement)?,
@@ -615,8 +640,24 @@ impl ManifestDocument {
         solve_group: Option<String>,
         no_default_features: bool,
     ) -> Result<(), TomlError> {
+        let name = name.into();
+        let env_table = TableName::new()
+            .with_prefix(self.table_prefix())
+            .with_feature_name(Some(&FeatureName::DEFAULT))
+            .with_table(Some("environments"));
+        let environments = self
+            .manifest_mut()
+            .get_or_insert_nested_table(&env_table.as_keys())?;
+
+        // Keep the environment variables of an existing environment
+        let env = environments
+            .get(&name)
+            .and_then(|item| item.get("env"))
+            .cloned()
+            .and_then(|item| item.into_value().ok());
+
         // Construct the TOML item
-        let item = if solve_group.is_some() || no_default_features {
+        let item = if solve_group.is_some() || no_default_features || env.is_some() {
             let mut table = toml_edit::InlineTable::new();
             if let Some(features) = features {
                 table.insert("features", Array::from_iter(features).into());
@@ -627,6 +668,9 @@ impl ManifestDocument {
             if no_default_features {
                 table.insert("no-default-feature", true.into());
             }
+            if let Some(env) = env {
+                table.insert("env", env);
+            }
             Item::Value(table.into())
         } else {
             Item::Value(Value::Array(Array::from_iter(
@@ -634,15 +678,8 @@ impl ManifestDocument {
             )))
         };
 
-        let env_table = TableName::new()
-            .with_prefix(self.table_prefix())
-            .with_feature_name(Some(&FeatureName::DEFAULT))
-            .with_table(Some("environments"));
-
         // Insert into the environment table
-        self.manifest_mut()
-            .get_or_insert_nested_table(&env_table.as_keys())?
-            .insert(&name.into(), item);
+        environments.insert(&name, item);
 
         Ok(())
     }
@@ -655,11 +692,32 @@ impl ManifestDocument {
             .with_feature_name(Some(&FeatureName::DEFAULT))
             .with_table(Some("environments"));
 
-        Ok(self
+        // Don't insert an empty `[environments]` table if there is none.
+        let Ok(environments) = self
             .manifest_mut()
-            .get_or_insert_nested_table(&env_table.as_keys())?
-            .remove(name)
-            .is_some())
+            .get_nested_table_mut(&env_table.as_keys())
+        else {
+            return Ok(false);
+        };
+        Ok(environments.remove(name).is_some())
+    }
+
+    /// Adds an empty feature table to the manifest. Returns `false` if the
+    /// table already exists.
+    pub fn add_feature(&mut self, feature_name: &FeatureName) -> Result<bool, TomlError> {
+        let table_name = TableName::new()
+            .with_prefix(self.table_prefix())
+            .with_table(Some("feature"));
+
+        let feature_table = self
+            .manifest_mut()
+            .get_or_insert_nested_table(&table_name.as_keys())?;
+        if feature_table.contains_key(feature_name.as_str()) {
+            return Ok(false);
+        }
+
+        feature_table.insert(feature_name.as_str(), Item::Table(Table::new()));
+        Ok(true)
     }
 
     /// Removes a feature from the manifest. Returns `true` if the feature was
@@ -818,6 +876,48 @@ impl ManifestDocument {
 
         Ok(())
     }
+
+    /// Returns the `project.requires-python` of a `pyproject.toml` manifest.
+    pub fn requires_python(&self) -> Option<&str> {
+        let ManifestDocument::PyProjectToml(document) = self else {
+            return None;
+        };
+        document
+            .get_nested_table(&["project"])
+            .ok()?
+            .get("requires-python")?
+            .as_str()
+    }
+
+    /// Sets the `project.requires-python` of a `pyproject.toml` manifest, other
+    /// manifests have no such field and are left unchanged.
+    pub fn set_requires_python(&mut self, requires_python: &str) -> Result<(), TomlError> {
+        if !matches!(self, ManifestDocument::PyProjectToml(_)) {
+            return Ok(());
+        }
+        let table = self
+            .manifest_mut()
+            .get_or_insert_nested_table(&["project"])?;
+        if let Some(item) = table.get_mut("requires-python") {
+            *item = value(requires_python);
+        } else {
+            table.insert("requires-python", value(requires_python));
+        }
+        Ok(())
+    }
+
+    /// Formats the pixi tables of the manifest canonically: dependencies are
+    /// sorted by name, their version specs are normalized and inline tables
+    /// are written in a consistent style. Comments are preserved.
+    pub fn format(&mut self) {
+        let table_name = TableName::new().with_prefix(self.table_prefix());
+        if let Ok(table) = self
+            .manifest_mut()
+            .get_nested_table_mut(&table_name.as_keys())
+        {
+            format_pixi_table(table);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -941,6 +1041,24 @@ NumPy = ">=1.20.0" # table inline comment
         insta::assert_snapshot!(document.to_string());
     }
 
+    #[test]
+    pub fn set_requires_python() {
+        let manifest_content = r#"
+[project]
+name = "pixi-demo"
+requires-python = ">= 3.11"
+version = "0.1.0"
+"#;
+
+        let mut document = ManifestDocument::PyProjectToml(TomlDocument::new(
+            DocumentMut::from_str(manifest_content).unwrap(),
+        ));
+        assert_eq!(document.requires_python(), Some(">= 3.11"));
+
+        document.set_requires_python(">=3.12").unwrap();
+        assert_eq!(document.requires_python(), Some(">=3.12"));
+    }
+
     /// This test checks that removing a pypi dependency
     /// uses the same source name as the one used to add it.
     #[test]
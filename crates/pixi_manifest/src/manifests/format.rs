@@ -0,0 +1,208 @@
+//! Canonical formatting of the pixi tables of a manifest.
+//!
+//! The formatting only touches the dependency tables: their keys are sorted,
+//! the version specs are normalized and inline tables are written in a
+//! consistent style. Comments and the layout of everything else are
+//! preserved.
+
+use std::str::FromStr;
+
+use pep440_rs::VersionSpecifiers;
+use pixi_consts::consts;
+use rattler_conda_types::VersionSpec;
+use toml_edit::{Item, TableLike, Value};
+
+/// The tables that contain conda dependencies.
+const CONDA_DEPENDENCY_TABLES: [&str; 5] = [
+    consts::DEPENDENCIES,
+    "host-dependencies",
+    "build-dependencies",
+    "run-dependencies",
+    "constraints",
+];
+
+/// Formats the dependency tables of a pixi table, i.e. the root of a
+/// `pixi.toml` or the `[tool.pixi]` table of a `pyproject.toml`.
+pub(crate) fn format_pixi_table(table: &mut dyn TableLike) {
+    format_target_tables(table);
+
+    // Features and packages can contain their own dependencies.
+    if let Some(features) = table.get_mut("feature").and_then(Item::as_table_like_mut) {
+        for (_, feature) in features.iter_mut() {
+            if let Some(feature) = feature.as_table_like_mut() {
+                format_target_tables(feature);
+            }
+        }
+    }
+    if let Some(package) = table.get_mut("package").and_then(Item::as_table_like_mut) {
+        format_target_tables(package);
+    }
+}
+
+/// Formats the dependency tables of a table and of its `target` tables.
+fn format_target_tables(table: &mut dyn TableLike) {
+    format_dependency_tables(table);
+    if let Some(targets) = table.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (_, target) in targets.iter_mut() {
+            if let Some(target) = target.as_table_like_mut() {
+                format_dependency_tables(target);
+            }
+        }
+    }
+}
+
+fn format_dependency_tables(table: &mut dyn TableLike) {
+    for name in CONDA_DEPENDENCY_TABLES {
+        if let Some(dependencies) = table.get_mut(name).and_then(Item::as_table_like_mut) {
+            format_dependencies(dependencies, normalize_conda_spec);
+        }
+    }
+    if let Some(dependencies) = table
+        .get_mut(consts::PYPI_DEPENDENCIES)
+        .and_then(Item::as_table_like_mut)
+    {
+        format_dependencies(dependencies, normalize_pypi_spec);
+    }
+}
+
+/// Sorts the dependencies by name and normalizes their specs.
+fn format_dependencies(dependencies: &mut dyn TableLike, normalize: fn(&str) -> Option<String>) {
+    dependencies.sort_values();
+    for (mut key, item) in dependencies.iter_mut() {
+        let Some(value) = item.as_value_mut() else {
+            continue;
+        };
+        key.leaf_decor_mut().set_suffix(" ");
+        match value {
+            Value::String(spec) => {
+                if let Some(normalized) = normalize(spec.value()) {
+                    let decor = spec.decor().clone();
+                    *spec = toml_edit::Formatted::new(normalized);
+                    *spec.decor_mut() = decor;
+                }
+            }
+            Value::InlineTable(table) => {
+                if let Some(Value::String(version)) =
+                    table.get_mut("version").and_then(Item::as_value_mut)
+                    && let Some(normalized) = normalize(version.value())
+                {
+                    *version = toml_edit::Formatted::new(normalized);
+                }
+                table.fmt();
+            }
+            _ => {}
+        }
+
+        // Keep a trailing comment, but remove any other whitespace.
+        let decor = value.decor_mut();
+        let suffix = decor
+            .suffix()
+            .and_then(|suffix| suffix.as_str())
+            .filter(|suffix| suffix.contains('#'))
+            .map(|suffix| format!(" {}", suffix.trim_start()))
+            .unwrap_or_default();
+        decor.set_prefix(" ");
+        decor.set_suffix(suffix);
+    }
+}
+
+/// Removes the whitespace from a conda version spec, if that does not change
+/// its meaning.
+fn normalize_conda_spec(spec: &str) -> Option<String> {
+    let compact = spec.split_whitespace().collect::<String>();
+    if compact == spec {
+        return None;
+    }
+    let original = VersionSpec::from_str(spec).ok()?;
+    let normalized = VersionSpec::from_str(&compact).ok()?;
+    (original == normalized).then_some(compact)
+}
+
+/// Writes a PyPI version specifier in its canonical form.
+fn normalize_pypi_spec(spec: &str) -> Option<String> {
+    let specifiers = VersionSpecifiers::from_str(spec).ok()?;
+    let normalized = specifiers.to_string();
+    (normalized != spec).then_some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use toml_edit::DocumentMut;
+
+    use super::*;
+
+    fn format(source: &str) -> String {
+        let mut document = DocumentMut::from_str(source).unwrap();
+        format_pixi_table(document.as_table_mut());
+        document.to_string()
+    }
+
+    #[test]
+    fn test_format_pixi_toml() {
+        let formatted = format(
+            r#"[workspace]
+channels = ["conda-forge"]
+platforms = ["linux-64"]
+
+[dependencies]
+# The interpreter
+python = ">=3.12 , <3.13"
+numpy={version=">=2",channel="conda-forge"}   # pinned channel
+cmake   =   "*"
+
+[pypi-dependencies]
+requests = ">= 2.0,<3"
+flask = "*"
+
+[feature.test.target.linux-64.dependencies]
+pytest = "*"
+coverage = "7.*"
+"#,
+        );
+        assert_eq!(
+            formatted,
+            r#"[workspace]
+channels = ["conda-forge"]
+platforms = ["linux-64"]
+
+[dependencies]
+cmake = "*"
+numpy = { version = ">=2", channel = "conda-forge" } # pinned channel
+# The interpreter
+python = ">=3.12,<3.13"
+
+[pypi-dependencies]
+flask = "*"
+requests = ">=2.0, <3"
+
+[feature.test.target.linux-64.dependencies]
+coverage = "7.*"
+pytest = "*"
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let source = r#"
+[dependencies]
+b = ">=1 , <2"
+a = { version = "1.*", build = "py*" }
+"#;
+        let formatted = format(source);
+        assert_eq!(format(&formatted), formatted);
+    }
+
+    #[test]
+    fn test_normalize_conda_spec() {
+        assert_eq!(
+            normalize_conda_spec(">=1.2 , <2"),
+            Some(String::from(">=1.2,<2"))
+        );
+        assert_eq!(normalize_conda_spec(">=1.2,<2"), None);
+        // A build string is not part of a version spec.
+        assert_eq!(normalize_conda_spec("1.2 py_0"), None);
+    }
+}
@@ -11,6 +11,7 @@
 pub mod table_name;
 
 mod document;
+mod format;
 mod package;
 mod provenance;
 mod source;
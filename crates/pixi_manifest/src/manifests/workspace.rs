@@ -313,6 +313,7 @@ impl WorkspaceManifestMut<'_> {
             features: features.unwrap_or_default(),
             solve_group: None,
             no_default_feature,
+            env: Default::default(),
         });
 
         if let Some(solve_group) = solve_group {
@@ -351,6 +352,26 @@ impl WorkspaceManifestMut<'_> {
         Ok(true)
     }
 
+    /// Adds an empty feature to the project. Returns `false` if the feature
+    /// already exists.
+    ///
+    /// This function modifies both the workspace and the TOML document. Use
+    /// `ManifestProvenance::save` to persist the changes to disk.
+    pub fn add_feature(&mut self, feature_name: &FeatureName) -> miette::Result<bool> {
+        if feature_name.is_default() {
+            miette::bail!("Cannot add the default feature");
+        }
+
+        if self.workspace.features.contains_key(feature_name) {
+            return Ok(false);
+        }
+
+        self.document.add_feature(feature_name)?;
+        self.workspace.get_or_insert_feature_mut(feature_name);
+
+        Ok(true)
+    }
+
     /// Removes a feature from the project. The feature is automatically
     /// removed from all environments that use it.
     ///
@@ -407,6 +428,7 @@ impl WorkspaceManifestMut<'_> {
                 features: updated_features,
                 solve_group: None,
                 no_default_feature: env.no_default_feature,
+                env: env.env.clone(),
             });
 
             if let Some(solve_group) = solve_group {
@@ -549,6 +571,10 @@ impl WorkspaceManifestMut<'_> {
         platforms: &[Platform],
         feature_name: &FeatureName,
     ) -> miette::Result<()> {
+        if self.workspace.feature(feature_name).is_none() {
+            return Err(UnknownFeature::new(feature_name.to_string(), &*self.workspace).into());
+        }
+
         for platform in crate::to_options(platforms) {
             // Remove the dependency from the manifest
             match self
@@ -621,6 +647,10 @@ impl WorkspaceManifestMut<'_> {
         platforms: &[Platform],
         feature_name: &FeatureName,
     ) -> miette::Result<()> {
+        if self.workspace.feature(feature_name).is_none() {
+            return Err(UnknownFeature::new(feature_name.to_string(), &*self.workspace).into());
+        }
+
         for platform in crate::to_options(platforms) {
             // Remove the dependency from the manifest
             match self
@@ -838,6 +868,17 @@ impl WorkspaceManifestMut<'_> {
         };
         self.document.set_requires_pixi(version).into_diagnostic()
     }
+
+    /// Sets the `requires-python` of a `pyproject.toml` manifest.
+    ///
+    /// This only modifies the TOML document, the python version of the
+    /// workspace is defined by its `python` dependency. Use
+    /// `ManifestProvenance::save` to persist the changes to disk.
+    pub fn set_requires_python(&mut self, requires_python: &str) -> miette::Result<()> {
+        self.document
+            .set_requires_python(requires_python)
+            .into_diagnostic()
+    }
 }
 
 // Handles the target missing error cases
@@ -2934,6 +2975,39 @@ bar = "*"
         "###);
     }
 
+    #[test]
+    fn test_remove_dependency_unknown_feature() {
+        let contents = r#"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [feature.cuda.dependencies]
+        cudatoolkit = "*"
+        "#;
+        let mut manifest = parse_pixi_toml(contents);
+        let mut manifest = manifest.editable();
+
+        let err = manifest
+            .remove_dependency(
+                &PackageName::new_unchecked("cudatoolkit"),
+                SpecType::Run,
+                &[],
+                &FeatureName::from_str("cudo").unwrap(),
+            )
+            .unwrap_err();
+
+        let mut s = String::new();
+        let report_handler = NarratableReportHandler::new().with_cause_chain();
+        report_handler.render_report(&mut s, err.as_ref()).unwrap();
+        assert_snapshot!(s, @r###"
+        the feature 'cudo' is not defined in the project manifest
+            Diagnostic severity: error
+        diagnostic help: Did you mean 'cuda'?
+        "###);
+    }
+
     #[test]
     fn test_remove_environment() {
         let contents = r#"
@@ -2952,6 +3026,104 @@ bar = "*"
         assert!(!manifest.remove_environment("default").unwrap());
     }
 
+    #[test]
+    fn test_add_remove_environment_preserves_formatting() {
+        let contents = r#"[workspace]
+name = "foo"
+channels = []
+platforms = []
+
+[feature.test]
+
+# The environments of the workspace
+[environments]
+# The test environment
+test = ["test"]
+"#;
+        let mut manifest = parse_pixi_toml(contents);
+        let mut manifest = manifest.editable();
+
+        manifest
+            .add_environment(
+                String::from("lint"),
+                Some(vec![String::from("test")]),
+                Some(String::from("default")),
+                true,
+            )
+            .unwrap();
+        assert_snapshot!(manifest.document.to_string(), @r###"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = []
+
+        [feature.test]
+
+        # The environments of the workspace
+        [environments]
+        # The test environment
+        test = ["test"]
+        lint = { features = ["test"], solve-group = "default", no-default-feature = true }
+        "###);
+
+        assert!(manifest.remove_environment("lint").unwrap());
+        assert_eq!(manifest.document.to_string(), contents);
+    }
+
+    #[test]
+    fn test_remove_environment_without_environments_table() {
+        let contents = r#"[workspace]
+name = "foo"
+channels = []
+platforms = []
+"#;
+        let mut manifest = parse_pixi_toml(contents);
+        let mut manifest = manifest.editable();
+
+        assert!(!manifest.remove_environment("foo").unwrap());
+        assert_eq!(manifest.document.to_string(), contents);
+    }
+
+    #[test]
+    fn test_add_feature() {
+        let contents = r#"[workspace]
+name = "foo"
+channels = []
+platforms = ["linux-64"]
+
+[feature.existing.dependencies]
+python = "*"
+"#;
+        let mut manifest = parse_pixi_toml(contents);
+        let mut manifest = manifest.editable();
+
+        let feature = FeatureName::from_str("test").unwrap();
+        assert!(manifest.add_feature(&feature).unwrap());
+        assert!(
+            !manifest
+                .add_feature(&FeatureName::from_str("existing").unwrap())
+                .unwrap()
+        );
+        assert!(manifest.add_feature(&FeatureName::DEFAULT).is_err());
+
+        manifest
+            .add_platforms([Platform::Osx64].iter(), &feature)
+            .unwrap();
+        assert!(manifest.workspace.feature("test").is_some());
+        assert_snapshot!(manifest.document.to_string(), @r###"
+        [workspace]
+        name = "foo"
+        channels = []
+        platforms = ["linux-64"]
+
+        [feature.existing.dependencies]
+        python = "*"
+
+        [feature.test]
+        platforms = ["osx-64"]
+        "###);
+    }
+
     #[test]
     fn test_remove_feature() {
         let contents = r#"
@@ -0,0 +1,308 @@
+//! Resolution of the member packages of a workspace.
+//!
+//! A workspace can list the directories of its member packages in the
+//! `members` field of the `[workspace]` table. A dependency on a member
+//! package that does not specify a version (e.g. `my-lib = "*"`) is resolved
+//! as a source dependency on the directory of the member, both in the
+//! workspace manifest and in the manifests of the other members.
+
+use std::path::{Path, PathBuf};
+
+use miette::Diagnostic;
+use pixi_spec::{PathSpec, PixiSpec};
+use rattler_conda_types::{PackageName, VersionSpec};
+use thiserror::Error;
+
+use crate::{CondaDependencies, ManifestKind, Manifests, WorkspaceDiscoverer};
+
+/// A member package of a workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceMember {
+    /// The name of the package.
+    pub name: PackageName,
+
+    /// The absolute path of the directory that contains the package.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum WorkspaceMemberError {
+    #[error("the workspace member '{}' does not exist", .0.display())]
+    #[diagnostic(help("remove the path from the `members` of the workspace"))]
+    MissingMember(PathBuf),
+
+    #[error("the workspace member '{}' does not contain a manifest", .0.display())]
+    MissingManifest(PathBuf),
+
+    #[error("the manifest of workspace member '{}' does not define a package name", .0.display())]
+    #[diagnostic(help("add a `[package]` section with a `name` to the manifest"))]
+    MissingPackageName(PathBuf),
+
+    #[error("the manifest of workspace member '{}' is invalid", .0.display())]
+    InvalidManifest(PathBuf, #[source] toml_span::Error),
+
+    #[error("the workspace members '{}' and '{}' both define the package '{}'", .first.display(), .second.display(), .name.as_source())]
+    DuplicatePackage {
+        name: PackageName,
+        first: PathBuf,
+        second: PathBuf,
+    },
+
+    #[error("failed to read the workspace member '{}'", .0.display())]
+    Io(PathBuf, #[source] std::io::Error),
+}
+
+/// Returns the member packages that match the given patterns. Patterns are
+/// paths relative to `root`, a pattern ending in `/*` matches all direct
+/// subdirectories that contain a manifest.
+pub fn resolve_members(
+    root: &Path,
+    patterns: &[String],
+) -> Result<Vec<WorkspaceMember>, WorkspaceMemberError> {
+    let mut members: Vec<WorkspaceMember> = Vec::new();
+    for pattern in patterns {
+        let directories = if let Some(parent) = pattern
+            .strip_suffix("/*")
+            .or_else(|| (pattern == "*").then_some(""))
+        {
+            let parent = root.join(parent);
+            if !parent.is_dir() {
+                return Err(WorkspaceMemberError::MissingMember(parent));
+            }
+            let mut directories = fs_err::read_dir(&parent)
+                .map_err(|err| WorkspaceMemberError::Io(parent.clone(), err))?
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_dir() && WorkspaceDiscoverer::provenance_from_dir(path).is_some()
+                })
+                .collect::<Vec<_>>();
+            directories.sort();
+            directories
+        } else {
+            let directory = root.join(pattern);
+            if !directory.is_dir() {
+                return Err(WorkspaceMemberError::MissingMember(directory));
+            }
+            vec![directory]
+        };
+
+        for directory in directories {
+            let directory = dunce::canonicalize(&directory)
+                .map_err(|err| WorkspaceMemberError::Io(directory.clone(), err))?;
+            let name = read_package_name(&directory)?;
+            if let Some(existing) = members.iter().find(|member| member.name == name) {
+                if existing.path == directory {
+                    continue;
+                }
+                return Err(WorkspaceMemberError::DuplicatePackage {
+                    name,
+                    first: existing.path.clone(),
+                    second: directory,
+                });
+            }
+            members.push(WorkspaceMember {
+                name,
+                path: directory,
+            });
+        }
+    }
+    Ok(members)
+}
+
+/// Reads the name of the package defined in the manifest in the given
+/// directory.
+fn read_package_name(directory: &Path) -> Result<PackageName, WorkspaceMemberError> {
+    let provenance = WorkspaceDiscoverer::provenance_from_dir(directory)
+        .ok_or_else(|| WorkspaceMemberError::MissingManifest(directory.to_path_buf()))?;
+    let contents = fs_err::read_to_string(&provenance.path)
+        .map_err(|err| WorkspaceMemberError::Io(directory.to_path_buf(), err))?;
+    let toml = toml_span::parse(&contents)
+        .map_err(|err| WorkspaceMemberError::InvalidManifest(directory.to_path_buf(), err))?;
+
+    let name = match provenance.kind {
+        ManifestKind::Pixi | ManifestKind::MojoProject => toml.pointer("/package/name"),
+        ManifestKind::Pyproject => toml
+            .pointer("/tool/pixi/package/name")
+            .or_else(|| toml.pointer("/project/name")),
+    };
+    name.and_then(|name| name.as_str())
+        .and_then(|name| PackageName::try_from(name).ok())
+        .ok_or_else(|| WorkspaceMemberError::MissingPackageName(directory.to_path_buf()))
+}
+
+/// Replaces dependencies on members without a version by source dependencies
+/// on the directory of the member, relative to `base`.
+fn resolve_member_dependencies(
+    dependencies: &mut CondaDependencies,
+    members: &[WorkspaceMember],
+    base: &Path,
+) {
+    for member in members {
+        if !matches!(
+            dependencies.get_single(&member.name),
+            Ok(Some(PixiSpec::Version(VersionSpec::Any)))
+        ) {
+            continue;
+        }
+        let path = pathdiff::diff_paths(&member.path, base).unwrap_or_else(|| member.path.clone());
+        let path = if path.as_os_str().is_empty() {
+            String::from(".")
+        } else {
+            path.to_string_lossy().replace('\\', "/")
+        };
+        dependencies.insert_overwrite(member.name.clone(), PixiSpec::Path(PathSpec::new(path)));
+    }
+}
+
+impl Manifests {
+    /// Resolves the dependencies on the member packages of the workspace to
+    /// source dependencies. Does nothing if the workspace has no members.
+    pub fn with_resolved_members(mut self) -> Result<Self, WorkspaceMemberError> {
+        let patterns = &self.workspace.value.workspace.members;
+        if patterns.is_empty() {
+            return Ok(self);
+        }
+
+        let workspace_manifest = self.workspace.provenance.absolute_path();
+        let root = workspace_manifest
+            .parent()
+            .expect("a manifest must have a parent directory");
+        let members = resolve_members(root, patterns)?;
+
+        for feature in self.workspace.value.features.values_mut() {
+            for target in feature.targets.targets_mut() {
+                for dependencies in target.dependencies.values_mut() {
+                    resolve_member_dependencies(dependencies, &members, root);
+                }
+            }
+        }
+
+        if let Some(package) = &mut self.package {
+            let package_manifest = package.provenance.absolute_path();
+            let package_dir = package_manifest
+                .parent()
+                .expect("a manifest must have a parent directory");
+            for target in package.value.targets.targets_mut() {
+                for dependencies in target.dependencies.values_mut() {
+                    resolve_member_dependencies(dependencies, &members, package_dir);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{DiscoveryStart, SpecType};
+
+    fn write(path: &Path, contents: &str) {
+        fs_err::create_dir_all(path.parent().unwrap()).unwrap();
+        fs_err::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_member_dependencies_resolve_to_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write(
+            &root.join("pixi.toml"),
+            r#"
+            [workspace]
+            channels = []
+            platforms = []
+            preview = ["pixi-build"]
+            members = ["packages/*"]
+
+            [dependencies]
+            app = "*"
+            lib = ">=1"
+            "#,
+        );
+        write(
+            &root.join("packages/lib/pixi.toml"),
+            r#"
+            [package]
+            name = "lib"
+            version = "0.1.0"
+
+            [package.build.backend]
+            name = "pixi-build-cmake"
+            version = "*"
+            "#,
+        );
+        write(
+            &root.join("packages/app/pixi.toml"),
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [package.build.backend]
+            name = "pixi-build-cmake"
+            version = "*"
+
+            [package.run-dependencies]
+            lib = "*"
+            "#,
+        );
+
+        let manifests =
+            WorkspaceDiscoverer::new(DiscoveryStart::SearchRoot(root.join("packages/app")))
+                .with_closest_package(true)
+                .discover()
+                .unwrap()
+                .unwrap()
+                .value;
+
+        let workspace_dependencies = manifests
+            .workspace
+            .value
+            .default_feature()
+            .targets
+            .default()
+            .run_dependencies()
+            .unwrap();
+        assert_eq!(
+            workspace_dependencies
+                .get_single(&PackageName::new_unchecked("app"))
+                .unwrap()
+                .unwrap(),
+            &PixiSpec::Path(PathSpec::new("packages/app"))
+        );
+        // A dependency with a version is still taken from the channels.
+        assert!(matches!(
+            workspace_dependencies
+                .get_single(&PackageName::new_unchecked("lib"))
+                .unwrap()
+                .unwrap(),
+            PixiSpec::Version(_)
+        ));
+
+        let package = manifests.package.unwrap();
+        let package_dependencies = package
+            .value
+            .targets
+            .default()
+            .dependencies
+            .get(&SpecType::Run)
+            .unwrap();
+        assert_eq!(
+            package_dependencies
+                .get_single(&PackageName::new_unchecked("lib"))
+                .unwrap()
+                .unwrap(),
+            &PixiSpec::Path(PathSpec::new("../lib"))
+        );
+    }
+
+    #[test]
+    fn test_missing_member() {
+        let tmp = tempfile::tempdir().unwrap();
+        let err = resolve_members(tmp.path(), &[String::from("missing")]).unwrap_err();
+        assert!(matches!(err, WorkspaceMemberError::MissingMember(_)));
+    }
+}
@@ -1,9 +1,10 @@
-use std::path::PathBuf;
+use std::{fmt::Display, path::PathBuf, str::FromStr};
 
 use indexmap::IndexMap;
 use indexmap::IndexSet;
 use pep508_rs::PackageName;
 use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
+use rattler_conda_types::Platform;
 use serde::{Serialize, Serializer, ser::SerializeSeq};
 use thiserror::Error;
 use url::Url;
@@ -149,6 +150,108 @@ impl NoBinary {
     }
 }
 
+/// The platform tag that wheels are selected for when resolving PyPI packages
+/// for a specific platform, e.g. `musllinux_1_2` or `manylinux_2_17`.
+///
+/// By default the tag is derived from the system requirements of the
+/// environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PypiPlatformTag {
+    /// A glibc based linux, e.g. `manylinux_2_17`
+    Manylinux { major: u16, minor: u16 },
+    /// A musl based linux, e.g. `musllinux_1_2`
+    Musllinux { major: u16, minor: u16 },
+    /// A macOS version, e.g. `macosx_11_0`
+    Macos { major: u16, minor: u16 },
+}
+
+impl PypiPlatformTag {
+    /// The prefix of the platform tags in a wheel filename that belong to this
+    /// family of tags.
+    pub fn wheel_tag_prefix(&self) -> &'static str {
+        match self {
+            PypiPlatformTag::Manylinux { .. } => "manylinux",
+            PypiPlatformTag::Musllinux { .. } => "musllinux",
+            PypiPlatformTag::Macos { .. } => "macosx",
+        }
+    }
+
+    /// Returns true if this tag can be used for the given conda platform.
+    pub fn is_compatible_with(&self, platform: Platform) -> bool {
+        match self {
+            PypiPlatformTag::Manylinux { .. } | PypiPlatformTag::Musllinux { .. } => {
+                platform.is_linux()
+            }
+            PypiPlatformTag::Macos { .. } => platform.is_osx(),
+        }
+    }
+}
+
+impl Display for PypiPlatformTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (major, minor) = match self {
+            PypiPlatformTag::Manylinux { major, minor }
+            | PypiPlatformTag::Musllinux { major, minor }
+            | PypiPlatformTag::Macos { major, minor } => (major, minor),
+        };
+        write!(f, "{}_{major}_{minor}", self.wheel_tag_prefix())
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error(
+    "invalid platform tag '{0}', expected e.g. 'manylinux_2_17', 'musllinux_1_2' or 'macosx_11_0'"
+)]
+pub struct ParsePypiPlatformTagError(String);
+
+impl FromStr for PypiPlatformTag {
+    type Err = ParsePypiPlatformTagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // The legacy manylinux tags are aliases for specific glibc versions.
+        match s {
+            "manylinux1" => return Ok(PypiPlatformTag::Manylinux { major: 2, minor: 5 }),
+            "manylinux2010" => {
+                return Ok(PypiPlatformTag::Manylinux {
+                    major: 2,
+                    minor: 12,
+                });
+            }
+            "manylinux2014" => {
+                return Ok(PypiPlatformTag::Manylinux {
+                    major: 2,
+                    minor: 17,
+                });
+            }
+            _ => {}
+        }
+
+        let err = || ParsePypiPlatformTagError(s.to_string());
+        let mut parts = s.splitn(3, '_');
+        let (Some(family), Some(major), Some(minor)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(err());
+        };
+        let major = major.parse().map_err(|_| err())?;
+        let minor = minor.parse().map_err(|_| err())?;
+        match family {
+            "manylinux" => Ok(PypiPlatformTag::Manylinux { major, minor }),
+            "musllinux" => Ok(PypiPlatformTag::Musllinux { major, minor }),
+            "macosx" => Ok(PypiPlatformTag::Macos { major, minor }),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl Serialize for PypiPlatformTag {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 /// Specific options for a PyPI registries
 #[derive(Debug, Clone, PartialEq, Serialize, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
@@ -173,6 +276,8 @@ pub struct PypiOptions {
     pub dependency_overrides: Option<IndexMap<PypiPackageName, PixiPypiSpec>>,
     /// Don't use pre-built wheels all or certain packages
     pub no_binary: Option<NoBinary>,
+    /// The platform tags to resolve wheels for, per platform
+    pub platform_tags: Option<IndexMap<Platform, PypiPlatformTag>>,
 }
 
 use crate::pypi::merge::{
@@ -191,6 +296,7 @@ impl PypiOptions {
         no_build: Option<NoBuild>,
         dependency_overrides: Option<IndexMap<PypiPackageName, PixiPypiSpec>>,
         no_binary: Option<NoBinary>,
+        platform_tags: Option<IndexMap<Platform, PypiPlatformTag>>,
     ) -> Self {
         Self {
             index_url: index,
@@ -202,9 +308,15 @@ impl PypiOptions {
             no_build,
             dependency_overrides,
             no_binary,
+            platform_tags,
         }
     }
 
+    /// Returns the platform tag override for the given platform, if any.
+    pub fn platform_tag(&self, platform: Platform) -> Option<&PypiPlatformTag> {
+        self.platform_tags.as_ref()?.get(&platform)
+    }
+
     /// Return an iterator over all [`Url`] entries.
     /// In order of:
     /// - `find_links`
@@ -269,6 +381,7 @@ impl PypiOptions {
         // Maps with left (self) overriding right (other)
         let dependency_overrides =
             merge_map_override_left(&self.dependency_overrides, &other.dependency_overrides);
+        let platform_tags = merge_map_override_left(&self.platform_tags, &other.platform_tags);
 
         Ok(PypiOptions {
             index_url: index,
@@ -280,6 +393,7 @@ impl PypiOptions {
             no_build,
             dependency_overrides,
             no_binary,
+            platform_tags,
         })
     }
 }
@@ -499,6 +613,7 @@ mod tests {
                 ),
             ])),
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Create the second set of options
@@ -525,6 +640,7 @@ mod tests {
             ])),
 
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Merge the two options
@@ -571,6 +687,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_platform_tag_from_str() {
+        assert_eq!(
+            "musllinux_1_2".parse(),
+            Ok(PypiPlatformTag::Musllinux { major: 1, minor: 2 })
+        );
+        assert_eq!(
+            "manylinux2014".parse(),
+            Ok(PypiPlatformTag::Manylinux {
+                major: 2,
+                minor: 17
+            })
+        );
+        assert_eq!(
+            "macosx_11_0".parse(),
+            Ok(PypiPlatformTag::Macos {
+                major: 11,
+                minor: 0
+            })
+        );
+        assert!("win_amd64".parse::<PypiPlatformTag>().is_err());
+        assert!("manylinux_2".parse::<PypiPlatformTag>().is_err());
+
+        // The legacy aliases are normalized.
+        let tag: PypiPlatformTag = "manylinux1".parse().unwrap();
+        assert_eq!(tag.to_string(), "manylinux_2_5");
+    }
+
     #[test]
     fn test_no_binary_union() {
         let pkg1 = pep508_rs::PackageName::new("pkg1".to_string()).unwrap();
@@ -622,6 +766,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Create the second set of options
@@ -635,6 +780,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Merge the two options
@@ -656,6 +802,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Create the second set of options
@@ -669,6 +816,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Merge the two options
@@ -690,6 +838,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Create the second set of options
@@ -703,6 +852,7 @@ mod tests {
             no_build: Default::default(),
             dependency_overrides: None,
             no_binary: Default::default(),
+            platform_tags: None,
         };
 
         // Merge the two options
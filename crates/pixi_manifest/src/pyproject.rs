@@ -16,7 +16,7 @@ use super::{
     error::{RequirementConversionError, TomlError},
 };
 use crate::{
-    FeatureName, ManifestKind, Warning,
+    Environment, EnvironmentName, FeatureName, ManifestKind, Warning,
     manifests::PackageManifest,
     toml::{
         ExternalWorkspaceProperties, FromTomlStr, PackageDefaults, PyProjectToml, TomlManifest,
@@ -173,7 +173,21 @@ impl PyProjectManifest {
         let (pixi, pyproject, package_defaults) = self.load_pixi_and_defaults()?;
         let resolved = pyproject.resolve()?;
         let mut groups = resolved.optional_dependencies;
-        groups.extend(resolved.dependency_groups);
+        let toml_workspace = pixi.workspace.as_ref().map(|workspace| &workspace.value);
+        let dependency_groups_as_features = toml_workspace
+            .and_then(|workspace| workspace.dependency_groups_as_features)
+            .unwrap_or(true);
+        let extras: Vec<String> = if toml_workspace
+            .and_then(|workspace| workspace.extras_as_environments)
+            .unwrap_or(false)
+        {
+            groups.keys().cloned().collect()
+        } else {
+            Vec::new()
+        };
+        if dependency_groups_as_features {
+            groups.extend(resolved.dependency_groups);
+        }
 
         // Convert the TOML document into a pixi manifest.
         // TODO:  would be nice to add license, license-file, readme, homepage,
@@ -256,6 +270,27 @@ impl PyProjectManifest {
             }
         }
 
+        // Create an environment for every extra, unless one with the same name is
+        // defined explicitly.
+        for extra in extras {
+            let Ok(name) =
+                EnvironmentName::from_str(&extra.to_lowercase().replace(['_', '.'], "-"))
+            else {
+                tracing::warn!("cannot create an environment for the extra '{extra}'");
+                continue;
+            };
+            if name.is_default() || workspace_manifest.environments.find(&name).is_some() {
+                continue;
+            }
+            workspace_manifest.environments.add(Environment {
+                name,
+                features: vec![extra],
+                solve_group: None,
+                no_default_feature: false,
+                env: Default::default(),
+            });
+        }
+
         Ok((workspace_manifest, package_manifest, warnings))
     }
 }
@@ -547,6 +582,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dependency_groups_as_features_opt_out() {
+        const PYPROJECT_NO_GROUP_FEATURES: &str = r#"
+            [project]
+            name = "example"
+
+            [project.optional-dependencies]
+            extra = ["requests"]
+
+            [dependency-groups]
+            dev = ["pytest"]
+
+            [tool.pixi.workspace]
+            channels = ["conda-forge"]
+            platforms = ["linux-64"]
+            dependency-groups-as-features = false
+            "#;
+
+        let manifest =
+            super::PyProjectManifest::from_toml_str(PYPROJECT_NO_GROUP_FEATURES).unwrap();
+        let (workspace_manifest, _, _) = manifest.into_workspace_manifest(None).unwrap();
+
+        assert!(
+            workspace_manifest
+                .feature(&FeatureName::from("dev"))
+                .is_none()
+        );
+        assert!(
+            workspace_manifest
+                .feature(&FeatureName::from("extra"))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn extras_as_environments() {
+        const PYPROJECT_EXTRAS: &str = r#"
+            [project]
+            name = "example"
+
+            [project.optional-dependencies]
+            docs = ["sphinx"]
+            test_all = ["pytest"]
+            cuda = ["cupy"]
+
+            [tool.pixi.workspace]
+            channels = ["conda-forge"]
+            platforms = ["linux-64"]
+            extras-as-environments = true
+
+            [tool.pixi.environments]
+            cuda = { features = ["cuda"], solve-group = "gpu" }
+            "#;
+
+        let manifest = super::PyProjectManifest::from_toml_str(PYPROJECT_EXTRAS).unwrap();
+        let (workspace_manifest, _, _) = manifest.into_workspace_manifest(None).unwrap();
+
+        let docs = workspace_manifest
+            .environment("docs")
+            .expect("an environment is created for the extra");
+        assert_eq!(docs.features, vec![String::from("docs")]);
+        let test_all = workspace_manifest
+            .environment("test-all")
+            .expect("the name of the environment is normalized");
+        assert_eq!(test_all.features, vec![String::from("test_all")]);
+        // The explicitly defined environment is kept.
+        assert!(
+            workspace_manifest
+                .environment("cuda")
+                .unwrap()
+                .solve_group
+                .is_some()
+        );
+    }
+
     #[test]
     fn optional_dependencies_include_preserves_all_extras() {
         const PYPROJECT_OPTIONAL_DEPENDENCIES: &str = r#"
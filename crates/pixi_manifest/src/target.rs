@@ -642,6 +642,12 @@ impl<T> Targets<T> {
         std::iter::once(&self.default_target).chain(self.targets.iter().map(|(_, target)| target))
     }
 
+    /// Returns a mutable iterator over all targets.
+    pub fn targets_mut(&mut self) -> impl Iterator<Item = &'_ mut T> + '_ {
+        std::iter::once(&mut self.default_target)
+            .chain(self.targets.iter_mut().map(|(_, target)| target))
+    }
+
     /// Returns user defined target selectors
     pub fn user_defined_selectors(&self) -> impl Iterator<Item = &TargetSelector> + '_ {
         self.targets.keys()
@@ -270,6 +270,23 @@ impl Task {
         }
     }
 
+    /// Returns the sandbox policy of the task, tasks without a policy are
+    /// only allowed to write to the workspace.
+    pub fn sandbox(&self) -> Cow<'_, TaskSandbox> {
+        match self {
+            Task::Execute(execute) => Cow::Borrowed(&execute.sandbox),
+            _ => Cow::Owned(TaskSandbox::default()),
+        }
+    }
+
+    /// True if the output of the task is always written to a log file.
+    pub fn log(&self) -> bool {
+        match self {
+            Task::Execute(execute) => execute.log,
+            _ => false,
+        }
+    }
+
     /// Returns the inputs of the task.
     pub fn inputs(&self) -> Option<&GlobPatterns> {
         match self {
@@ -359,6 +376,31 @@ pub struct Execute {
 
     /// The arguments to pass to the task
     pub args: Option<Vec<TaskArg>>,
+
+    /// What the task is allowed to do when it runs in a sandbox
+    pub sandbox: TaskSandbox,
+
+    /// Write the output of the task to a log file in `.pixi/logs`
+    pub log: bool,
+}
+
+/// The policy of a task that is run with `pixi run --sandbox`. Writes outside
+/// of the workspace and network access are denied unless they are allowed
+/// here.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskSandbox {
+    /// Whether the task is allowed to access the network
+    pub network: bool,
+
+    /// Paths outside of the workspace that the task is allowed to write to
+    pub writable: Vec<PathBuf>,
+}
+
+impl TaskSandbox {
+    /// Returns true if the policy does not allow anything beyond the default.
+    pub fn is_default(&self) -> bool {
+        self == &TaskSandbox::default()
+    }
 }
 
 impl From<Execute> for Task {
@@ -891,6 +933,28 @@ impl From<Task> for Item {
                 if let Some(description) = &process.description {
                     table.insert("description", description.into());
                 }
+                if !process.sandbox.is_default() {
+                    let mut sandbox = InlineTable::new();
+                    if process.sandbox.network {
+                        sandbox.insert("network", true.into());
+                    }
+                    if !process.sandbox.writable.is_empty() {
+                        sandbox.insert(
+                            "writable",
+                            Value::Array(Array::from_iter(
+                                process
+                                    .sandbox
+                                    .writable
+                                    .iter()
+                                    .map(|path| path.to_string_lossy().to_string()),
+                            )),
+                        );
+                    }
+                    table.insert("sandbox", Value::InlineTable(sandbox));
+                }
+                if process.log {
+                    table.insert("log", true.into());
+                }
                 Item::Value(Value::InlineTable(table))
             }
             Task::Alias(alias) => {
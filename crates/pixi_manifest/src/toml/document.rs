@@ -55,6 +55,24 @@ impl TomlDocument {
         Ok(current_table)
     }
 
+    /// Retrieve a mutable reference to a target table using key array. Unlike
+    /// [`Self::get_or_insert_nested_table`], missing tables are not inserted.
+    pub fn get_nested_table_mut<'a>(
+        &'a mut self,
+        keys: &[&str],
+    ) -> Result<&'a mut dyn TableLike, TomlError> {
+        let mut current_table = self.0.as_table_mut() as &mut dyn TableLike;
+
+        for part in keys {
+            current_table = current_table
+                .get_mut(part)
+                .ok_or_else(|| TomlError::table_error(part, &keys.join(".")))?
+                .as_table_like_mut()
+                .ok_or_else(|| TomlError::table_error(part, &keys.join(".")))?;
+        }
+        Ok(current_table)
+    }
+
     /// Retrieve a mutable reference to a target table using key array.
     /// If the table is not found, it is inserted into the document.
     pub fn get_or_insert_nested_table<'a>(
@@ -1,3 +1,5 @@
+use indexmap::IndexMap;
+use pixi_toml::TomlIndexMap;
 use toml_span::{DeserError, Spanned, Value, de_helpers::expected};
 
 /// Helper struct to deserialize the environment from TOML.
@@ -7,6 +9,7 @@ pub struct TomlEnvironment {
     pub features: Option<Spanned<Vec<Spanned<String>>>>,
     pub solve_group: Option<String>,
     pub no_default_feature: bool,
+    pub env: IndexMap<String, String>,
 }
 
 #[derive(Debug)]
@@ -22,10 +25,11 @@ impl<'de> toml_span::Deserialize<'de> for TomlEnvironment {
         let features = th.optional_s("features");
         let solve_group = th.optional("solve-group");
         let no_default_feature = th.optional("no-default-feature");
+        let env = th.optional::<TomlIndexMap<_, _>>("env");
 
         th.finalize(None)?;
 
-        if features.is_none() && solve_group.is_none() {
+        if features.is_none() && solve_group.is_none() && env.is_none() {
             return Err(DeserError::from(toml_span::Error {
                 kind: toml_span::ErrorKind::MissingField("features"),
                 span: value.span,
@@ -37,6 +41,7 @@ impl<'de> toml_span::Deserialize<'de> for TomlEnvironment {
             features,
             solve_group,
             no_default_feature: no_default_feature.unwrap_or_default(),
+            env: env.map(TomlIndexMap::into_inner).unwrap_or_default(),
         })
     }
 }
@@ -164,4 +169,18 @@ mod test {
         let top_level = TopLevel::from_toml_str(input).unwrap();
         assert_matches!(top_level.env, TomlEnvironmentList::Map(_));
     }
+
+    #[test]
+    pub fn test_parse_environment_env() {
+        let input = r#"
+            env = { features = ["foo"], env = { FOO = "bar", BAZ = "$FOO/baz" } }
+        "#;
+
+        let toplevel = TopLevel::from_toml_str(input).unwrap();
+        assert_matches!(
+            toplevel.env,
+            TomlEnvironmentList::Map(map) if
+                map.env.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect::<Vec<_>>()
+                    == vec![("FOO", "bar"), ("BAZ", "$FOO/baz")]);
+    }
 }
@@ -0,0 +1,77 @@
+use toml_span::{DeserError, ErrorKind, Value, de_helpers::TableHelper};
+
+use crate::LicensePolicy;
+
+/// A helper struct to deserialize an SPDX expression of a license policy from
+/// a TOML string.
+struct SpdxExpression(String);
+
+impl<'de> toml_span::Deserialize<'de> for SpdxExpression {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let str = value.take_string(None)?;
+        if let Err(e) = spdx::Expression::parse(&str) {
+            return Err(DeserError::from(toml_span::Error {
+                kind: ErrorKind::Custom(
+                    format!("not a valid SPDX expression: {}", e.reason).into(),
+                ),
+                span: value.span,
+                line_info: None,
+            }));
+        }
+        Ok(Self(str.into_owned()))
+    }
+}
+
+impl<'de> toml_span::Deserialize<'de> for LicensePolicy {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let allow = th
+            .optional::<Vec<SpdxExpression>>("allow")
+            .unwrap_or_default();
+        let deny = th
+            .optional::<Vec<SpdxExpression>>("deny")
+            .unwrap_or_default();
+        th.finalize(None)?;
+
+        Ok(Self {
+            allow: allow.into_iter().map(|e| e.0).collect(),
+            deny: deny.into_iter().map(|e| e.0).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use insta::assert_snapshot;
+    use pixi_test_utils::format_parse_error;
+
+    use super::*;
+    use crate::toml::FromTomlStr;
+
+    #[test]
+    fn test_parse_license_policy() {
+        let policy = LicensePolicy::from_toml_str(
+            r#"
+        allow = ["MIT", "Apache-2.0 OR BSD-3-Clause"]
+        deny = ["GPL-3.0-only"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(policy.allow, vec!["MIT", "Apache-2.0 OR BSD-3-Clause"]);
+        assert_eq!(policy.deny, vec!["GPL-3.0-only"]);
+    }
+
+    #[test]
+    fn test_invalid_license_policy() {
+        let input = r#"deny = ["FOOBAR"]"#;
+        let parse_error = LicensePolicy::from_toml_str(input).unwrap_err();
+        assert_snapshot!(format_parse_error(input, parse_error), @r###"
+         × not a valid SPDX expression: unknown term
+          ╭─[pixi.toml:1:10]
+        1 │ deny = ["FOOBAR"]
+          ·          ──────
+          ╰────
+        "###);
+    }
+}
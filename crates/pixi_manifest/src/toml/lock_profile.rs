@@ -0,0 +1,48 @@
+use pixi_toml::{TomlFromStr, TomlWith};
+use toml_span::{DeserError, Value, de_helpers::TableHelper};
+
+use crate::LockProfile;
+
+impl<'de> toml_span::Deserialize<'de> for LockProfile {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+
+        let solve_strategy = th
+            .optional::<TomlWith<_, TomlFromStr<_>>>("solve-strategy")
+            .map(TomlWith::into_inner);
+        let exclude_newer = th
+            .optional::<TomlWith<_, TomlFromStr<_>>>("exclude-newer")
+            .map(TomlWith::into_inner);
+        th.finalize(None)?;
+
+        Ok(Self {
+            solve_strategy,
+            exclude_newer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{SolveStrategy, toml::FromTomlStr};
+
+    #[test]
+    fn test_parse_lock_profile() {
+        let profile = LockProfile::from_toml_str(
+            r#"
+        solve-strategy = "lowest-direct"
+        exclude-newer = "2024-01-01"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(profile.solve_strategy, Some(SolveStrategy::LowestDirect));
+        assert!(profile.exclude_newer.is_some());
+    }
+
+    #[test]
+    fn test_invalid_lock_profile() {
+        assert!(LockProfile::from_toml_str(r#"solve-strategy = "newest""#).is_err());
+        assert!(LockProfile::from_toml_str(r#"channels = ["conda-forge"]"#).is_err());
+    }
+}
@@ -8,7 +8,7 @@ use indexmap::IndexMap;
 use miette::LabeledSpan;
 use pixi_pypi_spec::{PixiPypiSpec, PypiPackageName};
 use pixi_toml::{Same, TomlHashMap, TomlIndexMap, TomlWith};
-use rattler_conda_types::{Platform, Version};
+use rattler_conda_types::{PackageName, Platform, Version};
 use toml_span::{
     DeserError, Spanned, Value,
     de_helpers::{TableHelper, expected},
@@ -77,6 +77,63 @@ impl TomlManifest {
         self.package.is_some()
     }
 
+    /// Returns the locations of the entries that define a conda dependency on
+    /// the given package, in all the dependency tables of the manifest,
+    /// features and targets. A location spans from the start of the name to
+    /// the end of the spec.
+    pub fn conda_dependency_spans(&self, name: &PackageName) -> Vec<Range<usize>> {
+        fn target_maps(
+            target: &TomlTarget,
+        ) -> impl Iterator<Item = &Option<PixiSpanned<UniquePackageMap>>> {
+            [
+                &target.dependencies,
+                &target.host_dependencies,
+                &target.build_dependencies,
+            ]
+            .into_iter()
+        }
+
+        let top_level = [
+            &self.dependencies,
+            &self.host_dependencies,
+            &self.build_dependencies,
+        ]
+        .into_iter();
+        let targets = self
+            .target
+            .iter()
+            .flat_map(|targets| targets.value.values())
+            .flat_map(target_maps);
+        let features = self
+            .feature
+            .iter()
+            .flat_map(|features| features.value.values())
+            .flat_map(|feature| {
+                [
+                    &feature.dependencies,
+                    &feature.host_dependencies,
+                    &feature.build_dependencies,
+                ]
+                .into_iter()
+                .chain(feature.target.values().flat_map(target_maps))
+            });
+
+        top_level
+            .chain(targets)
+            .chain(features)
+            .flatten()
+            .filter_map(|map| {
+                let name_span = map.value.name_spans.get(name)?;
+                let end = map
+                    .value
+                    .value_spans
+                    .get(name)
+                    .map_or(name_span.end, |span| span.end);
+                Some(name_span.start..end)
+            })
+            .collect()
+    }
+
     /// Assume that the manifest is a workspace manifest and convert it as such.
     ///
     /// If the manifest also contains a package section that will be converted
@@ -277,7 +334,8 @@ impl TomlManifest {
         let mut features_used_by_environments = HashSet::new();
         for (name, env) in toml_environments {
             // Decompose the TOML
-            let (included_features, features_span, solve_group, no_default_feature) = match env {
+            let (included_features, features_span, solve_group, no_default_feature, env) = match env
+            {
                 TomlEnvironmentList::Map(env) => {
                     let (features, features_span) = env.features.map_or_else(
                         || (Vec::new(), None),
@@ -288,11 +346,16 @@ impl TomlManifest {
                         features_span,
                         env.solve_group,
                         env.no_default_feature,
+                        env.env,
                     )
                 }
-                TomlEnvironmentList::Seq(features) => {
-                    (features.value, Some(features.span), None, false)
-                }
+                TomlEnvironmentList::Seq(features) => (
+                    features.value,
+                    Some(features.span),
+                    None,
+                    false,
+                    IndexMap::new(),
+                ),
             };
 
             features_used_by_environments
@@ -381,6 +444,7 @@ impl TomlManifest {
                 features: included_features.into_iter().map(Spanned::take).collect(),
                 solve_group: solve_group.map(|sg| solve_groups.add(sg, environment_idx)),
                 no_default_feature,
+                env,
             }));
         }
 
@@ -4,6 +4,8 @@ mod channel;
 mod document;
 mod environment;
 mod feature;
+mod license_policy;
+mod lock_profile;
 mod manifest;
 mod package;
 mod package_target;
@@ -132,6 +132,9 @@ impl<'de> toml_span::Deserialize<'de> for PypiOptions {
             .map(TomlIndexMap::into_inner);
 
         let no_binary = th.optional::<NoBinary>("no-binary");
+        let platform_tags = th
+            .optional::<TomlWith<_, TomlIndexMap<_, TomlFromStr<_>>>>("platform-tags")
+            .map(TomlWith::into_inner);
 
         th.finalize(None)?;
 
@@ -145,6 +148,7 @@ impl<'de> toml_span::Deserialize<'de> for PypiOptions {
             no_build,
             dependency_overrides,
             no_binary,
+            platform_tags,
         })
     }
 }
@@ -153,7 +157,8 @@ impl<'de> toml_span::Deserialize<'de> for FindLinksUrlOrPath {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut table = match value.take() {
             ValueInner::Table(table) => table,
-            other => return Err(expected("a table", other, value.span).into()),
+            ValueInner::String(str) => return Ok(Self::from_str_lossy(&str)),
+            other => return Err(expected("a string or a table", other, value.span).into()),
         };
 
         let mut errors = DeserError { errors: vec![] };
@@ -221,6 +226,18 @@ impl<'de> toml_span::Deserialize<'de> for FindLinksUrlOrPath {
     }
 }
 
+impl FindLinksUrlOrPath {
+    /// Interprets a string as a URL if it has a scheme, e.g. `file:///wheels`
+    /// or `https://example.com/wheels`, and as a path otherwise.
+    fn from_str_lossy(str: &str) -> Self {
+        match Url::parse(str) {
+            // A single letter scheme is the drive of a Windows path.
+            Ok(url) if url.scheme().len() > 1 => Self::Url(url),
+            _ => Self::Path(PathBuf::from(str)),
+        }
+    }
+}
+
 impl<'de> toml_span::Deserialize<'de> for NoBuildIsolation {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         match value.take() {
@@ -250,7 +267,7 @@ mod test {
     use pixi_test_utils::format_parse_error;
 
     use super::*;
-    use crate::toml::FromTomlStr;
+    use crate::{pypi::pypi_options::PypiPlatformTag, toml::FromTomlStr};
 
     #[test]
     fn test_empty() {
@@ -299,10 +316,28 @@ mod test {
                     )
                 )]),),
                 no_binary: Default::default(),
+                platform_tags: None,
             },
         );
     }
 
+    #[test]
+    fn test_find_links_strings() {
+        let input = r#"
+        find-links = ["./wheels", "file:///opt/wheels", "https://example.com/wheels", 'C:\wheels']
+        "#;
+        let options = PypiOptions::from_toml_str(input).unwrap();
+        assert_eq!(
+            options.find_links,
+            Some(vec![
+                FindLinksUrlOrPath::Path("./wheels".into()),
+                FindLinksUrlOrPath::Url(Url::parse("file:///opt/wheels").unwrap()),
+                FindLinksUrlOrPath::Url(Url::parse("https://example.com/wheels").unwrap()),
+                FindLinksUrlOrPath::Path("C:\\wheels".into()),
+            ])
+        );
+    }
+
     #[test]
     fn test_full() {
         let input = r#"
@@ -349,6 +384,44 @@ mod test {
         assert_debug_snapshot!(options);
     }
 
+    #[test]
+    fn test_platform_tags() {
+        let input = r#"
+        platform-tags = { linux-64 = "musllinux_1_2", osx-arm64 = "macosx_12_0" }
+        "#;
+        let options = PypiOptions::from_toml_str(input).unwrap();
+        assert_eq!(
+            options.platform_tag(rattler_conda_types::Platform::Linux64),
+            Some(&PypiPlatformTag::Musllinux { major: 1, minor: 2 })
+        );
+        assert_eq!(
+            options.platform_tag(rattler_conda_types::Platform::OsxArm64),
+            Some(&PypiPlatformTag::Macos {
+                major: 12,
+                minor: 0
+            })
+        );
+        assert_eq!(
+            options.platform_tag(rattler_conda_types::Platform::Win64),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid_platform_tag() {
+        let input = r#"platform-tags = { linux-64 = "alpine" }"#;
+        assert_snapshot!(format_parse_error(
+            input,
+            PypiOptions::from_toml_str(input).unwrap_err()
+        ), @r###"
+         × invalid platform tag 'alpine', expected e.g. 'manylinux_2_17', 'musllinux_1_2' or 'macosx_11_0'
+          ╭─[pixi.toml:1:31]
+        1 │ platform-tags = { linux-64 = "alpine" }
+          ·                               ──────
+          ╰────
+        "###)
+    }
+
     #[test]
     fn test_invalid_strategy_missing_dash() {
         let input = r#"
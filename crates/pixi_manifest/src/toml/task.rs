@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use itertools::Itertools;
@@ -13,7 +14,7 @@ use crate::{
     EnvironmentName, Task, TaskName, WithWarnings,
     task::{
         Alias, ArgName, CmdArgs, Dependency, DependencyArg, Execute, GlobPatterns, TaskArg,
-        TemplateString,
+        TaskSandbox, TemplateString,
     },
     warning::Deprecation,
 };
@@ -44,6 +45,21 @@ impl<'de> toml_span::Deserialize<'de> for GlobPatterns {
     }
 }
 
+impl<'de> toml_span::Deserialize<'de> for TaskSandbox {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        let mut th = TableHelper::new(value)?;
+        let network = th.optional("network").unwrap_or(false);
+        let writable = th
+            .optional::<Vec<String>>("writable")
+            .unwrap_or_default()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        th.finalize(None)?;
+        Ok(TaskSandbox { network, writable })
+    }
+}
+
 impl<'de> toml_span::Deserialize<'de> for TaskArg {
     fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
         let mut th = match value.take() {
@@ -222,6 +238,8 @@ impl<'de> toml_span::Deserialize<'de> for TomlTask {
             let description = th.optional("description");
             let clean_env = th.optional("clean-env").unwrap_or(false);
             let args = th.optional::<Vec<TaskArg>>("args");
+            let sandbox = th.optional("sandbox").unwrap_or_default();
+            let log = th.optional("log").unwrap_or(false);
 
             let mut have_default = false;
             for arg in args.iter().flat_map(|a| a.iter()) {
@@ -250,6 +268,8 @@ impl<'de> toml_span::Deserialize<'de> for TomlTask {
                 description,
                 clean_env,
                 args,
+                sandbox,
+                log,
             }))
         } else {
             let depends_on = depends_on(&mut th)?;
@@ -335,6 +355,35 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_sandbox() {
+        let task = TomlTask::from_toml_str(
+            r#"
+            cmd = "test"
+            sandbox = { network = true, writable = ["/tmp/cache"] }
+        "#,
+        )
+        .unwrap()
+        .value;
+        assert_eq!(
+            task.sandbox().into_owned(),
+            TaskSandbox {
+                network: true,
+                writable: vec![PathBuf::from("/tmp/cache")],
+            }
+        );
+
+        assert!(
+            expect_parse_failure(
+                r#"
+            cmd = "test"
+            sandbox = { net = true }
+        "#
+            )
+            .contains("'net' was not expected here")
+        );
+    }
+
     #[test]
     fn test_depends_on_is_list() {
         insta::assert_snapshot!(expect_parse_failure(
@@ -6,18 +6,19 @@ use std::{
 use indexmap::{IndexMap, IndexSet};
 use pixi_spec::TomlVersionSpecStr;
 use pixi_toml::{TomlFromStr, TomlHashMap, TomlIndexMap, TomlIndexSet, TomlWith};
-use rattler_conda_types::{NamedChannelOrUrl, Platform, Version, VersionSpec};
+use rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform, Version, VersionSpec};
 use toml_span::{DeserError, Span, Spanned, Value, de_helpers::TableHelper};
 use url::Url;
 
 use crate::exclude_newer::ExcludeNewer;
 use crate::{
-    PrioritizedChannel, S3Options, TargetSelector, Targets, TomlError, WithWarnings, Workspace,
+    LicensePolicy, LockProfile, LockProfileName, PrioritizedChannel, S3Options, TargetSelector,
+    Targets, TomlError, WithWarnings, Workspace,
     error::GenericError,
     pypi::pypi_options::PypiOptions,
     toml::{manifest::ExternalWorkspaceProperties, platform::TomlPlatform, preview::TomlPreview},
     utils::PixiSpanned,
-    workspace::{BuildVariantSource, ChannelPriority, SolveStrategy},
+    workspace::{BuildVariantSource, ChannelPriority, CondaPypiOverrides, SolveStrategy},
 };
 
 #[derive(Debug, Clone)]
@@ -46,6 +47,7 @@ pub struct TomlWorkspace {
     pub repository: Option<Url>,
     pub documentation: Option<Url>,
     pub conda_pypi_map: Option<HashMap<NamedChannelOrUrl, String>>,
+    pub conda_pypi_overrides: Option<CondaPypiOverrides>,
     pub pypi_options: Option<PypiOptions>,
     pub s3_options: Option<HashMap<String, S3Options>>,
     pub preview: TomlPreview,
@@ -54,6 +56,19 @@ pub struct TomlWorkspace {
     pub build_variant_files: Option<Vec<Spanned<TomlFromStr<PathBuf>>>>,
     pub requires_pixi: Option<VersionSpec>,
     pub exclude_newer: Option<ExcludeNewer>,
+    pub members: Option<Vec<String>>,
+    pub hold: Option<Vec<PackageName>>,
+    pub license_policy: Option<LicensePolicy>,
+    pub lock_profiles: Option<IndexMap<LockProfileName, LockProfile>>,
+
+    /// Whether the `[dependency-groups]` of a `pyproject.toml` are turned into
+    /// features. Has no effect in a `pixi.toml`.
+    pub dependency_groups_as_features: Option<bool>,
+
+    /// Whether an environment is created for every extra in the
+    /// `[project.optional-dependencies]` of a `pyproject.toml`. Has no effect
+    /// in a `pixi.toml`.
+    pub extras_as_environments: Option<bool>,
 
     pub span: Span,
 }
@@ -133,6 +148,7 @@ impl TomlWorkspace {
             solve_strategy: self.solve_strategy,
             platforms: self.platforms.value,
             conda_pypi_map: self.conda_pypi_map,
+            conda_pypi_overrides: self.conda_pypi_overrides,
             pypi_options: self.pypi_options,
             s3_options: self.s3_options,
             preview,
@@ -147,6 +163,10 @@ impl TomlWorkspace {
             ),
             requires_pixi: self.requires_pixi,
             exclude_newer: self.exclude_newer,
+            members: self.members.unwrap_or_default(),
+            hold: self.hold.unwrap_or_default(),
+            license_policy: self.license_policy.unwrap_or_default(),
+            lock_profiles: self.lock_profiles.unwrap_or_default(),
         })
         .with_warnings(warnings))
     }
@@ -227,6 +247,7 @@ impl<'de> toml_span::Deserialize<'de> for TomlWorkspace {
         let conda_pypi_map = th
             .optional::<TomlHashMap<_, _>>("conda-pypi-map")
             .map(TomlHashMap::into_inner);
+        let conda_pypi_overrides = th.optional("conda-pypi-overrides");
         let pypi_options = th.optional("pypi-options");
         let s3_options = th
             .optional::<TomlHashMap<_, _>>("s3-options")
@@ -246,6 +267,16 @@ impl<'de> toml_span::Deserialize<'de> for TomlWorkspace {
         let exclude_newer = th
             .optional::<TomlWith<_, TomlFromStr<_>>>("exclude-newer")
             .map(TomlWith::into_inner);
+        let members = th.optional("members");
+        let hold = th
+            .optional::<TomlWith<_, Vec<TomlFromStr<_>>>>("hold")
+            .map(TomlWith::into_inner);
+        let license_policy = th.optional("license-policy");
+        let lock_profiles = th
+            .optional::<TomlIndexMap<_, _>>("lock-profiles")
+            .map(TomlIndexMap::into_inner);
+        let dependency_groups_as_features = th.optional("dependency-groups-as-features");
+        let extras_as_environments = th.optional("extras-as-environments");
 
         th.finalize(None)?;
 
@@ -265,6 +296,7 @@ impl<'de> toml_span::Deserialize<'de> for TomlWorkspace {
             repository,
             documentation,
             conda_pypi_map,
+            conda_pypi_overrides,
             pypi_options,
             s3_options,
             preview,
@@ -273,6 +305,12 @@ impl<'de> toml_span::Deserialize<'de> for TomlWorkspace {
             build_variant_files,
             requires_pixi,
             exclude_newer,
+            members,
+            hold,
+            license_policy,
+            lock_profiles,
+            dependency_groups_as_features,
+            extras_as_environments,
             span: value.span,
         })
     }
@@ -359,6 +397,27 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn test_hold_is_normalized() {
+        let input = r#"
+        channels = []
+        platforms = []
+        hold = ["OpenSSL", "cuda-version"]
+        "#;
+        let workspace = TomlWorkspace::from_toml_str(input)
+            .and_then(|w| w.into_workspace(ExternalWorkspaceProperties::default(), None))
+            .unwrap();
+        assert_eq!(
+            workspace
+                .value
+                .hold
+                .iter()
+                .map(|name| name.as_normalized())
+                .collect::<Vec<_>>(),
+            vec!["openssl", "cuda-version"]
+        );
+    }
+
     #[test]
     fn test_missing_build_variant_file() {
         let input = r#"
@@ -1,15 +1,16 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use indexmap::IndexSet;
-use pixi_toml::TomlEnum;
-use rattler_conda_types::{NamedChannelOrUrl, Platform, Version, VersionSpec};
+use indexmap::{IndexMap, IndexSet};
+use pixi_toml::{TomlEnum, TomlIndexMap};
+use rattler_conda_types::{NamedChannelOrUrl, PackageName, Platform, Version, VersionSpec};
 use serde::Deserialize;
-use toml_span::{DeserError, Value};
+use toml_span::{DeserError, Value, de_helpers::expected, value::ValueInner};
 use url::Url;
 
 use super::pypi::pypi_options::PypiOptions;
 use crate::{
-    PrioritizedChannel, S3Options, Targets, exclude_newer::ExcludeNewer, preview::Preview,
+    LicensePolicy, LockProfile, LockProfileName, PrioritizedChannel, S3Options, Targets,
+    exclude_newer::ExcludeNewer, preview::Preview,
 };
 use minijinja::{AutoEscape, Environment, UndefinedBehavior};
 use once_cell::sync::Lazy;
@@ -69,6 +70,9 @@ pub struct Workspace {
     /// URL or Path of the conda to pypi name mapping
     pub conda_pypi_map: Option<HashMap<NamedChannelOrUrl, String>>,
 
+    /// Conda to pypi name overrides that apply to packages of every channel
+    pub conda_pypi_overrides: Option<CondaPypiOverrides>,
+
     /// The pypi options supported in the project
     pub pypi_options: Option<PypiOptions>,
 
@@ -89,6 +93,55 @@ pub struct Workspace {
 
     /// Exclude package candidates that are newer than this date.
     pub exclude_newer: Option<ExcludeNewer>,
+
+    /// The directories of the member packages of the workspace, relative to
+    /// the workspace root. A path ending in `/*` includes all its direct
+    /// subdirectories.
+    pub members: Vec<String>,
+
+    /// Packages that `pixi upgrade` leaves untouched unless they are
+    /// requested explicitly.
+    pub hold: Vec<PackageName>,
+
+    /// The licenses that the locked packages are allowed to use, checked by
+    /// `pixi license check`.
+    pub license_policy: LicensePolicy,
+
+    /// Additional lock-files that are solved with different settings, selected
+    /// with `--lock-profile`.
+    pub lock_profiles: IndexMap<LockProfileName, LockProfile>,
+}
+
+/// Overrides of the conda to pypi name mapping, defined in the
+/// `conda-pypi-overrides` field of the workspace.
+///
+/// The overrides apply to packages from every channel and take precedence over
+/// the mapping of the channel itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CondaPypiOverrides {
+    /// A URL or path of a JSON file that maps conda names to pypi names.
+    Location(String),
+
+    /// A map of conda names to pypi names.
+    Map(IndexMap<PackageName, String>),
+}
+
+impl<'de> toml_span::Deserialize<'de> for CondaPypiOverrides {
+    fn deserialize(value: &mut Value<'de>) -> Result<Self, DeserError> {
+        match value.take() {
+            ValueInner::String(location) => Ok(Self::Location(location.into_owned())),
+            inner @ ValueInner::Table(_) => {
+                let mut value = Value::with_span(inner, value.span);
+                TomlIndexMap::deserialize(&mut value).map(|map| Self::Map(map.into_inner()))
+            }
+            inner => Err(expected(
+                "a URL or path of a mapping file, or a table of conda names to pypi names",
+                inner,
+                value.span,
+            )
+            .into()),
+        }
+    }
 }
 
 /// A source that contributes additional build variant definitions.
@@ -174,7 +174,8 @@ impl PixiPypiSpec {
     }
 
     /// Updates this spec with a new PEP 508 requirement, preserving pixi-specific
-    /// fields (`index`, `extras`) from self.
+    /// fields (`index`, `extras`, and the `path` and `editable` of a path
+    /// requirement) from self.
     ///
     /// This is useful when updating a dependency (e.g., during `pixi upgrade`)
     /// where the version changes but pixi-specific fields like `index` should
@@ -206,6 +207,19 @@ impl PixiPypiSpec {
                     index: index.clone(),
                 };
             }
+            // Both are Path variants - keep the path as it is written, the
+            // requirement only knows the absolute path
+            (
+                PixiPypiSpec::Path {
+                    path: new_path,
+                    editable: new_editable,
+                    ..
+                },
+                PixiPypiSpec::Path { path, editable, .. },
+            ) => {
+                *new_path = path.clone();
+                *new_editable = *editable;
+            }
             _ => {}
         }
 
@@ -447,4 +461,16 @@ mod tests {
                 .join("\n")
         );
     }
+
+    #[test]
+    fn test_update_requirement_keeps_path() {
+        let existing = PixiPypiSpec::Path {
+            path: PathBuf::from("./libs/mylib"),
+            editable: Some(true),
+            extras: Vec::new(),
+        };
+        let url = Url::from_directory_path(std::env::temp_dir()).unwrap();
+        let requirement = Requirement::from_str(&format!("mylib @ {url}")).unwrap();
+        assert_eq!(existing.update_requirement(&requirement).unwrap(), existing);
+    }
 }
@@ -141,7 +141,7 @@ impl<'p> ExecutableTask<'p> {
     }
 
     /// Returns the task as script
-    fn as_script(&self) -> Result<Option<String>, FailedToParseShellScript> {
+    pub fn as_script(&self) -> Result<Option<String>, FailedToParseShellScript> {
         // Convert the task into an executable string
         let context = self.render_context();
         let task = self
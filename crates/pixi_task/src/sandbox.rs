@@ -0,0 +1,166 @@
+use std::path::{Path, PathBuf};
+
+use miette::Diagnostic;
+use pixi_manifest::task::TaskSandbox;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum SandboxError {
+    #[error("running tasks in a sandbox is not supported on {0}")]
+    #[diagnostic(help("run the task without `--sandbox`"))]
+    Unsupported(&'static str),
+
+    #[error("failed to determine the path of the pixi executable")]
+    CurrentExe(#[source] std::io::Error),
+
+    #[error("failed to start the sandbox with '{launcher}'")]
+    #[diagnostic(help(
+        "sandboxing tasks requires `bwrap` (bubblewrap) on Linux and `sandbox-exec` on macOS to be available in the PATH"
+    ))]
+    Launch {
+        launcher: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// A sandbox that denies a task to write outside of the workspace and to
+/// access the network, unless the policy of the task allows it.
+///
+/// The sandbox is implemented with bubblewrap on Linux, which runs the task in
+/// its own mount and network namespace, and with `sandbox-exec` on macOS.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    writable: Vec<PathBuf>,
+    network: bool,
+}
+
+impl Sandbox {
+    /// Constructs the sandbox of a task in the workspace at `workspace_root`.
+    /// Relative paths of the policy are relative to the workspace root and a
+    /// leading `~` is expanded to the home directory.
+    pub fn new(workspace_root: &Path, policy: &TaskSandbox) -> Self {
+        let writable = [workspace_root.to_path_buf(), std::env::temp_dir()]
+            .into_iter()
+            .chain(policy.writable.iter().map(|path| {
+                match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+                    (Ok(rest), Some(home_dir)) => Path::new(&home_dir).join(rest),
+                    _ => workspace_root.join(path),
+                }
+            }))
+            .map(|path| fs_err::canonicalize(&path).unwrap_or(path))
+            .collect();
+        Self {
+            writable,
+            network: policy.network,
+        }
+    }
+
+    /// Returns the paths that can be written to in the sandbox.
+    pub fn writable(&self) -> &[PathBuf] {
+        &self.writable
+    }
+
+    /// Returns a command that runs `program` in the sandbox.
+    pub fn command(&self, program: &Path) -> Result<tokio::process::Command, SandboxError> {
+        let mut command = tokio::process::Command::new(self.launcher()?);
+        if cfg!(target_os = "macos") {
+            command.arg("-p").arg(self.seatbelt_profile()).arg(program);
+        } else {
+            command.args(self.bubblewrap_args()).arg("--").arg(program);
+        }
+        Ok(command)
+    }
+
+    /// Returns the executable that is used to start the sandbox.
+    pub fn launcher(&self) -> Result<&'static str, SandboxError> {
+        if cfg!(target_os = "linux") {
+            Ok("bwrap")
+        } else if cfg!(target_os = "macos") {
+            Ok("sandbox-exec")
+        } else {
+            Err(SandboxError::Unsupported(std::env::consts::OS))
+        }
+    }
+
+    /// The arguments of bubblewrap, the whole filesystem is mounted read-only
+    /// and the writable paths are mounted on top of it.
+    fn bubblewrap_args(&self) -> Vec<String> {
+        let mut args = ["--ro-bind", "/", "/", "--dev", "/dev", "--proc", "/proc"]
+            .map(String::from)
+            .to_vec();
+        for path in &self.writable {
+            let path = path.to_string_lossy().to_string();
+            args.extend(["--bind-try".to_string(), path.clone(), path]);
+        }
+        if !self.network {
+            args.push("--unshare-net".to_string());
+        }
+        args.push("--die-with-parent".to_string());
+        args
+    }
+
+    /// The profile of `sandbox-exec`, which allows everything but writes
+    /// outside of the writable paths and, unless allowed, network access.
+    fn seatbelt_profile(&self) -> String {
+        let mut profile =
+            String::from("(version 1)\n(allow default)\n(deny file-write*)\n(allow file-write*");
+        for path in &self.writable {
+            let path = path
+                .to_string_lossy()
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            profile.push_str(&format!(" (subpath \"{path}\")"));
+        }
+        profile.push_str(" (subpath \"/dev\"))\n");
+        if !self.network {
+            profile.push_str("(deny network-outbound (remote ip))\n");
+        }
+        profile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_policy() {
+        let root = Path::new("/does/not/exist/workspace");
+        let sandbox = Sandbox::new(
+            root,
+            &TaskSandbox {
+                network: false,
+                writable: vec![PathBuf::from("build-cache")],
+            },
+        );
+        assert_eq!(sandbox.writable()[0], root);
+        assert_eq!(sandbox.writable()[2], root.join("build-cache"));
+
+        let args = sandbox.bubblewrap_args();
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert!(args.windows(3).any(|w| w
+            == [
+                "--bind-try",
+                "/does/not/exist/workspace",
+                "/does/not/exist/workspace"
+            ]));
+        let profile = sandbox.seatbelt_profile();
+        assert!(profile.contains("(subpath \"/does/not/exist/workspace\")"));
+        assert!(profile.contains("(deny network-outbound (remote ip))"));
+
+        let sandbox = Sandbox::new(
+            root,
+            &TaskSandbox {
+                network: true,
+                writable: Vec::new(),
+            },
+        );
+        assert!(
+            !sandbox
+                .bubblewrap_args()
+                .contains(&"--unshare-net".to_string())
+        );
+        assert!(!sandbox.seatbelt_profile().contains("network"));
+    }
+}
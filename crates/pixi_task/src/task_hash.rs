@@ -123,6 +123,19 @@ impl TaskHash {
         ComputationHash(format!("{:x}", hasher.finish()))
     }
 
+    /// Computes a hash of the command, the inputs and the environment of the
+    /// task, but not of its outputs. Runs of the task with the same input hash
+    /// produce the same outputs, which makes it the key of the outputs in a
+    /// remote task cache. Returns `None` if the task has no inputs.
+    pub fn input_hash(&self) -> Option<ComputationHash> {
+        self.inputs.as_ref()?;
+        let mut hasher = Xxh3::new();
+        self.command.hash(&mut hasher);
+        self.inputs.hash(&mut hasher);
+        self.environment.hash(&mut hasher);
+        Some(ComputationHash(format!("{:x}", hasher.finish())))
+    }
+
     /// Return the hash that should be used as the name of the task cache file.
     /// It takes the rendered inputs and rendered outputs of the task into account.
     pub fn task_args_hash(task: &ExecutableTask<'_>) -> Result<Option<NameHash>, InputHashesError> {
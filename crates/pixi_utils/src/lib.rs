@@ -1,10 +1,16 @@
 pub mod cache;
 pub mod conda_environment_file;
 pub mod indicatif;
+pub mod metrics;
+pub mod oci;
+pub mod porcelain;
 pub mod prefix;
 mod prefix_guard;
 pub mod reqwest;
+pub mod resume;
 pub mod rlimit;
+pub mod sharded;
+pub mod timings;
 pub mod variants;
 
 mod executable_utils;
@@ -0,0 +1,74 @@
+//! Counts what a pixi invocation did, for the `--metrics-file` report.
+//!
+//! Counting is disabled by default and is enabled with [`enable`]. The
+//! counters are only added to, the installers of the conda and PyPI packages
+//! report the packages they install and remove, and whether the packages were
+//! taken from the cache or downloaded.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use serde::Serialize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNTERS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+
+/// A quantity that is counted during a pixi invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Packages that were installed in a prefix.
+    PackagesInstalled,
+    /// Packages that were removed from a prefix.
+    PackagesRemoved,
+    /// Packages that were installed from the cache.
+    CacheHits,
+    /// Packages that had to be downloaded before they were installed.
+    CacheMisses,
+    /// The bytes of the downloaded packages.
+    BytesDownloaded,
+}
+
+/// Enables the counting.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if the counters are recorded.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Adds `value` to the counter, if counting is enabled.
+pub fn add(counter: Counter, value: u64) {
+    if is_enabled() {
+        COUNTERS[counter as usize].fetch_add(value, Ordering::Relaxed);
+    }
+}
+
+/// The values of the counters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Metrics {
+    pub packages_installed: u64,
+    pub packages_removed: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// The fraction of the installed packages that were taken from the cache,
+    /// `None` if no packages were installed.
+    pub cache_hit_rate: Option<f64>,
+    pub bytes_downloaded: u64,
+}
+
+/// Returns the current values of the counters.
+pub fn summary() -> Metrics {
+    let get = |counter: Counter| COUNTERS[counter as usize].load(Ordering::Relaxed);
+    let cache_hits = get(Counter::CacheHits);
+    let cache_misses = get(Counter::CacheMisses);
+    let lookups = cache_hits + cache_misses;
+    Metrics {
+        packages_installed: get(Counter::PackagesInstalled),
+        packages_removed: get(Counter::PackagesRemoved),
+        cache_hits,
+        cache_misses,
+        cache_hit_rate: (lookups > 0).then(|| cache_hits as f64 / lookups as f64),
+        bytes_downloaded: get(Counter::BytesDownloaded),
+    }
+}
@@ -0,0 +1,573 @@
+//! Middleware to resolve `oci://` channel URLs against an OCI registry.
+//!
+//! This follows the same layout as [`rattler_networking::OciMiddleware`] (and
+//! the conda-forge mirrors on `ghcr.io`), but looks up credentials for the
+//! registry host in the authentication storage before requesting a registry
+//! token. This makes it possible to host channels on private registries after
+//! running `pixi auth login <registry>`. The tokens are cached per repository
+//! until they expire.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::Extensions;
+use rattler_networking::{Authentication, AuthenticationStorage};
+use reqwest::{
+    Request, Response, ResponseBuilderExt,
+    header::{ACCEPT, AUTHORIZATION, HeaderValue},
+};
+use reqwest_middleware::{Middleware, Next};
+use serde::Deserialize;
+use url::Url;
+
+use crate::reqwest::LazyReqwestClient;
+
+/// The URL scheme of channels that are stored in an OCI registry.
+pub const OCI_SCHEME: &str = "oci";
+
+/// The header used by rattler to pass the expected hash of a package. When
+/// present, we can fetch the blob directly without resolving the manifest.
+const EXPECTED_SHA256_HEADER: &str = "X-Expected-Sha256";
+
+/// The lifetime of a registry token that does not specify one, as defined by
+/// the token authentication specification of the registry API.
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Tokens are renewed this long before they expire, so they don't expire while
+/// a request is in flight.
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OciError {
+    #[error("failed to request '{0}' from the OCI registry")]
+    Request(Url, #[source] reqwest::Error),
+
+    #[error("'{0}' is not a valid OCI url")]
+    InvalidUrl(Url),
+
+    #[error(transparent)]
+    ParseUrl(#[from] url::ParseError),
+
+    #[error("the OCI registry returned an invalid token")]
+    InvalidToken(#[from] reqwest::header::InvalidHeaderValue),
+
+    #[error("the OCI manifest does not contain a layer of type '{0}'")]
+    LayerNotFound(&'static str),
+}
+
+/// The location of an artifact inside an OCI registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OciReference {
+    /// The host of the registry (e.g. `ghcr.io`).
+    host: String,
+    /// The host and port of the registry.
+    authority: String,
+    /// The repository that contains the artifact (e.g.
+    /// `channel-mirrors/conda-forge/linux-64/python`).
+    repository: String,
+    /// The tag of the artifact.
+    tag: String,
+    /// The media type of the layer that contains the requested file.
+    media_type: &'static str,
+}
+
+impl OciReference {
+    /// Determine where the file referenced by the given `oci://` url is stored
+    /// in the registry. Returns `None` if the file is not something that can
+    /// be stored in an OCI registry (e.g. `.jlap` files or sharded repodata).
+    fn from_url(url: &Url) -> Result<Option<Self>, OciError> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| OciError::InvalidUrl(url.clone()))?
+            .to_string();
+        let authority = match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.clone(),
+        };
+        let file_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|segment| !segment.is_empty())
+            .ok_or_else(|| OciError::InvalidUrl(url.clone()))?;
+
+        let (name, tag, media_type) = if let Some(archive) = file_name.strip_suffix(".conda") {
+            let Some((name, tag)) = split_archive_name(archive) else {
+                return Err(OciError::InvalidUrl(url.clone()));
+            };
+            (name, tag, "application/vnd.conda.package.v2")
+        } else if let Some(archive) = file_name.strip_suffix(".tar.bz2") {
+            let Some((name, tag)) = split_archive_name(archive) else {
+                return Err(OciError::InvalidUrl(url.clone()));
+            };
+            (name, tag, "application/vnd.conda.package.v1")
+        } else {
+            let media_type = match file_name {
+                "repodata.json" => "application/vnd.conda.repodata.v1+json",
+                "repodata.json.gz" => "application/vnd.conda.repodata.v1+json+gzip",
+                "repodata.json.zst" => "application/vnd.conda.repodata.v1+json+zst",
+                _ => return Ok(None),
+            };
+            ("repodata.json", String::from("latest"), media_type)
+        };
+
+        // OCI repository names are not allowed to start with an underscore.
+        let name = if name.starts_with('_') {
+            format!("zzz{name}")
+        } else {
+            name.to_string()
+        };
+
+        let repository = url.join(&name)?.path().trim_start_matches('/').to_string();
+
+        Ok(Some(Self {
+            host,
+            authority,
+            repository,
+            tag,
+            media_type,
+        }))
+    }
+
+    /// Returns the scope of the token that is needed to pull the artifact.
+    fn scope(&self) -> String {
+        format!("repository:{}:pull", self.repository)
+    }
+
+    fn token_url(&self, scheme: &str) -> Result<Url, url::ParseError> {
+        Url::parse(&format!(
+            "{scheme}://{}/token?scope={}",
+            self.authority,
+            self.scope()
+        ))
+    }
+
+    fn manifest_url(&self, scheme: &str) -> Result<Url, url::ParseError> {
+        Url::parse(&format!(
+            "{scheme}://{}/v2/{}/manifests/{}",
+            self.authority, self.repository, self.tag
+        ))
+    }
+
+    fn blob_url(&self, scheme: &str, digest: &str) -> Result<Url, url::ParseError> {
+        Url::parse(&format!(
+            "{scheme}://{}/v2/{}/blobs/{}",
+            self.authority, self.repository, digest
+        ))
+    }
+}
+
+/// Splits the name of a package archive (without extension) into the package
+/// name and the tag under which it is stored in the registry.
+fn split_archive_name(archive: &str) -> Option<(&str, String)> {
+    let mut parts = archive.rsplitn(3, '-');
+    let build = parts.next()?;
+    let version = parts.next()?;
+    let name = parts.next()?;
+
+    // OCI tags are not allowed to contain `+`, `!`, or `=`.
+    let tag = format!("{version}-{build}")
+        .replace('+', "__p__")
+        .replace('!', "__e__")
+        .replace('=', "__eq__");
+
+    Some((name, tag))
+}
+
+#[derive(Debug, Deserialize)]
+struct OciToken {
+    token: String,
+    /// The number of seconds the token is valid for.
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// A registry token and the moment it has to be renewed.
+#[derive(Debug, Clone)]
+struct CachedToken {
+    token: String,
+    renew_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct OciManifest {
+    layers: Vec<OciLayer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OciLayer {
+    digest: String,
+    media_type: String,
+}
+
+/// Middleware that rewrites requests to `oci://` urls to requests against the
+/// registry that stores the artifact.
+///
+/// The rewritten request carries the registry token in its `Authorization`
+/// header, the authentication middlewares that run after this middleware leave
+/// such requests alone.
+#[derive(Clone)]
+pub struct OciMiddleware {
+    client: LazyReqwestClient,
+    auth_storage: AuthenticationStorage,
+    /// The registry tokens by the host of the registry and their scope.
+    tokens: Arc<Mutex<HashMap<(String, String), CachedToken>>>,
+    /// The scheme of the registry API, only tests talk to registries without
+    /// TLS.
+    scheme: &'static str,
+}
+
+impl OciMiddleware {
+    pub fn new(client: LazyReqwestClient, auth_storage: AuthenticationStorage) -> Self {
+        Self {
+            client,
+            auth_storage,
+            tokens: Arc::default(),
+            scheme: "https",
+        }
+    }
+
+    /// Returns the cached token for the reference, or requests a new one.
+    async fn token(&self, reference: &OciReference) -> Result<String, OciError> {
+        let key = (reference.authority.clone(), reference.scope());
+        if let Some(cached) = self.tokens.lock().expect("poisoned lock").get(&key)
+            && cached.renew_at > Instant::now()
+        {
+            return Ok(cached.token.clone());
+        }
+
+        let requested_at = Instant::now();
+        let token = self.request_token(reference).await?;
+        let lifetime = token
+            .expires_in
+            .map_or(DEFAULT_TOKEN_LIFETIME, Duration::from_secs);
+        self.tokens.lock().expect("poisoned lock").insert(
+            key,
+            CachedToken {
+                token: token.token.clone(),
+                renew_at: requested_at + lifetime.saturating_sub(TOKEN_EXPIRY_MARGIN),
+            },
+        );
+        Ok(token.token)
+    }
+
+    /// Requests a pull token for the given reference. If credentials for the
+    /// registry are stored they are used to authenticate the request,
+    /// otherwise an anonymous token is requested.
+    async fn request_token(&self, reference: &OciReference) -> Result<OciToken, OciError> {
+        let credentials = match self.auth_storage.get(&reference.host) {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                tracing::warn!(
+                    "failed to retrieve credentials for '{}': {err}",
+                    reference.host
+                );
+                None
+            }
+        };
+
+        // A bearer token can be used as-is to talk to the registry.
+        if let Some(Authentication::BearerToken(token)) = credentials {
+            return Ok(OciToken {
+                token,
+                expires_in: None,
+            });
+        }
+
+        let token_url = reference.token_url(self.scheme)?;
+        let mut request = self.client.clone().into_client().get(token_url.clone());
+        if let Some(Authentication::BasicHTTP { username, password }) = credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+
+        let token: OciToken = request
+            .send()
+            .await
+            .and_then(Response::error_for_status)
+            .map_err(|err| OciError::Request(token_url.clone(), err))?
+            .json()
+            .await
+            .map_err(|err| OciError::Request(token_url, err))?;
+
+        Ok(token)
+    }
+
+    /// Rewrites the request to point to the blob in the registry that contains
+    /// the requested file.
+    async fn resolve(&self, req: &mut Request, reference: &OciReference) -> Result<(), OciError> {
+        let token = self.token(reference).await?;
+
+        let mut header = HeaderValue::from_str(&format!("Bearer {token}"))?;
+        header.set_sensitive(true);
+        req.headers_mut().insert(AUTHORIZATION, header);
+
+        // If we know the hash of the file we can fetch the blob directly,
+        // otherwise we first have to look up the digest in the manifest.
+        let expected_sha256 = req
+            .headers()
+            .get(EXPECTED_SHA256_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToOwned::to_owned);
+        let digest = match expected_sha256 {
+            Some(sha256) => format!("sha256:{sha256}"),
+            None => {
+                let manifest_url = reference.manifest_url(self.scheme)?;
+                let manifest: OciManifest = self
+                    .client
+                    .clone()
+                    .into_client()
+                    .get(manifest_url.clone())
+                    .bearer_auth(&token)
+                    .header(ACCEPT, "application/vnd.oci.image.manifest.v1+json")
+                    .send()
+                    .await
+                    .and_then(Response::error_for_status)
+                    .map_err(|err| OciError::Request(manifest_url.clone(), err))?
+                    .json()
+                    .await
+                    .map_err(|err| OciError::Request(manifest_url, err))?;
+
+                manifest
+                    .layers
+                    .into_iter()
+                    .find(|layer| layer.media_type == reference.media_type)
+                    .map(|layer| layer.digest)
+                    .ok_or(OciError::LayerNotFound(reference.media_type))?
+            }
+        };
+
+        *req.url_mut() = reference.blob_url(self.scheme, &digest)?;
+        Ok(())
+    }
+}
+
+/// Constructs a `404 Not Found` response for files that are not available in
+/// the registry, this allows the caller to fall back to other files.
+fn not_found(url: &Url, body: &str) -> Response {
+    Response::from(
+        http::response::Builder::new()
+            .status(http::StatusCode::NOT_FOUND)
+            .url(url.clone())
+            .body(body.to_string())
+            .expect("a 404 response is always valid"),
+    )
+}
+
+#[async_trait::async_trait]
+impl Middleware for OciMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if req.url().scheme() != OCI_SCHEME {
+            return next.run(req, extensions).await;
+        }
+
+        let reference = match OciReference::from_url(req.url()) {
+            Ok(Some(reference)) => reference,
+            Ok(None) => {
+                return Ok(not_found(
+                    req.url(),
+                    "this file type is not stored in OCI registries",
+                ));
+            }
+            Err(err) => return Err(reqwest_middleware::Error::middleware(err)),
+        };
+
+        match self.resolve(&mut req, &reference).await {
+            Ok(()) => next.run(req, extensions).await,
+            Err(OciError::LayerNotFound(media_type)) => Ok(not_found(
+                req.url(),
+                &format!("no layer available for media type '{media_type}'"),
+            )),
+            Err(err) => Err(reqwest_middleware::Error::middleware(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+
+    use pixi_config::Config;
+    use rattler_networking::{
+        AuthenticationMiddleware, authentication_storage::backends::memory::MemoryStorage,
+    };
+    use reqwest_middleware::ClientWithMiddleware;
+
+    use super::*;
+
+    /// Starts a registry that hands out `registry-token` and serves every blob,
+    /// returns its address and the request lines and `Authorization` headers
+    /// of the requests it received.
+    fn serve_registry() -> (String, Arc<Mutex<Vec<(String, Option<String>)>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut authorization = None;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("authorization")
+                    {
+                        authorization = Some(value.trim().to_string());
+                    }
+                }
+                let body = if request_line.starts_with("GET /token") {
+                    r#"{"token": "registry-token", "expires_in": 300}"#
+                } else {
+                    "blob"
+                };
+                received
+                    .lock()
+                    .unwrap()
+                    .push((request_line.trim().to_string(), authorization));
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (address, requests)
+    }
+
+    #[tokio::test]
+    async fn test_stored_credentials_are_exchanged_for_a_registry_token() {
+        let (address, requests) = serve_registry();
+
+        let mut auth_storage = AuthenticationStorage::empty();
+        auth_storage.add_backend(Arc::new(MemoryStorage::new()));
+        auth_storage
+            .store(
+                "127.0.0.1",
+                &Authentication::BasicHTTP {
+                    username: String::from("user"),
+                    password: String::from("secret"),
+                },
+            )
+            .unwrap();
+
+        // The same order as in the middleware stack of pixi.
+        let mut middleware = OciMiddleware::new(
+            LazyReqwestClient::new(&Config::default()).unwrap(),
+            auth_storage.clone(),
+        );
+        middleware.scheme = "http";
+        let client = ClientWithMiddleware::new(
+            reqwest::Client::new(),
+            vec![
+                Arc::new(middleware) as Arc<dyn Middleware>,
+                Arc::new(AuthenticationMiddleware::from_auth_storage(auth_storage)),
+            ],
+        );
+
+        let url = format!("oci://{address}/channel/linux-64/foo-1.0-h0_0.conda");
+        for _ in 0..2 {
+            let body = client
+                .get(&url)
+                .header(EXPECTED_SHA256_HEADER, "abc")
+                .send()
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap();
+            assert_eq!(body, "blob");
+        }
+
+        // The token is requested once with the stored credentials, and the
+        // blobs are requested with the token.
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(
+            requests,
+            vec![
+                (
+                    String::from("GET /token?scope=repository:channel/linux-64/foo:pull HTTP/1.1"),
+                    Some(String::from("Basic dXNlcjpzZWNyZXQ="))
+                ),
+                (
+                    String::from("GET /v2/channel/linux-64/foo/blobs/sha256:abc HTTP/1.1"),
+                    Some(String::from("Bearer registry-token"))
+                ),
+                (
+                    String::from("GET /v2/channel/linux-64/foo/blobs/sha256:abc HTTP/1.1"),
+                    Some(String::from("Bearer registry-token"))
+                ),
+            ]
+        );
+    }
+
+    fn reference(url: &str) -> Option<OciReference> {
+        OciReference::from_url(&Url::parse(url).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_package_reference() {
+        let reference = reference(
+            "oci://ghcr.io/channel-mirrors/conda-forge/linux-64/xtensor-0.25.0-h2ffa867_0.conda",
+        )
+        .unwrap();
+        assert_eq!(reference.host, "ghcr.io");
+        assert_eq!(
+            reference.repository,
+            "channel-mirrors/conda-forge/linux-64/xtensor"
+        );
+        assert_eq!(reference.tag, "0.25.0-h2ffa867_0");
+        assert_eq!(reference.media_type, "application/vnd.conda.package.v2");
+    }
+
+    #[test]
+    fn test_package_reference_escapes_tag() {
+        let reference =
+            reference("oci://ghcr.io/my-org/channel/noarch/_private-1!2.0+local-py_0.tar.bz2")
+                .unwrap();
+        assert_eq!(reference.repository, "my-org/channel/noarch/zzz_private");
+        assert_eq!(reference.tag, "1__e__2.0__p__local-py_0");
+        assert_eq!(reference.media_type, "application/vnd.conda.package.v1");
+    }
+
+    #[test]
+    fn test_repodata_reference() {
+        let reference =
+            reference("oci://ghcr.io/my-org/channel/linux-64/repodata.json.zst").unwrap();
+        assert_eq!(
+            reference.repository,
+            "my-org/channel/linux-64/repodata.json"
+        );
+        assert_eq!(reference.tag, "latest");
+        assert_eq!(
+            reference.media_type,
+            "application/vnd.conda.repodata.v1+json+zst"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_files() {
+        assert!(reference("oci://ghcr.io/my-org/channel/linux-64/repodata.jlap").is_none());
+        assert!(
+            reference("oci://ghcr.io/my-org/channel/linux-64/repodata_shards.msgpack.zst")
+                .is_none()
+        );
+    }
+}
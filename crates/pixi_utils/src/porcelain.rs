@@ -0,0 +1,50 @@
+//! The porcelain output mode for scripts.
+//!
+//! Porcelain mode is disabled by default and is enabled with [`enable`], e.g.
+//! by the `--porcelain` flag. In porcelain mode commands print their results
+//! as plain lines of tab separated values, without headers, tables, styling
+//! or progress bars. This format is kept stable between releases, unlike the
+//! human-facing output.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables porcelain output for the rest of the invocation.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if commands should print porcelain output.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Formats the values as a single porcelain line, the values are separated by
+/// tabs. Tabs and newlines in the values are replaced by spaces, so every
+/// line always has the same number of values.
+pub fn line<I, S>(values: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    values
+        .into_iter()
+        .map(|value| value.as_ref().replace(['\t', '\n', '\r'], " "))
+        .collect::<Vec<_>>()
+        .join("\t")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        assert_eq!(line(["numpy", "2.2.1", ""]), "numpy\t2.2.1\t");
+        assert_eq!(
+            line(["start", "Start the\tserver\nnow"]),
+            "start\tStart the server now"
+        );
+    }
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 use miette::IntoDiagnostic;
-use pixi_auth::{get_auth_middleware, get_auth_store};
+use pixi_auth::{OidcMiddleware, get_auth_middleware, get_auth_store};
 use pixi_config::Config;
 use pixi_consts::consts;
 use rattler_networking::{
@@ -18,10 +18,12 @@ use reqwest_middleware::{ClientWithMiddleware, Middleware};
 use reqwest_retry::RetryTransientMiddleware;
 use retry_policies::policies::ExponentialBackoff;
 
-/// The default retry policy employed by pixi.
-/// TODO: At some point we might want to make this configurable.
-pub fn default_retry_policy() -> ExponentialBackoff {
-    ExponentialBackoff::builder().build_with_max_retries(3)
+use crate::resume::ResumeDownloadMiddleware;
+
+/// The retry policy employed by pixi, which retries a failed request up to
+/// `max-retries` times with an exponential backoff.
+pub fn retry_policy(config: &Config) -> ExponentialBackoff {
+    ExponentialBackoff::builder().build_with_max_retries(config.max_retries())
 }
 
 pub fn mirror_middleware(config: &Config) -> MirrorMiddleware {
@@ -60,6 +62,19 @@ pub fn oci_middleware() -> OciMiddleware {
     OciMiddleware
 }
 
+/// Returns the middleware that requests short-lived tokens for the hosts in
+/// `auth.oidc`, or `None` if no hosts are configured.
+pub fn oidc_middleware(config: &Config) -> miette::Result<Option<OidcMiddleware>> {
+    if config.auth.oidc.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(OidcMiddleware::new(
+        LazyReqwestClient::new(config)?.into_client(),
+        config.auth.oidc.clone(),
+    )))
+}
+
 static DEFAULT_REQWEST_USER_AGENT: LazyLock<String> =
     LazyLock::new(|| format!("pixi/{}", consts::PIXI_VERSION));
 static DEFAULT_REQWEST_TIMEOUT_SEC: Duration = Duration::from_secs(5 * 60);
@@ -161,11 +176,24 @@ pub fn build_reqwest_middleware_stack(
 ) -> miette::Result<Box<[Arc<dyn Middleware>]>> {
     let mut result: Vec<Arc<dyn Middleware>> = Vec::new();
 
+    // Fall back to `repodata.json` if the shard index of a channel cannot be
+    // fetched. This has to wrap all other middlewares to see the final result.
+    result.push(Arc::new(crate::sharded::ShardedRepodataFallbackMiddleware));
+
     if !config.mirror_map().is_empty() {
         result.push(Arc::new(mirror_middleware(config)));
-        result.push(Arc::new(oci_middleware()));
     }
 
+    // Resolve `oci://` urls (either from a channel or a mirror) against the
+    // registry, using stored credentials for private registries. The OIDC and
+    // authentication middlewares below skip requests that already carry an
+    // `Authorization` header, so they keep the registry token.
+    let store = get_auth_store(config).into_diagnostic()?;
+    result.push(Arc::new(crate::oci::OciMiddleware::new(
+        LazyReqwestClient::new(config)?,
+        store.clone(),
+    )));
+
     result.push(Arc::new(GCSMiddleware));
 
     let s3_config_global = config.compute_s3_config();
@@ -174,15 +202,26 @@ pub fn build_reqwest_middleware_stack(
     s3_config.extend(s3_config_global);
     s3_config.extend(s3_config_project);
 
-    let store = get_auth_store(config).into_diagnostic()?;
     result.push(Arc::new(S3Middleware::new(s3_config, store)));
 
+    if let Some(oidc_middleware) = oidc_middleware(config)? {
+        result.push(Arc::new(oidc_middleware));
+    }
+
     result.push(Arc::new(
         get_auth_middleware(config).expect("could not create auth middleware"),
     ));
 
+    let retry_policy = retry_policy(config);
     result.push(Arc::new(RetryTransientMiddleware::new_with_policy(
-        default_retry_policy(),
+        retry_policy,
+    )));
+
+    // Downloads that are interrupted after the response was received are not
+    // seen by the retry middleware, so they are resumed separately.
+    result.push(Arc::new(ResumeDownloadMiddleware::new(
+        LazyReqwestClient::new(config)?,
+        retry_policy,
     )));
 
     Ok(result.into_boxed_slice())
@@ -280,6 +319,11 @@ pub fn uv_middlewares(config: &Config) -> Vec<Arc<dyn Middleware>> {
     // Add authentication middleware after mirror rewriting so it can authenticate
     // against the rewritten URLs (important for mirrors that require different
     // credentials)
+    match oidc_middleware(config) {
+        Ok(Some(oidc_middleware)) => middlewares.push(Arc::new(oidc_middleware)),
+        Ok(None) => {}
+        Err(err) => tracing::warn!("failed to create OIDC middleware: {err}"),
+    }
     if let Ok(auth_middleware) = get_auth_middleware(config) {
         middlewares.push(Arc::new(auth_middleware));
     }
@@ -0,0 +1,337 @@
+//! Resumption of interrupted downloads.
+//!
+//! The retry middleware only sees requests that fail before the headers of
+//! the response are received. A download whose connection is dropped while
+//! the body is streamed, e.g. by a flaky proxy, otherwise fails as a whole
+//! and has to start over. This middleware continues such a download with a
+//! range request from the last received byte instead.
+
+use std::{
+    error::Error,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, ready},
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+use futures::{Stream, StreamExt, stream::BoxStream};
+use http::Extensions;
+use http_body::{Frame, SizeHint};
+use reqwest::{
+    Method, Request, Response, ResponseBuilderExt, StatusCode,
+    header::{
+        ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_RANGE, ETAG, HeaderValue, IF_RANGE, LAST_MODIFIED,
+        RANGE,
+    },
+};
+use reqwest_middleware::{Middleware, Next};
+use retry_policies::{RetryDecision, RetryPolicy, policies::ExponentialBackoff};
+use url::Url;
+
+use crate::reqwest::LazyReqwestClient;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+enum ResumeError {
+    #[error("the server did not resume the download of '{0}' at byte {1}")]
+    RangeNotSatisfied(Url, u64),
+
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+}
+
+/// Middleware that resumes downloads that are interrupted while their body is
+/// received.
+///
+/// Only downloads of which the server advertises support for range requests
+/// are resumed. The number of resumptions and the delay between them follow
+/// the retry policy.
+pub struct ResumeDownloadMiddleware {
+    client: LazyReqwestClient,
+    retry_policy: ExponentialBackoff,
+}
+
+impl ResumeDownloadMiddleware {
+    /// Constructs the middleware. The `client` is used to send the range
+    /// requests, so the middleware has to come after the middlewares that
+    /// modify the request, like authentication.
+    pub fn new(client: LazyReqwestClient, retry_policy: ExponentialBackoff) -> Self {
+        Self {
+            client,
+            retry_policy,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for ResumeDownloadMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let template = if req.method() == Method::GET && !req.headers().contains_key(RANGE) {
+            req.try_clone()
+        } else {
+            None
+        };
+
+        let response = next.run(req, extensions).await?;
+        let Some(length) = resumable_length(&response) else {
+            return Ok(response);
+        };
+        let Some(request) = template else {
+            return Ok(response);
+        };
+
+        let url = response.url().clone();
+        let mut builder = http::response::Builder::new()
+            .status(response.status())
+            .version(response.version())
+            .url(url.clone());
+        if let Some(headers) = builder.headers_mut() {
+            headers.extend(response.headers().clone());
+        }
+
+        // A strong validator makes sure that the remaining bytes are taken from
+        // the same file.
+        let validator = response
+            .headers()
+            .get(ETAG)
+            .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+            .or_else(|| response.headers().get(LAST_MODIFIED))
+            .cloned();
+
+        let download = Download {
+            client: self.client.clone(),
+            request,
+            url,
+            validator,
+            length,
+            received: 0,
+            body: response.bytes_stream().boxed(),
+            retry_policy: self.retry_policy,
+            started: SystemTime::now(),
+            resumes: 0,
+        };
+        let body = ResumableBody {
+            stream: Mutex::new(download.into_stream().boxed()),
+            remaining: length,
+        };
+        Ok(Response::from(
+            builder
+                .body(reqwest::Body::wrap(body))
+                .expect("the parts of the response are valid"),
+        ))
+    }
+}
+
+/// Returns the length of the body of the response if the download can be
+/// resumed with a range request.
+fn resumable_length(response: &Response) -> Option<u64> {
+    let headers = response.headers();
+    if response.status() != StatusCode::OK
+        || headers.get(ACCEPT_RANGES).is_none_or(|value| value != "bytes")
+        // The range refers to the encoded bytes, which are not the bytes that
+        // are received.
+        || headers.contains_key(CONTENT_ENCODING)
+    {
+        return None;
+    }
+    response.content_length().filter(|length| *length > 0)
+}
+
+/// Returns true if the `Content-Range` of a partial response starts at the
+/// given offset.
+fn starts_at(content_range: Option<&HeaderValue>, offset: u64) -> bool {
+    content_range
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("bytes "))
+        .and_then(|range| range.split_once('-'))
+        .is_some_and(|(start, _)| start.parse::<u64>().ok() == Some(offset))
+}
+
+/// The body of a download that can be resumed. Unlike a plain stream it
+/// reports the number of remaining bytes, so that the length of the response
+/// is still known to consumers like progress bars.
+struct ResumableBody {
+    // The mutex is never locked, it only makes the body `Sync` as required by
+    // `reqwest::Body::wrap`.
+    stream: Mutex<BoxStream<'static, Result<Bytes, BoxError>>>,
+    remaining: u64,
+}
+
+impl http_body::Body for ResumableBody {
+    type Data = Bytes;
+    type Error = BoxError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let stream = this
+            .stream
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match ready!(stream.poll_next_unpin(cx)) {
+            Some(Ok(bytes)) => {
+                this.remaining = this.remaining.saturating_sub(bytes.len() as u64);
+                Poll::Ready(Some(Ok(Frame::data(bytes))))
+            }
+            Some(Err(err)) => Poll::Ready(Some(Err(err))),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.remaining)
+    }
+}
+
+/// The state of a download that can be resumed.
+struct Download {
+    client: LazyReqwestClient,
+    request: Request,
+    url: Url,
+    validator: Option<HeaderValue>,
+    length: u64,
+    received: u64,
+    body: BoxStream<'static, reqwest::Result<Bytes>>,
+    retry_policy: ExponentialBackoff,
+    started: SystemTime,
+    resumes: u32,
+}
+
+impl Download {
+    /// Returns the body of the download, resuming it when it is interrupted.
+    fn into_stream(self) -> impl Stream<Item = Result<Bytes, BoxError>> {
+        futures::stream::unfold(Some(self), |download| async move {
+            let mut download = download?;
+            loop {
+                match download.body.next().await {
+                    Some(Ok(bytes)) => {
+                        download.received += bytes.len() as u64;
+                        return Some((Ok(bytes), Some(download)));
+                    }
+                    Some(Err(err)) => {
+                        if let Err(err) = download.resume(err.into()).await {
+                            return Some((Err(err), None));
+                        }
+                    }
+                    None => return None,
+                }
+            }
+        })
+    }
+
+    /// Continues the download after it was interrupted by `err`, or returns
+    /// the error if it cannot be continued.
+    async fn resume(&mut self, mut err: BoxError) -> Result<(), BoxError> {
+        while self.received < self.length {
+            let RetryDecision::Retry { execute_after } =
+                self.retry_policy.should_retry(self.started, self.resumes)
+            else {
+                break;
+            };
+            self.resumes += 1;
+            tracing::warn!(
+                "the download of {} was interrupted after {} of {} bytes ({err}), resuming",
+                self.url,
+                self.received,
+                self.length
+            );
+            if let Ok(delay) = execute_after.duration_since(SystemTime::now()) {
+                tokio::time::sleep(delay).await;
+            }
+
+            match self.request_remaining().await {
+                Ok(body) => {
+                    self.body = body;
+                    return Ok(());
+                }
+                Err(ResumeError::Request(request_err)) => err = request_err.into(),
+                Err(resume_err) => return Err(resume_err.into()),
+            }
+        }
+        Err(err)
+    }
+
+    /// Requests the bytes of the download that have not been received yet.
+    async fn request_remaining(
+        &self,
+    ) -> Result<BoxStream<'static, reqwest::Result<Bytes>>, ResumeError> {
+        let Some(mut request) = self.request.try_clone() else {
+            return Err(ResumeError::RangeNotSatisfied(
+                self.url.clone(),
+                self.received,
+            ));
+        };
+        let headers = request.headers_mut();
+        headers.insert(
+            RANGE,
+            HeaderValue::from_str(&format!("bytes={}-", self.received))
+                .expect("a byte range is a valid header value"),
+        );
+        if let Some(validator) = &self.validator {
+            headers.insert(IF_RANGE, validator.clone());
+        }
+
+        let response = self
+            .client
+            .clone()
+            .into_client()
+            .execute(request)
+            .await?
+            .error_for_status()?;
+        if response.status() != StatusCode::PARTIAL_CONTENT
+            || !starts_at(response.headers().get(CONTENT_RANGE), self.received)
+        {
+            return Err(ResumeError::RangeNotSatisfied(
+                self.url.clone(),
+                self.received,
+            ));
+        }
+        Ok(response.bytes_stream().boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resumable_body_keeps_content_length() {
+        let chunks: Vec<Result<Bytes, BoxError>> = vec![
+            Ok(Bytes::from_static(b"hello ")),
+            Ok(Bytes::from_static(b"world")),
+        ];
+        let body = ResumableBody {
+            stream: Mutex::new(futures::stream::iter(chunks).boxed()),
+            remaining: 11,
+        };
+        let response = Response::from(
+            http::Response::builder()
+                .status(StatusCode::OK)
+                .body(reqwest::Body::wrap(body))
+                .unwrap(),
+        );
+        assert_eq!(response.content_length(), Some(11));
+        assert_eq!(response.bytes().await.unwrap(), "hello world");
+    }
+
+    #[test]
+    fn test_starts_at() {
+        let range = HeaderValue::from_static("bytes 1024-2047/2048");
+        assert!(starts_at(Some(&range), 1024));
+        assert!(!starts_at(Some(&range), 0));
+        assert!(!starts_at(
+            Some(&HeaderValue::from_static("bytes */2048")),
+            1024
+        ));
+        assert!(!starts_at(None, 0));
+    }
+}
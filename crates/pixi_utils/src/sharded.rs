@@ -0,0 +1,93 @@
+//! Graceful fallback for channels that do not serve sharded repodata.
+//!
+//! The repodata gateway only falls back to `repodata.json` when the shard
+//! index of a channel does not exist. Some channels (or proxies in front of
+//! them) deny access to the index with a `403 Forbidden` instead, which would
+//! make every solve against that channel fail. This middleware turns such a
+//! response into a `404 Not Found` so the full `repodata.json` is used
+//! instead.
+//!
+//! Transport and server errors are passed on unchanged. They are usually
+//! transient, and the gateway remembers that a channel has no shard index, so
+//! a single failure must not turn off sharded repodata for the channel.
+
+use http::Extensions;
+use reqwest::{Request, Response, ResponseBuilderExt, header::CACHE_CONTROL};
+use reqwest_middleware::{Middleware, Next};
+use url::Url;
+
+/// The file name of the index of a channel that serves sharded repodata.
+pub const SHARDS_INDEX_FILE_NAME: &str = "repodata_shards.msgpack.zst";
+
+/// Returns true if the url points to the shard index of a channel subdir.
+fn is_shard_index(url: &Url) -> bool {
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .is_some_and(|file_name| file_name == SHARDS_INDEX_FILE_NAME)
+}
+
+/// Constructs a `404 Not Found` response that makes the gateway fall back to
+/// `repodata.json`. The response must not be cached, so that the shard index
+/// is tried again on the next request.
+fn unavailable(url: &Url) -> Response {
+    Response::from(
+        http::response::Builder::new()
+            .status(http::StatusCode::NOT_FOUND)
+            .header(CACHE_CONTROL, "no-store")
+            .url(url.clone())
+            .body(String::from("the shard index is unavailable"))
+            .expect("a 404 response is always valid"),
+    )
+}
+
+/// Middleware that makes the repodata gateway fall back to `repodata.json`
+/// when the channel does not serve a shard index.
+#[derive(Clone, Default)]
+pub struct ShardedRepodataFallbackMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for ShardedRepodataFallbackMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        if !is_shard_index(req.url()) {
+            return next.run(req, extensions).await;
+        }
+
+        let url = req.url().clone();
+        let response = next.run(req, extensions).await?;
+        let status = response.status();
+        if status == http::StatusCode::FORBIDDEN {
+            tracing::debug!(
+                "sharded repodata is not available at {url} ({status}), falling back to repodata.json"
+            );
+            return Ok(unavailable(&url));
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_shard_index() {
+        assert!(is_shard_index(
+            &Url::parse("https://prefix.dev/conda-forge/linux-64/repodata_shards.msgpack.zst")
+                .unwrap()
+        ));
+        assert!(!is_shard_index(
+            &Url::parse("https://prefix.dev/conda-forge/linux-64/repodata.json").unwrap()
+        ));
+        assert!(!is_shard_index(
+            &Url::parse(
+                "https://prefix.dev/conda-forge/linux-64/shards/0123456789abcdef.msgpack.zst"
+            )
+            .unwrap()
+        ));
+    }
+}
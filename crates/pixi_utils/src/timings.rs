@@ -0,0 +1,183 @@
+//! Records how long the major phases of a pixi invocation take.
+//!
+//! Recording is disabled by default and is enabled with [`enable`], e.g. by
+//! the `--timings` flag. A phase is timed by holding the [`PhaseTimer`]
+//! returned by [`start`] for its duration. Phases may run concurrently, for
+//! example when several environments are solved at once.
+
+use std::{
+    fmt::{Display, Formatter},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDS: Mutex<Vec<(Phase, Instant, Instant)>> = Mutex::new(Vec::new());
+
+/// A major phase of a pixi invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Phase {
+    /// Locating and parsing the manifest of the workspace.
+    WorkspaceDiscovery,
+    /// Fetching the repodata of the conda channels.
+    RepodataFetch,
+    /// Solving the conda packages of an environment.
+    CondaSolve,
+    /// Resolving the PyPI packages of an environment.
+    PypiSolve,
+    /// Downloading and linking packages into a prefix.
+    Link,
+    /// Running the activation scripts of an environment.
+    Activation,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Phase::WorkspaceDiscovery => "workspace discovery",
+            Phase::RepodataFetch => "repodata fetch",
+            Phase::CondaSolve => "conda solve",
+            Phase::PypiSolve => "pypi solve",
+            Phase::Link => "link",
+            Phase::Activation => "activation",
+        })
+    }
+}
+
+/// Enables the recording of timings.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns true if timings are recorded.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Starts timing a phase, the phase ends when the returned timer is dropped.
+pub fn start(phase: Phase) -> PhaseTimer {
+    PhaseTimer {
+        phase,
+        start: is_enabled().then(Instant::now),
+    }
+}
+
+/// Times a phase until it is dropped.
+#[must_use = "the phase ends when the timer is dropped"]
+pub struct PhaseTimer {
+    phase: Phase,
+    start: Option<Instant>,
+}
+
+impl Drop for PhaseTimer {
+    fn drop(&mut self) {
+        if let Some(start) = self.start
+            && let Ok(mut records) = RECORDS.lock()
+        {
+            records.push((self.phase, start, Instant::now()));
+        }
+    }
+}
+
+/// The recorded timings of a phase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    /// How often the phase was run.
+    pub count: usize,
+    /// The wall-clock time during which the phase was running. Concurrent runs
+    /// of the phase are only counted once.
+    #[serde(serialize_with = "serialize_secs")]
+    pub duration: Duration,
+    /// The sum of the durations of all runs of the phase.
+    #[serde(serialize_with = "serialize_secs")]
+    pub cumulative: Duration,
+}
+
+fn serialize_secs<S: serde::Serializer>(duration: &Duration, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_f64(duration.as_secs_f64())
+}
+
+/// Returns the timings of the phases that were recorded, in the order of the
+/// phases.
+pub fn summary() -> Vec<PhaseTiming> {
+    let records = RECORDS
+        .lock()
+        .map(|records| records.clone())
+        .unwrap_or_default();
+    summarize(records)
+}
+
+fn summarize(mut records: Vec<(Phase, Instant, Instant)>) -> Vec<PhaseTiming> {
+    records.sort();
+    let mut timings: Vec<PhaseTiming> = Vec::new();
+    // The end of the last run of the current phase, used to merge overlapping
+    // runs.
+    let mut covered_until = None;
+    for (phase, start, end) in records {
+        let elapsed = end.duration_since(start);
+        let timing = match timings.last_mut() {
+            Some(timing) if timing.phase == phase => timing,
+            _ => {
+                covered_until = None;
+                timings.push(PhaseTiming {
+                    phase,
+                    count: 0,
+                    duration: Duration::ZERO,
+                    cumulative: Duration::ZERO,
+                });
+                timings.last_mut().expect("a timing was just added")
+            }
+        };
+        timing.count += 1;
+        timing.cumulative += elapsed;
+        timing.duration += match covered_until {
+            Some(until) if until >= end => Duration::ZERO,
+            Some(until) if until > start => end.duration_since(until),
+            _ => elapsed,
+        };
+        covered_until = Some(covered_until.map_or(end, |until: Instant| until.max(end)));
+    }
+    timings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_merges_concurrent_runs() {
+        let t = Instant::now();
+        let secs = |s| t + Duration::from_secs(s);
+        let timings = summarize(vec![
+            (Phase::CondaSolve, secs(0), secs(4)),
+            (Phase::WorkspaceDiscovery, secs(0), secs(1)),
+            (Phase::CondaSolve, secs(2), secs(6)),
+            (Phase::CondaSolve, secs(3), secs(5)),
+            (Phase::CondaSolve, secs(8), secs(9)),
+        ]);
+        assert_eq!(
+            timings,
+            vec![
+                PhaseTiming {
+                    phase: Phase::WorkspaceDiscovery,
+                    count: 1,
+                    duration: Duration::from_secs(1),
+                    cumulative: Duration::from_secs(1),
+                },
+                PhaseTiming {
+                    phase: Phase::CondaSolve,
+                    count: 4,
+                    duration: Duration::from_secs(7),
+                    cumulative: Duration::from_secs(11),
+                },
+            ]
+        );
+    }
+}
@@ -34,6 +34,8 @@ pub struct UvResolutionContext {
     /// Whether UV should use native TLS (system certificates).
     /// This is computed based on the `tls-root-certs` config and the TLS feature used.
     pub use_native_tls: bool,
+    /// The maximum number of times a failed request is retried.
+    pub retries: u32,
     pub package_config_settings: PackageConfigSettings,
     pub extra_build_requires: ExtraBuildRequires,
     pub extra_build_variables: ExtraBuildVariables,
@@ -91,6 +93,7 @@ impl UvResolutionContext {
             proxies: config.get_proxies().into_diagnostic()?,
             tls_no_verify: config.tls_no_verify(),
             use_native_tls: should_use_native_tls_for_uv(config),
+            retries: config.max_retries(),
             package_config_settings: PackageConfigSettings::default(),
             extra_build_requires: ExtraBuildRequires::default(),
             extra_build_variables: ExtraBuildVariables::default(),
@@ -38,32 +38,10 @@ impl CustomMapping {
             .get_or_try_init(async {
                 let mut mapping_url_to_name: MappingByChannel = Default::default();
 
-                for (name, url) in self.mapping.iter() {
+                for (name, location) in self.mapping.iter() {
                     // Fetch the mapping from the server or from the local
-
-                    match url {
-                        MappingLocation::Url(url) => {
-                            let mapping_by_name = match url.scheme() {
-                                "file" => {
-                                    let file_path = url.to_file_path().map_err(|_| {
-                                        miette::miette!("{} is not a valid file url", url)
-                                    })?;
-                                    fetch_mapping_from_path(&file_path)?
-                                }
-                                _ => fetch_mapping_from_url(client, url).await?,
-                            };
-
-                            mapping_url_to_name.insert(name.to_string(), mapping_by_name);
-                        }
-                        MappingLocation::Path(path) => {
-                            let mapping_by_name = fetch_mapping_from_path(path)?;
-
-                            mapping_url_to_name.insert(name.to_string(), mapping_by_name);
-                        }
-                        MappingLocation::Memory(mapping) => {
-                            mapping_url_to_name.insert(name.to_string(), mapping.clone());
-                        }
-                    }
+                    let mapping_by_name = fetch_mapping_from_location(client, location).await?;
+                    mapping_url_to_name.insert(name.to_string(), mapping_by_name);
                 }
 
                 Ok(mapping_url_to_name)
@@ -73,6 +51,53 @@ impl CustomMapping {
     }
 }
 
+/// Conda to pypi name overrides that apply to the packages of every channel.
+/// They take precedence over any other mapping.
+///
+/// This struct caches the overrides internally.
+#[derive(Debug)]
+pub struct MappingOverrides {
+    pub location: MappingLocation,
+    mapping_value: AsyncCell<CompressedMapping>,
+}
+
+impl MappingOverrides {
+    /// Create new `MappingOverrides` from the specified location.
+    pub fn new(location: MappingLocation) -> Self {
+        Self {
+            location,
+            mapping_value: Default::default(),
+        }
+    }
+
+    /// Fetch the overrides from the server or load them from the local file.
+    pub async fn fetch_overrides(&self, client: &LazyClient) -> miette::Result<CompressedMapping> {
+        self.mapping_value
+            .get_or_try_init(fetch_mapping_from_location(client, &self.location))
+            .await
+            .cloned()
+    }
+}
+
+async fn fetch_mapping_from_location(
+    client: &LazyClient,
+    location: &MappingLocation,
+) -> miette::Result<CompressedMapping> {
+    match location {
+        MappingLocation::Url(url) => match url.scheme() {
+            "file" => {
+                let file_path = url
+                    .to_file_path()
+                    .map_err(|_| miette::miette!("{} is not a valid file url", url))?;
+                fetch_mapping_from_path(&file_path)
+            }
+            _ => fetch_mapping_from_url(client, url).await,
+        },
+        MappingLocation::Path(path) => fetch_mapping_from_path(path),
+        MappingLocation::Memory(mapping) => Ok(mapping.clone()),
+    }
+}
+
 async fn fetch_mapping_from_url(
     client: &LazyClient,
     url: &Url,
@@ -162,13 +187,7 @@ impl DerivePurls for CustomMappingClient {
         // Find the mapping for this particular record
         match custom_mapping.get(record.package_record.name.as_normalized()) {
             // The record is in the mapping, and it has a pypi name
-            Some(Some(mapped_name)) => {
-                let purl = PackageUrl::builder(String::from("pypi"), mapped_name.to_string())
-                    .with_qualifier("source", PurlSource::ProjectDefinedMapping.as_str())
-                    .expect("valid qualifier");
-                let built_purl = purl.build().expect("valid pypi package url");
-                Ok(Some(vec![built_purl]))
-            }
+            Some(Some(mapped_name)) => Ok(Some(vec![project_defined_purl(mapped_name)])),
             Some(None) => {
                 // The record is in the mapping, but it has no pypi name
                 Ok(Some(vec![]))
@@ -180,3 +199,12 @@ impl DerivePurls for CustomMappingClient {
         }
     }
 }
+
+/// Returns the purl of a pypi package that was mapped by the project.
+pub(crate) fn project_defined_purl(pypi_name: &str) -> PackageUrl {
+    PackageUrl::builder(String::from("pypi"), pypi_name.to_string())
+        .with_qualifier("source", PurlSource::ProjectDefinedMapping.as_str())
+        .expect("valid qualifier")
+        .build()
+        .expect("valid pypi package url")
+}
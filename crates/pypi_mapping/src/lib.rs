@@ -24,10 +24,10 @@ mod custom_mapping;
 pub mod prefix;
 mod reporter;
 
-pub use custom_mapping::CustomMapping;
+pub use custom_mapping::{CustomMapping, MappingOverrides};
 pub use reporter::Reporter;
 
-use crate::custom_mapping::CustomMappingClient;
+use crate::custom_mapping::{CustomMappingClient, project_defined_purl};
 
 /// A compressed mapping is a mapping of a package name to a potential pypi
 /// name.
@@ -213,6 +213,20 @@ impl MappingClient {
         mapping_source: &MappingSource,
         conda_packages: impl IntoIterator<Item = &mut RepoDataRecord>,
         reporter: Option<Arc<dyn Reporter>>,
+    ) -> miette::Result<()> {
+        self.amend_purls_with_overrides(mapping_source, None, conda_packages, reporter)
+            .await
+    }
+
+    /// Given a set of `RepoDataRecord`s, amend the purls for each record. The
+    /// `overrides` take precedence over the `mapping_source` and over the purls
+    /// that are already present on the records.
+    pub async fn amend_purls_with_overrides(
+        &self,
+        mapping_source: &MappingSource,
+        overrides: Option<&MappingOverrides>,
+        conda_packages: impl IntoIterator<Item = &mut RepoDataRecord>,
+        reporter: Option<Arc<dyn Reporter>>,
     ) -> miette::Result<()> {
         let start = Instant::now();
 
@@ -227,6 +241,20 @@ impl MappingClient {
                 .map(|c| c.trim_end_matches('/').to_string());
         }
 
+        // Apply the overrides and discard the overridden records.
+        if let Some(overrides) = overrides {
+            let overrides = overrides.fetch_overrides(&self.client).await?;
+            records.retain_mut(|record| {
+                match overrides.get(record.package_record.name.as_normalized()) {
+                    Some(pypi_name) => {
+                        override_purls(record, pypi_name.as_deref());
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+
         // Discard all records for which we already have pypi purls.
         records.retain(|record| !has_pypi_purl(record));
 
@@ -380,6 +408,17 @@ fn has_pypi_purl(record: &RepoDataRecord) -> bool {
         .is_some_and(|vec| vec.iter().any(|p| p.package_type() == "pypi"))
 }
 
+/// Replaces the pypi purls of the record with the purl of the given pypi name.
+/// If no pypi name is given the record is not a pypi package.
+fn override_purls(record: &mut RepoDataRecord, pypi_name: Option<&str>) {
+    let record_purls = record
+        .package_record
+        .purls
+        .get_or_insert_with(BTreeSet::new);
+    record_purls.retain(|purl| purl.package_type() != "pypi");
+    record_purls.extend(pypi_name.map(project_defined_purl));
+}
+
 /// Adds the specified purls to the `purls` field of the record.
 fn amend_purls(record: &mut RepoDataRecord, purls: impl IntoIterator<Item = PackageUrl>) {
     let record_purls = record
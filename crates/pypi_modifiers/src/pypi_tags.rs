@@ -1,6 +1,8 @@
 use miette::Diagnostic;
 use pixi_default_versions::{default_glibc_version, default_mac_os_version};
-use pixi_manifest::{LibCSystemRequirement, SystemRequirements};
+use pixi_manifest::{
+    LibCSystemRequirement, SystemRequirements, pypi::pypi_options::PypiPlatformTag,
+};
 use rattler_conda_types::MatchSpec;
 use rattler_conda_types::{Arch, PackageName, PackageRecord, Platform};
 use rattler_virtual_packages::VirtualPackage;
@@ -38,6 +40,9 @@ pub enum PyPITagError {
     #[error("no tags could be created for platform: {0}")]
     NoTagsForPlatform(String),
 
+    #[error("the pypi platform tag '{0}' cannot be used for platform: {1}")]
+    IncompatiblePlatformTag(PypiPlatformTag, Platform),
+
     #[error(transparent)]
     ParseMatchSpecError(#[from] rattler_conda_types::ParseMatchSpecError),
 }
@@ -53,12 +58,19 @@ pub fn package_name_is_python(record: &rattler_conda_types::PackageName) -> bool
 }
 
 /// Get the python version and implementation name for the specified platform.
+///
+/// If a `platform_tag` is specified it is used instead of the tag derived
+/// from the system requirements.
 pub fn get_pypi_tags(
     platform: Platform,
     system_requirements: &SystemRequirements,
+    platform_tag: Option<&PypiPlatformTag>,
     python_record: &PackageRecord,
 ) -> Result<uv_platform_tags::Tags, PyPITagError> {
-    let platform = get_platform_tags(platform, system_requirements)?;
+    let platform = match platform_tag {
+        Some(tag) => get_platform_tags_from_override(platform, tag)?,
+        None => get_platform_tags(platform, system_requirements)?,
+    };
     let python_version = get_python_version(python_record)?;
     let implementation_name = get_implementation_name(python_record)?;
     let gil_disabled = gil_disabled(python_record)?;
@@ -81,6 +93,30 @@ fn get_platform_tags(
     }
 }
 
+/// Create a uv platform tag from a user specified platform tag
+fn get_platform_tags_from_override(
+    platform: Platform,
+    tag: &PypiPlatformTag,
+) -> Result<uv_platform_tags::Platform, PyPITagError> {
+    if !tag.is_compatible_with(platform) {
+        return Err(PyPITagError::IncompatiblePlatformTag(*tag, platform));
+    }
+
+    let os = match *tag {
+        PypiPlatformTag::Manylinux { major, minor } => {
+            uv_platform_tags::Os::Manylinux { major, minor }
+        }
+        PypiPlatformTag::Musllinux { major, minor } => {
+            uv_platform_tags::Os::Musllinux { major, minor }
+        }
+        PypiPlatformTag::Macos { major, minor } => uv_platform_tags::Os::Macos { major, minor },
+    };
+    Ok(uv_platform_tags::Platform::new(
+        os,
+        get_arch_tags(platform)?,
+    ))
+}
+
 /// Get linux specific platform tags
 fn get_linux_platform_tags(
     platform: Platform,
@@ -551,6 +587,43 @@ mod tests {
         assert!(!wheel.is_compatible(&res));
     }
 
+    #[test]
+    fn test_tags_with_platform_tag_override() {
+        let python_record = PackageRecord::new(
+            "python".parse().unwrap(),
+            VersionWithSource::from_str("3.13.3").unwrap(),
+            "h2334245_104_cp313".to_string(),
+        );
+        let tag = PypiPlatformTag::Musllinux { major: 1, minor: 2 };
+        let res = get_pypi_tags(
+            Platform::Linux64,
+            &SystemRequirements::default(),
+            Some(&tag),
+            &python_record,
+        )
+        .unwrap();
+
+        let wheel =
+            WheelFilename::from_str("numpy-2.2.0-cp313-cp313-musllinux_1_2_x86_64.whl").unwrap();
+        assert!(wheel.is_compatible(&res));
+
+        let wheel =
+            WheelFilename::from_str("numpy-2.2.0-cp313-cp313-manylinux_2_17_x86_64.whl").unwrap();
+        assert!(!wheel.is_compatible(&res));
+
+        // A linux tag cannot be used for macOS
+        let res = get_pypi_tags(
+            Platform::OsxArm64,
+            &SystemRequirements::default(),
+            Some(&tag),
+            &python_record,
+        );
+        assert!(matches!(
+            res.unwrap_err(),
+            PyPITagError::IncompatiblePlatformTag(_, Platform::OsxArm64)
+        ));
+    }
+
     #[test]
     fn test_tags_from_windows_machine() {
         let vpkgs = vec![];
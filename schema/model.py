@@ -182,6 +182,10 @@ class Workspace(StrictBaseModel):
     conda_pypi_map: dict[ChannelName, AnyHttpUrl | NonEmptyStr] | None = Field(
         None, description="The `conda` to PyPI mapping configuration"
     )
+    conda_pypi_overrides: dict[CondaPackageName, NonEmptyStr] | NonEmptyStr | None = Field(
+        None,
+        description="Overrides of the `conda` to PyPI name mapping that apply to every channel, either a table or a URL or path of a mapping file",
+    )
     pypi_options: PyPIOptions | None = Field(
         None, description="Options related to PyPI indexes for this project"
     )
@@ -203,6 +207,32 @@ class Workspace(StrictBaseModel):
         description="The required version spec for pixi itself to resolve and build the project.",
         examples=[">=0.40"],
     )
+    members: list[PathNoBackslash] | None = Field(
+        None,
+        description="The directories of the member packages of the workspace. A path ending in `/*` includes all its direct subdirectories.",
+        examples=[["packages/*", "tools/cli"]],
+    )
+    hold: list[NonEmptyStr] | None = Field(
+        None,
+        description="Packages that are not upgraded by `pixi upgrade` unless they are requested explicitly.",
+        examples=[["openssl", "cuda-version"]],
+    )
+    license_policy: LicensePolicy | None = Field(
+        None,
+        description="The licenses that the locked packages are allowed to use, checked by `pixi license check`.",
+    )
+    lock_profiles: dict[LockProfileName, LockProfile] | None = Field(
+        None,
+        description="Additional lock-files that are solved with different settings, selected with `--lock-profile`. The lock-file of a profile is named `pixi.<NAME>.lock`.",
+    )
+    dependency_groups_as_features: bool | None = Field(
+        None,
+        description="Whether the `[dependency-groups]` of a `pyproject.toml` are turned into features.",
+    )
+    extras_as_environments: bool | None = Field(
+        None,
+        description="Whether an environment is created for every extra in the `[project.optional-dependencies]` of a `pyproject.toml`.",
+    )
     target: dict[TargetName, WorkspaceTarget] | None = Field(
         None, description="The workspace targets"
     )
@@ -380,6 +410,17 @@ class TaskArgs(StrictBaseModel):
     default: str | None = Field(None, description="The default value of the argument")
 
 
+class TaskSandbox(StrictBaseModel):
+    """The policy of a task that is run with `pixi run --sandbox`."""
+
+    network: bool = Field(False, description="Whether the task is allowed to access the network")
+    writable: list[NonEmptyStr] | None = Field(
+        None,
+        description="Paths outside of the workspace that the task is allowed to write to",
+        examples=[["~/.cache/datasets"]],
+    )
+
+
 class DependsOn(StrictBaseModel):
     """The dependencies of a task."""
 
@@ -440,6 +481,14 @@ class TaskInlineTable(StrictBaseModel):
             ["arg", {"arg": "arg2", "default": "2"}],
         ],
     )
+    sandbox: TaskSandbox | None = Field(
+        None,
+        description="What the task is allowed to do when it is run with `pixi run --sandbox`",
+    )
+    log: bool | None = Field(
+        None,
+        description="Whether to always write the output of the task to a log file in `.pixi/logs`",
+    )
 
 
 #######################
@@ -493,6 +542,11 @@ class Environment(StrictBaseModel):
         False,
         description="Whether to add the default feature to this environment",
     )
+    env: dict[NonEmptyStr, NonEmptyStr] | None = Field(
+        None,
+        description="A map of environment variables to values, set when this environment is activated. These take precedence over the activation variables of the features.",
+        examples=[{"key": "value"}],
+    )
 
 
 ######################
@@ -637,6 +691,42 @@ class S3Options(StrictBaseModel):
     )
 
 
+class LicensePolicy(StrictBaseModel):
+    """The licenses that the locked packages of the workspace are allowed to use"""
+
+    allow: list[NonEmptyStr] | None = Field(
+        None,
+        description="The SPDX expressions of the allowed licenses. If empty, every license that is not denied is allowed.",
+        examples=[["MIT", "BSD-3-Clause", "Apache-2.0"]],
+    )
+    deny: list[NonEmptyStr] | None = Field(
+        None,
+        description="The SPDX expressions of the denied licenses.",
+        examples=[["GPL-3.0-only", "AGPL-3.0-only"]],
+    )
+
+
+LockProfileName = Annotated[str, Field(pattern=r"^[a-z\d\-]+$")]
+
+
+class LockProfile(StrictBaseModel):
+    """An additional lock-file of the workspace that is solved with different settings, selected with `--lock-profile`"""
+
+    solve_strategy: SolveStrategy | None = Field(
+        None,
+        examples=["lowest", "lowest-direct", "highest"],
+        description="""The strategy that is used in the solve.
+- 'highest': solve all packages to the highest compatible version.
+- 'lowest': solve all packages to the lowest compatible version.
+- 'lowest-direct': solve direct dependencies to the lowest compatible version and transitive ones to the highest compatible version.""",
+    )
+    exclude_newer: ExcludeNewer | None = Field(
+        None,
+        examples=["2023-11-03", "2023-11-03T03:33:12Z"],
+        description="Exclude any package newer than this date",
+    )
+
+
 class PyPIOptions(StrictBaseModel):
     """Options that determine the behavior of PyPI package resolution and installation"""
 
@@ -650,10 +740,10 @@ class PyPIOptions(StrictBaseModel):
         description="Additional PyPI registries that should be used as extra indexes",
         examples=[["https://pypi.org/simple"]],
     )
-    find_links: list[FindLinksPath | FindLinksURL] | None = Field(
+    find_links: list[NonEmptyStr | FindLinksPath | FindLinksURL] | None = Field(
         None,
-        description="Paths to directory containing",
-        examples=[["https://pypi.org/simple"]],
+        description="Directories or URLs of flat indexes that contain packages, a string with a scheme is a URL and a path otherwise",
+        examples=[["./wheels", "https://pypi.org/simple"]],
     )
     no_build_isolation: bool | list[PyPIPackageName] | None = Field(
         None,
@@ -696,6 +786,11 @@ class PyPIOptions(StrictBaseModel):
         description="The strategy to use when considering pre-release versions",
         examples=["disallow", "allow", "if-necessary", "explicit", "if-necessary-or-explicit"],
     )
+    platform_tags: dict[Platform, NonEmptyStr] | None = Field(
+        None,
+        description="The wheel platform tag to resolve PyPI packages for, per platform",
+        examples=[{"linux-64": "musllinux_1_2"}],
+    )
 
 
 #######################
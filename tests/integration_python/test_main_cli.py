@@ -435,6 +435,61 @@ def test_dont_add_broken_dep(pixi: Path, tmp_pixi_workspace: Path, dummy_channel
     assert manifest_content == tmp_pixi_workspace.joinpath("pixi.toml").read_text()
 
 
+def test_add_remove_from_file(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -> None:
+    manifest_path = tmp_pixi_workspace / "pixi.toml"
+    specs_path = tmp_pixi_workspace / "specs.txt"
+
+    verify_cli_command([pixi, "init", "--channel", dummy_channel_1, tmp_pixi_workspace])
+
+    specs_path.write_text("# Dependencies\ndummy-a\n\ndummy-b  # the second one\n")
+    verify_cli_command(
+        [pixi, "add", "--manifest-path", manifest_path, "--from-file", specs_path, "dummy-c"]
+    )
+    parsed_manifest = tomllib.loads(manifest_path.read_text())
+    assert {"dummy-a", "dummy-b", "dummy-c"} <= parsed_manifest["dependencies"].keys()
+
+    verify_cli_command(
+        [pixi, "remove", "--manifest-path", manifest_path, "--from-file", specs_path]
+    )
+    parsed_manifest = tomllib.loads(manifest_path.read_text())
+    assert "dummy-a" not in parsed_manifest["dependencies"]
+    assert "dummy-b" not in parsed_manifest["dependencies"]
+    assert "dummy-c" in parsed_manifest["dependencies"]
+
+    # A file without any specs is an error
+    specs_path.write_text("# Nothing here\n")
+    verify_cli_command(
+        [pixi, "add", "--manifest-path", manifest_path, "--from-file", specs_path],
+        ExitCode.FAILURE,
+        stderr_contains="no dependencies found",
+    )
+
+
+def test_remove_dry_run(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -> None:
+    manifest_path = tmp_pixi_workspace / "pixi.toml"
+    lock_file_path = tmp_pixi_workspace / "pixi.lock"
+
+    verify_cli_command([pixi, "init", "--channel", dummy_channel_1, tmp_pixi_workspace])
+    verify_cli_command([pixi, "add", "--manifest-path", manifest_path, "dummy-a", "dummy-b"])
+
+    manifest_content = manifest_path.read_text()
+    lock_file_content = lock_file_path.read_text()
+
+    # The preview shows the manifest edit and the lock-file changes
+    verify_cli_command(
+        [pixi, "remove", "--manifest-path", manifest_path, "--dry-run", "dummy-a"],
+        stderr_contains=["-dummy-a", "would be unlinked"],
+    )
+
+    # Nothing on disk should have changed
+    assert manifest_path.read_text() == manifest_content
+    assert lock_file_path.read_text() == lock_file_content
+    verify_cli_command(
+        [pixi, "list", "--manifest-path", manifest_path],
+        stdout_contains="dummy-a",
+    )
+
+
 def test_list_exits_unsuccessful_on_unknown_pkg(
     pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str
 ) -> None:
@@ -638,14 +693,29 @@ def test_pixi_lock(pixi: Path, tmp_pixi_workspace: Path, dummy_channel_1: str) -
     dot_pixi = tmp_pixi_workspace / ".pixi"
     shutil.rmtree(dot_pixi)
 
-    # Run pixi lock to recreate the lock file and validate the return code with --check is 1
+    # Validate that the return code with --check is 1 and that the lock file is not created
     verify_cli_command(
         [pixi, "lock", "--manifest-path", manifest_path, "--check"],
         expected_exit_code=ExitCode.FAILURE,
+        stderr_contains=["default"],
+    )
+    assert not lock_file_path.exists()
+
+    # The JSON report lists the out of date environments
+    output = verify_cli_command(
+        [pixi, "lock", "--manifest-path", manifest_path, "--check", "--json"],
+        expected_exit_code=ExitCode.FAILURE,
+    )
+    report = json.loads(output.stdout)
+    assert [env["environment"] for env in report["outdated"]] == ["default"]
+
+    # Run pixi lock to recreate the lock file
+    verify_cli_command(
+        [pixi, "lock", "--manifest-path", manifest_path],
         stderr_contains=["+", "dummy-a"],
     )
 
-    # Run pixi lock again to validate that the return code with --check is 0
+    # Validate that the return code with --check is 0
     verify_cli_command(
         [pixi, "lock", "--manifest-path", manifest_path, "--check"],
     )
@@ -1062,11 +1132,17 @@ def test_info_output_extended(pixi: Path, tmp_pixi_workspace: Path) -> None:
     IsAnyList = IsList(length=...)
     assert info_data == snapshot(
         {
+            "schema_version": 1,
             "platform": IsStr,
             "virtual_packages": IsAnyList,
             "version": IsStr,
+            "tls_backend": IsStr,
             "cache_dir": IsStr,
+            "package_cache_dir": IsStr,
+            "repodata_cache_dir": IsStr,
             "cache_size": AnyThing,
+            "package_cache_size": AnyThing,
+            "repodata_cache_size": AnyThing,
             "auth_dir": IsStr,
             "global_info": {
                 "bin_dir": IsStr,
@@ -1075,10 +1151,12 @@ def test_info_output_extended(pixi: Path, tmp_pixi_workspace: Path) -> None:
             },
             "project_info": {
                 "name": "test",
+                "root": IsStr,
                 "manifest_path": IsStr,
                 "last_updated": IsStr,
                 "pixi_folder_size": IsStr,
                 "version": None,
+                "features": IsAnyList,
             },
             "environments_info": [
                 {